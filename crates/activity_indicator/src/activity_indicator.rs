@@ -1,5 +1,9 @@
+mod environment_indicator;
+mod offline_indicator;
+
 use auto_update::{AutoUpdateStatus, AutoUpdater, DismissErrorMessage};
 use editor::Editor;
+pub use environment_indicator::EnvironmentIndicator;
 use futures::StreamExt;
 use gpui::{
     actions, svg, AppContext, CursorStyle, EventEmitter, InteractiveElement as _, Model,
@@ -7,6 +11,7 @@ use gpui::{
     ViewContext, VisualContext as _,
 };
 use language::{LanguageRegistry, LanguageServerBinaryStatus};
+pub use offline_indicator::OfflineIndicator;
 use project::{LanguageServerProgress, Project};
 use smallvec::SmallVec;
 use std::{cmp::Reverse, fmt::Write, sync::Arc};
@@ -315,5 +320,9 @@ impl Render for ActivityIndicator {
 }
 
 impl StatusItemView for ActivityIndicator {
+    fn status_item_id(&self) -> &'static str {
+        "activity_indicator"
+    }
+
     fn set_active_pane_item(&mut self, _: Option<&dyn ItemHandle>, _: &mut ViewContext<Self>) {}
 }
@@ -0,0 +1,64 @@
+use gpui::{div, IntoElement, Model, ParentElement, Render, Subscription, ViewContext};
+use project::{Project, WorktreeId};
+use ui::{Button, ButtonCommon, FluentBuilder, LabelSize, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView, Workspace};
+
+/// Shows where the environment of the active item's project came from, when the
+/// project defines one.
+pub struct EnvironmentIndicator {
+    project: Model<Project>,
+    worktree_id: Option<WorktreeId>,
+    _observe_project: Subscription,
+}
+
+impl EnvironmentIndicator {
+    pub fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
+        let project = workspace.project().clone();
+        Self {
+            _observe_project: cx.observe(&project, |_, _, cx| cx.notify()),
+            project,
+            worktree_id: None,
+        }
+    }
+}
+
+impl Render for EnvironmentIndicator {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let environment = self.worktree_id.and_then(|worktree_id| {
+            self.project
+                .read(cx)
+                .loaded_worktree_environment(worktree_id)
+        });
+        div().when_some(environment, |el, environment| {
+            let source = environment.source.label();
+            let variable_count = environment.vars.len();
+            el.child(
+                Button::new("project-environment", source)
+                    .label_size(LabelSize::Small)
+                    .tooltip(move |cx| {
+                        Tooltip::text(
+                            format!("Loaded {variable_count} environment variables from {source}"),
+                            cx,
+                        )
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for EnvironmentIndicator {
+    fn status_item_id(&self) -> &'static str {
+        "project_environment"
+    }
+
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.worktree_id = active_pane_item
+            .and_then(|item| item.project_path(cx))
+            .map(|project_path| project_path.worktree_id);
+        cx.notify();
+    }
+}
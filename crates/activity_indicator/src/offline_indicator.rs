@@ -0,0 +1,54 @@
+use client::OfflineModeSetting;
+use gpui::{div, IntoElement, ParentElement, Render, Subscription, ViewContext};
+use settings::{Settings, SettingsStore};
+use ui::{
+    Button, ButtonCommon, Clickable, FluentBuilder, IconName, IconPosition, IconSize, LabelSize,
+    Tooltip,
+};
+use workspace::{item::ItemHandle, StatusItemView, ToggleOfflineMode};
+
+/// Shows that offline mode is enabled, explaining which features it disables.
+pub struct OfflineIndicator {
+    _observe_settings: Subscription,
+}
+
+impl OfflineIndicator {
+    pub fn new(cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            _observe_settings: cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+        }
+    }
+}
+
+impl Render for OfflineIndicator {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let offline = OfflineModeSetting::get_global(cx).0;
+        div().when(offline, |el| {
+            el.child(
+                Button::new("offline-mode", "Offline")
+                    .icon(IconName::Disconnected)
+                    .icon_size(IconSize::Small)
+                    .icon_position(IconPosition::Start)
+                    .label_size(LabelSize::Small)
+                    .on_click(|_, cx| cx.dispatch_action(Box::new(ToggleOfflineMode)))
+                    .tooltip(|cx| {
+                        Tooltip::with_meta(
+                            "Offline Mode",
+                            Some(&ToggleOfflineMode),
+                            "Telemetry, update checks, Copilot, language server downloads \
+                             and collaboration are disabled. Click to go back online.",
+                            cx,
+                        )
+                    }),
+            )
+        })
+    }
+}
+
+impl StatusItemView for OfflineIndicator {
+    fn status_item_id(&self) -> &'static str {
+        "offline_mode"
+    }
+
+    fn set_active_pane_item(&mut self, _: Option<&dyn ItemHandle>, _: &mut ViewContext<Self>) {}
+}
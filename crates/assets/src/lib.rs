@@ -17,6 +17,10 @@ pub struct Assets;
 
 impl AssetSource for Assets {
     fn load(&self, path: &str) -> Result<std::borrow::Cow<'static, [u8]>> {
+        // Absolute paths refer to files outside the bundle, such as the icons of user icon themes.
+        if std::path::Path::new(path).is_absolute() {
+            return Ok(std::fs::read(path)?.into());
+        }
         Self::get(path)
             .map(|f| f.data)
             .ok_or_else(|| anyhow!("could not find asset at path \"{}\"", path))
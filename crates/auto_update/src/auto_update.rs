@@ -1,7 +1,7 @@
 mod update_notification;
 
 use anyhow::{anyhow, Context, Result};
-use client::{Client, TelemetrySettings, ZED_APP_PATH, ZED_APP_VERSION};
+use client::{Client, OfflineModeSetting, TelemetrySettings, ZED_APP_PATH, ZED_APP_VERSION};
 use db::kvp::KEY_VALUE_STORE;
 use db::RELEASE_CHANNEL;
 use gpui::{
@@ -16,23 +16,31 @@ use serde_derive::Serialize;
 use smol::io::AsyncReadExt;
 
 use settings::{Settings, SettingsStore};
-use smol::{fs::File, process::Command};
+use smol::{fs::File, process::Command, stream::StreamExt};
 
 use std::{
     env::consts::{ARCH, OS},
-    ffi::OsString,
+    ffi::{OsStr, OsString},
+    path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
 use update_notification::UpdateNotification;
 use util::channel::{AppCommitSha, ReleaseChannel};
 use util::http::HttpClient;
+use util::paths::PREVIOUS_VERSION_DIR;
+use util::ResultExt;
 use workspace::Workspace;
 
 const SHOULD_SHOW_UPDATE_NOTIFICATION_KEY: &str = "auto-updater-should-show-updated-notification";
 const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const PREVIOUS_VERSION_APP_NAME: &str = "Zed.app";
+const PREVIOUS_VERSION_INFO_NAME: &str = "version.json";
 
-actions!(auto_update, [Check, DismissErrorMessage, ViewReleaseNotes]);
+actions!(
+    auto_update,
+    [Check, DismissErrorMessage, ViewReleaseNotes, RollBack]
+);
 
 #[derive(Serialize)]
 struct UpdateRequestBody {
@@ -65,6 +73,19 @@ struct JsonRelease {
     url: String,
 }
 
+/// The version that the last update replaced, a copy of which is kept in
+/// `PREVIOUS_VERSION_DIR` so that the update can be rolled back.
+#[derive(Deserialize, Serialize)]
+struct PreviousVersion {
+    /// The replaced version, as shown to the user.
+    version: String,
+    /// The release that replaced it.
+    replaced_by: String,
+    /// Whether the update was rolled back, in which case the release that replaced
+    /// it isn't installed again.
+    rolled_back: bool,
+}
+
 struct AutoUpdateSetting(bool);
 
 /// Whether or not to automatically check for updates.
@@ -74,6 +95,50 @@ struct AutoUpdateSetting(bool);
 #[serde(transparent)]
 struct AutoUpdateSettingOverride(Option<bool>);
 
+/// The channel that updates are downloaded from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, JsonSchema, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    Stable,
+    Preview,
+    Nightly,
+}
+
+impl From<UpdateChannel> for ReleaseChannel {
+    fn from(channel: UpdateChannel) -> Self {
+        match channel {
+            UpdateChannel::Stable => ReleaseChannel::Stable,
+            UpdateChannel::Preview => ReleaseChannel::Preview,
+            UpdateChannel::Nightly => ReleaseChannel::Nightly,
+        }
+    }
+}
+
+/// The channel to update to, when it differs from the installation's own.
+///
+/// Default: null
+struct AutoUpdateChannelSetting(Option<UpdateChannel>);
+
+impl Settings for AutoUpdateChannelSetting {
+    const KEY: Option<&'static str> = Some("auto_update_channel");
+
+    type FileContent = Option<UpdateChannel>;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self> {
+        Ok(Self(
+            user_values
+                .iter()
+                .rev()
+                .find_map(|value| **value)
+                .or(*default_value),
+        ))
+    }
+}
+
 impl Settings for AutoUpdateSetting {
     const KEY: Option<&'static str> = Some("auto_update");
 
@@ -94,9 +159,11 @@ impl Settings for AutoUpdateSetting {
 
 pub fn init(http_client: Arc<dyn HttpClient>, server_url: String, cx: &mut AppContext) {
     AutoUpdateSetting::register(cx);
+    AutoUpdateChannelSetting::register(cx);
 
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         workspace.register_action(|_, action: &Check, cx| check(action, cx));
+        workspace.register_action(|_, action: &RollBack, cx| roll_back(action, cx));
 
         workspace.register_action(|_, action, cx| {
             view_release_notes(action, cx);
@@ -108,12 +175,10 @@ pub fn init(http_client: Arc<dyn HttpClient>, server_url: String, cx: &mut AppCo
         let auto_updater = cx.new_model(|cx| {
             let updater = AutoUpdater::new(version, http_client, server_url);
 
-            let mut update_subscription = AutoUpdateSetting::get_global(cx)
-                .0
-                .then(|| updater.start_polling(cx));
+            let mut update_subscription = should_poll(cx).then(|| updater.start_polling(cx));
 
             cx.observe_global::<SettingsStore>(move |updater, cx| {
-                if AutoUpdateSetting::get_global(cx).0 {
+                if should_poll(cx) {
                     if update_subscription.is_none() {
                         update_subscription = Some(updater.start_polling(cx))
                     }
@@ -129,8 +194,19 @@ pub fn init(http_client: Arc<dyn HttpClient>, server_url: String, cx: &mut AppCo
     }
 }
 
+fn should_poll(cx: &AppContext) -> bool {
+    AutoUpdateSetting::get_global(cx).0 && !OfflineModeSetting::get_global(cx).0
+}
+
 pub fn check(_: &Check, cx: &mut WindowContext) {
-    if let Some(updater) = AutoUpdater::get(cx) {
+    if OfflineModeSetting::get_global(cx).0 {
+        drop(cx.prompt(
+            gpui::PromptLevel::Info,
+            "Could not check for updates",
+            Some("Zed doesn't connect to the network while offline mode is enabled."),
+            &["Ok"],
+        ));
+    } else if let Some(updater) = AutoUpdater::get(cx) {
         updater.update(cx, |updater, cx| updater.poll(cx));
     } else {
         drop(cx.prompt(
@@ -142,6 +218,52 @@ pub fn check(_: &Check, cx: &mut WindowContext) {
     }
 }
 
+pub fn roll_back(_: &RollBack, cx: &mut WindowContext) {
+    cx.spawn(|mut cx| async move {
+        let previous_version = read_previous_version().await;
+        let previous_app_path = PREVIOUS_VERSION_DIR.join(PREVIOUS_VERSION_APP_NAME);
+        let Some(previous_version) = previous_version.filter(|_| previous_app_path.exists()) else {
+            cx.update(|cx| {
+                cx.prompt(
+                    gpui::PromptLevel::Info,
+                    "Could not roll back",
+                    Some("There's no previous version of Zed to roll back to."),
+                    &["Ok"],
+                )
+            })?
+            .await
+            .ok();
+            return Ok(());
+        };
+
+        let answer = cx.update(|cx| {
+            cx.prompt(
+                gpui::PromptLevel::Warning,
+                &format!("Roll back to Zed {}?", previous_version.version),
+                Some("Zed will restart, and won't update to the current version again."),
+                &["Roll Back", "Cancel"],
+            )
+        })?;
+        if answer.await != Ok(0) {
+            return Ok(());
+        }
+
+        let running_app_path = ZED_APP_PATH
+            .clone()
+            .map_or_else(|| cx.update(|cx| cx.app_path())?, Ok)?;
+        copy_app(&previous_app_path, &running_app_path).await?;
+        smol::fs::remove_dir_all(&previous_app_path).await?;
+        write_previous_version(&PreviousVersion {
+            rolled_back: true,
+            ..previous_version
+        })
+        .await?;
+
+        cx.update(|cx| cx.restart())
+    })
+    .detach_and_log_err(cx);
+}
+
 pub fn view_release_notes(_: &ViewReleaseNotes, cx: &mut AppContext) -> Option<()> {
     let auto_updater = AutoUpdater::get(cx)?;
     let release_channel = cx.try_global::<ReleaseChannel>()?;
@@ -258,16 +380,16 @@ impl AutoUpdater {
             "{server_url}/api/releases/latest?asset=Zed.dmg&os={}&arch={}",
             OS, ARCH
         );
-        cx.update(|cx| {
-            if let Some(param) = cx
-                .try_global::<ReleaseChannel>()
-                .map(|release_channel| release_channel.release_query_param())
-                .flatten()
-            {
-                url_string += "&";
-                url_string += param;
-            }
+        let release_channel = cx.update(|cx| {
+            AutoUpdateChannelSetting::get_global(cx)
+                .0
+                .map(ReleaseChannel::from)
+                .or_else(|| cx.try_global::<ReleaseChannel>().copied())
         })?;
+        if let Some(param) = release_channel.and_then(|channel| channel.release_query_param()) {
+            url_string += "&";
+            url_string += param;
+        }
 
         let mut response = client.get(&url_string, Default::default(), true).await?;
 
@@ -280,11 +402,26 @@ impl AutoUpdater {
         let release: JsonRelease =
             serde_json::from_slice(body.as_slice()).context("error deserializing release")?;
 
-        let should_download = match *RELEASE_CHANNEL {
+        let previous_version = read_previous_version().await;
+        let current_version_name = match *RELEASE_CHANNEL {
             ReleaseChannel::Nightly => cx
-                .try_read_global::<AppCommitSha, _>(|sha, _| release.version != sha.0)
-                .unwrap_or(true),
-            _ => release.version.parse::<SemanticVersion>()? > current_version,
+                .try_read_global::<AppCommitSha, _>(|sha, _| sha.0.clone())
+                .unwrap_or_else(|| current_version.to_string()),
+            _ => current_version.to_string(),
+        };
+        let should_download = if previous_version.map_or(false, |previous_version| {
+            previous_version.rolled_back && previous_version.replaced_by == release.version
+        }) {
+            false
+        } else if release_channel.map_or(false, |channel| channel != *RELEASE_CHANNEL) {
+            // When switching channels, install the other channel's latest release, even
+            // if it's older than this one.
+            true
+        } else {
+            match *RELEASE_CHANNEL {
+                ReleaseChannel::Nightly => release.version != current_version_name,
+                _ => release.version.parse::<SemanticVersion>()? > current_version,
+            }
         };
 
         if !should_download {
@@ -311,8 +448,6 @@ impl AutoUpdater {
         let running_app_filename = running_app_path
             .file_name()
             .ok_or_else(|| anyhow!("invalid running app path"))?;
-        let mut mounted_app_path: OsString = mount_path.join(running_app_filename).into();
-        mounted_app_path.push("/");
 
         let mut dmg_file = File::create(&dmg_path).await?;
 
@@ -355,18 +490,22 @@ impl AutoUpdater {
             ))?;
         }
 
-        let output = Command::new("rsync")
-            .args(&["-av", "--delete"])
-            .arg(&mounted_app_path)
-            .arg(&running_app_path)
-            .output()
-            .await?;
-        if !output.status.success() {
-            Err(anyhow!(
-                "failed to copy app: {:?}",
-                String::from_utf8_lossy(&output.stderr)
-            ))?;
-        }
+        // Keep a copy of the running version, so that the update can be rolled back.
+        smol::fs::create_dir_all(&*PREVIOUS_VERSION_DIR).await?;
+        copy_app(
+            &running_app_path,
+            &PREVIOUS_VERSION_DIR.join(PREVIOUS_VERSION_APP_NAME),
+        )
+        .await?;
+        write_previous_version(&PreviousVersion {
+            version: current_version_name,
+            replaced_by: release.version.clone(),
+            rolled_back: false,
+        })
+        .await?;
+
+        let mounted_app_path = find_mounted_app(&mount_path, running_app_filename).await?;
+        copy_app(&mounted_app_path, &running_app_path).await?;
 
         let output = Command::new("hdiutil")
             .args(&["detach"])
@@ -419,3 +558,57 @@ impl AutoUpdater {
         })
     }
 }
+
+/// Replaces the app bundle at `destination` with a copy of the one at `source`.
+async fn copy_app(source: &Path, destination: &Path) -> Result<()> {
+    let mut source: OsString = source.into();
+    source.push("/");
+    let output = Command::new("rsync")
+        .args(&["-av", "--delete"])
+        .arg(&source)
+        .arg(destination)
+        .output()
+        .await?;
+    if !output.status.success() {
+        Err(anyhow!(
+            "failed to copy app: {:?}",
+            String::from_utf8_lossy(&output.stderr)
+        ))?;
+    }
+    Ok(())
+}
+
+/// Finds the app bundle in a mounted update, which is named for the channel it's from.
+async fn find_mounted_app(mount_path: &Path, running_app_filename: &OsStr) -> Result<PathBuf> {
+    let app_path = mount_path.join(running_app_filename);
+    if app_path.exists() {
+        return Ok(app_path);
+    }
+    let mut entries = smol::fs::read_dir(mount_path).await?;
+    while let Some(entry) = entries.next().await {
+        let path = entry?.path();
+        if path
+            .extension()
+            .map_or(false, |extension| extension == "app")
+        {
+            return Ok(path);
+        }
+    }
+    Err(anyhow!("no app found in update"))
+}
+
+async fn read_previous_version() -> Option<PreviousVersion> {
+    let info = smol::fs::read(PREVIOUS_VERSION_DIR.join(PREVIOUS_VERSION_INFO_NAME))
+        .await
+        .ok()?;
+    serde_json::from_slice(&info).log_err()
+}
+
+async fn write_previous_version(previous_version: &PreviousVersion) -> Result<()> {
+    smol::fs::write(
+        PREVIOUS_VERSION_DIR.join(PREVIOUS_VERSION_INFO_NAME),
+        serde_json::to_vec(previous_version)?,
+    )
+    .await?;
+    Ok(())
+}
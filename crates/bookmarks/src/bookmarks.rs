@@ -0,0 +1,350 @@
+//! Lists the bookmarks of a workspace in a picker, and edits the labels of bookmarks.
+//! Toggling bookmarks and moving between them is done by the editor itself.
+
+use editor::{open_bookmark, BookmarkStore, Editor};
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, div, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    Render, SharedString, Subscription, Task, View, ViewContext, VisualContext, WeakView,
+};
+use language::Buffer;
+use picker::{Picker, PickerDelegate};
+use std::{path::Path, sync::Arc};
+use theme::ActiveTheme;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(bookmarks, [Toggle, EditLabel]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(toggle_bookmark_picker);
+        },
+    )
+    .detach();
+    cx.observe_new_views(BookmarkLabelEditor::register).detach();
+}
+
+fn toggle_bookmark_picker(workspace: &mut Workspace, _: &Toggle, cx: &mut ViewContext<Workspace>) {
+    let bookmark_store = BookmarkStore::for_workspace(workspace.database_id(), cx);
+    let project = workspace.project().read(cx);
+    let entries = bookmark_store
+        .read(cx)
+        .bookmarks()
+        .iter()
+        .map(|bookmark| {
+            // Show paths relative to the worktree containing them, like elsewhere in the project.
+            let location = match project.find_local_worktree(&bookmark.path, cx) {
+                Some((_, relative_path)) => relative_path.display().to_string(),
+                None => bookmark.path.display().to_string(),
+            };
+            let location = format!("{location}:{}", bookmark.row() + 1);
+            BookmarkEntry {
+                path: bookmark.path.clone(),
+                row: bookmark.row(),
+                title: bookmark
+                    .label
+                    .as_ref()
+                    .map_or_else(|| location.clone(), ToString::to_string),
+                location: bookmark.label.is_some().then_some(location),
+            }
+        })
+        .collect();
+    let weak_workspace = cx.view().downgrade();
+    workspace.toggle_modal(cx, move |cx| {
+        let delegate = BookmarkPickerDelegate::new(cx.view().downgrade(), weak_workspace, entries);
+        BookmarkPicker {
+            picker: cx.new_view(|cx| Picker::new(delegate, cx)),
+        }
+    });
+}
+
+struct BookmarkEntry {
+    path: Arc<Path>,
+    row: u32,
+    /// The bookmark's label, or its location when it doesn't have one.
+    title: String,
+    /// The bookmark's location, shown next to its label.
+    location: Option<String>,
+}
+
+pub struct BookmarkPicker {
+    picker: View<Picker<BookmarkPickerDelegate>>,
+}
+
+impl ModalView for BookmarkPicker {}
+
+impl EventEmitter<DismissEvent> for BookmarkPicker {}
+
+impl FocusableView for BookmarkPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for BookmarkPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+/// Picks a bookmark to jump to, matching against the bookmarks' labels, or the locations of
+/// those without one.
+pub struct BookmarkPickerDelegate {
+    bookmark_picker: WeakView<BookmarkPicker>,
+    workspace: WeakView<Workspace>,
+    entries: Vec<BookmarkEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl BookmarkPickerDelegate {
+    fn new(
+        bookmark_picker: WeakView<BookmarkPicker>,
+        workspace: WeakView<Workspace>,
+        entries: Vec<BookmarkEntry>,
+    ) -> Self {
+        Self {
+            bookmark_picker,
+            workspace,
+            entries,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for BookmarkPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Go to a bookmark...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let entry = &self.entries[mat.candidate_id];
+            let (path, row) = (entry.path.clone(), entry.row);
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    open_bookmark(workspace, path, row, cx).detach_and_log_err(cx)
+                })
+                .log_err();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.bookmark_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(id, entry)| StringMatchCandidate::new(id, entry.title.clone()))
+            .collect::<Vec<_>>();
+        let background = cx.background_executor().clone();
+        cx.spawn(|this, mut cx| async move {
+            // Bookmarks are listed in order, by file and then by line, until there's a query.
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let location = self.entries[mat.candidate_id].location.clone();
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .start_slot(Icon::new(IconName::Bookmark).color(Color::Muted))
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot::<Label>(
+                    location.map(|location| Label::new(location).color(Color::Muted)),
+                ),
+        )
+    }
+}
+
+/// Edits the label of the bookmark on the cursor's line, bookmarking the line if it isn't
+/// already.
+pub struct BookmarkLabelEditor {
+    label_editor: View<Editor>,
+    bookmark_store: Model<BookmarkStore>,
+    buffer: Model<Buffer>,
+    row: u32,
+    _subscription: Subscription,
+}
+
+impl ModalView for BookmarkLabelEditor {}
+
+impl EventEmitter<DismissEvent> for BookmarkLabelEditor {}
+
+impl FocusableView for BookmarkLabelEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.label_editor.focus_handle(cx)
+    }
+}
+
+impl BookmarkLabelEditor {
+    fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+        let handle = cx.view().downgrade();
+        editor.register_action(move |_: &EditLabel, cx| {
+            let Some(editor) = handle.upgrade() else {
+                return;
+            };
+            let editor = editor.read(cx);
+            let (Some(workspace), Some(bookmark_store), Some((buffer, row))) = (
+                editor.workspace(),
+                editor.bookmark_store().cloned(),
+                editor.bookmark_position(cx),
+            ) else {
+                return;
+            };
+            workspace.update(cx, |workspace, cx| {
+                workspace.toggle_modal(cx, move |cx| {
+                    BookmarkLabelEditor::new(bookmark_store, buffer, row, cx)
+                });
+            })
+        });
+    }
+
+    fn new(
+        bookmark_store: Model<BookmarkStore>,
+        buffer: Model<Buffer>,
+        row: u32,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let label = bookmark_store
+            .read(cx)
+            .bookmark_at(&buffer, row, cx)
+            .and_then(|bookmark| bookmark.label.clone());
+        let label_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Bookmark label", cx);
+            if let Some(label) = label {
+                editor.set_text(label.to_string(), cx);
+                editor.select_all(&editor::actions::SelectAll, cx);
+            }
+            editor
+        });
+        let subscription = cx.subscribe(&label_editor, |_, _, event, cx| {
+            if let editor::EditorEvent::Blurred = event {
+                cx.emit(DismissEvent);
+            }
+        });
+        Self {
+            label_editor,
+            bookmark_store,
+            buffer,
+            row,
+            _subscription: subscription,
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let text = self.label_editor.read(cx).text(cx);
+        let label = Some(text.trim())
+            .filter(|label| !label.is_empty())
+            .map(|label| SharedString::from(label.to_string()));
+        self.bookmark_store.update(cx, |store, cx| {
+            store.set_label(&self.buffer, self.row, label, cx)
+        });
+        cx.emit(DismissEvent);
+    }
+}
+
+impl Render for BookmarkLabelEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div()
+            .elevation_2(cx)
+            .key_context("BookmarkLabelEditor")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .w_96()
+            .child(
+                v_flex()
+                    .px_1()
+                    .pt_0p5()
+                    .gap_px()
+                    .child(
+                        v_flex()
+                            .py_0p5()
+                            .px_1()
+                            .child(div().px_1().py_0p5().child(self.label_editor.clone())),
+                    )
+                    .child(
+                        div()
+                            .h_px()
+                            .w_full()
+                            .bg(cx.theme().colors().element_background),
+                    )
+                    .child(
+                        h_flex().justify_between().px_2().py_1().child(
+                            Label::new(format!("Label the bookmark on line {}", self.row + 1))
+                                .color(Color::Muted),
+                        ),
+                    ),
+            )
+    }
+}
@@ -1,11 +1,12 @@
-use editor::Editor;
+use editor::{scroll::Autoscroll, Editor, ToOffset};
 use gpui::{
-    Element, EventEmitter, IntoElement, ParentElement, Render, StyledText, Subscription,
-    ViewContext,
+    AnyElement, Element, EventEmitter, IntoElement, ParentElement, Render, StyledText,
+    Subscription, View, ViewContext, WindowContext,
 };
 use itertools::Itertools;
+use project::ProjectPath;
 use theme::ActiveTheme;
-use ui::{prelude::*, ButtonLike, ButtonStyle, Label, Tooltip};
+use ui::{popover_menu, prelude::*, ButtonLike, ButtonStyle, ContextMenu, Label, Tooltip};
 use workspace::{
     item::{ItemEvent, ItemHandle},
     ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView,
@@ -38,43 +39,181 @@ impl Render for Breadcrumbs {
         let Some(segments) = active_item.breadcrumbs(cx.theme(), cx) else {
             return element;
         };
+        let editor = active_item.downcast::<Editor>();
 
-        let highlighted_segments = segments.into_iter().map(|segment| {
+        let highlighted_segments = segments.into_iter().enumerate().map(|(ix, segment)| {
             let mut text_style = cx.text_style();
             text_style.color = Color::Muted.color(cx);
 
-            StyledText::new(segment.text)
-                .with_highlights(&text_style, segment.highlights.unwrap_or_default())
-                .into_any()
+            let text = StyledText::new(segment.text)
+                .with_highlights(&text_style, segment.highlights.unwrap_or_default());
+            match editor.as_ref() {
+                Some(editor) => render_segment_menu(ix, text, editor),
+                None => text.into_any(),
+            }
         });
         let breadcrumbs = Itertools::intersperse_with(highlighted_segments, || {
             Label::new("›").color(Color::Muted).into_any_element()
         });
 
         let breadcrumbs_stack = h_flex().gap_1().children(breadcrumbs);
-        match active_item
-            .downcast::<Editor>()
-            .map(|editor| editor.downgrade())
-        {
-            Some(editor) => element.child(
-                ButtonLike::new("toggle outline view")
-                    .child(breadcrumbs_stack)
-                    .style(ButtonStyle::Subtle)
-                    .on_click(move |_, cx| {
-                        if let Some(editor) = editor.upgrade() {
-                            outline::toggle(editor, &outline::Toggle, cx)
-                        }
-                    })
-                    .tooltip(|cx| Tooltip::for_action("Show symbol outline", &outline::Toggle, cx)),
-            ),
-            None => element
-                // Match the height of the `ButtonLike` in the other arm.
+        if editor.is_some() {
+            element.child(breadcrumbs_stack)
+        } else {
+            element
+                // Match the height of the segment buttons shown for editors.
                 .h(rems(22. / 16.))
-                .child(breadcrumbs_stack),
+                .child(breadcrumbs_stack)
         }
     }
 }
 
+/// Renders a breadcrumb segment of an editor as a button that shows the segment's
+/// siblings: the files next to the editor's file for the first segment, and the
+/// symbols next to the enclosing symbol for the others.
+fn render_segment_menu(ix: usize, text: StyledText, editor: &View<Editor>) -> AnyElement {
+    let editor = editor.downgrade();
+    popover_menu(("breadcrumb-menu", ix))
+        .trigger(
+            ButtonLike::new(("breadcrumb", ix))
+                .style(ButtonStyle::Subtle)
+                .child(text)
+                .tooltip(move |cx| {
+                    Tooltip::text(
+                        if ix == 0 {
+                            "Show sibling files"
+                        } else {
+                            "Show sibling symbols"
+                        },
+                        cx,
+                    )
+                }),
+        )
+        .menu(move |cx| {
+            let editor = editor.upgrade()?;
+            if ix == 0 {
+                sibling_files_menu(editor, cx)
+            } else {
+                sibling_symbols_menu(editor, ix - 1, cx)
+            }
+        })
+        .into_any_element()
+}
+
+fn sibling_files_menu(editor: View<Editor>, cx: &mut WindowContext) -> Option<View<ContextMenu>> {
+    let workspace = editor.read(cx).workspace()?;
+    let project_path = editor.project_path(cx)?;
+    let worktree = workspace
+        .read(cx)
+        .project()
+        .read(cx)
+        .worktree_for_id(project_path.worktree_id, cx)?;
+    let directory = project_path.path.parent()?;
+    let sibling_paths = worktree
+        .read(cx)
+        .child_entries(directory)
+        .filter(|entry| entry.is_file())
+        .map(|entry| entry.path.clone())
+        .collect::<Vec<_>>();
+
+    let workspace = workspace.downgrade();
+    Some(ContextMenu::build(cx, move |menu, _| {
+        sibling_paths.into_iter().fold(menu, |menu, path| {
+            let label = path
+                .file_name()
+                .map(|file_name| file_name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let project_path = ProjectPath {
+                worktree_id: project_path.worktree_id,
+                path,
+            };
+            let workspace = workspace.clone();
+            menu.entry(label, None, move |cx| {
+                workspace
+                    .update(cx, |workspace, cx| {
+                        workspace
+                            .open_path(project_path.clone(), None, true, cx)
+                            .detach_and_log_err(cx);
+                    })
+                    .ok();
+            })
+        })
+    }))
+}
+
+fn sibling_symbols_menu(
+    editor: View<Editor>,
+    symbol_ix: usize,
+    cx: &mut WindowContext,
+) -> Option<View<ContextMenu>> {
+    let snapshot = editor.read(cx).buffer().read(cx).snapshot(cx);
+    let cursor = editor.read(cx).selections.newest_anchor().head();
+    let (_, symbols) = snapshot.symbols_containing(cursor, None)?;
+    let symbol_start = symbols.get(symbol_ix)?.range.start.to_offset(&snapshot);
+    let outline = snapshot.outline(None)?;
+    let outline_ix = outline
+        .items
+        .iter()
+        .position(|item| item.range.start.to_offset(&snapshot) == symbol_start)?;
+    let depths = outline
+        .items
+        .iter()
+        .map(|item| item.depth)
+        .collect::<Vec<_>>();
+    let siblings = sibling_indices(&depths, outline_ix)
+        .into_iter()
+        .map(|ix| {
+            let item = &outline.items[ix];
+            (item.text.clone(), item.range.start.to_offset(&snapshot))
+        })
+        .collect::<Vec<_>>();
+
+    let editor = editor.downgrade();
+    Some(ContextMenu::build(cx, move |menu, _| {
+        let menu = siblings
+            .into_iter()
+            .fold(menu, |menu, (text, position)| {
+                let editor = editor.clone();
+                menu.entry(text, None, move |cx| {
+                    editor
+                        .update(cx, |editor, cx| {
+                            editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                                s.select_ranges([position..position])
+                            });
+                            editor.focus(cx);
+                        })
+                        .ok();
+                })
+            })
+            .separator();
+        let editor = editor.clone();
+        menu.entry(
+            "Show Symbol Outline",
+            Some(Box::new(outline::Toggle)),
+            move |cx| {
+                if let Some(editor) = editor.upgrade() {
+                    outline::toggle(editor, &outline::Toggle, cx)
+                }
+            },
+        )
+    }))
+}
+
+/// Returns the indices of the outline items that share a parent with the item at `ix`,
+/// given the depths of all outline items in order, including the item itself.
+fn sibling_indices(depths: &[usize], ix: usize) -> Vec<usize> {
+    let depth = depths[ix];
+    let start = depths[..ix]
+        .iter()
+        .rposition(|item_depth| *item_depth < depth)
+        .map_or(0, |parent_ix| parent_ix + 1);
+    let end = depths[ix..]
+        .iter()
+        .position(|item_depth| *item_depth < depth)
+        .map_or(depths.len(), |next_ix| ix + next_ix);
+    (start..end).filter(|ix| depths[*ix] == depth).collect()
+}
+
 impl ToolbarItemView for Breadcrumbs {
     fn set_active_pane_item(
         &mut self,
@@ -112,3 +251,24 @@ impl ToolbarItemView for Breadcrumbs {
         self.pane_focused = pane_focused;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sibling_indices() {
+        // mod a
+        //   fn b
+        //   struct c
+        //     field d
+        //   fn e
+        // fn f
+        let depths = [0, 1, 1, 2, 1, 0];
+        assert_eq!(sibling_indices(&depths, 0), [0, 5]);
+        assert_eq!(sibling_indices(&depths, 1), [1, 2, 4]);
+        assert_eq!(sibling_indices(&depths, 4), [1, 2, 4]);
+        assert_eq!(sibling_indices(&depths, 3), [3]);
+        assert_eq!(sibling_indices(&depths, 5), [0, 5]);
+    }
+}
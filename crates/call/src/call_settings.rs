@@ -1,5 +1,6 @@
 use anyhow::Result;
 use gpui::AppContext;
+use live_kit_client::VideoEncoding;
 use schemars::JsonSchema;
 use serde_derive::{Deserialize, Serialize};
 use settings::Settings;
@@ -7,6 +8,7 @@ use settings::Settings;
 #[derive(Deserialize, Debug)]
 pub struct CallSettings {
     pub mute_on_join: bool,
+    pub screen_share_quality: ScreenShareQuality,
 }
 
 /// Configuration of voice calls in Zed.
@@ -16,6 +18,46 @@ pub struct CallSettingsContent {
     ///
     /// Default: false
     pub mute_on_join: Option<bool>,
+    /// The bitrate and framerate to share screens at.
+    ///
+    /// Default: standard
+    pub screen_share_quality: Option<ScreenShareQuality>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenShareQuality {
+    /// 500 kbps at 5 frames per second, for slow connections.
+    Low,
+    /// 2.5 Mbps at 15 frames per second.
+    #[default]
+    Standard,
+    /// 6 Mbps at 30 frames per second, for smooth scrolling and animations.
+    High,
+}
+
+impl ScreenShareQuality {
+    pub const ALL: [Self; 3] = [Self::Low, Self::Standard, Self::High];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Low => "Low",
+            Self::Standard => "Standard",
+            Self::High => "High",
+        }
+    }
+
+    pub fn video_encoding(&self) -> VideoEncoding {
+        let (max_bitrate, max_framerate) = match self {
+            Self::Low => (500_000, 5),
+            Self::Standard => (2_500_000, 15),
+            Self::High => (6_000_000, 30),
+        };
+        VideoEncoding {
+            max_bitrate,
+            max_framerate,
+        }
+    }
 }
 
 impl Settings for CallSettings {
@@ -1417,6 +1417,9 @@ impl Room {
             return Task::ready(Err(anyhow!("screen was already shared")));
         }
 
+        let encoding = CallSettings::get_global(cx)
+            .screen_share_quality
+            .video_encoding();
         let (displays, publish_id) = if let Some(live_kit) = self.live_kit.as_mut() {
             let publish_id = post_inc(&mut live_kit.next_publish_id);
             live_kit.screen_track = LocalTrack::Pending { publish_id };
@@ -1438,7 +1441,7 @@ impl Room {
                     .update(&mut cx, |this, _| {
                         this.live_kit
                             .as_ref()
-                            .map(|live_kit| live_kit.room.publish_video_track(track))
+                            .map(|live_kit| live_kit.room.publish_video_track(track, encoding))
                     })?
                     .ok_or_else(|| anyhow!("live-kit was not initialized"))?
                     .await
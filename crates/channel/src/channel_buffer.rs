@@ -8,7 +8,8 @@ use rpc::{
     proto::{self, PeerId},
     TypedEnvelope,
 };
-use std::{sync::Arc, time::Duration};
+use similar::{ChangeTag, TextDiff};
+use std::{ops::Range, sync::Arc, time::Duration};
 use text::BufferId;
 use util::ResultExt;
 
@@ -22,6 +23,9 @@ pub(crate) fn init(client: &Arc<Client>) {
 pub struct ChannelBuffer {
     pub channel_id: ChannelId,
     connected: bool,
+    /// The text of the buffer when the connection was lost, which edits made while
+    /// offline are merged from once the buffer is reopened.
+    offline_base_text: Option<String>,
     collaborators: HashMap<PeerId, Collaborator>,
     user_store: Model<UserStore>,
     channel_store: Model<ChannelStore>,
@@ -35,6 +39,9 @@ pub struct ChannelBuffer {
 pub enum ChannelBufferEvent {
     CollaboratorsChanged,
     Disconnected,
+    /// The buffer was opened again after going offline, with any edits made while
+    /// offline merged into the new buffer.
+    Reopened(Model<ChannelBuffer>),
     BufferEdited,
     ChannelChanged,
 }
@@ -78,6 +85,7 @@ impl ChannelBuffer {
                 buffer_epoch: response.epoch,
                 client,
                 connected: true,
+                offline_base_text: None,
                 collaborators: Default::default(),
                 acknowledge_task: None,
                 channel_id: channel.id,
@@ -178,6 +186,9 @@ impl ChannelBuffer {
     ) {
         match event {
             language::Event::Operation(operation) => {
+                if !self.connected {
+                    return;
+                }
                 if *ZED_ALWAYS_ACTIVE {
                     match operation {
                         language::Operation::UpdateSelections { selections, .. } => {
@@ -244,6 +255,52 @@ impl ChannelBuffer {
             .cloned()
     }
 
+    /// Remembers the text of the buffer when the connection is lost, in case the buffer
+    /// can't be rejoined and has to be reopened.
+    pub(crate) fn connection_lost(&mut self, cx: &AppContext) {
+        if self.offline_base_text.is_none() {
+            self.offline_base_text = Some(self.buffer.read(cx).text());
+        }
+    }
+
+    pub(crate) fn connection_restored(&mut self) {
+        self.offline_base_text = None;
+    }
+
+    /// Whether the buffer was edited since the connection was lost.
+    pub fn has_offline_edits(&self, cx: &AppContext) -> bool {
+        self.offline_base_text
+            .as_ref()
+            .map_or(false, |base_text| *base_text != self.buffer.read(cx).text())
+    }
+
+    /// Merges the edits made to this buffer while offline into the given buffer, which
+    /// was opened for the same channel after reconnecting.
+    pub(crate) fn merge_offline_edits_into(
+        &self,
+        reopened_buffer: &Model<ChannelBuffer>,
+        cx: &mut AppContext,
+    ) -> Task<()> {
+        let Some(base_text) = self.offline_base_text.clone() else {
+            return Task::ready(());
+        };
+        let offline_text = self.buffer.read(cx).text();
+        let buffer = reopened_buffer.read(cx).buffer();
+        if offline_text == base_text || buffer.read(cx).read_only() {
+            return Task::ready(());
+        }
+
+        let current_text = buffer.read(cx).text();
+        let merged_text = merge_offline_edits(&base_text, &offline_text, &current_text);
+        let diff = buffer.update(cx, |buffer, cx| buffer.diff(merged_text, cx));
+        cx.spawn(|mut cx| async move {
+            let diff = diff.await;
+            buffer
+                .update(&mut cx, |buffer, cx| buffer.apply_diff(diff, cx))
+                .ok();
+        })
+    }
+
     pub(crate) fn disconnect(&mut self, cx: &mut ModelContext<Self>) {
         log::info!("channel buffer {} disconnected", self.channel_id);
         if self.connected {
@@ -267,3 +324,85 @@ impl ChannelBuffer {
         self.buffer.read(cx).replica_id()
     }
 }
+
+/// Merges the edits made to a buffer while offline with the edits others made to it in the
+/// meantime. Both sets of edits are applied to the text the buffer had when the connection
+/// was lost, by two replicas of that text, so that they combine the way concurrent edits of
+/// collaborators do: text inserted by either is kept, even where the other deleted around it.
+fn merge_offline_edits(base_text: &str, offline_text: &str, current_text: &str) -> String {
+    let buffer_id = BufferId::new(1).unwrap();
+    let mut current = text::Buffer::new(0, buffer_id, base_text.to_string());
+    let mut offline = text::Buffer::new(1, buffer_id, base_text.to_string());
+    let current_edits = text_edits(base_text, current_text);
+    if !current_edits.is_empty() {
+        current.edit(current_edits);
+    }
+    let offline_edits = text_edits(base_text, offline_text);
+    if !offline_edits.is_empty() {
+        let operation = offline.edit(offline_edits);
+        current.apply_ops([operation]).log_err();
+    }
+    current.text()
+}
+
+/// The edits that turn one text into another, as ranges of the old text and their new text.
+fn text_edits(old_text: &str, new_text: &str) -> Vec<(Range<usize>, String)> {
+    let mut edits = Vec::<(Range<usize>, String)>::new();
+    let mut old_offset = 0;
+    for change in TextDiff::from_chars(old_text, new_text).iter_all_changes() {
+        let value = change.value();
+        match change.tag() {
+            ChangeTag::Equal => old_offset += value.len(),
+            ChangeTag::Delete => {
+                let range = old_offset..old_offset + value.len();
+                old_offset = range.end;
+                match edits.last_mut() {
+                    Some((last_range, _)) if last_range.end == range.start => {
+                        last_range.end = range.end
+                    }
+                    _ => edits.push((range, String::new())),
+                }
+            }
+            ChangeTag::Insert => match edits.last_mut() {
+                Some((last_range, text)) if last_range.end == old_offset => text.push_str(value),
+                _ => edits.push((old_offset..old_offset, value.to_string())),
+            },
+        }
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_offline_edits() {
+        let base_text = "# Agenda\n\n- design review\n- retro\n";
+
+        // Edits made in different places are both kept.
+        assert_eq!(
+            merge_offline_edits(
+                base_text,
+                "# Agenda\n\n- design review\n- retro\n- planning\n",
+                "# Weekly Agenda\n\n- design review\n- retro\n",
+            ),
+            "# Weekly Agenda\n\n- design review\n- retro\n- planning\n"
+        );
+
+        // Text inserted offline survives the deletion of the text around it.
+        let merged_text = merge_offline_edits(
+            base_text,
+            "# Agenda\n\n- design review (30 min)\n- retro\n",
+            "# Agenda\n\n- retro\n",
+        );
+        assert!(merged_text.contains(" (30 min)"), "{merged_text:?}");
+        assert!(!merged_text.contains("design review"), "{merged_text:?}");
+
+        // Without offline edits, the current text is kept as it is.
+        assert_eq!(
+            merge_offline_edits(base_text, base_text, "# Agenda\n"),
+            "# Agenda\n"
+        );
+    }
+}
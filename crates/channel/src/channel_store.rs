@@ -1,6 +1,10 @@
 mod channel_index;
 
-use crate::{channel_buffer::ChannelBuffer, channel_chat::ChannelChat, ChannelMessage};
+use crate::{
+    channel_buffer::{ChannelBuffer, ChannelBufferEvent},
+    channel_chat::ChannelChat,
+    ChannelMessage,
+};
 use anyhow::{anyhow, Result};
 use channel_index::ChannelIndex;
 use client::{Client, Subscription, User, UserId, UserStore};
@@ -44,6 +48,9 @@ pub struct ChannelStore {
     outgoing_invites: HashSet<(ChannelId, UserId)>,
     update_channels_tx: mpsc::UnboundedSender<proto::UpdateChannels>,
     opened_buffers: HashMap<ChannelId, OpenedModelHandle<ChannelBuffer>>,
+    /// Buffers that were disconnected while open, to reopen once their channels are
+    /// known again.
+    offline_buffers: HashMap<ChannelId, WeakModel<ChannelBuffer>>,
     opened_chats: HashMap<ChannelId, OpenedModelHandle<ChannelChat>>,
     client: Arc<Client>,
     user_store: Model<UserStore>,
@@ -189,6 +196,7 @@ impl ChannelStore {
             channel_participants: Default::default(),
             outgoing_invites: Default::default(),
             opened_buffers: Default::default(),
+            offline_buffers: Default::default(),
             opened_chats: Default::default(),
             update_channels_tx,
             client,
@@ -898,6 +906,7 @@ impl ChannelStore {
                                 let remote_version =
                                     language::proto::deserialize_version(&remote_buffer.version);
 
+                                channel_buffer.connection_restored();
                                 channel_buffer.replace_collaborators(
                                     mem::take(&mut remote_buffer.collaborators),
                                     cx,
@@ -940,11 +949,14 @@ impl ChannelStore {
                             }
 
                             channel_buffer.disconnect(cx);
+                            this.offline_buffers
+                                .insert(channel_buffer.channel_id, cx.handle().downgrade());
                             false
                         })
                     }
                     OpenedModelHandle::Loading(_) => true,
                 });
+                this.reopen_offline_buffers(cx);
             })
             .ok();
             anyhow::Ok(())
@@ -954,6 +966,14 @@ impl ChannelStore {
     fn handle_disconnect(&mut self, wait_for_reconnect: bool, cx: &mut ModelContext<Self>) {
         cx.notify();
 
+        for buffer in self.opened_buffers.values() {
+            if let OpenedModelHandle::Open(buffer) = buffer {
+                if let Some(buffer) = buffer.upgrade() {
+                    buffer.update(cx, |buffer, cx| buffer.connection_lost(cx));
+                }
+            }
+        }
+
         self.disconnect_channel_buffers_task.get_or_insert_with(|| {
             cx.spawn(move |this, mut cx| async move {
                 if wait_for_reconnect {
@@ -962,10 +982,11 @@ impl ChannelStore {
 
                 if let Some(this) = this.upgrade() {
                     this.update(&mut cx, |this, cx| {
-                        for (_, buffer) in this.opened_buffers.drain() {
+                        for (channel_id, buffer) in this.opened_buffers.drain() {
                             if let OpenedModelHandle::Open(buffer) = buffer {
                                 if let Some(buffer) = buffer.upgrade() {
                                     buffer.update(cx, |buffer, cx| buffer.disconnect(cx));
+                                    this.offline_buffers.insert(channel_id, buffer.downgrade());
                                 }
                             }
                         }
@@ -976,6 +997,40 @@ impl ChannelStore {
         });
     }
 
+    /// Reopens the buffers that were disconnected while open, once their channels are
+    /// known again, and merges the edits made to them while offline into the new buffers.
+    fn reopen_offline_buffers(&mut self, cx: &mut ModelContext<Self>) {
+        let channel_ids = self
+            .offline_buffers
+            .keys()
+            .copied()
+            .filter(|channel_id| self.channel_for_id(*channel_id).is_some())
+            .collect::<Vec<_>>();
+        for channel_id in channel_ids {
+            let Some(offline_buffer) = self
+                .offline_buffers
+                .remove(&channel_id)
+                .and_then(|buffer| buffer.upgrade())
+            else {
+                continue;
+            };
+
+            let reopened_buffer = self.open_channel_buffer(channel_id, cx);
+            cx.spawn(|_, mut cx| async move {
+                let reopened_buffer = reopened_buffer.await?;
+                offline_buffer
+                    .update(&mut cx, |offline_buffer, cx| {
+                        offline_buffer.merge_offline_edits_into(&reopened_buffer, cx)
+                    })?
+                    .await;
+                offline_buffer.update(&mut cx, |_, cx| {
+                    cx.emit(ChannelBufferEvent::Reopened(reopened_buffer))
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
     pub(crate) fn update_channels(
         &mut self,
         payload: proto::UpdateChannels,
@@ -1025,6 +1080,7 @@ impl ChannelStore {
                     {
                         continue;
                     }
+                    self.offline_buffers.remove(&channel_id);
                     if let Some(OpenedModelHandle::Open(buffer)) =
                         self.opened_buffers.remove(&channel_id)
                     {
@@ -1063,6 +1119,8 @@ impl ChannelStore {
                     .or_default()
                     .update_latest_message_id(latest_channel_message.message_id);
             }
+
+            self.reopen_offline_buffers(cx);
         }
 
         cx.notify();
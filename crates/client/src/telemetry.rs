@@ -1,6 +1,6 @@
 mod event_coalescer;
 
-use crate::{TelemetrySettings, ZED_SERVER_URL};
+use crate::{OfflineModeSetting, TelemetrySettings, ZED_SERVER_URL};
 use chrono::{DateTime, Utc};
 use futures::Future;
 use gpui::{AppContext, AppMetadata, BackgroundExecutor, Task};
@@ -28,6 +28,7 @@ pub struct Telemetry {
 
 struct TelemetryState {
     settings: TelemetrySettings,
+    offline: bool,
     metrics_id: Option<Arc<str>>,      // Per logged-in user
     installation_id: Option<Arc<str>>, // Per app installation (different for dev, nightly, preview, and stable)
     session_id: Option<Arc<str>>,      // Per app launch
@@ -155,9 +156,11 @@ impl Telemetry {
             .map(|release_channel| release_channel.display_name());
 
         TelemetrySettings::register(cx);
+        OfflineModeSetting::register(cx);
 
         let state = Arc::new(Mutex::new(TelemetryState {
             settings: TelemetrySettings::get_global(cx).clone(),
+            offline: OfflineModeSetting::get_global(cx).0,
             app_metadata: cx.app_metadata(),
             architecture: env::consts::ARCH,
             release_channel,
@@ -193,6 +196,7 @@ impl Telemetry {
             move |cx| {
                 let mut state = state.lock();
                 state.settings = TelemetrySettings::get_global(cx).clone();
+                state.offline = OfflineModeSetting::get_global(cx).0;
             }
         })
         .detach();
@@ -461,7 +465,7 @@ impl Telemetry {
     fn report_event(self: &Arc<Self>, event: Event) {
         let mut state = self.state.lock();
 
-        if !state.settings.metrics {
+        if !state.settings.metrics || state.offline {
             return;
         }
 
@@ -502,8 +506,10 @@ impl Telemetry {
         state.first_event_date_time = None;
         let mut events = mem::take(&mut state.events_queue);
         state.flush_events_task.take();
+        let offline = state.offline;
         drop(state);
-        if events.is_empty() {
+        // Events queued before going offline are dropped rather than sent.
+        if events.is_empty() || offline {
             return;
         }
 
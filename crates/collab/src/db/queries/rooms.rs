@@ -1017,6 +1017,10 @@ impl Database {
         user_id: UserId,
         role: ChannelRole,
     ) -> Result<RoomGuard<proto::Room>> {
+        if admin_id == user_id {
+            Err(anyhow!("admins cannot change their own role"))?;
+        }
+
         self.room_transaction(room_id, |tx| async move {
             room_participant::Entity::find()
                 .filter(
@@ -1028,6 +1032,28 @@ impl Database {
                 .one(&*tx)
                 .await?
                 .ok_or_else(|| anyhow!("only admins can set participant role"))?;
+            let participant = room_participant::Entity::find()
+                .filter(
+                    Condition::all()
+                        .add(room_participant::Column::RoomId.eq(room_id))
+                        .add(room_participant::Column::UserId.eq(user_id)),
+                )
+                .one(&*tx)
+                .await?
+                .ok_or_else(|| anyhow!("could not find room participant"))?;
+
+            // Participants promoted to admin during the call can't change the roles of
+            // the room's owners, or make or unmake other admins.
+            if self.is_room_owner(room_id, user_id, &tx).await? {
+                Err(anyhow!("cannot change the role of a channel admin or host"))?;
+            }
+            let changes_admins =
+                role == ChannelRole::Admin || participant.role == Some(ChannelRole::Admin);
+            if changes_admins && !self.is_room_owner(room_id, admin_id, &tx).await? {
+                Err(anyhow!(
+                    "only channel admins or the host can grant or revoke admin"
+                ))?;
+            }
 
             if role.requires_cla() {
                 self.check_user_has_signed_cla(user_id, room_id, &*tx)
@@ -1055,6 +1081,37 @@ impl Database {
         .await
     }
 
+    /// Returns whether the user owns the room: an admin of its channel, or the host of
+    /// a call outside of channels.
+    async fn is_room_owner(
+        &self,
+        room_id: RoomId,
+        user_id: UserId,
+        tx: &DatabaseTransaction,
+    ) -> Result<bool> {
+        let room = room::Entity::find_by_id(room_id)
+            .one(&*tx)
+            .await?
+            .ok_or_else(|| anyhow!("could not find room"))?;
+        if let Some(channel_id) = room.channel_id {
+            let channel = self.get_channel_internal(channel_id, &*tx).await?;
+            let role = self.channel_role_for_user(&channel, user_id, &*tx).await?;
+            Ok(role == Some(ChannelRole::Admin))
+        } else {
+            // The host is the participant who started the call, by calling themselves.
+            let host = room_participant::Entity::find()
+                .filter(
+                    Condition::all()
+                        .add(room_participant::Column::RoomId.eq(room_id))
+                        .add(room_participant::Column::UserId.eq(user_id))
+                        .add(room_participant::Column::CallingUserId.eq(user_id)),
+                )
+                .one(&*tx)
+                .await?;
+            Ok(host.is_some())
+        }
+    }
+
     async fn check_user_has_signed_cla(
         &self,
         user_id: UserId,
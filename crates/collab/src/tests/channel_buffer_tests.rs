@@ -3,7 +3,7 @@ use crate::{
     tests::TestServer,
 };
 use call::ActiveCall;
-use channel::ACKNOWLEDGE_DEBOUNCE_INTERVAL;
+use channel::{ChannelBufferEvent, ACKNOWLEDGE_DEBOUNCE_INTERVAL};
 use client::{Collaborator, ParticipantIndex, UserId};
 use collab_ui::channel_view::ChannelView;
 use collections::HashMap;
@@ -12,7 +12,7 @@ use futures::future;
 use gpui::{BackgroundExecutor, Model, TestAppContext, ViewContext};
 use rpc::{proto::PeerId, RECEIVE_TIMEOUT};
 use serde_json::json;
-use std::ops::Range;
+use std::{cell::RefCell, ops::Range, rc::Rc};
 
 #[gpui::test]
 async fn test_core_channel_buffers(
@@ -115,7 +115,6 @@ async fn test_core_channel_buffers(
     });
 
     // TODO:
-    // - Test synchronizing offline updates, what happens to A's channel buffer when A disconnects
     // - Test interaction with channel deletion while buffer is open
 }
 
@@ -512,6 +511,99 @@ async fn test_rejoin_channel_buffer(
     });
 }
 
+#[gpui::test]
+async fn test_channel_buffer_offline_edits(
+    deterministic: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(deterministic.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+
+    let channel_id = server
+        .make_channel(
+            "the-channel",
+            None,
+            (&client_a, cx_a),
+            &mut [(&client_b, cx_b)],
+        )
+        .await;
+
+    let channel_buffer_a = client_a
+        .channel_store()
+        .update(cx_a, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+    let channel_buffer_b = client_b
+        .channel_store()
+        .update(cx_b, |store, cx| store.open_channel_buffer(channel_id, cx))
+        .await
+        .unwrap();
+
+    channel_buffer_a.update(cx_a, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "notes\n")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+
+    // Client A stays offline for longer than the server waits for it to rejoin.
+    server.forbid_connections();
+    server.disconnect_client(client_a.peer_id().unwrap());
+    deterministic.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    channel_buffer_a.read_with(cx_a, |buffer, cx| {
+        assert!(!buffer.is_connected());
+        assert!(!buffer.has_offline_edits(cx));
+    });
+
+    // Both clients keep editing.
+    channel_buffer_a.update(cx_a, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            let len = buffer.len();
+            buffer.edit([(len..len, "offline edit\n")], None, cx);
+        })
+    });
+    channel_buffer_b.update(cx_b, |buffer, cx| {
+        buffer.buffer().update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "online edit\n")], None, cx);
+        })
+    });
+    deterministic.run_until_parked();
+    channel_buffer_a.read_with(cx_a, |buffer, cx| assert!(buffer.has_offline_edits(cx)));
+
+    let reopened_buffer_a = Rc::new(RefCell::new(None));
+    let _subscription = cx_a.update(|cx| {
+        let reopened_buffer_a = reopened_buffer_a.clone();
+        cx.subscribe(&channel_buffer_a, move |_, event, _| {
+            if let ChannelBufferEvent::Reopened(buffer) = event {
+                reopened_buffer_a.replace(Some(buffer.clone()));
+            }
+        })
+    });
+
+    // Client A reconnects. Its buffer is reopened, and the offline edit is merged with
+    // the edit that client B made in the meantime.
+    server.allow_connections();
+    deterministic.advance_clock(RECEIVE_TIMEOUT + RECONNECT_TIMEOUT);
+    deterministic.run_until_parked();
+
+    let reopened_buffer_a = reopened_buffer_a.borrow_mut().take().unwrap();
+    reopened_buffer_a.read_with(cx_a, |buffer, cx| {
+        assert!(buffer.is_connected());
+        assert_eq!(
+            buffer.buffer().read(cx).text(),
+            "online edit\nnotes\noffline edit\n"
+        );
+    });
+    channel_buffer_b.read_with(cx_b, |buffer, cx| {
+        assert_eq!(
+            buffer.buffer().read(cx).text(),
+            "online edit\nnotes\noffline edit\n"
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_channel_buffers_and_server_restarts(
     deterministic: BackgroundExecutor,
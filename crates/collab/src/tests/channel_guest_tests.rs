@@ -160,6 +160,180 @@ async fn test_channel_guest_promotion(cx_a: &mut TestAppContext, cx_b: &mut Test
         .is_err());
 }
 
+#[gpui::test]
+async fn test_channel_guest_full_access(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let channel_id = server
+        .make_public_channel("the-channel", &client_a, cx_a)
+        .await;
+
+    let project_a = client_a.build_test_project(cx_a).await;
+    cx_a.update(|cx| workspace::join_channel(channel_id, client_a.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // Clients B and C join the channel as guests
+    cx_b.update(|cx| workspace::join_channel(channel_id, client_b.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_c.update(|cx| workspace::join_channel(channel_id, client_c.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    let room_b = cx_b
+        .read(ActiveCall::global)
+        .read_with(cx_b, |call, _| call.room().unwrap().clone());
+    let (workspace_c, cx_c) = client_c.active_workspace(cx_c);
+    let project_c = workspace_c.read_with(cx_c, |workspace, _| workspace.project().clone());
+    assert!(project_c.read_with(cx_c, |project, _| project.is_read_only()));
+
+    // A grants B full access
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.room().unwrap().update(cx, |room, cx| {
+                room.set_participant_role(
+                    client_b.user_id().unwrap(),
+                    proto::ChannelRole::Admin,
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    assert!(room_b.read_with(cx_b, |room, _| room.local_participant_is_admin()));
+
+    // B can now manage the access of other guests
+    room_b
+        .update(cx_b, |room, cx| {
+            room.set_participant_role(client_c.user_id().unwrap(), proto::ChannelRole::Member, cx)
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    assert!(project_c.read_with(cx_c, |project, _| !project.is_read_only()));
+
+    // Admins can't change their own role
+    assert!(room_b
+        .update(cx_b, |room, cx| {
+            room.set_participant_role(client_b.user_id().unwrap(), proto::ChannelRole::Guest, cx)
+        })
+        .await
+        .is_err());
+
+    // A revokes B's full access, after which B can't change anyone's role
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.room().unwrap().update(cx, |room, cx| {
+                room.set_participant_role(
+                    client_b.user_id().unwrap(),
+                    proto::ChannelRole::Member,
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    assert!(!room_b.read_with(cx_b, |room, _| room.local_participant_is_admin()));
+    assert!(room_b
+        .update(cx_b, |room, cx| {
+            room.set_participant_role(client_c.user_id().unwrap(), proto::ChannelRole::Guest, cx)
+        })
+        .await
+        .is_err());
+    cx_a.run_until_parked();
+    assert!(project_c.read_with(cx_c, |project, _| !project.is_read_only()));
+}
+
+#[gpui::test]
+async fn test_channel_guest_admin_cannot_change_owners(
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+    cx_c: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(cx_a.executor()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    let client_c = server.create_client(cx_c, "user_c").await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    let channel_id = server
+        .make_public_channel("the-channel", &client_a, cx_a)
+        .await;
+    cx_a.update(|cx| workspace::join_channel(channel_id, client_a.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_b.update(|cx| workspace::join_channel(channel_id, client_b.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_c.update(|cx| workspace::join_channel(channel_id, client_c.app_state.clone(), None, cx))
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+
+    // A, the channel admin, makes guest B an admin of the call
+    active_call_a
+        .update(cx_a, |call, cx| {
+            call.room().unwrap().update(cx, |room, cx| {
+                room.set_participant_role(
+                    client_b.user_id().unwrap(),
+                    proto::ChannelRole::Admin,
+                    cx,
+                )
+            })
+        })
+        .await
+        .unwrap();
+    cx_a.run_until_parked();
+    let room_b = cx_b
+        .read(ActiveCall::global)
+        .read_with(cx_b, |call, _| call.room().unwrap().clone());
+    assert!(room_b.read_with(cx_b, |room, _| room.local_participant_is_admin()));
+
+    // B can't demote A
+    assert!(room_b
+        .update(cx_b, |room, cx| {
+            room.set_participant_role(client_a.user_id().unwrap(), proto::ChannelRole::Guest, cx)
+        })
+        .await
+        .is_err());
+    cx_a.run_until_parked();
+    assert!(active_call_a.read_with(cx_a, |call, cx| call
+        .room()
+        .unwrap()
+        .read(cx)
+        .local_participant_is_admin()));
+
+    // B can't make C an admin
+    assert!(room_b
+        .update(cx_b, |room, cx| {
+            room.set_participant_role(client_c.user_id().unwrap(), proto::ChannelRole::Admin, cx)
+        })
+        .await
+        .is_err());
+    cx_a.run_until_parked();
+    let room_c = cx_c
+        .read(ActiveCall::global)
+        .read_with(cx_c, |call, _| call.room().unwrap().clone());
+    assert!(!room_c.read_with(cx_c, |room, _| room.local_participant_is_admin()));
+}
+
 #[gpui::test]
 async fn test_channel_requires_zed_cla(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
     let mut server = TestServer::start(cx_a.executor()).await;
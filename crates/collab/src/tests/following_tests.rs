@@ -13,6 +13,7 @@ use language::Capability;
 use live_kit_client::MacOSDisplay;
 use project::project_settings::ProjectSettings;
 use rpc::proto::PeerId;
+use search::ProjectSearchView;
 use serde_json::json;
 use settings::SettingsStore;
 use workspace::{
@@ -1996,3 +1997,61 @@ async fn test_following_to_channel_notes_without_a_shared_project(
         );
     });
 }
+
+#[gpui::test]
+async fn test_following_project_search(cx_a: &mut TestAppContext, cx_b: &mut TestAppContext) {
+    let (_, client_a, client_b, channel_id) = TestServer::start2(cx_a, cx_b).await;
+
+    let (workspace_a, cx_a) = client_a.build_test_workspace(cx_a).await;
+    client_a
+        .host_workspace(&workspace_a, channel_id, cx_a)
+        .await;
+    let (workspace_b, cx_b) = client_b.join_workspace(channel_id, cx_b).await;
+    let peer_id_a = client_a.peer_id().unwrap();
+
+    // Client A searches the project.
+    cx_a.simulate_keystrokes("cmd-shift-f");
+    cx_a.simulate_input("one");
+    cx_a.simulate_keystrokes("enter");
+    cx_a.run_until_parked();
+    let search_a = workspace_a.update(cx_a, |workspace, cx| {
+        workspace.active_item_as::<ProjectSearchView>(cx).unwrap()
+    });
+    let results_a = search_a.update(cx_a, |search, cx| search.results_editor().read(cx).text(cx));
+    assert!(results_a.contains("one"));
+
+    // Client B follows client A into the search, seeing the same results.
+    workspace_b.update(cx_b, |workspace, cx| workspace.follow(peer_id_a, cx));
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    let search_b = workspace_b.update(cx_b, |workspace, cx| {
+        workspace
+            .active_item(cx)
+            .expect("no active item")
+            .downcast::<ProjectSearchView>()
+            .expect("active item is not a project search")
+    });
+    search_b.update(cx_b, |search, cx| {
+        assert!(search.has_matches());
+        assert_eq!(search.results_editor().read(cx).text(cx), results_a);
+    });
+
+    // Client A searches for something else, and client B's results follow along.
+    cx_a.simulate_keystrokes("cmd-shift-f");
+    cx_a.simulate_input("two");
+    cx_a.simulate_keystrokes("enter");
+    cx_a.run_until_parked();
+    cx_b.run_until_parked();
+    let results_a = search_a.update(cx_a, |search, cx| search.results_editor().read(cx).text(cx));
+    assert!(results_a.contains("function two()"));
+    search_b.update(cx_b, |search, cx| {
+        assert!(search.has_matches());
+        assert_eq!(search.results_editor().read(cx).text(cx), results_a);
+    });
+    workspace_b.update(cx_b, |workspace, _| {
+        assert_eq!(
+            workspace.leader_for_pane(workspace.active_pane()),
+            Some(peer_id_a)
+        );
+    });
+}
@@ -264,6 +264,7 @@ impl TestServer {
             notifications::init(client.clone(), user_store, cx);
             collab_ui::init(&app_state, cx);
             file_finder::init(cx);
+            search::init(cx);
             menu::init();
             settings::KeymapFile::load_asset("keymaps/default.json", cx).unwrap();
         });
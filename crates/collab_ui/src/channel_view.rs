@@ -39,6 +39,7 @@ pub struct ChannelView {
     channel_buffer: Model<ChannelBuffer>,
     remote_id: Option<ViewId>,
     _editor_event_subscription: Subscription,
+    _channel_buffer_subscription: Subscription,
 }
 
 impl ChannelView {
@@ -132,19 +133,11 @@ impl ChannelView {
         channel_buffer: Model<ChannelBuffer>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
-        let buffer = channel_buffer.read(cx).buffer();
-        let editor = cx.new_view(|cx| {
-            let mut editor = Editor::for_buffer(buffer, None, cx);
-            editor.set_collaboration_hub(Box::new(ChannelBufferCollaborationHub(
-                channel_buffer.clone(),
-            )));
-            editor
-        });
+        let editor = Self::build_editor(&channel_buffer, cx);
         let _editor_event_subscription =
             cx.subscribe(&editor, |_, _, e: &EditorEvent, cx| cx.emit(e.clone()));
-
-        cx.subscribe(&channel_buffer, Self::handle_channel_buffer_event)
-            .detach();
+        let _channel_buffer_subscription =
+            cx.subscribe(&channel_buffer, Self::handle_channel_buffer_event);
 
         Self {
             editor,
@@ -153,7 +146,70 @@ impl ChannelView {
             channel_buffer,
             remote_id: None,
             _editor_event_subscription,
+            _channel_buffer_subscription,
+        }
+    }
+
+    fn build_editor(
+        channel_buffer: &Model<ChannelBuffer>,
+        cx: &mut ViewContext<Self>,
+    ) -> View<Editor> {
+        let buffer = channel_buffer.read(cx).buffer();
+        cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer, None, cx);
+            editor.set_collaboration_hub(Box::new(ChannelBufferCollaborationHub(
+                channel_buffer.clone(),
+            )));
+            editor
+        })
+    }
+
+    /// Switches to a buffer that was reopened after going offline, keeping the cursor
+    /// where it was.
+    fn replace_channel_buffer(
+        &mut self,
+        channel_buffer: Model<ChannelBuffer>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let language_registry = self.project.read(cx).languages().clone();
+        let language = self
+            .channel_buffer
+            .read(cx)
+            .buffer()
+            .read(cx)
+            .language()
+            .cloned();
+        channel_buffer.update(cx, |channel_buffer, cx| {
+            channel_buffer.buffer().update(cx, |buffer, cx| {
+                buffer.set_language_registry(language_registry);
+                buffer.set_language(language, cx);
+            })
+        });
+
+        let cursor_offset = self.editor.update(cx, |editor, cx| {
+            editor.selections.newest::<usize>(cx).head()
+        });
+        let cursor_offset = cursor_offset.min(channel_buffer.read(cx).buffer().read(cx).len());
+        let was_focused = self.editor.read(cx).is_focused(cx);
+
+        self.editor = Self::build_editor(&channel_buffer, cx);
+        self.editor.update(cx, |editor, cx| {
+            editor.change_selections(None, cx, |selections| {
+                selections.select_ranges([cursor_offset..cursor_offset])
+            });
+        });
+        if was_focused {
+            cx.focus_view(&self.editor);
         }
+        self._editor_event_subscription =
+            cx.subscribe(&self.editor, |_, _, e: &EditorEvent, cx| cx.emit(e.clone()));
+        self._channel_buffer_subscription =
+            cx.subscribe(&channel_buffer, Self::handle_channel_buffer_event);
+        self.channel_buffer = channel_buffer;
+        self.acknowledge_buffer_version(cx);
+
+        cx.emit(EditorEvent::TitleChanged);
+        cx.notify();
     }
 
     pub fn channel(&self, cx: &AppContext) -> Option<Arc<Channel>> {
@@ -167,10 +223,15 @@ impl ChannelView {
         cx: &mut ViewContext<Self>,
     ) {
         match event {
-            ChannelBufferEvent::Disconnected => self.editor.update(cx, |editor, cx| {
-                editor.set_read_only(true);
-                cx.notify();
-            }),
+            ChannelBufferEvent::Disconnected => {
+                self.editor.update(cx, |_, cx| {
+                    cx.emit(editor::EditorEvent::TitleChanged);
+                    cx.notify()
+                });
+            }
+            ChannelBufferEvent::Reopened(channel_buffer) => {
+                self.replace_channel_buffer(channel_buffer.clone(), cx)
+            }
             ChannelBufferEvent::ChannelChanged => {
                 self.editor.update(cx, |_, cx| {
                     cx.emit(editor::EditorEvent::TitleChanged);
@@ -246,13 +307,17 @@ impl Item for ChannelView {
 
     fn tab_content(&self, _: Option<usize>, selected: bool, cx: &WindowContext) -> AnyElement {
         let label = if let Some(channel) = self.channel(cx) {
+            let channel_buffer = self.channel_buffer.read(cx);
             match (
-                self.channel_buffer.read(cx).buffer().read(cx).read_only(),
-                self.channel_buffer.read(cx).is_connected(),
+                channel_buffer.buffer().read(cx).read_only(),
+                channel_buffer.is_connected(),
             ) {
                 (false, true) => format!("#{}", channel.name),
                 (true, true) => format!("#{} (read-only)", channel.name),
-                (_, false) => format!("#{} (disconnected)", channel.name),
+                (_, false) if channel_buffer.has_offline_edits(cx) => {
+                    format!("#{} (offline, unsynced edits)", channel.name)
+                }
+                (_, false) => format!("#{} (offline)", channel.name),
             }
         } else {
             format!("channel notes (disconnected)")
@@ -5,20 +5,20 @@ use channel::{ChannelChat, ChannelChatEvent, ChannelMessageId, ChannelStore};
 use client::Client;
 use collections::HashMap;
 use db::kvp::KEY_VALUE_STORE;
-use editor::Editor;
+use editor::{actions::MoveToEnd, Editor};
 use gpui::{
     actions, div, list, prelude::*, px, Action, AppContext, AsyncWindowContext, DismissEvent,
     ElementId, EventEmitter, FocusHandle, FocusableView, FontWeight, ListOffset, ListScrollEvent,
     ListState, Model, Render, Subscription, Task, View, ViewContext, VisualContext, WeakView,
 };
-use language::LanguageRegistry;
+use language::{LanguageRegistry, Point};
 use menu::Confirm;
 use message_editor::MessageEditor;
 use project::Fs;
 use rich_text::RichText;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use std::{sync::Arc, time::Duration};
+use std::{ops::RangeInclusive, sync::Arc, time::Duration};
 use time::{OffsetDateTime, UtcOffset};
 use ui::{
     popover_menu, prelude::*, Avatar, Button, ContextMenu, IconButton, IconName, KeyBinding, Label,
@@ -40,6 +40,9 @@ pub fn init(cx: &mut AppContext) {
         workspace.register_action(|workspace, _: &ToggleFocus, cx| {
             workspace.toggle_panel_focus::<ChatPanel>(cx);
         });
+        workspace.register_action(|workspace, _: &ShareSelection, cx| {
+            ChatPanel::share_selection(workspace, cx);
+        });
     })
     .detach();
 }
@@ -68,7 +71,7 @@ struct SerializedChatPanel {
     width: Option<Pixels>,
 }
 
-actions!(chat_panel, [ToggleFocus]);
+actions!(chat_panel, [ToggleFocus, ShareSelection]);
 
 impl ChatPanel {
     pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
@@ -459,6 +462,33 @@ impl ChatPanel {
         }
     }
 
+    /// Adds the lines selected in the active editor to the message being written, as a
+    /// code block headed by their path and line numbers, and focuses the chat.
+    fn share_selection(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+        let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+            return;
+        };
+        let Some(snippet) = selection_snippet(editor.read(cx), cx) else {
+            return;
+        };
+
+        let Some(panel) = workspace.focus_panel::<ChatPanel>(cx) else {
+            return;
+        };
+        panel.update(cx, |panel, cx| {
+            panel.message_editor.update(cx, |message_editor, cx| {
+                message_editor.editor.update(cx, |editor, cx| {
+                    editor.move_to_end(&MoveToEnd, cx);
+                    let text = editor.text(cx);
+                    if !text.is_empty() && !text.ends_with('\n') {
+                        editor.insert("\n", cx);
+                    }
+                    editor.insert(&snippet, cx);
+                })
+            })
+        });
+    }
+
     fn remove_message(&mut self, id: u64, cx: &mut ViewContext<Self>) {
         if let Some((chat, _)) = self.active_chat.as_ref() {
             chat.update(cx, |chat, cx| chat.remove_message(id, cx).detach())
@@ -670,6 +700,64 @@ impl Panel for ChatPanel {
 
 impl EventEmitter<PanelEvent> for ChatPanel {}
 
+/// The lines selected in the editor, formatted as a code snippet for sharing in chat.
+fn selection_snippet(editor: &Editor, cx: &AppContext) -> Option<String> {
+    let buffer = editor.buffer().read(cx).as_singleton()?;
+    let buffer = buffer.read(cx);
+    let path = buffer.file()?.path().to_string_lossy().into_owned();
+    let language = buffer
+        .language()
+        .map(|language| language.name().to_lowercase())
+        .filter(|name| !name.contains(char::is_whitespace));
+
+    let selection = editor.selections.newest::<Point>(cx);
+    let start_row = selection.start.row;
+    let mut end_row = selection.end.row;
+    if end_row > start_row && selection.end.column == 0 {
+        end_row -= 1;
+    }
+    let text = buffer
+        .text_for_range(Point::new(start_row, 0)..Point::new(end_row, buffer.line_len(end_row)))
+        .collect::<String>();
+    Some(code_snippet(
+        &path,
+        start_row..=end_row,
+        language.as_deref(),
+        &text,
+    ))
+}
+
+/// Formats lines of code as a markdown code block, headed by their path and their one-based
+/// line numbers. The block's fence is longer than any run of backticks in the code.
+fn code_snippet(
+    path: &str,
+    rows: RangeInclusive<u32>,
+    language: Option<&str>,
+    text: &str,
+) -> String {
+    let mut longest_backtick_run = 0;
+    let mut backtick_run = 0;
+    for c in text.chars() {
+        if c == '`' {
+            backtick_run += 1;
+            longest_backtick_run = longest_backtick_run.max(backtick_run);
+        } else {
+            backtick_run = 0;
+        }
+    }
+    let fence = "`".repeat((longest_backtick_run + 1).max(3));
+
+    let location = if rows.start() == rows.end() {
+        format!("{path}:{}", rows.start() + 1)
+    } else {
+        format!("{path}:{}-{}", rows.start() + 1, rows.end() + 1)
+    };
+    format!(
+        "`{location}`\n{fence}{}\n{text}\n{fence}\n",
+        language.unwrap_or_default()
+    )
+}
+
 fn format_timestamp(
     reference: OffsetDateTime,
     timestamp: OffsetDateTime,
@@ -844,6 +932,18 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_code_snippet() {
+        assert_eq!(
+            code_snippet("src/main.rs", 2..=3, Some("rust"), "fn main() {\n}"),
+            "`src/main.rs:3-4`\n```rust\nfn main() {\n}\n```\n"
+        );
+        assert_eq!(
+            code_snippet("README.md", 0..=0, None, "Run ```make```"),
+            "`README.md:1`\n````\nRun ```make```\n````\n"
+        );
+    }
+
     fn test_timezone() -> UtcOffset {
         UtcOffset::from_hms(0, 0, 0).expect("Valid timezone offset")
     }
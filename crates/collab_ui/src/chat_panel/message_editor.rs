@@ -15,7 +15,6 @@ use language::{
 use lazy_static::lazy_static;
 use parking_lot::RwLock;
 use project::search::SearchQuery;
-use settings::Settings;
 use std::{sync::Arc, time::Duration};
 use theme::ThemeSettings;
 use ui::{prelude::*, UiTextSize};
@@ -326,7 +325,7 @@ impl MessageEditor {
 
 impl Render for MessageEditor {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let settings = ThemeSettings::get_global(cx);
+        let settings = ThemeSettings::for_window(cx);
         let text_style = TextStyle {
             color: if self.editor.read(cx).read_only(cx) {
                 cx.theme().colors().text_disabled
@@ -334,7 +333,8 @@ impl Render for MessageEditor {
                 cx.theme().colors().text
             },
             font_family: settings.ui_font.family.clone(),
-            font_features: settings.ui_font.features,
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
             font_size: UiTextSize::Small.rems().into(),
             font_weight: FontWeight::NORMAL,
             font_style: FontStyle::Normal,
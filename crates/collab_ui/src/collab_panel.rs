@@ -855,7 +855,7 @@ impl CollabPanel {
                             .ok();
                     }))
             })
-            .when(is_call_admin, |el| {
+            .when(is_call_admin && !is_current_user, |el| {
                 el.on_secondary_mouse_down(cx.listener(move |this, event: &MouseDownEvent, cx| {
                     this.deploy_participant_context_menu(event.position, user_id, role, cx)
                 }))
@@ -1000,61 +1000,34 @@ impl CollabPanel {
         cx: &mut ViewContext<Self>,
     ) {
         let this = cx.view().clone();
-        if !(role == proto::ChannelRole::Guest || role == proto::ChannelRole::Member) {
-            return;
-        }
+        let role_changes: &[(&'static str, proto::ChannelRole)] = match role {
+            proto::ChannelRole::Guest => &[
+                ("Grant Write Access", proto::ChannelRole::Member),
+                ("Grant Full Access", proto::ChannelRole::Admin),
+            ],
+            proto::ChannelRole::Member => &[
+                ("Grant Full Access", proto::ChannelRole::Admin),
+                ("Revoke Write Access", proto::ChannelRole::Guest),
+            ],
+            proto::ChannelRole::Admin => &[
+                ("Revoke Full Access", proto::ChannelRole::Member),
+                ("Make Read-Only", proto::ChannelRole::Guest),
+            ],
+            proto::ChannelRole::Banned => return,
+        };
 
         let context_menu = ContextMenu::build(cx, |context_menu, cx| {
-            if role == proto::ChannelRole::Guest {
-                context_menu.entry(
-                    "Grant Write Access",
-                    None,
-                    cx.handler_for(&this, move |_, cx| {
-                        ActiveCall::global(cx)
-                            .update(cx, |call, cx| {
-                                let Some(room) = call.room() else {
-                                    return Task::ready(Ok(()));
-                                };
-                                room.update(cx, |room, cx| {
-                                    room.set_participant_role(
-                                        user_id,
-                                        proto::ChannelRole::Member,
-                                        cx,
-                                    )
-                                })
-                            })
-                            .detach_and_prompt_err("Failed to grant write access", cx, |e, _| {
-                                match e.error_code() {
-                                    ErrorCode::NeedsCla => Some("This user has not yet signed the CLA at https://zed.dev/cla.".into()),
-                                    _ => None,
-                                }
-                            })
-                    }),
-                )
-            } else if role == proto::ChannelRole::Member {
-                context_menu.entry(
-                    "Revoke Write Access",
-                    None,
-                    cx.handler_for(&this, move |_, cx| {
-                        ActiveCall::global(cx)
-                            .update(cx, |call, cx| {
-                                let Some(room) = call.room() else {
-                                    return Task::ready(Ok(()));
-                                };
-                                room.update(cx, |room, cx| {
-                                    room.set_participant_role(
-                                        user_id,
-                                        proto::ChannelRole::Guest,
-                                        cx,
-                                    )
-                                })
-                            })
-                            .detach_and_prompt_err("Failed to revoke write access", cx, |_, _| None)
-                    }),
-                )
-            } else {
-                unreachable!()
-            }
+            role_changes
+                .iter()
+                .fold(context_menu, |context_menu, &(label, new_role)| {
+                    context_menu.entry(
+                        label,
+                        None,
+                        cx.handler_for(&this, move |_, cx| {
+                            Self::set_participant_role(user_id, new_role, cx)
+                        }),
+                    )
+                })
         });
 
         cx.focus_view(&context_menu);
@@ -1533,6 +1506,24 @@ impl CollabPanel {
         self.collapsed_channels.binary_search(&channel_id).is_ok()
     }
 
+    /// Changes what a participant of the call can do in the shared projects: guests can only
+    /// read them, members can edit them, and admins can also change the roles of others.
+    fn set_participant_role(user_id: u64, role: proto::ChannelRole, cx: &mut WindowContext) {
+        ActiveCall::global(cx)
+            .update(cx, |call, cx| {
+                let Some(room) = call.room() else {
+                    return Task::ready(Ok(()));
+                };
+                room.update(cx, |room, cx| room.set_participant_role(user_id, role, cx))
+            })
+            .detach_and_prompt_err("Failed to change access", cx, |e, _| match e.error_code() {
+                ErrorCode::NeedsCla => {
+                    Some("This user has not yet signed the CLA at https://zed.dev/cla.".into())
+                }
+                _ => None,
+            })
+    }
+
     fn leave_call(cx: &mut WindowContext) {
         ActiveCall::global(cx)
             .update(cx, |call, cx| call.hang_up(cx))
@@ -2040,7 +2031,7 @@ impl CollabPanel {
         editor: &View<Editor>,
         cx: &mut ViewContext<Self>,
     ) -> impl IntoElement {
-        let settings = ThemeSettings::get_global(cx);
+        let settings = ThemeSettings::for_window(cx);
         let text_style = TextStyle {
             color: if editor.read(cx).read_only(cx) {
                 cx.theme().colors().text_disabled
@@ -2048,7 +2039,8 @@ impl CollabPanel {
                 cx.theme().colors().text
             },
             font_family: settings.ui_font.family.clone(),
-            font_features: settings.ui_font.features,
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
             font_size: rems(0.875).into(),
             font_weight: FontWeight::NORMAL,
             font_style: FontStyle::Normal,
@@ -2806,7 +2798,7 @@ struct DraggedChannelView {
 
 impl Render for DraggedChannelView {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl Element {
-        let ui_font = ThemeSettings::get_global(cx).ui_font.family.clone();
+        let ui_font = ThemeSettings::for_window(cx).ui_font.family.clone();
         h_flex()
             .font(ui_font)
             .bg(cx.theme().colors().background)
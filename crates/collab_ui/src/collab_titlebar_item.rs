@@ -1,6 +1,9 @@
 use crate::face_pile::FacePile;
 use auto_update::AutoUpdateStatus;
-use call::{ActiveCall, ParticipantLocation, Room};
+use call::{
+    call_settings::{CallSettings, ScreenShareQuality},
+    ActiveCall, ParticipantLocation, Room,
+};
 use client::{proto::PeerId, Client, User, UserStore};
 use gpui::{
     actions, canvas, div, point, px, Action, AnyElement, AppContext, Element, Hsla,
@@ -11,6 +14,7 @@ use gpui::{
 use project::{Project, RepositoryEntry};
 use recent_projects::RecentProjects;
 use rpc::proto;
+use settings::{update_settings_file, Settings};
 use std::sync::Arc;
 use theme::ActiveTheme;
 use ui::{
@@ -310,6 +314,7 @@ impl Render for CollabTitlebarItem {
                                         crate::toggle_screen_sharing(&Default::default(), cx)
                                     }),
                             )
+                            .child(self.render_screen_share_quality_menu(cx))
                         })
                         .child(div().pr_2())
                     })
@@ -677,6 +682,60 @@ impl CollabTitlebarItem {
             })
     }
 
+    fn render_screen_share_quality_menu(&self, cx: &mut ViewContext<Self>) -> impl Element {
+        let fs = self.project.read(cx).fs().clone();
+        popover_menu("screen-share-quality")
+            .menu(move |cx| {
+                let fs = fs.clone();
+                Some(ContextMenu::build(cx, move |menu, cx| {
+                    let selected_quality = CallSettings::get_global(cx).screen_share_quality;
+                    ScreenShareQuality::ALL.into_iter().fold(
+                        menu.header("Screen Share Quality"),
+                        |menu, quality| {
+                            let fs = fs.clone();
+                            menu.custom_entry(
+                                move |_| {
+                                    h_flex()
+                                        .gap_2()
+                                        .justify_between()
+                                        .child(Label::new(quality.label()))
+                                        .when(quality == selected_quality, |this| {
+                                            this.child(
+                                                Icon::new(IconName::Check).size(IconSize::Small),
+                                            )
+                                        })
+                                        .into_any_element()
+                                },
+                                move |cx| {
+                                    update_settings_file::<CallSettings>(
+                                        fs.clone(),
+                                        cx,
+                                        move |settings| {
+                                            settings.screen_share_quality = Some(quality)
+                                        },
+                                    )
+                                },
+                            )
+                        },
+                    )
+                }))
+            })
+            .trigger(
+                IconButton::new("screen-share-quality", IconName::ChevronDown)
+                    .style(ButtonStyle::Subtle)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| {
+                        Tooltip::with_meta(
+                            "Screen Share Quality",
+                            None,
+                            "Takes effect the next time you share",
+                            cx,
+                        )
+                    }),
+            )
+            .anchor(gpui::AnchorCorner::TopRight)
+    }
+
     pub fn render_user_menu_button(&mut self, cx: &mut ViewContext<Self>) -> impl Element {
         if let Some(user) = self.user_store.read(cx).current_user() {
             popover_menu("user-menu")
@@ -21,3 +21,38 @@ pub struct CommandPaletteFilter {
     pub hidden_namespaces: HashSet<&'static str>,
     pub hidden_action_types: HashSet<TypeId>,
 }
+
+/// The arguments that actions can be given in the command palette, where they're typed
+/// after the name of the command, e.g. `workspace: activate pane 2`.
+#[derive(Default)]
+pub struct CommandPaletteArguments {
+    pub arguments_by_action_name: HashMap<&'static str, CommandArgument>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CommandArgument {
+    /// The field of the action that the argument is deserialized into, or `None` for
+    /// actions that are deserialized from the argument itself.
+    pub field: Option<&'static str>,
+    pub kind: CommandArgumentKind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CommandArgumentKind {
+    Number,
+    /// A path, as typed.
+    File,
+    /// One of the given values, which are matched case-insensitively.
+    Enum(&'static [&'static str]),
+}
+
+impl CommandArgumentKind {
+    /// Describes the argument where it's yet to be typed.
+    pub fn placeholder(&self) -> String {
+        match self {
+            Self::Number => "<number>".into(),
+            Self::File => "<file>".into(),
+            Self::Enum(values) => format!("<{}>", values.join("|")),
+        }
+    }
+}
@@ -1,16 +1,22 @@
 use std::{
-    cmp::{self, Reverse},
+    cmp::{self, Ordering},
     sync::Arc,
+    time::{Duration, SystemTime},
 };
 
+use anyhow::Result;
 use client::telemetry::Telemetry;
-use collections::{CommandPaletteFilter, HashMap};
+use collections::{
+    CommandArgument, CommandArgumentKind, CommandPaletteArguments, CommandPaletteFilter, HashMap,
+};
+use db::kvp::KEY_VALUE_STORE;
 use fuzzy::{StringMatch, StringMatchCandidate};
 use gpui::{
     actions, Action, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
-    ParentElement, Render, Styled, View, ViewContext, VisualContext, WeakView,
+    KeyContext, ParentElement, Render, Styled, View, ViewContext, VisualContext, WeakView,
 };
 use picker::{Picker, PickerDelegate};
+use serde::{Deserialize, Serialize};
 
 use ui::{h_flex, prelude::*, v_flex, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing};
 use util::{
@@ -22,10 +28,18 @@ use zed_actions::OpenZedURL;
 
 actions!(command_palette, [Toggle]);
 
+const COMMAND_USAGE_KEY: &str = "command_palette_usage";
+/// How long it takes for the boost from having recently used a command to halve.
+const RECENCY_HALF_LIFE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const FREQUENCY_WEIGHT: f64 = 0.1;
+const RECENCY_WEIGHT: f64 = 0.3;
+const CONTEXT_WEIGHT: f64 = 0.3;
+
 pub fn init(cx: &mut AppContext) {
-    cx.set_global(HitCounts::default());
+    cx.set_global(CommandUsage::default());
     cx.set_global(CommandPaletteFilter::default());
     cx.observe_new_views(CommandPalette::register).detach();
+    CommandUsage::load(cx);
 }
 
 impl ModalView for CommandPalette {}
@@ -53,30 +67,67 @@ impl CommandPalette {
         cx: &mut ViewContext<Self>,
     ) -> Self {
         let filter = cx.try_global::<CommandPaletteFilter>();
+        let is_hidden = |action: &dyn Action| {
+            let namespace = action
+                .name()
+                .split("::")
+                .next()
+                .unwrap_or("malformed action name");
+            filter.is_some_and(|f| {
+                f.hidden_namespaces.contains(namespace)
+                    || f.hidden_action_types.contains(&action.type_id())
+            })
+        };
 
-        let commands = cx
+        let mut commands = cx
             .available_actions()
             .into_iter()
-            .filter_map(|action| {
-                let name = action.name();
-                let namespace = name.split("::").next().unwrap_or("malformed action name");
-                if filter.is_some_and(|f| {
-                    f.hidden_namespaces.contains(namespace)
-                        || f.hidden_action_types.contains(&action.type_id())
-                }) {
-                    return None;
+            .filter(|action| !is_hidden(action.as_ref()))
+            .map(|action| Command {
+                name: humanize_action_name(action.name()),
+                action,
+                argument: None,
+            })
+            .collect::<Vec<_>>();
+
+        // Actions that need an argument can't be built without one, so they're listed
+        // with a placeholder argument, and confirming them prompts for the real one.
+        if let Some(arguments) = cx.try_global::<CommandPaletteArguments>() {
+            for (action_name, argument) in &arguments.arguments_by_action_name {
+                let Some(action) = build_argument_action(
+                    action_name,
+                    argument,
+                    placeholder_value(argument.kind),
+                    cx,
+                )
+                .log_err() else {
+                    continue;
+                };
+                if is_hidden(action.as_ref()) || !cx.is_action_available(action.as_ref()) {
+                    continue;
                 }
-
-                Some(Command {
-                    name: humanize_action_name(&name),
+                commands.push(Command {
+                    name: humanize_action_name(action_name),
                     action,
-                })
-            })
-            .collect();
+                    argument: Some(argument.clone()),
+                });
+            }
+        }
+
+        let context_stack = cx.context_stack_in(&previous_focus_handle);
+        let mut context_weights = HashMap::default();
+        for command in &commands {
+            let namespace = command.namespace();
+            if !context_weights.contains_key(namespace) {
+                let weight = context_weight(namespace, &context_stack);
+                context_weights.insert(namespace.to_string(), weight);
+            }
+        }
 
         let delegate = CommandPaletteDelegate::new(
             cx.view().downgrade(),
             commands,
+            context_weights,
             telemetry,
             previous_focus_handle,
         );
@@ -113,6 +164,8 @@ pub struct CommandPaletteDelegate {
     command_palette: WeakView<CommandPalette>,
     all_commands: Vec<Command>,
     commands: Vec<Command>,
+    /// How closely the namespace of each command relates to the focused element.
+    context_weights: HashMap<String, f64>,
     matches: Vec<StringMatch>,
     selected_ix: usize,
     telemetry: Arc<Telemetry>,
@@ -122,6 +175,8 @@ pub struct CommandPaletteDelegate {
 struct Command {
     name: String,
     action: Box<dyn Action>,
+    /// The argument that the command prompts for before its action can be dispatched.
+    argument: Option<CommandArgument>,
 }
 
 impl Clone for Command {
@@ -129,20 +184,116 @@ impl Clone for Command {
         Self {
             name: self.name.clone(),
             action: self.action.boxed_clone(),
+            argument: self.argument.clone(),
         }
     }
 }
 
-/// Hit count for each command in the palette.
+impl Command {
+    fn namespace(&self) -> &str {
+        self.action.name().split("::").next().unwrap_or_default()
+    }
+}
+
+/// How often and how recently each command in the palette was used, which is kept in the
+/// database so that commands rank by it across restarts.
 /// We only account for commands triggered directly via command palette and not by e.g. keystrokes because
 /// if an user already knows a keystroke for a command, they are unlikely to use a command palette to look for it.
-#[derive(Default)]
-struct HitCounts(HashMap<String, usize>);
+#[derive(Default, Serialize, Deserialize)]
+struct CommandUsage(HashMap<String, CommandUsageEntry>);
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+struct CommandUsageEntry {
+    count: usize,
+    /// When the command was last used, in seconds since the Unix epoch.
+    last_used: u64,
+}
+
+impl CommandUsage {
+    fn load(cx: &mut AppContext) {
+        cx.spawn(|mut cx| async move {
+            let usage = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(COMMAND_USAGE_KEY) })
+                .await
+                .log_err()
+                .flatten()
+                .and_then(|usage| serde_json::from_str::<CommandUsage>(&usage).log_err());
+            if let Some(usage) = usage {
+                cx.update_global(|this: &mut Self, _| {
+                    // Commands may have been used while the usage was loading.
+                    for (name, entry) in usage.0 {
+                        this.0.entry(name).or_insert(entry);
+                    }
+                })
+                .log_err();
+            }
+        })
+        .detach();
+    }
+
+    fn record(&mut self, name: String, now: SystemTime, cx: &AppContext) {
+        let entry = self.0.entry(name).or_insert(CommandUsageEntry {
+            count: 0,
+            last_used: 0,
+        });
+        entry.count += 1;
+        entry.last_used = unix_seconds(now);
+
+        let Some(usage) = serde_json::to_string(self).log_err() else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(KEY_VALUE_STORE.write_kvp(COMMAND_USAGE_KEY.to_string(), usage))
+            .detach_and_log_err(cx);
+    }
+
+    /// How much to boost a command's rank by, given how often and how recently it was used
+    /// and how closely it relates to the focused element.
+    fn rank_boost(&self, name: &str, context_weight: f64, now: SystemTime) -> f64 {
+        let (frequency, recency) = self.0.get(name).map_or((0., 0.), |entry| {
+            let age = unix_seconds(now).saturating_sub(entry.last_used) as f64;
+            (
+                (entry.count as f64).ln_1p(),
+                0.5_f64.powf(age / RECENCY_HALF_LIFE.as_secs_f64()),
+            )
+        });
+        1. + FREQUENCY_WEIGHT * frequency
+            + RECENCY_WEIGHT * recency
+            + CONTEXT_WEIGHT * context_weight
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// How closely the actions in a namespace relate to the focused element, from 0 when
+/// none of its key contexts are named after the namespace, to 1 when the focused
+/// element's own context is, e.g. `terminal` actions when a terminal is focused.
+fn context_weight(namespace: &str, context_stack: &[KeyContext]) -> f64 {
+    let context_name = namespace
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<String>();
+    context_stack
+        .iter()
+        .rposition(|context| context.contains(&context_name))
+        .map_or(0., |ix| (ix + 1) as f64 / context_stack.len() as f64)
+}
 
 impl CommandPaletteDelegate {
     fn new(
         command_palette: WeakView<CommandPalette>,
         commands: Vec<Command>,
+        context_weights: HashMap<String, f64>,
         telemetry: Arc<Telemetry>,
         previous_focus_handle: FocusHandle,
     ) -> Self {
@@ -151,11 +302,48 @@ impl CommandPaletteDelegate {
             all_commands: commands.clone(),
             matches: vec![],
             commands,
+            context_weights,
             selected_ix: 0,
             telemetry,
             previous_focus_handle,
         }
     }
+
+    /// Returns a command for each of the arguments that the query could be giving to a
+    /// command that takes one, e.g. `workspace: activate pane 2`, along with the length
+    /// of the command's name.
+    fn commands_with_arguments(&self, query: &str, cx: &AppContext) -> Vec<(Command, usize)> {
+        let query = query.trim_start();
+        let mut commands = Vec::new();
+        for command in &self.all_commands {
+            let Some(argument) = &command.argument else {
+                continue;
+            };
+            let Some(text) = query
+                .get(..command.name.len())
+                .filter(|name| name.eq_ignore_ascii_case(&command.name))
+                .and_then(|_| query[command.name.len()..].strip_prefix(' '))
+            else {
+                continue;
+            };
+
+            for (label, value) in argument_values(argument.kind, text.trim()) {
+                if let Some(action) =
+                    build_argument_action(command.action.name(), argument, value, cx).log_err()
+                {
+                    commands.push((
+                        Command {
+                            name: format!("{} {}", command.name, label),
+                            action,
+                            argument: None,
+                        },
+                        command.name.len(),
+                    ));
+                }
+            }
+        }
+        commands
+    }
 }
 
 impl PickerDelegate for CommandPaletteDelegate {
@@ -183,17 +371,24 @@ impl PickerDelegate for CommandPaletteDelegate {
         cx: &mut ViewContext<Picker<Self>>,
     ) -> gpui::Task<()> {
         let mut commands = self.all_commands.clone();
+        let commands_with_arguments = self.commands_with_arguments(&query, cx);
+        let context_weights = self.context_weights.clone();
 
         cx.spawn(move |picker, mut cx| async move {
-            cx.read_global::<HitCounts, _>(|hit_counts, _| {
-                commands.sort_by_key(|action| {
-                    (
-                        Reverse(hit_counts.0.get(&action.name).cloned()),
-                        action.name.clone(),
-                    )
-                });
-            })
-            .ok();
+            commands.sort_by(|a, b| a.name.cmp(&b.name));
+            let now = SystemTime::now();
+            let rank_boosts = cx.try_read_global::<CommandUsage, _>(|usage, _| {
+                commands
+                    .iter()
+                    .map(|command| {
+                        let context_weight = context_weights
+                            .get(command.namespace())
+                            .copied()
+                            .unwrap_or(0.);
+                        usage.rank_boost(&command.name, context_weight, now)
+                    })
+                    .collect::<Vec<_>>()
+            });
 
             let candidates = commands
                 .iter()
@@ -227,6 +422,25 @@ impl PickerDelegate for CommandPaletteDelegate {
                 .await
             };
 
+            // Commands rank by how well they match the query, boosted by their usage and
+            // context. Without a query, they rank by the boosts alone and then by name.
+            if let Some(rank_boosts) = rank_boosts {
+                for mat in &mut matches {
+                    let rank_boost = rank_boosts[mat.candidate_id];
+                    mat.score = if query.is_empty() {
+                        rank_boost
+                    } else {
+                        mat.score * rank_boost
+                    };
+                }
+                matches.sort_by(|a, b| {
+                    b.score
+                        .partial_cmp(&a.score)
+                        .unwrap_or(Ordering::Equal)
+                        .then_with(|| a.candidate_id.cmp(&b.candidate_id))
+                });
+            }
+
             let mut intercept_result = cx
                 .try_read_global(|interceptor: &CommandPaletteInterceptor, cx| {
                     (interceptor)(&query, cx)
@@ -270,6 +484,19 @@ impl PickerDelegate for CommandPaletteDelegate {
                 )
             }
 
+            for (ix, (command, name_len)) in commands_with_arguments.into_iter().enumerate() {
+                matches.insert(
+                    ix,
+                    StringMatch {
+                        candidate_id: commands.len(),
+                        string: command.name.clone(),
+                        positions: (0..name_len).collect(),
+                        score: 0.0,
+                    },
+                );
+                commands.push(command);
+            }
+
             picker
                 .update(&mut cx, |picker, _| {
                     let delegate = &mut picker.delegate;
@@ -298,6 +525,14 @@ impl PickerDelegate for CommandPaletteDelegate {
             return;
         }
         let action_ix = self.matches[self.selected_ix].candidate_id;
+        if self.commands[action_ix].argument.is_some() {
+            let query = format!("{} ", self.commands[action_ix].name);
+            cx.spawn(|picker, mut cx| async move {
+                picker.update(&mut cx, |picker, cx| picker.set_query(query, cx))
+            })
+            .detach_and_log_err(cx);
+            return;
+        }
         let command = self.commands.swap_remove(action_ix);
 
         self.telemetry
@@ -305,8 +540,8 @@ impl PickerDelegate for CommandPaletteDelegate {
 
         self.matches.clear();
         self.commands.clear();
-        cx.update_global(|hit_counts: &mut HitCounts, _| {
-            *hit_counts.0.entry(command.name).or_default() += 1;
+        cx.update_global(|usage: &mut CommandUsage, cx| {
+            usage.record(command.name, SystemTime::now(), cx);
         });
         let action = command.action;
         cx.focus(&self.previous_focus_handle);
@@ -333,20 +568,76 @@ impl PickerDelegate for CommandPaletteDelegate {
                     h_flex()
                         .w_full()
                         .justify_between()
-                        .child(HighlightedLabel::new(
-                            command.name.clone(),
-                            r#match.positions.clone(),
-                        ))
-                        .children(KeyBinding::for_action_in(
-                            &*command.action,
-                            &self.previous_focus_handle,
-                            cx,
-                        )),
+                        .child(
+                            h_flex()
+                                .gap_2()
+                                .child(HighlightedLabel::new(
+                                    command.name.clone(),
+                                    r#match.positions.clone(),
+                                ))
+                                .children(command.argument.as_ref().map(|argument| {
+                                    Label::new(argument.kind.placeholder()).color(Color::Muted)
+                                })),
+                        )
+                        // The placeholder argument's bindings aren't the command's.
+                        .when(command.argument.is_none(), |this| {
+                            this.children(KeyBinding::for_action_in(
+                                &*command.action,
+                                &self.previous_focus_handle,
+                                cx,
+                            ))
+                        }),
                 ),
         )
     }
 }
 
+fn build_argument_action(
+    action_name: &str,
+    argument: &CommandArgument,
+    value: serde_json::Value,
+    cx: &AppContext,
+) -> Result<Box<dyn Action>> {
+    let data = match argument.field {
+        Some(field) => serde_json::json!({ field: value }),
+        None => value,
+    };
+    cx.build_action(action_name, Some(data))
+}
+
+/// A value that an argument's action can be built with, to check whether it's available.
+fn placeholder_value(kind: CommandArgumentKind) -> serde_json::Value {
+    match kind {
+        CommandArgumentKind::Number => 0.into(),
+        CommandArgumentKind::File => "".into(),
+        CommandArgumentKind::Enum(values) => values.first().copied().unwrap_or_default().into(),
+    }
+}
+
+/// The values, along with their labels, that the given text could be giving an argument.
+fn argument_values(kind: CommandArgumentKind, text: &str) -> Vec<(String, serde_json::Value)> {
+    match kind {
+        CommandArgumentKind::Number => text
+            .parse::<serde_json::Number>()
+            .ok()
+            .map(|number| (text.to_string(), number.into()))
+            .into_iter()
+            .collect(),
+        CommandArgumentKind::File => (!text.is_empty())
+            .then(|| (text.to_string(), text.into()))
+            .into_iter()
+            .collect(),
+        CommandArgumentKind::Enum(values) => {
+            let text = text.to_lowercase();
+            values
+                .iter()
+                .filter(|value| value.to_lowercase().starts_with(&text))
+                .map(|value| (value.to_string(), (*value).into()))
+                .collect()
+        }
+    }
+}
+
 fn humanize_action_name(name: &str) -> String {
     let capacity = name.len() + name.chars().filter(|c| c.is_uppercase()).count();
     let mut result = String::with_capacity(capacity);
@@ -386,7 +677,7 @@ mod tests {
     use super::*;
     use editor::Editor;
     use go_to_line::GoToLine;
-    use gpui::TestAppContext;
+    use gpui::{KeyContext, TestAppContext};
     use language::Point;
     use project::Project;
     use settings::KeymapFile;
@@ -480,6 +771,76 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_command_ranking() {
+        let context_stack =
+            ["Workspace", "Pane", "Terminal"].map(|context| KeyContext::parse(context).unwrap());
+        assert_eq!(context_weight("terminal", &context_stack), 1.);
+        assert_eq!(context_weight("workspace", &context_stack), 1. / 3.);
+        assert_eq!(context_weight("editor", &context_stack), 0.);
+        assert_eq!(
+            context_weight(
+                "project_panel",
+                &[KeyContext::parse("ProjectPanel").unwrap()]
+            ),
+            1.
+        );
+
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        let mut usage = CommandUsage::default();
+        usage.0.insert(
+            "editor: backspace".into(),
+            CommandUsageEntry {
+                count: 3,
+                last_used: unix_seconds(now),
+            },
+        );
+        usage.0.insert(
+            "editor: undo".into(),
+            CommandUsageEntry {
+                count: 3,
+                last_used: unix_seconds(now - RECENCY_HALF_LIFE * 4),
+            },
+        );
+        let rank_boost =
+            |name: &str, context_weight: f64| usage.rank_boost(name, context_weight, now);
+        assert_eq!(rank_boost("editor: redo", 0.), 1.);
+        assert!(rank_boost("editor: backspace", 0.) > rank_boost("editor: undo", 0.));
+        assert!(rank_boost("editor: undo", 0.) > rank_boost("editor: redo", 0.));
+        assert!(rank_boost("editor: redo", 1.) > rank_boost("editor: redo", 0.));
+    }
+
+    #[gpui::test]
+    async fn test_recently_used_commands_rank_first(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        cx.simulate_keystrokes("cmd-n");
+        let editor = workspace.update(cx, |workspace, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        });
+        editor.update(cx, |editor, cx| editor.set_text("abc", cx));
+
+        cx.simulate_keystrokes("cmd-shift-p");
+        cx.simulate_input("editor: backspace");
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+
+        cx.simulate_keystrokes("cmd-shift-p");
+        let palette = workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<CommandPalette>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        });
+        palette.update(cx, |palette, _| {
+            assert_eq!(palette.delegate.matches[0].string, "editor: backspace");
+        });
+    }
+
     #[gpui::test]
     async fn test_go_to_line(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
@@ -512,6 +873,69 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_command_arguments(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        cx.simulate_keystrokes("cmd-n");
+        cx.simulate_keystrokes("cmd-n");
+        let pane = workspace.update(cx, |workspace, _| workspace.active_pane().clone());
+        pane.update(cx, |pane, _| assert_eq!(pane.active_item_index(), 1));
+
+        cx.simulate_keystrokes("cmd-shift-p");
+        cx.simulate_input("pane: activate item");
+
+        let palette = workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<CommandPalette>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        });
+        palette.update(cx, |palette, _| {
+            assert_eq!(palette.delegate.matches[0].string, "pane: activate item");
+        });
+
+        // Confirming a command that takes an argument prompts for the argument.
+        cx.simulate_keystrokes("enter");
+        cx.run_until_parked();
+        palette.update(cx, |palette, cx| {
+            assert_eq!(palette.query(cx), "pane: activate item ");
+        });
+
+        cx.simulate_input("0");
+        palette.update(cx, |palette, _| {
+            assert_eq!(palette.delegate.matches[0].string, "pane: activate item 0");
+        });
+
+        cx.simulate_keystrokes("enter");
+        workspace.update(cx, |workspace, cx| {
+            assert!(workspace.active_modal::<CommandPalette>(cx).is_none());
+        });
+        pane.update(cx, |pane, _| assert_eq!(pane.active_item_index(), 0));
+
+        cx.simulate_keystrokes("cmd-shift-p");
+        cx.simulate_input("workspace: activate pane in direction l");
+
+        let palette = workspace.update(cx, |workspace, cx| {
+            workspace
+                .active_modal::<CommandPalette>(cx)
+                .unwrap()
+                .read(cx)
+                .picker
+                .clone()
+        });
+        palette.update(cx, |palette, _| {
+            assert_eq!(
+                palette.delegate.matches[0].string,
+                "workspace: activate pane in direction Left"
+            );
+        });
+    }
+
     fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
         cx.update(|cx| {
             let app_state = AppState::test(cx);
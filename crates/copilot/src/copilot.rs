@@ -2,6 +2,7 @@ pub mod request;
 use anyhow::{anyhow, Context as _, Result};
 use async_compression::futures::bufread::GzipDecoder;
 use async_tar::Archive;
+use client::OfflineModeSetting;
 use collections::{HashMap, HashSet};
 use futures::{channel::oneshot, future::Shared, Future, FutureExt, TryFutureExt};
 use gpui::{
@@ -17,7 +18,7 @@ use lsp::{LanguageServer, LanguageServerBinary, LanguageServerId};
 use node_runtime::NodeRuntime;
 use parking_lot::Mutex;
 use request::StatusNotification;
-use settings::SettingsStore;
+use settings::{Settings, SettingsStore};
 use smol::{fs, io::BufReader, stream::StreamExt};
 use std::{
     any::TypeId,
@@ -352,7 +353,9 @@ impl Copilot {
         let server_id = self.server_id;
         let http = self.http.clone();
         let node_runtime = self.node_runtime.clone();
-        if all_language_settings(None, cx).copilot_enabled(None, None) {
+        if all_language_settings(None, cx).copilot_enabled(None, None)
+            && !OfflineModeSetting::get_global(cx).0
+        {
             if matches!(self.server, CopilotServer::Disabled) {
                 let start_task = cx
                     .spawn(move |this, cx| {
@@ -414,6 +417,7 @@ impl Copilot {
                     Arc::new(Mutex::new(None)),
                     new_server_id,
                     binary,
+                    None,
                     Path::new("/"),
                     None,
                     cx.clone(),
@@ -1238,7 +1242,7 @@ mod tests {
             self.abs_path.clone()
         }
 
-        fn load(&self, _: &AppContext) -> Task<Result<String>> {
+        fn load(&self, _: language::Encoding, _: &AppContext) -> Task<Result<String>> {
             unimplemented!()
         }
 
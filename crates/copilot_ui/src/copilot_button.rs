@@ -236,6 +236,10 @@ impl CopilotButton {
 }
 
 impl StatusItemView for CopilotButton {
+    fn status_item_id(&self) -> &'static str {
+        "copilot"
+    }
+
     fn set_active_pane_item(&mut self, item: Option<&dyn ItemHandle>, cx: &mut ViewContext<Self>) {
         if let Some(editor) = item.map(|item| item.act_as::<Editor>(cx)).flatten() {
             self.editor_subscription = Some((
@@ -176,6 +176,10 @@ impl DiagnosticIndicator {
 impl EventEmitter<ToolbarItemEvent> for DiagnosticIndicator {}
 
 impl StatusItemView for DiagnosticIndicator {
+    fn status_item_id(&self) -> &'static str {
+        "diagnostics"
+    }
+
     fn set_active_pane_item(
         &mut self,
         active_pane_item: Option<&dyn ItemHandle>,
@@ -61,6 +61,30 @@ pub struct ToggleComments {
     pub advance_downwards: bool,
 }
 
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct AlignOnDelimiter {
+    #[serde(default)]
+    pub delimiter: Option<String>,
+    #[serde(default)]
+    pub regex: bool,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct IncrementNumber {
+    #[serde(default)]
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub sequence: bool,
+}
+
+#[derive(PartialEq, Clone, Deserialize, Default)]
+pub struct DecrementNumber {
+    #[serde(default)]
+    pub count: Option<u32>,
+    #[serde(default)]
+    pub sequence: bool,
+}
+
 #[derive(PartialEq, Clone, Deserialize, Default)]
 pub struct FoldAt {
     pub buffer_row: u32,
@@ -83,6 +107,9 @@ impl_actions!(
         ConfirmCompletion,
         ConfirmCodeAction,
         ToggleComments,
+        AlignOnDelimiter,
+        IncrementNumber,
+        DecrementNumber,
         FoldAt,
         UnfoldAt
     ]
@@ -93,8 +120,10 @@ gpui::actions!(
     [
         AddSelectionAbove,
         AddSelectionBelow,
+        ApplyRefactoring,
         Backspace,
         Cancel,
+        ChangeSurroundingPair,
         ConfirmRename,
         ContextMenuFirst,
         ContextMenuLast,
@@ -107,6 +136,7 @@ gpui::actions!(
         ConvertToTitleCase,
         ConvertToUpperCamelCase,
         ConvertToUpperCase,
+        ConvertToUpperSnakeCase,
         Copy,
         CopyHighlightJson,
         CopyPath,
@@ -121,8 +151,10 @@ gpui::actions!(
         DeleteToNextWordEnd,
         DeleteToPreviousSubwordStart,
         DeleteToPreviousWordStart,
+        DiscardRefactoring,
         DisplayCursorNames,
         DuplicateLine,
+        DuplicateSelection,
         ExpandMacroRecursively,
         FindAllReferences,
         Fold,
@@ -132,6 +164,8 @@ gpui::actions!(
         GoToDefinitionSplit,
         GoToDiagnostic,
         GoToHunk,
+        GoToNextBookmark,
+        GoToPrevBookmark,
         GoToPrevDiagnostic,
         GoToPrevHunk,
         GoToTypeDefinition,
@@ -141,8 +175,13 @@ gpui::actions!(
         Hover,
         Indent,
         JoinLines,
+        KillRingCut,
+        KillRingYank,
+        KillRingYankPop,
         LineDown,
         LineUp,
+        MakeReadOnly,
+        MakeWritable,
         MoveDown,
         MoveLeft,
         MoveLineDown,
@@ -169,8 +208,11 @@ gpui::actions!(
         PageDown,
         PageUp,
         Paste,
+        PeekDefinition,
+        PeekReferences,
         Redo,
         RedoSelection,
+        Refactor,
         Rename,
         RestartLanguageServer,
         RevealInFinder,
@@ -178,6 +220,10 @@ gpui::actions!(
         ScrollCursorBottom,
         ScrollCursorCenter,
         ScrollCursorTop,
+        ScrollHoverDown,
+        ScrollHoverPageDown,
+        ScrollHoverPageUp,
+        ScrollHoverUp,
         SelectAll,
         SelectAllMatches,
         SelectDown,
@@ -195,14 +241,21 @@ gpui::actions!(
         SelectToPreviousWordStart,
         SelectToStartOfParagraph,
         SelectUp,
+        SetMark,
         ShowCharacterPalette,
         ShowCompletions,
+        ShowSignatureHelp,
         ShuffleLines,
+        SignatureHelpNext,
+        SignatureHelpPrev,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
         SplitSelectionIntoLines,
+        SurroundSelections,
+        SwapSelectionEnds,
         Tab,
         TabPrev,
+        ToggleBookmark,
         ToggleInlayHints,
         ToggleSoftWrap,
         Transpose,
@@ -0,0 +1,413 @@
+use crate::{persistence::DB, scroll::Autoscroll, Editor};
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{
+    AppContext, Context, EntityId, Model, ModelContext, SharedString, Subscription, Task,
+    ViewContext,
+};
+use language::{Buffer, Event as BufferEvent};
+use std::{
+    cmp::Ordering,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use text::{Anchor, Bias, Point, ToPoint};
+use util::ResultExt;
+use workspace::{Workspace, WorkspaceId};
+
+/// A line of a file that was bookmarked to come back to later.
+pub struct Bookmark {
+    /// The absolute path of the bookmarked file.
+    pub path: Arc<Path>,
+    /// An optional name for the bookmark, shown when listing bookmarks.
+    pub label: Option<SharedString>,
+    row: u32,
+    /// Where the bookmark is in its buffer while the file is open, so that it follows edits.
+    anchor: Option<Anchor>,
+}
+
+impl Bookmark {
+    /// The bookmarked row, in the file's contents as of when it was last loaded or edited.
+    pub fn row(&self) -> u32 {
+        self.row
+    }
+}
+
+/// The bookmarks of a workspace, which are saved along with it and shared by all of its
+/// editors.
+pub struct BookmarkStore {
+    workspace_id: WorkspaceId,
+    bookmarks: Vec<Bookmark>,
+    /// Subscriptions to the open buffers of bookmarked files, keyed by their entity ids.
+    buffers: HashMap<EntityId, Vec<Subscription>>,
+}
+
+#[derive(Default)]
+struct WorkspaceBookmarks(HashMap<WorkspaceId, Model<BookmarkStore>>);
+
+impl BookmarkStore {
+    /// The bookmarks of the given workspace, loading them the first time they're needed.
+    pub fn for_workspace(workspace_id: WorkspaceId, cx: &mut AppContext) -> Model<Self> {
+        if let Some(store) = cx
+            .default_global::<WorkspaceBookmarks>()
+            .0
+            .get(&workspace_id)
+        {
+            return store.clone();
+        }
+
+        let store = cx.new_model(|cx| {
+            cx.spawn(|this, mut cx| async move {
+                let saved = cx
+                    .background_executor()
+                    .spawn(async move { DB.get_bookmarks(workspace_id) })
+                    .await
+                    .log_err()
+                    .unwrap_or_default();
+                this.update(&mut cx, |this, cx| {
+                    for (path, row, label) in saved {
+                        let path: Arc<Path> = path.into();
+                        if this.index_of(&path, row).is_err() {
+                            this.insert(path, row, label.map(Into::into));
+                        }
+                    }
+                    cx.notify();
+                })
+                .ok();
+            })
+            .detach();
+
+            Self {
+                workspace_id,
+                bookmarks: Vec::new(),
+                buffers: HashMap::default(),
+            }
+        });
+        cx.default_global::<WorkspaceBookmarks>()
+            .0
+            .insert(workspace_id, store.clone());
+        store
+    }
+
+    /// All bookmarks, ordered by path and then by row.
+    pub fn bookmarks(&self) -> &[Bookmark] {
+        &self.bookmarks
+    }
+
+    /// The bookmark on the given row of the buffer's file.
+    pub fn bookmark_at(
+        &self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        cx: &AppContext,
+    ) -> Option<&Bookmark> {
+        let path = abs_path(buffer, cx)?;
+        let ix = self.index_of(&path, row).ok()?;
+        Some(&self.bookmarks[ix])
+    }
+
+    /// The bookmarked rows of the buffer's file, in ascending order.
+    pub fn rows_for_buffer(
+        &mut self,
+        buffer: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Vec<u32> {
+        let Some(path) = abs_path(buffer, cx) else {
+            return Vec::new();
+        };
+        self.track_buffer(buffer, &path, cx);
+        self.bookmarks
+            .iter()
+            .filter(|bookmark| bookmark.path == path)
+            .map(|bookmark| bookmark.row)
+            .collect()
+    }
+
+    /// Adds a bookmark on the given row of the buffer's file, or removes the one that's
+    /// already there. Only files on the local file system can be bookmarked.
+    pub fn toggle(&mut self, buffer: &Model<Buffer>, row: u32, cx: &mut ModelContext<Self>) {
+        let Some(path) = abs_path(buffer, cx) else {
+            return;
+        };
+        match self.index_of(&path, row) {
+            Ok(ix) => {
+                self.bookmarks.remove(ix);
+            }
+            Err(_) => {
+                self.insert(path.clone(), row, None);
+                self.track_buffer(buffer, &path, cx);
+            }
+        }
+        self.save(cx);
+        cx.notify();
+    }
+
+    /// Labels the bookmark on the given row of the buffer's file, adding a bookmark there
+    /// if there isn't one already.
+    pub fn set_label(
+        &mut self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        label: Option<SharedString>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(path) = abs_path(buffer, cx) else {
+            return;
+        };
+        match self.index_of(&path, row) {
+            Ok(ix) => self.bookmarks[ix].label = label,
+            Err(_) => {
+                self.insert(path.clone(), row, label);
+                self.track_buffer(buffer, &path, cx);
+            }
+        }
+        self.save(cx);
+        cx.notify();
+    }
+
+    /// The bookmark after the given position, wrapping around to the first one.
+    pub fn next(&self, path: &Path, row: u32) -> Option<&Bookmark> {
+        let ix = match self.index_of(path, row) {
+            Ok(ix) => ix + 1,
+            Err(ix) => ix,
+        };
+        self.bookmarks.get(ix).or_else(|| self.bookmarks.first())
+    }
+
+    /// The bookmark before the given position, wrapping around to the last one.
+    pub fn prev(&self, path: &Path, row: u32) -> Option<&Bookmark> {
+        let ix = match self.index_of(path, row) {
+            Ok(ix) | Err(ix) => ix,
+        };
+        ix.checked_sub(1)
+            .and_then(|ix| self.bookmarks.get(ix))
+            .or_else(|| self.bookmarks.last())
+    }
+
+    fn index_of(&self, path: &Path, row: u32) -> Result<usize, usize> {
+        self.bookmarks
+            .binary_search_by(|bookmark| match bookmark.path.as_ref().cmp(path) {
+                Ordering::Equal => bookmark.row.cmp(&row),
+                ordering => ordering,
+            })
+    }
+
+    fn insert(&mut self, path: Arc<Path>, row: u32, label: Option<SharedString>) {
+        if let Err(ix) = self.index_of(&path, row) {
+            self.bookmarks.insert(
+                ix,
+                Bookmark {
+                    path,
+                    label,
+                    row,
+                    anchor: None,
+                },
+            );
+        }
+    }
+
+    /// Anchors the bookmarks of the buffer's file in the buffer, and keeps their rows up to
+    /// date as the buffer is edited, until it's released.
+    fn track_buffer(
+        &mut self,
+        buffer: &Model<Buffer>,
+        path: &Arc<Path>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let snapshot = buffer.read(cx).text_snapshot();
+        for bookmark in &mut self.bookmarks {
+            if bookmark.path == *path && bookmark.anchor.is_none() {
+                let point = snapshot.clip_point(Point::new(bookmark.row, 0), Bias::Left);
+                bookmark.anchor = Some(snapshot.anchor_before(point));
+            }
+        }
+
+        let buffer_id = buffer.entity_id();
+        if self.buffers.contains_key(&buffer_id) {
+            return;
+        }
+        let path = path.clone();
+        let subscriptions = vec![
+            cx.subscribe(buffer, {
+                let path = path.clone();
+                move |this, buffer, event, cx| match event {
+                    BufferEvent::Edited => this.update_rows(&buffer, &path, cx),
+                    BufferEvent::Saved => this.save(cx),
+                    _ => {}
+                }
+            }),
+            cx.observe_release(buffer, move |this, _, _| {
+                this.buffers.remove(&buffer_id);
+                for bookmark in &mut this.bookmarks {
+                    if bookmark.path == path {
+                        bookmark.anchor = None;
+                    }
+                }
+            }),
+        ];
+        self.buffers.insert(buffer_id, subscriptions);
+    }
+
+    fn update_rows(&mut self, buffer: &Model<Buffer>, path: &Path, cx: &mut ModelContext<Self>) {
+        let buffer = buffer.read(cx);
+        let mut changed = false;
+        for bookmark in &mut self.bookmarks {
+            if let Some(anchor) = bookmark.anchor.filter(|_| bookmark.path.as_ref() == path) {
+                let row = anchor.to_point(buffer).row;
+                changed |= row != bookmark.row;
+                bookmark.row = row;
+            }
+        }
+        if changed {
+            // Edits can move several bookmarks onto the same line, of which only one is kept.
+            self.bookmarks
+                .sort_by(|a, b| a.path.cmp(&b.path).then(a.row.cmp(&b.row)));
+            self.bookmarks
+                .dedup_by(|a, b| a.path == b.path && a.row == b.row);
+            cx.notify();
+        }
+    }
+
+    fn save(&self, cx: &mut ModelContext<Self>) {
+        let workspace_id = self.workspace_id;
+        let bookmarks = self
+            .bookmarks
+            .iter()
+            .map(|bookmark| {
+                (
+                    PathBuf::from(bookmark.path.as_ref()),
+                    bookmark.row,
+                    bookmark.label.as_ref().map(ToString::to_string),
+                )
+            })
+            .collect();
+        cx.background_executor()
+            .spawn(async move { DB.save_bookmarks(workspace_id, bookmarks).await })
+            .detach();
+    }
+}
+
+/// Opens the bookmarked file and moves the cursor to the start of the bookmarked row.
+pub fn open_bookmark(
+    workspace: &mut Workspace,
+    path: Arc<Path>,
+    row: u32,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let open = workspace.open_abs_path(path.to_path_buf(), false, cx);
+    cx.spawn(|_, mut cx| async move {
+        let item = open.await?;
+        if let Some(editor) = item.downcast::<Editor>() {
+            editor.update(&mut cx, |editor, cx| {
+                let snapshot = editor.buffer().read(cx).snapshot(cx);
+                let point = snapshot.clip_point(Point::new(row, 0), Bias::Left);
+                editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                    s.select_ranges([point..point])
+                });
+            })?;
+        }
+        Ok(())
+    })
+}
+
+fn abs_path(buffer: &Model<Buffer>, cx: &AppContext) -> Option<Arc<Path>> {
+    let file = buffer.read(cx).file()?.as_local()?;
+    Some(file.abs_path(cx).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::editor_tests::init_test;
+    use gpui::TestAppContext;
+    use project::{FakeFs, Project};
+    use serde_json::json;
+    use workspace::WORKSPACE_DB;
+
+    #[gpui::test]
+    async fn test_bookmarks(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({ "a.txt": "one\ntwo\nthree\nfour\n", "b.txt": "five\n" }),
+        )
+        .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let buffer_a = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/a.txt", cx)
+            })
+            .await
+            .unwrap();
+        let buffer_b = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/b.txt", cx)
+            })
+            .await
+            .unwrap();
+        let workspace_id = WORKSPACE_DB.next_id().await.unwrap();
+        let store = cx.update(|cx| BookmarkStore::for_workspace(workspace_id, cx));
+        cx.run_until_parked();
+
+        store.update(cx, |store, cx| {
+            store.toggle(&buffer_a, 2, cx);
+            store.toggle(&buffer_b, 0, cx);
+            store.set_label(&buffer_a, 0, Some("start".into()), cx);
+            assert_eq!(
+                positions(store),
+                [("/root/a.txt", 0), ("/root/a.txt", 2), ("/root/b.txt", 0)]
+            );
+            assert_eq!(
+                store.bookmark_at(&buffer_a, 0, cx).unwrap().label,
+                Some("start".into())
+            );
+
+            // Navigation wraps around across files.
+            let next = store.next(Path::new("/root/a.txt"), 1).unwrap();
+            assert_eq!(
+                (next.path.as_ref(), next.row),
+                (Path::new("/root/a.txt"), 2)
+            );
+            let next = store.next(Path::new("/root/b.txt"), 0).unwrap();
+            assert_eq!(
+                (next.path.as_ref(), next.row),
+                (Path::new("/root/a.txt"), 0)
+            );
+            let prev = store.prev(Path::new("/root/a.txt"), 0).unwrap();
+            assert_eq!(
+                (prev.path.as_ref(), prev.row),
+                (Path::new("/root/b.txt"), 0)
+            );
+
+            // Toggling a bookmarked line removes its bookmark.
+            store.toggle(&buffer_b, 0, cx);
+            assert_eq!(positions(store), [("/root/a.txt", 0), ("/root/a.txt", 2)]);
+        });
+
+        // Bookmarks follow edits to their lines.
+        buffer_a.update(cx, |buffer, cx| buffer.edit([(0..0, "zero\n")], None, cx));
+        store.update(cx, |store, cx| {
+            assert_eq!(positions(store), [("/root/a.txt", 1), ("/root/a.txt", 3)]);
+            assert_eq!(store.rows_for_buffer(&buffer_a, cx), [1, 3]);
+        });
+
+        // Bookmarks are saved along with the workspace, with the rows of the saved file.
+        cx.run_until_parked();
+        assert_eq!(
+            DB.get_bookmarks(workspace_id).unwrap(),
+            [
+                (PathBuf::from("/root/a.txt"), 0, Some("start".to_string())),
+                (PathBuf::from("/root/a.txt"), 2, None),
+            ]
+        );
+    }
+
+    fn positions(store: &BookmarkStore) -> Vec<(&str, u32)> {
+        store
+            .bookmarks()
+            .iter()
+            .map(|bookmark| (bookmark.path.to_str().unwrap(), bookmark.row()))
+            .collect()
+    }
+}
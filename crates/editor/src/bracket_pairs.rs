@@ -0,0 +1,267 @@
+use std::{cmp::Reverse, mem, ops::Range};
+
+use gpui::{AppContext, HighlightStyle, Hsla, ViewContext};
+use language::{Bias, Point};
+use multi_buffer::{Anchor, ToPoint};
+use settings::Settings;
+use theme::ActiveTheme;
+
+use crate::{Editor, EditorSettings};
+
+/// The number of nesting depths that brackets are colored for, after which the colors repeat.
+const COLORIZED_DEPTHS: usize = 6;
+
+enum ColorizedBracketHighlight<const DEPTH: usize> {}
+
+/// The innermost bracket pair enclosing the newest cursor, between whose lines a guide is drawn.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct BracketScopeGuide {
+    pub open: Anchor,
+    pub close: Anchor,
+    pub depth: usize,
+}
+
+pub(crate) fn bracket_color(depth: usize, cx: &AppContext) -> Hsla {
+    cx.theme()
+        .players()
+        .color_for_participant((depth % COLORIZED_DEPTHS) as u32)
+        .cursor
+}
+
+/// Colors the visible brackets by how deeply they're nested. Only the visible part of the syntax
+/// tree is queried, so this is refreshed as the editor scrolls and the buffer is reparsed.
+pub(crate) fn refresh_colorized_brackets(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let mut ranges_by_depth = vec![Vec::new(); COLORIZED_DEPTHS];
+    if EditorSettings::get_global(cx).bracket_pairs.colorize {
+        let multi_buffer = editor.buffer.read(cx);
+        let snapshot = multi_buffer.snapshot(cx);
+        let visible_start = Point::new(
+            editor
+                .scroll_manager
+                .anchor()
+                .anchor
+                .to_point(&snapshot)
+                .row,
+            0,
+        );
+        let visible_end = snapshot.clip_point(
+            visible_start + Point::new(editor.visible_line_count().unwrap_or(0.).ceil() as u32, 0),
+            Bias::Left,
+        );
+        for (buffer, visible_range, excerpt_id) in
+            multi_buffer.range_to_buffer_ranges(visible_start..visible_end, cx)
+        {
+            let buffer = buffer.read(cx).snapshot();
+            let pairs = nesting_depths(buffer.bracket_ranges(visible_range.clone()));
+            for (open, close, depth) in pairs {
+                for bracket in [open, close] {
+                    if bracket.end > visible_range.start && bracket.start < visible_range.end {
+                        ranges_by_depth[depth % COLORIZED_DEPTHS].push(
+                            snapshot
+                                .anchor_in_excerpt(excerpt_id, buffer.anchor_after(bracket.start))
+                                ..snapshot.anchor_in_excerpt(
+                                    excerpt_id,
+                                    buffer.anchor_before(bracket.end),
+                                ),
+                        );
+                    }
+                }
+            }
+        }
+        for ranges in &mut ranges_by_depth {
+            ranges.sort_by(|a, b| a.start.cmp(&b.start, &snapshot));
+        }
+    }
+
+    highlight_depth::<0>(editor, &mut ranges_by_depth, cx);
+    highlight_depth::<1>(editor, &mut ranges_by_depth, cx);
+    highlight_depth::<2>(editor, &mut ranges_by_depth, cx);
+    highlight_depth::<3>(editor, &mut ranges_by_depth, cx);
+    highlight_depth::<4>(editor, &mut ranges_by_depth, cx);
+    highlight_depth::<5>(editor, &mut ranges_by_depth, cx);
+}
+
+fn highlight_depth<const DEPTH: usize>(
+    editor: &mut Editor,
+    ranges_by_depth: &mut [Vec<Range<Anchor>>],
+    cx: &mut ViewContext<Editor>,
+) {
+    let ranges = mem::take(&mut ranges_by_depth[DEPTH]);
+    if ranges.is_empty() {
+        editor.clear_highlights::<ColorizedBracketHighlight<DEPTH>>(cx);
+    } else {
+        let style = HighlightStyle {
+            color: Some(bracket_color(DEPTH, cx)),
+            ..Default::default()
+        };
+        editor.highlight_text::<ColorizedBracketHighlight<DEPTH>>(ranges, style, cx);
+    }
+}
+
+/// Finds the bracket pair that a scope guide is drawn for, when the cursor moves or the buffer is
+/// reparsed.
+pub(crate) fn refresh_bracket_scope_guide(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let mut guide = None;
+    if EditorSettings::get_global(cx).bracket_pairs.scope_guide {
+        let head = editor.selections.newest::<usize>(cx).head();
+        let snapshot = editor.buffer.read(cx).snapshot(cx);
+        if let Some(pairs) = snapshot.enclosing_bracket_ranges(head..head) {
+            // The innermost pair is the one enclosed by all of the others.
+            guide = nesting_depths(pairs)
+                .into_iter()
+                .max_by_key(|(_, _, depth)| *depth)
+                .map(|(open, close, depth)| BracketScopeGuide {
+                    open: snapshot.anchor_after(open.start),
+                    close: snapshot.anchor_before(close.start),
+                    depth,
+                });
+        }
+    }
+
+    if editor.bracket_scope_guide != guide {
+        editor.bracket_scope_guide = guide;
+        cx.notify();
+    }
+}
+
+/// Pairs each bracket pair with the number of the given pairs that enclose it.
+fn nesting_depths(
+    pairs: impl Iterator<Item = (Range<usize>, Range<usize>)>,
+) -> Vec<(Range<usize>, Range<usize>, usize)> {
+    let mut pairs = pairs.collect::<Vec<_>>();
+    pairs.sort_by_key(|(open, close)| (open.start, Reverse(close.end)));
+    pairs.dedup();
+
+    let mut enclosing_ends = Vec::new();
+    pairs
+        .into_iter()
+        .map(|(open, close)| {
+            while enclosing_ends
+                .last()
+                .map_or(false, |end| *end <= open.start)
+            {
+                enclosing_ends.pop();
+            }
+            let depth = enclosing_ends.len();
+            enclosing_ends.push(close.end);
+            (open, close, depth)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        editor_settings::BracketPairsContent, editor_tests::init_test,
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+    use indoc::indoc;
+    use language::{BracketPair, BracketPairConfig, Language, LanguageConfig};
+    use settings::SettingsStore;
+
+    #[test]
+    fn test_nesting_depths() {
+        // `(a [b] (c {d}))`
+        let pairs = [
+            (0..1, 14..15),
+            (3..4, 5..6),
+            (7..8, 13..14),
+            (10..11, 12..13),
+        ];
+        assert_eq!(
+            nesting_depths(pairs.iter().cloned().rev()),
+            vec![
+                (0..1, 14..15, 0),
+                (3..4, 5..6, 1),
+                (7..8, 13..14, 1),
+                (10..11, 12..13, 2),
+            ]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_colorized_brackets_and_scope_guide(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Rust".into(),
+                    path_suffixes: vec!["rs".to_string()],
+                    brackets: BracketPairConfig {
+                        pairs: vec![
+                            BracketPair {
+                                start: "{".to_string(),
+                                end: "}".to_string(),
+                                close: false,
+                                newline: true,
+                            },
+                            BracketPair {
+                                start: "(".to_string(),
+                                end: ")".to_string(),
+                                close: false,
+                                newline: true,
+                            },
+                        ],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                Some(tree_sitter_rust::language()),
+            )
+            .with_brackets_query(indoc! {r#"
+                ("{" @open "}" @close)
+                ("(" @open ")" @close)
+                "#})
+            .unwrap(),
+            Default::default(),
+            cx,
+        )
+        .await;
+
+        _ = cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.bracket_pairs = Some(BracketPairsContent {
+                        colorize: Some(true),
+                        scope_guide: Some(true),
+                    });
+                });
+            })
+        });
+        cx.set_state(indoc! {r#"
+            fn a() {
+                ˇb(c());
+            }
+        "#});
+        cx.update_editor(|editor, cx| {
+            editor.set_visible_line_count(10., cx);
+            refresh_colorized_brackets(editor, cx);
+            refresh_bracket_scope_guide(editor, cx);
+        });
+
+        cx.assert_editor_text_highlights::<ColorizedBracketHighlight<0>>(indoc! {r#"
+            fn a«(»«)» «{»
+                b(c());
+            «}»
+        "#});
+        cx.assert_editor_text_highlights::<ColorizedBracketHighlight<1>>(indoc! {r#"
+            fn a() {
+                b«(»c()«)»;
+            }
+        "#});
+        cx.assert_editor_text_highlights::<ColorizedBracketHighlight<2>>(indoc! {r#"
+            fn a() {
+                b(c«(»«)»);
+            }
+        "#});
+
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.buffer.read(cx).snapshot(cx);
+            let guide = editor.bracket_scope_guide.clone().unwrap();
+            assert_eq!(guide.open.to_point(&snapshot), Point::new(0, 7));
+            assert_eq!(guide.close.to_point(&snapshot), Point::new(2, 0));
+            assert_eq!(guide.depth, 0);
+        });
+    }
+}
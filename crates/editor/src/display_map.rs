@@ -25,8 +25,9 @@ mod wrap_map;
 
 use crate::EditorStyle;
 use crate::{
-    link_go_to_definition::InlayHighlight, movement::TextLayoutDetails, Anchor, AnchorRangeExt,
-    InlayId, MultiBuffer, MultiBufferSnapshot, ToOffset, ToPoint,
+    editor_settings::SoftWrapIndent, link_go_to_definition::InlayHighlight,
+    movement::TextLayoutDetails, Anchor, AnchorRangeExt, InlayId, MultiBuffer, MultiBufferSnapshot,
+    ToOffset, ToPoint,
 };
 pub use block_map::{BlockMap, BlockPoint};
 use collections::{BTreeMap, HashMap, HashSet};
@@ -230,6 +231,20 @@ impl DisplayMap {
         block_map.remove(ids);
     }
 
+    pub fn resize_blocks(&mut self, heights: HashMap<BlockId, u8>, cx: &mut ModelContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let edits = self.buffer_subscription.consume().into_inner();
+        let tab_size = Self::tab_size(&self.buffer, cx);
+        let (snapshot, edits) = self.inlay_map.sync(snapshot, edits);
+        let (snapshot, edits) = self.fold_map.read(snapshot, edits);
+        let (snapshot, edits) = self.tab_map.sync(snapshot, edits, tab_size);
+        let (snapshot, edits) = self
+            .wrap_map
+            .update(cx, |map, cx| map.sync(snapshot, edits, cx));
+        let mut block_map = self.block_map.write(snapshot, edits);
+        block_map.resize(heights);
+    }
+
     pub fn highlight_text(
         &mut self,
         type_id: TypeId,
@@ -278,6 +293,15 @@ impl DisplayMap {
             .update(cx, |map, cx| map.set_wrap_width(width, cx))
     }
 
+    pub fn set_wrap_indent(
+        &self,
+        wrap_indent: SoftWrapIndent,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        self.wrap_map
+            .update(cx, |map, cx| map.set_wrap_indent(wrap_indent, cx))
+    }
+
     pub(crate) fn current_inlays(&self) -> impl Iterator<Item = &Inlay> {
         self.inlay_map.current_inlays()
     }
@@ -570,6 +570,48 @@ impl<'a> BlockMapWriter<'a> {
         });
         self.0.sync(wrap_snapshot, edits);
     }
+
+    pub fn resize(&mut self, mut heights: HashMap<BlockId, u8>) {
+        let wrap_snapshot = &*self.0.wrap_snapshot.borrow();
+        let buffer = wrap_snapshot.buffer_snapshot();
+        let mut edits = Patch::default();
+        let mut last_block_buffer_row = None;
+        for block in &mut self.0.blocks {
+            let Some(height) = heights.remove(&block.id) else {
+                continue;
+            };
+            if block.height == height {
+                continue;
+            }
+
+            let new_block = Block {
+                id: block.id,
+                position: block.position,
+                height,
+                style: block.style,
+                render: Mutex::new(block.render.lock().clone()),
+                disposition: block.disposition,
+            };
+            *block = Arc::new(new_block);
+
+            let buffer_row = block.position.to_point(buffer).row;
+            if last_block_buffer_row != Some(buffer_row) {
+                last_block_buffer_row = Some(buffer_row);
+                let wrap_row = wrap_snapshot
+                    .make_wrap_point(Point::new(buffer_row, 0), Bias::Left)
+                    .row();
+                let start_row = wrap_snapshot.prev_row_boundary(WrapPoint::new(wrap_row, 0));
+                let end_row = wrap_snapshot
+                    .next_row_boundary(WrapPoint::new(wrap_row, 0))
+                    .unwrap_or(wrap_snapshot.max_point().row() + 1);
+                edits.push(Edit {
+                    old: start_row..end_row,
+                    new: start_row..end_row,
+                })
+            }
+        }
+        self.0.sync(wrap_snapshot, edits);
+    }
 }
 
 impl BlockSnapshot {
@@ -1228,6 +1270,62 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_resize_blocks(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| init_test(cx));
+
+        let text = "aaa\nbbb\nccc";
+
+        let buffer = cx.update(|cx| MultiBuffer::build_simple(text, cx));
+        let buffer_snapshot = cx.update(|cx| buffer.read(cx).snapshot(cx));
+        let (_, inlay_snapshot) = InlayMap::new(buffer_snapshot.clone());
+        let (_, fold_snapshot) = FoldMap::new(inlay_snapshot);
+        let (_, tab_snapshot) = TabMap::new(fold_snapshot, 1.try_into().unwrap());
+        let (_, wraps_snapshot) =
+            cx.update(|cx| WrapMap::new(tab_snapshot, font("Helvetica"), px(14.0), None, cx));
+        let mut block_map = BlockMap::new(wraps_snapshot.clone(), 1, 1);
+
+        let mut writer = block_map.write(wraps_snapshot.clone(), Default::default());
+        let block_ids = writer.insert(vec![
+            BlockProperties {
+                style: BlockStyle::Flex,
+                position: buffer_snapshot.anchor_after(Point::new(0, 0)),
+                height: 1,
+                disposition: BlockDisposition::Below,
+                render: Arc::new(|_| div().into_any()),
+            },
+            BlockProperties {
+                style: BlockStyle::Fixed,
+                position: buffer_snapshot.anchor_after(Point::new(2, 0)),
+                height: 1,
+                disposition: BlockDisposition::Above,
+                render: Arc::new(|_| div().into_any()),
+            },
+        ]);
+        let snapshot = block_map.read(wraps_snapshot.clone(), Default::default());
+        assert_eq!(snapshot.text(), "aaa\n\nbbb\n\nccc");
+
+        let mut writer = block_map.write(wraps_snapshot.clone(), Default::default());
+        writer.resize(HashMap::from_iter([(block_ids[0], 3)]));
+        let snapshot = block_map.read(wraps_snapshot.clone(), Default::default());
+        assert_eq!(snapshot.text(), "aaa\n\n\n\nbbb\n\nccc");
+
+        // Resizing a block keeps its position, style and disposition.
+        let blocks = snapshot
+            .blocks_in_range(0..7)
+            .map(|(start_row, block)| {
+                let block = block.as_custom().unwrap();
+                (start_row..start_row + block.height as u32, block.id)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(blocks, &[(1..4, block_ids[0]), (5..6, block_ids[1])]);
+
+        let mut writer = block_map.write(wraps_snapshot.clone(), Default::default());
+        writer.resize(HashMap::from_iter([(block_ids[0], 1), (block_ids[1], 2)]));
+        let snapshot = block_map.read(wraps_snapshot, Default::default());
+        assert_eq!(snapshot.text(), "aaa\n\nbbb\n\n\nccc");
+    }
+
     #[gpui::test(iterations = 100)]
     fn test_random_blocks(cx: &mut gpui::TestAppContext, mut rng: StdRng) {
         cx.update(|cx| init_test(cx));
@@ -102,8 +102,7 @@ impl<'a> FoldMapWriter<'a> {
                 range: fold_range,
             });
 
-            let inlay_range =
-                snapshot.to_inlay_offset(range.start)..snapshot.to_inlay_offset(range.end);
+            let inlay_range = snapshot.to_inlay_fold_range(range);
             edits.push(InlayEdit {
                 old: inlay_range.clone(),
                 new: inlay_range,
@@ -146,8 +145,7 @@ impl<'a> FoldMapWriter<'a> {
                 let offset_range =
                     fold.range.start.to_offset(buffer)..fold.range.end.to_offset(buffer);
                 if offset_range.end > offset_range.start {
-                    let inlay_range = snapshot.to_inlay_offset(offset_range.start)
-                        ..snapshot.to_inlay_offset(offset_range.end);
+                    let inlay_range = snapshot.to_inlay_fold_range(offset_range);
                     edits.push(InlayEdit {
                         old: inlay_range.clone(),
                         new: inlay_range,
@@ -325,8 +323,7 @@ impl FoldMap {
                         let item = folds_cursor.item().map(|f| {
                             let buffer_start = f.range.start.to_offset(&inlay_snapshot.buffer);
                             let buffer_end = f.range.end.to_offset(&inlay_snapshot.buffer);
-                            inlay_snapshot.to_inlay_offset(buffer_start)
-                                ..inlay_snapshot.to_inlay_offset(buffer_end)
+                            inlay_snapshot.to_inlay_fold_range(buffer_start..buffer_end)
                         });
                         folds_cursor.next(&inlay_snapshot.buffer);
                         item
@@ -1158,7 +1155,10 @@ pub type FoldEdit = Edit<FoldOffset>;
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{display_map::inlay_map::InlayMap, MultiBuffer, ToPoint};
+    use crate::{
+        display_map::inlay_map::{Inlay, InlayMap},
+        InlayId, MultiBuffer, ToPoint,
+    };
     use collections::HashSet;
     use rand::prelude::*;
     use settings::SettingsStore;
@@ -1314,6 +1314,53 @@ mod tests {
         assert_eq!(snapshot.text(), "aa⋯eeeee");
     }
 
+    #[gpui::test]
+    fn test_suggestions_at_fold_boundaries(cx: &mut gpui::AppContext) {
+        init_test(cx);
+        let buffer = MultiBuffer::build_simple("fn a() {\n    b\n}\nc", cx);
+        let buffer_snapshot = buffer.read(cx).snapshot(cx);
+        let (mut inlay_map, inlay_snapshot) = InlayMap::new(buffer_snapshot.clone());
+        let mut map = FoldMap::new(inlay_snapshot.clone()).0;
+
+        let (mut writer, _, _) = map.write(inlay_snapshot.clone(), vec![]);
+        writer.fold(vec![Point::new(0, 8)..Point::new(2, 0)]);
+        let (snapshot, _) = map.read(inlay_snapshot, vec![]);
+        assert_eq!(snapshot.text(), "fn a() {⋯}\nc");
+
+        // Multi-line ghost text at either end of a fold is shown outside of it,
+        // whichever way the suggestion is biased.
+        let mut suggestion_ids = Vec::new();
+        for (ix, bias) in [Left, Right].into_iter().enumerate() {
+            let start = Inlay::suggestion(
+                2 * ix,
+                buffer_snapshot.anchor_at(Point::new(0, 8), bias),
+                "A\nB",
+            );
+            let end = Inlay::suggestion(
+                2 * ix + 1,
+                buffer_snapshot.anchor_at(Point::new(2, 0), bias),
+                "C\nD",
+            );
+            let (inlay_snapshot, inlay_edits) = inlay_map.splice(
+                mem::take(&mut suggestion_ids),
+                vec![start.clone(), end.clone()],
+            );
+            suggestion_ids = vec![start.id, end.id];
+            let (snapshot, _) = map.read(inlay_snapshot, inlay_edits);
+            assert_eq!(snapshot.text(), "fn a() {A\nB⋯C\nD}\nc", "bias {bias:?}");
+        }
+
+        // Hints are still folded along with the text they're next to.
+        let hint = Inlay {
+            id: InlayId::Hint(2),
+            position: buffer_snapshot.anchor_after(Point::new(0, 8)),
+            text: "h".into(),
+        };
+        let (inlay_snapshot, inlay_edits) = inlay_map.splice(suggestion_ids, vec![hint]);
+        let (snapshot, _) = map.read(inlay_snapshot, inlay_edits);
+        assert_eq!(snapshot.text(), "fn a() {⋯}\nc");
+    }
+
     #[gpui::test]
     fn test_merging_folds_via_edit(cx: &mut gpui::AppContext) {
         init_test(cx);
@@ -1428,10 +1475,8 @@ mod tests {
             snapshot_edits.push((snapshot.clone(), edits));
 
             let mut expected_text: String = inlay_snapshot.text().to_string();
-            for fold_range in map.merged_fold_ranges().into_iter().rev() {
-                let fold_inlay_start = inlay_snapshot.to_inlay_offset(fold_range.start);
-                let fold_inlay_end = inlay_snapshot.to_inlay_offset(fold_range.end);
-                expected_text.replace_range(fold_inlay_start.0..fold_inlay_end.0, "⋯");
+            for fold_range in map.merged_inlay_fold_ranges().into_iter().rev() {
+                expected_text.replace_range(fold_range.start.0..fold_range.end.0, "⋯");
             }
 
             assert_eq!(snapshot.text(), expected_text);
@@ -1443,13 +1488,9 @@ mod tests {
 
             let mut prev_row = 0;
             let mut expected_buffer_rows = Vec::new();
-            for fold_range in map.merged_fold_ranges().into_iter() {
-                let fold_start = inlay_snapshot
-                    .to_point(inlay_snapshot.to_inlay_offset(fold_range.start))
-                    .row();
-                let fold_end = inlay_snapshot
-                    .to_point(inlay_snapshot.to_inlay_offset(fold_range.end))
-                    .row();
+            for fold_range in map.merged_inlay_fold_ranges().into_iter() {
+                let fold_start = inlay_snapshot.to_point(fold_range.start).row();
+                let fold_end = inlay_snapshot.to_point(fold_range.end).row();
                 expected_buffer_rows.extend(
                     inlay_snapshot
                         .buffer_rows(prev_row)
@@ -1703,6 +1744,39 @@ mod tests {
             merged_ranges
         }
 
+        fn merged_inlay_fold_ranges(&self) -> Vec<Range<InlayOffset>> {
+            let inlay_snapshot = self.snapshot.inlay_snapshot.clone();
+            let buffer = &inlay_snapshot.buffer;
+            let mut folds = self.snapshot.folds.items(buffer);
+            folds.sort_by(|a, b| a.range.cmp(&b.range, buffer));
+            let mut fold_ranges = folds
+                .iter()
+                .map(|fold| {
+                    inlay_snapshot.to_inlay_fold_range(
+                        fold.range.start.to_offset(buffer)..fold.range.end.to_offset(buffer),
+                    )
+                })
+                .peekable();
+
+            let mut merged_ranges = Vec::new();
+            while let Some(mut fold_range) = fold_ranges.next() {
+                while let Some(next_range) = fold_ranges.peek() {
+                    if fold_range.end >= next_range.start {
+                        if next_range.end > fold_range.end {
+                            fold_range.end = next_range.end;
+                        }
+                        fold_ranges.next();
+                    } else {
+                        break;
+                    }
+                }
+                if fold_range.end > fold_range.start {
+                    merged_ranges.push(fold_range);
+                }
+            }
+            merged_ranges
+        }
+
         pub fn randomly_mutate(
             &mut self,
             rng: &mut impl Rng,
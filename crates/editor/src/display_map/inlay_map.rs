@@ -800,6 +800,34 @@ impl InlaySnapshot {
         }
     }
 
+    /// Converts the buffer range of a fold to an inlay range. Suggestions at either end
+    /// of the range are left outside of it, so that ghost text next to a fold is shown
+    /// rather than folded away, whichever way the suggestion is biased.
+    pub fn to_inlay_fold_range(&self, range: Range<usize>) -> Range<InlayOffset> {
+        let mut start = self.to_inlay_offset(range.start);
+        let mut cursor = self.transforms.cursor::<InlayOffset>();
+        cursor.seek(&start, Bias::Right, &());
+        while let Some(Transform::Inlay(inlay)) = cursor.item() {
+            if !matches!(inlay.id, InlayId::Suggestion(_)) {
+                break;
+            }
+            start = cursor.end(&());
+            cursor.next(&());
+        }
+
+        let mut end = self.to_inlay_offset(range.end);
+        cursor.seek(&end, Bias::Left, &());
+        while let Some(Transform::Inlay(inlay)) = cursor.item() {
+            if !matches!(inlay.id, InlayId::Suggestion(_)) || *cursor.start() < start {
+                break;
+            }
+            end = *cursor.start();
+            cursor.prev(&());
+        }
+
+        start..end.max(start)
+    }
+
     pub fn to_inlay_point(&self, point: Point) -> InlayPoint {
         let mut cursor = self.transforms.cursor::<(Point, InlayPoint)>();
         cursor.seek(&point, Bias::Left, &());
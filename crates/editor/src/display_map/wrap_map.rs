@@ -3,8 +3,8 @@ use super::{
     tab_map::{self, TabEdit, TabPoint, TabSnapshot},
     Highlights,
 };
-use crate::MultiBufferSnapshot;
-use gpui::{AppContext, Context, Font, LineWrapper, Model, ModelContext, Pixels, Task};
+use crate::{editor_settings::SoftWrapIndent, MultiBufferSnapshot};
+use gpui::{AppContext, Context, Font, LineWrapper, Model, ModelContext, Pixels, Task, WrapIndent};
 use language::{Chunk, Point};
 use lazy_static::lazy_static;
 use smol::future::yield_now;
@@ -21,6 +21,7 @@ pub struct WrapMap {
     interpolated_edits: Patch<u32>,
     edits_since_sync: Patch<u32>,
     wrap_width: Option<Pixels>,
+    wrap_indent: SoftWrapIndent,
     background_task: Option<Task<()>>,
     font_with_size: (Font, Pixels),
 }
@@ -81,6 +82,7 @@ impl WrapMap {
                 interpolated_edits: Default::default(),
                 edits_since_sync: Default::default(),
                 snapshot: WrapSnapshot::new(tab_snapshot),
+                wrap_indent: SoftWrapIndent::default(),
                 background_task: None,
             };
             this.set_wrap_width(wrap_width, cx);
@@ -146,6 +148,20 @@ impl WrapMap {
         true
     }
 
+    pub fn set_wrap_indent(
+        &mut self,
+        wrap_indent: SoftWrapIndent,
+        cx: &mut ModelContext<Self>,
+    ) -> bool {
+        if wrap_indent == self.wrap_indent {
+            return false;
+        }
+
+        self.wrap_indent = wrap_indent;
+        self.rewrap(cx);
+        true
+    }
+
     fn rewrap(&mut self, cx: &mut ModelContext<Self>) {
         self.background_task.take();
         self.interpolated_edits.clear();
@@ -156,6 +172,7 @@ impl WrapMap {
 
             let text_system = cx.text_system().clone();
             let (font, font_size) = self.font_with_size.clone();
+            let wrap_indent = self.wrap_indent;
             let task = cx.background_executor().spawn(async move {
                 let mut line_wrapper = text_system.line_wrapper(font, font_size);
                 let tab_snapshot = new_snapshot.tab_snapshot.clone();
@@ -168,6 +185,7 @@ impl WrapMap {
                             new: range.clone(),
                         }],
                         wrap_width,
+                        wrap_indent,
                         &mut line_wrapper,
                     )
                     .await;
@@ -240,12 +258,19 @@ impl WrapMap {
                 let mut snapshot = self.snapshot.clone();
                 let text_system = cx.text_system().clone();
                 let (font, font_size) = self.font_with_size.clone();
+                let wrap_indent = self.wrap_indent;
                 let update_task = cx.background_executor().spawn(async move {
                     let mut edits = Patch::default();
                     let mut line_wrapper = text_system.line_wrapper(font, font_size);
                     for (tab_snapshot, tab_edits) in pending_edits {
                         let wrap_edits = snapshot
-                            .update(tab_snapshot, &tab_edits, wrap_width, &mut line_wrapper)
+                            .update(
+                                tab_snapshot,
+                                &tab_edits,
+                                wrap_width,
+                                wrap_indent,
+                                &mut line_wrapper,
+                            )
                             .await;
                         edits = edits.compose(&wrap_edits);
                     }
@@ -387,8 +412,15 @@ impl WrapSnapshot {
         new_tab_snapshot: TabSnapshot,
         tab_edits: &[TabEdit],
         wrap_width: Pixels,
+        wrap_indent: SoftWrapIndent,
         line_wrapper: &mut LineWrapper,
     ) -> Patch<u32> {
+        let wrap_indent = match wrap_indent {
+            SoftWrapIndent::None => WrapIndent::None,
+            SoftWrapIndent::Same => WrapIndent::Same,
+            SoftWrapIndent::Indented => WrapIndent::Extra(new_tab_snapshot.tab_size.get()),
+        };
+
         #[derive(Debug)]
         struct RowEdit {
             old_rows: Range<u32>,
@@ -464,7 +496,9 @@ impl WrapSnapshot {
                     }
 
                     let mut prev_boundary_ix = 0;
-                    for boundary in line_wrapper.wrap_line(&line, wrap_width) {
+                    for boundary in
+                        line_wrapper.wrap_line_with_indent(&line, wrap_width, wrap_indent)
+                    {
                         let wrapped = &line[prev_boundary_ix..boundary.ix];
                         push_isomorphic(&mut edit_transforms, TextSummary::from(wrapped));
                         edit_transforms.push(Transform::wrap(boundary.next_indent));
@@ -1277,10 +1311,93 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_wrap_indent(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let text_system = cx.read(|cx| cx.text_system().clone());
+        let font = font("Helvetica");
+        let font_size = px(14.0);
+        let wrap_width = Some(px(90.0));
+        let tab_size = NonZeroU32::new(4).unwrap();
+
+        let text = "    漢字漢字漢字漢字漢字漢字 path/to/some-long-file_name.rs\nno indent here, but it is long";
+        let buffer = cx.update(|cx| MultiBuffer::build_simple(text, cx));
+        let buffer_snapshot = buffer.read_with(cx, |buffer, cx| buffer.snapshot(cx));
+        let (_, inlay_snapshot) = InlayMap::new(buffer_snapshot);
+        let (_, fold_snapshot) = FoldMap::new(inlay_snapshot);
+        let (_, tabs_snapshot) = TabMap::new(fold_snapshot, tab_size);
+
+        let (wrap_map, _) = cx.update(|cx| {
+            WrapMap::new(
+                tabs_snapshot.clone(),
+                font.clone(),
+                font_size,
+                wrap_width,
+                cx,
+            )
+        });
+        let mut line_wrapper = text_system.line_wrapper(font, font_size);
+        for (wrap_indent, line_wrap_indent) in [
+            (SoftWrapIndent::None, WrapIndent::None),
+            (SoftWrapIndent::Indented, WrapIndent::Extra(4)),
+            (SoftWrapIndent::Same, WrapIndent::Same),
+        ] {
+            wrap_map.update(cx, |map, cx| map.set_wrap_indent(wrap_indent, cx));
+            let mut notifications = observe(&wrap_map, cx);
+            if wrap_map.read_with(cx, |map, _| map.is_rewrapping()) {
+                notifications.next().await.unwrap();
+            }
+
+            let (snapshot, _) = wrap_map.update(cx, |map, cx| {
+                map.sync(tabs_snapshot.clone(), Vec::new(), cx)
+            });
+            snapshot.check_invariants();
+            let expected_text = wrap_text_with_indent(
+                &tabs_snapshot.text(),
+                wrap_width,
+                line_wrap_indent,
+                &mut line_wrapper,
+            );
+            assert_eq!(snapshot.text(), expected_text, "{wrap_indent:?}");
+
+            // Rows continuing a soft-wrapped line are indented relative to that line.
+            let expected_indents = match wrap_indent {
+                SoftWrapIndent::None => [0, 0],
+                SoftWrapIndent::Same => [4, 0],
+                SoftWrapIndent::Indented => [8, 4],
+            };
+            let mut buffer_row = 0;
+            let mut wrapped_rows = 0;
+            for (line, row) in snapshot.text().lines().zip(snapshot.buffer_rows(0)) {
+                if let Some(row) = row {
+                    buffer_row = row;
+                } else {
+                    wrapped_rows += 1;
+                    assert_eq!(
+                        line.len() - line.trim_start().len(),
+                        expected_indents[buffer_row as usize],
+                        "{wrap_indent:?}: {line:?}"
+                    );
+                }
+            }
+            assert!(wrapped_rows > 0);
+        }
+    }
+
     fn wrap_text(
         unwrapped_text: &str,
         wrap_width: Option<Pixels>,
         line_wrapper: &mut LineWrapper,
+    ) -> String {
+        wrap_text_with_indent(unwrapped_text, wrap_width, WrapIndent::Same, line_wrapper)
+    }
+
+    fn wrap_text_with_indent(
+        unwrapped_text: &str,
+        wrap_width: Option<Pixels>,
+        wrap_indent: WrapIndent,
+        line_wrapper: &mut LineWrapper,
     ) -> String {
         if let Some(wrap_width) = wrap_width {
             let mut wrapped_text = String::new();
@@ -1290,7 +1407,7 @@ mod tests {
                 }
 
                 let mut prev_ix = 0;
-                for boundary in line_wrapper.wrap_line(line, wrap_width) {
+                for boundary in line_wrapper.wrap_line_with_indent(line, wrap_width, wrap_indent) {
                     wrapped_text.push_str(&line[prev_ix..boundary.ix]);
                     wrapped_text.push('\n');
                     wrapped_text.push_str(&" ".repeat(boundary.next_indent as usize));
@@ -14,22 +14,31 @@
 //! If you're looking to improve Vim mode, you should check out Vim crate that wraps Editor and overrides it's behaviour.
 pub mod actions;
 mod blink_manager;
+mod bookmarks;
+mod bracket_pairs;
 pub mod display_map;
 mod editor_settings;
 mod element;
+mod emmet;
 mod inlay_hint_cache;
+mod kill_ring;
 
 mod git;
 mod highlight_matching_bracket;
 mod hover_popover;
+mod identifier_case;
+mod increment;
 pub mod items;
 mod link_go_to_definition;
 mod mouse_context_menu;
 pub mod movement;
+mod peek;
 mod persistence;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod signature_help;
+mod word_completions;
 
 #[cfg(test)]
 mod editor_tests;
@@ -40,6 +49,8 @@ pub(crate) use actions::*;
 use aho_corasick::AhoCorasick;
 use anyhow::{anyhow, Context as _, Result};
 use blink_manager::BlinkManager;
+pub use bookmarks::{open_bookmark, Bookmark, BookmarkStore};
+use bracket_pairs::{refresh_bracket_scope_guide, refresh_colorized_brackets, BracketScopeGuide};
 use client::{Collaborator, ParticipantIndex};
 use clock::ReplicaId;
 use collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
@@ -48,6 +59,7 @@ use copilot::Copilot;
 pub use display_map::DisplayPoint;
 use display_map::*;
 pub use editor_settings::EditorSettings;
+use editor_settings::{CompletionRanking, CursorMovement};
 use element::LineWithInvisibles;
 pub use element::{Cursor, EditorElement, HighlightedRange, HighlightedRangeLine};
 use futures::FutureExt;
@@ -59,20 +71,24 @@ use gpui::{
     DispatchPhase, ElementId, EventEmitter, FocusHandle, FocusableView, FontId, FontStyle,
     FontWeight, HighlightStyle, Hsla, InteractiveText, KeyContext, Model, MouseButton,
     ParentElement, Pixels, Render, SharedString, Styled, StyledText, Subscription, Task, TextStyle,
-    UniformListScrollHandle, View, ViewContext, ViewInputHandler, VisualContext, WeakView,
-    WhiteSpace, WindowContext,
+    UnderlineStyle, UniformListScrollHandle, View, ViewContext, ViewInputHandler, VisualContext,
+    WeakView, WhiteSpace, WindowContext,
 };
 use highlight_matching_bracket::refresh_matching_bracket_highlights;
 use hover_popover::{hide_hover, HoverState};
+use identifier_case::{convert_identifiers, IdentifierCase};
+pub use increment::{find_number, increment_number};
 use inlay_hint_cache::{InlayHintCache, InlaySplice, InvalidationStrategy};
 pub use items::MAX_TAB_TITLE_LEN;
 use itertools::Itertools;
+use kill_ring::{KillRing, KillRingState};
 use language::{char_kind, CharKind};
 use language::{
-    language_settings::{self, all_language_settings, InlayHintSettings},
-    markdown, point_from_lsp, AutoindentMode, BracketPair, Buffer, Capability, CodeAction,
-    CodeLabel, Completion, CursorShape, Diagnostic, Documentation, IndentKind, IndentSize,
-    Language, LanguageServerName, OffsetRangeExt, Point, Selection, SelectionGoal, TransactionId,
+    language_settings::{self, all_language_settings, AcceptCompletionWith, InlayHintSettings},
+    markdown, point_from_lsp, AutoindentMode, BlockCommentContinuation, BracketPair, Buffer,
+    Capability, CodeAction, CodeLabel, Completion, CursorShape, Diagnostic, Documentation,
+    IndentKind, IndentSize, Language, LanguageScope, LanguageServerName, OffsetRangeExt, Point,
+    Selection, SelectionGoal, TransactionId,
 };
 
 use link_go_to_definition::{GoToDefinitionLink, InlayHighlight, LinkGoToDefinitionState};
@@ -86,13 +102,16 @@ pub use multi_buffer::{
 };
 use ordered_float::OrderedFloat;
 use parking_lot::RwLock;
+pub use peek::{PeekEvent, PeekView};
 use project::{FormatTrigger, Location, Project, ProjectPath, ProjectTransaction};
 use rand::prelude::*;
+use regex::Regex;
 use rpc::proto::*;
 use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAutoHide};
 use selections_collection::{resolve_multiple, MutableSelectionsCollection, SelectionsCollection};
 use serde::{Deserialize, Serialize};
 use settings::{Settings, SettingsStore};
+use signature_help::SignatureHelpState;
 use smallvec::SmallVec;
 use snippet::Snippet;
 use std::{
@@ -100,8 +119,7 @@ use std::{
     borrow::Cow,
     cmp::{self, Ordering, Reverse},
     mem,
-    num::NonZeroU32,
-    ops::{ControlFlow, Deref, DerefMut, Range, RangeInclusive},
+    ops::{ControlFlow, Deref, DerefMut, Range},
     path::Path,
     sync::Arc,
     sync::Weak,
@@ -115,16 +133,21 @@ use theme::{
     ThemeColors, ThemeSettings,
 };
 use ui::{
-    h_flex, prelude::*, ButtonSize, ButtonStyle, IconButton, IconName, IconSize, ListItem, Popover,
-    Tooltip,
+    h_flex, prelude::*, ButtonSize, ButtonStyle, IconButton, IconName, IconSize, LinkPreview,
+    ListItem, Popover, Tooltip,
 };
 use util::{post_inc, RangeExt, ResultExt, TryFutureExt};
-use workspace::{searchable::SearchEvent, ItemNavHistory, Pane, SplitDirection, ViewId, Workspace};
+use workspace::{
+    searchable::SearchEvent, ItemNavHistory, Pane, SaveIntent, SplitDirection, ViewId, Workspace,
+    WorkspaceId,
+};
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
 const MAX_LINE_LEN: usize = 1024;
 const MIN_NAVIGATION_HISTORY_ROW_DELTA: i64 = 10;
 const MAX_SELECTION_HISTORY_LEN: usize = 1024;
+/// How many lines above and below the cursor words count as nearby, when ranking completions.
+const COMPLETION_LOCALITY_LINES: u32 = 100;
 const COPILOT_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(75);
 pub(crate) const CURSORS_VISIBLE_FOR: Duration = Duration::from_millis(2000);
 #[doc(hidden)]
@@ -179,10 +202,24 @@ pub fn render_parsed_markdown(
         }
     }
 
+    // Show where web links lead when they're hovered.
+    let web_links = link_ranges
+        .iter()
+        .zip(&links)
+        .filter_map(|(range, link)| match link {
+            markdown::Link::Web { url } => Some((range.clone(), url.clone())),
+            markdown::Link::Path { .. } => None,
+        })
+        .collect::<Vec<_>>();
+
     InteractiveText::new(
         element_id,
         StyledText::new(parsed.text.clone()).with_highlights(&editor_style.text, highlights),
     )
+    .tooltip(move |ix, cx| {
+        let (_, url) = web_links.iter().find(|(range, _)| range.contains(&ix))?;
+        Some(LinkPreview::new(url, cx))
+    })
     .on_click(link_ranges, move |clicked_range_ix, cx| {
         match &links[clicked_range_ix] {
             markdown::Link::Web { url } => cx.open_url(url),
@@ -380,6 +417,8 @@ pub struct Editor {
     background_highlights: BTreeMap<TypeId, BackgroundHighlight>,
     inlay_background_highlights: TreeMap<Option<TypeId>, InlayBackgroundHighlight>,
     nav_history: Option<ItemNavHistory>,
+    bookmark_store: Option<Model<BookmarkStore>>,
+    peeks: Vec<peek::Peek>,
     context_menu: RwLock<Option<ContextMenu>>,
     mouse_context_menu: Option<MouseContextMenu>,
     completion_tasks: Vec<(CompletionId, Task<Option<()>>)>,
@@ -399,6 +438,8 @@ pub struct Editor {
     leader_peer_id: Option<PeerId>,
     remote_id: Option<ViewId>,
     hover_state: HoverState,
+    signature_help_state: SignatureHelpState,
+    bracket_scope_guide: Option<BracketScopeGuide>,
     gutter_hovered: bool,
     link_go_to_definition_state: LinkGoToDefinitionState,
     copilot_state: CopilotState,
@@ -411,6 +452,30 @@ pub struct Editor {
     editor_actions: Vec<Box<dyn Fn(&mut ViewContext<Self>)>>,
     show_copilot_suggestions: bool,
     use_autoclose: bool,
+    /// Whether a mark has been set, so that cursor movements extend the selections.
+    selection_mark_mode: bool,
+    kill_ring_state: Option<KillRingState>,
+    /// A surround action that's waiting for the next typed character to choose its pair.
+    pending_surround: Option<PendingSurround>,
+    /// Whether an alignment is waiting for the next typed character to choose its delimiter.
+    pending_alignment: bool,
+    /// The edits of a code action spanning several files that this editor previews, until
+    /// they're applied or discarded.
+    refactor_preview: Option<RefactorPreview>,
+}
+
+/// The unapplied edits of a code action, shown by an editor for a copy of each buffer they edit.
+struct RefactorPreview {
+    title: String,
+    edits: Vec<(Model<Buffer>, Vec<(Range<language::Anchor>, String)>)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PendingSurround {
+    /// Surround the selections, or the words containing the cursors, with a pair.
+    Add,
+    /// Replace the innermost pair of brackets enclosing each selection.
+    Change,
 }
 
 pub struct EditorSnapshot {
@@ -702,6 +767,9 @@ struct CompletionsMenu {
     matches: Arc<[StringMatch]>,
     selected_item: usize,
     scroll_handle: UniformListScrollHandle,
+    ranking: CompletionRanking,
+    /// The words around the cursor, and how many lines away from it they are.
+    nearby_words: Arc<HashMap<String, u32>>,
 }
 
 impl CompletionsMenu {
@@ -833,9 +901,27 @@ impl CompletionsMenu {
         let selected_item = self.selected_item;
         let style = style.clone();
 
-        let multiline_docs = {
+        // The details of the selected completion are shown next to the list: its detail, such as
+        // its type or signature, and its full documentation.
+        let details = if show_completion_documentation {
             let mat = &self.matches[selected_item];
-            let multiline_docs = match &self.completions.read()[mat.candidate_id].documentation {
+            let completions = self.completions.read();
+            let completion = &completions[mat.candidate_id];
+            let detail = completion
+                .lsp_completion
+                .detail
+                .as_deref()
+                .map(str::trim)
+                .filter(|detail| !detail.is_empty() && !completion.label.text.contains(detail))
+                .map(|detail| {
+                    div()
+                        .font(style.text.font_family.clone())
+                        .child(SharedString::from(detail.to_string()))
+                });
+            let documentation = match &completion.documentation {
+                Some(Documentation::SingleLine(text)) if !text.trim().is_empty() => {
+                    Some(div().child(SharedString::from(text.clone())))
+                }
                 Some(Documentation::MultiLinePlainText(text)) => {
                     Some(div().child(SharedString::from(text.clone())))
                 }
@@ -844,20 +930,32 @@ impl CompletionsMenu {
                 )),
                 _ => None,
             };
-            multiline_docs.map(|div| {
-                div.id("multiline_docs")
-                    .max_h(max_height)
-                    .flex_1()
-                    .px_1p5()
-                    .py_1()
-                    .min_w(px(260.))
-                    .max_w(px(640.))
-                    .w(px(500.))
-                    .overflow_y_scroll()
-                    // Prevent a mouse down on documentation from being propagated to the editor,
-                    // because that would move the cursor.
-                    .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
-            })
+            if detail.is_none() && documentation.is_none() {
+                None
+            } else {
+                Some(
+                    div()
+                        .id("completion_details")
+                        .flex()
+                        .flex_col()
+                        .gap_1()
+                        .max_h(max_height)
+                        .flex_1()
+                        .px_1p5()
+                        .py_1()
+                        .min_w(px(260.))
+                        .max_w(px(640.))
+                        .w(px(500.))
+                        .overflow_y_scroll()
+                        // Prevent a mouse down on the details from being propagated to the editor,
+                        // because that would move the cursor.
+                        .on_mouse_down(MouseButton::Left, |_, cx| cx.stop_propagation())
+                        .children(detail)
+                        .children(documentation),
+                )
+            }
+        } else {
+            None
         };
 
         let list = uniform_list(
@@ -901,16 +999,32 @@ impl CompletionsMenu {
                                     None
                                 } else {
                                     Some(
-                                        h_flex().ml_4().child(
-                                            Label::new(text.clone())
-                                                .size(LabelSize::Small)
-                                                .color(Color::Muted),
-                                        ),
+                                        Label::new(text.clone())
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted),
                                     )
                                 }
                             } else {
                                 None
                             };
+                        // Completions with additional edits usually import what they complete.
+                        let auto_import_label = completion.has_additional_edits().then(|| {
+                            Label::new("auto-import")
+                                .size(LabelSize::XSmall)
+                                .color(Color::Accent)
+                        });
+                        let end_slot =
+                            if documentation_label.is_some() || auto_import_label.is_some() {
+                                Some(
+                                    h_flex()
+                                        .ml_4()
+                                        .gap_2()
+                                        .children(auto_import_label)
+                                        .children(documentation_label),
+                                )
+                            } else {
+                                None
+                            };
 
                         div().min_w(px(220.)).max_w(px(540.)).child(
                             ListItem::new(mat.candidate_id)
@@ -928,7 +1042,7 @@ impl CompletionsMenu {
                                         .map(|task| task.detach_and_log_err(cx));
                                 }))
                                 .child(h_flex().overflow_hidden().child(completion_label))
-                                .end_slot::<Div>(documentation_label),
+                                .end_slot::<Div>(end_slot),
                         )
                     })
                     .collect()
@@ -940,9 +1054,7 @@ impl CompletionsMenu {
 
         Popover::new()
             .child(list)
-            .when_some(multiline_docs, |popover, multiline_docs| {
-                popover.aside(multiline_docs)
-            })
+            .when_some(details, |popover, details| popover.aside(details))
             .into_any_element()
     }
 
@@ -986,11 +1098,24 @@ impl CompletionsMenu {
             }
         }
 
+        let prefix = query
+            .filter(|_| self.ranking.prefer_prefix_matches)
+            .map(|query| query.to_lowercase());
         let completions = self.completions.read();
         matches.sort_unstable_by_key(|mat| {
             let completion = &completions[mat.candidate_id];
+            let is_prefix_match = prefix.as_ref().map_or(false, |prefix| {
+                mat.string.to_lowercase().starts_with(prefix)
+            });
             (
+                // Emmet expansions come first, so that tab expands the abbreviation.
+                completion.server_id != emmet::EMMET_SERVER_ID,
                 completion.lsp_completion.sort_text.as_ref(),
+                Reverse(is_prefix_match),
+                self.nearby_words
+                    .get(&mat.string)
+                    .copied()
+                    .unwrap_or(u32::MAX),
                 Reverse(OrderedFloat(mat.score)),
                 completion.sort_key(),
             )
@@ -1364,7 +1489,10 @@ impl Editor {
         let style = cx.text_style();
         let font_size = style.font_size.to_pixels(cx.rem_size());
         let display_map = cx.new_model(|cx| {
-            DisplayMap::new(buffer.clone(), style.font(), font_size, None, 2, 1, cx)
+            let display_map =
+                DisplayMap::new(buffer.clone(), style.font(), font_size, None, 2, 1, cx);
+            display_map.set_wrap_indent(EditorSettings::get_global(cx).soft_wrap_indent, cx);
+            display_map
         });
 
         let selections = SelectionsCollection::new(display_map.clone(), buffer.clone());
@@ -1431,6 +1559,8 @@ impl Editor {
             background_highlights: Default::default(),
             inlay_background_highlights: Default::default(),
             nav_history: None,
+            bookmark_store: None,
+            peeks: Vec::new(),
             context_menu: RwLock::new(None),
             mouse_context_menu: None,
             completion_tasks: Default::default(),
@@ -1449,9 +1579,16 @@ impl Editor {
             input_enabled: true,
             read_only: false,
             use_autoclose: true,
+            selection_mark_mode: false,
+            pending_surround: None,
+            pending_alignment: false,
+            refactor_preview: None,
+            kill_ring_state: None,
             leader_peer_id: None,
             remote_id: None,
             hover_state: Default::default(),
+            signature_help_state: Default::default(),
+            bracket_scope_guide: None,
             link_go_to_definition_state: Default::default(),
             copilot_state: Default::default(),
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
@@ -1510,11 +1647,31 @@ impl Editor {
         if self.pending_rename.is_some() {
             key_context.add("renaming");
         }
+        if self.selection_mark_mode {
+            key_context.add("selection_mode");
+        }
+        if self.pending_surround.is_some() {
+            key_context.add("surround_pending");
+        }
+        if self.pending_alignment {
+            key_context.add("align_pending");
+        }
+        if self.refactor_preview.is_some() {
+            key_context.add("refactor_preview");
+        }
         if self.context_menu_visible() {
             match self.context_menu.read().as_ref() {
                 Some(ContextMenu::Completions(_)) => {
                     key_context.add("menu");
-                    key_context.add("showing_completions")
+                    key_context.add("showing_completions");
+                    if let Some(accept_completion_with) = self.accept_completion_with(cx) {
+                        if accept_completion_with.tab() {
+                            key_context.add("accept_completion_on_tab");
+                        }
+                        if accept_completion_with.enter() {
+                            key_context.add("accept_completion_on_enter");
+                        }
+                    }
                 }
                 Some(ContextMenu::CodeActions(_)) => {
                     key_context.add("menu");
@@ -1523,6 +1680,15 @@ impl Editor {
                 None => {}
             }
         }
+        if self.hover_state.focused && self.hover_state.info_popover.is_some() {
+            key_context.add("hover_focused");
+        }
+        if self.signature_help_state.visible() {
+            key_context.add("showing_signature_help");
+            if self.signature_help_state.has_overloads() {
+                key_context.add("signature_overloads");
+            }
+        }
 
         for layer in self.keymap_context_layers.values() {
             key_context.extend(layer);
@@ -1595,6 +1761,12 @@ impl Editor {
         self.workspace.as_ref()?.0.upgrade()
     }
 
+    /// The id of the workspace containing this editor, which can be read while the workspace
+    /// itself is being updated.
+    pub fn workspace_id(&self) -> Option<WorkspaceId> {
+        Some(self.workspace.as_ref()?.1)
+    }
+
     pub fn pane(&self, cx: &AppContext) -> Option<View<Pane>> {
         self.workspace()?.read(cx).pane_for(&self.handle.upgrade()?)
     }
@@ -1831,6 +2003,7 @@ impl Editor {
             }
 
             hide_hover(self, cx);
+            self.refresh_signature_help(cx);
 
             if old_cursor_position.to_display_point(&display_map).row()
                 != new_cursor_position.to_display_point(&display_map).row()
@@ -1840,6 +2013,7 @@ impl Editor {
             self.refresh_code_actions(cx);
             self.refresh_document_highlights(cx);
             refresh_matching_bracket_highlights(self, cx);
+            refresh_bracket_scope_guide(self, cx);
             self.discard_copilot_suggestion(cx);
         }
 
@@ -1999,6 +2173,7 @@ impl Editor {
         if !self.focus_handle.is_focused(cx) {
             cx.focus(&self.focus_handle);
         }
+        self.selection_mark_mode = false;
 
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = &display_map.buffer_snapshot;
@@ -2252,6 +2427,10 @@ impl Editor {
             return;
         }
 
+        if self.hide_signature_help(cx) {
+            return;
+        }
+
         if self.discard_copilot_suggestion(cx) {
             return;
         }
@@ -2260,6 +2439,27 @@ impl Editor {
             return;
         }
 
+        if self.pending_surround.take().is_some() {
+            cx.notify();
+            return;
+        }
+
+        if self.pending_alignment {
+            self.pending_alignment = false;
+            cx.notify();
+            return;
+        }
+
+        if self.selection_mark_mode {
+            self.selection_mark_mode = false;
+            self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.move_with(|_, selection| {
+                    selection.collapse_to(selection.head(), SelectionGoal::None)
+                });
+            });
+            return;
+        }
+
         if self.mode == EditorMode::Full {
             if self.active_diagnostics.is_some() {
                 self.dismiss_diagnostics(cx);
@@ -2269,6 +2469,10 @@ impl Editor {
             if self.change_selections(Some(Autoscroll::fit()), cx, |s| s.try_cancel()) {
                 return;
             }
+
+            if self.close_last_peek(cx) {
+                return;
+            }
         }
 
         cx.propagate();
@@ -2280,6 +2484,28 @@ impl Editor {
         if self.read_only(cx) {
             return;
         }
+        self.selection_mark_mode = false;
+
+        if let Some(pending_surround) = self.pending_surround.take() {
+            if !text.is_empty() {
+                self.surround_with_pair(pending_surround, &text, cx);
+            }
+            return;
+        }
+
+        if self.pending_alignment {
+            self.pending_alignment = false;
+            if !text.is_empty() {
+                self.align_lines(&AlignmentDelimiter::Text(text.to_string()), cx);
+            }
+            return;
+        }
+
+        if self.is_completion_commit_character(&text, cx) {
+            if let Some(task) = self.confirm_completion(&ConfirmCompletion::default(), cx) {
+                task.detach_and_log_err(cx);
+            }
+        }
 
         let selections = self.selections.all_adjusted(cx);
         let mut brace_inserted = false;
@@ -2292,6 +2518,8 @@ impl Editor {
             self.selections_with_autoclose_regions(selections, &snapshot)
         {
             if let Some(scope) = snapshot.language_scope_at(selection.head()) {
+                let settings = snapshot.settings_at(selection.start, cx);
+
                 // Determine if the inserted text matches the opening or closing
                 // bracket of any of this language's bracket pairs.
                 let mut bracket_pair = None;
@@ -2300,7 +2528,12 @@ impl Editor {
                     // `text` can be empty when an user is using IME (e.g. Chinese Wubi Simplified)
                     //  and they are removing the character that triggered IME popup.
                     for (pair, enabled) in scope.brackets() {
-                        if enabled && pair.close && pair.start.ends_with(text.as_ref()) {
+                        let close = settings
+                            .autoclose_brackets
+                            .get(&pair.start)
+                            .copied()
+                            .unwrap_or(pair.close);
+                        if enabled && close && pair.start.ends_with(text.as_ref()) {
                             bracket_pair = Some(pair.clone());
                             is_bracket_pair_start = true;
                             break;
@@ -2313,6 +2546,17 @@ impl Editor {
 
                 if let Some(bracket_pair) = bracket_pair {
                     if selection.is_empty() {
+                        // Closing brackets that weren't auto-inserted can be typed over too.
+                        if settings.always_type_over_closing_brackets
+                            && text.as_ref() == bracket_pair.end
+                            && snapshot.contains_str_at(selection.end, &bracket_pair.end)
+                        {
+                            let anchor = snapshot.anchor_after(selection.end);
+                            new_selections
+                                .push((selection.map(|_| anchor), bracket_pair.end.len()));
+                            continue;
+                        }
+
                         if is_bracket_pair_start {
                             let prefix_len = bracket_pair.start.len() - text.len();
 
@@ -2332,8 +2576,7 @@ impl Editor {
                                         ),
                                         &bracket_pair.start[..prefix_len],
                                     ));
-                            let autoclose = self.use_autoclose
-                                && snapshot.settings_at(selection.start, cx).use_autoclose;
+                            let autoclose = self.use_autoclose && settings.use_autoclose;
                             if autoclose
                                 && following_text_allows_autoclose
                                 && preceding_text_matches_prefix
@@ -2371,7 +2614,10 @@ impl Editor {
                     }
                     // If an opening bracket is 1 character long and is typed while
                     // text is selected, then surround that text with the bracket pair.
-                    else if is_bracket_pair_start && bracket_pair.start.chars().count() == 1 {
+                    else if is_bracket_pair_start
+                        && settings.use_auto_surround
+                        && bracket_pair.start.chars().count() == 1
+                    {
                         edits.push((selection.start..selection.start, text.clone()));
                         edits.push((
                             selection.end..selection.end,
@@ -2468,6 +2714,7 @@ impl Editor {
                 this.trigger_completion_on_input(&text, cx);
                 this.refresh_copilot_suggestions(true, cx);
             }
+            this.trigger_signature_help_on_input(&text, cx);
         });
     }
 
@@ -2520,11 +2767,11 @@ impl Editor {
                                         )
                                 });
                             // Comment extension on newline is allowed only for cursor selections
-                            let comment_delimiter = language.line_comment_prefixes().filter(|_| {
-                                let is_comment_extension_enabled =
-                                    multi_buffer.settings_at(0, cx).extend_comment_on_newline;
-                                is_cursor && is_comment_extension_enabled
-                            });
+                            let is_comment_extension_enabled = is_cursor
+                                && multi_buffer.settings_at(0, cx).extend_comment_on_newline;
+                            let comment_delimiter = language
+                                .line_comment_prefixes()
+                                .filter(|_| is_comment_extension_enabled);
                             let get_comment_delimiter = |delimiters: &[Arc<str>]| {
                                 let max_len_of_delimiter =
                                     delimiters.iter().map(|delimiter| delimiter.len()).max()?;
@@ -2560,6 +2807,12 @@ impl Editor {
                             } else {
                                 None
                             };
+                            let comment_delimiter = comment_delimiter.or_else(|| {
+                                let continuation = language
+                                    .block_comment_continuation()
+                                    .filter(|_| is_comment_extension_enabled)?;
+                                block_comment_leader(&buffer, start_point, continuation)
+                            });
                             (comment_delimiter, insert_extra_newline)
                         } else {
                             (None, false)
@@ -2882,6 +3135,53 @@ impl Editor {
         });
     }
 
+    /// Which keys accept the selected completion, according to the settings of the language at
+    /// the newest cursor, or `None` if it shouldn't be accepted because nothing has been typed.
+    fn accept_completion_with(&self, cx: &AppContext) -> Option<AcceptCompletionWith> {
+        let buffer = self.buffer.read(cx).read(cx);
+        let position = self.selections.newest_anchor().head();
+        let settings = buffer.settings_at(position.clone(), cx);
+        if !settings.accept_completion_without_typing
+            && Self::completion_query(&buffer, position).is_none()
+        {
+            return None;
+        }
+        Some(settings.accept_completion_with)
+    }
+
+    /// Whether the text being typed is a commit character of the selected completion, so that
+    /// the completion should be accepted before the text is inserted.
+    fn is_completion_commit_character(&self, text: &str, cx: &AppContext) -> bool {
+        let context_menu = self.context_menu.read();
+        let Some(ContextMenu::Completions(menu)) = context_menu.as_ref() else {
+            return false;
+        };
+        let Some(mat) = menu.matches.get(menu.selected_item) else {
+            return false;
+        };
+
+        // Like the keys that accept completions, commit characters only accept them once
+        // something has been typed, so they can still be typed right after a trigger character.
+        let buffer = self.buffer.read(cx).read(cx);
+        let position = self.selections.newest_anchor().head();
+        if !buffer
+            .settings_at(position.clone(), cx)
+            .use_completion_commit_characters
+            || Self::completion_query(&buffer, position).is_none()
+        {
+            return false;
+        }
+
+        let completions = menu.completions.read();
+        completions[mat.candidate_id]
+            .lsp_completion
+            .commit_characters
+            .as_ref()
+            .map_or(false, |commit_characters| {
+                commit_characters.iter().any(|character| character == text)
+            })
+    }
+
     fn completion_query(buffer: &MultiBufferSnapshot, position: impl ToOffset) -> Option<String> {
         let offset = position.to_offset(buffer);
         let (word_range, kind) = buffer.surrounding_word(offset);
@@ -3136,6 +3436,12 @@ impl Editor {
         };
 
         let query = Self::completion_query(&self.buffer.read(cx).read(cx), position.clone());
+        let ranking = EditorSettings::get_global(cx).completion_ranking;
+        let nearby_words = if ranking.locality_bonus {
+            nearby_words(&self.buffer.read(cx).read(cx), position.clone())
+        } else {
+            HashMap::default()
+        };
         let completions = provider.completions(&buffer, buffer_position, cx);
 
         let id = post_inc(&mut self.next_completion_id);
@@ -3162,6 +3468,8 @@ impl Editor {
                         matches: Vec::new().into(),
                         selected_item: 0,
                         scroll_handle: UniformListScrollHandle::new(),
+                        ranking,
+                        nearby_words: Arc::new(nearby_words),
                     };
                     menu.filter(query.as_deref(), cx.background_executor().clone())
                         .await;
@@ -3356,6 +3664,21 @@ impl Editor {
     }
 
     pub fn toggle_code_actions(&mut self, action: &ToggleCodeActions, cx: &mut ViewContext<Self>) {
+        self.deploy_code_actions(action.deployed_from_indicator, false, cx);
+    }
+
+    /// Shows the refactoring code actions available at the cursor, such as extracting a
+    /// function or inlining a variable.
+    pub fn refactor(&mut self, _: &Refactor, cx: &mut ViewContext<Self>) {
+        self.deploy_code_actions(false, true, cx);
+    }
+
+    fn deploy_code_actions(
+        &mut self,
+        deployed_from_indicator: bool,
+        only_refactorings: bool,
+        cx: &mut ViewContext<Self>,
+    ) {
         let mut context_menu = self.context_menu.write();
         if matches!(context_menu.as_ref(), Some(ContextMenu::CodeActions(_))) {
             *context_menu = None;
@@ -3364,7 +3687,6 @@ impl Editor {
         }
         drop(context_menu);
 
-        let deployed_from_indicator = action.deployed_from_indicator;
         let mut task = self.code_actions_task.take();
         cx.spawn(|this, mut cx| async move {
             while let Some(prev_task) = task {
@@ -3374,7 +3696,17 @@ impl Editor {
 
             this.update(&mut cx, |this, cx| {
                 if this.focus_handle.is_focused(cx) {
-                    if let Some((buffer, actions)) = this.available_code_actions.clone() {
+                    if let Some((buffer, mut actions)) = this.available_code_actions.clone() {
+                        if only_refactorings {
+                            actions = actions
+                                .iter()
+                                .filter(|action| is_refactoring(action))
+                                .cloned()
+                                .collect();
+                            if actions.is_empty() {
+                                return;
+                            }
+                        }
                         this.completion_tasks.clear();
                         this.discard_copilot_suggestion(cx);
                         *this.context_menu.write() =
@@ -3411,20 +3743,171 @@ impl Editor {
         let buffer = actions_menu.buffer;
         let workspace = self.workspace()?;
 
-        let apply_code_actions = workspace
-            .read(cx)
-            .project()
-            .clone()
-            .update(cx, |project, cx| {
-                project.apply_code_action(buffer, action, true, cx)
-            });
+        let project = workspace.read(cx).project().clone();
+        let code_action_edits = project.update(cx, |project, cx| {
+            project.code_action_edits(buffer.clone(), action.clone(), cx)
+        });
         let workspace = workspace.downgrade();
-        Some(cx.spawn(|editor, cx| async move {
-            let project_transaction = apply_code_actions.await?;
+        Some(cx.spawn(|editor, mut cx| async move {
+            let project_transaction = match code_action_edits.await? {
+                Some(edits) if edits.len() > 1 => {
+                    return Self::open_refactor_preview(&editor, workspace, edits, title, cx).await;
+                }
+                Some(edits) => cx.update(|cx| apply_buffer_edits(edits, cx))?,
+                None => {
+                    project
+                        .update(&mut cx, |project, cx| {
+                            project.apply_code_action(buffer, action, true, cx)
+                        })?
+                        .await?
+                }
+            };
             Self::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
         }))
     }
 
+    /// Applies the edits of the code action that this editor previews, and closes the preview.
+    pub fn apply_refactoring(&mut self, _: &ApplyRefactoring, cx: &mut ViewContext<Self>) {
+        let Some(preview) = self.refactor_preview.take() else {
+            cx.propagate();
+            return;
+        };
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let workspace = workspace.downgrade();
+        let project_transaction = apply_buffer_edits(preview.edits, cx);
+        cx.spawn(|editor, cx| async move {
+            Self::open_project_transaction(
+                &editor,
+                workspace.clone(),
+                project_transaction,
+                preview.title,
+                cx.clone(),
+            )
+            .await?;
+            Self::close_refactor_preview(editor, workspace, cx).await
+        })
+        .detach_and_log_err(cx);
+    }
+
+    /// Closes the preview of a code action without applying its edits.
+    pub fn discard_refactoring(&mut self, _: &DiscardRefactoring, cx: &mut ViewContext<Self>) {
+        if self.refactor_preview.take().is_none() {
+            cx.propagate();
+            return;
+        }
+        let Some(workspace) = self.workspace() else {
+            return;
+        };
+        let workspace = workspace.downgrade();
+        cx.spawn(|editor, cx| Self::close_refactor_preview(editor, workspace, cx))
+            .detach_and_log_err(cx);
+    }
+
+    async fn close_refactor_preview(
+        editor: WeakView<Editor>,
+        workspace: WeakView<Workspace>,
+        mut cx: AsyncWindowContext,
+    ) -> Result<()> {
+        let Some(editor) = editor.upgrade() else {
+            return Ok(());
+        };
+        let close_tasks = workspace.update(&mut cx, |workspace, cx| {
+            workspace
+                .panes()
+                .iter()
+                .filter(|pane| pane.read(cx).index_for_item(&editor).is_some())
+                .map(|pane| {
+                    pane.update(cx, |pane, cx| {
+                        pane.close_item_by_id(editor.entity_id(), SaveIntent::Skip, cx)
+                    })
+                })
+                .collect::<Vec<_>>()
+        })?;
+        for task in close_tasks {
+            task.await?;
+        }
+        Ok(())
+    }
+
+    /// Shows what the edits of a code action spanning several buffers would change, by editing
+    /// copies of those buffers. The edits are applied with [`ApplyRefactoring`] or discarded
+    /// with [`DiscardRefactoring`].
+    async fn open_refactor_preview(
+        this: &WeakView<Editor>,
+        workspace: WeakView<Workspace>,
+        mut edits: Vec<(Model<Buffer>, Vec<(Range<language::Anchor>, String)>)>,
+        title: String,
+        mut cx: AsyncWindowContext,
+    ) -> Result<()> {
+        let replica_id = this.update(&mut cx, |this, cx| this.replica_id(cx))?;
+        cx.update(|cx| {
+            edits.sort_unstable_by_key(|(buffer, _)| {
+                buffer.read(cx).file().map(|f| f.path().clone())
+            });
+        })?;
+
+        let mut ranges_to_highlight = Vec::new();
+        let excerpt_buffer = cx.new_model(|cx| {
+            let mut multibuffer = MultiBuffer::new(replica_id, Capability::ReadOnly)
+                .with_title(format!("Preview: {}", title));
+            for (buffer_handle, buffer_edits) in &edits {
+                let buffer = buffer_handle.read(cx);
+                let language = buffer.language().cloned();
+                let old_text = buffer.text();
+                let mut buffer_edits = buffer_edits
+                    .iter()
+                    .map(|(range, text)| (range.to_offset(buffer), text.as_str()))
+                    .collect::<Vec<_>>();
+                buffer_edits.sort_by_key(|(range, _)| range.start);
+
+                let mut new_text = String::new();
+                let mut new_ranges = Vec::new();
+                let mut old_offset = 0;
+                for (range, text) in buffer_edits {
+                    let start = range.start.max(old_offset);
+                    new_text.push_str(&old_text[old_offset..start]);
+                    new_ranges.push(new_text.len()..new_text.len() + text.len());
+                    new_text.push_str(text);
+                    old_offset = range.end.max(start);
+                }
+                new_text.push_str(&old_text[old_offset..]);
+
+                let preview_buffer = cx.new_model(|cx| {
+                    let mut buffer =
+                        Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), new_text);
+                    buffer.set_language(language, cx);
+                    buffer
+                });
+                ranges_to_highlight.extend(multibuffer.push_excerpts_with_context_lines(
+                    preview_buffer,
+                    new_ranges,
+                    1,
+                    cx,
+                ));
+            }
+            multibuffer
+        })?;
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let project = workspace.project().clone();
+            let editor =
+                cx.new_view(|cx| Editor::for_multibuffer(excerpt_buffer, Some(project), cx));
+            workspace.add_item(Box::new(editor.clone()), cx);
+            editor.update(cx, |editor, cx| {
+                editor.highlight_background::<Self>(
+                    ranges_to_highlight,
+                    |theme| theme.editor_highlighted_line_background,
+                    cx,
+                );
+                editor.refactor_preview = Some(RefactorPreview { title, edits });
+            });
+        })?;
+
+        Ok(())
+    }
+
     async fn open_project_transaction(
         this: &WeakView<Editor>,
         workspace: WeakView<Workspace>,
@@ -4157,12 +4640,11 @@ impl Editor {
                                 buffer.indent_size_for_line(line_buffer_range.start.row);
                             let indent_len = match indent_size.kind {
                                 IndentKind::Space => {
-                                    buffer.settings_at(line_buffer_range.start, cx).tab_size
+                                    indent_unit_at(&display_map.buffer_snapshot, old_head, cx).1
                                 }
-                                IndentKind::Tab => NonZeroU32::new(1).unwrap(),
+                                IndentKind::Tab => 1,
                             };
                             if old_head.column <= indent_size.len && old_head.column > 0 {
-                                let indent_len = indent_len.get();
                                 new_head = cmp::min(
                                     new_head,
                                     Point::new(
@@ -4232,8 +4714,7 @@ impl Editor {
 
             // If the selection is non-empty, then increase the indentation of the selected lines.
             if !selection.is_empty() {
-                row_delta =
-                    Self::indent_selection(buffer, &snapshot, selection, &mut edits, row_delta, cx);
+                row_delta = Self::indent_selection(&snapshot, selection, &mut edits, row_delta, cx);
                 continue;
             }
 
@@ -4271,11 +4752,10 @@ impl Editor {
             }
 
             // Otherwise, insert a hard or soft tab.
-            let settings = buffer.settings_at(cursor, cx);
-            let tab_size = if settings.hard_tabs {
+            let (indent_kind, tab_size) = indent_unit_at(&snapshot, cursor, cx);
+            let tab_size = if indent_kind == IndentKind::Tab {
                 IndentSize::tab()
             } else {
-                let tab_size = settings.tab_size.get();
                 let char_column = snapshot
                     .text_for_range(Point::new(cursor.row, 0)..cursor)
                     .flat_map(str::chars)
@@ -4310,8 +4790,7 @@ impl Editor {
             }
             prev_edited_row = selection.end.row;
 
-            row_delta =
-                Self::indent_selection(buffer, &snapshot, selection, &mut edits, row_delta, cx);
+            row_delta = Self::indent_selection(&snapshot, selection, &mut edits, row_delta, cx);
         }
 
         self.transact(cx, |this, cx| {
@@ -4321,20 +4800,13 @@ impl Editor {
     }
 
     fn indent_selection(
-        buffer: &MultiBuffer,
         snapshot: &MultiBufferSnapshot,
         selection: &mut Selection<Point>,
         edits: &mut Vec<(Range<Point>, String)>,
         delta_for_start_row: u32,
         cx: &AppContext,
     ) -> u32 {
-        let settings = buffer.settings_at(selection.start, cx);
-        let tab_size = settings.tab_size.get();
-        let indent_kind = if settings.hard_tabs {
-            IndentKind::Tab
-        } else {
-            IndentKind::Space
-        };
+        let (indent_kind, tab_size) = indent_unit_at(snapshot, selection.start, cx);
         let mut start_row = selection.start.row;
         let mut end_row = selection.end.row + 1;
 
@@ -4399,8 +4871,7 @@ impl Editor {
             let buffer = self.buffer.read(cx);
             let snapshot = buffer.snapshot(cx);
             for selection in &selections {
-                let settings = buffer.settings_at(selection.start, cx);
-                let tab_size = settings.tab_size.get();
+                let (_, tab_size) = indent_unit_at(&snapshot, selection.start, cx);
                 let mut rows = selection.spanned_rows(false, &display_map);
 
                 // Avoid re-outdenting a row that has already been outdented by a
@@ -4562,19 +5033,9 @@ impl Editor {
         self.transact(cx, |this, cx| {
             for row_range in row_ranges.into_iter().rev() {
                 for row in row_range.rev() {
-                    let end_of_line = Point::new(row, snapshot.line_len(row));
-                    let indent = snapshot.indent_size_for_line(row + 1);
-                    let start_of_next_line = Point::new(row + 1, indent.len);
-
-                    let replace = if snapshot.line_len(row + 1) > indent.len {
-                        " "
-                    } else {
-                        ""
-                    };
-
-                    this.buffer.update(cx, |buffer, cx| {
-                        buffer.edit([(end_of_line..start_of_next_line, replace)], None, cx)
-                    });
+                    let (range, replace) = join_lines_range(&snapshot, row);
+                    this.buffer
+                        .update(cx, |buffer, cx| buffer.edit([(range, replace)], None, cx));
                 }
             }
 
@@ -4693,11 +5154,21 @@ impl Editor {
     }
 
     pub fn convert_to_snake_case(&mut self, _: &ConvertToSnakeCase, cx: &mut ViewContext<Self>) {
-        self.manipulate_text(cx, |text| text.to_case(Case::Snake))
+        self.manipulate_text(cx, |text| convert_identifiers(text, IdentifierCase::Snake))
+    }
+
+    pub fn convert_to_upper_snake_case(
+        &mut self,
+        _: &ConvertToUpperSnakeCase,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.manipulate_text(cx, |text| {
+            convert_identifiers(text, IdentifierCase::UpperSnake)
+        })
     }
 
     pub fn convert_to_kebab_case(&mut self, _: &ConvertToKebabCase, cx: &mut ViewContext<Self>) {
-        self.manipulate_text(cx, |text| text.to_case(Case::Kebab))
+        self.manipulate_text(cx, |text| convert_identifiers(text, IdentifierCase::Kebab))
     }
 
     pub fn convert_to_upper_camel_case(
@@ -4706,11 +5177,7 @@ impl Editor {
         cx: &mut ViewContext<Self>,
     ) {
         self.manipulate_text(cx, |text| {
-            // Hack to get around the fact that to_case crate doesn't support '\n' as a word boundary
-            // https://github.com/rutrum/convert-case/issues/16
-            text.split("\n")
-                .map(|line| line.to_case(Case::UpperCamel))
-                .join("\n")
+            convert_identifiers(text, IdentifierCase::UpperCamel)
         })
     }
 
@@ -4719,7 +5186,7 @@ impl Editor {
         _: &ConvertToLowerCamelCase,
         cx: &mut ViewContext<Self>,
     ) {
-        self.manipulate_text(cx, |text| text.to_case(Case::Camel))
+        self.manipulate_text(cx, |text| convert_identifiers(text, IdentifierCase::Camel))
     }
 
     fn manipulate_text<Fn>(&mut self, cx: &mut ViewContext<Self>, mut callback: Fn)
@@ -4777,29 +5244,109 @@ impl Editor {
         });
     }
 
-    pub fn duplicate_line(&mut self, _: &DuplicateLine, cx: &mut ViewContext<Self>) {
-        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
-        let buffer = &display_map.buffer_snapshot;
-        let selections = self.selections.all::<Point>(cx);
+    /// Adds to the number at or after each cursor, and to the first number on each line of
+    /// non-empty selections. In sequence mode, each successive number is incremented by a
+    /// further `count`, so that identical numbers become a sequence.
+    pub fn increment_number(&mut self, action: &IncrementNumber, cx: &mut ViewContext<Self>) {
+        let count = action.count.unwrap_or(1) as i32;
+        self.add_to_numbers(count, action.sequence, cx);
+    }
 
-        let mut edits = Vec::new();
-        let mut selections_iter = selections.iter().peekable();
-        while let Some(selection) = selections_iter.next() {
-            // Avoid duplicating the same lines twice.
-            let mut rows = selection.spanned_rows(false, &display_map);
+    pub fn decrement_number(&mut self, action: &DecrementNumber, cx: &mut ViewContext<Self>) {
+        let count = action.count.unwrap_or(1) as i32;
+        self.add_to_numbers(-count, action.sequence, cx);
+    }
 
-            while let Some(next_selection) = selections_iter.peek() {
-                let next_rows = next_selection.spanned_rows(false, &display_map);
-                if next_rows.start < rows.end {
-                    rows.end = next_rows.end;
-                    selections_iter.next().unwrap();
-                } else {
+    fn add_to_numbers(&mut self, delta: i32, sequence: bool, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut edits: Vec<(Range<Point>, String)> = Vec::new();
+        let mut new_selections = Vec::new();
+        let mut next_delta = delta;
+        for selection in self.selections.all::<Point>(cx) {
+            let mut number_end = None;
+            for row in selection.start.row..=selection.end.row {
+                if row > selection.start.row
+                    && row == selection.end.row
+                    && selection.end.column == 0
+                {
                     break;
                 }
-            }
-
-            // Copy the text from the selected row region and splice it at the start of the region.
-            let start = Point::new(rows.start, 0);
+                let start = if row == selection.start.row {
+                    selection.start
+                } else {
+                    Point::new(row, 0)
+                };
+                let Some((range, num, radix)) = find_number(&snapshot, start) else {
+                    continue;
+                };
+                // Cursors within the same number only change it once.
+                let is_edited = edits.last().map_or(false, |(last, _)| *last == range);
+                if !is_edited {
+                    if let Some(new_text) = increment_number(&num, radix, next_delta) {
+                        edits.push((range.clone(), new_text));
+                        if sequence {
+                            next_delta += delta;
+                        }
+                    }
+                }
+                number_end = Some(range.end);
+            }
+
+            // Cursors move to the end of the number they changed, and selections keep covering
+            // the numbers within them.
+            let (start, end) = if selection.is_empty() {
+                let head = snapshot.anchor_after(number_end.unwrap_or(selection.head()));
+                (head, head)
+            } else {
+                (
+                    snapshot.anchor_before(selection.start),
+                    snapshot.anchor_after(selection.end),
+                )
+            };
+            new_selections.push(Selection {
+                id: selection.id,
+                start,
+                end,
+                reversed: selection.reversed,
+                goal: SelectionGoal::None,
+            });
+        }
+        if edits.is_empty() {
+            return;
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchors(new_selections)
+            });
+        });
+    }
+
+    pub fn duplicate_line(&mut self, _: &DuplicateLine, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer = &display_map.buffer_snapshot;
+        let selections = self.selections.all::<Point>(cx);
+
+        let mut edits = Vec::new();
+        let mut selections_iter = selections.iter().peekable();
+        while let Some(selection) = selections_iter.next() {
+            // Avoid duplicating the same lines twice.
+            let mut rows = selection.spanned_rows(false, &display_map);
+
+            while let Some(next_selection) = selections_iter.peek() {
+                let next_rows = next_selection.spanned_rows(false, &display_map);
+                if next_rows.start < rows.end {
+                    rows.end = next_rows.end;
+                    selections_iter.next().unwrap();
+                } else {
+                    break;
+                }
+            }
+
+            // Copy the text from the selected row region and splice it at the start of the region.
+            let start = Point::new(rows.start, 0);
             let end = Point::new(rows.end - 1, buffer.line_len(rows.end - 1));
             let text = buffer
                 .text_for_range(start..end)
@@ -4817,6 +5364,56 @@ impl Editor {
         });
     }
 
+    /// Inserts a copy of each selection's text after it and selects the copy. Cursors duplicate
+    /// the line that they're on, moving to the copy, as with [`Editor::duplicate_line`].
+    pub fn duplicate_selection(&mut self, _: &DuplicateSelection, cx: &mut ViewContext<Self>) {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let selections = self.selections.all::<usize>(cx);
+
+        let mut edits = Vec::new();
+        let mut new_selections = Vec::with_capacity(selections.len());
+        let mut inserted_len = 0;
+        let mut last_duplicated_row = None;
+        for selection in selections {
+            if selection.is_empty() {
+                // Avoid duplicating the same line twice.
+                let row = selection.head().to_point(&buffer).row;
+                if last_duplicated_row != Some(row) {
+                    let start = Point::new(row, 0);
+                    let end = Point::new(row, buffer.line_len(row));
+                    let text = buffer
+                        .text_for_range(start..end)
+                        .chain(Some("\n"))
+                        .collect::<String>();
+                    let start = start.to_offset(&buffer);
+                    inserted_len += text.len();
+                    edits.push((start..start, text));
+                    last_duplicated_row = Some(row);
+                }
+                new_selections.push(selection.map(|offset| offset + inserted_len));
+            } else {
+                let text = buffer.text_for_range(selection.range()).collect::<String>();
+                let start = selection.end + inserted_len;
+                new_selections.push(Selection {
+                    id: selection.id,
+                    start,
+                    end: start + text.len(),
+                    reversed: selection.reversed,
+                    goal: SelectionGoal::None,
+                });
+                inserted_len += text.len();
+                edits.push((selection.end..selection.end, text));
+            }
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(edits, None, cx);
+            });
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
+        });
+    }
+
     pub fn move_line_up(&mut self, _: &MoveLineUp, cx: &mut ViewContext<Self>) {
         let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
         let buffer = self.buffer.read(cx).snapshot(cx);
@@ -4831,8 +5428,9 @@ impl Editor {
         let mut new_selections = Vec::new();
 
         while let Some(selection) = selections.next() {
-            // Find all the selections that span a contiguous row range
-            let (start_row, end_row) = consume_contiguous_rows(
+            // Find all the selections that span a contiguous row range, along with the
+            // rest of any statements that they span
+            let (start_row, end_row) = consume_contiguous_statement_rows(
                 &mut contiguous_row_selections,
                 selection,
                 &display_map,
@@ -4876,9 +5474,13 @@ impl Editor {
                     // Move selections up
                     new_selections.extend(contiguous_row_selections.drain(..).map(
                         |mut selection| {
+                            let original_indents = (
+                                buffer.indent_size_for_line(selection.start.row).len,
+                                buffer.indent_size_for_line(selection.end.row).len,
+                            );
                             selection.start.row -= row_delta;
                             selection.end.row -= row_delta;
-                            selection
+                            (selection, Some(original_indents))
                         },
                     ));
 
@@ -4898,19 +5500,20 @@ impl Editor {
             }
 
             // If we didn't move line(s), preserve the existing selections
-            new_selections.append(&mut contiguous_row_selections);
+            new_selections.extend(
+                contiguous_row_selections
+                    .drain(..)
+                    .map(|selection| (selection, None)),
+            );
         }
 
         self.transact(cx, |this, cx| {
             this.unfold_ranges(unfold_ranges, true, true, cx);
-            this.buffer.update(cx, |buffer, cx| {
-                for (range, text) in edits {
-                    buffer.edit([(range, text)], None, cx);
-                }
-            });
+            this.edit_moved_lines(edits, cx);
             this.fold_ranges(refold_ranges, true, cx);
+            let new_selections = this.anchor_moved_selections(new_selections, cx);
             this.change_selections(Some(Autoscroll::fit()), cx, |s| {
-                s.select(new_selections);
+                s.select_anchors(new_selections);
             })
         });
     }
@@ -4929,8 +5532,9 @@ impl Editor {
         let mut new_selections = Vec::new();
 
         while let Some(selection) = selections.next() {
-            // Find all the selections that span a contiguous row range
-            let (start_row, end_row) = consume_contiguous_rows(
+            // Find all the selections that span a contiguous row range, along with the
+            // rest of any statements that they span
+            let (start_row, end_row) = consume_contiguous_statement_rows(
                 &mut contiguous_row_selections,
                 selection,
                 &display_map,
@@ -4967,9 +5571,13 @@ impl Editor {
                     // Move selections down
                     new_selections.extend(contiguous_row_selections.drain(..).map(
                         |mut selection| {
+                            let original_indents = (
+                                buffer.indent_size_for_line(selection.start.row).len,
+                                buffer.indent_size_for_line(selection.end.row).len,
+                            );
                             selection.start.row += row_delta;
                             selection.end.row += row_delta;
-                            selection
+                            (selection, Some(original_indents))
                         },
                     ));
 
@@ -4989,21 +5597,84 @@ impl Editor {
             }
 
             // If we didn't move line(s), preserve the existing selections
-            new_selections.append(&mut contiguous_row_selections);
+            new_selections.extend(
+                contiguous_row_selections
+                    .drain(..)
+                    .map(|selection| (selection, None)),
+            );
         }
 
         self.transact(cx, |this, cx| {
             this.unfold_ranges(unfold_ranges, true, true, cx);
-            this.buffer.update(cx, |buffer, cx| {
-                for (range, text) in edits {
-                    buffer.edit([(range, text)], None, cx);
-                }
-            });
+            this.edit_moved_lines(edits, cx);
             this.fold_ranges(refold_ranges, true, cx);
-            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
+            let new_selections = this.anchor_moved_selections(new_selections, cx);
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select_anchors(new_selections)
+            });
+        });
+    }
+
+    /// Applies the edits that move lines, reindenting the moved lines as a block to fit
+    /// their new position, as when pasting.
+    fn edit_moved_lines(
+        &mut self,
+        edits: Vec<(Range<Anchor>, String)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let autoindent_mode = self
+            .autoindent_mode
+            .as_ref()
+            .map(|_| AutoindentMode::Block {
+                original_indent_columns: Vec::new(),
+            });
+        self.buffer.update(cx, |buffer, cx| {
+            for (range, text) in edits {
+                // Only the insertions are reindented, not the deletions.
+                let autoindent_mode = if text.is_empty() {
+                    None
+                } else {
+                    autoindent_mode.clone()
+                };
+                buffer.edit([(range, text)], autoindent_mode, cx);
+            }
         });
     }
 
+    /// Anchors the selections in moved lines to the text they were in, given the lines'
+    /// indentation before they were moved. The anchors are placed past the lines' current
+    /// indentation and biased to the right, so they stay with the text when the moved lines
+    /// are reindented, whether that already happened or happens once autoindent finishes.
+    fn anchor_moved_selections(
+        &self,
+        selections: Vec<(Selection<Point>, Option<(u32, u32)>)>,
+        cx: &AppContext,
+    ) -> Vec<Selection<Anchor>> {
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let anchor = |mut point: Point, original_indent: Option<u32>| {
+            if let Some(original_indent) = original_indent {
+                if point.column >= original_indent {
+                    point.column =
+                        point.column - original_indent + buffer.indent_size_for_line(point.row).len;
+                }
+            }
+            buffer.anchor_after(point)
+        };
+        selections
+            .into_iter()
+            .map(|(selection, original_indents)| {
+                let (start_indent, end_indent) = original_indents.unzip();
+                Selection {
+                    id: selection.id,
+                    start: anchor(selection.start, start_indent),
+                    end: anchor(selection.end, end_indent),
+                    reversed: selection.reversed,
+                    goal: selection.goal,
+                }
+            })
+            .collect()
+    }
+
     pub fn transpose(&mut self, _: &Transpose, cx: &mut ViewContext<Self>) {
         let text_layout_details = &self.text_layout_details(cx);
         self.transact(cx, |this, cx| {
@@ -5063,6 +5734,7 @@ impl Editor {
     }
 
     pub fn cut(&mut self, _: &Cut, cx: &mut ViewContext<Self>) {
+        self.selection_mark_mode = false;
         let mut text = String::new();
         let buffer = self.buffer.read(cx).snapshot(cx);
         let mut selections = self.selections.all::<Point>(cx);
@@ -5140,6 +5812,15 @@ impl Editor {
         }
 
         cx.write_to_clipboard(ClipboardItem::new(text).with_metadata(clipboard_selections));
+
+        if self.selection_mark_mode {
+            self.selection_mark_mode = false;
+            self.change_selections(None, cx, |s| {
+                s.move_with(|_, selection| {
+                    selection.collapse_to(selection.head(), SelectionGoal::None)
+                });
+            });
+        }
     }
 
     pub fn paste(&mut self, _: &Paste, cx: &mut ViewContext<Self>) {
@@ -5261,11 +5942,12 @@ impl Editor {
     }
 
     pub fn move_left(&mut self, _: &MoveLeft, cx: &mut ViewContext<Self>) {
+        let left = self.left_movement(cx);
         self.change_selections(Some(Autoscroll::fit()), cx, |s| {
             let line_mode = s.line_mode;
             s.move_with(|map, selection| {
                 let cursor = if selection.is_empty() && !line_mode {
-                    movement::left(map, selection.start)
+                    left(map, selection.start)
                 } else {
                     selection.start
                 };
@@ -5275,17 +5957,19 @@ impl Editor {
     }
 
     pub fn select_left(&mut self, _: &SelectLeft, cx: &mut ViewContext<Self>) {
+        let left = self.left_movement(cx);
         self.change_selections(Some(Autoscroll::fit()), cx, |s| {
-            s.move_heads_with(|map, head, _| (movement::left(map, head), SelectionGoal::None));
+            s.move_heads_with(|map, head, _| (left(map, head), SelectionGoal::None));
         })
     }
 
     pub fn move_right(&mut self, _: &MoveRight, cx: &mut ViewContext<Self>) {
+        let right = self.right_movement(cx);
         self.change_selections(Some(Autoscroll::fit()), cx, |s| {
             let line_mode = s.line_mode;
             s.move_with(|map, selection| {
                 let cursor = if selection.is_empty() && !line_mode {
-                    movement::right(map, selection.end)
+                    right(map, selection.end)
                 } else {
                     selection.end
                 };
@@ -5295,11 +5979,29 @@ impl Editor {
     }
 
     pub fn select_right(&mut self, _: &SelectRight, cx: &mut ViewContext<Self>) {
+        let right = self.right_movement(cx);
         self.change_selections(Some(Autoscroll::fit()), cx, |s| {
-            s.move_heads_with(|map, head, _| (movement::right(map, head), SelectionGoal::None));
+            s.move_heads_with(|map, head, _| (right(map, head), SelectionGoal::None));
         })
     }
 
+    fn left_movement(&self, cx: &AppContext) -> fn(&DisplaySnapshot, DisplayPoint) -> DisplayPoint {
+        match EditorSettings::get_global(cx).cursor_movement {
+            CursorMovement::Logical => movement::left,
+            CursorMovement::Visual => movement::visual_left,
+        }
+    }
+
+    fn right_movement(
+        &self,
+        cx: &AppContext,
+    ) -> fn(&DisplaySnapshot, DisplayPoint) -> DisplayPoint {
+        match EditorSettings::get_global(cx).cursor_movement {
+            CursorMovement::Logical => movement::right,
+            CursorMovement::Visual => movement::visual_right,
+        }
+    }
+
     pub fn move_up(&mut self, _: &MoveUp, cx: &mut ViewContext<Self>) {
         if self.take_rename(true, cx).is_some() {
             return;
@@ -5766,6 +6468,341 @@ impl Editor {
         });
     }
 
+    /// Kills from each cursor to the end of its line, or the line break when the cursor is
+    /// already there, or else the selected text. Consecutive kills are joined into one.
+    pub fn kill_ring_cut(&mut self, _: &KillRingCut, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+        self.selection_mark_mode = false;
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let mut selections = self.selections.all::<Point>(cx);
+        let append = match &self.kill_ring_state {
+            Some(KillRingState::Killed {
+                edit_count,
+                cursors,
+            }) => {
+                *edit_count == buffer.edit_count()
+                    && cursors.len() == selections.len()
+                    && cursors.iter().zip(&selections).all(|(cursor, selection)| {
+                        selection.is_empty() && *cursor == selection.head().to_offset(&buffer)
+                    })
+            }
+            _ => false,
+        };
+
+        let mut text = String::new();
+        for (ix, selection) in selections.iter_mut().enumerate() {
+            if selection.is_empty() {
+                let row = selection.head().row;
+                let line_end = Point::new(row, buffer.line_len(row));
+                selection.end = if selection.head() == line_end {
+                    cmp::min(buffer.max_point(), Point::new(row + 1, 0))
+                } else {
+                    line_end
+                };
+                selection.goal = SelectionGoal::None;
+            }
+            if ix > 0 {
+                text.push('\n');
+            }
+            text.extend(buffer.text_for_range(selection.start..selection.end));
+        }
+
+        self.transact(cx, |this, cx| {
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                s.select(selections);
+            });
+            this.insert("", cx);
+        });
+
+        let kill_ring = cx.default_global::<KillRing>();
+        if append {
+            kill_ring.append(&text);
+        } else {
+            kill_ring.push(text);
+        }
+        if let Some(latest) = kill_ring.latest() {
+            let latest = latest.to_string();
+            cx.write_to_clipboard(ClipboardItem::new(latest));
+        }
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        self.kill_ring_state = Some(KillRingState::Killed {
+            edit_count: buffer.edit_count(),
+            cursors: self
+                .selections
+                .all::<usize>(cx)
+                .iter()
+                .map(|selection| selection.head())
+                .collect(),
+        });
+    }
+
+    /// Inserts the most recent kill at each cursor. Text copied or cut in the meantime,
+    /// here or in other applications, counts as the most recent kill.
+    pub fn kill_ring_yank(&mut self, _: &KillRingYank, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        if let Some(item) = cx.read_from_clipboard() {
+            let kill_ring = cx.default_global::<KillRing>();
+            if kill_ring.latest() != Some(item.text().as_str()) {
+                kill_ring.push(item.text().clone());
+            }
+        }
+        self.yank_kill(0, cx);
+    }
+
+    /// Replaces the text just yanked with the kill before it.
+    pub fn kill_ring_yank_pop(&mut self, _: &KillRingYankPop, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+
+        let edit_count = self.buffer.read(cx).snapshot(cx).edit_count();
+        match self.kill_ring_state.take() {
+            Some(KillRingState::Yanked {
+                edit_count: yank_edit_count,
+                ranges,
+                index,
+            }) if yank_edit_count == edit_count => {
+                self.change_selections(None, cx, |s| s.select_anchor_ranges(ranges));
+                self.yank_kill(index + 1, cx);
+            }
+            state => self.kill_ring_state = state,
+        }
+    }
+
+    fn yank_kill(&mut self, index: usize, cx: &mut ViewContext<Self>) {
+        let Some(text) = cx
+            .try_global::<KillRing>()
+            .and_then(|kill_ring| kill_ring.get(index))
+            .map(ToString::to_string)
+        else {
+            return;
+        };
+        self.selection_mark_mode = false;
+
+        let starts = {
+            let buffer = self.buffer.read(cx).snapshot(cx);
+            self.selections
+                .all::<usize>(cx)
+                .iter()
+                .map(|selection| buffer.anchor_before(selection.start))
+                .collect::<Vec<_>>()
+        };
+        self.transact(cx, |this, cx| this.insert(&text, cx));
+
+        let buffer = self.buffer.read(cx).snapshot(cx);
+        let ranges = starts
+            .into_iter()
+            .zip(self.selections.all::<usize>(cx))
+            .map(|(start, selection)| start..buffer.anchor_after(selection.head()))
+            .collect();
+        self.kill_ring_state = Some(KillRingState::Yanked {
+            edit_count: buffer.edit_count(),
+            ranges,
+            index,
+        });
+    }
+
+    /// Starts extending the selections from the cursors, until the next edit or cancel.
+    pub fn set_mark(&mut self, _: &SetMark, cx: &mut ViewContext<Self>) {
+        self.change_selections(None, cx, |s| {
+            s.move_with(|_, selection| {
+                selection.collapse_to(selection.head(), SelectionGoal::None)
+            });
+        });
+        self.selection_mark_mode = true;
+        cx.notify();
+    }
+
+    pub fn swap_selection_ends(&mut self, _: &SwapSelectionEnds, cx: &mut ViewContext<Self>) {
+        self.change_selections(Some(Autoscroll::fit()), cx, |s| {
+            s.move_with(|_, selection| {
+                selection.reversed = !selection.reversed;
+            });
+        });
+    }
+
+    /// Waits for the next typed character, then surrounds each selection with the pair that the
+    /// character opens or closes. Empty selections surround the word containing the cursor.
+    pub fn surround_selections(&mut self, _: &SurroundSelections, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+        self.pending_surround = Some(PendingSurround::Add);
+        cx.notify();
+    }
+
+    /// Waits for the next typed character, then replaces the innermost pair of brackets
+    /// enclosing each selection with the pair that the character opens or closes.
+    pub fn change_surrounding_pair(
+        &mut self,
+        _: &ChangeSurroundingPair,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.read_only(cx) {
+            return;
+        }
+        self.pending_surround = Some(PendingSurround::Change);
+        cx.notify();
+    }
+
+    fn surround_with_pair(
+        &mut self,
+        pending_surround: PendingSurround,
+        text: &str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let selections = self.selections.all::<usize>(cx);
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+
+        // Each edit is paired with whether it inserts an opening bracket, so that the selections
+        // can be placed between the brackets once the edits are applied.
+        let mut edits: Vec<(Range<usize>, Arc<str>, bool)> = Vec::new();
+        for selection in &selections {
+            let (open, close) = surround_pair(snapshot.language_scope_at(selection.head()), text);
+            match pending_surround {
+                PendingSurround::Add => {
+                    let range = if selection.is_empty() {
+                        let (word_range, kind) = snapshot.surrounding_word(selection.head());
+                        if kind != Some(CharKind::Word) {
+                            continue;
+                        }
+                        word_range
+                    } else {
+                        selection.range()
+                    };
+                    edits.push((range.start..range.start, open, true));
+                    edits.push((range.end..range.end, close, false));
+                }
+                PendingSurround::Change => {
+                    if let Some((open_range, close_range)) =
+                        snapshot.innermost_enclosing_bracket_ranges(selection.range())
+                    {
+                        edits.push((open_range, open, true));
+                        edits.push((close_range, close, false));
+                    }
+                }
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+        // Selections within the same pair of brackets change it only once.
+        edits.sort_by_key(|(range, _, _)| (range.start, range.end));
+        edits.dedup_by(|(a, _, a_is_open), (b, _, b_is_open)| a == b && a_is_open == b_is_open);
+
+        let adjusted_offset = |offset: usize| {
+            edits
+                .iter()
+                .filter(|(range, _, is_open)| {
+                    if *is_open {
+                        range.end <= offset
+                    } else {
+                        range.start < offset
+                    }
+                })
+                .fold(offset as isize, |offset, (range, new_text, _)| {
+                    offset + new_text.len() as isize - range.len() as isize
+                }) as usize
+        };
+        let new_selections = selections
+            .into_iter()
+            .map(|selection| {
+                let start = adjusted_offset(selection.start);
+                let end = adjusted_offset(selection.end);
+                Selection {
+                    start,
+                    end,
+                    ..selection
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.transact(cx, |this, cx| {
+            this.buffer.update(cx, |buffer, cx| {
+                buffer.edit(
+                    edits
+                        .into_iter()
+                        .map(|(range, new_text, _)| (range, new_text)),
+                    None,
+                    cx,
+                );
+            });
+            this.change_selections(Some(Autoscroll::fit()), cx, |s| s.select(new_selections));
+        });
+    }
+
+    /// Pads the selected lines so that the delimiter lines up vertically. Without a delimiter,
+    /// waits for the next typed character and aligns on it.
+    pub fn align_on_delimiter(&mut self, action: &AlignOnDelimiter, cx: &mut ViewContext<Self>) {
+        if self.read_only(cx) {
+            return;
+        }
+        let delimiter = match &action.delimiter {
+            None => {
+                self.pending_alignment = true;
+                cx.notify();
+                return;
+            }
+            Some(pattern) if action.regex => match Regex::new(pattern) {
+                Ok(regex) => AlignmentDelimiter::Regex(regex),
+                Err(error) => {
+                    log::error!("invalid alignment pattern {pattern:?}: {error}");
+                    return;
+                }
+            },
+            Some(text) => AlignmentDelimiter::Text(text.clone()),
+        };
+        self.align_lines(&delimiter, cx);
+    }
+
+    fn align_lines(&mut self, delimiter: &AlignmentDelimiter, cx: &mut ViewContext<Self>) {
+        let display_map = self.display_map.update(cx, |map, cx| map.snapshot(cx));
+        let buffer = self.buffer.read(cx).snapshot(cx);
+
+        let mut edits = Vec::new();
+        let selections = self.selections.all::<Point>(cx);
+        let mut selections = selections.iter().peekable();
+        let mut contiguous_row_selections = Vec::new();
+        while let Some(selection) = selections.next() {
+            let (start_row, end_row) = consume_contiguous_rows(
+                &mut contiguous_row_selections,
+                selection,
+                &display_map,
+                &mut selections,
+            );
+            let lines = (start_row..end_row)
+                .map(|row| {
+                    buffer
+                        .text_for_range(Point::new(row, 0)..Point::new(row, buffer.line_len(row)))
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>();
+            for (line_ix, range, new_text) in alignment_edits(&lines, delimiter) {
+                let row = start_row + line_ix as u32;
+                edits.push((
+                    Point::new(row, range.start as u32)..Point::new(row, range.end as u32),
+                    new_text,
+                ));
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+
+        self.transact(cx, |this, cx| {
+            this.buffer
+                .update(cx, |buffer, cx| buffer.edit(edits, None, cx));
+            this.request_autoscroll(Autoscroll::fit(), cx);
+        });
+    }
+
     pub fn move_to_start_of_paragraph(
         &mut self,
         _: &MoveToStartOfParagraph,
@@ -5918,6 +6955,24 @@ impl Editor {
         }
     }
 
+    fn push_edit_to_nav_history(&mut self, cx: &mut ViewContext<Self>) {
+        if let Some(nav_history) = self.nav_history.as_mut() {
+            let buffer = self.buffer.read(cx).read(cx);
+            let cursor_anchor = self.selections.newest_anchor().head();
+            let cursor_position = cursor_anchor.to_point(&buffer);
+            let scroll_state = self.scroll_manager.anchor();
+            let scroll_top_row = scroll_state.top_row(&buffer);
+            drop(buffer);
+
+            nav_history.push_edit(Some(NavigationData {
+                cursor_anchor,
+                cursor_position,
+                scroll_anchor: scroll_state,
+                scroll_top_row,
+            }));
+        }
+    }
+
     pub fn select_to_end(&mut self, _: &SelectToEnd, cx: &mut ViewContext<Self>) {
         let buffer = self.buffer.read(cx).snapshot(cx);
         let mut selection = self.selections.first::<usize>(cx);
@@ -6556,19 +7611,27 @@ impl Editor {
                 }
             }
 
-            // TODO: Handle selections that cross excerpts
-            for selection in &mut selections {
-                let start_column = snapshot.indent_size_for_line(selection.start.row).len;
-                let language = if let Some(language) =
-                    snapshot.language_scope_at(Point::new(selection.start.row, start_column))
-                {
-                    language
-                } else {
-                    continue;
-                };
+            fn language_scope_for_row(
+                snapshot: &MultiBufferSnapshot,
+                row: u32,
+            ) -> Option<LanguageScope> {
+                let start_column = snapshot.indent_size_for_line(row).len;
+                snapshot.language_scope_at(Point::new(row, start_column))
+            }
 
-                selection_edit_ranges.clear();
+            fn comment_delimiters(
+                language: &LanguageScope,
+            ) -> (Option<&Arc<str>>, Option<(&Arc<str>, &Arc<str>)>) {
+                (
+                    language
+                        .line_comment_prefixes()
+                        .and_then(|prefixes| prefixes.first()),
+                    language.block_comment_delimiters(),
+                )
+            }
 
+            // TODO: Handle selections that cross excerpts
+            for selection in &mut selections {
                 // If multiple selections contain a given row, avoid processing that
                 // row more than once.
                 let mut start_row = selection.start.row;
@@ -6587,83 +7650,123 @@ impl Editor {
                     continue;
                 }
 
-                // If the language has line comments, toggle those.
-                if let Some(full_comment_prefix) = language
-                    .line_comment_prefixes()
-                    .and_then(|prefixes| prefixes.first())
-                {
-                    // Split the comment prefix's trailing whitespace into a separate string,
-                    // as that portion won't be used for detecting if a line is a comment.
-                    let comment_prefix = full_comment_prefix.trim_end_matches(' ');
-                    let comment_prefix_whitespace = &full_comment_prefix[comment_prefix.len()..];
-                    let mut all_selection_lines_are_comments = true;
-
-                    for row in start_row..=end_row {
-                        if start_row < end_row && snapshot.is_line_blank(row) {
-                            continue;
+                // Rows may belong to different languages, such as a script within an HTML
+                // document, so split the selection into runs of rows that share a comment
+                // style. Blank rows are toggled along with the run that they're part of.
+                let mut row_groups: Vec<(u32, u32, LanguageScope)> = Vec::new();
+                for row in start_row..=end_row {
+                    if snapshot.is_line_blank(row) {
+                        continue;
+                    }
+                    let Some(language) = language_scope_for_row(snapshot.deref(), row) else {
+                        continue;
+                    };
+                    match row_groups.last_mut() {
+                        Some((_, group_end_row, group_language))
+                            if comment_delimiters(group_language)
+                                == comment_delimiters(&language) =>
+                        {
+                            *group_end_row = row;
+                        }
+                        Some((_, group_end_row, _)) => {
+                            let group_start_row = *group_end_row + 1;
+                            row_groups.push((group_start_row, row, language));
+                        }
+                        None => row_groups.push((start_row, row, language)),
+                    }
+                }
+                if let Some((_, group_end_row, _)) = row_groups.last_mut() {
+                    *group_end_row = end_row;
+                } else if let Some(language) = language_scope_for_row(snapshot.deref(), start_row) {
+                    row_groups.push((start_row, end_row, language));
+                }
+
+                let selection_start_row = start_row;
+                let selection_end_row = end_row;
+                for (start_row, end_row, language) in row_groups {
+                    selection_edit_ranges.clear();
+
+                    // If the language has line comments, toggle those.
+                    if let Some(full_comment_prefix) = language
+                        .line_comment_prefixes()
+                        .and_then(|prefixes| prefixes.first())
+                    {
+                        // Split the comment prefix's trailing whitespace into a separate string,
+                        // as that portion won't be used for detecting if a line is a comment.
+                        let comment_prefix = full_comment_prefix.trim_end_matches(' ');
+                        let comment_prefix_whitespace =
+                            &full_comment_prefix[comment_prefix.len()..];
+                        let mut all_selection_lines_are_comments = true;
+
+                        for row in start_row..=end_row {
+                            if selection_start_row < selection_end_row
+                                && snapshot.is_line_blank(row)
+                            {
+                                continue;
+                            }
+
+                            let prefix_range = comment_prefix_range(
+                                snapshot.deref(),
+                                row,
+                                comment_prefix,
+                                comment_prefix_whitespace,
+                            );
+                            if prefix_range.is_empty() {
+                                all_selection_lines_are_comments = false;
+                            }
+                            selection_edit_ranges.push(prefix_range);
                         }
 
+                        if all_selection_lines_are_comments {
+                            edits.extend(
+                                selection_edit_ranges
+                                    .iter()
+                                    .cloned()
+                                    .map(|range| (range, empty_str.clone())),
+                            );
+                        } else {
+                            let min_column = selection_edit_ranges
+                                .iter()
+                                .map(|r| r.start.column)
+                                .min()
+                                .unwrap_or(0);
+                            edits.extend(selection_edit_ranges.iter().map(|range| {
+                                let position = Point::new(range.start.row, min_column);
+                                (position..position, full_comment_prefix.clone())
+                            }));
+                        }
+                    } else if let Some((full_comment_prefix, comment_suffix)) =
+                        language.block_comment_delimiters()
+                    {
+                        let comment_prefix = full_comment_prefix.trim_end_matches(' ');
+                        let comment_prefix_whitespace =
+                            &full_comment_prefix[comment_prefix.len()..];
                         let prefix_range = comment_prefix_range(
                             snapshot.deref(),
-                            row,
+                            start_row,
                             comment_prefix,
                             comment_prefix_whitespace,
                         );
-                        if prefix_range.is_empty() {
-                            all_selection_lines_are_comments = false;
-                        }
-                        selection_edit_ranges.push(prefix_range);
-                    }
-
-                    if all_selection_lines_are_comments {
-                        edits.extend(
-                            selection_edit_ranges
-                                .iter()
-                                .cloned()
-                                .map(|range| (range, empty_str.clone())),
+                        let suffix_range = comment_suffix_range(
+                            snapshot.deref(),
+                            end_row,
+                            comment_suffix.trim_start_matches(' '),
+                            comment_suffix.starts_with(' '),
                         );
-                    } else {
-                        let min_column = selection_edit_ranges
-                            .iter()
-                            .map(|r| r.start.column)
-                            .min()
-                            .unwrap_or(0);
-                        edits.extend(selection_edit_ranges.iter().map(|range| {
-                            let position = Point::new(range.start.row, min_column);
-                            (position..position, full_comment_prefix.clone())
-                        }));
-                    }
-                } else if let Some((full_comment_prefix, comment_suffix)) =
-                    language.block_comment_delimiters()
-                {
-                    let comment_prefix = full_comment_prefix.trim_end_matches(' ');
-                    let comment_prefix_whitespace = &full_comment_prefix[comment_prefix.len()..];
-                    let prefix_range = comment_prefix_range(
-                        snapshot.deref(),
-                        start_row,
-                        comment_prefix,
-                        comment_prefix_whitespace,
-                    );
-                    let suffix_range = comment_suffix_range(
-                        snapshot.deref(),
-                        end_row,
-                        comment_suffix.trim_start_matches(' '),
-                        comment_suffix.starts_with(' '),
-                    );
 
-                    if prefix_range.is_empty() || suffix_range.is_empty() {
-                        edits.push((
-                            prefix_range.start..prefix_range.start,
-                            full_comment_prefix.clone(),
-                        ));
-                        edits.push((suffix_range.end..suffix_range.end, comment_suffix.clone()));
-                        suffixes_inserted.push((end_row, comment_suffix.len()));
-                    } else {
-                        edits.push((prefix_range, empty_str.clone()));
-                        edits.push((suffix_range, empty_str.clone()));
+                        if prefix_range.is_empty() || suffix_range.is_empty() {
+                            edits.push((
+                                prefix_range.start..prefix_range.start,
+                                full_comment_prefix.clone(),
+                            ));
+                            edits
+                                .push((suffix_range.end..suffix_range.end, comment_suffix.clone()));
+                            suffixes_inserted.push((end_row, comment_suffix.len()));
+                        } else {
+                            edits.push((prefix_range, empty_str.clone()));
+                            edits.push((suffix_range, empty_str.clone()));
+                        }
                     }
-                } else {
-                    continue;
                 }
             }
 
@@ -7061,10 +8164,100 @@ impl Editor {
                 s.select_display_ranges([point..point]);
             });
 
-            true
-        } else {
-            false
-        }
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn toggle_bookmark(&mut self, _: &ToggleBookmark, cx: &mut ViewContext<Self>) {
+        let Some(bookmark_store) = self.bookmark_store.clone() else {
+            return;
+        };
+        if let Some((buffer, row)) = self.bookmark_position(cx) {
+            bookmark_store.update(cx, |store, cx| store.toggle(&buffer, row, cx));
+        }
+    }
+
+    fn go_to_next_bookmark(&mut self, _: &GoToNextBookmark, cx: &mut ViewContext<Self>) {
+        self.go_to_bookmark(Direction::Next, cx);
+    }
+
+    fn go_to_prev_bookmark(&mut self, _: &GoToPrevBookmark, cx: &mut ViewContext<Self>) {
+        self.go_to_bookmark(Direction::Prev, cx);
+    }
+
+    fn go_to_bookmark(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        let (Some(bookmark_store), Some(workspace)) =
+            (self.bookmark_store.clone(), self.workspace())
+        else {
+            return;
+        };
+        // When the cursor isn't in a file, the empty path sorts before all bookmarks, so that
+        // the first or last bookmark is picked.
+        let (path, row) = self
+            .bookmark_position(cx)
+            .and_then(|(buffer, row)| {
+                let file = buffer.read(cx).file()?.as_local()?;
+                Some((file.abs_path(cx), row))
+            })
+            .unwrap_or_default();
+        let store = bookmark_store.read(cx);
+        let bookmark = match direction {
+            Direction::Next => store.next(&path, row),
+            Direction::Prev => store.prev(&path, row),
+        };
+        let Some((path, row)) = bookmark.map(|bookmark| (bookmark.path.clone(), bookmark.row()))
+        else {
+            return;
+        };
+
+        // Defer the navigation, as opening the file may need to update this editor.
+        cx.window_context().defer(move |cx| {
+            workspace.update(cx, |workspace, cx| {
+                open_bookmark(workspace, path, row, cx).detach_and_log_err(cx);
+            });
+        });
+    }
+
+    /// The bookmarks of the workspace containing this editor.
+    pub fn bookmark_store(&self) -> Option<&Model<BookmarkStore>> {
+        self.bookmark_store.as_ref()
+    }
+
+    /// The buffer and row of the newest cursor, where bookmarks are toggled.
+    pub fn bookmark_position(&self, cx: &AppContext) -> Option<(Model<Buffer>, u32)> {
+        let head = self.selections.newest_anchor().head();
+        let (buffer, offset, _) = self.buffer.read(cx).point_to_buffer_offset(head, cx)?;
+        let row = buffer.read(cx).offset_to_point(offset).row;
+        Some((buffer, row))
+    }
+
+    /// The bookmarked rows of this editor's file among the given display rows.
+    fn bookmark_display_rows(
+        &self,
+        snapshot: &DisplaySnapshot,
+        display_rows: Range<u32>,
+        cx: &mut ViewContext<Self>,
+    ) -> Vec<u32> {
+        let (Some(bookmark_store), Some(buffer)) = (
+            self.bookmark_store.as_ref(),
+            self.buffer.read(cx).as_singleton(),
+        ) else {
+            return Vec::new();
+        };
+        bookmark_store
+            .update(cx, |store, cx| store.rows_for_buffer(&buffer, cx))
+            .into_iter()
+            .map(|row| {
+                let point = snapshot
+                    .buffer_snapshot
+                    .clip_point(Point::new(row, 0), Bias::Left);
+                point.to_display_point(snapshot).row()
+            })
+            .filter(|row| display_rows.contains(row))
+            .dedup()
+            .collect()
     }
 
     pub fn go_to_definition(&mut self, _: &GoToDefinition, cx: &mut ViewContext<Self>) {
@@ -7342,17 +8535,46 @@ impl Editor {
     /// Opens a multibuffer with the given project locations in it
     pub fn open_locations_in_multibuffer(
         workspace: &mut Workspace,
-        mut locations: Vec<Location>,
+        locations: Vec<Location>,
         replica_id: ReplicaId,
         title: String,
         split: bool,
         cx: &mut ViewContext<Workspace>,
     ) {
         // If there are multiple definitions, open them in a multibuffer
+        let capability = workspace.project().read(cx).capability();
+        let (excerpt_buffer, ranges_to_highlight) =
+            Self::multibuffer_for_locations(locations, replica_id, capability, title, cx);
+
+        let editor = cx.new_view(|cx| {
+            Editor::for_multibuffer(excerpt_buffer, Some(workspace.project().clone()), cx)
+        });
+        editor.update(cx, |editor, cx| {
+            editor.highlight_background::<Self>(
+                ranges_to_highlight,
+                |theme| theme.editor_highlighted_line_background,
+                cx,
+            );
+        });
+        if split {
+            workspace.split_item(SplitDirection::Right, Box::new(editor), cx);
+        } else {
+            workspace.add_item(Box::new(editor), cx);
+        }
+    }
+
+    /// Builds a multibuffer with an excerpt around each of the given locations, returning it
+    /// along with the ranges of the locations within it.
+    pub(crate) fn multibuffer_for_locations(
+        mut locations: Vec<Location>,
+        replica_id: ReplicaId,
+        capability: Capability,
+        title: String,
+        cx: &mut AppContext,
+    ) -> (Model<MultiBuffer>, Vec<Range<Anchor>>) {
         locations.sort_by_key(|location| location.buffer.read(cx).remote_id());
         let mut locations = locations.into_iter().peekable();
         let mut ranges_to_highlight = Vec::new();
-        let capability = workspace.project().read(cx).capability();
 
         let excerpt_buffer = cx.new_model(|cx| {
             let mut multibuffer = MultiBuffer::new(replica_id, capability);
@@ -7382,22 +8604,7 @@ impl Editor {
 
             multibuffer.with_title(title)
         });
-
-        let editor = cx.new_view(|cx| {
-            Editor::for_multibuffer(excerpt_buffer, Some(workspace.project().clone()), cx)
-        });
-        editor.update(cx, |editor, cx| {
-            editor.highlight_background::<Self>(
-                ranges_to_highlight,
-                |theme| theme.editor_highlighted_line_background,
-                cx,
-            );
-        });
-        if split {
-            workspace.split_item(SplitDirection::Right, Box::new(editor), cx);
-        } else {
-            workspace.add_item(Box::new(editor), cx);
-        }
+        (excerpt_buffer, ranges_to_highlight)
     }
 
     pub fn rename(&mut self, _: &Rename, cx: &mut ViewContext<Self>) -> Option<Task<Result<()>>> {
@@ -7867,6 +9074,7 @@ impl Editor {
                 log::error!("unexpectedly ended a transaction that wasn't started by this editor");
             }
 
+            self.push_edit_to_nav_history(cx);
             cx.emit(EditorEvent::Edited);
             Some(tx_id)
         } else {
@@ -8055,6 +9263,19 @@ impl Editor {
         }
     }
 
+    pub fn resize_blocks(
+        &mut self,
+        heights: HashMap<BlockId, u8>,
+        autoscroll: Option<Autoscroll>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.display_map
+            .update(cx, |display_map, cx| display_map.resize_blocks(heights, cx));
+        if let Some(autoscroll) = autoscroll {
+            self.request_autoscroll(autoscroll, cx);
+        }
+    }
+
     pub fn longest_row(&self, cx: &mut AppContext) -> u32 {
         self.display_map
             .update(cx, |map, cx| map.snapshot(cx))
@@ -8176,6 +9397,28 @@ impl Editor {
         cx.notify();
     }
 
+    pub fn make_read_only(&mut self, _: &MakeReadOnly, cx: &mut ViewContext<Self>) {
+        self.set_local_buffers_capability(Capability::ReadOnly, cx);
+    }
+
+    pub fn make_writable(&mut self, _: &MakeWritable, cx: &mut ViewContext<Self>) {
+        self.set_local_buffers_capability(Capability::ReadWrite, cx);
+    }
+
+    /// Locks or unlocks the buffers of files on this machine. Buffers shared by a
+    /// collaborator keep the capability the host gave them.
+    fn set_local_buffers_capability(&mut self, capability: Capability, cx: &mut ViewContext<Self>) {
+        for buffer in self.buffer.read(cx).all_buffers() {
+            let is_local = buffer
+                .read(cx)
+                .file()
+                .map_or(true, |file| file.as_local().is_some());
+            if is_local && buffer.read(cx).capability() != capability {
+                buffer.update(cx, |buffer, cx| buffer.set_capability(capability, cx));
+            }
+        }
+    }
+
     pub fn set_show_gutter(&mut self, show_gutter: bool, cx: &mut ViewContext<Self>) {
         self.show_gutter = show_gutter;
         cx.notify();
@@ -8353,79 +9596,67 @@ impl Editor {
         results
     }
 
-    pub fn background_highlight_row_ranges<T: 'static>(
+    /// Returns the whitespace at the end of the lines in the given display
+    /// rows, skipping the lines with a cursor, where it's likely still being
+    /// typed.
+    pub fn trailing_whitespace_in_range(
         &self,
-        search_range: Range<Anchor>,
+        display_rows: Range<u32>,
         display_snapshot: &DisplaySnapshot,
-        count: usize,
-    ) -> Vec<RangeInclusive<DisplayPoint>> {
-        let mut results = Vec::new();
-        let Some((_, ranges)) = self.background_highlights.get(&TypeId::of::<T>()) else {
-            return vec![];
-        };
+        cx: &AppContext,
+    ) -> Vec<Range<DisplayPoint>> {
+        let buffer = &display_snapshot.buffer_snapshot;
+        let max_row = display_snapshot.max_point().row();
+        let start_row = DisplayPoint::new(display_rows.start.min(max_row), 0)
+            .to_point(display_snapshot)
+            .row;
+        let end_row = DisplayPoint::new(display_rows.end.min(max_row), 0)
+            .to_point(display_snapshot)
+            .row;
+        let cursor_rows = self
+            .selections
+            .all::<Point>(cx)
+            .iter()
+            .map(|selection| selection.head().row)
+            .collect::<HashSet<_>>();
 
-        let start_ix = match ranges.binary_search_by(|probe| {
-            let cmp = probe
-                .end
-                .cmp(&search_range.start, &display_snapshot.buffer_snapshot);
-            if cmp.is_gt() {
-                Ordering::Greater
-            } else {
-                Ordering::Less
-            }
-        }) {
-            Ok(i) | Err(i) => i,
-        };
-        let mut push_region = |start: Option<Point>, end: Option<Point>| {
-            if let (Some(start_display), Some(end_display)) = (start, end) {
-                results.push(
-                    start_display.to_display_point(display_snapshot)
-                        ..=end_display.to_display_point(display_snapshot),
-                );
-            }
-        };
-        let mut start_row: Option<Point> = None;
-        let mut end_row: Option<Point> = None;
-        if ranges.len() > count {
-            return Vec::new();
-        }
-        for range in &ranges[start_ix..] {
-            if range
-                .start
-                .cmp(&search_range.end, &display_snapshot.buffer_snapshot)
-                .is_ge()
-            {
-                break;
-            }
-            let end = range.end.to_point(&display_snapshot.buffer_snapshot);
-            if let Some(current_row) = &end_row {
-                if end.row == current_row.row {
-                    continue;
-                }
-            }
-            let start = range.start.to_point(&display_snapshot.buffer_snapshot);
-            if start_row.is_none() {
-                assert_eq!(end_row, None);
-                start_row = Some(start);
-                end_row = Some(end);
+        let mut ranges = Vec::new();
+        for row in start_row..=end_row {
+            if cursor_rows.contains(&row) {
                 continue;
             }
-            if let Some(current_end) = end_row.as_mut() {
-                if start.row > current_end.row + 1 {
-                    push_region(start_row, end_row);
-                    start_row = Some(start);
-                    end_row = Some(end);
-                } else {
-                    // Merge two hunks.
-                    *current_end = end;
-                }
-            } else {
-                unreachable!();
+            let line_len = buffer.line_len(row);
+            let whitespace_len = buffer
+                .reversed_chars_at(Point::new(row, line_len))
+                .take_while(|c| *c == ' ' || *c == '\t')
+                .count() as u32;
+            if whitespace_len > 0 {
+                let start = Point::new(row, line_len - whitespace_len);
+                let end = Point::new(row, line_len);
+                ranges.push(
+                    start.to_display_point(display_snapshot)
+                        ..end.to_display_point(display_snapshot),
+                );
             }
         }
-        // We might still have a hunk that was not rendered (if there was a search hit on the last line)
-        push_region(start_row, end_row);
-        results
+        ranges
+    }
+
+    /// Returns whether any of the background highlights of type `T` intersect the given range.
+    pub fn has_background_highlights_in_range<T: 'static>(
+        &self,
+        search_range: Range<Anchor>,
+        buffer: &MultiBufferSnapshot,
+    ) -> bool {
+        let Some((_, ranges)) = self.background_highlights.get(&TypeId::of::<T>()) else {
+            return false;
+        };
+
+        let start_ix =
+            ranges.partition_point(|probe| probe.end.cmp(&search_range.start, buffer).is_le());
+        ranges.get(start_ix).map_or(false, |range| {
+            range.start.cmp(&search_range.end, buffer).is_lt()
+        })
     }
 
     pub fn highlight_text<T: 'static>(
@@ -8489,6 +9720,8 @@ impl Editor {
             } => {
                 self.refresh_active_diagnostics(cx);
                 self.refresh_code_actions(cx);
+                refresh_colorized_brackets(self, cx);
+                refresh_bracket_scope_guide(self, cx);
                 if self.has_active_copilot_suggestion(cx) {
                     self.update_visible_copilot_suggestion(cx);
                 }
@@ -8539,12 +9772,17 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                refresh_colorized_brackets(self, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
                 cx.emit(EditorEvent::ExcerptsRemoved { ids: ids.clone() })
             }
-            multi_buffer::Event::Reparsed => cx.emit(EditorEvent::Reparsed),
+            multi_buffer::Event::Reparsed => {
+                refresh_colorized_brackets(self, cx);
+                refresh_bracket_scope_guide(self, cx);
+                cx.emit(EditorEvent::Reparsed)
+            }
             multi_buffer::Event::DirtyChanged => cx.emit(EditorEvent::DirtyChanged),
             multi_buffer::Event::Saved => cx.emit(EditorEvent::Saved),
             multi_buffer::Event::FileHandleChanged | multi_buffer::Event::Reloaded => {
@@ -8552,6 +9790,7 @@ impl Editor {
             }
             multi_buffer::Event::DiffBaseChanged => cx.emit(EditorEvent::DiffBaseChanged),
             multi_buffer::Event::Closed => cx.emit(EditorEvent::Closed),
+            multi_buffer::Event::CapabilityChanged => cx.notify(),
             multi_buffer::Event::DiagnosticsUpdated => {
                 self.refresh_active_diagnostics(cx);
             }
@@ -8565,6 +9804,9 @@ impl Editor {
 
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         self.refresh_copilot_suggestions(true, cx);
+        let wrap_indent = EditorSettings::get_global(cx).soft_wrap_indent;
+        self.display_map
+            .update(cx, |map, cx| map.set_wrap_indent(wrap_indent, cx));
         self.refresh_inlay_hints(
             InlayHintRefreshReason::SettingsChange(inlay_hint_settings(
                 self.selections.newest_anchor().head(),
@@ -8573,6 +9815,8 @@ impl Editor {
             )),
             cx,
         );
+        refresh_colorized_brackets(self, cx);
+        refresh_bracket_scope_guide(self, cx);
         cx.notify();
     }
 
@@ -9051,8 +10295,42 @@ impl CompletionProvider for Model<Project> {
         buffer_position: text::Anchor,
         cx: &mut ViewContext<Editor>,
     ) -> Task<Result<Vec<Completion>>> {
-        self.update(cx, |project, cx| {
+        let completions = self.update(cx, |project, cx| {
             project.completions(&buffer, buffer_position, cx)
+        });
+        let emmet_completion = emmet::emmet_completion(buffer, buffer_position, cx);
+
+        // Fall back to completing words for buffers without a language server. Guests can't tell
+        // whether the host runs one, so they fall back when no completions are returned.
+        let use_word_completions = {
+            let buffer = buffer.read(cx);
+            let settings = language_settings::language_settings(
+                buffer.language_at(buffer_position).as_ref(),
+                buffer.file(),
+                cx,
+            );
+            settings.word_completions
+                && self
+                    .read(cx)
+                    .language_servers_for_buffer(buffer, cx)
+                    .next()
+                    .is_none()
+        };
+        if !use_word_completions && emmet_completion.is_none() {
+            return completions;
+        }
+
+        let word_completions = use_word_completions
+            .then(|| word_completions::word_completions(self, buffer, buffer_position, cx));
+        cx.background_executor().spawn(async move {
+            let mut completions = completions.await?;
+            if completions.is_empty() {
+                if let Some(word_completions) = word_completions {
+                    completions = word_completions.await;
+                }
+            }
+            completions.splice(0..0, emmet_completion);
+            Ok(completions)
         })
     }
 
@@ -9074,6 +10352,11 @@ impl CompletionProvider for Model<Project> {
         push_to_history: bool,
         cx: &mut ViewContext<Editor>,
     ) -> Task<Result<Option<language::Transaction>>> {
+        if completion.server_id == word_completions::WORD_COMPLETIONS_SERVER_ID
+            || completion.server_id == emmet::EMMET_SERVER_ID
+        {
+            return Task::ready(Ok(None));
+        }
         self.update(cx, |project, cx| {
             project.apply_additional_edits_for_completion(buffer, completion, push_to_history, cx)
         })
@@ -9093,6 +10376,358 @@ fn inlay_hint_settings(
         .inlay_hints
 }
 
+/// Returns the pair of brackets that the given text opens or closes, falling back to common
+/// brackets for buffers without a language, or to the text itself, such as `*` or `_`.
+fn surround_pair(language_scope: Option<LanguageScope>, text: &str) -> (Arc<str>, Arc<str>) {
+    if let Some(language_scope) = language_scope {
+        if let Some((pair, _)) = language_scope
+            .brackets()
+            .find(|(pair, _)| pair.start == text || pair.end == text)
+        {
+            return (pair.start.as_str().into(), pair.end.as_str().into());
+        }
+    }
+    let (open, close) = match text {
+        "(" | ")" => ("(", ")"),
+        "[" | "]" => ("[", "]"),
+        "{" | "}" => ("{", "}"),
+        "<" | ">" => ("<", ">"),
+        _ => (text, text),
+    };
+    (open.into(), close.into())
+}
+
+/// Returns the range to replace when joining the line after the given row onto it, and the
+/// text to replace it with. Along with the indentation of the next line, this removes any text
+/// matched by the language's join patterns, such as comment leaders within comments.
+fn join_lines_range(snapshot: &MultiBufferSnapshot, row: u32) -> (Range<Point>, &'static str) {
+    let line_len = snapshot.line_len(row);
+    let mut end_of_line = Point::new(row, line_len);
+    let indent = snapshot.indent_size_for_line(row + 1);
+    let mut start_of_next_line = Point::new(row + 1, indent.len);
+    let mut is_within_string = false;
+
+    if let Some(scope) = snapshot.language_scope_at(Point::new(row, line_len.saturating_sub(1))) {
+        let config = scope.join_lines_config();
+        let line_text = |row, start_column| {
+            snapshot
+                .text_for_range(
+                    Point::new(row, start_column)..Point::new(row, snapshot.line_len(row)),
+                )
+                .collect::<String>()
+        };
+        if let Some(pattern) = &config.line_end_pattern {
+            let line = line_text(row, 0);
+            if let Some(mat) = pattern.find_iter(&line).find(|mat| mat.end() == line.len()) {
+                is_within_string = scope.override_name() == Some("string");
+                let trimmed_len = if is_within_string {
+                    mat.start()
+                } else {
+                    line[..mat.start()].trim_end().len()
+                };
+                end_of_line.column = trimmed_len as u32;
+                if is_within_string && !config.string_continuation_skips_whitespace {
+                    start_of_next_line.column = 0;
+                }
+            }
+        }
+        if let Some(pattern) = config
+            .line_start_pattern
+            .as_ref()
+            .filter(|_| scope.override_name() == Some("comment"))
+        {
+            let next_line = line_text(row + 1, indent.len);
+            if let Some(mat) = pattern.find(&next_line).filter(|mat| mat.start() == 0) {
+                start_of_next_line.column += mat.end() as u32;
+            }
+        }
+    }
+
+    let replace = if is_within_string || start_of_next_line.column >= snapshot.line_len(row + 1) {
+        ""
+    } else {
+        " "
+    };
+    (end_of_line..start_of_next_line, replace)
+}
+
+/// Returns the kind of indentation to insert at the given point and its
+/// width in columns, which for tabs is the tab size from the settings.
+fn indent_unit_at(
+    snapshot: &MultiBufferSnapshot,
+    point: Point,
+    cx: &AppContext,
+) -> (IndentKind, u32) {
+    let tab_size = snapshot.settings_at(point, cx).tab_size.get();
+    match snapshot.language_indent_size_at(point, cx) {
+        Some(IndentSize {
+            kind: IndentKind::Space,
+            len,
+        }) => (IndentKind::Space, len),
+        Some(IndentSize {
+            kind: IndentKind::Tab,
+            ..
+        }) => (IndentKind::Tab, tab_size),
+        None => (IndentKind::Space, tab_size),
+    }
+}
+
+/// Returns the leader to insert after the indentation of a new line that is inserted at the given
+/// point, when that point is within a block comment that hasn't been closed yet on its line.
+/// On the line that opens the comment, the leader is aligned with the opening delimiter's text.
+fn block_comment_leader(
+    snapshot: &MultiBufferSnapshot,
+    point: Point,
+    continuation: &BlockCommentContinuation,
+) -> Option<Arc<str>> {
+    let line_text = |row| {
+        snapshot
+            .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+            .collect::<String>()
+    };
+    let marker = continuation.prefix.trim_end();
+    let line = line_text(point.row);
+    let text_before_cursor = line.get(..point.column as usize)?.trim_start();
+
+    if let Some(comment_text) = text_before_cursor.strip_prefix(continuation.start.as_ref()) {
+        if comment_text.contains(continuation.end.as_ref()) {
+            return None;
+        }
+        let alignment = continuation.start.find(marker).unwrap_or(0);
+        return Some(format!("{}{}", " ".repeat(alignment), continuation.prefix).into());
+    }
+
+    let is_continued_line = |text: &str| {
+        !marker.is_empty() && text.starts_with(marker) && !text.contains(continuation.end.as_ref())
+    };
+    if !is_continued_line(text_before_cursor) {
+        return None;
+    }
+    // Only continue the leader if the lines above lead back to an unclosed comment opening,
+    // so that e.g. a dereference at the start of a line isn't mistaken for a comment.
+    for row in (0..point.row).rev() {
+        let line = line_text(row);
+        let text = line.trim_start();
+        if text.starts_with(continuation.start.as_ref()) {
+            return (!text.contains(continuation.end.as_ref()))
+                .then(|| continuation.prefix.clone());
+        } else if !is_continued_line(text) {
+            return None;
+        }
+    }
+    None
+}
+
+/// Applies edits to several buffers, each in a transaction of its own.
+fn apply_buffer_edits(
+    edits: Vec<(Model<Buffer>, Vec<(Range<language::Anchor>, String)>)>,
+    cx: &mut AppContext,
+) -> ProjectTransaction {
+    let mut project_transaction = ProjectTransaction::default();
+    for (buffer, edits) in edits {
+        let transaction = buffer.update(cx, |buffer, cx| {
+            buffer.finalize_last_transaction();
+            buffer.start_transaction();
+            buffer.edit(edits, None, cx);
+            buffer.end_transaction(cx)?;
+            buffer.finalize_last_transaction().cloned()
+        });
+        if let Some(transaction) = transaction {
+            project_transaction.0.insert(buffer, transaction);
+        }
+    }
+    project_transaction
+}
+
+fn is_refactoring(action: &CodeAction) -> bool {
+    action.lsp_action.kind.as_ref().map_or(false, |kind| {
+        let kind = kind.as_str();
+        kind == lsp::CodeActionKind::REFACTOR.as_str() || kind.starts_with("refactor.")
+    })
+}
+
+enum AlignmentDelimiter {
+    Text(String),
+    Regex(Regex),
+}
+
+impl AlignmentDelimiter {
+    /// Returns the ranges of the delimiter within the line. Text delimiters that are part of a
+    /// longer run of themselves, such as the `=` in `==` or the `:` in `::`, are skipped.
+    fn ranges_in(&self, line: &str) -> Vec<Range<usize>> {
+        match self {
+            AlignmentDelimiter::Text(text) if text.is_empty() => Vec::new(),
+            AlignmentDelimiter::Text(text) => line
+                .match_indices(text.as_str())
+                .map(|(ix, _)| ix..ix + text.len())
+                .filter(|range| {
+                    !line[..range.start].ends_with(text.as_str())
+                        && !line[range.end..].starts_with(text.as_str())
+                })
+                .collect(),
+            AlignmentDelimiter::Regex(regex) => regex
+                .find_iter(line)
+                .filter(|found| found.start() < found.end())
+                .map(|found| found.range())
+                .collect(),
+        }
+    }
+}
+
+/// Returns the edits, as line indices, byte ranges and replacement text, that align the
+/// delimiters in the given lines.
+///
+/// Every occurrence is aligned when each line containing the delimiter contains it the same
+/// number of times, as in a table. Otherwise only the first occurrence is. Delimiters that are
+/// attached to the preceding text in every line, as in `key: value`, stay attached and are
+/// followed by the padding instead.
+fn alignment_edits(
+    lines: &[String],
+    delimiter: &AlignmentDelimiter,
+) -> Vec<(usize, Range<usize>, String)> {
+    struct Cell {
+        range: Range<usize>,
+        width: usize,
+        has_content: bool,
+    }
+
+    let delimiter_ranges = lines
+        .iter()
+        .map(|line| delimiter.ranges_in(line))
+        .collect::<Vec<_>>();
+    let mut counts = delimiter_ranges
+        .iter()
+        .map(Vec::len)
+        .filter(|count| *count > 0);
+    let Some(first_count) = counts.next() else {
+        return Vec::new();
+    };
+    let column_count = if counts.all(|count| count == first_count) {
+        first_count
+    } else {
+        1
+    };
+
+    // Each line is split into the text around its delimiters, without the surrounding
+    // whitespace. The first cell keeps the line's indentation.
+    let cells = lines
+        .iter()
+        .zip(&delimiter_ranges)
+        .map(|(line, ranges)| {
+            let mut cells = Vec::new();
+            if ranges.is_empty() {
+                return cells;
+            }
+            let mut cell_start = 0;
+            for (ix, delimiter_range) in ranges.iter().take(column_count).enumerate() {
+                cells.push(cell_range(line, cell_start..delimiter_range.start, ix == 0));
+                cell_start = delimiter_range.end;
+            }
+            cells.push(cell_range(line, cell_start..line.len(), false));
+            cells
+                .into_iter()
+                .map(|(range, has_content)| Cell {
+                    width: line[range.clone()].chars().count(),
+                    range,
+                    has_content,
+                })
+                .collect()
+        })
+        .collect::<Vec<Vec<Cell>>>();
+
+    let columns = (0..column_count)
+        .map(|column| {
+            let column_cells = || {
+                cells
+                    .iter()
+                    .zip(&delimiter_ranges)
+                    .filter_map(move |(cells, ranges)| Some((cells.get(column)?, &ranges[column])))
+            };
+            let width = column_cells()
+                .map(|(cell, _)| cell.width)
+                .max()
+                .unwrap_or(0);
+            let has_content = column_cells().any(|(cell, _)| cell.has_content);
+            let is_attached = column_cells().all(|(cell, delimiter_range)| {
+                cell.has_content && cell.range.end == delimiter_range.start
+            });
+            (width, has_content, is_attached)
+        })
+        .collect::<Vec<_>>();
+
+    let mut edits = Vec::new();
+    for (line_ix, (line, cells)) in lines.iter().zip(&cells).enumerate() {
+        for (column, window) in cells.windows(2).enumerate() {
+            let (cell, next_cell) = (&window[0], &window[1]);
+            let (width, has_content, is_attached) = columns[column];
+            let padding = " ".repeat(width - cell.width);
+            let delimiter_range = &delimiter_ranges[line_ix][column];
+            let mut new_text = String::new();
+            if is_attached {
+                new_text.push_str(&line[delimiter_range.clone()]);
+                new_text.push_str(&padding);
+            } else {
+                new_text.push_str(&padding);
+                if has_content {
+                    new_text.push(' ');
+                }
+                new_text.push_str(&line[delimiter_range.clone()]);
+            }
+            let next_column_has_content = columns
+                .get(column + 1)
+                .map_or(false, |(_, has_content, _)| *has_content);
+            if next_cell.has_content || next_column_has_content {
+                new_text.push(' ');
+            }
+
+            // Only the text that changes is edited, so that cursors next to the delimiter
+            // aren't moved by the edit.
+            let old_text = &line[cell.range.end..next_cell.range.start];
+            let prefix_len = common_prefix_len(old_text.chars(), new_text.chars());
+            let suffix_len = common_prefix_len(
+                old_text[prefix_len..].chars().rev(),
+                new_text[prefix_len..].chars().rev(),
+            );
+            if old_text != new_text {
+                edits.push((
+                    line_ix,
+                    cell.range.end + prefix_len..next_cell.range.start - suffix_len,
+                    new_text[prefix_len..new_text.len() - suffix_len].to_string(),
+                ));
+            }
+        }
+    }
+    edits
+}
+
+/// Returns the length in bytes of the longest common prefix of the given characters.
+fn common_prefix_len(a: impl Iterator<Item = char>, b: impl Iterator<Item = char>) -> usize {
+    a.zip(b)
+        .take_while(|(a, b)| a == b)
+        .map(|(c, _)| c.len_utf8())
+        .sum()
+}
+
+/// Returns the range of the text within the given range of the line, without its surrounding
+/// whitespace, and whether there is any such text.
+fn cell_range(line: &str, range: Range<usize>, keep_indentation: bool) -> (Range<usize>, bool) {
+    let text = &line[range.clone()];
+    if text.trim().is_empty() {
+        if keep_indentation {
+            (range, false)
+        } else {
+            (range.start..range.start, false)
+        }
+    } else {
+        let start = if keep_indentation {
+            range.start
+        } else {
+            range.start + text.len() - text.trim_start().len()
+        };
+        (start..range.start + text.trim_end().len(), true)
+    }
+}
+
 fn consume_contiguous_rows(
     contiguous_row_selections: &mut Vec<Selection<Point>>,
     selection: &Selection<Point>,
@@ -9114,6 +10749,62 @@ fn consume_contiguous_rows(
     (start_row, end_row)
 }
 
+/// Like [`consume_contiguous_rows`], but extends row ranges that span several lines to the end
+/// of the outermost syntax node that starts within them and contains their last line, so that
+/// moving part of a statement moves the whole statement.
+fn consume_contiguous_statement_rows(
+    contiguous_row_selections: &mut Vec<Selection<Point>>,
+    selection: &Selection<Point>,
+    display_map: &DisplaySnapshot,
+    selections: &mut std::iter::Peekable<std::slice::Iter<Selection<Point>>>,
+) -> (u32, u32) {
+    let (start_row, mut end_row) = consume_contiguous_rows(
+        contiguous_row_selections,
+        selection,
+        display_map,
+        selections,
+    );
+    let buffer = &display_map.buffer_snapshot;
+    let last_row = end_row - 1;
+    if last_row == start_row || buffer.is_line_blank(last_row) {
+        return (start_row, end_row);
+    }
+
+    let rows_start = Point::new(start_row, 0).to_offset(buffer);
+    let mut node_range = Point::new(last_row, buffer.indent_size_for_line(last_row).len)
+        .to_offset(buffer)
+        ..Point::new(last_row, buffer.line_len(last_row)).to_offset(buffer);
+    while let Some(ancestor_range) = buffer.range_for_syntax_ancestor(node_range.clone()) {
+        // Stop before nodes that start above the rows, or that are the root of the syntax tree.
+        if ancestor_range.start < rows_start
+            || buffer
+                .range_for_syntax_ancestor(ancestor_range.clone())
+                .is_none()
+        {
+            break;
+        }
+        node_range = ancestor_range;
+    }
+    let node_end = node_range.end.to_point(buffer);
+    let node_end_row = if node_end.column == 0 {
+        node_end.row
+    } else {
+        node_end.row + 1
+    };
+    end_row = cmp::max(end_row, node_end_row);
+
+    // Include any selections within the extended rows.
+    while let Some(next_selection) = selections.peek() {
+        if next_selection.start.row < end_row {
+            end_row = cmp::max(end_row, ending_row(next_selection, display_map));
+            contiguous_row_selections.push(selections.next().unwrap().clone());
+        } else {
+            break;
+        }
+    }
+    (start_row, end_row)
+}
+
 fn ending_row(next_selection: &Selection<Point>, display_map: &DisplaySnapshot) -> u32 {
     if next_selection.end.column > 0 || next_selection.is_empty() {
         display_map.next_line_boundary(next_selection.end).0.row + 1
@@ -9253,12 +10944,13 @@ impl FocusableView for Editor {
 
 impl Render for Editor {
     fn render<'a>(&mut self, cx: &mut ViewContext<'a, Self>) -> impl IntoElement {
-        let settings = ThemeSettings::get_global(cx);
+        let settings = ThemeSettings::for_window(cx);
         let text_style = match self.mode {
             EditorMode::SingleLine | EditorMode::AutoHeight { .. } => TextStyle {
                 color: cx.theme().colors().editor_foreground,
                 font_family: settings.ui_font.family.clone(),
-                font_features: settings.ui_font.features,
+                font_features: settings.ui_font.features.clone(),
+                font_fallbacks: settings.ui_font.fallbacks.clone(),
                 font_size: rems(0.875).into(),
                 font_weight: FontWeight::NORMAL,
                 font_style: FontStyle::Normal,
@@ -9271,7 +10963,8 @@ impl Render for Editor {
             EditorMode::Full => TextStyle {
                 color: cx.theme().colors().editor_foreground,
                 font_family: settings.buffer_font.family.clone(),
-                font_features: settings.buffer_font.features,
+                font_features: settings.buffer_font.features.clone(),
+                font_fallbacks: settings.buffer_font.fallbacks.clone(),
                 font_size: settings.buffer_font_size(cx).into(),
                 font_weight: FontWeight::NORMAL,
                 font_style: FontStyle::Normal,
@@ -9482,9 +11175,17 @@ impl ViewInputHandler for Editor {
             if text.is_empty() {
                 this.unmark_text(cx);
             } else {
+                // Underline the text being composed, like native text fields do.
                 this.highlight_text::<InputComposition>(
                     marked_ranges.clone(),
-                    HighlightStyle::default(), // todo!() this.style(cx).composition_mark,
+                    HighlightStyle {
+                        underline: Some(UnderlineStyle {
+                            thickness: px(1.),
+                            color: None,
+                            wavy: false,
+                        }),
+                        ..Default::default()
+                    },
                     cx,
                 );
             }
@@ -9546,13 +11247,22 @@ impl ViewInputHandler for Editor {
         let scroll_left = scroll_position.x * em_width;
 
         let start = OffsetUtf16(range_utf16.start).to_display_point(&snapshot);
-        let x = snapshot.x_for_display_point(start, &text_layout_details) - scroll_left
-            + self.gutter_width;
+        let end = OffsetUtf16(range_utf16.end).to_display_point(&snapshot);
+        let start_x = snapshot.x_for_display_point(start, &text_layout_details);
+        let x = start_x - scroll_left + self.gutter_width;
         let y = line_height * (start.row() as f32 - scroll_position.y);
 
+        // Cover the whole range when it's on a single line, so that input method windows
+        // are placed next to the text being composed rather than over it.
+        let width = if end.row() == start.row() && end.column() > start.column() {
+            snapshot.x_for_display_point(end, &text_layout_details) - start_x
+        } else {
+            em_width
+        };
+
         Some(Bounds {
             origin: element_bounds.origin + point(x, y),
-            size: size(em_width, line_height),
+            size: size(width, line_height),
         })
     }
 }
@@ -9839,6 +11549,51 @@ pub(crate) fn split_words<'a>(text: &'a str) -> impl std::iter::Iterator<Item =
     .flat_map(|word| word.split_inclusive('-'))
 }
 
+/// Returns the words within `COMPLETION_LOCALITY_LINES` of the position, and the least number of
+/// lines between the position and each word. The word being typed at the position is excluded.
+fn nearby_words(buffer: &MultiBufferSnapshot, position: impl ToOffset) -> HashMap<String, u32> {
+    let offset = position.to_offset(buffer);
+    let cursor = offset.to_point(buffer);
+    let (typed_word_range, _) = buffer.surrounding_word(offset);
+    let start = Point::new(cursor.row.saturating_sub(COMPLETION_LOCALITY_LINES), 0);
+    let end = buffer.clip_point(
+        Point::new(cursor.row + COMPLETION_LOCALITY_LINES, u32::MAX),
+        Bias::Left,
+    );
+
+    let mut words = HashMap::default();
+    let mut word = String::new();
+    let mut word_start = 0;
+    let mut row = start.row;
+    let mut offset = start.to_offset(buffer);
+    let chars = buffer
+        .text_for_range(start..end)
+        .flat_map(str::chars)
+        .chain(Some('\n'));
+    for char in chars {
+        if char.is_alphanumeric() || char == '_' {
+            if word.is_empty() {
+                word_start = offset;
+            }
+            word.push(char);
+        } else if !word.is_empty() {
+            if word_start != typed_word_range.start {
+                let distance = row.abs_diff(cursor.row);
+                words
+                    .entry(mem::take(&mut word))
+                    .and_modify(|nearest: &mut u32| *nearest = (*nearest).min(distance))
+                    .or_insert(distance);
+            }
+            word.clear();
+        }
+        if char == '\n' {
+            row += 1;
+        }
+        offset += char.len_utf8();
+    }
+    words
+}
+
 trait RangeToAnchorExt {
     fn to_anchors(self, snapshot: &MultiBufferSnapshot) -> Range<Anchor>;
 }
@@ -8,10 +8,38 @@ pub struct EditorSettings {
     pub hover_popover_enabled: bool,
     pub show_completions_on_input: bool,
     pub show_completion_documentation: bool,
+    pub completion_ranking: CompletionRanking,
     pub use_on_type_format: bool,
     pub scrollbar: Scrollbar,
     pub relative_line_numbers: bool,
     pub seed_search_query_from_cursor: SeedQuerySetting,
+    pub soft_wrap_indent: SoftWrapIndent,
+    pub cursor_movement: CursorMovement,
+    pub bracket_pairs: BracketPairs,
+}
+
+/// Which way the left and right arrow keys move the cursor through right-to-left text.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CursorMovement {
+    /// Move to the previous or next character in the text, whichever way it's laid out.
+    #[default]
+    Logical,
+    /// Move in the direction of the arrow on screen.
+    Visual,
+}
+
+/// How the lines that a soft-wrapped line continues onto are indented.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SoftWrapIndent {
+    /// Continue at the start of the line.
+    None,
+    /// Indent as far as the wrapped line.
+    #[default]
+    Same,
+    /// Indent one level further than the wrapped line.
+    Indented,
 }
 
 /// When to populate a new search's query based on the text under the cursor.
@@ -26,6 +54,20 @@ pub enum SeedQuerySetting {
     Never,
 }
 
+/// How completions are ranked, after the order given by the language server.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct CompletionRanking {
+    pub prefer_prefix_matches: bool,
+    pub locality_bonus: bool,
+}
+
+/// How bracket pairs are drawn.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct BracketPairs {
+    pub colorize: bool,
+    pub scope_guide: bool,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Scrollbar {
     pub show: ShowScrollbar,
@@ -71,6 +113,8 @@ pub struct EditorSettingsContent {
     ///
     /// Default: true
     pub show_completion_documentation: Option<bool>,
+    /// How completions are ranked in the completions menu.
+    pub completion_ranking: Option<CompletionRankingContent>,
     /// Whether to use additional LSP queries to format (and amend) the code after
     /// every "trigger" symbol input, defined by LSP server capabilities.
     ///
@@ -86,6 +130,46 @@ pub struct EditorSettingsContent {
     ///
     /// Default: always
     pub seed_search_query_from_cursor: Option<SeedQuerySetting>,
+    /// How the lines that a soft-wrapped line continues onto are indented.
+    ///
+    /// Default: same
+    pub soft_wrap_indent: Option<SoftWrapIndent>,
+    /// Which way the left and right arrow keys move the cursor through right-to-left text.
+    ///
+    /// Default: logical
+    pub cursor_movement: Option<CursorMovement>,
+    /// How bracket pairs are drawn.
+    pub bracket_pairs: Option<BracketPairsContent>,
+}
+
+/// Bracket pair related settings
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct BracketPairsContent {
+    /// Whether to color brackets by how deeply they're nested, cycling
+    /// through the theme's player colors.
+    ///
+    /// Default: false
+    pub colorize: Option<bool>,
+    /// Whether to draw a vertical guide between the lines of the innermost
+    /// bracket pair that encloses the cursor.
+    ///
+    /// Default: false
+    pub scope_guide: Option<bool>,
+}
+
+/// Completion ranking related settings
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct CompletionRankingContent {
+    /// Whether to rank completions that start with the typed text above
+    /// other fuzzy matches.
+    ///
+    /// Default: true
+    pub prefer_prefix_matches: Option<bool>,
+    /// Whether to rank completions for words that appear close to the cursor
+    /// above ones for words that appear further away, or not at all.
+    ///
+    /// Default: false
+    pub locality_bonus: Option<bool>,
 }
 
 /// Scrollbar related settings
@@ -1,5 +1,6 @@
 use super::*;
 use crate::{
+    editor_settings::CompletionRankingContent,
     scroll::scroll_amount::ScrollAmount,
     test::{
         assert_text_with_selections, build_editor, editor_lsp_test_context::EditorLspTestContext,
@@ -13,7 +14,7 @@ use gpui::{div, TestAppContext, VisualTestContext, WindowBounds, WindowOptions};
 use indoc::indoc;
 use language::{
     language_settings::{AllLanguageSettings, AllLanguageSettingsContent, LanguageSettingsContent},
-    BracketPairConfig,
+    BlockCommentContinuation, BracketPairConfig,
     Capability::ReadWrite,
     FakeLspAdapter, LanguageConfig, LanguageConfigOverride, LanguageRegistry, Override, Point,
 };
@@ -1988,6 +1989,87 @@ async fn test_newline_comments(cx: &mut gpui::TestAppContext) {
     "});
 }
 
+#[gpui::test]
+async fn test_newline_block_comments(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.tab_size = NonZeroU32::new(4)
+    });
+
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            line_comments: vec!["// ".into()],
+            block_comment_continuation: Some(BlockCommentContinuation {
+                start: "/*".into(),
+                end: "*/".into(),
+                prefix: "* ".into(),
+            }),
+            ..LanguageConfig::default()
+        },
+        None,
+    ));
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // The leader is aligned with the opening delimiter on the comment's first line.
+    cx.set_state(indoc! {"
+        /**ˇ
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        /**
+         * ˇ
+    "});
+
+    cx.set_state(indoc! {"
+        fn a() {
+            /* Fooˇ
+        }
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            /* Foo
+             * ˇ
+        }
+    "});
+
+    // Lines that already begin with the leader continue it.
+    cx.set_state(indoc! {"
+        /**
+         * Fooˇ
+         */
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        /**
+         * Foo
+         * ˇ
+         */
+    "});
+
+    // Closed comments aren't continued.
+    cx.set_state(indoc! {"
+        /* Foo */ˇ
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        /* Foo */
+        ˇ
+    "});
+
+    // Lines that begin with the leader's text outside of a comment aren't continued.
+    cx.set_state(indoc! {"
+        a = 1;
+        *b = 2;ˇ
+    "});
+    cx.update_editor(|e, cx| e.newline(&Newline, cx));
+    cx.assert_editor_state(indoc! {"
+        a = 1;
+        *b = 2;
+        ˇ
+    "});
+}
+
 #[gpui::test]
 fn test_insert_with_old_selections(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -2686,6 +2768,114 @@ fn test_join_lines_with_multi_selection(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_join_lines_with_join_patterns(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                join_lines: serde_json::from_value(json!({
+                    "line_start_pattern": r"^(?://[/!]?\s*|\*(?:\s+|$))",
+                    "line_end_pattern": r"\\$",
+                    "string_continuation_skips_whitespace": true,
+                }))
+                .unwrap(),
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )
+        .with_override_query(indoc! {r#"
+            [(line_comment) (block_comment)] @comment
+            (string_literal) @string
+        "#})
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // Comment leaders are removed from lines joined onto comments.
+    cx.set_state(indoc! {"
+        // aˇaa
+        // bbb
+        fn c() {}
+    "});
+    cx.executor().run_until_parked();
+    cx.update_editor(|e, cx| e.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        // aaaˇ bbb
+        fn c() {}
+    "});
+
+    cx.set_state(indoc! {"
+        «/**
+         * aaa
+         */ˇ»
+    "});
+    cx.executor().run_until_parked();
+    cx.update_editor(|e, cx| e.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        /** aaaˇ */
+    "});
+
+    // Comments joined onto code keep their leaders.
+    cx.set_state(indoc! {"
+        let a = 1;ˇ
+        // b
+    "});
+    cx.executor().run_until_parked();
+    cx.update_editor(|e, cx| e.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {"
+        let a = 1;ˇ // b
+    "});
+
+    // String continuations are removed without separating the string's contents.
+    cx.set_state(indoc! {r#"
+        let a = "aaa\ˇ
+            bbb";
+    "#});
+    cx.executor().run_until_parked();
+    cx.update_editor(|e, cx| e.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {r#"
+        let a = "aaaˇbbb";
+    "#});
+}
+
+#[gpui::test]
+async fn test_join_lines_keeps_string_continuation_whitespace(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                join_lines: serde_json::from_value(json!({
+                    "line_end_pattern": r"\\$",
+                }))
+                .unwrap(),
+                ..Default::default()
+            },
+            Some(tree_sitter_typescript::language_typescript()),
+        )
+        .with_override_query("(string) @string")
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // Unlike in Rust, the whitespace after a string continuation is part of the string.
+    cx.set_state(indoc! {r#"
+        let a = "aaa\ˇ
+            bbb";
+    "#});
+    cx.executor().run_until_parked();
+    cx.update_editor(|e, cx| e.join_lines(&JoinLines, cx));
+    cx.assert_editor_state(indoc! {r#"
+        let a = "aaaˇ    bbb";
+    "#});
+}
+
 #[gpui::test]
 async fn test_manipulate_lines_with_single_selection(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -2940,6 +3130,24 @@ async fn test_manipulate_text(cx: &mut TestAppContext) {
     cx.assert_editor_state(indoc! {"
         «aaaBbbˇ» «bbbCccˇ» «cccDddˇ»
     "});
+
+    // Identifiers within code are converted separately, keeping the text between them
+    cx.set_state(indoc! {"
+        «self.maxRetryCount = HTTPClient::new(utf8Name, count-1);ˇ»
+    "});
+    cx.update_editor(|e, cx| e.convert_to_snake_case(&ConvertToSnakeCase, cx));
+    cx.assert_editor_state(indoc! {"
+        «self.max_retry_count = http_client::new(utf8_name, count-1);ˇ»
+    "});
+
+    // Test convert_to_upper_snake_case() with multiple cursors
+    cx.set_state(indoc! {"
+        let ˇmaxCount = _defauˇltSize;
+    "});
+    cx.update_editor(|e, cx| e.convert_to_upper_snake_case(&ConvertToUpperSnakeCase, cx));
+    cx.assert_editor_state(indoc! {"
+        let «MAX_COUNTˇ» = «_DEFAULT_SIZEˇ»;
+    "});
 }
 
 #[gpui::test]
@@ -3094,6 +3302,121 @@ fn test_move_line_up_down(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_duplicate_selection(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Selections are duplicated after themselves, and cursors duplicate their lines.
+    cx.set_state(indoc! {"
+        a«bˇ»c
+        dˇef
+    "});
+    cx.update_editor(|e, cx| e.duplicate_selection(&DuplicateSelection, cx));
+    cx.assert_editor_state(indoc! {"
+        ab«bˇ»c
+        def
+        dˇef
+    "});
+
+    // Reversed and multi-line selections keep their direction.
+    cx.set_state(indoc! {"
+        a«ˇbc
+        d»ef
+    "});
+    cx.update_editor(|e, cx| e.duplicate_selection(&DuplicateSelection, cx));
+    cx.assert_editor_state(indoc! {"
+        abc
+        d«ˇbc
+        d»ef
+    "});
+
+    // Lines with several cursors are only duplicated once.
+    cx.set_state(indoc! {"
+        aˇbcˇ
+        def
+    "});
+    cx.update_editor(|e, cx| e.duplicate_selection(&DuplicateSelection, cx));
+    cx.assert_editor_state(indoc! {"
+        abc
+        aˇbcˇ
+        def
+    "});
+}
+
+#[gpui::test]
+async fn test_move_line_up_down_with_reindent(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |settings| {
+        settings.defaults.tab_size = NonZeroU32::new(4)
+    });
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig::default(),
+            Some(tree_sitter_rust::language()),
+        )
+        .with_indents_query(r#"(_ "{" "}" @end) @indent"#)
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // Moved lines are reindented to fit their new position.
+    cx.set_state(indoc! {"
+        fn a() {
+            if b {
+                c();
+            }
+            ˇd();
+        }
+    "});
+    cx.executor().run_until_parked();
+    cx.update_editor(|e, cx| e.move_line_up(&MoveLineUp, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            if b {
+                c();
+                ˇd();
+            }
+        }
+    "});
+
+    cx.update_editor(|e, cx| e.move_line_down(&MoveLineDown, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            if b {
+                c();
+            }
+            ˇd();
+        }
+    "});
+
+    // Selections spanning several lines move the whole statements that they start.
+    cx.set_state(indoc! {"
+        fn a() {
+            b();
+            «if c {
+                dˇ»();
+            }
+        }
+    "});
+    cx.executor().run_until_parked();
+    cx.update_editor(|e, cx| e.move_line_up(&MoveLineUp, cx));
+    cx.executor().run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        fn a() {
+            «if c {
+                dˇ»();
+            }
+            b();
+        }
+    "});
+}
+
 #[gpui::test]
 fn test_move_line_up_down_with_blocks(cx: &mut TestAppContext) {
     init_test(cx, |_| {});
@@ -3301,6 +3624,79 @@ async fn test_clipboard(cx: &mut gpui::TestAppContext) {
         tˇhe lazy dog"});
 }
 
+#[gpui::test]
+async fn test_kill_ring(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Killing at the end of a line kills the line break, and consecutive kills are joined.
+    cx.set_state("ˇone\ntwo");
+    cx.update_editor(|e, cx| e.kill_ring_cut(&KillRingCut, cx));
+    cx.assert_editor_state("ˇ\ntwo");
+    cx.update_editor(|e, cx| e.kill_ring_cut(&KillRingCut, cx));
+    cx.assert_editor_state("ˇtwo");
+
+    cx.set_state("twoˇ three");
+    cx.update_editor(|e, cx| e.kill_ring_cut(&KillRingCut, cx));
+    cx.assert_editor_state("twoˇ");
+
+    // Yank-pop cycles through the older kills.
+    cx.update_editor(|e, cx| e.kill_ring_yank(&KillRingYank, cx));
+    cx.assert_editor_state("two threeˇ");
+    cx.update_editor(|e, cx| e.kill_ring_yank_pop(&KillRingYankPop, cx));
+    cx.assert_editor_state("twoone\nˇ");
+    cx.update_editor(|e, cx| e.kill_ring_yank_pop(&KillRingYankPop, cx));
+    cx.assert_editor_state("two threeˇ");
+
+    // Text copied since the last kill is yanked first.
+    cx.set_state("«fourˇ» ");
+    cx.update_editor(|e, cx| {
+        e.copy(&Copy, cx);
+        e.move_to_end_of_line(&MoveToEndOfLine, cx);
+        e.kill_ring_yank(&KillRingYank, cx);
+    });
+    cx.assert_editor_state("four fourˇ");
+    cx.update_editor(|e, cx| e.kill_ring_yank_pop(&KillRingYankPop, cx));
+    cx.assert_editor_state("four  threeˇ");
+}
+
+#[gpui::test]
+async fn test_set_mark(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    cx.set_state("o«neˇ» two");
+    cx.update_editor(|e, cx| {
+        e.set_mark(&SetMark, cx);
+        assert!(e.key_context(cx).contains("selection_mode"));
+    });
+    cx.assert_editor_state("oneˇ two");
+
+    cx.update_editor(|e, cx| {
+        e.select_right(&SelectRight, cx);
+        e.select_to_next_word_end(&SelectToNextWordEnd, cx);
+        e.swap_selection_ends(&SwapSelectionEnds, cx);
+    });
+    cx.assert_editor_state("one«ˇ two»");
+
+    cx.update_editor(|e, cx| {
+        e.cancel(&Cancel, cx);
+        assert!(!e.key_context(cx).contains("selection_mode"));
+    });
+    cx.assert_editor_state("oneˇ two");
+
+    // Editing ends the selection mode.
+    cx.update_editor(|e, cx| {
+        e.set_mark(&SetMark, cx);
+        e.select_right(&SelectRight, cx);
+        e.handle_input("!", cx);
+        assert!(!e.key_context(cx).contains("selection_mode"));
+    });
+    cx.assert_editor_state("one!ˇtwo");
+}
+
 #[gpui::test]
 async fn test_paste_multiline(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -4361,42 +4757,103 @@ async fn test_autoclose_pairs(cx: &mut gpui::TestAppContext) {
 }
 
 #[gpui::test]
-async fn test_autoclose_with_embedded_language(cx: &mut gpui::TestAppContext) {
+async fn test_autoclose_bracket_settings(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
 
     let mut cx = EditorTestContext::new(cx).await;
 
-    let html_language = Arc::new(
-        Language::new(
-            LanguageConfig {
-                name: "HTML".into(),
-                brackets: BracketPairConfig {
-                    pairs: vec![
-                        BracketPair {
-                            start: "<".into(),
-                            end: ">".into(),
-                            close: true,
-                            ..Default::default()
-                        },
-                        BracketPair {
-                            start: "{".into(),
-                            end: "}".into(),
-                            close: true,
-                            ..Default::default()
-                        },
-                        BracketPair {
-                            start: "(".into(),
-                            end: ")".into(),
-                            close: true,
-                            ..Default::default()
-                        },
-                    ],
-                    ..Default::default()
-                },
-                autoclose_before: "})]>".into(),
-                ..Default::default()
-            },
-            Some(tree_sitter_html::language()),
+    let language = Arc::new(Language::new(
+        LanguageConfig {
+            brackets: BracketPairConfig {
+                pairs: vec![
+                    BracketPair {
+                        start: "(".to_string(),
+                        end: ")".to_string(),
+                        close: true,
+                        newline: true,
+                    },
+                    BracketPair {
+                        start: "[".to_string(),
+                        end: "]".to_string(),
+                        close: false,
+                        newline: true,
+                    },
+                ],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    ));
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // Only auto-inserted closing brackets are typed over by default.
+    cx.set_state("(ˇ)");
+    cx.update_editor(|view, cx| view.handle_input(")", cx));
+    cx.assert_editor_state("()ˇ)");
+
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.defaults.always_type_over_closing_brackets = Some(true);
+                settings.defaults.autoclose_brackets = Some(HashMap::from_iter([
+                    ("(".to_string(), false),
+                    ("[".to_string(), true),
+                ]));
+            });
+        })
+    });
+
+    cx.set_state("(ˇ)");
+    cx.update_editor(|view, cx| view.handle_input(")", cx));
+    cx.assert_editor_state("()ˇ");
+
+    // The settings override which brackets the language closes.
+    cx.set_state("ˇ");
+    cx.update_editor(|view, cx| view.handle_input("[", cx));
+    cx.assert_editor_state("[ˇ]");
+    cx.set_state("ˇ");
+    cx.update_editor(|view, cx| view.handle_input("(", cx));
+    cx.assert_editor_state("(ˇ");
+}
+
+#[gpui::test]
+async fn test_autoclose_with_embedded_language(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    let html_language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                name: "HTML".into(),
+                brackets: BracketPairConfig {
+                    pairs: vec![
+                        BracketPair {
+                            start: "<".into(),
+                            end: ">".into(),
+                            close: true,
+                            ..Default::default()
+                        },
+                        BracketPair {
+                            start: "{".into(),
+                            end: "}".into(),
+                            close: true,
+                            ..Default::default()
+                        },
+                        BracketPair {
+                            start: "(".into(),
+                            end: ")".into(),
+                            close: true,
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                },
+                autoclose_before: "})]>".into(),
+                ..Default::default()
+            },
+            Some(tree_sitter_html::language()),
         )
         .with_injection_query(
             r#"
@@ -4730,127 +5187,584 @@ async fn test_surround_with_pair(cx: &mut gpui::TestAppContext) {
     "#
     .unindent();
 
-    let buffer = cx.new_model(|cx| {
-        Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), text)
-            .with_language(language, cx)
+    let buffer = cx.new_model(|cx| {
+        Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), text)
+            .with_language(language, cx)
+    });
+    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+    let (view, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
+    view.condition::<crate::EditorEvent>(cx, |view, cx| !view.buffer.read(cx).is_parsing(cx))
+        .await;
+
+    _ = view.update(cx, |view, cx| {
+        view.change_selections(None, cx, |s| {
+            s.select_display_ranges([
+                DisplayPoint::new(0, 0)..DisplayPoint::new(0, 1),
+                DisplayPoint::new(1, 0)..DisplayPoint::new(1, 1),
+                DisplayPoint::new(2, 0)..DisplayPoint::new(2, 1),
+            ])
+        });
+
+        view.handle_input("{", cx);
+        view.handle_input("{", cx);
+        view.handle_input("{", cx);
+        assert_eq!(
+            view.text(cx),
+            "
+                {{{a}}}
+                {{{b}}}
+                {{{c}}}
+            "
+            .unindent()
+        );
+        assert_eq!(
+            view.selections.display_ranges(cx),
+            [
+                DisplayPoint::new(0, 3)..DisplayPoint::new(0, 4),
+                DisplayPoint::new(1, 3)..DisplayPoint::new(1, 4),
+                DisplayPoint::new(2, 3)..DisplayPoint::new(2, 4)
+            ]
+        );
+
+        view.undo(&Undo, cx);
+        view.undo(&Undo, cx);
+        view.undo(&Undo, cx);
+        assert_eq!(
+            view.text(cx),
+            "
+                a
+                b
+                c
+            "
+            .unindent()
+        );
+        assert_eq!(
+            view.selections.display_ranges(cx),
+            [
+                DisplayPoint::new(0, 0)..DisplayPoint::new(0, 1),
+                DisplayPoint::new(1, 0)..DisplayPoint::new(1, 1),
+                DisplayPoint::new(2, 0)..DisplayPoint::new(2, 1)
+            ]
+        );
+
+        // Ensure inserting the first character of a multi-byte bracket pair
+        // doesn't surround the selections with the bracket.
+        view.handle_input("/", cx);
+        assert_eq!(
+            view.text(cx),
+            "
+                /
+                /
+                /
+            "
+            .unindent()
+        );
+        assert_eq!(
+            view.selections.display_ranges(cx),
+            [
+                DisplayPoint::new(0, 1)..DisplayPoint::new(0, 1),
+                DisplayPoint::new(1, 1)..DisplayPoint::new(1, 1),
+                DisplayPoint::new(2, 1)..DisplayPoint::new(2, 1)
+            ]
+        );
+
+        view.undo(&Undo, cx);
+        assert_eq!(
+            view.text(cx),
+            "
+                a
+                b
+                c
+            "
+            .unindent()
+        );
+        assert_eq!(
+            view.selections.display_ranges(cx),
+            [
+                DisplayPoint::new(0, 0)..DisplayPoint::new(0, 1),
+                DisplayPoint::new(1, 0)..DisplayPoint::new(1, 1),
+                DisplayPoint::new(2, 0)..DisplayPoint::new(2, 1)
+            ]
+        );
+
+        // Ensure inserting the last character of a multi-byte bracket pair
+        // doesn't surround the selections with the bracket.
+        view.handle_input("*", cx);
+        assert_eq!(
+            view.text(cx),
+            "
+                *
+                *
+                *
+            "
+            .unindent()
+        );
+        assert_eq!(
+            view.selections.display_ranges(cx),
+            [
+                DisplayPoint::new(0, 1)..DisplayPoint::new(0, 1),
+                DisplayPoint::new(1, 1)..DisplayPoint::new(1, 1),
+                DisplayPoint::new(2, 1)..DisplayPoint::new(2, 1)
+            ]
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_surround_actions(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let language = Arc::new(
+        Language::new(
+            LanguageConfig {
+                brackets: BracketPairConfig {
+                    pairs: vec![
+                        BracketPair {
+                            start: "(".to_string(),
+                            end: ")".to_string(),
+                            close: true,
+                            newline: false,
+                        },
+                        BracketPair {
+                            start: "[".to_string(),
+                            end: "]".to_string(),
+                            close: true,
+                            newline: false,
+                        },
+                    ],
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )
+        .with_brackets_query(indoc! {r#"
+            ("(" @open ")" @close)
+            ("[" @open "]" @close)
+        "#})
+        .unwrap(),
+    );
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.update_buffer(|buffer, cx| buffer.set_language(Some(language), cx));
+
+    // Selections are surrounded with the pair of the next typed character, and
+    // cursors surround the word that contains them.
+    cx.set_state("let a = «bˇ» + cˇd;");
+    cx.update_editor(|editor, cx| {
+        editor.surround_selections(&SurroundSelections, cx);
+        editor.handle_input(")", cx);
+    });
+    cx.assert_editor_state("let a = («bˇ») + (cˇd);");
+
+    // Characters that aren't brackets surround with themselves.
+    cx.update_editor(|editor, cx| {
+        editor.surround_selections(&SurroundSelections, cx);
+        editor.handle_input("*", cx);
+    });
+    cx.assert_editor_state("let a = (*«bˇ»*) + (*cˇd*);");
+
+    // Cancelling doesn't surround anything.
+    cx.set_state("let a = «bˇ»;");
+    cx.update_editor(|editor, cx| {
+        editor.surround_selections(&SurroundSelections, cx);
+        editor.cancel(&Cancel, cx);
+        editor.handle_input("x", cx);
+    });
+    cx.assert_editor_state("let a = xˇ;");
+
+    // The innermost pair enclosing each selection is changed.
+    cx.set_state("fn a() { f(g(bˇ), c); }");
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| {
+        editor.change_surrounding_pair(&ChangeSurroundingPair, cx);
+        editor.handle_input("[", cx);
+    });
+    cx.assert_editor_state("fn a() { f(g[bˇ], c); }");
+
+    // Typing an opening bracket replaces the selection when auto-surround is disabled.
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                settings.defaults.use_auto_surround = Some(false);
+            });
+        });
+    });
+    cx.set_state("let a = «bˇ»;");
+    cx.update_editor(|editor, cx| editor.handle_input("(", cx));
+    cx.assert_editor_state("let a = (ˇ;");
+}
+
+#[gpui::test]
+async fn test_align_on_delimiter(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // The delimiter is padded so that it lines up across the selected lines, and
+    // delimiters that are part of longer operators are ignored.
+    cx.set_state(indoc! {"
+        «let a = 1;
+        let bcd = 22;
+        let ef=3;
+        if a == b {}ˇ»
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.align_on_delimiter(
+            &AlignOnDelimiter {
+                delimiter: Some("=".to_string()),
+                regex: false,
+            },
+            cx,
+        );
+    });
+    cx.assert_editor_state(indoc! {"
+        «let a   = 1;
+        let bcd = 22;
+        let ef  = 3;
+        if a == b {}ˇ»
+    "});
+
+    // Without a delimiter, the next typed character is used. Every occurrence is
+    // aligned when the lines contain the same number of them.
+    cx.set_state(indoc! {"
+        ˇ| a | bb |
+        ˇ| ccc | d |
+        ˇ|x|y|
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.align_on_delimiter(&AlignOnDelimiter::default(), cx);
+        editor.handle_input("|", cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        ˇ| a   | bb |
+        ˇ| ccc | d  |
+        ˇ| x   | y  |
+    "});
+
+    // Delimiters that are attached to the preceding text stay attached.
+    cx.set_state(indoc! {"
+        Foo {
+            «a: Bar::new(),
+            bcd: 2,ˇ»
+        }
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.align_on_delimiter(&AlignOnDelimiter::default(), cx);
+        editor.handle_input(":", cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        Foo {
+            «a:   Bar::new(),
+            bcd: 2,ˇ»
+        }
+    "});
+
+    // Regular expressions can be used as delimiters.
+    cx.set_state(indoc! {"
+        «x += 1;
+        total -= 10;ˇ»
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.align_on_delimiter(
+            &AlignOnDelimiter {
+                delimiter: Some("[-+]?=".to_string()),
+                regex: true,
+            },
+            cx,
+        );
+    });
+    cx.assert_editor_state(indoc! {"
+        «x     += 1;
+        total -= 10;ˇ»
+    "});
+}
+
+#[gpui::test]
+async fn test_increment_and_decrement_number(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+
+    // Cursors change the number at or after them and move to its end, and
+    // selections change the first number on each of their lines.
+    cx.set_state(indoc! {"
+        ˇlet a = 9;
+        let b = ˇ0xff;
+        let c = «-1ˇ»;
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.increment_number(&IncrementNumber::default(), cx);
+    });
+    cx.assert_editor_state(indoc! {"
+        let a = 10ˇ;
+        let b = 0x100ˇ;
+        let c = «0ˇ»;
+    "});
+
+    cx.set_state("let a = ˇ12ˇ3 + ˇ4;");
+    cx.update_editor(|editor, cx| {
+        editor.decrement_number(
+            &DecrementNumber {
+                count: Some(5),
+                sequence: false,
+            },
+            cx,
+        );
+    });
+    cx.assert_editor_state("let a = 118ˇ + -1ˇ;");
+
+    // In sequence mode, each number is changed by a further count.
+    cx.set_state(indoc! {"
+        ˇ1
+        ˇ1
+        ˇ1
+    "});
+    cx.update_editor(|editor, cx| {
+        editor.decrement_number(
+            &DecrementNumber {
+                count: Some(2),
+                sequence: true,
+            },
+            cx,
+        );
+    });
+    cx.assert_editor_state(indoc! {"
+        -1ˇ
+        -3ˇ
+        -5ˇ
+    "});
+
+    cx.set_state(indoc! {"
+        «item_0 = 0;
+        item_0 = 0;
+        item_0 = 0;
+        ˇ»"});
+    cx.update_editor(|editor, cx| {
+        editor.increment_number(
+            &IncrementNumber {
+                count: None,
+                sequence: true,
+            },
+            cx,
+        );
+    });
+    cx.assert_editor_state(indoc! {"
+        «item_1 = 0;
+        item_2 = 0;
+        item_3 = 0;
+        ˇ»"});
+}
+
+#[gpui::test]
+async fn test_refactor_actions(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state("let a = ˇb;");
+
+    // Only refactoring code actions are shown in the refactor menu.
+    cx.update_editor(|editor, cx| {
+        let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+        let code_action = |title: &str, kind: lsp::CodeActionKind| CodeAction {
+            server_id: LanguageServerId(0),
+            range: text::Anchor::MIN..text::Anchor::MAX,
+            lsp_action: lsp::CodeAction {
+                title: title.to_string(),
+                kind: Some(kind),
+                ..Default::default()
+            },
+        };
+        editor.code_actions_task.take();
+        editor.available_code_actions = Some((
+            buffer,
+            vec![
+                code_action("Import b", lsp::CodeActionKind::QUICKFIX),
+                code_action(
+                    "Extract into function",
+                    lsp::CodeActionKind::REFACTOR_EXTRACT,
+                ),
+                code_action("Inline variable", lsp::CodeActionKind::REFACTOR_INLINE),
+            ]
+            .into(),
+        ));
+        editor.refactor(&Refactor, cx);
+    });
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, _| match editor.context_menu.read().as_ref() {
+        Some(ContextMenu::CodeActions(menu)) => assert_eq!(
+            menu.actions
+                .iter()
+                .map(|action| action.lsp_action.title.as_str())
+                .collect::<Vec<_>>(),
+            ["Extract into function", "Inline variable"]
+        ),
+        _ => panic!("expected the refactor menu to be shown"),
+    });
+}
+
+#[gpui::test]
+async fn test_refactor_preview(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_action_provider: Some(lsp::CodeActionProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/a",
+        json!({
+            "main.rs": "fn main() { let a = 5; }",
+            "other.rs": "// Uses a",
+        }),
+    )
+    .await;
+    let project = Project::test(fs, ["/a".as_ref()], cx).await;
+    _ = project.update(cx, |project, _| project.languages().add(Arc::new(language)));
+    let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+
+    let cx = &mut VisualTestContext::from_window(*workspace, cx);
+
+    let worktree_id = workspace
+        .update(cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.worktrees().next().unwrap().read(cx).id()
+            })
+        })
+        .unwrap();
+
+    let main_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/a/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    let other_buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/a/other.rs", cx)
+        })
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    cx.executor().start_waiting();
+    let fake_server = fake_servers.next().await.unwrap();
+    let editor_handle = workspace
+        .update(cx, |workspace, cx| {
+            workspace.open_path((worktree_id, "main.rs"), None, true, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap()
+        .downcast::<Editor>()
+        .unwrap();
+
+    fake_server.handle_request::<lsp::request::CodeActionRequest, _, _>(|_, _| async move {
+        let edit = |line, column, new_text: &str| lsp::TextEdit {
+            range: lsp::Range::new(
+                lsp::Position::new(line, column),
+                lsp::Position::new(line, column + 1),
+            ),
+            new_text: new_text.to_string(),
+        };
+        Ok(Some(vec![lsp::CodeActionOrCommand::CodeAction(
+            lsp::CodeAction {
+                title: "Rename a".to_string(),
+                kind: Some(lsp::CodeActionKind::REFACTOR),
+                edit: Some(lsp::WorkspaceEdit {
+                    changes: Some(
+                        [
+                            (
+                                lsp::Url::from_file_path("/a/main.rs").unwrap(),
+                                vec![edit(0, 16, "value")],
+                            ),
+                            (
+                                lsp::Url::from_file_path("/a/other.rs").unwrap(),
+                                vec![edit(0, 8, "value")],
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )]))
     });
-    let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
-    let (view, cx) = cx.add_window_view(|cx| build_editor(buffer, cx));
-    view.condition::<crate::EditorEvent>(cx, |view, cx| !view.buffer.read(cx).is_parsing(cx))
-        .await;
-
-    _ = view.update(cx, |view, cx| {
-        view.change_selections(None, cx, |s| {
-            s.select_display_ranges([
-                DisplayPoint::new(0, 0)..DisplayPoint::new(0, 1),
-                DisplayPoint::new(1, 0)..DisplayPoint::new(1, 1),
-                DisplayPoint::new(2, 0)..DisplayPoint::new(2, 1),
-            ])
-        });
-
-        view.handle_input("{", cx);
-        view.handle_input("{", cx);
-        view.handle_input("{", cx);
-        assert_eq!(
-            view.text(cx),
-            "
-                {{{a}}}
-                {{{b}}}
-                {{{c}}}
-            "
-            .unindent()
-        );
-        assert_eq!(
-            view.selections.display_ranges(cx),
-            [
-                DisplayPoint::new(0, 3)..DisplayPoint::new(0, 4),
-                DisplayPoint::new(1, 3)..DisplayPoint::new(1, 4),
-                DisplayPoint::new(2, 3)..DisplayPoint::new(2, 4)
-            ]
-        );
-
-        view.undo(&Undo, cx);
-        view.undo(&Undo, cx);
-        view.undo(&Undo, cx);
-        assert_eq!(
-            view.text(cx),
-            "
-                a
-                b
-                c
-            "
-            .unindent()
-        );
-        assert_eq!(
-            view.selections.display_ranges(cx),
-            [
-                DisplayPoint::new(0, 0)..DisplayPoint::new(0, 1),
-                DisplayPoint::new(1, 0)..DisplayPoint::new(1, 1),
-                DisplayPoint::new(2, 0)..DisplayPoint::new(2, 1)
-            ]
-        );
 
-        // Ensure inserting the first character of a multi-byte bracket pair
-        // doesn't surround the selections with the bracket.
-        view.handle_input("/", cx);
-        assert_eq!(
-            view.text(cx),
-            "
-                /
-                /
-                /
-            "
-            .unindent()
-        );
-        assert_eq!(
-            view.selections.display_ranges(cx),
-            [
-                DisplayPoint::new(0, 1)..DisplayPoint::new(0, 1),
-                DisplayPoint::new(1, 1)..DisplayPoint::new(1, 1),
-                DisplayPoint::new(2, 1)..DisplayPoint::new(2, 1)
-            ]
-        );
+    let code_actions = project
+        .update(cx, |project, cx| {
+            project.code_actions(&main_buffer, 16..16, cx)
+        })
+        .await
+        .unwrap();
+    editor_handle.update(cx, |editor, cx| {
+        editor.focus(cx);
+        editor.code_actions_task.take();
+        editor.available_code_actions = Some((main_buffer.clone(), code_actions.into()));
+        editor.refactor(&Refactor, cx);
+    });
+    cx.executor().run_until_parked();
 
-        view.undo(&Undo, cx);
-        assert_eq!(
-            view.text(cx),
-            "
-                a
-                b
-                c
-            "
-            .unindent()
-        );
+    // A code action editing several files is previewed, without changing those files.
+    editor_handle
+        .update(cx, |editor, cx| {
+            editor.confirm_code_action(&ConfirmCodeAction { item_ix: Some(0) }, cx)
+        })
+        .unwrap()
+        .await
+        .unwrap();
+    cx.executor().run_until_parked();
+    let preview = workspace
+        .update(cx, |workspace, cx| {
+            workspace.active_item_as::<Editor>(cx).unwrap()
+        })
+        .unwrap();
+    preview.update(cx, |preview, cx| {
+        assert!(preview.refactor_preview.is_some());
+        let multibuffer = preview.buffer().read(cx);
+        assert_eq!(multibuffer.title(cx), "Preview: Rename a");
         assert_eq!(
-            view.selections.display_ranges(cx),
-            [
-                DisplayPoint::new(0, 0)..DisplayPoint::new(0, 1),
-                DisplayPoint::new(1, 0)..DisplayPoint::new(1, 1),
-                DisplayPoint::new(2, 0)..DisplayPoint::new(2, 1)
-            ]
+            multibuffer.snapshot(cx).text(),
+            "fn main() { let value = 5; }\n// Uses value"
         );
+    });
+    main_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "fn main() { let a = 5; }")
+    });
+    other_buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "// Uses a"));
 
-        // Ensure inserting the last character of a multi-byte bracket pair
-        // doesn't surround the selections with the bracket.
-        view.handle_input("*", cx);
-        assert_eq!(
-            view.text(cx),
-            "
-                *
-                *
-                *
-            "
-            .unindent()
-        );
-        assert_eq!(
-            view.selections.display_ranges(cx),
-            [
-                DisplayPoint::new(0, 1)..DisplayPoint::new(0, 1),
-                DisplayPoint::new(1, 1)..DisplayPoint::new(1, 1),
-                DisplayPoint::new(2, 1)..DisplayPoint::new(2, 1)
-            ]
-        );
+    // Applying the refactoring edits the files and closes the preview.
+    preview.update(cx, |preview, cx| {
+        preview.apply_refactoring(&ApplyRefactoring, cx)
     });
+    cx.executor().run_until_parked();
+    main_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "fn main() { let value = 5; }")
+    });
+    other_buffer.update(cx, |buffer, _| assert_eq!(buffer.text(), "// Uses value"));
+    workspace
+        .update(cx, |workspace, cx| {
+            assert!(workspace
+                .items(cx)
+                .all(|item| item.item_id() != preview.entity_id()));
+        })
+        .unwrap();
 }
 
 #[gpui::test]
@@ -6110,6 +7024,36 @@ async fn test_toggle_block_comment(cx: &mut gpui::TestAppContext) {
         "#
         .unindent(),
     );
+
+    // Toggle comments when a single selection spans rows of different languages.
+    cx.set_state(
+        &r#"
+            «<script>
+                var x = new Y();
+            </script>ˇ»
+        "#
+        .unindent(),
+    );
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| editor.toggle_comments(&ToggleComments::default(), cx));
+    cx.assert_editor_state(
+        &r#"
+            <!-- «<script> -->
+                // var x = new Y();
+            <!-- </script>ˇ» -->
+        "#
+        .unindent(),
+    );
+    cx.executor().run_until_parked();
+    cx.update_editor(|editor, cx| editor.toggle_comments(&ToggleComments::default(), cx));
+    cx.assert_editor_state(
+        &r#"
+            «<script>
+                var x = new Y();
+            </script>ˇ»
+        "#
+        .unindent(),
+    );
 }
 
 #[gpui::test]
@@ -8178,6 +9122,174 @@ async fn test_completions_in_languages_with_extra_word_characters(cx: &mut gpui:
     });
 }
 
+#[gpui::test]
+async fn test_completion_ranking(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions::default()),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    fn completion_labels(cx: &mut EditorLspTestContext) -> Vec<String> {
+        cx.update_editor(|editor, _| {
+            if let Some(ContextMenu::Completions(menu)) = editor.context_menu.read().as_ref() {
+                menu.matches.iter().map(|m| m.string.clone()).collect()
+            } else {
+                panic!("expected completion menu to be open");
+            }
+        })
+    }
+
+    // Completions starting with the query are ranked above other fuzzy matches.
+    cx.set_state("baˇ");
+    cx.update_editor(|editor, cx| editor.show_completions(&ShowCompletions, cx));
+    handle_completion_request(&mut cx, "<ba|>", vec!["sub_ba", "ba_sub"]).await;
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    assert_eq!(completion_labels(&mut cx), &["ba_sub", "sub_ba"]);
+    cx.update_editor(|editor, cx| editor.hide_context_menu(cx));
+
+    // With the locality bonus, completions for words closer to the cursor are ranked higher.
+    _ = cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|settings, cx| {
+            settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                settings.completion_ranking = Some(CompletionRankingContent {
+                    prefer_prefix_matches: None,
+                    locality_bonus: Some(true),
+                });
+            });
+        })
+    });
+    cx.set_state(indoc! {"
+        let bottom = 1;
+        let beta = 2;
+        bˇ
+    "});
+    cx.update_editor(|editor, cx| editor.show_completions(&ShowCompletions, cx));
+    handle_completion_request(
+        &mut cx,
+        indoc! {"
+            let bottom = 1;
+            let beta = 2;
+            <b|>
+        "},
+        vec!["bar", "bottom", "beta"],
+    )
+    .await;
+    cx.run_until_parked();
+    assert_eq!(completion_labels(&mut cx), &["beta", "bottom", "bar"]);
+}
+
+#[gpui::test]
+async fn test_completion_accept_keys_and_commit_characters(cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorLspTestContext::new_rust(
+        lsp::ServerCapabilities {
+            completion_provider: Some(lsp::CompletionOptions {
+                trigger_characters: Some(vec![".".to_string()]),
+                all_commit_characters: Some(vec![";".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+        cx,
+    )
+    .await;
+
+    let _requests = cx.handle_request::<lsp::request::Completion, _, _>(|_, _, _| async move {
+        Ok(Some(lsp::CompletionResponse::Array(vec![
+            lsp::CompletionItem {
+                label: "first".to_string(),
+                commit_characters: Some(vec!["(".to_string()]),
+                ..Default::default()
+            },
+            lsp::CompletionItem {
+                label: "second".to_string(),
+                ..Default::default()
+            },
+        ])))
+    });
+
+    fn accept_keys(cx: &mut EditorLspTestContext) -> (bool, bool) {
+        cx.update_editor(|editor, cx| {
+            let key_context = editor.key_context(cx);
+            (
+                key_context.contains("accept_completion_on_tab"),
+                key_context.contains("accept_completion_on_enter"),
+            )
+        })
+    }
+
+    fn update_settings(
+        cx: &mut EditorLspTestContext,
+        f: impl Fn(&mut LanguageSettingsContent) + 'static,
+    ) {
+        _ = cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    f(&mut settings.defaults)
+                });
+            })
+        });
+    }
+
+    // By default, both tab and enter accept completions, even when nothing has been typed.
+    cx.set_state("one.ˇ");
+    cx.update_editor(|editor, cx| editor.show_completions(&ShowCompletions, cx));
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    assert_eq!(accept_keys(&mut cx), (true, true));
+
+    update_settings(&mut cx, |settings| {
+        settings.accept_completion_with = Some(AcceptCompletionWith::Tab);
+    });
+    assert_eq!(accept_keys(&mut cx), (true, false));
+
+    update_settings(&mut cx, |settings| {
+        settings.accept_completion_without_typing = Some(false);
+    });
+    assert_eq!(accept_keys(&mut cx), (false, false));
+
+    // Once something has been typed, commit characters accept the selected completion,
+    // whether they're given by the completion or for all of the server's completions.
+    cx.simulate_keystroke("f");
+    cx.executor().run_until_parked();
+    assert_eq!(accept_keys(&mut cx), (true, false));
+    cx.simulate_keystroke("(");
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("one.first(ˇ");
+
+    cx.set_state("one.ˇ");
+    cx.update_editor(|editor, cx| editor.show_completions(&ShowCompletions, cx));
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    cx.simulate_keystroke("s");
+    cx.executor().run_until_parked();
+    cx.simulate_keystroke(";");
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("one.second;ˇ");
+
+    // Without commit characters, the typed character is only inserted.
+    update_settings(&mut cx, |settings| {
+        settings.use_completion_commit_characters = Some(false);
+    });
+    cx.set_state("one.ˇ");
+    cx.update_editor(|editor, cx| editor.show_completions(&ShowCompletions, cx));
+    cx.condition(|editor, _| editor.context_menu_visible())
+        .await;
+    cx.simulate_keystroke("s");
+    cx.executor().run_until_parked();
+    cx.simulate_keystroke(";");
+    cx.executor().run_until_parked();
+    cx.assert_editor_state("one.s;ˇ");
+}
+
 #[gpui::test]
 async fn test_document_format_with_prettier(cx: &mut gpui::TestAppContext) {
     init_test(cx, |settings| {
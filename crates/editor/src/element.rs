@@ -1,4 +1,5 @@
 use crate::{
+    bracket_pairs::{bracket_color, BracketScopeGuide},
     display_map::{
         BlockContext, BlockStyle, DisplaySnapshot, FoldStatus, HighlightedChunk, ToDisplayPoint,
         TransformBlock,
@@ -24,17 +25,17 @@ use anyhow::Result;
 use collections::{BTreeMap, HashMap};
 use git::diff::DiffHunkStatus;
 use gpui::{
-    div, fill, outline, overlay, point, px, quad, relative, size, transparent_black, Action,
-    AnchorCorner, AnyElement, AvailableSpace, Bounds, ContentMask, Corners, CursorStyle,
-    DispatchPhase, Edges, Element, ElementInputHandler, Entity, Hsla, InteractiveBounds,
-    InteractiveElement, IntoElement, ModifiersChangedEvent, MouseButton, MouseDownEvent,
-    MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, ScrollDelta, ScrollWheelEvent, ShapedLine,
-    SharedString, Size, StackingOrder, StatefulInteractiveElement, Style, Styled, TextRun,
-    TextStyle, View, ViewContext, WindowContext,
+    div, fill, outline, overlay, point, px, quad, relative, size, transparent_black,
+    AccessibilityProperties, AccessibilityRole, Action, AnchorCorner, AnyElement, AvailableSpace,
+    Bounds, ContentMask, Corners, CursorStyle, DispatchPhase, Edges, Element, ElementInputHandler,
+    Entity, Hsla, InteractiveBounds, InteractiveElement, IntoElement, ModifiersChangedEvent,
+    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, ScrollDelta,
+    ScrollWheelEvent, ShapedLine, SharedString, Size, StackingOrder, StatefulInteractiveElement,
+    Style, Styled, TextRun, TextStyle, View, ViewContext, WindowContext,
 };
 use itertools::Itertools;
 use language::language_settings::ShowWhitespaceSetting;
-use multi_buffer::Anchor;
+use multi_buffer::{Anchor, ToOffset};
 use project::{
     project_settings::{GitGutterSetting, ProjectSettings},
     ProjectPath,
@@ -166,6 +167,9 @@ impl EditorElement {
         register_action(view, cx, Editor::convert_to_lower_case);
         register_action(view, cx, Editor::convert_to_title_case);
         register_action(view, cx, Editor::convert_to_snake_case);
+        register_action(view, cx, Editor::convert_to_upper_snake_case);
+        register_action(view, cx, Editor::increment_number);
+        register_action(view, cx, Editor::decrement_number);
         register_action(view, cx, Editor::convert_to_kebab_case);
         register_action(view, cx, Editor::convert_to_upper_camel_case);
         register_action(view, cx, Editor::convert_to_lower_camel_case);
@@ -176,7 +180,19 @@ impl EditorElement {
         register_action(view, cx, Editor::delete_to_beginning_of_line);
         register_action(view, cx, Editor::delete_to_end_of_line);
         register_action(view, cx, Editor::cut_to_end_of_line);
+        register_action(view, cx, Editor::kill_ring_cut);
+        register_action(view, cx, Editor::kill_ring_yank);
+        register_action(view, cx, Editor::kill_ring_yank_pop);
+        register_action(view, cx, Editor::set_mark);
+        register_action(view, cx, Editor::swap_selection_ends);
+        register_action(view, cx, Editor::surround_selections);
+        register_action(view, cx, Editor::change_surrounding_pair);
+        register_action(view, cx, Editor::align_on_delimiter);
+        register_action(view, cx, Editor::refactor);
+        register_action(view, cx, Editor::apply_refactoring);
+        register_action(view, cx, Editor::discard_refactoring);
         register_action(view, cx, Editor::duplicate_line);
+        register_action(view, cx, Editor::duplicate_selection);
         register_action(view, cx, Editor::move_line_up);
         register_action(view, cx, Editor::move_line_down);
         register_action(view, cx, Editor::transpose);
@@ -257,10 +273,15 @@ impl EditorElement {
         register_action(view, cx, Editor::go_to_prev_diagnostic);
         register_action(view, cx, Editor::go_to_hunk);
         register_action(view, cx, Editor::go_to_prev_hunk);
+        register_action(view, cx, Editor::toggle_bookmark);
+        register_action(view, cx, Editor::go_to_next_bookmark);
+        register_action(view, cx, Editor::go_to_prev_bookmark);
         register_action(view, cx, Editor::go_to_definition);
         register_action(view, cx, Editor::go_to_definition_split);
         register_action(view, cx, Editor::go_to_type_definition);
         register_action(view, cx, Editor::go_to_type_definition_split);
+        register_action(view, cx, Editor::peek_definition);
+        register_action(view, cx, Editor::peek_references);
         register_action(view, cx, Editor::fold);
         register_action(view, cx, Editor::fold_at);
         register_action(view, cx, Editor::unfold_lines);
@@ -270,8 +291,17 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_code_actions);
         register_action(view, cx, Editor::open_excerpts);
         register_action(view, cx, Editor::toggle_soft_wrap);
+        register_action(view, cx, Editor::make_read_only);
+        register_action(view, cx, Editor::make_writable);
         register_action(view, cx, Editor::toggle_inlay_hints);
         register_action(view, cx, hover_popover::hover);
+        register_action(view, cx, hover_popover::scroll_hover_up);
+        register_action(view, cx, hover_popover::scroll_hover_down);
+        register_action(view, cx, hover_popover::scroll_hover_page_up);
+        register_action(view, cx, hover_popover::scroll_hover_page_down);
+        register_action(view, cx, Editor::show_signature_help);
+        register_action(view, cx, Editor::signature_help_next);
+        register_action(view, cx, Editor::signature_help_prev);
         register_action(view, cx, Editor::reveal_in_finder);
         register_action(view, cx, Editor::copy_path);
         register_action(view, cx, Editor::copy_relative_path);
@@ -634,6 +664,48 @@ impl EditorElement {
         cx.notify()
     }
 
+    /// Describes the editor to assistive technologies. Only the visible text is exposed,
+    /// along with the newest selection within it.
+    fn accessibility_properties(
+        &self,
+        layout: &LayoutState,
+        cx: &WindowContext,
+    ) -> AccessibilityProperties {
+        let editor = self.editor.read(cx);
+        let buffer = &layout.position_map.snapshot.buffer_snapshot;
+        let visible_range = layout.visible_anchor_range.start.to_offset(buffer)
+            ..layout.visible_anchor_range.end.to_offset(buffer);
+        let value = buffer
+            .text_for_range(visible_range.clone())
+            .collect::<String>();
+
+        let selection = editor.selections.newest::<usize>(cx);
+        let utf16_offset = |offset: usize| {
+            let offset = offset.clamp(visible_range.start, visible_range.end) - visible_range.start;
+            value[..offset].encode_utf16().count()
+        };
+        let selected_range = utf16_offset(selection.start)..utf16_offset(selection.end);
+
+        let (role, label) = match layout.mode {
+            EditorMode::SingleLine | EditorMode::AutoHeight { .. } => (
+                AccessibilityRole::TextField,
+                editor.placeholder_text().map(SharedString::from),
+            ),
+            EditorMode::Full => (
+                AccessibilityRole::TextArea,
+                Some(editor.buffer().read(cx).title(cx).to_string().into()),
+            ),
+        };
+
+        AccessibilityProperties {
+            role,
+            label,
+            value: Some(value.into()),
+            selected_range: Some(selected_range),
+            selected: false,
+        }
+    }
+
     fn paint_background(
         &self,
         gutter_bounds: Bounds<Pixels>,
@@ -715,9 +787,66 @@ impl EditorElement {
                     color,
                 ));
             }
+
+            if let Some(guide) = &layout.bracket_scope_guide {
+                let x = text_bounds.origin.x + layout.gutter_margin + guide.x - scroll_left;
+                if x >= text_bounds.origin.x {
+                    cx.paint_quad(fill(
+                        Bounds {
+                            origin: point(
+                                x,
+                                text_bounds.origin.y
+                                    + layout.position_map.line_height * guide.rows.start as f32
+                                    - scroll_top,
+                            ),
+                            size: size(
+                                px(1.),
+                                layout.position_map.line_height * guide.rows.len() as f32,
+                            ),
+                        },
+                        guide.color,
+                    ));
+                }
+            }
         }
     }
 
+    /// Lays out the guide between the lines of a bracket pair, at the indentation of the line
+    /// with the opening bracket. Pairs without lines between them have no guide.
+    fn layout_bracket_scope_guide(
+        guide: &BracketScopeGuide,
+        snapshot: &EditorSnapshot,
+        em_width: Pixels,
+        cx: &WindowContext,
+    ) -> Option<BracketScopeGuideLayout> {
+        let buffer = &snapshot.buffer_snapshot;
+        let open = guide.open.to_point(buffer);
+        let open_row = open.to_display_point(snapshot).row();
+        let close_row = guide
+            .close
+            .to_point(buffer)
+            .to_display_point(snapshot)
+            .row();
+        if close_row <= open_row + 1 {
+            return None;
+        }
+
+        let indent = buffer.indent_size_for_line(open.row).len;
+        let column = Point::new(open.row, indent)
+            .to_display_point(snapshot)
+            .column();
+        let color = if EditorSettings::get_global(cx).bracket_pairs.colorize {
+            bracket_color(guide.depth, cx)
+        } else {
+            cx.theme().colors().editor_active_wrap_guide
+        };
+        Some(BracketScopeGuideLayout {
+            rows: open_row + 1..close_row,
+            x: em_width * column as f32,
+            color,
+        })
+    }
+
     fn paint_gutter(
         &mut self,
         bounds: Bounds<Pixels>,
@@ -774,6 +903,31 @@ impl EditorElement {
                 }
             }
 
+            let code_actions_row = layout
+                .code_actions_indicator
+                .as_ref()
+                .map(|indicator| indicator.row);
+            for row in layout.bookmark_rows.drain(..) {
+                // The code actions indicator takes precedence over the bookmark icon.
+                if Some(row) == code_actions_row {
+                    continue;
+                }
+                let mut icon = Icon::new(IconName::Bookmark)
+                    .size(IconSize::XSmall)
+                    .color(Color::Accent)
+                    .into_any_element();
+                let available_space = size(
+                    AvailableSpace::MinContent,
+                    AvailableSpace::Definite(line_height),
+                );
+                let icon_size = icon.measure(available_space, cx);
+
+                let x = ((layout.gutter_padding + layout.gutter_margin) - icon_size.width) / 2.;
+                let y =
+                    row as f32 * line_height - scroll_top + (line_height - icon_size.height) / 2.;
+                icon.draw(bounds.origin + point(x, y), available_space, cx);
+            }
+
             if let Some(indicator) = layout.code_actions_indicator.take() {
                 let mut button = indicator.button.into_any_element();
                 let available_space = size(
@@ -1061,11 +1215,18 @@ impl EditorElement {
                                     .line;
                                 let cursor_column = cursor_position.column() as usize;
 
-                                let cursor_character_x =
+                                let mut cursor_character_x =
                                     cursor_row_layout.x_for_index(cursor_column);
                                 let mut block_width = cursor_row_layout
                                     .x_for_index(cursor_column + 1)
                                     - cursor_character_x;
+                                if block_width < Pixels::ZERO {
+                                    // Right-to-left characters extend to the left of the cursor.
+                                    if selection.cursor_shape != CursorShape::Bar {
+                                        cursor_character_x += block_width;
+                                    }
+                                    block_width = -block_width;
+                                }
                                 if block_width == Pixels::ZERO {
                                     block_width = layout.position_map.em_width;
                                 }
@@ -1168,6 +1329,34 @@ impl EditorElement {
     ) {
         let content_origin = text_bounds.origin + point(layout.gutter_margin, Pixels::ZERO);
         let start_row = layout.visible_display_row_range.start;
+        // Signature help is painted first, so that the completions menu covers it when there's
+        // no room above the cursor and both are shown below it.
+        if let Some((position, mut signature_help)) = layout.signature_help.take() {
+            let available_space = size(AvailableSpace::MinContent, AvailableSpace::MinContent);
+            let signature_help_size = signature_help.measure(available_space, cx);
+
+            let cursor_row_layout =
+                &layout.position_map.line_layouts[(position.row() - start_row) as usize].line;
+            let x = cursor_row_layout.x_for_index(position.column() as usize)
+                - layout.position_map.scroll_position.x;
+            let y = position.row() as f32 * layout.position_map.line_height
+                - layout.position_map.scroll_position.y;
+            let mut origin = content_origin + point(x, y - signature_help_size.height);
+
+            // Show it below the cursor's line when there's no room above it.
+            if origin.y < text_bounds.origin.y {
+                origin.y = content_origin.y + y + layout.position_map.line_height;
+            }
+
+            let x_out_of_bounds =
+                text_bounds.upper_right().x - (origin.x + signature_help_size.width);
+            if x_out_of_bounds < Pixels::ZERO {
+                origin.x = (origin.x + x_out_of_bounds).max(text_bounds.origin.x);
+            }
+
+            cx.break_content_mask(|cx| signature_help.draw(origin, available_space, cx));
+        }
+
         if let Some((position, mut context_menu)) = layout.context_menu.take() {
             let available_space = size(AvailableSpace::MinContent, AvailableSpace::MinContent);
             let context_menu_size = context_menu.measure(available_space, cx);
@@ -1333,80 +1522,74 @@ impl EditorElement {
                 cx.theme().colors().scrollbar_track_border,
             ));
             let scrollbar_settings = EditorSettings::get_global(cx).scrollbar;
+            let snapshot = &layout.position_map.snapshot;
+            let row_count = layout.max_row + 1;
+            let buffer_rows_for_display_rows = |rows: Range<u32>| {
+                let start = DisplayPoint::new(rows.start, 0).to_point(snapshot).row;
+                let end = if rows.end > layout.max_row {
+                    snapshot.buffer_snapshot.max_point().row + 1
+                } else {
+                    DisplayPoint::new(rows.end, 0).to_point(snapshot).row
+                };
+                start..end.max(start + 1)
+            };
+            let mut markers = Vec::new();
+
             if layout.is_singleton && scrollbar_settings.selections {
-                let start_anchor = Anchor::min();
-                let end_anchor = Anchor::max();
-                let background_ranges = self
-                    .editor
-                    .read(cx)
-                    .background_highlight_row_ranges::<BufferSearchHighlights>(
-                        start_anchor..end_anchor,
-                        &layout.position_map.snapshot,
-                        50000,
-                    );
-                for range in background_ranges {
-                    let start_y = y_for_row(range.start().row() as f32);
-                    let mut end_y = y_for_row(range.end().row() as f32);
-                    if end_y - start_y < px(1.) {
-                        end_y = start_y + px(1.);
-                    }
-                    let bounds = Bounds::from_corners(point(left, start_y), point(right, end_y));
-                    cx.paint_quad(quad(
-                        bounds,
-                        Corners::default(),
-                        cx.theme().status().info,
-                        Edges {
-                            top: Pixels::ZERO,
-                            right: px(1.),
-                            bottom: Pixels::ZERO,
-                            left: px(1.),
-                        },
-                        cx.theme().colors().scrollbar_thumb_border,
-                    ));
-                }
+                let editor = self.editor.read(cx);
+                let color = cx.theme().status().info;
+                markers.extend(scrollbar_markers(row_count, height, |rows| {
+                    let rows = buffer_rows_for_display_rows(rows);
+                    let buffer = &snapshot.buffer_snapshot;
+                    let range = buffer.anchor_before(Point::new(rows.start, 0))
+                        ..buffer.anchor_before(Point::new(rows.end, 0));
+                    editor
+                        .has_background_highlights_in_range::<BufferSearchHighlights>(range, buffer)
+                        .then_some(color)
+                }));
             }
 
             if layout.is_singleton && scrollbar_settings.git_diff {
-                for hunk in layout
-                    .position_map
-                    .snapshot
-                    .buffer_snapshot
-                    .git_diff_hunks_in_range(0..(max_row.floor() as u32))
-                {
-                    let start_display = Point::new(hunk.buffer_range.start, 0)
-                        .to_display_point(&layout.position_map.snapshot.display_snapshot);
-                    let end_display = Point::new(hunk.buffer_range.end, 0)
-                        .to_display_point(&layout.position_map.snapshot.display_snapshot);
-                    let start_y = y_for_row(start_display.row() as f32);
-                    let mut end_y = if hunk.buffer_range.start == hunk.buffer_range.end {
-                        y_for_row((end_display.row() + 1) as f32)
-                    } else {
-                        y_for_row((end_display.row()) as f32)
-                    };
-
-                    if end_y - start_y < px(1.) {
-                        end_y = start_y + px(1.);
-                    }
-                    let bounds = Bounds::from_corners(point(left, start_y), point(right, end_y));
-
-                    let color = match hunk.status() {
+                markers.extend(scrollbar_markers(row_count, height, |rows| {
+                    let rows = buffer_rows_for_display_rows(rows);
+                    let hunk = snapshot
+                        .buffer_snapshot
+                        .git_diff_hunks_in_range(rows.clone())
+                        .find(|hunk| {
+                            let hunk_rows = &hunk.buffer_range;
+                            if hunk_rows.is_empty() {
+                                rows.contains(&hunk_rows.start)
+                            } else {
+                                hunk_rows.start < rows.end && hunk_rows.end > rows.start
+                            }
+                        })?;
+                    Some(match hunk.status() {
                         DiffHunkStatus::Added => cx.theme().status().created,
                         DiffHunkStatus::Modified => cx.theme().status().modified,
                         DiffHunkStatus::Removed => cx.theme().status().deleted,
-                    };
-                    cx.paint_quad(quad(
-                        bounds,
-                        Corners::default(),
-                        color,
-                        Edges {
-                            top: Pixels::ZERO,
-                            right: px(1.),
-                            bottom: Pixels::ZERO,
-                            left: px(1.),
-                        },
-                        cx.theme().colors().scrollbar_thumb_border,
-                    ));
+                    })
+                }));
+            }
+
+            for (rows, color) in markers {
+                let start_y = y_for_row(rows.start as f32);
+                let mut end_y = y_for_row(rows.end as f32);
+                if end_y - start_y < px(1.) {
+                    end_y = start_y + px(1.);
                 }
+                let bounds = Bounds::from_corners(point(left, start_y), point(right, end_y));
+                cx.paint_quad(quad(
+                    bounds,
+                    Corners::default(),
+                    color,
+                    Edges {
+                        top: Pixels::ZERO,
+                        right: px(1.),
+                        bottom: Pixels::ZERO,
+                        left: px(1.),
+                    },
+                    cx.theme().colors().scrollbar_thumb_border,
+                ));
             }
 
             cx.paint_quad(quad(
@@ -1534,6 +1717,9 @@ impl EditorElement {
                 cmp::max(range.start.row(), start_row)..cmp::min(range.end.row() + 1, end_row)
             };
 
+            // In rows mixing both text directions, the selected characters can be spread
+            // over several disjoint ranges, which are painted separately.
+            let mut extra_ranges = Vec::new();
             let highlighted_range = HighlightedRange {
                 color,
                 line_height: layout.position_map.line_height,
@@ -1546,21 +1732,49 @@ impl EditorElement {
                     .map(|row| {
                         let line_layout =
                             &layout.position_map.line_layouts[(row - start_row) as usize].line;
+                        let line_origin_x =
+                            content_origin.x - layout.position_map.scroll_position.x;
+                        if line_layout.has_rtl_runs() {
+                            let start_ix = if row == range.start.row() {
+                                range.start.column() as usize
+                            } else {
+                                0
+                            };
+                            let end_ix = if row == range.end.row() {
+                                range.end.column() as usize
+                            } else {
+                                line_layout.len
+                            };
+                            let mut x_ranges = line_layout
+                                .x_ranges_for_range(start_ix..end_ix)
+                                .into_iter()
+                                .map(|x_range| {
+                                    let mut end_x = line_origin_x + x_range.end;
+                                    if row != range.end.row() && x_range.end == line_layout.width {
+                                        end_x += line_end_overshoot;
+                                    }
+                                    HighlightedRangeLine {
+                                        start_x: line_origin_x + x_range.start,
+                                        end_x,
+                                    }
+                                });
+                            if let Some(first_range) = x_ranges.next() {
+                                extra_ranges.extend(x_ranges.map(|line| (row, line)));
+                                return first_range;
+                            }
+                        }
+
                         HighlightedRangeLine {
                             start_x: if row == range.start.row() {
-                                content_origin.x
+                                line_origin_x
                                     + line_layout.x_for_index(range.start.column() as usize)
-                                    - layout.position_map.scroll_position.x
                             } else {
-                                content_origin.x - layout.position_map.scroll_position.x
+                                line_origin_x
                             },
                             end_x: if row == range.end.row() {
-                                content_origin.x
-                                    + line_layout.x_for_index(range.end.column() as usize)
-                                    - layout.position_map.scroll_position.x
+                                line_origin_x + line_layout.x_for_index(range.end.column() as usize)
                             } else {
-                                content_origin.x + line_layout.width + line_end_overshoot
-                                    - layout.position_map.scroll_position.x
+                                line_origin_x + line_layout.width + line_end_overshoot
                             },
                         }
                     })
@@ -1568,6 +1782,17 @@ impl EditorElement {
             };
 
             highlighted_range.paint(bounds, cx);
+            for (row, line) in extra_ranges {
+                HighlightedRange {
+                    color,
+                    line_height: layout.position_map.line_height,
+                    corner_radius,
+                    start_y: content_origin.y + row as f32 * layout.position_map.line_height
+                        - layout.position_map.scroll_position.y,
+                    lines: vec![line],
+                }
+                .paint(bounds, cx);
+            }
         }
     }
 
@@ -1911,11 +2136,29 @@ impl EditorElement {
             let is_singleton = editor.is_singleton(cx);
 
             let highlighted_rows = editor.highlighted_rows();
-            let highlighted_ranges = editor.background_highlights_in_range(
+            let mut highlighted_ranges = editor.background_highlights_in_range(
                 start_anchor..end_anchor,
                 &snapshot.display_snapshot,
                 cx.theme().colors(),
             );
+            if editor
+                .buffer
+                .read(cx)
+                .settings_at(0, cx)
+                .highlight_trailing_whitespace
+            {
+                let color = cx.theme().status().error_background;
+                highlighted_ranges.extend(
+                    editor
+                        .trailing_whitespace_in_range(
+                            start_row..end_row,
+                            &snapshot.display_snapshot,
+                            cx,
+                        )
+                        .into_iter()
+                        .map(|range| (range, color)),
+                );
+            }
 
             let mut newest_selection_head = None;
 
@@ -2157,6 +2400,8 @@ impl EditorElement {
                 }
             }
 
+            let bookmark_rows = editor.bookmark_display_rows(&snapshot, start_row..end_row, cx);
+
             let visible_rows = start_row..start_row + line_layouts.len() as u32;
             let max_size = size(
                 (120. * em_width) // Default size
@@ -2180,6 +2425,23 @@ impl EditorElement {
             )
             };
 
+            // Hover popovers take precedence, as they're shown in the same place.
+            let signature_help = if hover.is_some() {
+                None
+            } else {
+                newest_selection_head
+                    .filter(|head| (start_row..end_row).contains(&head.row()))
+                    .and_then(|head| {
+                        let element = editor.signature_help_state.render(
+                            &style,
+                            max_size,
+                            editor.workspace.as_ref().map(|(w, _)| w.clone()),
+                            cx,
+                        )?;
+                        Some((head, element))
+                    })
+            };
+
             let editor_view = cx.view().clone();
             let fold_indicators = cx.with_element_context(|cx| {
 
@@ -2195,6 +2457,11 @@ impl EditorElement {
             })
             });
 
+            let bracket_scope_guide = editor
+                .bracket_scope_guide
+                .as_ref()
+                .and_then(|guide| Self::layout_bracket_scope_guide(guide, &snapshot, em_width, cx));
+
             let invisible_symbol_font_size = font_size / 2.;
             let tab_invisible = cx
                 .text_system()
@@ -2243,6 +2510,7 @@ impl EditorElement {
                 visible_anchor_range: start_anchor..end_anchor,
                 visible_display_row_range: start_row..end_row,
                 wrap_guides,
+                bracket_scope_guide,
                 gutter_size,
                 gutter_padding: gutter_dimensions.padding,
                 text_size,
@@ -2260,10 +2528,12 @@ impl EditorElement {
                 selections,
                 context_menu,
                 code_actions_indicator,
+                bookmark_rows,
                 fold_indicators,
                 tab_invisible,
                 space_invisible,
                 hover_popovers: hover,
+                signature_help,
             }
         })
     }
@@ -2962,45 +3232,57 @@ impl Element for EditorElement {
 
                     let focus_handle = editor.focus_handle(cx);
                     let key_context = self.editor.read(cx).key_context(cx);
+                    let accessibility = self.accessibility_properties(&layout, cx);
                     cx.with_key_dispatch(Some(key_context), Some(focus_handle.clone()), |_, cx| {
+                        cx.register_mouse_binding_target(bounds);
                         self.register_actions(cx);
                         self.register_key_listeners(cx);
 
-                        cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
-                            cx.handle_input(
-                                &focus_handle,
-                                ElementInputHandler::new(bounds, self.editor.clone()),
-                            );
-
-                            self.paint_background(gutter_bounds, text_bounds, &layout, cx);
-                            if layout.gutter_size.width > Pixels::ZERO {
-                                self.paint_gutter(gutter_bounds, &mut layout, cx);
-                            }
-                            self.paint_text(text_bounds, &mut layout, cx);
-
-                            cx.with_z_index(0, |cx| {
-                                self.paint_mouse_listeners(
-                                    bounds,
-                                    gutter_bounds,
-                                    text_bounds,
-                                    &layout,
-                                    cx,
-                                );
-                            });
-                            if !layout.blocks.is_empty() {
-                                cx.with_z_index(0, |cx| {
-                                    cx.with_element_id(Some("editor_blocks"), |cx| {
-                                        self.paint_blocks(bounds, &mut layout, cx);
+                        let focus_handle = &focus_handle;
+                        cx.with_accessibility_node(
+                            Some(accessibility),
+                            bounds,
+                            Some(focus_handle),
+                            |cx| {
+                                cx.with_content_mask(Some(ContentMask { bounds }), |cx| {
+                                    cx.handle_input(
+                                        focus_handle,
+                                        ElementInputHandler::new(bounds, self.editor.clone()),
+                                    );
+
+                                    self.paint_background(gutter_bounds, text_bounds, &layout, cx);
+                                    if layout.gutter_size.width > Pixels::ZERO {
+                                        self.paint_gutter(gutter_bounds, &mut layout, cx);
+                                    }
+                                    self.paint_text(text_bounds, &mut layout, cx);
+
+                                    cx.with_z_index(0, |cx| {
+                                        self.paint_mouse_listeners(
+                                            bounds,
+                                            gutter_bounds,
+                                            text_bounds,
+                                            &layout,
+                                            cx,
+                                        );
                                     });
-                                })
-                            }
+                                    if !layout.blocks.is_empty() {
+                                        cx.with_z_index(0, |cx| {
+                                            cx.with_element_id(Some("editor_blocks"), |cx| {
+                                                self.paint_blocks(bounds, &mut layout, cx);
+                                            });
+                                        })
+                                    }
 
-                            cx.with_z_index(1, |cx| {
-                                self.paint_overlays(text_bounds, &mut layout, cx);
-                            });
+                                    cx.with_z_index(1, |cx| {
+                                        self.paint_overlays(text_bounds, &mut layout, cx);
+                                    });
 
-                            cx.with_z_index(2, |cx| self.paint_scrollbar(bounds, &mut layout, cx));
-                        });
+                                    cx.with_z_index(2, |cx| {
+                                        self.paint_scrollbar(bounds, &mut layout, cx)
+                                    });
+                                })
+                            },
+                        );
                     })
                 },
             )
@@ -3030,6 +3312,7 @@ pub struct LayoutState {
     text_size: gpui::Size<Pixels>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
+    bracket_scope_guide: Option<BracketScopeGuideLayout>,
     visible_anchor_range: Range<Anchor>,
     visible_display_row_range: Range<u32>,
     active_rows: BTreeMap<u32, bool>,
@@ -3045,12 +3328,20 @@ pub struct LayoutState {
     max_row: u32,
     context_menu: Option<(DisplayPoint, AnyElement)>,
     code_actions_indicator: Option<CodeActionsIndicator>,
+    bookmark_rows: Vec<u32>,
     hover_popovers: Option<(DisplayPoint, Vec<AnyElement>)>,
+    signature_help: Option<(DisplayPoint, AnyElement)>,
     fold_indicators: Vec<Option<IconButton>>,
     tab_invisible: ShapedLine,
     space_invisible: ShapedLine,
 }
 
+struct BracketScopeGuideLayout {
+    rows: Range<u32>,
+    x: Pixels,
+    color: Hsla,
+}
+
 struct CodeActionsIndicator {
     row: u32,
     button: IconButton,
@@ -3143,6 +3434,36 @@ struct BlockLayout {
     style: BlockStyle,
 }
 
+/// Splits the editor's display rows into at most one bucket per pixel of the scrollbar
+/// track and returns the runs of adjacent buckets that `marker` gives the same color.
+///
+/// Querying decorations per bucket, rather than materializing every hunk or highlight in
+/// the buffer, bounds the work done for the scrollbar by its height, so that buffers with
+/// tens of thousands of them can still be drawn every frame.
+fn scrollbar_markers(
+    row_count: u32,
+    track_height: Pixels,
+    mut marker: impl FnMut(Range<u32>) -> Option<Hsla>,
+) -> Vec<(Range<u32>, Hsla)> {
+    let bucket_count = (f32::from(track_height).ceil() as u32).max(1);
+    let rows_per_bucket = ((row_count + bucket_count - 1) / bucket_count).max(1);
+    let mut markers: Vec<(Range<u32>, Hsla)> = Vec::new();
+    let mut start = 0;
+    while start < row_count {
+        let end = (start + rows_per_bucket).min(row_count);
+        if let Some(color) = marker(start..end) {
+            match markers.last_mut() {
+                Some((rows, last_color)) if rows.end == start && *last_color == color => {
+                    rows.end = end;
+                }
+                _ => markers.push((start..end, color)),
+            }
+        }
+        start = end;
+    }
+    markers
+}
+
 fn layout_line(
     row: u32,
     snapshot: &EditorSnapshot,
@@ -3732,6 +4053,38 @@ mod tests {
         .unwrap()
     }
 
+    #[test]
+    fn test_scrollbar_markers() {
+        let red = gpui::red();
+        let blue = gpui::blue();
+
+        // With fewer rows than pixels, each row is queried on its own.
+        let mut queried_rows = Vec::new();
+        let markers = scrollbar_markers(6, px(100.), |rows| {
+            queried_rows.push(rows.clone());
+            match rows.start {
+                1 | 2 => Some(red),
+                3 => Some(blue),
+                5 => Some(red),
+                _ => None,
+            }
+        });
+        assert_eq!(
+            queried_rows,
+            (0..6).map(|row| row..row + 1).collect::<Vec<_>>()
+        );
+        assert_eq!(markers, vec![(1..3, red), (3..4, blue), (5..6, red)]);
+
+        // With many more rows than pixels, there is one query per pixel.
+        let mut query_count = 0;
+        let markers = scrollbar_markers(100_000, px(200.), |rows| {
+            query_count += 1;
+            (rows.start < 50_000).then_some(red)
+        });
+        assert_eq!(query_count, 200);
+        assert_eq!(markers, vec![(0..50_000, red)]);
+    }
+
     #[gpui::test]
     fn test_all_invisibles_drawing(cx: &mut TestAppContext) {
         const TAB_SIZE: u32 = 4;
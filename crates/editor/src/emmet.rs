@@ -0,0 +1,997 @@
+//! Emmet abbreviations expand to markup or stylesheet snippets, such as `ul>li*3` to a list with
+//! three items or `m10` to `margin: 10px;`. An abbreviation before the cursor is offered as the
+//! first completion in languages with the `emmet` setting enabled, so that tab expands it.
+
+use gpui::{AppContext, Model};
+use language::{
+    language_settings::language_settings, Buffer, CodeLabel, Completion, Documentation, Point,
+    ToOffset, ToPoint,
+};
+use lsp::LanguageServerId;
+use snippet::Snippet;
+
+/// The server id given to Emmet expansions, which don't come from a language server.
+pub(crate) const EMMET_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX - 1);
+
+/// The largest number of repetitions of a single node, as in `li*1000`.
+const MAX_REPEAT: usize = 1000;
+
+/// The largest number of elements that an abbreviation expands to, counting nested repetitions,
+/// so that abbreviations such as `(a*1000)*1000` aren't expanded.
+const MAX_EXPANDED_NODES: usize = 1000;
+
+/// Expansions with more lines than this aren't previewed in the completion's documentation.
+const MAX_PREVIEW_LINES: usize = 100;
+
+/// The flavor of output that abbreviations expand to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Syntax {
+    Markup,
+    Jsx,
+    Stylesheet,
+}
+
+impl Syntax {
+    fn for_language(language_name: Option<&str>) -> Self {
+        match language_name {
+            Some("CSS" | "SCSS" | "Sass" | "Less") => Self::Stylesheet,
+            Some("JavaScript" | "TSX") => Self::Jsx,
+            _ => Self::Markup,
+        }
+    }
+}
+
+/// Returns a completion that expands the abbreviation before the position, if there is one.
+pub(crate) fn emmet_completion(
+    buffer: &Model<Buffer>,
+    position: text::Anchor,
+    cx: &AppContext,
+) -> Option<Completion> {
+    let buffer = buffer.read(cx);
+    let language = buffer.language_at(position);
+    let settings = language_settings(language.as_ref(), buffer.file(), cx);
+    if !settings.emmet {
+        return None;
+    }
+    let syntax = Syntax::for_language(language.as_ref().map(|l| l.name()).as_deref());
+    let indent = if settings.hard_tabs {
+        "\t".to_string()
+    } else {
+        " ".repeat(settings.tab_size.get() as usize)
+    };
+
+    let snapshot = buffer.snapshot();
+    let offset = position.to_offset(&snapshot);
+    let line_start = snapshot.point_to_offset(Point::new(offset.to_point(&snapshot).row, 0));
+    let line = snapshot
+        .text_for_range(line_start..offset)
+        .collect::<String>();
+    let abbreviation_start = abbreviation_start(&line, syntax)?;
+    let abbreviation = &line[abbreviation_start..];
+    let expansion = expand(abbreviation, syntax, &indent)?;
+    let documentation = if expansion.lines().count() <= MAX_PREVIEW_LINES {
+        Some(Documentation::MultiLinePlainText(
+            Snippet::parse(&expansion).ok()?.text,
+        ))
+    } else {
+        None
+    };
+
+    // Continuation lines keep the indentation of the line the abbreviation is on.
+    let line_indent = &line[..line.len() - line.trim_start().len()];
+    let new_text = expansion.replace('\n', &format!("\n{line_indent}"));
+
+    // The menu filters by the word being typed, which is the end of the abbreviation.
+    let word_len = abbreviation
+        .chars()
+        .rev()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .map(char::len_utf8)
+        .sum::<usize>();
+
+    Some(Completion {
+        old_range: snapshot.anchor_before(line_start + abbreviation_start)
+            ..snapshot.anchor_after(offset),
+        new_text,
+        label: CodeLabel {
+            text: abbreviation.to_string(),
+            runs: Vec::new(),
+            filter_range: abbreviation.len() - word_len..abbreviation.len(),
+        },
+        server_id: EMMET_SERVER_ID,
+        documentation,
+        lsp_completion: lsp::CompletionItem {
+            label: abbreviation.to_string(),
+            kind: Some(lsp::CompletionItemKind::SNIPPET),
+            detail: Some("Emmet Abbreviation".to_string()),
+            insert_text_format: Some(lsp::InsertTextFormat::SNIPPET),
+            ..Default::default()
+        },
+    })
+}
+
+/// Returns where the abbreviation ending at the end of the line starts.
+fn abbreviation_start(line: &str, syntax: Syntax) -> Option<usize> {
+    let start = if syntax == Syntax::Stylesheet {
+        line.len()
+            - line
+                .chars()
+                .rev()
+                .take_while(|c| c.is_alphanumeric() || matches!(c, '#' | '.' | '-' | '%'))
+                .map(char::len_utf8)
+                .sum::<usize>()
+    } else {
+        // Abbreviations end at whitespace, except for whitespace in text and attributes.
+        let mut depth = 0_usize;
+        let mut start = 0;
+        for (ix, c) in line.char_indices().rev() {
+            match c {
+                '}' | ']' => depth += 1,
+                '{' | '[' => depth = depth.saturating_sub(1),
+                c if c.is_whitespace() && depth == 0 => {
+                    start = ix + c.len_utf8();
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        // Abbreviations can directly follow a tag, but can't be within one.
+        match line.rfind('<') {
+            Some(tag_start) => start.max(tag_start + line[tag_start..].find('>')? + 1),
+            None => start,
+        }
+    };
+
+    (start < line.len()).then_some(start)
+}
+
+fn expand(abbreviation: &str, syntax: Syntax, indent: &str) -> Option<String> {
+    match syntax {
+        Syntax::Stylesheet => expand_stylesheet(abbreviation),
+        Syntax::Markup | Syntax::Jsx => expand_markup(abbreviation, syntax, indent),
+    }
+}
+
+#[derive(Debug)]
+struct Node {
+    kind: NodeKind,
+    repeat: usize,
+    children: Vec<Node>,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Element(Element),
+    Group(Vec<Node>),
+}
+
+#[derive(Debug, Default)]
+struct Element {
+    name: String,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, Option<String>)>,
+    text: Option<String>,
+}
+
+impl Element {
+    fn is_text(&self) -> bool {
+        self.name.is_empty()
+            && self.id.is_none()
+            && self.classes.is_empty()
+            && self.attributes.is_empty()
+            && self.text.is_some()
+    }
+}
+
+struct Parser {
+    chars: Vec<char>,
+    ix: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.ix).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let eaten = self.peek() == Some(c);
+        if eaten {
+            self.ix += 1;
+        }
+        eaten
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.ix;
+        while self.peek().map_or(false, &predicate) {
+            self.ix += 1;
+        }
+        self.chars[start..self.ix].iter().collect()
+    }
+
+    fn take_name(&mut self) -> Option<String> {
+        let name =
+            self.take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | ':' | '$' | '@'));
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// Parses siblings up to the end of the abbreviation or of the enclosing group.
+    fn parse_expression(&mut self, in_group: bool) -> Option<Vec<Node>> {
+        // Each level holds the children of the last node on the level below it.
+        let mut levels = vec![Vec::<Node>::new()];
+        loop {
+            let node = self.parse_item()?;
+            levels.last_mut()?.push(node);
+            match self.peek() {
+                None => break,
+                Some(')') if in_group => break,
+                Some('+') => self.ix += 1,
+                Some('>') => {
+                    self.ix += 1;
+                    if matches!(levels.last()?.last()?.kind, NodeKind::Group(_)) {
+                        return None;
+                    }
+                    levels.push(Vec::new());
+                }
+                Some('^') => {
+                    while self.eat('^') {
+                        if levels.len() > 1 {
+                            let children = levels.pop()?;
+                            levels.last_mut()?.last_mut()?.children = children;
+                        }
+                    }
+                }
+                Some(_) => return None,
+            }
+        }
+
+        while levels.len() > 1 {
+            let children = levels.pop()?;
+            levels.last_mut()?.last_mut()?.children = children;
+        }
+        levels.pop()
+    }
+
+    fn parse_item(&mut self) -> Option<Node> {
+        let kind = if self.eat('(') {
+            let nodes = self.parse_expression(true)?;
+            if !self.eat(')') {
+                return None;
+            }
+            NodeKind::Group(nodes)
+        } else {
+            NodeKind::Element(self.parse_element()?)
+        };
+        let repeat = if self.eat('*') {
+            self.take_while(|c| c.is_ascii_digit())
+                .parse()
+                .ok()
+                .filter(|repeat| (1..=MAX_REPEAT).contains(repeat))?
+        } else {
+            1
+        };
+        Some(Node {
+            kind,
+            repeat,
+            children: Vec::new(),
+        })
+    }
+
+    fn parse_element(&mut self) -> Option<Element> {
+        let mut element = Element {
+            name: self
+                .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | ':' | '$' | '@' | '!')),
+            ..Default::default()
+        };
+        loop {
+            match self.peek() {
+                Some('#') => {
+                    self.ix += 1;
+                    element.id = Some(self.take_name()?);
+                }
+                Some('.') => {
+                    self.ix += 1;
+                    element.classes.push(self.take_name()?);
+                }
+                Some('[') => {
+                    self.ix += 1;
+                    self.parse_attributes(&mut element.attributes)?;
+                }
+                Some('{') => {
+                    self.ix += 1;
+                    element.text = Some(self.take_while(|c| c != '}'));
+                    if !self.eat('}') {
+                        return None;
+                    }
+                }
+                _ => break,
+            }
+        }
+
+        let is_empty = element.name.is_empty()
+            && element.id.is_none()
+            && element.classes.is_empty()
+            && element.attributes.is_empty()
+            && element.text.is_none();
+        (!is_empty).then_some(element)
+    }
+
+    fn parse_attributes(&mut self, attributes: &mut Vec<(String, Option<String>)>) -> Option<()> {
+        loop {
+            self.take_while(char::is_whitespace);
+            if self.eat(']') {
+                return Some(());
+            }
+            let name = self.take_name()?;
+            let value = if self.eat('=') {
+                Some(match self.peek() {
+                    Some(quote @ ('"' | '\'')) => {
+                        self.ix += 1;
+                        let value = self.take_while(|c| c != quote);
+                        if !self.eat(quote) {
+                            return None;
+                        }
+                        value
+                    }
+                    _ => self.take_while(|c| !c.is_whitespace() && c != ']'),
+                })
+            } else {
+                None
+            };
+            attributes.push((name, value));
+        }
+    }
+}
+
+const KNOWN_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "address",
+    "article",
+    "aside",
+    "audio",
+    "b",
+    "blockquote",
+    "body",
+    "br",
+    "button",
+    "canvas",
+    "caption",
+    "code",
+    "dd",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "head",
+    "header",
+    "hr",
+    "html",
+    "i",
+    "iframe",
+    "img",
+    "input",
+    "label",
+    "legend",
+    "li",
+    "link",
+    "main",
+    "meta",
+    "nav",
+    "ol",
+    "option",
+    "p",
+    "pre",
+    "script",
+    "section",
+    "select",
+    "small",
+    "source",
+    "span",
+    "strong",
+    "style",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "textarea",
+    "tfoot",
+    "th",
+    "thead",
+    "title",
+    "tr",
+    "u",
+    "ul",
+    "video",
+];
+
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+fn expand_markup(abbreviation: &str, syntax: Syntax, indent: &str) -> Option<String> {
+    let mut parser = Parser {
+        chars: abbreviation.chars().collect(),
+        ix: 0,
+    };
+    let nodes = parser.parse_expression(false)?;
+    if expanded_node_count(&nodes)? > MAX_EXPANDED_NODES {
+        return None;
+    }
+
+    // A lone word is only expanded when it's a tag name, so that typing prose isn't expanded.
+    if let [Node {
+        kind: NodeKind::Element(element),
+        repeat: 1,
+        children,
+    }] = nodes.as_slice()
+    {
+        let is_plain_word = children.is_empty()
+            && element.id.is_none()
+            && element.classes.is_empty()
+            && element.attributes.is_empty()
+            && element.text.is_none();
+        if is_plain_word
+            && (syntax == Syntax::Jsx
+                || !(element.name == "!" || KNOWN_TAGS.contains(&element.name.as_str())))
+        {
+            return None;
+        }
+    }
+
+    let mut renderer = Renderer {
+        syntax,
+        indent,
+        tabstop: 0,
+        lines: Vec::new(),
+    };
+    renderer.render_nodes(&nodes, None, 0, (0, 1));
+    Some(renderer.lines.join("\n"))
+}
+
+/// Returns the number of elements that the nodes expand to, or `None` if it overflows.
+fn expanded_node_count(nodes: &[Node]) -> Option<usize> {
+    nodes.iter().try_fold(0_usize, |count, node| {
+        let own_count = match &node.kind {
+            NodeKind::Group(nodes) => expanded_node_count(nodes)?,
+            NodeKind::Element(_) => 1,
+        };
+        let repeated_count = own_count
+            .checked_add(expanded_node_count(&node.children)?)?
+            .checked_mul(node.repeat)?;
+        count.checked_add(repeated_count)
+    })
+}
+
+struct Renderer<'a> {
+    syntax: Syntax,
+    indent: &'a str,
+    tabstop: usize,
+    lines: Vec<String>,
+}
+
+impl Renderer<'_> {
+    fn next_tabstop(&mut self) -> String {
+        self.tabstop += 1;
+        format!("${{{}}}", self.tabstop)
+    }
+
+    /// Renders nodes, numbering them with their index among the repetitions of the nearest
+    /// repeated node and the number of repetitions.
+    fn render_nodes(
+        &mut self,
+        nodes: &[Node],
+        parent: Option<&str>,
+        depth: usize,
+        numbering: (usize, usize),
+    ) {
+        for node in nodes {
+            for ix in 0..node.repeat {
+                let numbering = if node.repeat > 1 {
+                    (ix, node.repeat)
+                } else {
+                    numbering
+                };
+                match &node.kind {
+                    NodeKind::Group(nodes) => self.render_nodes(nodes, parent, depth, numbering),
+                    NodeKind::Element(element) => {
+                        self.render_element(element, &node.children, parent, depth, numbering)
+                    }
+                }
+            }
+        }
+    }
+
+    fn render_element(
+        &mut self,
+        element: &Element,
+        children: &[Node],
+        parent: Option<&str>,
+        depth: usize,
+        numbering: (usize, usize),
+    ) {
+        let indent = self.indent.repeat(depth);
+        let text = element
+            .text
+            .as_ref()
+            .map(|text| escape(&number(text, numbering)));
+        if element.is_text() {
+            self.lines
+                .push(format!("{indent}{}", text.unwrap_or_default()));
+            self.render_nodes(children, parent, depth, numbering);
+            return;
+        }
+
+        let name = if element.name.is_empty() {
+            implicit_tag(parent).to_string()
+        } else {
+            number(&element.name, numbering)
+        };
+        if name == "!" {
+            self.render_document(children, depth);
+            return;
+        }
+
+        let mut attributes = Vec::new();
+        if let Some(id) = &element.id {
+            attributes.push(("id".to_string(), Some(id.clone())));
+        }
+        if !element.classes.is_empty() {
+            let name = match self.syntax {
+                Syntax::Jsx => "className",
+                _ => "class",
+            };
+            attributes.push((name.to_string(), Some(element.classes.join(" "))));
+        }
+        attributes.extend(element.attributes.iter().cloned());
+        for (default_name, default_value) in default_attributes(&name, self.syntax) {
+            if !attributes.iter().any(|(name, _)| name == default_name) {
+                attributes.push((default_name.to_string(), Some(default_value.to_string())));
+            }
+        }
+
+        let mut open_tag = format!("{indent}<{name}");
+        for (name, value) in &attributes {
+            open_tag.push(' ');
+            open_tag.push_str(name);
+            match value {
+                Some(value) if value.is_empty() => {
+                    let tabstop = self.next_tabstop();
+                    open_tag.push_str(&format!("=\"{tabstop}\""));
+                }
+                Some(value) => {
+                    open_tag.push_str(&format!("=\"{}\"", escape(&number(value, numbering))))
+                }
+                None => {}
+            }
+        }
+
+        if VOID_TAGS.contains(&name.as_str()) {
+            open_tag.push_str(match self.syntax {
+                Syntax::Jsx => " />",
+                _ => ">",
+            });
+            self.lines.push(open_tag);
+        } else if children.is_empty() {
+            let content = text.unwrap_or_else(|| self.next_tabstop());
+            self.lines.push(format!("{open_tag}>{content}</{name}>"));
+        } else {
+            self.lines.push(format!("{open_tag}>"));
+            if let Some(text) = text {
+                self.lines.push(format!("{indent}{}{text}", self.indent));
+            }
+            self.render_nodes(children, Some(&name), depth + 1, numbering);
+            self.lines.push(format!("{indent}</{name}>"));
+        }
+    }
+
+    fn render_document(&mut self, children: &[Node], depth: usize) {
+        let indent = self.indent.repeat(depth);
+        let inner = self.indent;
+        let title = self.next_tabstop();
+        self.lines.extend([
+            format!("{indent}<!DOCTYPE html>"),
+            format!("{indent}<html lang=\"en\">"),
+            format!("{indent}<head>"),
+            format!("{indent}{inner}<meta charset=\"UTF-8\">"),
+            format!(
+                "{indent}{inner}<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\">"
+            ),
+            format!("{indent}{inner}<title>{title}</title>"),
+            format!("{indent}</head>"),
+            format!("{indent}<body>"),
+        ]);
+        if children.is_empty() {
+            let body = self.next_tabstop();
+            self.lines.push(format!("{indent}{inner}{body}"));
+        } else {
+            self.render_nodes(children, Some("body"), depth + 1, (0, 1));
+        }
+        self.lines.push(format!("{indent}</body>"));
+        self.lines.push(format!("{indent}</html>"));
+    }
+}
+
+fn implicit_tag(parent: Option<&str>) -> &'static str {
+    match parent {
+        Some("ul" | "ol") => "li",
+        Some("table" | "thead" | "tbody" | "tfoot") => "tr",
+        Some("tr") => "td",
+        Some("select" | "optgroup") => "option",
+        Some("span" | "a" | "em" | "strong" | "b" | "i" | "label" | "button") => "span",
+        _ => "div",
+    }
+}
+
+fn default_attributes(tag: &str, syntax: Syntax) -> &'static [(&'static str, &'static str)] {
+    match tag {
+        "a" => &[("href", "")],
+        "img" => &[("src", ""), ("alt", "")],
+        "input" => &[("type", "text")],
+        "link" => &[("rel", "stylesheet"), ("href", "")],
+        "form" => &[("action", "")],
+        "label" if syntax == Syntax::Jsx => &[("htmlFor", "")],
+        "label" => &[("for", "")],
+        _ => &[],
+    }
+}
+
+/// Replaces runs of `$` with the repetition number, padded to the length of the run. A run can
+/// be followed by `@-` to count down, and by `@N` to start counting from N.
+fn number(text: &str, (ix, count): (usize, usize)) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            result.push(c);
+            continue;
+        }
+
+        let mut width = 1;
+        while chars.next_if_eq(&'$').is_some() {
+            width += 1;
+        }
+        let mut reverse = false;
+        let mut base = 1;
+        if chars.next_if_eq(&'@').is_some() {
+            reverse = chars.next_if_eq(&'-').is_some();
+            let mut digits = String::new();
+            while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+                digits.push(digit);
+            }
+            base = digits.parse().unwrap_or(1);
+        }
+        let value = if reverse { count - 1 - ix } else { ix } + base;
+        result.push_str(&format!("{value:0width$}"));
+    }
+    result
+}
+
+/// Escapes the characters that have a meaning in snippets.
+fn escape(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '$' | '}' | '\\') {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+const CSS_PROPERTIES: &[(&str, &str)] = &[
+    ("ai", "align-items"),
+    ("b", "bottom"),
+    ("bd", "border"),
+    ("bdrs", "border-radius"),
+    ("bg", "background"),
+    ("bgc", "background-color"),
+    ("bxsh", "box-shadow"),
+    ("bxz", "box-sizing"),
+    ("c", "color"),
+    ("cur", "cursor"),
+    ("d", "display"),
+    ("ff", "font-family"),
+    ("fl", "float"),
+    ("fw", "font-weight"),
+    ("fx", "flex"),
+    ("fxd", "flex-direction"),
+    ("fxg", "flex-grow"),
+    ("fxs", "flex-shrink"),
+    ("fz", "font-size"),
+    ("g", "gap"),
+    ("h", "height"),
+    ("jc", "justify-content"),
+    ("l", "left"),
+    ("lh", "line-height"),
+    ("lts", "letter-spacing"),
+    ("m", "margin"),
+    ("mah", "max-height"),
+    ("maw", "max-width"),
+    ("mb", "margin-bottom"),
+    ("mih", "min-height"),
+    ("miw", "min-width"),
+    ("ml", "margin-left"),
+    ("mr", "margin-right"),
+    ("mt", "margin-top"),
+    ("op", "opacity"),
+    ("ord", "order"),
+    ("ov", "overflow"),
+    ("p", "padding"),
+    ("pb", "padding-bottom"),
+    ("pl", "padding-left"),
+    ("pos", "position"),
+    ("pr", "padding-right"),
+    ("pt", "padding-top"),
+    ("r", "right"),
+    ("t", "top"),
+    ("ta", "text-align"),
+    ("td", "text-decoration"),
+    ("trs", "transition"),
+    ("va", "vertical-align"),
+    ("w", "width"),
+    ("ws", "white-space"),
+    ("z", "z-index"),
+];
+
+const CSS_KEYWORDS: &[(&str, &str)] = &[
+    ("aic", "align-items: center;"),
+    ("aife", "align-items: flex-end;"),
+    ("aifs", "align-items: flex-start;"),
+    ("bdn", "border: none;"),
+    ("bxzbb", "box-sizing: border-box;"),
+    ("curp", "cursor: pointer;"),
+    ("db", "display: block;"),
+    ("df", "display: flex;"),
+    ("dg", "display: grid;"),
+    ("di", "display: inline;"),
+    ("dib", "display: inline-block;"),
+    ("dif", "display: inline-flex;"),
+    ("dn", "display: none;"),
+    ("fll", "float: left;"),
+    ("flr", "float: right;"),
+    ("fsi", "font-style: italic;"),
+    ("fwb", "font-weight: bold;"),
+    ("fwn", "font-weight: normal;"),
+    ("fxdc", "flex-direction: column;"),
+    ("fxdr", "flex-direction: row;"),
+    ("jcc", "justify-content: center;"),
+    ("jcfe", "justify-content: flex-end;"),
+    ("jcfs", "justify-content: flex-start;"),
+    ("jcsb", "justify-content: space-between;"),
+    ("ova", "overflow: auto;"),
+    ("ovh", "overflow: hidden;"),
+    ("posa", "position: absolute;"),
+    ("posf", "position: fixed;"),
+    ("posr", "position: relative;"),
+    ("poss", "position: sticky;"),
+    ("tac", "text-align: center;"),
+    ("tal", "text-align: left;"),
+    ("tar", "text-align: right;"),
+    ("tdn", "text-decoration: none;"),
+    ("tdu", "text-decoration: underline;"),
+    ("wsnw", "white-space: nowrap;"),
+];
+
+/// Properties whose numeric values have no unit.
+const UNITLESS_CSS_PROPERTIES: &[&str] = &[
+    "flex-grow",
+    "flex-shrink",
+    "font-weight",
+    "line-height",
+    "opacity",
+    "order",
+    "z-index",
+];
+
+fn expand_stylesheet(abbreviation: &str) -> Option<String> {
+    if let Some((_, expansion)) = CSS_KEYWORDS.iter().find(|(abbr, _)| *abbr == abbreviation) {
+        return Some(expansion.to_string());
+    }
+
+    let name_len = abbreviation
+        .find(|c: char| !c.is_ascii_lowercase())
+        .unwrap_or(abbreviation.len());
+    let (name, mut rest) = abbreviation.split_at(name_len);
+    let (_, property) = CSS_PROPERTIES.iter().find(|(abbr, _)| *abbr == name)?;
+    if rest.is_empty() {
+        return Some(format!("{property}: ${{1}};"));
+    }
+
+    if let Some(color) = rest.strip_prefix('#') {
+        if !color.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let color = match color.len() {
+            1 | 2 => color.repeat(3),
+            3 | 6 => color.to_string(),
+            _ => return None,
+        };
+        return Some(format!("{property}: #{color};"));
+    }
+
+    // Values are separated by `-`, and a value starting with another `-` is negative.
+    let mut values = Vec::new();
+    while !rest.is_empty() {
+        let sign = if let Some(unsigned) = rest.strip_prefix('-') {
+            rest = unsigned;
+            "-"
+        } else {
+            ""
+        };
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        if number_len == 0 {
+            return None;
+        }
+        let (number, after_number) = rest.split_at(number_len);
+        let unit_len = after_number
+            .find(|c: char| !(c.is_ascii_alphabetic() || c == '%'))
+            .unwrap_or(after_number.len());
+        let (unit, after_unit) = after_number.split_at(unit_len);
+        let unit = match unit {
+            "" if number == "0" || UNITLESS_CSS_PROPERTIES.contains(property) => "",
+            "" => "px",
+            "p" => "%",
+            "e" => "em",
+            "r" => "rem",
+            "x" => "ex",
+            unit => unit,
+        };
+        values.push(format!("{sign}{number}{unit}"));
+        rest = after_unit.strip_prefix('-').unwrap_or(after_unit);
+    }
+    Some(format!("{property}: {};", values.join(" ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        editor_tests::init_test, test::editor_test_context::EditorTestContext, ConfirmCompletion,
+    };
+    use language::language_settings::AllLanguageSettings;
+    use settings::SettingsStore;
+
+    fn expand_html(abbreviation: &str) -> Option<String> {
+        expand(abbreviation, Syntax::Markup, "  ")
+    }
+
+    #[test]
+    fn test_expand_markup() {
+        assert_eq!(
+            expand_html("ul>li.item$*3").unwrap(),
+            concat!(
+                "<ul>\n",
+                "  <li class=\"item1\">${1}</li>\n",
+                "  <li class=\"item2\">${2}</li>\n",
+                "  <li class=\"item3\">${3}</li>\n",
+                "</ul>",
+            )
+        );
+        assert_eq!(
+            expand_html("div#main>p{Hello}+a[href=/]^footer").unwrap(),
+            concat!(
+                "<div id=\"main\">\n",
+                "  <p>Hello</p>\n",
+                "  <a href=\"/\">${1}</a>\n",
+                "</div>\n",
+                "<footer>${2}</footer>",
+            )
+        );
+        assert_eq!(
+            expand_html("(dt+dd)*2").unwrap(),
+            "<dt>${1}</dt>\n<dd>${2}</dd>\n<dt>${3}</dt>\n<dd>${4}</dd>"
+        );
+        assert_eq!(
+            expand_html("h$$@-*2").unwrap(),
+            "<h02>${1}</h02>\n<h01>${2}</h01>"
+        );
+        assert_eq!(
+            expand_html("img+input").unwrap(),
+            "<img src=\"${1}\" alt=\"${2}\">\n<input type=\"text\">"
+        );
+        assert_eq!(expand_html("p{C:\\dir}").unwrap(), "<p>C:\\\\dir</p>");
+        assert_eq!(
+            expand("label.field+br", Syntax::Jsx, "  ").unwrap(),
+            "<label className=\"field\" htmlFor=\"${1}\">${2}</label>\n<br />"
+        );
+
+        // Lone words are only expanded when they're tag names.
+        assert_eq!(expand_html("div").unwrap(), "<div>${1}</div>");
+        assert_eq!(expand_html("hello"), None);
+        assert_eq!(expand("div", Syntax::Jsx, "  "), None);
+
+        // Malformed abbreviations aren't expanded.
+        assert_eq!(expand_html("ul>"), None);
+        assert_eq!(expand_html("e.g."), None);
+        assert_eq!(expand_html("(li"), None);
+    }
+
+    #[test]
+    fn test_expansion_limits() {
+        assert_eq!(expand_html("li*100000000"), None);
+        assert_eq!(expand_html("(a*1000)*1000"), None);
+        assert_eq!(expand_html("ul>li*1000>a"), None);
+        assert_eq!(
+            expand_html("li*1000").map(|html| html.lines().count()),
+            Some(1000)
+        );
+    }
+
+    #[test]
+    fn test_expand_stylesheet() {
+        let expand_css = |abbreviation| expand(abbreviation, Syntax::Stylesheet, "  ");
+        assert_eq!(expand_css("m10").unwrap(), "margin: 10px;");
+        assert_eq!(expand_css("p10-0--5").unwrap(), "padding: 10px 0 -5px;");
+        assert_eq!(expand_css("w50p").unwrap(), "width: 50%;");
+        assert_eq!(expand_css("lh1.5").unwrap(), "line-height: 1.5;");
+        assert_eq!(expand_css("fz1.2r").unwrap(), "font-size: 1.2rem;");
+        assert_eq!(expand_css("c#f").unwrap(), "color: #fff;");
+        assert_eq!(expand_css("df").unwrap(), "display: flex;");
+        assert_eq!(expand_css("bgc").unwrap(), "background-color: ${1};");
+        assert_eq!(expand_css("color"), None);
+        assert_eq!(expand_css("m-"), None);
+    }
+
+    #[test]
+    fn test_abbreviation_start() {
+        assert_eq!(abbreviation_start("  ul>li", Syntax::Markup), Some(2));
+        assert_eq!(abbreviation_start("<div>ul>li", Syntax::Markup), Some(5));
+        assert_eq!(abbreviation_start("a p{one two}", Syntax::Markup), Some(2));
+        assert_eq!(abbreviation_start("<div cla", Syntax::Markup), None);
+        assert_eq!(abbreviation_start("a ", Syntax::Markup), None);
+        assert_eq!(
+            abbreviation_start("color: red; m10", Syntax::Stylesheet),
+            Some(12)
+        );
+    }
+
+    #[gpui::test]
+    async fn test_emmet_completion(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |settings| settings.defaults.emmet = Some(true));
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("<body>\n  ul>lˇ\n</body>");
+        cx.simulate_keystroke("i");
+        cx.executor().run_until_parked();
+        cx.update_editor(|editor, cx| {
+            editor
+                .confirm_completion(&ConfirmCompletion::default(), cx)
+                .unwrap()
+        })
+        .await
+        .unwrap();
+        cx.assert_editor_state("<body>\n  <ul>\n      <li>ˇ</li>\n  </ul>\n</body>");
+
+        _ = cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings.defaults.emmet = Some(false);
+                });
+            })
+        });
+        cx.set_state("ul>lˇ");
+        cx.simulate_keystroke("i");
+        cx.executor().run_until_parked();
+        cx.update_editor(|editor, _| assert!(editor.context_menu.read().is_none()));
+    }
+}
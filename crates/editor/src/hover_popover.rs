@@ -2,13 +2,14 @@ use crate::{
     display_map::{InlayOffset, ToDisplayPoint},
     link_go_to_definition::{InlayHighlight, RangeInEditor},
     Anchor, AnchorRangeExt, DisplayPoint, Editor, EditorSettings, EditorSnapshot, EditorStyle,
-    ExcerptId, Hover, RangeToAnchorExt,
+    ExcerptId, Hover, RangeToAnchorExt, ScrollHoverDown, ScrollHoverPageDown, ScrollHoverPageUp,
+    ScrollHoverUp,
 };
 use futures::FutureExt;
 use gpui::{
-    div, px, AnyElement, CursorStyle, Hsla, InteractiveElement, IntoElement, Model, MouseButton,
-    ParentElement, Pixels, SharedString, Size, StatefulInteractiveElement, Styled, Task,
-    ViewContext, WeakView,
+    div, img, point, px, AnyElement, CursorStyle, Hsla, InteractiveElement, IntoElement, Model,
+    MouseButton, ParentElement, Pixels, ScrollHandle, SharedString, Size,
+    StatefulInteractiveElement, Styled, Task, ViewContext, WeakView,
 };
 use language::{markdown, Bias, DiagnosticEntry, Language, LanguageRegistry, ParsedMarkdown};
 
@@ -27,12 +28,71 @@ pub const MIN_POPOVER_CHARACTER_WIDTH: f32 = 20.;
 pub const MIN_POPOVER_LINE_HEIGHT: Pixels = px(4.);
 pub const HOVER_POPOVER_GAP: Pixels = px(10.);
 
-/// Bindable action which uses the most recent selection head to trigger a hover
+/// Bindable action which uses the most recent selection head to trigger a hover.
+/// When the info popover is already shown, it's focused instead, so its content can be
+/// scrolled with the keyboard.
 pub fn hover(editor: &mut Editor, _: &Hover, cx: &mut ViewContext<Editor>) {
+    if editor.hover_state.info_popover.is_some() && !editor.hover_state.focused {
+        editor.hover_state.focused = true;
+        cx.notify();
+        return;
+    }
+
     let head = editor.selections.newest_display(cx).head();
     show_hover(editor, head, true, cx);
 }
 
+pub fn scroll_hover_up(editor: &mut Editor, _: &ScrollHoverUp, cx: &mut ViewContext<Editor>) {
+    scroll_hover(editor, HoverScroll::Line(-1.), cx);
+}
+
+pub fn scroll_hover_down(editor: &mut Editor, _: &ScrollHoverDown, cx: &mut ViewContext<Editor>) {
+    scroll_hover(editor, HoverScroll::Line(1.), cx);
+}
+
+pub fn scroll_hover_page_up(
+    editor: &mut Editor,
+    _: &ScrollHoverPageUp,
+    cx: &mut ViewContext<Editor>,
+) {
+    scroll_hover(editor, HoverScroll::Page(-1.), cx);
+}
+
+pub fn scroll_hover_page_down(
+    editor: &mut Editor,
+    _: &ScrollHoverPageDown,
+    cx: &mut ViewContext<Editor>,
+) {
+    scroll_hover(editor, HoverScroll::Page(1.), cx);
+}
+
+enum HoverScroll {
+    Line(f32),
+    Page(f32),
+}
+
+fn scroll_hover(editor: &mut Editor, amount: HoverScroll, cx: &mut ViewContext<Editor>) {
+    let Some(info_popover) = editor.hover_state.info_popover.as_ref() else {
+        cx.propagate();
+        return;
+    };
+    let distance = match amount {
+        HoverScroll::Line(lines) => {
+            let line_height = editor.style.as_ref().map_or(px(16.), |style| {
+                style.text.line_height_in_pixels(cx.rem_size())
+            });
+            line_height * lines
+        }
+        HoverScroll::Page(pages) => info_popover.scroll_handle.bounds().size.height * pages,
+    };
+    let offset = info_popover.scroll_handle.offset();
+    // Offsets are negative, as the content moves up when scrolling down.
+    info_popover
+        .scroll_handle
+        .set_offset(point(offset.x, offset.y - distance));
+    cx.notify();
+}
+
 /// The internal hover action dispatches between `show_hover` or `hide_hover`
 /// depending on whether a point to hover over is provided.
 pub fn hover_at(editor: &mut Editor, point: Option<DisplayPoint>, cx: &mut ViewContext<Editor>) {
@@ -111,6 +171,7 @@ pub fn hover_at_inlay(editor: &mut Editor, inlay_hover: InlayHover, cx: &mut Vie
                     symbol_range: RangeInEditor::Inlay(inlay_hover.range.clone()),
                     blocks,
                     parsed_content,
+                    scroll_handle: ScrollHandle::new(),
                 };
 
                 this.update(&mut cx, |this, cx| {
@@ -142,6 +203,7 @@ pub fn hide_hover(editor: &mut Editor, cx: &mut ViewContext<Editor>) -> bool {
 
     editor.hover_state.info_task = None;
     editor.hover_state.triggered_from = None;
+    editor.hover_state.focused = false;
 
     editor.clear_background_highlights::<HoverState>(cx);
 
@@ -316,6 +378,7 @@ fn show_hover(
                         symbol_range: RangeInEditor::Text(range),
                         blocks,
                         parsed_content,
+                        scroll_handle: ScrollHandle::new(),
                     })
                 }
 
@@ -350,7 +413,7 @@ fn show_hover(
     editor.hover_state.info_task = Some(task);
 }
 
-async fn parse_blocks(
+pub(crate) async fn parse_blocks(
     blocks: &[HoverBlock],
     language_registry: &Arc<LanguageRegistry>,
     language: Option<Arc<Language>>,
@@ -428,6 +491,8 @@ pub struct HoverState {
     pub diagnostic_popover: Option<DiagnosticPopover>,
     pub triggered_from: Option<Anchor>,
     pub info_task: Option<Task<Option<()>>>,
+    /// Whether the info popover has keyboard focus, so that it's scrolled by the keyboard.
+    pub focused: bool,
 }
 
 impl HoverState {
@@ -471,19 +536,20 @@ impl HoverState {
             elements.push(diagnostic_popover.render(style, max_size, cx));
         }
         if let Some(info_popover) = self.info_popover.as_mut() {
-            elements.push(info_popover.render(style, max_size, workspace, cx));
+            elements.push(info_popover.render(style, max_size, self.focused, workspace, cx));
         }
 
         Some((point, elements))
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct InfoPopover {
     pub project: Model<Project>,
     symbol_range: RangeInEditor,
     pub blocks: Vec<HoverBlock>,
     parsed_content: ParsedMarkdown,
+    scroll_handle: ScrollHandle,
 }
 
 impl InfoPopover {
@@ -491,14 +557,31 @@ impl InfoPopover {
         &mut self,
         style: &EditorStyle,
         max_size: Size<Pixels>,
+        focused: bool,
         workspace: Option<WeakView<Workspace>>,
         cx: &mut ViewContext<Editor>,
     ) -> AnyElement {
+        // Images are shown after the text, as they can't be laid out within it.
+        let image_urls = self
+            .parsed_content
+            .regions
+            .iter()
+            .filter(|region| region.image)
+            .filter_map(|region| match &region.link {
+                Some(markdown::Link::Web { url }) => Some(SharedString::from(url.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
         div()
             .id("info_popover")
             .elevation_2(cx)
+            .when(focused, |this| {
+                this.border_color(cx.theme().colors().border_focused)
+            })
             .p_2()
             .overflow_y_scroll()
+            .track_scroll(&self.scroll_handle)
             .max_w(max_size.width)
             .max_h(max_size.height)
             // Prevent a mouse move on the popover from being propagated to the editor,
@@ -511,6 +594,11 @@ impl InfoPopover {
                 workspace,
                 cx,
             ))
+            .children(
+                image_urls
+                    .into_iter()
+                    .map(|url| img(url).mt_2().w_full().h_40()),
+            )
             .into_any_element()
     }
 }
@@ -608,7 +696,7 @@ mod tests {
         inlay_hint_cache::tests::{cached_hint_labels, visible_hint_labels},
         link_go_to_definition::update_inlay_link_and_hover_points,
         test::editor_lsp_test_context::EditorLspTestContext,
-        InlayId,
+        Cancel, InlayId,
     };
     use collections::BTreeSet;
     use gpui::{FontWeight, HighlightStyle, UnderlineStyle};
@@ -732,6 +820,21 @@ mod tests {
                 }]
             )
         });
+
+        // Hovering again focuses the popover, so it can be scrolled with the keyboard
+        cx.update_editor(|editor, cx| {
+            hover(editor, &Hover, cx);
+            assert!(editor.hover_state.focused);
+            assert!(editor.key_context(cx).contains("hover_focused"));
+        });
+
+        // Cancelling hides the popover, along with its focus
+        cx.update_editor(|editor, cx| {
+            editor.cancel(&Cancel, cx);
+            assert!(!editor.hover_state.visible());
+            assert!(!editor.hover_state.focused);
+            assert!(!editor.key_context(cx).contains("hover_focused"));
+        });
     }
 
     #[gpui::test]
@@ -1021,6 +1124,43 @@ mod tests {
                             ..Default::default()
                         }],
                     },
+                    // Tables
+                    Row {
+                        blocks: vec![HoverBlock {
+                            text: "
+                            | Name | Value |
+                            |:-----|------:|
+                            | a | 1 |
+                            | long | 22 |"
+                                .unindent(),
+                            kind: HoverBlockKind::Markdown,
+                        }],
+                        expected_marked_text: "
+                        «Name │ Value»
+                        ─────┼──────
+                        a    │     1
+                        long │    22"
+                            .unindent(),
+                        expected_styles: vec![HighlightStyle {
+                            font_weight: Some(FontWeight::BOLD),
+                            ..Default::default()
+                        }],
+                    },
+                    // Images
+                    Row {
+                        blocks: vec![HoverBlock {
+                            text: "see ![the logo](https://the-url/logo.png)".to_string(),
+                            kind: HoverBlockKind::Markdown,
+                        }],
+                        expected_marked_text: "see «the logo»".to_string(),
+                        expected_styles: vec![HighlightStyle {
+                            underline: Some(UnderlineStyle {
+                                thickness: 1.0.into(),
+                                ..Default::default()
+                            }),
+                            ..Default::default()
+                        }],
+                    },
                 ];
 
                 for Row {
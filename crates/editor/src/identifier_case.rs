@@ -0,0 +1,222 @@
+/// The cases that identifiers can be converted to, by splitting them into words and joining
+/// the words back together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum IdentifierCase {
+    /// `snake_case`
+    Snake,
+    /// `UPPER_SNAKE_CASE`
+    UpperSnake,
+    /// `kebab-case`
+    Kebab,
+    /// `camelCase`
+    Camel,
+    /// `PascalCase`
+    UpperCamel,
+}
+
+/// Converts the identifiers in the given text to the given case.
+///
+/// Lines that only contain words, such as `The quick brown fox`, are treated as a single
+/// identifier, so that their words are joined. In other lines, such as `self.fooBar(baz_qux)`,
+/// each identifier is converted separately and the text between them is kept.
+pub(crate) fn convert_identifiers(text: &str, case: IdentifierCase) -> String {
+    text.split('\n')
+        .map(|line| {
+            let is_phrase = line
+                .chars()
+                .all(|c| is_identifier_char(c) || c == '-' || c.is_whitespace());
+            if is_phrase {
+                let trimmed = line.trim();
+                let start = line.len() - line.trim_start().len();
+                let mut result = String::with_capacity(line.len());
+                result.push_str(&line[..start]);
+                result.push_str(&convert_identifier(trimmed, case));
+                result.push_str(&line[start + trimmed.len()..]);
+                result
+            } else {
+                convert_identifiers_in_line(line, case)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn convert_identifiers_in_line(line: &str, case: IdentifierCase) -> String {
+    let chars = line.char_indices().collect::<Vec<_>>();
+    let mut result = String::with_capacity(line.len());
+    let mut identifier_start = None;
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        // Hyphens are part of identifiers when followed by a letter, as in `kebab-case`, but
+        // are otherwise operators, as in `count-1`.
+        let is_part_of_identifier = is_identifier_char(c)
+            || (c == '-'
+                && identifier_start.is_some()
+                && chars
+                    .get(i + 1)
+                    .map_or(false, |(_, next)| next.is_alphabetic()));
+        match (is_part_of_identifier, identifier_start) {
+            (true, None) => identifier_start = Some(offset),
+            (false, Some(start)) => {
+                result.push_str(&convert_identifier(&line[start..offset], case));
+                result.push(c);
+                identifier_start = None;
+            }
+            (false, None) => result.push(c),
+            (true, Some(_)) => {}
+        }
+    }
+    if let Some(start) = identifier_start {
+        result.push_str(&convert_identifier(&line[start..], case));
+    }
+    result
+}
+
+/// Converts a single identifier, keeping any leading and trailing underscores, as in
+/// `_private` or `__init__`.
+fn convert_identifier(identifier: &str, case: IdentifierCase) -> String {
+    let body = identifier.trim_matches('_');
+    if body.is_empty() {
+        return identifier.to_string();
+    }
+    let leading_len = identifier.len() - identifier.trim_start_matches('_').len();
+    let trailing_len = identifier.len() - identifier.trim_end_matches('_').len();
+
+    let words = identifier_words(body);
+    let body = match case {
+        IdentifierCase::Snake => join_words(&words, "_", str::to_lowercase),
+        IdentifierCase::UpperSnake => join_words(&words, "_", str::to_uppercase),
+        IdentifierCase::Kebab => join_words(&words, "-", str::to_lowercase),
+        IdentifierCase::Camel => {
+            let mut result = words
+                .first()
+                .map_or(String::new(), |word| word.to_lowercase());
+            for word in words.iter().skip(1) {
+                result.push_str(&capitalize(word));
+            }
+            result
+        }
+        IdentifierCase::UpperCamel => join_words(&words, "", capitalize),
+    };
+
+    let mut result = String::with_capacity(leading_len + body.len() + trailing_len);
+    result.push_str(&identifier[..leading_len]);
+    result.push_str(&body);
+    result.push_str(&identifier[identifier.len() - trailing_len..]);
+    result
+}
+
+/// Splits an identifier into its words, at separators and at changes of case. Runs of capital
+/// letters are kept together as acronyms, as in `HTTPServer`, and digits stay with the word that
+/// they follow, as in `utf8String`.
+fn identifier_words(identifier: &str) -> Vec<&str> {
+    let chars = identifier.char_indices().collect::<Vec<_>>();
+    let mut words = Vec::new();
+    let mut word_start = None;
+    for (i, &(offset, c)) in chars.iter().enumerate() {
+        if c == '_' || c == '-' || c.is_whitespace() {
+            if let Some(start) = word_start.take() {
+                words.push(&identifier[start..offset]);
+            }
+            continue;
+        }
+
+        if let Some(start) = word_start {
+            let previous = chars[i - 1].1;
+            let next = chars.get(i + 1).map(|(_, next)| *next);
+            let is_boundary = c.is_uppercase()
+                && (previous.is_lowercase()
+                    || previous.is_numeric()
+                    || (previous.is_uppercase() && next.map_or(false, char::is_lowercase)));
+            if is_boundary {
+                words.push(&identifier[start..offset]);
+                word_start = Some(offset);
+            }
+        } else {
+            word_start = Some(offset);
+        }
+    }
+    if let Some(start) = word_start {
+        words.push(&identifier[start..]);
+    }
+    words
+}
+
+fn join_words(words: &[&str], separator: &str, transform: impl Fn(&str) -> String) -> String {
+    words
+        .iter()
+        .map(|word| transform(word))
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first
+            .to_uppercase()
+            .chain(chars.flat_map(char::to_lowercase))
+            .collect(),
+        None => String::new(),
+    }
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifier_words() {
+        assert_eq!(identifier_words("fooBarBaz"), ["foo", "Bar", "Baz"]);
+        assert_eq!(identifier_words("foo_bar-baz"), ["foo", "bar", "baz"]);
+        assert_eq!(
+            identifier_words("HTTPServerError"),
+            ["HTTP", "Server", "Error"]
+        );
+        assert_eq!(identifier_words("utf8String"), ["utf8", "String"]);
+        assert_eq!(identifier_words("UPPER_SNAKE"), ["UPPER", "SNAKE"]);
+        assert_eq!(identifier_words("The quick fox"), ["The", "quick", "fox"]);
+    }
+
+    #[test]
+    fn test_convert_identifiers() {
+        use IdentifierCase::*;
+
+        assert_eq!(convert_identifiers("HTTPServer", Snake), "http_server");
+        assert_eq!(convert_identifiers("http_server", Camel), "httpServer");
+        assert_eq!(convert_identifiers("http_server", UpperCamel), "HttpServer");
+        assert_eq!(
+            convert_identifiers("maxRetryCount", UpperSnake),
+            "MAX_RETRY_COUNT"
+        );
+        assert_eq!(
+            convert_identifiers("MAX_RETRY_COUNT", Kebab),
+            "max-retry-count"
+        );
+        assert_eq!(convert_identifiers("utf8String", Snake), "utf8_string");
+        assert_eq!(
+            convert_identifiers("_private_field", Camel),
+            "_privateField"
+        );
+        assert_eq!(convert_identifiers("__init__", UpperCamel), "__Init__");
+
+        // Identifiers within code are converted separately.
+        assert_eq!(
+            convert_identifiers("self.fooBar(baz_qux, 1 - minValue)", Snake),
+            "self.foo_bar(baz_qux, 1 - min_value)"
+        );
+        assert_eq!(
+            convert_identifiers("let font-size: $baseSize;", Kebab),
+            "let font-size: $base-size;"
+        );
+
+        // Lines of words are joined, and each line is converted separately.
+        assert_eq!(
+            convert_identifiers("  the quick fox\njumps over", UpperCamel),
+            "  TheQuickFox\nJumpsOver"
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{MultiBufferSnapshot, ToOffset, ToPoint};
+use language::Point;
+use std::ops::Range;
+
+/// Finds the number that contains `start`, or else the next number after it on the same line.
+/// Returns its range, its digits (with any minus sign, but without a `0x` or `0b` prefix) and
+/// its radix.
+pub fn find_number(
+    snapshot: &MultiBufferSnapshot,
+    start: Point,
+) -> Option<(Range<Point>, String, u32)> {
+    let mut offset = start.to_offset(snapshot);
+
+    // go backwards to the start of any number the selection is within
+    for ch in snapshot.reversed_chars_at(offset) {
+        if ch.is_ascii_digit() || ch == '-' || ch == 'b' || ch == 'x' {
+            offset -= ch.len_utf8();
+            continue;
+        }
+        break;
+    }
+
+    let mut begin = None;
+    let mut end = None;
+    let mut num = String::new();
+    let mut radix = 10;
+
+    let mut chars = snapshot.chars_at(offset).peekable();
+    // find the next number on the line (may start after the original cursor position)
+    while let Some(ch) = chars.next() {
+        if num == "0" && ch == 'b' && chars.peek().is_some() && chars.peek().unwrap().is_digit(2) {
+            radix = 2;
+            begin = None;
+            num = String::new();
+        }
+        if num == "0" && ch == 'x' && chars.peek().is_some() && chars.peek().unwrap().is_digit(16) {
+            radix = 16;
+            begin = None;
+            num = String::new();
+        }
+
+        if ch.is_digit(radix)
+            || (begin.is_none()
+                && ch == '-'
+                && chars.peek().is_some()
+                && chars.peek().unwrap().is_digit(radix))
+        {
+            if begin.is_none() {
+                begin = Some(offset);
+            }
+            num.push(ch);
+        } else {
+            if begin.is_some() {
+                end = Some(offset);
+                break;
+            } else if ch == '\n' {
+                break;
+            }
+        }
+        offset += ch.len_utf8();
+    }
+    if let Some(begin) = begin {
+        let end = end.unwrap_or(offset);
+        Some((begin.to_point(snapshot)..end.to_point(snapshot), num, radix))
+    } else {
+        None
+    }
+}
+
+/// Adds `delta` to a number found by [`find_number`], keeping its radix and the case of its
+/// hexadecimal digits. Returns `None` if the number doesn't fit in an `i32`.
+pub fn increment_number(num: &str, radix: u32, delta: i32) -> Option<String> {
+    let result = i32::from_str_radix(num, radix).ok()?.checked_add(delta)?;
+    Some(match radix {
+        16 => {
+            if num.to_ascii_lowercase() == num {
+                format!("{:x}", result)
+            } else {
+                format!("{:X}", result)
+            }
+        }
+        2 => format!("{:b}", result),
+        _ => format!("{}", result),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_increment_number() {
+        assert_eq!(increment_number("12", 10, 1).as_deref(), Some("13"));
+        assert_eq!(increment_number("-1", 10, 1).as_deref(), Some("0"));
+        assert_eq!(increment_number("ff", 16, 1).as_deref(), Some("100"));
+        assert_eq!(increment_number("FF", 16, -1).as_deref(), Some("FE"));
+        assert_eq!(increment_number("10", 2, 1).as_deref(), Some("11"));
+        assert_eq!(increment_number("2147483647", 10, 1), None);
+    }
+}
@@ -1,7 +1,8 @@
 use crate::{
     editor_settings::SeedQuerySetting, link_go_to_definition::hide_link_definition,
-    persistence::DB, scroll::ScrollAnchor, Anchor, Autoscroll, Editor, EditorEvent, EditorSettings,
-    ExcerptId, ExcerptRange, MultiBuffer, MultiBufferSnapshot, NavigationData, ToPoint as _,
+    persistence::DB, scroll::ScrollAnchor, Anchor, Autoscroll, BookmarkStore, Editor, EditorEvent,
+    EditorSettings, ExcerptId, ExcerptRange, MakeWritable, MultiBuffer, MultiBufferSnapshot,
+    NavigationData, ToPoint as _,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::HashSet;
@@ -32,7 +33,7 @@ use std::{
 };
 use text::{BufferId, Selection};
 use theme::Theme;
-use ui::{h_flex, prelude::*, Label};
+use ui::{h_flex, prelude::*, Label, Tooltip};
 use util::{paths::PathExt, paths::FILE_ROW_COLUMN_DELIMITER, ResultExt, TryFutureExt};
 use workspace::{
     item::{BreadcrumbText, FollowEvent, FollowableItemHandle},
@@ -304,6 +305,7 @@ impl FollowableItem for Editor {
                 }
                 _ => false,
             },
+            _ => false,
         }
     }
 
@@ -313,7 +315,9 @@ impl FollowableItem for Editor {
         message: update_view::Variant,
         cx: &mut ViewContext<Self>,
     ) -> Task<Result<()>> {
-        let update_view::Variant::Editor(message) = message;
+        let update_view::Variant::Editor(message) = message else {
+            return Task::ready(Err(anyhow!("editors can only apply editor updates")));
+        };
         let project = project.clone();
         cx.spawn(|this, mut cx| async move {
             update_editor_from_message(this, project, message, &mut cx).await
@@ -834,6 +838,10 @@ impl Item for Editor {
         let item_id = cx.view().item_id().as_u64() as ItemId;
         self.workspace = Some((workspace.weak_handle(), workspace.database_id()));
 
+        let bookmark_store = BookmarkStore::for_workspace(workspace_id, cx);
+        cx.observe(&bookmark_store, |_, _, cx| cx.notify()).detach();
+        self.bookmark_store = Some(bookmark_store);
+
         fn serialize(
             buffer: Model<Buffer>,
             workspace_id: WorkspaceId,
@@ -1241,6 +1249,10 @@ impl Render for CursorPosition {
 }
 
 impl StatusItemView for CursorPosition {
+    fn status_item_id(&self) -> &'static str {
+        "cursor_position"
+    }
+
     fn set_active_pane_item(
         &mut self,
         active_pane_item: Option<&dyn ItemHandle>,
@@ -1258,6 +1270,80 @@ impl StatusItemView for CursorPosition {
     }
 }
 
+/// A lock shown in the status bar while the active editor's buffer is read-only.
+pub struct ReadOnlyIndicator {
+    active_editor: Option<WeakView<Editor>>,
+    read_only: bool,
+    _observe_active_editor: Option<Subscription>,
+}
+
+impl Default for ReadOnlyIndicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReadOnlyIndicator {
+    pub fn new() -> Self {
+        Self {
+            active_editor: None,
+            read_only: false,
+            _observe_active_editor: None,
+        }
+    }
+
+    fn update_read_only(&mut self, editor: View<Editor>, cx: &mut ViewContext<Self>) {
+        self.read_only = editor.read(cx).buffer().read(cx).read_only();
+        cx.notify();
+    }
+}
+
+impl Render for ReadOnlyIndicator {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div().when(self.read_only, |el| {
+            el.child(
+                IconButton::new("read-only", IconName::FileLock)
+                    .style(ButtonStyle::Subtle)
+                    .icon_size(IconSize::Small)
+                    .tooltip(|cx| Tooltip::for_action("Make Writable", &MakeWritable, cx))
+                    .on_click(cx.listener(|this, _, cx| {
+                        if let Some(editor) = this
+                            .active_editor
+                            .as_ref()
+                            .and_then(|editor| editor.upgrade())
+                        {
+                            editor.update(cx, |editor, cx| editor.make_writable(&MakeWritable, cx));
+                        }
+                    })),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ReadOnlyIndicator {
+    fn status_item_id(&self) -> &'static str {
+        "read_only"
+    }
+
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.act_as::<Editor>(cx)) {
+            self.active_editor = Some(editor.downgrade());
+            self._observe_active_editor = Some(cx.observe(&editor, Self::update_read_only));
+            self.update_read_only(editor, cx);
+        } else {
+            self.active_editor = None;
+            self.read_only = false;
+            self._observe_active_editor = None;
+        }
+
+        cx.notify();
+    }
+}
+
 fn path_for_buffer<'a>(
     buffer: &Model<MultiBuffer>,
     height: usize,
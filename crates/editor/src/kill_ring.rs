@@ -0,0 +1,57 @@
+use collections::VecDeque;
+use multi_buffer::Anchor;
+use std::ops::Range;
+
+/// How many kills are remembered, like Emacs' `kill-ring-max`.
+const MAX_KILL_RING_LEN: usize = 120;
+
+/// Text removed with [`KillRingCut`](crate::actions::KillRingCut), shared by all editors.
+/// The most recent kill comes first.
+#[derive(Default)]
+pub(crate) struct KillRing {
+    entries: VecDeque<String>,
+}
+
+impl KillRing {
+    pub fn push(&mut self, text: String) {
+        self.entries.push_front(text);
+        self.entries.truncate(MAX_KILL_RING_LEN);
+    }
+
+    /// Extends the most recent kill, for consecutive kills.
+    pub fn append(&mut self, text: &str) {
+        match self.entries.front_mut() {
+            Some(latest) => latest.push_str(text),
+            None => self.push(text.to_string()),
+        }
+    }
+
+    pub fn latest(&self) -> Option<&str> {
+        self.entries.front().map(String::as_str)
+    }
+
+    /// Returns the kill `index` entries back, wrapping around to the most recent one.
+    pub fn get(&self, index: usize) -> Option<&str> {
+        if self.entries.is_empty() {
+            None
+        } else {
+            Some(&self.entries[index % self.entries.len()])
+        }
+    }
+}
+
+/// What the last kill ring command in an editor did, so that the next one can build on it
+/// when nothing has been edited in between.
+pub(crate) enum KillRingState {
+    /// A kill that left the cursors at `cursors`. Killing again from there appends to it.
+    Killed {
+        edit_count: usize,
+        cursors: Vec<usize>,
+    },
+    /// A yank of the kill `index` entries back, which a yank-pop replaces with the one before.
+    Yanked {
+        edit_count: usize,
+        ranges: Vec<Range<Anchor>>,
+        index: usize,
+    },
+}
@@ -34,7 +34,18 @@ pub fn left(map: &DisplaySnapshot, mut point: DisplayPoint) -> DisplayPoint {
         *point.row_mut() -= 1;
         *point.column_mut() = map.line_len(point.row());
     }
-    map.clip_point(point, Bias::Left)
+    let mut point = map.clip_point(point, Bias::Left);
+
+    // Don't stop between a character and the marks combined with it.
+    while point.column() > 0 && is_before_combining_mark(map, point) {
+        let prev_point = point;
+        *point.column_mut() -= 1;
+        point = map.clip_point(point, Bias::Left);
+        if point == prev_point {
+            break;
+        }
+    }
+    point
 }
 
 /// Returns a column to the left of the current point, doing nothing if
@@ -56,7 +67,94 @@ pub fn right(map: &DisplaySnapshot, mut point: DisplayPoint) -> DisplayPoint {
         *point.row_mut() += 1;
         *point.column_mut() = 0;
     }
-    map.clip_point(point, Bias::Right)
+    let mut point = map.clip_point(point, Bias::Right);
+
+    // Don't stop between a character and the marks combined with it.
+    while point.column() < map.line_len(point.row()) && is_before_combining_mark(map, point) {
+        let prev_point = point;
+        *point.column_mut() += 1;
+        point = map.clip_point(point, Bias::Right);
+        if point == prev_point {
+            break;
+        }
+    }
+    point
+}
+
+/// Returns the point visually to the left of the current point. In right-to-left
+/// text, such as Arabic or Hebrew, that's the following character.
+pub fn visual_left(map: &DisplaySnapshot, point: DisplayPoint) -> DisplayPoint {
+    if is_rtl_at(map, point) {
+        right(map, point)
+    } else {
+        left(map, point)
+    }
+}
+
+/// Returns the point visually to the right of the current point. In right-to-left
+/// text, such as Arabic or Hebrew, that's the preceding character.
+pub fn visual_right(map: &DisplaySnapshot, point: DisplayPoint) -> DisplayPoint {
+    if is_rtl_at(map, point) {
+        left(map, point)
+    } else {
+        right(map, point)
+    }
+}
+
+/// Whether the text at the given point is laid out right to left, judging by the
+/// character after it or, if that isn't a letter, the one before it.
+pub fn is_rtl_at(map: &DisplaySnapshot, point: DisplayPoint) -> bool {
+    let next_char = map.chars_at(point).next().map(|(c, _)| c);
+    if let Some(c) = next_char.filter(|c| c.is_alphanumeric()) {
+        return is_rtl(c);
+    }
+    map.reverse_chars_at(point)
+        .next()
+        .map_or(false, |(c, _)| is_rtl(c))
+}
+
+fn is_before_combining_mark(map: &DisplaySnapshot, point: DisplayPoint) -> bool {
+    map.chars_at(point)
+        .next()
+        .map_or(false, |(c, _)| is_combining_mark(c))
+}
+
+fn is_rtl(c: char) -> bool {
+    matches!(c as u32,
+        0x0590..=0x08FF // Hebrew, Arabic, Syriac, Thaana, N'Ko, Samaritan, Mandaic
+        | 0xFB1D..=0xFDFF // Hebrew and Arabic presentation forms
+        | 0xFE70..=0xFEFF
+        | 0x10800..=0x10FFF
+        | 0x1E800..=0x1EFFF)
+}
+
+fn is_combining_mark(c: char) -> bool {
+    matches!(c as u32,
+        0x0300..=0x036F // Combining diacritical marks
+        | 0x0483..=0x0489
+        | 0x0591..=0x05BD // Hebrew points
+        | 0x05BF
+        | 0x05C1..=0x05C2
+        | 0x05C4..=0x05C5
+        | 0x05C7
+        | 0x0610..=0x061A // Arabic marks
+        | 0x064B..=0x065F
+        | 0x0670
+        | 0x06D6..=0x06DC
+        | 0x06DF..=0x06E4
+        | 0x06E7..=0x06E8
+        | 0x06EA..=0x06ED
+        | 0x0900..=0x0903 // Devanagari vowel signs
+        | 0x093A..=0x093C
+        | 0x093E..=0x094F
+        | 0x0951..=0x0957
+        | 0x0962..=0x0963
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200D // Zero width joiner
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F // Variation selectors
+        | 0xFE20..=0xFE2F)
 }
 
 /// Returns a column to the right of the current point, not performing any wrapping
@@ -525,6 +623,46 @@ mod tests {
     use text::BufferId;
     use util::post_inc;
 
+    #[gpui::test]
+    fn test_left_right_over_combining_marks(cx: &mut gpui::AppContext) {
+        init_test(cx);
+
+        fn assert(marked_text: &str, cx: &mut gpui::AppContext) {
+            let (snapshot, display_points) = marked_display_snapshot(marked_text, cx);
+            assert_eq!(left(&snapshot, display_points[1]), display_points[0]);
+            assert_eq!(right(&snapshot, display_points[0]), display_points[1]);
+        }
+
+        assert("aˇe\u{301}ˇb", cx);
+        assert("ˇe\u{301}\u{302}ˇ", cx);
+        assert("ˇש\u{5b8}ˇל", cx);
+        assert("ˇب\u{64e}ˇ", cx);
+        assert("ˇक\u{93f}ˇ", cx);
+    }
+
+    #[gpui::test]
+    fn test_visual_left_right(cx: &mut gpui::AppContext) {
+        init_test(cx);
+
+        fn assert(marked_text: &str, cx: &mut gpui::AppContext) {
+            let (snapshot, display_points) = marked_display_snapshot(marked_text, cx);
+            let (first, second) = (display_points[0], display_points[1]);
+            if is_rtl_at(&snapshot, first) {
+                // In right-to-left text, the following character is on the left.
+                assert_eq!(visual_left(&snapshot, first), second);
+                assert_eq!(visual_right(&snapshot, second), first);
+            } else {
+                assert_eq!(visual_right(&snapshot, first), second);
+                assert_eq!(visual_left(&snapshot, second), first);
+            }
+        }
+
+        assert("aˇbˇc", cx);
+        assert("ˇשˇלום", cx);
+        assert("שלוˇםˇ", cx);
+        assert("abc ˇשˇלום", cx);
+    }
+
     #[gpui::test]
     fn test_previous_word_start(cx: &mut gpui::AppContext) {
         init_test(cx);
@@ -0,0 +1,398 @@
+//! Peeking shows the definitions or references of a symbol in an editor embedded below the
+//! cursor's line, so they can be read and edited without navigating away from it.
+
+use crate::{
+    display_map::{BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle},
+    scroll::Autoscroll,
+    Anchor, Cancel, Editor, PeekDefinition, PeekReferences,
+};
+use anyhow::Result;
+use collections::HashMap;
+use gpui::{
+    px, DragMoveEvent, EntityId, EventEmitter, FocusHandle, FocusableView, Model, Pixels, Render,
+    SharedString, Subscription, Task, View, ViewContext, VisualContext,
+};
+use project::{Location, Project};
+use std::{cell::Cell, rc::Rc, sync::Arc};
+use ui::{prelude::*, IconButton, Tooltip};
+use workspace::{item::Item as _, Save};
+
+/// The number of lines a peek takes up when it's opened.
+const DEFAULT_PEEK_HEIGHT: u8 = 12;
+const MIN_PEEK_HEIGHT: u8 = 4;
+const MAX_PEEK_HEIGHT: u8 = 40;
+
+#[derive(Clone, Copy)]
+enum PeekKind {
+    Definition,
+    References,
+}
+
+pub(crate) struct Peek {
+    block_id: BlockId,
+    view: View<PeekView>,
+    _subscription: Subscription,
+}
+
+pub enum PeekEvent {
+    Resized,
+    Dismissed,
+}
+
+/// An editor of the locations being peeked at, embedded in a block of the editor they were
+/// peeked from.
+pub struct PeekView {
+    editor: View<Editor>,
+    project: Model<Project>,
+    title: SharedString,
+    height: u8,
+    line_height: Rc<Cell<Pixels>>,
+}
+
+#[derive(Clone, Render)]
+struct DraggedPeek(EntityId);
+
+impl Editor {
+    pub fn peek_definition(&mut self, _: &PeekDefinition, cx: &mut ViewContext<Self>) {
+        self.peek(PeekKind::Definition, cx);
+    }
+
+    pub fn peek_references(&mut self, _: &PeekReferences, cx: &mut ViewContext<Self>) {
+        self.peek(PeekKind::References, cx);
+    }
+
+    fn peek(&mut self, kind: PeekKind, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let head = self.selections.newest_anchor().head();
+        let Some((buffer, buffer_head)) = self.buffer.read(cx).text_anchor_for_position(head, cx)
+        else {
+            return;
+        };
+
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let (word_range, _) = snapshot.surrounding_word(head);
+        let symbol = snapshot.text_for_range(word_range).collect::<String>();
+        let title = match kind {
+            PeekKind::Definition => format!("Definition of `{symbol}`"),
+            PeekKind::References => format!("References to `{symbol}`"),
+        };
+
+        let locations: Task<Result<Vec<Location>>> = match kind {
+            PeekKind::Definition => {
+                let definitions = project.update(cx, |project, cx| {
+                    project.definition(&buffer, buffer_head, cx)
+                });
+                cx.background_executor().spawn(async move {
+                    Ok(definitions
+                        .await?
+                        .into_iter()
+                        .map(|definition| definition.target)
+                        .collect())
+                })
+            }
+            PeekKind::References => project.update(cx, |project, cx| {
+                project.references(&buffer, buffer_head, cx)
+            }),
+        };
+
+        cx.spawn(|editor, mut cx| async move {
+            let locations = locations.await?;
+            if locations.is_empty() {
+                return Ok(());
+            }
+            editor.update(&mut cx, |editor, cx| {
+                editor.open_peek(title, locations, head, cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn open_peek(
+        &mut self,
+        title: String,
+        locations: Vec<Location>,
+        position: Anchor,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let capability = project.read(cx).capability();
+        let (excerpt_buffer, ranges_to_highlight) = Self::multibuffer_for_locations(
+            locations,
+            self.replica_id(cx),
+            capability,
+            title.clone(),
+            cx,
+        );
+        let workspace = self.workspace.clone();
+        let view = cx.new_view(|cx| {
+            let editor = cx.new_view(|cx| {
+                let mut editor = Editor::for_multibuffer(excerpt_buffer, Some(project.clone()), cx);
+                // Sharing the workspace lets peeks be opened from within a peek, stacking them.
+                editor.workspace = workspace;
+                editor.highlight_background::<PeekView>(
+                    ranges_to_highlight,
+                    |theme| theme.editor_highlighted_line_background,
+                    cx,
+                );
+                editor
+            });
+            PeekView {
+                editor,
+                project,
+                title: title.into(),
+                height: DEFAULT_PEEK_HEIGHT,
+                line_height: Rc::new(Cell::new(px(0.))),
+            }
+        });
+
+        let block_id = self.insert_blocks(
+            [BlockProperties {
+                position,
+                height: DEFAULT_PEEK_HEIGHT,
+                style: BlockStyle::Sticky,
+                render: Arc::new({
+                    let view = view.clone();
+                    move |cx: &mut BlockContext| {
+                        let height = view.read(cx).height;
+                        view.read(cx).line_height.set(cx.line_height);
+                        div()
+                            .h(cx.line_height * height as f32)
+                            .w_full()
+                            .pl(cx.gutter_width)
+                            .child(view.clone())
+                            .into_any_element()
+                    }
+                }),
+                disposition: BlockDisposition::Below,
+            }],
+            Some(Autoscroll::fit()),
+            cx,
+        )[0];
+        let subscription = cx.subscribe(&view, Self::handle_peek_event);
+        cx.focus_view(&view);
+        self.peeks.push(Peek {
+            block_id,
+            view,
+            _subscription: subscription,
+        });
+        cx.notify();
+    }
+
+    fn handle_peek_event(
+        &mut self,
+        view: View<PeekView>,
+        event: &PeekEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(ix) = self.peeks.iter().position(|peek| peek.view == view) else {
+            return;
+        };
+        match event {
+            PeekEvent::Resized => {
+                let heights = HashMap::from_iter([(self.peeks[ix].block_id, view.read(cx).height)]);
+                self.resize_blocks(heights, None, cx);
+                cx.notify();
+            }
+            PeekEvent::Dismissed => self.close_peek(ix, cx),
+        }
+    }
+
+    /// Closes the most recently opened peek, returning whether there was one.
+    pub(crate) fn close_last_peek(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        if self.peeks.is_empty() {
+            return false;
+        }
+        self.close_peek(self.peeks.len() - 1, cx);
+        true
+    }
+
+    fn close_peek(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let peek = self.peeks.remove(ix);
+        let had_focus = peek.view.focus_handle(cx).contains_focused(cx);
+        self.remove_blocks([peek.block_id].into_iter().collect(), None, cx);
+        if had_focus {
+            cx.focus(&self.focus_handle);
+        }
+        cx.notify();
+    }
+}
+
+impl PeekView {
+    fn cancel(&mut self, _: &Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(PeekEvent::Dismissed);
+    }
+
+    fn save(&mut self, _: &Save, cx: &mut ViewContext<Self>) {
+        // The workspace would save the item the peek is shown in, rather than what's being edited
+        // in the peek.
+        let project = self.project.clone();
+        self.editor
+            .update(cx, |editor, cx| editor.save(project, cx))
+            .detach_and_log_err(cx);
+    }
+
+    fn resize(&mut self, event: &DragMoveEvent<DraggedPeek>, cx: &mut ViewContext<Self>) {
+        let line_height = self.line_height.get();
+        if event.drag(cx).0 != cx.entity_id() || line_height <= px(0.) {
+            return;
+        }
+        let lines = ((event.event.position.y - event.bounds.top()) / line_height).round();
+        let height = (lines as u8).clamp(MIN_PEEK_HEIGHT, MAX_PEEK_HEIGHT);
+        if height != self.height {
+            self.height = height;
+            cx.emit(PeekEvent::Resized);
+        }
+    }
+
+    fn excerpt_count(&self, cx: &AppContext) -> usize {
+        self.editor.read(cx).buffer().read(cx).excerpt_ids().len()
+    }
+}
+
+impl EventEmitter<PeekEvent> for PeekView {}
+
+impl FocusableView for PeekView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for PeekView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let excerpt_count = self.excerpt_count(cx);
+        v_flex()
+            .key_context("PeekView")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::save))
+            .on_drag_move(cx.listener(Self::resize))
+            .size_full()
+            .border_y_1()
+            .border_color(cx.theme().colors().border)
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .flex_none()
+                    .justify_between()
+                    .px_2()
+                    .py_0p5()
+                    .bg(cx.theme().colors().editor_subheader_background)
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(self.title.clone()).size(LabelSize::Small))
+                            .when(excerpt_count > 1, |this| {
+                                this.child(
+                                    Label::new(format!("{excerpt_count} locations"))
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    .child(
+                        IconButton::new("close-peek", IconName::Close)
+                            .icon_size(IconSize::Small)
+                            .tooltip(|cx| Tooltip::for_action("Close Peek", &Cancel, cx))
+                            .on_click(cx.listener(|_, _, cx| cx.emit(PeekEvent::Dismissed))),
+                    ),
+            )
+            .child(div().flex_1().min_h_0().child(self.editor.clone()))
+            .child(
+                div()
+                    .id("peek-resize-handle")
+                    .flex_none()
+                    .h_1()
+                    .w_full()
+                    .cursor_row_resize()
+                    .on_drag(DraggedPeek(cx.entity_id()), |drag, cx| {
+                        cx.stop_propagation();
+                        cx.new_view(|_| drag.clone())
+                    }),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        display_map::TransformBlock, editor_tests::init_test,
+        test::editor_lsp_test_context::EditorLspTestContext,
+    };
+    use futures::StreamExt;
+    use indoc::indoc;
+    use lsp::request::GotoDefinition;
+
+    #[gpui::test]
+    async fn test_peek_definition(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                definition_provider: Some(lsp::OneOf::Left(true)),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn test() { do_wˇork(); }
+            fn do_work() { test(); }
+        "});
+        let target_range = cx.lsp_range(indoc! {"
+            fn test() { do_work(); }
+            fn «do_work»() { test(); }
+        "});
+
+        let mut requests = cx.handle_request::<GotoDefinition, _, _>(move |url, _, _| async move {
+            Ok(Some(lsp::GotoDefinitionResponse::Scalar(lsp::Location {
+                uri: url,
+                range: target_range,
+            })))
+        });
+        cx.update_editor(|editor, cx| editor.peek_definition(&PeekDefinition, cx));
+        requests.next().await;
+        cx.background_executor.run_until_parked();
+
+        // The definition is shown below the cursor, without moving it.
+        cx.update_editor(|editor, cx| {
+            assert_eq!(editor.peeks.len(), 1);
+            let peek = editor.peeks[0].view.read(cx);
+            assert_eq!(peek.title.as_ref(), "Definition of `do_work`");
+            assert!(peek
+                .editor
+                .read(cx)
+                .text(cx)
+                .contains("fn do_work() { test(); }"));
+        });
+        cx.assert_editor_state(indoc! {"
+            fn test() { do_wˇork(); }
+            fn do_work() { test(); }
+        "});
+
+        cx.update_editor(|editor, cx| {
+            let peek = editor.peeks[0].view.clone();
+            peek.update(cx, |peek, cx| {
+                peek.height = MAX_PEEK_HEIGHT;
+                cx.emit(PeekEvent::Resized);
+            });
+        });
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.snapshot(cx);
+            let heights = snapshot
+                .blocks_in_range(0..snapshot.max_point().row() + 1)
+                .filter(|(_, block)| matches!(block, TransformBlock::Custom(_)))
+                .map(|(_, block)| block.height())
+                .collect::<Vec<_>>();
+            assert_eq!(heights, &[MAX_PEEK_HEIGHT]);
+        });
+
+        // Cancelling in the editor closes the peek.
+        cx.update_editor(|editor, cx| editor.cancel(&Cancel, cx));
+        cx.update_editor(|editor, _| assert!(editor.peeks.is_empty()));
+    }
+}
@@ -1,7 +1,9 @@
 use std::path::PathBuf;
 
+use anyhow::Context as _;
 use db::sqlez_macros::sql;
 use db::{define_connection, query};
+use util::ResultExt;
 
 use workspace::{ItemId, WorkspaceDb, WorkspaceId};
 
@@ -15,6 +17,12 @@ define_connection!(
     //   scroll_vertical_offset: f32,
     //   scroll_horizontal_offset: f32,
     // )
+    // bookmarks(
+    //   workspace_id: usize,
+    //   path: PathBuf,
+    //   row: u32,
+    //   label: Option<String>,
+    // )
     pub static ref DB: EditorDb<WorkspaceDb> =
         &[sql! (
             CREATE TABLE editors(
@@ -31,6 +39,18 @@ define_connection!(
             ALTER TABLE editors ADD COLUMN scroll_top_row INTEGER NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_horizontal_offset REAL NOT NULL DEFAULT 0;
             ALTER TABLE editors ADD COLUMN scroll_vertical_offset REAL NOT NULL DEFAULT 0;
+        ),
+        sql! (
+            CREATE TABLE bookmarks(
+                workspace_id INTEGER NOT NULL,
+                path BLOB NOT NULL,
+                row INTEGER NOT NULL,
+                label TEXT,
+                PRIMARY KEY(workspace_id, path, row),
+                FOREIGN KEY(workspace_id) REFERENCES workspaces(workspace_id)
+                ON DELETE CASCADE
+                ON UPDATE CASCADE
+            ) STRICT;
         )];
 );
 
@@ -80,4 +100,36 @@ impl EditorDb {
             WHERE item_id = ?1 AND workspace_id = ?2
         }
     }
+
+    query! {
+        pub fn get_bookmarks(workspace_id: WorkspaceId) -> Result<Vec<(PathBuf, u32, Option<String>)>> {
+            SELECT path, row, label FROM bookmarks
+            WHERE workspace_id = ?
+        }
+    }
+
+    /// Replaces the bookmarks saved for the workspace.
+    pub async fn save_bookmarks(
+        &self,
+        workspace_id: WorkspaceId,
+        bookmarks: Vec<(PathBuf, u32, Option<String>)>,
+    ) {
+        self.write(move |conn| {
+            conn.with_savepoint("save_bookmarks", || {
+                conn.exec_bound(sql!(
+                    DELETE FROM bookmarks WHERE workspace_id = ?
+                ))?(workspace_id)
+                .context("Clearing old bookmarks")?;
+                let mut insert = conn.exec_bound(sql!(
+                    INSERT INTO bookmarks(workspace_id, path, row, label) VALUES (?, ?, ?, ?)
+                ))?;
+                for (path, row, label) in bookmarks {
+                    insert((workspace_id, path, row, label))?;
+                }
+                Ok(())
+            })
+            .log_err();
+        })
+        .await;
+    }
 }
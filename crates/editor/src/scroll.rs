@@ -3,6 +3,7 @@ pub(crate) mod autoscroll;
 pub(crate) mod scroll_amount;
 
 use crate::{
+    bracket_pairs::refresh_colorized_brackets,
     display_map::{DisplaySnapshot, ToDisplayPoint},
     hover_popover::hide_hover,
     persistence::DB,
@@ -314,12 +315,16 @@ impl Editor {
 
     pub(crate) fn set_visible_line_count(&mut self, lines: f32, cx: &mut ViewContext<Self>) {
         let opened_first_time = self.scroll_manager.visible_line_count.is_none();
+        let lines_changed = self.scroll_manager.visible_line_count != Some(lines);
         self.scroll_manager.visible_line_count = Some(lines);
-        if opened_first_time {
+        if lines_changed {
             cx.spawn(|editor, mut cx| async move {
                 editor
                     .update(&mut cx, |editor, cx| {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx)
+                        if opened_first_time {
+                            editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx)
+                        }
+                        refresh_colorized_brackets(editor, cx);
                     })
                     .ok()
             })
@@ -376,6 +381,7 @@ impl Editor {
         );
 
         self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+        refresh_colorized_brackets(self, cx);
     }
 
     pub fn scroll_position(&self, cx: &mut ViewContext<Self>) -> gpui::Point<f32> {
@@ -392,6 +398,7 @@ impl Editor {
             .row;
         self.scroll_manager
             .set_anchor(scroll_anchor, top_row, true, false, workspace_id, cx);
+        refresh_colorized_brackets(self, cx);
     }
 
     pub(crate) fn set_scroll_anchor_remote(
@@ -409,6 +416,7 @@ impl Editor {
         let top_row = scroll_anchor.anchor.to_point(snapshot).row;
         self.scroll_manager
             .set_anchor(scroll_anchor, top_row, false, false, workspace_id, cx);
+        refresh_colorized_brackets(self, cx);
     }
 
     pub fn scroll_screen(&mut self, amount: &ScrollAmount, cx: &mut ViewContext<Self>) {
@@ -0,0 +1,483 @@
+//! Signature help shows the signatures of the call surrounding the cursor, with the parameter
+//! that's being typed in bold. When a function has several overloads, they can be cycled through.
+
+use crate::{
+    hover_popover::parse_blocks, Editor, EditorStyle, ShowSignatureHelp, SignatureHelpNext,
+    SignatureHelpPrev,
+};
+use gpui::{
+    div, AnyElement, FontWeight, HighlightStyle, InteractiveElement, IntoElement, ParentElement,
+    Pixels, Size, StatefulInteractiveElement, Styled, StyledText, Task, ViewContext, WeakView,
+};
+use language::ParsedMarkdown;
+use project::SignatureHelp;
+use std::slice;
+use ui::prelude::*;
+use util::ResultExt;
+use workspace::Workspace;
+
+#[derive(Default)]
+pub struct SignatureHelpState {
+    popover: Option<SignatureHelpPopover>,
+    task: Option<Task<()>>,
+}
+
+struct SignatureHelpPopover {
+    help: SignatureHelp,
+    /// The documentation of each signature, parsed as markdown.
+    documentation: Vec<Option<ParsedMarkdown>>,
+    selected_signature: usize,
+    /// Whether the selected signature was picked by the user, rather than the language server.
+    user_selected: bool,
+}
+
+impl SignatureHelpState {
+    pub fn visible(&self) -> bool {
+        self.popover.is_some()
+    }
+
+    pub fn has_overloads(&self) -> bool {
+        self.popover
+            .as_ref()
+            .map_or(false, |popover| popover.help.signatures.len() > 1)
+    }
+
+    fn show(&mut self, help: SignatureHelp, documentation: Vec<Option<ParsedMarkdown>>) {
+        // Keep the overload the user picked while the call's signatures stay the same, and
+        // otherwise follow the language server, e.g. when moving into a nested call.
+        let selected_signature = match self.popover.as_ref() {
+            Some(popover)
+                if popover.user_selected
+                    && popover
+                        .help
+                        .signatures
+                        .iter()
+                        .map(|signature| &signature.label)
+                        .eq(help.signatures.iter().map(|signature| &signature.label)) =>
+            {
+                Some(popover.selected_signature)
+            }
+            _ => None,
+        };
+
+        self.popover = Some(SignatureHelpPopover {
+            selected_signature: selected_signature.unwrap_or(help.active_signature),
+            user_selected: selected_signature.is_some(),
+            help,
+            documentation,
+        });
+    }
+
+    pub fn render(
+        &self,
+        style: &EditorStyle,
+        max_size: Size<Pixels>,
+        workspace: Option<WeakView<Workspace>>,
+        cx: &mut ViewContext<Editor>,
+    ) -> Option<AnyElement> {
+        let popover = self.popover.as_ref()?;
+        let signature = popover.help.signatures.get(popover.selected_signature)?;
+
+        let active_parameter = signature
+            .active_parameter
+            .and_then(|ix| signature.parameters.get(ix)?.as_ref())
+            .map(|range| {
+                (
+                    range.clone(),
+                    HighlightStyle {
+                        font_weight: Some(FontWeight::BOLD),
+                        ..Default::default()
+                    },
+                )
+            });
+        let label =
+            StyledText::new(signature.label.clone()).with_highlights(&style.text, active_parameter);
+
+        let signature_count = popover.help.signatures.len();
+        let documentation = popover
+            .documentation
+            .get(popover.selected_signature)
+            .and_then(Option::as_ref);
+
+        Some(
+            div()
+                .id("signature_help_popover")
+                .elevation_2(cx)
+                .p_2()
+                .overflow_y_scroll()
+                .max_w(max_size.width)
+                .max_h(max_size.height)
+                // Prevent a mouse move on the popover from being propagated to the editor,
+                // because that would show a hover popover over it.
+                .on_mouse_move(|_, cx| cx.stop_propagation())
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(label)
+                        .when(signature_count > 1, |this| {
+                            this.child(
+                                Label::new(format!(
+                                    "{}/{}",
+                                    popover.selected_signature + 1,
+                                    signature_count
+                                ))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                            )
+                        }),
+                )
+                .when_some(documentation, |this, documentation| {
+                    this.child(
+                        div()
+                            .mt_1()
+                            .pt_1()
+                            .border_t_1()
+                            .border_color(cx.theme().colors().border_variant)
+                            .child(crate::render_parsed_markdown(
+                                "signature_documentation",
+                                documentation,
+                                style,
+                                workspace,
+                                cx,
+                            )),
+                    )
+                })
+                .into_any_element(),
+        )
+    }
+}
+
+impl Editor {
+    pub fn show_signature_help(&mut self, _: &ShowSignatureHelp, cx: &mut ViewContext<Self>) {
+        self.request_signature_help(None, cx);
+    }
+
+    pub fn signature_help_next(&mut self, _: &SignatureHelpNext, cx: &mut ViewContext<Self>) {
+        self.cycle_signatures(1, cx);
+    }
+
+    pub fn signature_help_prev(&mut self, _: &SignatureHelpPrev, cx: &mut ViewContext<Self>) {
+        self.cycle_signatures(-1, cx);
+    }
+
+    fn cycle_signatures(&mut self, delta: isize, cx: &mut ViewContext<Self>) {
+        let Some(popover) = self.signature_help_state.popover.as_mut() else {
+            cx.propagate();
+            return;
+        };
+        let signature_count = popover.help.signatures.len() as isize;
+        popover.selected_signature =
+            (popover.selected_signature as isize + delta).rem_euclid(signature_count) as usize;
+        popover.user_selected = true;
+        cx.notify();
+    }
+
+    /// Hides the signature help popover, returning whether it was shown.
+    pub fn hide_signature_help(&mut self, cx: &mut ViewContext<Self>) -> bool {
+        self.signature_help_state.task = None;
+        let was_visible = self.signature_help_state.popover.take().is_some();
+        if was_visible {
+            cx.notify();
+        }
+        was_visible
+    }
+
+    pub(crate) fn trigger_signature_help_on_input(
+        &mut self,
+        text: &str,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+        let position = self.selections.newest_anchor().head();
+        let Some((buffer, _)) = self.buffer.read(cx).text_anchor_for_position(position, cx) else {
+            return;
+        };
+        let is_retrigger = self.signature_help_state.visible();
+        if project
+            .read(cx)
+            .is_signature_help_trigger(buffer.read(cx), text, is_retrigger, cx)
+        {
+            self.request_signature_help(Some(text.to_string()), cx);
+        }
+    }
+
+    /// Requests signature help again once the cursor moved, so the shown signature and
+    /// parameter keep up with what's being typed.
+    pub(crate) fn refresh_signature_help(&mut self, cx: &mut ViewContext<Self>) {
+        if self.signature_help_state.visible() {
+            self.request_signature_help(None, cx);
+        }
+    }
+
+    fn request_signature_help(
+        &mut self,
+        trigger_character: Option<String>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.pending_rename.is_some() {
+            return;
+        }
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let position = self.selections.newest_anchor().head();
+        let Some((buffer, buffer_position)) =
+            self.buffer.read(cx).text_anchor_for_position(position, cx)
+        else {
+            return;
+        };
+
+        let is_retrigger = self.signature_help_state.visible();
+        let language = buffer.read(cx).language().cloned();
+        let language_registry = project.read(cx).languages().clone();
+        let request = project.update(cx, |project, cx| {
+            project.signature_help(
+                &buffer,
+                buffer_position,
+                trigger_character,
+                is_retrigger,
+                cx,
+            )
+        });
+
+        self.signature_help_state.task = Some(cx.spawn(|editor, mut cx| async move {
+            let help = request.await.log_err().flatten();
+            let mut documentation = Vec::new();
+            for signature in help.iter().flat_map(|help| &help.signatures) {
+                documentation.push(match &signature.documentation {
+                    Some(block) => Some(
+                        parse_blocks(slice::from_ref(block), &language_registry, language.clone())
+                            .await,
+                    ),
+                    None => None,
+                });
+            }
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    editor.signature_help_state.task = None;
+                    match help {
+                        Some(help) => editor.signature_help_state.show(help, documentation),
+                        None => editor.signature_help_state.popover = None,
+                    }
+                    cx.notify();
+                })
+                .ok();
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        editor_tests::init_test, test::editor_lsp_test_context::EditorLspTestContext, Cancel,
+    };
+    use indoc::indoc;
+    use smol::stream::StreamExt;
+
+    fn signatures() -> Vec<lsp::SignatureInformation> {
+        vec![
+            lsp::SignatureInformation {
+                label: "fn add(a: i32, b: i32) -> i32".to_string(),
+                documentation: Some(lsp::Documentation::String("Adds integers".to_string())),
+                parameters: Some(vec![
+                    lsp::ParameterInformation {
+                        label: lsp::ParameterLabel::Simple("a: i32".to_string()),
+                        documentation: None,
+                    },
+                    lsp::ParameterInformation {
+                        label: lsp::ParameterLabel::Simple("b: i32".to_string()),
+                        documentation: None,
+                    },
+                ]),
+                active_parameter: None,
+            },
+            lsp::SignatureInformation {
+                label: "fn add(a: f64, b: f64) -> f64".to_string(),
+                documentation: None,
+                parameters: Some(vec![
+                    lsp::ParameterInformation {
+                        label: lsp::ParameterLabel::LabelOffsets([7, 13]),
+                        documentation: None,
+                    },
+                    lsp::ParameterInformation {
+                        label: lsp::ParameterLabel::LabelOffsets([15, 21]),
+                        documentation: None,
+                    },
+                ]),
+                active_parameter: None,
+            },
+        ]
+    }
+
+    fn active_parameter_text(editor: &Editor) -> Option<String> {
+        let popover = editor.signature_help_state.popover.as_ref()?;
+        let signature = &popover.help.signatures[popover.selected_signature];
+        let range = signature
+            .parameters
+            .get(signature.active_parameter?)?
+            .as_ref()?;
+        Some(signature.label[range.clone()].to_string())
+    }
+
+    #[gpui::test]
+    async fn test_signature_help(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                signature_help_provider: Some(lsp::SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                    retrigger_characters: None,
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn main() { add(1ˇ) }
+        "});
+        let mut requests = cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(
+            |_, params, _| async move {
+                let context = params.context.unwrap();
+                let active_parameter = if context.trigger_character.as_deref() == Some(",") {
+                    1
+                } else {
+                    0
+                };
+                Ok(Some(lsp::SignatureHelp {
+                    signatures: signatures(),
+                    active_signature: Some(0),
+                    active_parameter: Some(active_parameter),
+                }))
+            },
+        );
+
+        cx.update_editor(|editor, cx| editor.show_signature_help(&ShowSignatureHelp, cx));
+        requests.next().await;
+        cx.condition(|editor, _| editor.signature_help_state.visible())
+            .await;
+        cx.editor(|editor, _| {
+            let popover = editor.signature_help_state.popover.as_ref().unwrap();
+            assert_eq!(popover.selected_signature, 0);
+            assert!(editor.signature_help_state.has_overloads());
+            assert_eq!(active_parameter_text(editor).as_deref(), Some("a: i32"));
+            assert_eq!(
+                popover.documentation[0].as_ref().unwrap().text,
+                "Adds integers"
+            );
+            assert!(popover.documentation[1].is_none());
+        });
+
+        // Overloads can be cycled through, wrapping around at either end.
+        cx.update_editor(|editor, cx| editor.signature_help_next(&SignatureHelpNext, cx));
+        cx.editor(|editor, _| {
+            assert_eq!(active_parameter_text(editor).as_deref(), Some("a: f64"));
+        });
+        cx.update_editor(|editor, cx| {
+            editor.signature_help_prev(&SignatureHelpPrev, cx);
+            editor.signature_help_prev(&SignatureHelpPrev, cx);
+        });
+        cx.editor(|editor, _| {
+            assert_eq!(active_parameter_text(editor).as_deref(), Some("a: f64"));
+        });
+
+        // Typing the next argument moves to the next parameter, keeping the picked overload.
+        cx.simulate_keystroke(",");
+        requests.next().await;
+        cx.run_until_parked();
+        cx.assert_editor_state(indoc! {"
+            fn main() { add(1,ˇ) }
+        "});
+        cx.editor(|editor, _| {
+            let popover = editor.signature_help_state.popover.as_ref().unwrap();
+            assert_eq!(popover.selected_signature, 1);
+            assert_eq!(active_parameter_text(editor).as_deref(), Some("b: f64"));
+        });
+
+        cx.update_editor(|editor, cx| editor.cancel(&Cancel, cx));
+        cx.editor(|editor, _| assert!(!editor.signature_help_state.visible()));
+    }
+
+    #[gpui::test]
+    async fn test_signature_help_parameter_ranges(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                signature_help_provider: Some(lsp::SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string()]),
+                    retrigger_characters: Some(vec![",".to_string()]),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            fn main() { valueˇ }
+        "});
+        let mut requests = cx.handle_request::<lsp::request::SignatureHelpRequest, _, _>(
+            |_, params, _| async move {
+                let context = params.context.unwrap();
+                let active_parameter = if context.trigger_character.as_deref() == Some(",") {
+                    2
+                } else {
+                    0
+                };
+                Ok(Some(lsp::SignatureHelp {
+                    signatures: vec![lsp::SignatureInformation {
+                        label: "fn value(value: i32, value2: i32)".to_string(),
+                        documentation: None,
+                        parameters: Some(
+                            ["value", "missing", "value"]
+                                .into_iter()
+                                .map(|label| lsp::ParameterInformation {
+                                    label: lsp::ParameterLabel::Simple(label.to_string()),
+                                    documentation: None,
+                                })
+                                .collect(),
+                        ),
+                        active_parameter: None,
+                    }],
+                    active_signature: Some(0),
+                    active_parameter: Some(active_parameter),
+                }))
+            },
+        );
+
+        // A retrigger character doesn't request signature help while none is shown.
+        cx.simulate_keystroke(",");
+        cx.run_until_parked();
+        cx.editor(|editor, _| assert!(!editor.signature_help_state.visible()));
+
+        cx.simulate_keystroke("(");
+        requests.next().await;
+        cx.condition(|editor, _| editor.signature_help_state.visible())
+            .await;
+        cx.editor(|editor, _| {
+            let popover = editor.signature_help_state.popover.as_ref().unwrap();
+            let parameters = &popover.help.signatures[0].parameters;
+            // Parameters are found after the function's name and after the parameter before
+            // them, and those that aren't found keep their place.
+            assert_eq!(parameters, &[Some(9..14), None, Some(21..26)]);
+        });
+
+        cx.simulate_keystroke(",");
+        requests.next().await;
+        cx.run_until_parked();
+        cx.editor(|editor, _| {
+            assert_eq!(active_parameter_text(editor).as_deref(), Some("value"));
+            let popover = editor.signature_help_state.popover.as_ref().unwrap();
+            assert_eq!(popover.help.signatures[0].active_parameter, Some(2));
+        });
+    }
+}
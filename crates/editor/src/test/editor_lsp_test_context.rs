@@ -143,6 +143,13 @@ impl EditorLspTestContext {
                 ("<" @open ">" @close)
                 ("\"" @open "\"" @close)
                 (closure_parameters "|" @open "|" @close)"#})),
+            text_objects: Some(Cow::from(indoc! {r#"
+                (function_item
+                    body: (_) @function.inside) @function.around
+                (struct_item
+                    body: (_) @class.inside) @class.around
+                (parameters (_) @parameter.inside)
+                (arguments (_) @parameter.inside)"#})),
             ..Default::default()
         })
         .expect("Could not parse queries");
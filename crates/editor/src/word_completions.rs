@@ -0,0 +1,198 @@
+//! Word completions offer the words in the open buffers of a buffer's language, for buffers that
+//! no language server provides completions for, such as plain text, logs or languages without one.
+
+use collections::{BTreeSet, HashMap};
+use fuzzy::StringMatchCandidate;
+use gpui::{AppContext, EntityId, Model, Task};
+use language::{
+    char_kind, Buffer, BufferSnapshot, CharKind, CodeLabel, Completion, Documentation, ToOffset,
+};
+use lsp::LanguageServerId;
+use parking_lot::Mutex;
+use project::Project;
+use std::sync::Arc;
+
+/// The server id given to word completions, which don't come from a language server.
+pub(crate) const WORD_COMPLETIONS_SERVER_ID: LanguageServerId = LanguageServerId(usize::MAX);
+
+/// The least number of characters in a word for it to be offered as a completion.
+const MIN_WORD_LEN: usize = 3;
+/// The most words offered at once, so that completing in large buffers stays responsive.
+const MAX_WORD_COMPLETIONS: usize = 1000;
+
+/// The words of each open buffer as of its version when they were collected, so that buffers
+/// that haven't changed since the last completion aren't scanned again.
+#[derive(Default)]
+struct BufferWords(Arc<Mutex<HashMap<EntityId, (clock::Global, Arc<BTreeSet<String>>)>>>);
+
+/// Completes the word at the position with the words in the open buffers that have the same
+/// language as the buffer, best matches for the typed word first.
+pub(crate) fn word_completions(
+    project: &Model<Project>,
+    buffer: &Model<Buffer>,
+    position: text::Anchor,
+    cx: &mut AppContext,
+) -> Task<Vec<Completion>> {
+    let snapshot = buffer.read(cx).snapshot();
+    let language_name = snapshot.language().map(|language| language.name());
+    let buffers = project.read(cx).opened_buffers();
+    let buffer_words = cx.default_global::<BufferWords>().0.clone();
+    buffer_words.lock().retain(|buffer_id, _| {
+        buffers
+            .iter()
+            .any(|buffer| buffer.entity_id() == *buffer_id)
+    });
+    let snapshots = buffers
+        .into_iter()
+        .map(|buffer| (buffer.entity_id(), buffer.read(cx).snapshot()))
+        .filter(|(_, buffer)| buffer.language().map(|language| language.name()) == language_name)
+        .collect::<Vec<_>>();
+
+    let executor = cx.background_executor().clone();
+    cx.background_executor().spawn(async move {
+        let offset = position.to_offset(&snapshot);
+        let (word_range, kind) = snapshot.surrounding_word(offset);
+        let typed_range = if kind == Some(CharKind::Word) {
+            word_range.start..offset
+        } else {
+            offset..offset
+        };
+        let typed_word = snapshot
+            .text_for_range(typed_range.clone())
+            .collect::<String>();
+
+        let mut words = BTreeSet::new();
+        for (buffer_id, buffer) in &snapshots {
+            let cached_words = buffer_words
+                .lock()
+                .get(buffer_id)
+                .filter(|(version, _)| version == buffer.version())
+                .map(|(_, words)| words.clone());
+            let buffer_words = cached_words.unwrap_or_else(|| {
+                let words = Arc::new(collect_words(buffer));
+                buffer_words
+                    .lock()
+                    .insert(*buffer_id, (buffer.version().clone(), words.clone()));
+                words
+            });
+            words.extend(
+                buffer_words
+                    .iter()
+                    .filter(|word| **word != typed_word)
+                    .cloned(),
+            );
+        }
+
+        let candidates = words
+            .into_iter()
+            .enumerate()
+            .map(|(id, word)| StringMatchCandidate::new(id, word))
+            .collect::<Vec<_>>();
+        let matches = fuzzy::match_strings(
+            &candidates,
+            &typed_word,
+            false,
+            MAX_WORD_COMPLETIONS,
+            &Default::default(),
+            executor,
+        )
+        .await;
+
+        let old_range =
+            snapshot.anchor_before(typed_range.start)..snapshot.anchor_after(typed_range.end);
+        matches
+            .into_iter()
+            .map(|word| Completion {
+                old_range: old_range.clone(),
+                new_text: word.string.clone(),
+                label: CodeLabel::plain(word.string.clone(), None),
+                server_id: WORD_COMPLETIONS_SERVER_ID,
+                // Words have nothing to resolve.
+                documentation: Some(Documentation::Undocumented),
+                lsp_completion: lsp::CompletionItem {
+                    label: word.string,
+                    kind: Some(lsp::CompletionItemKind::TEXT),
+                    ..Default::default()
+                },
+            })
+            .collect()
+    })
+}
+
+fn collect_words(buffer: &BufferSnapshot) -> BTreeSet<String> {
+    let scope = buffer.language_scope_at(0);
+    let mut words = BTreeSet::new();
+    let mut word = String::new();
+    for char in buffer.chars().chain(Some(' ')) {
+        if char_kind(&scope, char) == CharKind::Word {
+            word.push(char);
+        } else if !word.is_empty() {
+            if word.chars().count() >= MIN_WORD_LEN {
+                words.insert(word.clone());
+            }
+            word.clear();
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        editor_tests::init_test, test::editor_test_context::EditorTestContext, ConfirmCompletion,
+        ContextMenu,
+    };
+    use language::language_settings::AllLanguageSettings;
+    use settings::SettingsStore;
+
+    fn completion_labels(cx: &mut EditorTestContext) -> Vec<String> {
+        cx.update_editor(|editor, _| match editor.context_menu.read().as_ref() {
+            Some(ContextMenu::Completions(menu)) => {
+                menu.matches.iter().map(|m| m.string.clone()).collect()
+            }
+            _ => Vec::new(),
+        })
+    }
+
+    #[gpui::test]
+    async fn test_word_completions(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorTestContext::new(cx).await;
+
+        cx.set_state("hello wide world\nheˇ");
+        cx.simulate_keystroke("l");
+        cx.executor().run_until_parked();
+        assert_eq!(completion_labels(&mut cx), &["hello"]);
+
+        cx.update_editor(|editor, cx| {
+            editor
+                .confirm_completion(&ConfirmCompletion::default(), cx)
+                .unwrap()
+        })
+        .await
+        .unwrap();
+        cx.assert_editor_state("hello wide world\nhelloˇ");
+
+        // Words are collected again once the buffer changes, and those not matching the typed
+        // word are left out.
+        cx.set_state("hello wide world helium\nheˇ");
+        cx.simulate_keystroke("l");
+        cx.executor().run_until_parked();
+        let mut labels = completion_labels(&mut cx);
+        labels.sort();
+        assert_eq!(labels, &["helium", "hello"]);
+
+        // Word completions can be turned off.
+        _ = cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<AllLanguageSettings>(cx, |settings| {
+                    settings.defaults.word_completions = Some(false);
+                });
+            })
+        });
+        cx.set_state("hello wide world\nheˇ");
+        cx.simulate_keystroke("l");
+        cx.executor().run_until_parked();
+        assert!(completion_labels(&mut cx).is_empty());
+    }
+}
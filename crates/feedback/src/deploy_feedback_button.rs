@@ -40,6 +40,10 @@ impl Render for DeployFeedbackButton {
 }
 
 impl StatusItemView for DeployFeedbackButton {
+    fn status_item_id(&self) -> &'static str {
+        "feedback"
+    }
+
     fn set_active_pane_item(
         &mut self,
         _item: Option<&dyn ItemHandle>,
@@ -1,15 +1,20 @@
 #[cfg(test)]
 mod file_finder_tests;
 
+mod file_finder_settings;
+
 use collections::HashMap;
 use editor::{scroll::Autoscroll, Bias, Editor};
+use file_finder_settings::FileFinderSettings;
+use file_icons::FileIcons;
 use fuzzy::{CharBag, PathMatch, PathMatchCandidate};
 use gpui::{
     actions, rems, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
-    ParentElement, Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
+    ParentElement, Render, Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView,
 };
 use picker::{Picker, PickerDelegate};
 use project::{PathMatchCandidateSet, Project, ProjectPath, WorktreeId};
+use settings::Settings;
 use std::{
     cmp,
     path::{Path, PathBuf},
@@ -23,15 +28,20 @@ use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
 use util::{paths::PathLikeWithPosition, post_inc, ResultExt};
 use workspace::{ModalView, Workspace};
 
-actions!(file_finder, [Toggle]);
+actions!(file_finder, [Toggle, ToggleSearchExcluded]);
 
 impl ModalView for FileFinder {}
 
 pub struct FileFinder {
     picker: View<Picker<FileFinderDelegate>>,
+    /// Re-runs the query once a worktree finishes scanning, so that the search-excluded mode
+    /// finds the files in the directories it loaded.
+    refresh_after_scan: Task<()>,
+    _project_subscription: Subscription,
 }
 
 pub fn init(cx: &mut AppContext) {
+    FileFinderSettings::register(cx);
     cx.observe_new_views(FileFinder::register).detach();
 }
 
@@ -109,9 +119,60 @@ impl FileFinder {
     }
 
     fn new(delegate: FileFinderDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let project_subscription = cx.subscribe(&delegate.project, |this, project, event, cx| {
+            if let project::Event::WorktreeUpdatedEntries(worktree_id, _) = event {
+                this.refresh_after_scan(project, *worktree_id, cx);
+            }
+        });
         Self {
             picker: cx.new_view(|cx| Picker::new(delegate, cx)),
+            refresh_after_scan: Task::ready(()),
+            _project_subscription: project_subscription,
+        }
+    }
+
+    fn refresh_after_scan(
+        &mut self,
+        project: Model<Project>,
+        worktree_id: WorktreeId,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if !self.picker.read(cx).delegate.search_excluded {
+            return;
         }
+        let Some(scan_complete) = project
+            .read(cx)
+            .worktree_for_id(worktree_id, cx)
+            .and_then(|worktree| Some(worktree.read(cx).as_local()?.scan_complete()))
+        else {
+            return;
+        };
+        self.refresh_after_scan = cx.spawn(|this, mut cx| async move {
+            scan_complete.await;
+            this.update(&mut cx, |this, cx| {
+                this.picker.update(cx, |picker, cx| picker.refresh(cx))
+            })
+            .ok();
+        });
+    }
+
+    /// Switches to searching the directories that are skipped when scanning worktrees too,
+    /// loading them the first time. Once loaded, they're left out of the results again
+    /// when switching back.
+    fn toggle_search_excluded(&mut self, _: &ToggleSearchExcluded, cx: &mut ViewContext<Self>) {
+        self.picker.update(cx, |picker, cx| {
+            let delegate = &mut picker.delegate;
+            delegate.search_excluded = !delegate.search_excluded;
+            if delegate.search_excluded {
+                for worktree in delegate.project.read(cx).visible_worktrees(cx) {
+                    if let Some(worktree) = worktree.read(cx).as_local() {
+                        worktree.load_deferred_directories();
+                    }
+                }
+            }
+            picker.refresh(cx);
+        });
+        cx.notify();
     }
 }
 
@@ -124,8 +185,22 @@ impl FocusableView for FileFinder {
 }
 
 impl Render for FileFinder {
-    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
-        v_flex().w(rems(34.)).child(self.picker.clone())
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let search_excluded = self.picker.read(cx).delegate.search_excluded;
+        v_flex()
+            .key_context("FileFinder")
+            .w(rems(34.))
+            .on_action(cx.listener(Self::toggle_search_excluded))
+            .child(self.picker.clone())
+            .when(search_excluded, |el| {
+                el.child(
+                    h_flex().px_2().py_1().child(
+                        Label::new("Including excluded directories")
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+                )
+            })
     }
 }
 
@@ -142,6 +217,7 @@ pub struct FileFinderDelegate {
     selected_index: Option<usize>,
     cancel_flag: Arc<AtomicBool>,
     history_items: Vec<FoundPath>,
+    search_excluded: bool,
 }
 
 /// Use a custom ordering for file finder: the regular one
@@ -375,6 +451,7 @@ impl FileFinderDelegate {
             selected_index: None,
             cancel_flag: Arc::new(AtomicBool::new(false)),
             history_items,
+            search_excluded: false,
         }
     }
 
@@ -427,6 +504,12 @@ impl FileFinderDelegate {
             let did_cancel = cancel_flag.load(atomic::Ordering::Relaxed);
             picker
                 .update(&mut cx, |picker, cx| {
+                    let matches = matches
+                        .filter(|path_match| {
+                            picker.delegate.search_excluded
+                                || !picker.delegate.is_in_deferred_directory(&path_match.0, cx)
+                        })
+                        .collect::<Vec<_>>();
                     picker.delegate.selected_index.take();
                     picker
                         .delegate
@@ -436,6 +519,23 @@ impl FileFinderDelegate {
         })
     }
 
+    /// Whether the match is within a directory that's only scanned on demand, which is only
+    /// searched in the search-excluded mode.
+    fn is_in_deferred_directory(&self, path_match: &PathMatch, cx: &AppContext) -> bool {
+        self.project
+            .read(cx)
+            .worktree_for_id(WorktreeId::from_usize(path_match.worktree_id), cx)
+            .and_then(|worktree| {
+                Some(
+                    worktree
+                        .read(cx)
+                        .as_local()?
+                        .is_path_deferred(&path_match.path),
+                )
+            })
+            .unwrap_or(false)
+    }
+
     fn set_search_matches(
         &mut self,
         search_id: usize,
@@ -835,12 +935,20 @@ impl PickerDelegate for FileFinderDelegate {
 
         let (file_name, file_name_positions, full_path, full_path_positions) =
             self.labels_for_match(path_match, cx, ix);
+        let file_icon = if FileFinderSettings::get_global(cx).file_icons {
+            FileIcons::get_icon(Path::new(&file_name), cx)
+        } else {
+            None
+        };
 
         Some(
             ListItem::new(ix)
                 .spacing(ListItemSpacing::Sparse)
                 .inset(true)
                 .selected(selected)
+                .start_slot::<Icon>(
+                    file_icon.map(|icon| Icon::from_path(icon.to_string()).color(Color::Muted)),
+                )
                 .child(
                     v_flex()
                         .child(HighlightedLabel::new(file_name, file_name_positions))
@@ -450,6 +450,75 @@ async fn test_ignored_root(cx: &mut TestAppContext) {
     picker.update(cx, |picker, _| assert_eq!(picker.delegate.matches.len(), 7));
 }
 
+#[gpui::test]
+async fn test_search_excluded(cx: &mut TestAppContext) {
+    let app_state = init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<settings::SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<project::project_settings::ProjectSettings>(
+                cx,
+                |project_settings| {
+                    project_settings.file_scan_deferred_directories =
+                        Some(vec!["**/node_modules".to_string()]);
+                },
+            );
+        });
+    });
+    app_state
+        .fs
+        .as_fake()
+        .insert_tree(
+            "/root",
+            json!({
+                "node_modules": {
+                    "dep": {
+                        "index.js": "",
+                    },
+                },
+                "src": {
+                    "index.rs": "",
+                },
+            }),
+        )
+        .await;
+
+    let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+    let (picker, _, cx) = build_find_picker(project, cx);
+
+    cx.simulate_input("index");
+    picker.update(cx, |picker, _| {
+        assert_eq!(
+            collect_search_matches(picker).search_only(),
+            vec![PathBuf::from("src/index.rs")],
+        );
+    });
+
+    // The query is re-run once the deferred directories are loaded.
+    cx.dispatch_action(ToggleSearchExcluded);
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        let mut matches = collect_search_matches(picker).search_only();
+        matches.sort();
+        assert_eq!(
+            matches,
+            vec![
+                PathBuf::from("node_modules/dep/index.js"),
+                PathBuf::from("src/index.rs"),
+            ],
+        );
+    });
+
+    // Their files are left out again when the mode is turned off.
+    cx.dispatch_action(ToggleSearchExcluded);
+    cx.run_until_parked();
+    picker.update(cx, |picker, _| {
+        assert_eq!(
+            collect_search_matches(picker).search_only(),
+            vec![PathBuf::from("src/index.rs")],
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_single_file_worktrees(cx: &mut TestAppContext) {
     let app_state = init_test(cx);
@@ -0,0 +1,324 @@
+use std::{path::Path, str, sync::Arc};
+
+use anyhow::{Context as _, Result};
+use collections::HashMap;
+use fs::Fs;
+use futures::StreamExt;
+use gpui::{AppContext, AssetSource, AsyncAppContext};
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, RootSchema, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use settings::{Settings, SettingsJsonSchemaParams};
+use util::{paths::PathExt, ResultExt};
+
+/// The name of the icon theme bundled with Zed.
+pub const DEFAULT_ICON_THEME: &'static str = "Zed (Default)";
+pub const FILE_TYPES_ASSET: &'static str = "icons/file_icons/file_types.json";
+
+const DEFAULT_FILE_TYPE: &'static str = "default";
+const COLLAPSED_DIRECTORY_TYPE: &'static str = "collapsed_folder";
+const EXPANDED_DIRECTORY_TYPE: &'static str = "expanded_folder";
+const COLLAPSED_CHEVRON_TYPE: &'static str = "collapsed_chevron";
+const EXPANDED_CHEVRON_TYPE: &'static str = "expanded_chevron";
+
+pub fn init(assets: impl AssetSource, cx: &mut AppContext) {
+    IconThemeSettings::register(cx);
+    cx.set_global(FileIcons::default());
+    FileIcons::load_default_icon_theme(assets, cx);
+}
+
+#[derive(Deserialize, Debug)]
+struct TypeConfig {
+    icon: Arc<str>,
+}
+
+/// A set of file icons, mapping the suffixes of file names to types of file and those
+/// types to the icons that represent them.
+#[derive(Deserialize, Debug)]
+pub struct IconTheme {
+    #[serde(default)]
+    pub name: String,
+    suffixes: HashMap<String, String>,
+    types: HashMap<String, TypeConfig>,
+}
+
+impl IconTheme {
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    fn icon_for_type(&self, file_type: &str) -> Option<Arc<str>> {
+        self.types
+            .get(file_type)
+            .map(|type_config| type_config.icon.clone())
+    }
+
+    fn icon_for_path(&self, path: &Path) -> Option<Arc<str>> {
+        path.icon_suffix()
+            .and_then(|suffix| self.suffixes.get(suffix))
+            .and_then(|file_type| self.icon_for_type(file_type))
+            .or_else(|| self.icon_for_type(DEFAULT_FILE_TYPE))
+    }
+
+    /// Makes the icon paths that are relative to the theme file absolute, given the
+    /// directory the theme was loaded from.
+    fn resolve_icon_paths(&mut self, dir: &Path) {
+        for type_config in self.types.values_mut() {
+            let icon_path = Path::new(type_config.icon.as_ref());
+            if icon_path.is_relative() {
+                type_config.icon = dir.join(icon_path).to_string_lossy().into();
+            }
+        }
+    }
+}
+
+/// The icon themes that are available to pick file icons from.
+#[derive(Default)]
+pub struct FileIcons {
+    icon_themes: HashMap<String, Arc<IconTheme>>,
+}
+
+impl FileIcons {
+    fn load_default_icon_theme(assets: impl AssetSource, cx: &mut AppContext) {
+        let icon_theme = assets
+            .load(FILE_TYPES_ASSET)
+            .and_then(|file| IconTheme::from_json(str::from_utf8(&file)?))
+            .log_err();
+        if let Some(mut icon_theme) = icon_theme {
+            icon_theme.name = DEFAULT_ICON_THEME.to_string();
+            Self::insert_icon_theme(icon_theme, cx);
+        }
+    }
+
+    /// Reloads the bundled icon theme, such as after its file has been edited.
+    pub fn reload_default_icon_theme(assets: impl AssetSource, cx: &mut AppContext) {
+        if cx.has_global::<Self>() {
+            Self::load_default_icon_theme(assets, cx);
+        }
+    }
+
+    /// Makes the given icon theme available to select in the `icon_theme` setting,
+    /// replacing any icon theme with the same name.
+    pub fn insert_icon_theme(icon_theme: IconTheme, cx: &mut AppContext) {
+        cx.default_global::<Self>()
+            .icon_themes
+            .insert(icon_theme.name.clone(), Arc::new(icon_theme));
+    }
+
+    /// Loads the icon themes in the given directory. Icon paths in these themes are
+    /// relative to the directory.
+    pub async fn load_user_icon_themes(
+        icon_themes_path: &Path,
+        fs: Arc<dyn Fs>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        let mut icon_theme_paths = fs
+            .read_dir(icon_themes_path)
+            .await
+            .with_context(|| format!("reading icon themes from {icon_themes_path:?}"))?;
+
+        while let Some(icon_theme_path) = icon_theme_paths.next().await {
+            let Some(icon_theme_path) = icon_theme_path.log_err() else {
+                continue;
+            };
+            if icon_theme_path.extension() != Some("json".as_ref()) {
+                continue;
+            }
+
+            let Some(mut icon_theme) = fs
+                .load(&icon_theme_path)
+                .await
+                .and_then(|json| IconTheme::from_json(&json))
+                .with_context(|| format!("loading icon theme from {icon_theme_path:?}"))
+                .log_err()
+            else {
+                continue;
+            };
+            if icon_theme.name.is_empty() {
+                log::error!("icon theme at {icon_theme_path:?} has no name");
+                continue;
+            }
+            icon_theme.resolve_icon_paths(icon_themes_path);
+
+            cx.update(|cx| Self::insert_icon_theme(icon_theme, cx))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the names of the available icon themes, in alphabetical order.
+    pub fn icon_theme_names(cx: &AppContext) -> Vec<String> {
+        let mut names = cx
+            .try_global::<Self>()
+            .map(|this| this.icon_themes.keys().cloned().collect::<Vec<_>>())
+            .unwrap_or_default();
+        names.sort_unstable();
+        names
+    }
+
+    /// Looks up an icon in the selected icon theme, falling back to the default icon
+    /// theme when the selected one doesn't have it.
+    fn get(cx: &AppContext, f: impl Fn(&IconTheme) -> Option<Arc<str>>) -> Option<Arc<str>> {
+        let this = cx.try_global::<Self>()?;
+        let selected_theme = &IconThemeSettings::get_global(cx).icon_theme;
+        this.icon_themes
+            .get(selected_theme)
+            .and_then(|icon_theme| f(icon_theme))
+            .or_else(|| f(this.icon_themes.get(DEFAULT_ICON_THEME)?))
+    }
+
+    pub fn get_icon(path: &Path, cx: &AppContext) -> Option<Arc<str>> {
+        // FIXME: Associate a type with the languages and have the file's language
+        //        override these associations
+        Self::get(cx, |icon_theme| icon_theme.icon_for_path(path))
+    }
+
+    pub fn get_folder_icon(expanded: bool, cx: &AppContext) -> Option<Arc<str>> {
+        let key = if expanded {
+            EXPANDED_DIRECTORY_TYPE
+        } else {
+            COLLAPSED_DIRECTORY_TYPE
+        };
+
+        Self::get(cx, |icon_theme| icon_theme.icon_for_type(key))
+    }
+
+    pub fn get_chevron_icon(expanded: bool, cx: &AppContext) -> Option<Arc<str>> {
+        let key = if expanded {
+            EXPANDED_CHEVRON_TYPE
+        } else {
+            COLLAPSED_CHEVRON_TYPE
+        };
+
+        Self::get(cx, |icon_theme| icon_theme.icon_for_type(key))
+    }
+}
+
+#[derive(Deserialize)]
+pub struct IconThemeSettings {
+    pub icon_theme: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct IconThemeSettingsContent {
+    /// The name of the icon theme to take file icons from.
+    ///
+    /// Default: "Zed (Default)"
+    pub icon_theme: Option<String>,
+}
+
+impl Settings for IconThemeSettings {
+    const KEY: Option<&'static str> = None;
+
+    type FileContent = IconThemeSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self> {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+
+    fn json_schema(
+        generator: &mut SchemaGenerator,
+        _: &SettingsJsonSchemaParams,
+        cx: &AppContext,
+    ) -> RootSchema {
+        let mut root_schema = generator.root_schema_for::<IconThemeSettingsContent>();
+        let icon_theme_names = FileIcons::icon_theme_names(cx)
+            .into_iter()
+            .map(Value::String)
+            .collect();
+        let icon_theme_name_schema = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(icon_theme_names),
+            ..Default::default()
+        };
+        root_schema.schema.object().properties.insert(
+            "icon_theme".to_owned(),
+            Schema::Object(icon_theme_name_schema),
+        );
+        root_schema
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use settings::SettingsStore;
+
+    const ICON_THEME: &str = r#"{
+        "name": "Minimal",
+        "suffixes": { "rs": "rust", "gitignore": "vcs" },
+        "types": {
+            "default": { "icon": "file.svg" },
+            "rust": { "icon": "/icons/rust.svg" },
+            "vcs": { "icon": "vcs.svg" }
+        }
+    }"#;
+
+    #[gpui::test]
+    fn test_icon_themes(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let store = SettingsStore::test(cx);
+            cx.set_global(store);
+            init((), cx);
+
+            let mut default_icon_theme = IconTheme::from_json(
+                r#"{
+                    "suffixes": { "rs": "rust", "toml": "toml" },
+                    "types": {
+                        "default": { "icon": "icons/file_icons/file.svg" },
+                        "rust": { "icon": "icons/file_icons/rust.svg" },
+                        "toml": { "icon": "icons/file_icons/toml.svg" },
+                        "expanded_folder": { "icon": "icons/file_icons/folder_open.svg" }
+                    }
+                }"#,
+            )
+            .unwrap();
+            default_icon_theme.name = DEFAULT_ICON_THEME.into();
+            FileIcons::insert_icon_theme(default_icon_theme, cx);
+
+            let mut icon_theme = IconTheme::from_json(ICON_THEME).unwrap();
+            icon_theme.resolve_icon_paths(Path::new("/config/icon_themes"));
+            FileIcons::insert_icon_theme(icon_theme, cx);
+            assert_eq!(
+                FileIcons::icon_theme_names(cx),
+                ["Minimal", DEFAULT_ICON_THEME]
+            );
+
+            let icon = |path: &str, cx: &AppContext| {
+                FileIcons::get_icon(Path::new(path), cx).map(|icon| icon.to_string())
+            };
+            assert_eq!(
+                icon("src/main.rs", cx).as_deref(),
+                Some("icons/file_icons/rust.svg")
+            );
+
+            cx.update_global(|store: &mut SettingsStore, cx| {
+                store
+                    .set_user_settings(r#"{ "icon_theme": "Minimal" }"#, cx)
+                    .unwrap();
+            });
+            assert_eq!(icon("src/main.rs", cx).as_deref(), Some("/icons/rust.svg"));
+            assert_eq!(
+                icon(".gitignore", cx).as_deref(),
+                Some("/config/icon_themes/vcs.svg")
+            );
+            assert_eq!(
+                icon("Cargo.toml", cx).as_deref(),
+                Some("/config/icon_themes/file.svg")
+            );
+            assert_eq!(
+                FileIcons::get_folder_icon(true, cx).as_deref(),
+                Some("icons/file_icons/folder_open.svg")
+            );
+        });
+    }
+}
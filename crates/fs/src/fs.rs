@@ -11,6 +11,7 @@ pub use notify::Event;
 #[cfg(not(target_os = "macos"))]
 use notify::{Config, Watcher};
 
+use collections::HashMap;
 use futures::{future::BoxFuture, Stream, StreamExt};
 use git2::Repository as LibGitRepository;
 use parking_lot::Mutex;
@@ -20,14 +21,15 @@ use smol::io::{AsyncReadExt, AsyncWriteExt};
 use std::io::Write;
 use std::sync::Arc;
 use std::{
+    ffi::CString,
     io,
-    os::unix::fs::MetadataExt,
+    os::unix::{ffi::OsStrExt, fs::MetadataExt},
     path::{Component, Path, PathBuf},
     pin::Pin,
     time::{Duration, SystemTime},
 };
 use tempfile::NamedTempFile;
-use text::LineEnding;
+use text::{Encoding, LineEnding};
 use util::ResultExt;
 
 #[cfg(any(test, feature = "test-support"))]
@@ -47,8 +49,10 @@ pub trait Fs: Send + Sync {
     async fn remove_file(&self, path: &Path, options: RemoveOptions) -> Result<()>;
     async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read>>;
     async fn load(&self, path: &Path) -> Result<String>;
+    async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>>;
     async fn atomic_write(&self, path: PathBuf, text: String) -> Result<()>;
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()>;
+    async fn write(&self, path: &Path, content: &[u8]) -> Result<()>;
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf>;
     async fn is_file(&self, path: &Path) -> bool;
     async fn metadata(&self, path: &Path) -> Result<Option<Metadata>>;
@@ -63,11 +67,51 @@ pub trait Fs: Send + Sync {
         path: &Path,
         latency: Duration,
     ) -> Pin<Box<dyn Send + Stream<Item = Vec<Event>>>>;
+    /// Watches a path by scanning it for changes at an interval, for filesystems whose
+    /// changes aren't reported to the platform's file watcher.
+    async fn poll(
+        &self,
+        path: &Path,
+        interval: Duration,
+    ) -> Pin<Box<dyn Send + Stream<Item = Vec<Event>>>>;
+    /// Whether the path is on a network filesystem, such as NFS or SMB, where the
+    /// platform's file watcher misses changes made by other machines.
+    async fn is_network_filesystem(&self, path: &Path) -> bool;
 
     fn open_repo(&self, abs_dot_git: &Path) -> Option<Arc<Mutex<dyn GitRepository>>>;
     fn is_fake(&self) -> bool;
     #[cfg(any(test, feature = "test-support"))]
     fn as_fake(&self) -> &FakeFs;
+
+    /// Loads a file's contents, decoding them with the given encoding or, if
+    /// there isn't one, the encoding detected from them.
+    async fn load_with_encoding(
+        &self,
+        path: &Path,
+        encoding: Option<Encoding>,
+    ) -> Result<(String, Encoding)> {
+        let bytes = self.load_bytes(path).await?;
+        let encoding = match encoding {
+            Some(encoding) => encoding,
+            None => Encoding::detect(&bytes)
+                .ok_or_else(|| anyhow!("{} is not a text file", path.display()))?,
+        };
+        Ok((encoding.decode(bytes), encoding))
+    }
+
+    async fn save_with_encoding(
+        &self,
+        path: &Path,
+        text: &Rope,
+        line_ending: LineEnding,
+        encoding: Encoding,
+    ) -> Result<()> {
+        if encoding == Encoding::Utf8 {
+            return self.save(path, text, line_ending).await;
+        }
+        let text = chunks(text, line_ending).collect::<String>();
+        self.write(path, &encoding.encode(&text)?).await
+    }
 }
 
 #[derive(Copy, Clone, Default)]
@@ -100,6 +144,8 @@ pub struct Metadata {
     pub mtime: SystemTime,
     pub is_symlink: bool,
     pub is_dir: bool,
+    /// Whether the file's permissions forbid writing to it.
+    pub is_read_only: bool,
 }
 
 pub struct RealFs;
@@ -184,6 +230,10 @@ impl Fs for RealFs {
         Ok(text)
     }
 
+    async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>> {
+        Ok(smol::fs::read(path).await?)
+    }
+
     async fn atomic_write(&self, path: PathBuf, data: String) -> Result<()> {
         smol::unblock(move || {
             let mut tmp_file = NamedTempFile::new()?;
@@ -210,6 +260,14 @@ impl Fs for RealFs {
         Ok(())
     }
 
+    async fn write(&self, path: &Path, content: &[u8]) -> Result<()> {
+        if let Some(path) = path.parent() {
+            self.create_dir(path).await?;
+        }
+        smol::fs::write(path, content).await?;
+        Ok(())
+    }
+
     async fn canonicalize(&self, path: &Path) -> Result<PathBuf> {
         Ok(smol::fs::canonicalize(path).await?)
     }
@@ -243,6 +301,7 @@ impl Fs for RealFs {
             mtime: metadata.modified().unwrap(),
             is_symlink,
             is_dir: metadata.file_type().is_dir(),
+            is_read_only: metadata.permissions().readonly(),
         }))
     }
 
@@ -287,7 +346,7 @@ impl Fs for RealFs {
     ) -> Pin<Box<dyn Send + Stream<Item = Vec<Event>>>> {
         let (tx, rx) = smol::channel::unbounded();
 
-        let mut watcher = notify::recommended_watcher(move |res| match res {
+        let watcher = notify::recommended_watcher(move |res| match res {
             Ok(event) => {
                 let _ = tx.try_send(vec![event]);
             }
@@ -295,19 +354,71 @@ impl Fs for RealFs {
                 eprintln!("watch error: {:?}", err);
             }
         })
-        .unwrap();
-
-        watcher
-            .configure(Config::default().with_poll_interval(latency))
-            .unwrap();
+        .and_then(|mut watcher| {
+            watcher.configure(Config::default().with_poll_interval(latency))?;
+            watcher.watch(path, notify::RecursiveMode::Recursive)?;
+            Ok(watcher)
+        });
 
-        watcher
-            .watch(path, notify::RecursiveMode::Recursive)
-            .unwrap();
+        match watcher {
+            Ok(watcher) => Box::pin(rx.chain(futures::stream::once(async move {
+                drop(watcher);
+                vec![]
+            }))),
+            // Watches can't be added when the system's limit on them is reached, and in
+            // some containers, so notice changes by scanning for them instead.
+            Err(err) => {
+                log::warn!("failed to watch {path:?}, polling it instead: {err}");
+                self.poll(path, FALLBACK_POLL_INTERVAL).await
+            }
+        }
+    }
 
+    async fn poll(
+        &self,
+        path: &Path,
+        interval: Duration,
+    ) -> Pin<Box<dyn Send + Stream<Item = Vec<Event>>>> {
+        let (tx, rx) = smol::channel::unbounded();
+        let path = path.to_path_buf();
+        std::thread::spawn(move || {
+            let mut files = poll_files(&path);
+            while !tx.is_closed() {
+                std::thread::sleep(interval);
+                let new_files = poll_files(&path);
+                let events = changed_paths(&files, &new_files)
+                    .into_iter()
+                    .map(path_event)
+                    .collect::<Vec<_>>();
+                files = new_files;
+                if !events.is_empty() && smol::block_on(tx.send(events)).is_err() {
+                    break;
+                }
+            }
+        });
         Box::pin(rx)
     }
 
+    async fn is_network_filesystem(&self, path: &Path) -> bool {
+        let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+            return false;
+        };
+        let mut stat = unsafe { std::mem::zeroed::<libc::statfs>() };
+        if unsafe { libc::statfs(c_path.as_ptr(), &mut stat) } != 0 {
+            return false;
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            let name = unsafe { std::ffi::CStr::from_ptr(stat.f_fstypename.as_ptr()) };
+            NETWORK_FILESYSTEM_NAMES.contains(&name.to_bytes())
+        }
+        #[cfg(not(target_os = "macos"))]
+        {
+            NETWORK_FILESYSTEM_MAGICS.contains(&(stat.f_type as i64))
+        }
+    }
+
     fn open_repo(&self, dotgit_path: &Path) -> Option<Arc<Mutex<dyn GitRepository>>> {
         LibGitRepository::open(&dotgit_path)
             .log_err()
@@ -339,6 +450,86 @@ pub fn fs_events_paths(events: Vec<Event>) -> Vec<PathBuf> {
         .collect()
 }
 
+#[cfg(target_os = "macos")]
+fn path_event(path: PathBuf) -> Event {
+    Event {
+        event_id: 0,
+        flags: fsevent::StreamFlags::empty(),
+        path,
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn path_event(path: PathBuf) -> Event {
+    Event::new(notify::EventKind::Any).add_path(path)
+}
+
+/// How often paths that can't be watched natively are scanned for changes instead.
+#[cfg(not(target_os = "macos"))]
+const FALLBACK_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+#[cfg(target_os = "macos")]
+const NETWORK_FILESYSTEM_NAMES: &[&[u8]] = &[b"nfs", b"smbfs", b"afpfs", b"webdav", b"cifs"];
+
+/// The `statfs` magic numbers of NFS, SMB, CIFS, SMB2, AFS, Coda, NCP and 9P.
+#[cfg(not(target_os = "macos"))]
+const NETWORK_FILESYSTEM_MAGICS: &[i64] = &[
+    0x6969, 0x517B, 0xFF534D42, 0xFE534D42, 0x5346414F, 0x73757245, 0x564C, 0x01021997,
+];
+
+/// The state of a path that polling compares between scans to notice changes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct PolledFile {
+    inode: u64,
+    mtime: Option<SystemTime>,
+    len: u64,
+}
+
+/// Records the state of every path under a root, without following symlinks.
+fn poll_files(root: &Path) -> HashMap<PathBuf, PolledFile> {
+    let mut files = HashMap::default();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(path) = stack.pop() {
+        let Ok(metadata) = std::fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if let Ok(entries) = std::fs::read_dir(&path) {
+                stack.extend(entries.filter_map(|entry| Some(entry.ok()?.path())));
+            }
+        }
+        files.insert(
+            path,
+            PolledFile {
+                inode: metadata.ino(),
+                mtime: metadata.modified().ok(),
+                len: metadata.len(),
+            },
+        );
+    }
+    files
+}
+
+/// The paths that were created, removed or modified between two scans.
+fn changed_paths(
+    old_files: &HashMap<PathBuf, PolledFile>,
+    new_files: &HashMap<PathBuf, PolledFile>,
+) -> Vec<PathBuf> {
+    let mut paths = new_files
+        .iter()
+        .filter(|(path, file)| old_files.get(*path) != Some(file))
+        .map(|(path, _)| path.clone())
+        .chain(
+            old_files
+                .keys()
+                .filter(|path| !new_files.contains_key(*path))
+                .cloned(),
+        )
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
 #[cfg(any(test, feature = "test-support"))]
 pub struct FakeFs {
     // Use an unfair lock to ensure tests are deterministic.
@@ -364,7 +555,8 @@ enum FakeFsEntry {
     File {
         inode: u64,
         mtime: SystemTime,
-        content: String,
+        content: Vec<u8>,
+        is_read_only: bool,
     },
     Dir {
         inode: u64,
@@ -514,6 +706,20 @@ impl FakeFs {
         self.write_file_internal(path, content).unwrap()
     }
 
+    pub async fn insert_file_bytes(&self, path: impl AsRef<Path>, content: Vec<u8>) {
+        self.write_file_internal(path, content).unwrap()
+    }
+
+    pub async fn set_read_only(&self, path: impl AsRef<Path>, read_only: bool) {
+        let mut state = self.state.lock();
+        let path = path.as_ref();
+        let entry = state.read_path(path).unwrap();
+        if let FakeFsEntry::File { is_read_only, .. } = &mut *entry.lock() {
+            *is_read_only = read_only;
+        }
+        state.emit_event(&[path]);
+    }
+
     pub async fn insert_symlink(&self, path: impl AsRef<Path>, target: PathBuf) {
         let mut state = self.state.lock();
         let path = path.as_ref();
@@ -533,7 +739,11 @@ impl FakeFs {
         state.emit_event(&[path]);
     }
 
-    pub fn write_file_internal(&self, path: impl AsRef<Path>, content: String) -> Result<()> {
+    pub fn write_file_internal(
+        &self,
+        path: impl AsRef<Path>,
+        content: impl Into<Vec<u8>>,
+    ) -> Result<()> {
         let mut state = self.state.lock();
         let path = path.as_ref();
         let inode = state.next_inode;
@@ -543,7 +753,8 @@ impl FakeFs {
         let file = Arc::new(Mutex::new(FakeFsEntry::File {
             inode,
             mtime,
-            content,
+            content: content.into(),
+            is_read_only: false,
         }));
         state.write_path(path, move |entry| {
             match entry {
@@ -767,7 +978,7 @@ impl FakeFsEntry {
         matches!(self, Self::Symlink { .. })
     }
 
-    fn file_content(&self, path: &Path) -> Result<&String> {
+    fn file_content(&self, path: &Path) -> Result<&Vec<u8>> {
         if let Self::File { content, .. } = self {
             Ok(content)
         } else {
@@ -775,7 +986,7 @@ impl FakeFsEntry {
         }
     }
 
-    fn set_file_content(&mut self, path: &Path, new_content: String) -> Result<()> {
+    fn set_file_content(&mut self, path: &Path, new_content: Vec<u8>) -> Result<()> {
         if let Self::File { content, mtime, .. } = self {
             *mtime = SystemTime::now();
             *content = new_content;
@@ -844,7 +1055,8 @@ impl Fs for FakeFs {
         let file = Arc::new(Mutex::new(FakeFsEntry::File {
             inode,
             mtime,
-            content: String::new(),
+            content: Vec::new(),
+            is_read_only: false,
         }));
         state.write_path(path, |entry| {
             match entry {
@@ -935,7 +1147,8 @@ impl Fs for FakeFs {
                 e.insert(Arc::new(Mutex::new(FakeFsEntry::File {
                     inode,
                     mtime,
-                    content: String::new(),
+                    content: Vec::new(),
+                    is_read_only: false,
                 })))
                 .clone(),
             )),
@@ -1014,11 +1227,16 @@ impl Fs for FakeFs {
     }
 
     async fn open_sync(&self, path: &Path) -> Result<Box<dyn io::Read>> {
-        let text = self.load(path).await?;
-        Ok(Box::new(io::Cursor::new(text)))
+        let content = self.load_bytes(path).await?;
+        Ok(Box::new(io::Cursor::new(content)))
     }
 
     async fn load(&self, path: &Path) -> Result<String> {
+        let content = self.load_bytes(path).await?;
+        Ok(String::from_utf8(content)?)
+    }
+
+    async fn load_bytes(&self, path: &Path) -> Result<Vec<u8>> {
         let path = normalize_path(path);
         self.simulate_random_delay().await;
         let state = self.state.lock();
@@ -1038,7 +1256,17 @@ impl Fs for FakeFs {
     async fn save(&self, path: &Path, text: &Rope, line_ending: LineEnding) -> Result<()> {
         self.simulate_random_delay().await;
         let path = normalize_path(path);
-        let content = chunks(text, line_ending).collect();
+        let content = chunks(text, line_ending).collect::<String>();
+        if let Some(path) = path.parent() {
+            self.create_dir(path).await?;
+        }
+        self.write_file_internal(path, content)?;
+        Ok(())
+    }
+
+    async fn write(&self, path: &Path, content: &[u8]) -> Result<()> {
+        self.simulate_random_delay().await;
+        let path = normalize_path(path);
         if let Some(path) = path.parent() {
             self.create_dir(path).await?;
         }
@@ -1085,17 +1313,24 @@ impl Fs for FakeFs {
 
             let entry = entry.lock();
             Ok(Some(match &*entry {
-                FakeFsEntry::File { inode, mtime, .. } => Metadata {
+                FakeFsEntry::File {
+                    inode,
+                    mtime,
+                    is_read_only,
+                    ..
+                } => Metadata {
                     inode: *inode,
                     mtime: *mtime,
                     is_dir: false,
                     is_symlink,
+                    is_read_only: *is_read_only,
                 },
                 FakeFsEntry::Dir { inode, mtime, .. } => Metadata {
                     inode: *inode,
                     mtime: *mtime,
                     is_dir: true,
                     is_symlink,
+                    is_read_only: false,
                 },
                 FakeFsEntry::Symlink { .. } => unreachable!(),
             }))
@@ -1158,6 +1393,18 @@ impl Fs for FakeFs {
         }))
     }
 
+    async fn poll(
+        &self,
+        path: &Path,
+        interval: Duration,
+    ) -> Pin<Box<dyn Send + Stream<Item = Vec<fsevent::Event>>>> {
+        self.watch(path, interval).await
+    }
+
+    async fn is_network_filesystem(&self, _: &Path) -> bool {
+        false
+    }
+
     fn open_repo(&self, abs_dot_git: &Path) -> Option<Arc<Mutex<dyn GitRepository>>> {
         let state = self.state.lock();
         let entry = state.read_path(abs_dot_git).unwrap();
@@ -1330,4 +1577,29 @@ mod tests {
             "D",
         );
     }
+
+    #[test]
+    fn test_polling_changed_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join("sub")).unwrap();
+        std::fs::write(root.join("sub/modified"), "a").unwrap();
+        std::fs::write(root.join("removed"), "b").unwrap();
+        std::fs::write(root.join("unchanged"), "c").unwrap();
+
+        let old_files = poll_files(root);
+        assert_eq!(
+            changed_paths(&old_files, &poll_files(root)),
+            Vec::<PathBuf>::new()
+        );
+
+        std::fs::write(root.join("sub/modified"), "abc").unwrap();
+        std::fs::remove_file(root.join("removed")).unwrap();
+        std::fs::write(root.join("sub/created"), "d").unwrap();
+        let changed = changed_paths(&old_files, &poll_files(root));
+        assert!(changed.contains(&root.join("sub/created")));
+        assert!(changed.contains(&root.join("sub/modified")));
+        assert!(changed.contains(&root.join("removed")));
+        assert!(!changed.contains(&root.join("unchanged")));
+    }
 }
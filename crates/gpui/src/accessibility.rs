@@ -0,0 +1,284 @@
+use crate::{Bounds, EntityId, FocusId, GlobalElementId, Pixels, SharedString};
+use collections::{FxHashMap, FxHashSet};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    ops::Range,
+};
+
+/// The kind of user interface element an accessibility node represents, which determines
+/// how assistive technologies such as screen readers present it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AccessibilityRole {
+    /// A container for related elements, such as a panel or a pane.
+    #[default]
+    Group,
+    /// A toolbar containing buttons and other controls.
+    Toolbar,
+    /// A button that performs an action when clicked.
+    Button,
+    /// A piece of static text.
+    Label,
+    /// A single line of editable text.
+    TextField,
+    /// Multiple lines of editable text.
+    TextArea,
+    /// A list of items, such as the matches of a picker.
+    List,
+    /// An item in a [`AccessibilityRole::List`].
+    ListItem,
+    /// A list of tabs.
+    TabList,
+    /// A tab in a [`AccessibilityRole::TabList`].
+    Tab,
+}
+
+/// The information an element exposes to assistive technologies.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AccessibilityProperties {
+    /// The kind of element.
+    pub role: AccessibilityRole,
+    /// A short description of the element, such as the text of a button.
+    pub label: Option<SharedString>,
+    /// The contents of the element, such as the text of a text field.
+    pub value: Option<SharedString>,
+    /// The selected range of the value, in UTF-16 code units.
+    pub selected_range: Option<Range<usize>>,
+    /// Whether the element is the selected item of a list or tab list.
+    pub selected: bool,
+}
+
+impl AccessibilityProperties {
+    /// Create the properties of an element with the given role.
+    pub fn new(role: AccessibilityRole) -> Self {
+        Self {
+            role,
+            ..Default::default()
+        }
+    }
+}
+
+/// Identifies a node of an [`AccessibilityTree`] across frames.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AccessibilityNodeId(pub u64);
+
+/// A node of an [`AccessibilityTree`], describing an element that was painted in a window.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessibilityNode {
+    /// The id of the node.
+    pub id: AccessibilityNodeId,
+    /// The id of the node's parent, or `None` if it's at the root of the tree.
+    pub parent: Option<AccessibilityNodeId>,
+    /// The bounds of the element, in window coordinates.
+    pub bounds: Bounds<Pixels>,
+    /// The information the element exposes.
+    pub properties: AccessibilityProperties,
+    pub(crate) view_id: EntityId,
+    pub(crate) focus_id: Option<FocusId>,
+}
+
+/// The elements of a window that are exposed to assistive technologies, built up while
+/// painting each frame.
+#[derive(Clone, Debug, Default)]
+pub struct AccessibilityTree {
+    nodes: Vec<AccessibilityNode>,
+    focused: Option<AccessibilityNodeId>,
+    stack: Vec<AccessibilityNodeId>,
+    child_counts: FxHashMap<(EntityId, Option<AccessibilityNodeId>), usize>,
+}
+
+impl PartialEq for AccessibilityTree {
+    fn eq(&self, other: &Self) -> bool {
+        self.nodes == other.nodes && self.focused == other.focused
+    }
+}
+
+impl AccessibilityTree {
+    /// All nodes in the tree, with parents before their children and siblings in the
+    /// order they were painted.
+    pub fn nodes(&self) -> &[AccessibilityNode] {
+        &self.nodes
+    }
+
+    /// The node with the given id.
+    pub fn node(&self, id: AccessibilityNodeId) -> Option<&AccessibilityNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// The children of the node with the given id, or the roots of the tree if `None`.
+    pub fn children(
+        &self,
+        parent: Option<AccessibilityNodeId>,
+    ) -> impl Iterator<Item = &AccessibilityNode> {
+        self.nodes.iter().filter(move |node| node.parent == parent)
+    }
+
+    /// The node of the element that has keyboard focus.
+    pub fn focused(&self) -> Option<&AccessibilityNode> {
+        self.node(self.focused?)
+    }
+
+    pub(crate) fn push_node(
+        &mut self,
+        view_id: EntityId,
+        element_id: &GlobalElementId,
+        bounds: Bounds<Pixels>,
+        properties: AccessibilityProperties,
+        focus_id: Option<FocusId>,
+    ) {
+        let parent = self.stack.last().copied();
+        let child_ix = self.child_counts.entry((view_id, parent)).or_default();
+        let mut hasher = DefaultHasher::new();
+        (parent, view_id, element_id, *child_ix).hash(&mut hasher);
+        *child_ix += 1;
+
+        let id = AccessibilityNodeId(hasher.finish());
+        self.nodes.push(AccessibilityNode {
+            id,
+            parent,
+            bounds,
+            properties,
+            view_id,
+            focus_id,
+        });
+        self.stack.push(id);
+    }
+
+    pub(crate) fn pop_node(&mut self) {
+        self.stack.pop();
+    }
+
+    /// Reuse the nodes painted by the given views in the previous frame.
+    pub(crate) fn reuse_views(&mut self, view_ids: &[EntityId], prev_tree: &Self) {
+        let parent = self.stack.last().copied();
+        let reused_nodes = prev_tree
+            .nodes
+            .iter()
+            .filter(|node| view_ids.contains(&node.view_id))
+            .collect::<Vec<_>>();
+        let reused_ids = reused_nodes
+            .iter()
+            .map(|node| node.id)
+            .collect::<FxHashSet<_>>();
+        for node in reused_nodes {
+            let mut node = node.clone();
+            // Attach the outermost nodes of the views to wherever the views are painted now.
+            if node.parent.map_or(true, |id| !reused_ids.contains(&id)) {
+                node.parent = parent;
+            }
+            self.nodes.push(node);
+        }
+    }
+
+    pub(crate) fn finish(&mut self, focus: Option<FocusId>) {
+        debug_assert!(self.stack.is_empty());
+        self.focused = focus.and_then(|focus| {
+            self.nodes
+                .iter()
+                .find(|node| node.focus_id == Some(focus))
+                .map(|node| node.id)
+        });
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.nodes.clear();
+        self.focused = None;
+        self.stack.clear();
+        self.child_counts.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        self as gpui, div, FocusHandle, InteractiveElement, IntoElement, ParentElement, Render,
+        TestAppContext, ViewContext, VisualContext,
+    };
+
+    struct TestView {
+        focus_handle: FocusHandle,
+        selected_ix: usize,
+    }
+
+    impl Render for TestView {
+        fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+            div()
+                .child(
+                    div()
+                        .accessibility_role(AccessibilityRole::TextField)
+                        .accessibility_value("query")
+                        .track_focus(&self.focus_handle),
+                )
+                .child(
+                    div()
+                        .accessibility_role(AccessibilityRole::List)
+                        .children((0..2).map(|ix| {
+                            div()
+                                .accessibility_role(AccessibilityRole::ListItem)
+                                .accessibility_label(format!("item {ix}"))
+                                .accessibility_selected(ix == self.selected_ix)
+                        })),
+                )
+        }
+    }
+
+    #[gpui::test]
+    fn test_accessibility_tree(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|cx| TestView {
+            focus_handle: cx.focus_handle(),
+            selected_ix: 0,
+        });
+
+        let list_id = cx.update(|cx| {
+            let tree = cx.accessibility_tree();
+            let roots = tree.children(None).collect::<Vec<_>>();
+            assert_eq!(
+                roots
+                    .iter()
+                    .map(|node| node.properties.role)
+                    .collect::<Vec<_>>(),
+                [AccessibilityRole::TextField, AccessibilityRole::List]
+            );
+            assert_eq!(
+                list_items(tree, roots[1].id),
+                [(Some("item 0"), true), (Some("item 1"), false)]
+            );
+            assert!(tree.focused().is_none());
+            roots[1].id
+        });
+
+        view.update(cx, |view, cx| {
+            view.selected_ix = 1;
+            cx.focus(&view.focus_handle);
+            cx.notify();
+        });
+        cx.run_until_parked();
+
+        cx.update(|cx| {
+            let tree = cx.accessibility_tree();
+            // Nodes keep their ids across frames.
+            assert_eq!(
+                list_items(tree, list_id),
+                [(Some("item 0"), false), (Some("item 1"), true)]
+            );
+            let focused = tree.focused().unwrap();
+            assert_eq!(focused.properties.role, AccessibilityRole::TextField);
+            assert_eq!(focused.properties.value, Some("query".into()));
+        });
+    }
+
+    fn list_items(
+        tree: &AccessibilityTree,
+        list_id: AccessibilityNodeId,
+    ) -> Vec<(Option<&str>, bool)> {
+        tree.children(Some(list_id))
+            .map(|node| {
+                (
+                    node.properties.label.as_ref().map(AsRef::as_ref),
+                    node.properties.selected,
+                )
+            })
+            .collect()
+    }
+}
@@ -18,9 +18,10 @@ use crate::{
     current_platform, image_cache::ImageCache, init_app_menus, Action, ActionRegistry, Any,
     AnyView, AnyWindowHandle, AppMetadata, AssetSource, BackgroundExecutor, ClipboardItem, Context,
     DispatchPhase, DisplayId, Entity, EventEmitter, ForegroundExecutor, KeyBinding, Keymap,
-    Keystroke, LayoutId, Menu, PathPromptOptions, Pixels, Platform, PlatformDisplay, Point, Render,
-    SharedString, SubscriberSet, Subscription, SvgRenderer, Task, TextStyle, TextStyleRefinement,
-    TextSystem, View, ViewContext, Window, WindowContext, WindowHandle, WindowId,
+    Keystroke, LayoutId, Menu, MouseBinding, PathPromptOptions, Pixels, Platform, PlatformDisplay,
+    Point, Render, SharedString, SubscriberSet, Subscription, SvgRenderer, Task, TextStyle,
+    TextStyleRefinement, TextSystem, View, ViewContext, Window, WindowContext, WindowHandle,
+    WindowId,
 };
 use anyhow::{anyhow, Result};
 use collections::{FxHashMap, FxHashSet, VecDeque};
@@ -127,6 +128,14 @@ impl App {
         self
     }
 
+    /// Assign the HTTP client that images are fetched with.
+    pub fn with_http_client(self, http_client: Arc<dyn HttpClient>) -> Self {
+        let mut context_lock = self.0.borrow_mut();
+        context_lock.image_cache = ImageCache::new(http_client);
+        drop(context_lock);
+        self
+    }
+
     /// Start the application. The provided callback will be called once the
     /// app is fully launched.
     pub fn run<F>(self, on_finish_launching: F)
@@ -225,6 +234,7 @@ pub struct AppContext {
     pub(crate) new_view_observers: SubscriberSet<TypeId, NewViewListener>,
     pub(crate) windows: SlotMap<WindowId, Option<Window>>,
     pub(crate) keymap: Rc<RefCell<Keymap>>,
+    pub(crate) keystroke_timeout: Duration,
     pub(crate) global_action_listeners:
         FxHashMap<TypeId, Vec<Rc<dyn Fn(&dyn Any, DispatchPhase, &mut Self)>>>,
     pending_effects: VecDeque<Effect>,
@@ -287,6 +297,7 @@ impl AppContext {
                 new_view_observers: SubscriberSet::new(),
                 windows: SlotMap::with_key(),
                 keymap: Rc::new(RefCell::new(Keymap::default())),
+                keystroke_timeout: Duration::from_secs(1),
                 global_action_listeners: FxHashMap::default(),
                 pending_effects: VecDeque::new(),
                 pending_notifications: FxHashSet::default(),
@@ -492,6 +503,26 @@ impl AppContext {
         self.platform.activate(ignoring_other_apps);
     }
 
+    /// Registers a system-wide hotkey, which invokes the handler set with
+    /// [`AppContext::on_global_hotkey`] even while the application isn't active. This replaces
+    /// the hotkey registered before, and passing `None` unregisters it.
+    pub fn set_global_hotkey(&self, keystroke: Option<&Keystroke>) -> Result<()> {
+        self.platform.set_global_hotkey(keystroke)
+    }
+
+    /// Invokes a handler when the system-wide hotkey is pressed.
+    pub fn on_global_hotkey<F>(&self, mut callback: F)
+    where
+        F: 'static + FnMut(&mut AppContext),
+    {
+        let this = self.this.clone();
+        self.platform.on_global_hotkey(Box::new(move || {
+            if let Some(app) = this.upgrade() {
+                callback(&mut app.borrow_mut());
+            }
+        }));
+    }
+
     /// Hide the application at the platform level.
     pub fn hide(&self) {
         self.platform.hide();
@@ -1006,6 +1037,24 @@ impl AppContext {
         self.pending_effects.push_back(Effect::Refresh);
     }
 
+    /// Register mouse bindings, which take precedence over the default behavior of mouse
+    /// buttons in the contexts they're bound in.
+    pub fn bind_mouse(&mut self, bindings: impl IntoIterator<Item = MouseBinding>) {
+        self.keymap.borrow_mut().add_mouse_bindings(bindings);
+        self.pending_effects.push_back(Effect::Refresh);
+    }
+
+    /// The key bindings registered in the app.
+    pub fn key_bindings(&self) -> Rc<RefCell<Keymap>> {
+        self.keymap.clone()
+    }
+
+    /// Set how long a partially typed multi-stroke binding waits for its next keystroke
+    /// before the keystrokes typed so far are handled on their own. Defaults to one second.
+    pub fn set_keystroke_timeout(&mut self, timeout: Duration) {
+        self.keystroke_timeout = timeout;
+    }
+
     /// Clear all key bindings in the app.
     pub fn clear_key_bindings(&mut self) {
         self.keymap.borrow_mut().clear();
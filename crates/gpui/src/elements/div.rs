@@ -16,12 +16,12 @@
 //! constructed by combining these two systems into an all-in-one element.
 
 use crate::{
-    point, px, size, Action, AnyDrag, AnyElement, AnyTooltip, AnyView, AppContext, Bounds,
-    ClickEvent, DispatchPhase, Element, ElementContext, ElementId, FocusHandle, IntoElement,
-    IsZero, KeyContext, KeyDownEvent, KeyUpEvent, LayoutId, MouseButton, MouseDownEvent,
-    MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, Point, Render, ScrollWheelEvent,
-    SharedString, Size, StackingOrder, Style, StyleRefinement, Styled, Task, View, Visibility,
-    WindowContext,
+    point, px, size, AccessibilityProperties, AccessibilityRole, Action, AnyDrag, AnyElement,
+    AnyTooltip, AnyView, AppContext, Bounds, ClickEvent, DispatchPhase, Element, ElementContext,
+    ElementId, FocusHandle, IntoElement, IsZero, KeyContext, KeyDownEvent, KeyUpEvent, LayoutId,
+    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, PinchEvent, Pixels,
+    Point, Render, ScrollWheelEvent, SharedString, Size, StackingOrder, Style, StyleRefinement,
+    Styled, SwipeEvent, Task, View, Visibility, WindowContext,
 };
 
 use collections::HashMap;
@@ -283,6 +283,32 @@ impl Interactivity {
             }));
     }
 
+    /// Bind the given callback to pinch gestures over this element
+    /// The imperative API equivalent to [`InteractiveElement::on_pinch`]
+    ///
+    /// See [`ViewContext::listener`](crate::ViewContext::listener) to get access to a view's state from this callback.
+    pub fn on_pinch(&mut self, listener: impl Fn(&PinchEvent, &mut WindowContext) + 'static) {
+        self.pinch_listeners
+            .push(Box::new(move |event, bounds, phase, cx| {
+                if phase == DispatchPhase::Bubble && bounds.visibly_contains(&event.position, cx) {
+                    (listener)(event, cx);
+                }
+            }));
+    }
+
+    /// Bind the given callback to swipe gestures over this element
+    /// The imperative API equivalent to [`InteractiveElement::on_swipe`]
+    ///
+    /// See [`ViewContext::listener`](crate::ViewContext::listener) to get access to a view's state from this callback.
+    pub fn on_swipe(&mut self, listener: impl Fn(&SwipeEvent, &mut WindowContext) + 'static) {
+        self.swipe_listeners
+            .push(Box::new(move |event, bounds, phase, cx| {
+                if phase == DispatchPhase::Bubble && bounds.visibly_contains(&event.position, cx) {
+                    (listener)(event, cx);
+                }
+            }));
+    }
+
     /// Bind the given callback to an action dispatch during the capture phase
     /// The imperative API equivalent to [`InteractiveElement::capture_action`]
     ///
@@ -528,6 +554,44 @@ pub trait InteractiveElement: Sized {
         self
     }
 
+    /// Expose this element to assistive technologies such as screen readers, with the given role.
+    fn accessibility_role(mut self, role: AccessibilityRole) -> Self {
+        self.interactivity()
+            .accessibility
+            .get_or_insert_with(Default::default)
+            .role = role;
+        self
+    }
+
+    /// Set the label that assistive technologies use to describe this element.
+    fn accessibility_label(mut self, label: impl Into<SharedString>) -> Self {
+        self.interactivity()
+            .accessibility
+            .get_or_insert_with(Default::default)
+            .label = Some(label.into());
+        self
+    }
+
+    /// Set the value that assistive technologies report for this element, such as the text
+    /// of a text field.
+    fn accessibility_value(mut self, value: impl Into<SharedString>) -> Self {
+        self.interactivity()
+            .accessibility
+            .get_or_insert_with(Default::default)
+            .value = Some(value.into());
+        self
+    }
+
+    /// Report this element as the selected item of a list or tab list to assistive
+    /// technologies.
+    fn accessibility_selected(mut self, selected: bool) -> Self {
+        self.interactivity()
+            .accessibility
+            .get_or_insert_with(Default::default)
+            .selected = selected;
+        self
+    }
+
     /// Apply the given style to this element when the mouse hovers over it
     fn hover(mut self, f: impl FnOnce(StyleRefinement) -> StyleRefinement) -> Self {
         debug_assert!(
@@ -700,6 +764,24 @@ pub trait InteractiveElement: Sized {
         self
     }
 
+    /// Bind the given callback to pinch gestures over this element
+    /// The fluent API equivalent to [`Interactivity::on_pinch`]
+    ///
+    /// See [`ViewContext::listener`](crate::ViewContext::listener) to get access to a view's state from this callback.
+    fn on_pinch(mut self, listener: impl Fn(&PinchEvent, &mut WindowContext) + 'static) -> Self {
+        self.interactivity().on_pinch(listener);
+        self
+    }
+
+    /// Bind the given callback to swipe gestures over this element
+    /// The fluent API equivalent to [`Interactivity::on_swipe`]
+    ///
+    /// See [`ViewContext::listener`](crate::ViewContext::listener) to get access to a view's state from this callback.
+    fn on_swipe(mut self, listener: impl Fn(&SwipeEvent, &mut WindowContext) + 'static) -> Self {
+        self.interactivity().on_swipe(listener);
+        self
+    }
+
     /// Capture the given action, before normal action dispatch can fire
     /// The fluent API equivalent to [`Interactivity::on_scroll_wheel`]
     ///
@@ -989,6 +1071,12 @@ pub(crate) type MouseMoveListener =
 pub(crate) type ScrollWheelListener =
     Box<dyn Fn(&ScrollWheelEvent, &InteractiveBounds, DispatchPhase, &mut WindowContext) + 'static>;
 
+pub(crate) type PinchListener =
+    Box<dyn Fn(&PinchEvent, &InteractiveBounds, DispatchPhase, &mut WindowContext) + 'static>;
+
+pub(crate) type SwipeListener =
+    Box<dyn Fn(&SwipeEvent, &InteractiveBounds, DispatchPhase, &mut WindowContext) + 'static>;
+
 pub(crate) type ClickListener = Box<dyn Fn(&ClickEvent, &mut WindowContext) + 'static>;
 
 pub(crate) type DragListener = Box<dyn Fn(&dyn Any, &mut WindowContext) -> AnyView + 'static>;
@@ -1193,6 +1281,8 @@ pub struct Interactivity {
     pub(crate) mouse_up_listeners: Vec<MouseUpListener>,
     pub(crate) mouse_move_listeners: Vec<MouseMoveListener>,
     pub(crate) scroll_wheel_listeners: Vec<ScrollWheelListener>,
+    pub(crate) pinch_listeners: Vec<PinchListener>,
+    pub(crate) swipe_listeners: Vec<SwipeListener>,
     pub(crate) key_down_listeners: Vec<KeyDownListener>,
     pub(crate) key_up_listeners: Vec<KeyUpListener>,
     pub(crate) action_listeners: Vec<(TypeId, ActionListener)>,
@@ -1203,6 +1293,7 @@ pub struct Interactivity {
     pub(crate) hover_listener: Option<Box<dyn Fn(&bool, &mut WindowContext)>>,
     pub(crate) tooltip_builder: Option<TooltipBuilder>,
     pub(crate) block_mouse: bool,
+    pub(crate) accessibility: Option<Box<AccessibilityProperties>>,
 
     #[cfg(debug_assertions)]
     pub(crate) location: Option<core::panic::Location<'static>>,
@@ -1506,6 +1597,20 @@ impl Interactivity {
                             })
                         }
 
+                        for listener in self.pinch_listeners.drain(..) {
+                            let interactive_bounds = interactive_bounds.clone();
+                            cx.on_mouse_event(move |event: &PinchEvent, phase, cx| {
+                                listener(event, &interactive_bounds, phase, cx);
+                            })
+                        }
+
+                        for listener in self.swipe_listeners.drain(..) {
+                            let interactive_bounds = interactive_bounds.clone();
+                            cx.on_mouse_event(move |event: &SwipeEvent, phase, cx| {
+                                listener(event, &interactive_bounds, phase, cx);
+                            })
+                        }
+
                         paint_hover_group_handler(cx);
 
                         if self.hover_style.is_some()
@@ -1890,10 +1995,17 @@ impl Interactivity {
                         let key_down_listeners = mem::take(&mut self.key_down_listeners);
                         let key_up_listeners = mem::take(&mut self.key_up_listeners);
                         let action_listeners = mem::take(&mut self.action_listeners);
+                        let accessibility = self.accessibility.take();
+                        let focus_handle = element_state.focus_handle.clone();
+                        let has_key_context = self.key_context.is_some();
                         cx.with_key_dispatch(
                             self.key_context.clone(),
                             element_state.focus_handle.clone(),
                             |_, cx| {
+                                if has_key_context {
+                                    cx.register_mouse_binding_target(bounds);
+                                }
+
                                 for listener in key_down_listeners {
                                     cx.on_key_event(move |event: &KeyDownEvent, phase, cx| {
                                         listener(event, phase, cx);
@@ -1910,7 +2022,12 @@ impl Interactivity {
                                     cx.on_action(action_type, listener)
                                 }
 
-                                f(&style, scroll_offset.unwrap_or_default(), cx)
+                                cx.with_accessibility_node(
+                                    accessibility.map(|accessibility| *accessibility),
+                                    bounds,
+                                    focus_handle.as_ref(),
+                                    |cx| f(&style, scroll_offset.unwrap_or_default(), cx),
+                                )
                             },
                         );
 
@@ -2270,6 +2387,16 @@ impl ScrollHandle {
         *self.0.borrow().offset.borrow()
     }
 
+    /// Set the scroll offset. It's clamped to the scrollable area when the element is next painted.
+    pub fn set_offset(&self, offset: Point<Pixels>) {
+        *self.0.borrow().offset.borrow_mut() = offset;
+    }
+
+    /// Get the bounds of the scrolled element.
+    pub fn bounds(&self) -> Bounds<Pixels> {
+        self.0.borrow().bounds
+    }
+
     /// Get the top child that's scrolled into view.
     pub fn top_item(&self) -> usize {
         let state = self.0.borrow();
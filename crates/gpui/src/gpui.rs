@@ -63,6 +63,7 @@
 #![deny(missing_docs)]
 #![allow(clippy::type_complexity)]
 
+mod accessibility;
 #[macro_use]
 mod action;
 mod app;
@@ -111,6 +112,7 @@ mod seal {
     pub trait Sealed {}
 }
 
+pub use accessibility::*;
 pub use action::*;
 pub use anyhow::Result;
 pub use app::*;
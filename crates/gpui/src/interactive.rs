@@ -74,7 +74,7 @@ impl Deref for ModifiersChangedEvent {
 
 /// The phase of a touch motion event.
 /// Based on the winit enum of the same name.
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum TouchPhase {
     /// The touch started.
     Started,
@@ -254,6 +254,68 @@ impl Deref for ScrollWheelEvent {
     }
 }
 
+/// A pinch gesture on a trackpad, used to zoom in and out.
+#[derive(Clone, Debug, Default)]
+pub struct PinchEvent {
+    /// The position of the mouse on the window.
+    pub position: Point<Pixels>,
+
+    /// How much to scale by since the previous event of the gesture, where `0.1` means
+    /// 10% larger and `-0.1` means 10% smaller.
+    pub delta: f32,
+
+    /// The modifiers that were held down during the gesture.
+    pub modifiers: Modifiers,
+
+    /// The phase of the gesture.
+    pub phase: TouchPhase,
+}
+
+impl Sealed for PinchEvent {}
+impl InputEvent for PinchEvent {
+    fn to_platform_input(self) -> PlatformInput {
+        PlatformInput::Pinch(self)
+    }
+}
+impl MouseEvent for PinchEvent {}
+
+impl Deref for PinchEvent {
+    type Target = Modifiers;
+
+    fn deref(&self) -> &Self::Target {
+        &self.modifiers
+    }
+}
+
+/// A horizontal swipe gesture on a trackpad, used to navigate back and forward.
+#[derive(Clone, Debug, Default)]
+pub struct SwipeEvent {
+    /// The position of the mouse on the window.
+    pub position: Point<Pixels>,
+
+    /// Where to navigate, following the system's convention for swiping between pages.
+    pub direction: NavigationDirection,
+
+    /// The modifiers that were held down during the gesture.
+    pub modifiers: Modifiers,
+}
+
+impl Sealed for SwipeEvent {}
+impl InputEvent for SwipeEvent {
+    fn to_platform_input(self) -> PlatformInput {
+        PlatformInput::Swipe(self)
+    }
+}
+impl MouseEvent for SwipeEvent {}
+
+impl Deref for SwipeEvent {
+    type Target = Modifiers;
+
+    fn deref(&self) -> &Self::Target {
+        &self.modifiers
+    }
+}
+
 /// The scroll delta for a scroll wheel event.
 #[derive(Clone, Copy, Debug)]
 pub enum ScrollDelta {
@@ -398,11 +460,43 @@ pub enum PlatformInput {
     MouseExited(MouseExitEvent),
     /// The scroll wheel was used.
     ScrollWheel(ScrollWheelEvent),
+    /// A pinch gesture was performed.
+    Pinch(PinchEvent),
+    /// A swipe gesture was performed.
+    Swipe(SwipeEvent),
     /// Files were dragged and dropped onto the window.
     FileDrop(FileDropEvent),
 }
 
 impl PlatformInput {
+    /// Returns this event with its positions and pixel distances multiplied by the given factor.
+    pub(crate) fn scale(mut self, factor: f32) -> Self {
+        match &mut self {
+            PlatformInput::KeyDown(_)
+            | PlatformInput::KeyUp(_)
+            | PlatformInput::ModifiersChanged(_) => {}
+            PlatformInput::MouseDown(event) => event.position = event.position * factor,
+            PlatformInput::MouseUp(event) => event.position = event.position * factor,
+            PlatformInput::MouseMove(event) => event.position = event.position * factor,
+            PlatformInput::MouseExited(event) => event.position = event.position * factor,
+            PlatformInput::ScrollWheel(event) => {
+                event.position = event.position * factor;
+                if let ScrollDelta::Pixels(delta) = &mut event.delta {
+                    *delta = *delta * factor;
+                }
+            }
+            PlatformInput::Pinch(event) => event.position = event.position * factor,
+            PlatformInput::Swipe(event) => event.position = event.position * factor,
+            PlatformInput::FileDrop(event) => match event {
+                FileDropEvent::Entered { position, .. }
+                | FileDropEvent::Pending { position }
+                | FileDropEvent::Submit { position } => *position = *position * factor,
+                FileDropEvent::Exited => {}
+            },
+        }
+        self
+    }
+
     pub(crate) fn mouse_event(&self) -> Option<&dyn Any> {
         match self {
             PlatformInput::KeyDown { .. } => None,
@@ -413,6 +507,8 @@ impl PlatformInput {
             PlatformInput::MouseMove(event) => Some(event),
             PlatformInput::MouseExited(event) => Some(event),
             PlatformInput::ScrollWheel(event) => Some(event),
+            PlatformInput::Pinch(event) => Some(event),
+            PlatformInput::Swipe(event) => Some(event),
             PlatformInput::FileDrop(event) => Some(event),
         }
     }
@@ -427,6 +523,8 @@ impl PlatformInput {
             PlatformInput::MouseMove(_) => None,
             PlatformInput::MouseExited(_) => None,
             PlatformInput::ScrollWheel(_) => None,
+            PlatformInput::Pinch(_) => None,
+            PlatformInput::Swipe(_) => None,
             PlatformInput::FileDrop(_) => None,
         }
     }
@@ -435,8 +533,10 @@ impl PlatformInput {
 #[cfg(test)]
 mod test {
     use crate::{
-        self as gpui, div, Element, FocusHandle, InteractiveElement, IntoElement, KeyBinding,
-        Keystroke, ParentElement, Render, TestAppContext, VisualContext,
+        self as gpui, div, point, px, Element, FocusHandle, InteractiveElement, IntoElement,
+        KeyBinding, Keystroke, Modifiers, MouseMoveEvent, NavigationDirection, ParentElement,
+        PinchEvent, PlatformInput, Render, Styled, SwipeEvent, TestAppContext, TouchPhase,
+        VisualContext,
     };
 
     struct TestView {
@@ -445,7 +545,7 @@ mod test {
         focus_handle: FocusHandle,
     }
 
-    actions!(test, [TestAction]);
+    actions!(test, [TestAction, OtherAction]);
 
     impl Render for TestView {
         fn render(&mut self, cx: &mut gpui::ViewContext<Self>) -> impl Element {
@@ -502,4 +602,154 @@ mod test {
             })
             .unwrap();
     }
+
+    #[gpui::test]
+    fn test_ui_scale(cx: &mut TestAppContext) {
+        let window = cx.update(|cx| {
+            cx.open_window(Default::default(), |cx| {
+                cx.new_view(|cx| TestView {
+                    saw_key_down: false,
+                    saw_action: false,
+                    focus_handle: cx.focus_handle(),
+                })
+            })
+        });
+
+        window
+            .update(cx, |_, cx| {
+                let scale_factor = cx.scale_factor();
+                let viewport_size = cx.viewport_size();
+
+                cx.set_ui_scale(2.);
+                assert_eq!(cx.scale_factor(), scale_factor * 2.);
+                assert_eq!(cx.viewport_size().width, viewport_size.width * 0.5);
+                assert_eq!(cx.viewport_size().height, viewport_size.height * 0.5);
+
+                cx.dispatch_event(PlatformInput::MouseMove(MouseMoveEvent {
+                    position: point(px(100.), px(50.)),
+                    pressed_button: None,
+                    modifiers: Modifiers::default(),
+                }));
+                assert_eq!(cx.mouse_position(), point(px(50.), px(25.)));
+
+                cx.set_ui_scale(1.);
+                assert_eq!(cx.scale_factor(), scale_factor);
+                assert_eq!(cx.viewport_size(), viewport_size);
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    fn test_pending_bindings(cx: &mut TestAppContext) {
+        let window = cx.update(|cx| {
+            cx.open_window(Default::default(), |cx| {
+                cx.new_view(|cx| TestView {
+                    saw_key_down: false,
+                    saw_action: false,
+                    focus_handle: cx.focus_handle(),
+                })
+            })
+        });
+
+        cx.update(|cx| {
+            cx.bind_keys(vec![
+                KeyBinding::new("ctrl-k a", TestAction, Some("parent")),
+                KeyBinding::new("ctrl-k b", TestAction, Some("parent")),
+                KeyBinding::new("ctrl-k a", OtherAction, Some("nested")),
+                KeyBinding::new("ctrl-k c", OtherAction, Some("elsewhere")),
+                KeyBinding::new("ctrl-x a", OtherAction, Some("nested")),
+            ]);
+        });
+
+        window
+            .update(cx, |test_view, cx| {
+                cx.focus(&test_view.focus_handle);
+                assert!(cx.pending_keystrokes().is_none());
+                assert!(cx.pending_bindings().is_empty());
+            })
+            .unwrap();
+
+        cx.dispatch_keystroke(*window, Keystroke::parse("ctrl-k").unwrap(), false);
+
+        window
+            .update(cx, |_, cx| {
+                assert_eq!(
+                    cx.pending_keystrokes(),
+                    Some([Keystroke::parse("ctrl-k").unwrap()].as_slice())
+                );
+                let mut bindings = cx
+                    .pending_bindings()
+                    .into_iter()
+                    .map(|binding| {
+                        (
+                            binding.keystrokes()[1].unparse(),
+                            binding.action().name().to_string(),
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                bindings.sort();
+                assert_eq!(
+                    bindings,
+                    [
+                        ("a".to_string(), "test::OtherAction".to_string()),
+                        ("b".to_string(), "test::TestAction".to_string()),
+                    ]
+                );
+            })
+            .unwrap();
+
+        cx.dispatch_keystroke(*window, Keystroke::parse("b").unwrap(), false);
+
+        window
+            .update(cx, |test_view, cx| {
+                assert!(test_view.saw_action);
+                assert!(cx.pending_keystrokes().is_none());
+            })
+            .unwrap();
+    }
+
+    #[derive(Default)]
+    struct GestureView {
+        zoom: f32,
+        swipes: Vec<NavigationDirection>,
+    }
+
+    impl Render for GestureView {
+        fn render(&mut self, cx: &mut gpui::ViewContext<Self>) -> impl IntoElement {
+            div()
+                .size_full()
+                .on_pinch(cx.listener(|this, event: &PinchEvent, _| this.zoom += event.delta))
+                .on_swipe(
+                    cx.listener(|this, event: &SwipeEvent, _| this.swipes.push(event.direction)),
+                )
+        }
+    }
+
+    #[gpui::test]
+    fn test_gestures(cx: &mut TestAppContext) {
+        let (view, cx) = cx.add_window_view(|_| GestureView::default());
+
+        for (delta, phase) in [
+            (0.25, TouchPhase::Started),
+            (0.25, TouchPhase::Moved),
+            (0., TouchPhase::Ended),
+        ] {
+            cx.simulate_event(PinchEvent {
+                position: point(px(10.), px(10.)),
+                delta,
+                phase,
+                ..Default::default()
+            });
+        }
+        cx.simulate_event(SwipeEvent {
+            position: point(px(10.), px(10.)),
+            direction: NavigationDirection::Forward,
+            ..Default::default()
+        });
+
+        view.update(cx, |view, _| {
+            assert_eq!(view.zoom, 0.5);
+            assert_eq!(view.swipes, [NavigationDirection::Forward]);
+        });
+    }
 }
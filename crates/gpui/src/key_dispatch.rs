@@ -99,6 +99,14 @@ pub(crate) struct DispatchNode {
 
 type KeyListener = Rc<dyn Fn(&dyn Any, DispatchPhase, &mut ElementContext)>;
 
+/// Where a mouse binding applies: the key contexts of the element under the mouse, and the
+/// nearest focusable element the binding's action is dispatched to.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MouseBindingTarget {
+    pub context_stack: Vec<KeyContext>,
+    pub focus_id: Option<FocusId>,
+}
+
 #[derive(Clone)]
 pub(crate) struct DispatchActionListener {
     pub(crate) action_type: TypeId,
@@ -382,6 +390,17 @@ impl DispatchTree {
         KeymatchResult { bindings, pending }
     }
 
+    pub fn mouse_binding_target(&self) -> MouseBindingTarget {
+        MouseBindingTarget {
+            context_stack: self.context_stack.clone(),
+            focus_id: self
+                .node_stack
+                .iter()
+                .rev()
+                .find_map(|node_id| self.nodes[node_id.0].focus_id),
+        }
+    }
+
     pub fn has_pending_keystrokes(&self) -> bool {
         self.keystroke_matchers
             .iter()
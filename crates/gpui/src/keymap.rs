@@ -1,10 +1,12 @@
 mod binding;
 mod context;
 mod matcher;
+mod mouse_binding;
 
 pub use binding::*;
 pub use context::*;
 pub(crate) use matcher::*;
+pub use mouse_binding::*;
 
 use crate::{Action, Keystroke, NoAction};
 use collections::HashSet;
@@ -26,6 +28,7 @@ pub struct Keymap {
     binding_indices_by_action_id: HashMap<TypeId, SmallVec<[usize; 3]>>,
     disabled_keystrokes:
         HashMap<SmallVec<[Keystroke; 2]>, HashSet<Option<KeyBindingContextPredicate>>>,
+    mouse_bindings: Vec<MouseBinding>,
     version: KeymapVersion,
 }
 
@@ -65,11 +68,18 @@ impl Keymap {
         self.version.0 += 1;
     }
 
+    /// Add more mouse bindings to the keymap.
+    pub fn add_mouse_bindings<T: IntoIterator<Item = MouseBinding>>(&mut self, bindings: T) {
+        self.mouse_bindings.extend(bindings);
+        self.version.0 += 1;
+    }
+
     /// Reset this keymap to its initial state.
     pub fn clear(&mut self) {
         self.bindings.clear();
         self.binding_indices_by_action_id.clear();
         self.disabled_keystrokes.clear();
+        self.mouse_bindings.clear();
         self.version.0 += 1;
     }
 
@@ -92,6 +102,51 @@ impl Keymap {
             .filter(move |binding| binding.action().partial_eq(action))
     }
 
+    /// Iterate over all mouse bindings, in the order they were added.
+    pub fn mouse_bindings(&self) -> impl Iterator<Item = &MouseBinding> + DoubleEndedIterator {
+        self.mouse_bindings.iter()
+    }
+
+    /// Find the mouse binding for the given stroke in the given context. Bindings for the
+    /// innermost matching context win over those for outer contexts and bindings without a
+    /// context, and bindings added later win over earlier ones.
+    ///
+    /// A binding to [`NoAction`] is returned as well, so that it can suppress the default
+    /// behavior of the mouse button.
+    pub fn mouse_binding(
+        &self,
+        stroke: &MouseStroke,
+        context_stack: &[KeyContext],
+    ) -> Option<&MouseBinding> {
+        let mut bindings = self
+            .mouse_bindings
+            .iter()
+            .rev()
+            .filter(|binding| binding.stroke == *stroke);
+        for depth in (1..=context_stack.len()).rev() {
+            let context = &context_stack[..depth];
+            if let Some(binding) = bindings.clone().find(|binding| {
+                binding
+                    .context_predicate
+                    .as_ref()
+                    .map_or(false, |predicate| predicate.eval(context))
+            }) {
+                return Some(binding);
+            }
+        }
+        bindings.find(|binding| binding.context_predicate.is_none())
+    }
+
+    /// Check if the given binding's keystrokes have been disabled, in all contexts or in the
+    /// binding's own context.
+    pub fn binding_disabled(&self, binding: &KeyBinding) -> bool {
+        self.disabled_keystrokes
+            .get(&binding.keystrokes)
+            .map_or(false, |predicates| {
+                predicates.contains(&None) || predicates.contains(&binding.context_predicate)
+            })
+    }
+
     /// Check if the given binding is enabled, given a certain key context.
     pub fn binding_enabled(&self, binding: &KeyBinding, context: &[KeyContext]) -> bool {
         // If binding has a context predicate, it must match the current context,
@@ -125,7 +180,7 @@ impl Keymap {
 mod tests {
     use super::*;
     use crate as gpui;
-    use gpui::actions;
+    use gpui::{actions, Modifiers, MouseButton, NavigationDirection};
 
     actions!(
         keymap_test,
@@ -183,4 +238,62 @@ mod tests {
         // binding is globally disabled
         assert!(!keymap.binding_enabled(&bindings[1], &[KeyContext::parse("barf").unwrap()]));
     }
+
+    #[test]
+    fn test_mouse_bindings() {
+        let mut keymap = Keymap::default();
+        keymap.add_mouse_bindings([
+            MouseBinding::new("middle", ActionAlpha {}, None),
+            MouseBinding::new("middle", ActionBeta {}, Some("pane")),
+            MouseBinding::new("ctrl-middle", ActionGamma {}, Some("pane")),
+            MouseBinding::new("middle", ActionDelta {}, Some("editor")),
+            MouseBinding::new("middle", NoAction {}, Some("editor && mode==full")),
+        ]);
+
+        let action_for = |stroke: &str, contexts: &[&str]| {
+            let contexts = contexts
+                .iter()
+                .map(|context| KeyContext::parse(context).unwrap())
+                .collect::<Vec<_>>();
+            keymap
+                .mouse_binding(&MouseStroke::parse(stroke).unwrap(), &contexts)
+                .map(|binding| binding.action().name())
+        };
+
+        assert_eq!(action_for("middle", &[]), Some("keymap_test::ActionAlpha"));
+        assert_eq!(
+            action_for("middle", &["workspace", "pane"]),
+            Some("keymap_test::ActionBeta")
+        );
+        // the innermost context wins
+        assert_eq!(
+            action_for("middle", &["workspace", "pane", "editor"]),
+            Some("keymap_test::ActionDelta")
+        );
+        assert_eq!(
+            action_for("middle", &["pane", "editor mode=full"]),
+            Some("zed::NoAction")
+        );
+        // modifiers must match exactly
+        assert_eq!(
+            action_for("ctrl-middle", &["pane"]),
+            Some("keymap_test::ActionGamma")
+        );
+        assert_eq!(action_for("ctrl-middle", &[]), None);
+        assert_eq!(action_for("shift-middle", &["pane"]), None);
+
+        assert!(MouseStroke::parse("ctrl-wheel").is_err());
+        assert!(MouseStroke::parse("back-ctrl").is_err());
+        assert_eq!(
+            MouseStroke::parse("alt-cmd-forward").unwrap(),
+            MouseStroke {
+                button: MouseButton::Navigate(NavigationDirection::Forward),
+                modifiers: Modifiers {
+                    alt: true,
+                    command: true,
+                    ..Default::default()
+                },
+            }
+        );
+    }
 }
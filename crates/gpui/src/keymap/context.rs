@@ -323,6 +323,57 @@ impl KeyBindingContextPredicate {
     }
 }
 
+impl KeyBindingContextPredicate {
+    fn precedence(&self) -> u32 {
+        match self {
+            Self::Identifier(_) => u32::MAX,
+            Self::Equal(..) | Self::NotEqual(..) => PRECEDENCE_EQ,
+            Self::Not(_) => PRECEDENCE_NOT,
+            Self::Child(..) => PRECEDENCE_CHILD,
+            Self::And(..) => PRECEDENCE_AND,
+            Self::Or(..) => PRECEDENCE_OR,
+        }
+    }
+
+    fn fmt_operand(&self, f: &mut fmt::Formatter, min_precedence: u32) -> fmt::Result {
+        if self.precedence() < min_precedence {
+            write!(f, "({self})")
+        } else {
+            write!(f, "{self}")
+        }
+    }
+
+    fn fmt_binary(
+        f: &mut fmt::Formatter,
+        left: &Self,
+        operator: &str,
+        right: &Self,
+        precedence: u32,
+    ) -> fmt::Result {
+        left.fmt_operand(f, precedence)?;
+        write!(f, " {operator} ")?;
+        right.fmt_operand(f, precedence + 1)
+    }
+}
+
+/// Formats the predicate in the keymap's context syntax, so that it parses back to itself.
+impl fmt::Display for KeyBindingContextPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Identifier(name) => write!(f, "{name}"),
+            Self::Equal(left, right) => write!(f, "{left} == {right}"),
+            Self::NotEqual(left, right) => write!(f, "{left} != {right}"),
+            Self::Not(predicate) => {
+                write!(f, "!")?;
+                predicate.fmt_operand(f, PRECEDENCE_NOT)
+            }
+            Self::Child(parent, child) => Self::fmt_binary(f, parent, ">", child, PRECEDENCE_CHILD),
+            Self::And(left, right) => Self::fmt_binary(f, left, "&&", right, PRECEDENCE_AND),
+            Self::Or(left, right) => Self::fmt_binary(f, left, "||", right, PRECEDENCE_OR),
+        }
+    }
+}
+
 const PRECEDENCE_CHILD: u32 = 1;
 const PRECEDENCE_OR: u32 = 2;
 const PRECEDENCE_AND: u32 = 3;
@@ -505,4 +556,25 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn test_display_round_trips() {
+        for source in [
+            "Editor",
+            "Editor && mode == full",
+            "Editor && (showing_completions || showing_code_actions)",
+            "BufferSearchBar > Editor",
+            "Editor && !(vim_mode == insert)",
+            "(a || b) && !c",
+            "a && b && c",
+            "a > (b > c)",
+        ] {
+            let predicate = KeyBindingContextPredicate::parse(source).unwrap();
+            assert_eq!(predicate.to_string(), source);
+            assert_eq!(
+                KeyBindingContextPredicate::parse(&predicate.to_string()).unwrap(),
+                predicate
+            );
+        }
+    }
 }
@@ -0,0 +1,113 @@
+use crate::{Action, KeyBindingContextPredicate, Modifiers, MouseButton, NavigationDirection};
+use anyhow::{anyhow, Result};
+
+/// A mouse button pressed while holding some modifier keys, like `ctrl-left` or `middle`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MouseStroke {
+    /// The button that was pressed.
+    pub button: MouseButton,
+    /// The modifier keys that were held down.
+    pub modifiers: Modifiers,
+}
+
+impl MouseStroke {
+    /// Parse a mouse stroke from a string like `ctrl-left`. The button is one of `left`,
+    /// `right`, `middle`, `back` or `forward`, and the modifiers are the same as for
+    /// keystrokes.
+    pub fn parse(source: &str) -> Result<Self> {
+        let mut modifiers = Modifiers::default();
+        let mut button = None;
+
+        let mut components = source.split('-').peekable();
+        while let Some(component) = components.next() {
+            match component {
+                "ctrl" => modifiers.control = true,
+                "alt" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "cmd" => modifiers.command = true,
+                "fn" => modifiers.function = true,
+                _ if components.peek().is_none() => {
+                    button = Some(match component {
+                        "left" => MouseButton::Left,
+                        "right" => MouseButton::Right,
+                        "middle" => MouseButton::Middle,
+                        "back" => MouseButton::Navigate(NavigationDirection::Back),
+                        "forward" => MouseButton::Navigate(NavigationDirection::Forward),
+                        _ => {
+                            return Err(anyhow!("Invalid mouse button `{component}` in `{source}`"))
+                        }
+                    });
+                }
+                _ => return Err(anyhow!("Invalid mouse stroke `{source}`")),
+            }
+        }
+
+        let button = button.ok_or_else(|| anyhow!("Invalid mouse stroke `{source}`"))?;
+        Ok(Self { button, modifiers })
+    }
+}
+
+/// A mouse binding from the keymap, which runs an action when a mouse button is pressed
+/// over an element in a matching context.
+pub struct MouseBinding {
+    pub(crate) action: Box<dyn Action>,
+    pub(crate) stroke: MouseStroke,
+    pub(crate) context_predicate: Option<KeyBindingContextPredicate>,
+}
+
+impl Clone for MouseBinding {
+    fn clone(&self) -> Self {
+        MouseBinding {
+            action: self.action.boxed_clone(),
+            stroke: self.stroke,
+            context_predicate: self.context_predicate.clone(),
+        }
+    }
+}
+
+impl MouseBinding {
+    /// Construct a new mouse binding from the given data.
+    pub fn new<A: Action>(stroke: &str, action: A, context_predicate: Option<&str>) -> Self {
+        Self::load(stroke, Box::new(action), context_predicate).unwrap()
+    }
+
+    /// Load a mouse binding from the given raw data.
+    pub fn load(stroke: &str, action: Box<dyn Action>, context: Option<&str>) -> Result<Self> {
+        let context = if let Some(context) = context {
+            Some(KeyBindingContextPredicate::parse(context)?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            action,
+            stroke: MouseStroke::parse(stroke)?,
+            context_predicate: context,
+        })
+    }
+
+    /// Get the mouse stroke associated with this binding
+    pub fn stroke(&self) -> &MouseStroke {
+        &self.stroke
+    }
+
+    /// Get the action associated with this binding
+    pub fn action(&self) -> &dyn Action {
+        self.action.as_ref()
+    }
+
+    /// Get the context in which this binding applies, if it is restricted to one
+    pub fn predicate(&self) -> Option<&KeyBindingContextPredicate> {
+        self.context_predicate.as_ref()
+    }
+}
+
+impl std::fmt::Debug for MouseBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MouseBinding")
+            .field("stroke", &self.stroke)
+            .field("context_predicate", &self.context_predicate)
+            .field("action", &self.action.name())
+            .finish()
+    }
+}
@@ -6,10 +6,11 @@ mod mac;
 mod test;
 
 use crate::{
-    Action, AnyWindowHandle, AsyncWindowContext, BackgroundExecutor, Bounds, DevicePixels, Font,
-    FontId, FontMetrics, FontRun, ForegroundExecutor, GlobalPixels, GlyphId, Keymap, LineLayout,
-    Pixels, PlatformInput, Point, RenderGlyphParams, RenderImageParams, RenderSvgParams, Result,
-    Scene, SharedString, Size, Task, TaskLabel, WindowContext,
+    AccessibilityTree, Action, AnyWindowHandle, AsyncWindowContext, BackgroundExecutor, Bounds,
+    DevicePixels, Font, FontId, FontMetrics, FontRun, ForegroundExecutor, GlobalPixels, GlyphId,
+    Keymap, Keystroke, LineLayout, Pixels, PlatformInput, Point, RenderGlyphParams,
+    RenderImageParams, RenderSvgParams, Result, Scene, SharedString, Size, Task, TaskLabel,
+    WindowContext,
 };
 use anyhow::anyhow;
 use async_task::Runnable;
@@ -87,6 +88,8 @@ pub(crate) trait Platform: 'static {
     fn on_resign_active(&self, callback: Box<dyn FnMut()>);
     fn on_quit(&self, callback: Box<dyn FnMut()>);
     fn on_reopen(&self, callback: Box<dyn FnMut()>);
+    fn set_global_hotkey(&self, keystroke: Option<&Keystroke>) -> Result<()>;
+    fn on_global_hotkey(&self, callback: Box<dyn FnMut()>);
     fn on_event(&self, callback: Box<dyn FnMut(PlatformInput) -> bool>);
 
     fn set_menus(&self, menus: Vec<Menu>, keymap: &Keymap);
@@ -176,6 +179,7 @@ pub(crate) trait PlatformWindow {
     fn is_topmost_for_position(&self, position: Point<Pixels>) -> bool;
     fn invalidate(&self);
     fn draw(&self, scene: &Scene);
+    fn update_accessibility_tree(&self, tree: &AccessibilityTree);
 
     fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas>;
 
@@ -394,7 +398,13 @@ impl PlatformInputHandler {
 
     fn bounds_for_range(&mut self, range_utf16: Range<usize>) -> Option<Bounds<Pixels>> {
         self.cx
-            .update(|cx| self.handler.bounds_for_range(range_utf16, cx))
+            .update(|cx| {
+                // The platform expects the bounds in unscaled pixels.
+                let ui_scale = cx.ui_scale();
+                self.handler
+                    .bounds_for_range(range_utf16, cx)
+                    .map(|bounds| bounds.map(|dimension| dimension * ui_scale))
+            })
             .ok()
             .flatten()
     }
@@ -562,7 +572,7 @@ pub enum WindowBounds {
 /// The appearance of the window, as defined by the operating system
 /// On macOS, this corresponds to named [NSAppearance](https://developer.apple.com/documentation/appkit/nsappearance)
 /// values
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum WindowAppearance {
     /// A light appearance
     ///
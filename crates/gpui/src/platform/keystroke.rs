@@ -108,6 +108,28 @@ impl Keystroke {
             ime_key,
         })
     }
+
+    /// Produces the keymap's syntax for this keystroke, the inverse of [`Keystroke::parse`]
+    pub fn unparse(&self) -> String {
+        let mut str = String::new();
+        if self.modifiers.control {
+            str.push_str("ctrl-");
+        }
+        if self.modifiers.alt {
+            str.push_str("alt-");
+        }
+        if self.modifiers.shift {
+            str.push_str("shift-");
+        }
+        if self.modifiers.command {
+            str.push_str("cmd-");
+        }
+        if self.modifiers.function {
+            str.push_str("fn-");
+        }
+        str.push_str(&self.key);
+        str
+    }
 }
 
 impl std::fmt::Display for Keystroke {
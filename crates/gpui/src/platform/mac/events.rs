@@ -1,7 +1,7 @@
 use crate::{
     point, px, KeyDownEvent, KeyUpEvent, Keystroke, Modifiers, ModifiersChangedEvent, MouseButton,
-    MouseDownEvent, MouseExitEvent, MouseMoveEvent, MouseUpEvent, NavigationDirection, Pixels,
-    PlatformInput, ScrollDelta, ScrollWheelEvent, TouchPhase,
+    MouseDownEvent, MouseExitEvent, MouseMoveEvent, MouseUpEvent, NavigationDirection, PinchEvent,
+    Pixels, PlatformInput, ScrollDelta, ScrollWheelEvent, SwipeEvent, TouchPhase,
 };
 use cocoa::{
     appkit::{NSEvent, NSEventModifierFlags, NSEventPhase, NSEventType},
@@ -160,13 +160,7 @@ impl PlatformInput {
                 })
             }
             NSEventType::NSScrollWheel => window_height.map(|window_height| {
-                let phase = match native_event.phase() {
-                    NSEventPhase::NSEventPhaseMayBegin | NSEventPhase::NSEventPhaseBegan => {
-                        TouchPhase::Started
-                    }
-                    NSEventPhase::NSEventPhaseEnded => TouchPhase::Ended,
-                    _ => TouchPhase::Moved,
-                };
+                let phase = read_scroll_phase(native_event);
 
                 let raw_data = point(
                     native_event.scrollingDeltaX() as f32,
@@ -189,6 +183,45 @@ impl PlatformInput {
                     modifiers: read_modifiers(native_event),
                 })
             }),
+            NSEventType::NSEventTypeMagnify => window_height.map(|window_height| {
+                let phase = match native_event.phase() {
+                    NSEventPhase::NSEventPhaseBegan => TouchPhase::Started,
+                    NSEventPhase::NSEventPhaseEnded | NSEventPhase::NSEventPhaseCancelled => {
+                        TouchPhase::Ended
+                    }
+                    _ => TouchPhase::Moved,
+                };
+
+                Self::Pinch(PinchEvent {
+                    position: point(
+                        px(native_event.locationInWindow().x as f32),
+                        window_height - px(native_event.locationInWindow().y as f32),
+                    ),
+                    delta: native_event.magnification() as f32,
+                    modifiers: read_modifiers(native_event),
+                    phase,
+                })
+            }),
+            NSEventType::NSEventTypeSwipe => {
+                // Only horizontal swipes navigate, and the system reports a positive delta
+                // for going back.
+                let direction = match native_event.deltaX() {
+                    delta if delta > 0. => NavigationDirection::Back,
+                    delta if delta < 0. => NavigationDirection::Forward,
+                    _ => return None,
+                };
+
+                window_height.map(|window_height| {
+                    Self::Swipe(SwipeEvent {
+                        position: point(
+                            px(native_event.locationInWindow().x as f32),
+                            window_height - px(native_event.locationInWindow().y as f32),
+                        ),
+                        direction,
+                        modifiers: read_modifiers(native_event),
+                    })
+                })
+            }
             NSEventType::NSLeftMouseDragged
             | NSEventType::NSRightMouseDragged
             | NSEventType::NSOtherMouseDragged => {
@@ -239,6 +272,22 @@ impl PlatformInput {
     }
 }
 
+unsafe fn read_scroll_phase(native_event: id) -> TouchPhase {
+    match native_event.phase() {
+        NSEventPhase::NSEventPhaseMayBegin | NSEventPhase::NSEventPhaseBegan => TouchPhase::Started,
+        NSEventPhase::NSEventPhaseEnded | NSEventPhase::NSEventPhaseCancelled => TouchPhase::Ended,
+        // After the fingers are lifted, the system keeps sending momentum events that
+        // decelerate the scroll. They have no phase of their own, so they're reported as
+        // moves until the momentum runs out or is interrupted by another touch.
+        _ => match native_event.momentumPhase() {
+            NSEventPhase::NSEventPhaseEnded | NSEventPhase::NSEventPhaseCancelled => {
+                TouchPhase::Ended
+            }
+            _ => TouchPhase::Moved,
+        },
+    }
+}
+
 unsafe fn parse_keystroke(native_event: id) -> Keystroke {
     use cocoa::appkit::*;
 
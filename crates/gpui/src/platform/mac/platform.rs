@@ -1,9 +1,10 @@
 use super::{events::key_to_native, BoolExt};
 use crate::{
     Action, AnyWindowHandle, BackgroundExecutor, ClipboardItem, CursorStyle, DisplayId,
-    ForegroundExecutor, Keymap, MacDispatcher, MacDisplay, MacDisplayLinker, MacTextSystem,
-    MacWindow, Menu, MenuItem, PathPromptOptions, Platform, PlatformDisplay, PlatformInput,
-    PlatformTextSystem, PlatformWindow, Result, SemanticVersion, Task, WindowOptions,
+    ForegroundExecutor, Keymap, Keystroke, MacDispatcher, MacDisplay, MacDisplayLinker,
+    MacTextSystem, MacWindow, Menu, MenuItem, PathPromptOptions, Platform, PlatformDisplay,
+    PlatformInput, PlatformTextSystem, PlatformWindow, Result, SemanticVersion, Task,
+    WindowOptions,
 };
 use anyhow::anyhow;
 use block::ConcreteBlock;
@@ -152,6 +153,9 @@ pub(crate) struct MacPlatformState {
     become_active: Option<Box<dyn FnMut()>>,
     resign_active: Option<Box<dyn FnMut()>>,
     reopen: Option<Box<dyn FnMut()>>,
+    global_hotkey: Option<Box<dyn FnMut()>>,
+    global_hotkey_ref: Option<hotkey::EventHotKeyRef>,
+    global_hotkey_handler: Option<hotkey::EventHandlerRef>,
     quit: Option<Box<dyn FnMut()>>,
     event: Option<Box<dyn FnMut(PlatformInput) -> bool>>,
     menu_command: Option<Box<dyn FnMut(&dyn Action)>>,
@@ -182,6 +186,9 @@ impl MacPlatform {
             become_active: None,
             resign_active: None,
             reopen: None,
+            global_hotkey: None,
+            global_hotkey_ref: None,
+            global_hotkey_handler: None,
             quit: None,
             event: None,
             menu_command: None,
@@ -643,6 +650,68 @@ impl Platform for MacPlatform {
         self.0.lock().reopen = Some(callback);
     }
 
+    fn set_global_hotkey(&self, keystroke: Option<&Keystroke>) -> Result<()> {
+        let mut state = self.0.lock();
+        unsafe {
+            if let Some(hotkey_ref) = state.global_hotkey_ref.take() {
+                hotkey::UnregisterEventHotKey(hotkey_ref);
+            }
+            let Some(keystroke) = keystroke else {
+                return Ok(());
+            };
+            let key_code = hotkey::key_code(&keystroke.key)
+                .ok_or_else(|| anyhow!("{:?} can't be used in a global hotkey", keystroke.key))?;
+
+            // Carbon is the only API for hotkeys that work while other applications are active.
+            if state.global_hotkey_handler.is_none() {
+                let event_type = hotkey::EventTypeSpec {
+                    event_class: hotkey::kEventClassKeyboard,
+                    event_kind: hotkey::kEventHotKeyPressed,
+                };
+                let mut handler_ref = null_mut();
+                let status = hotkey::InstallEventHandler(
+                    hotkey::GetApplicationEventTarget(),
+                    handle_global_hotkey,
+                    1,
+                    &event_type,
+                    self as *const Self as *mut c_void,
+                    &mut handler_ref,
+                );
+                if status != hotkey::noErr {
+                    return Err(anyhow!(
+                        "failed to install global hotkey handler, status: {status}"
+                    ));
+                }
+                state.global_hotkey_handler = Some(handler_ref);
+            }
+
+            let hotkey_id = hotkey::EventHotKeyID {
+                signature: hotkey::SIGNATURE,
+                id: 1,
+            };
+            let mut hotkey_ref = null_mut();
+            let status = hotkey::RegisterEventHotKey(
+                key_code,
+                hotkey::modifiers(keystroke),
+                hotkey_id,
+                hotkey::GetApplicationEventTarget(),
+                0,
+                &mut hotkey_ref,
+            );
+            if status != hotkey::noErr {
+                return Err(anyhow!(
+                    "failed to register global hotkey {keystroke}, status: {status}"
+                ));
+            }
+            state.global_hotkey_ref = Some(hotkey_ref);
+        }
+        Ok(())
+    }
+
+    fn on_global_hotkey(&self, callback: Box<dyn FnMut()>) {
+        self.0.lock().global_hotkey = Some(callback);
+    }
+
     fn on_event(&self, callback: Box<dyn FnMut(PlatformInput) -> bool>) {
         self.0.lock().event = Some(callback);
     }
@@ -1034,6 +1103,21 @@ extern "C" fn should_handle_reopen(this: &mut Object, _: Sel, _: id, has_open_wi
     }
 }
 
+extern "C" fn handle_global_hotkey(
+    _: hotkey::EventHandlerCallRef,
+    _: hotkey::EventRef,
+    platform: *mut c_void,
+) -> OSStatus {
+    let platform = unsafe { &*(platform as *const MacPlatform) };
+    let mut lock = platform.0.lock();
+    if let Some(mut callback) = lock.global_hotkey.take() {
+        drop(lock);
+        callback();
+        platform.0.lock().global_hotkey.get_or_insert(callback);
+    }
+    hotkey::noErr
+}
+
 extern "C" fn did_become_active(this: &mut Object, _: Sel, _: id) {
     let platform = unsafe { get_mac_platform(this) };
     let mut lock = platform.0.lock();
@@ -1183,6 +1267,156 @@ mod security {
     pub const errSecItemNotFound: OSStatus = -25300;
 }
 
+mod hotkey {
+    #![allow(non_upper_case_globals, non_snake_case)]
+    use super::*;
+
+    pub type EventTargetRef = *mut c_void;
+    pub type EventHandlerRef = *mut c_void;
+    pub type EventHandlerCallRef = *mut c_void;
+    pub type EventHotKeyRef = *mut c_void;
+    pub type EventRef = *mut c_void;
+    pub type EventHandlerUPP =
+        extern "C" fn(EventHandlerCallRef, EventRef, *mut c_void) -> OSStatus;
+
+    #[repr(C)]
+    pub struct EventTypeSpec {
+        pub event_class: u32,
+        pub event_kind: u32,
+    }
+
+    #[repr(C)]
+    pub struct EventHotKeyID {
+        pub signature: u32,
+        pub id: u32,
+    }
+
+    #[link(name = "Carbon", kind = "framework")]
+    extern "C" {
+        pub fn GetApplicationEventTarget() -> EventTargetRef;
+        pub fn InstallEventHandler(
+            target: EventTargetRef,
+            handler: EventHandlerUPP,
+            num_types: u32,
+            list: *const EventTypeSpec,
+            user_data: *mut c_void,
+            out_ref: *mut EventHandlerRef,
+        ) -> OSStatus;
+        pub fn RegisterEventHotKey(
+            key_code: u32,
+            modifiers: u32,
+            hotkey_id: EventHotKeyID,
+            target: EventTargetRef,
+            options: u32,
+            out_ref: *mut EventHotKeyRef,
+        ) -> OSStatus;
+        pub fn UnregisterEventHotKey(hotkey: EventHotKeyRef) -> OSStatus;
+    }
+
+    pub const noErr: OSStatus = 0;
+    pub const kEventClassKeyboard: u32 = u32::from_be_bytes(*b"keyb");
+    pub const kEventHotKeyPressed: u32 = 5;
+    pub const SIGNATURE: u32 = u32::from_be_bytes(*b"zedh");
+
+    const cmdKey: u32 = 1 << 8;
+    const shiftKey: u32 = 1 << 9;
+    const optionKey: u32 = 1 << 11;
+    const controlKey: u32 = 1 << 12;
+
+    pub fn modifiers(keystroke: &Keystroke) -> u32 {
+        let mut modifiers = 0;
+        if keystroke.modifiers.command {
+            modifiers |= cmdKey;
+        }
+        if keystroke.modifiers.shift {
+            modifiers |= shiftKey;
+        }
+        if keystroke.modifiers.alt {
+            modifiers |= optionKey;
+        }
+        if keystroke.modifiers.control {
+            modifiers |= controlKey;
+        }
+        modifiers
+    }
+
+    /// The virtual key code of a key on an ANSI keyboard.
+    pub fn key_code(key: &str) -> Option<u32> {
+        let key_code = match key {
+            "a" => 0x00,
+            "s" => 0x01,
+            "d" => 0x02,
+            "f" => 0x03,
+            "h" => 0x04,
+            "g" => 0x05,
+            "z" => 0x06,
+            "x" => 0x07,
+            "c" => 0x08,
+            "v" => 0x09,
+            "b" => 0x0B,
+            "q" => 0x0C,
+            "w" => 0x0D,
+            "e" => 0x0E,
+            "r" => 0x0F,
+            "y" => 0x10,
+            "t" => 0x11,
+            "1" => 0x12,
+            "2" => 0x13,
+            "3" => 0x14,
+            "4" => 0x15,
+            "6" => 0x16,
+            "5" => 0x17,
+            "=" => 0x18,
+            "9" => 0x19,
+            "7" => 0x1A,
+            "-" => 0x1B,
+            "8" => 0x1C,
+            "0" => 0x1D,
+            "]" => 0x1E,
+            "o" => 0x1F,
+            "u" => 0x20,
+            "[" => 0x21,
+            "i" => 0x22,
+            "p" => 0x23,
+            "enter" => 0x24,
+            "l" => 0x25,
+            "j" => 0x26,
+            "'" => 0x27,
+            "k" => 0x28,
+            ";" => 0x29,
+            "\\" => 0x2A,
+            "," => 0x2B,
+            "/" => 0x2C,
+            "n" => 0x2D,
+            "m" => 0x2E,
+            "." => 0x2F,
+            "tab" => 0x30,
+            "space" => 0x31,
+            "`" => 0x32,
+            "backspace" => 0x33,
+            "escape" => 0x35,
+            "f5" => 0x60,
+            "f6" => 0x61,
+            "f7" => 0x62,
+            "f3" => 0x63,
+            "f8" => 0x64,
+            "f9" => 0x65,
+            "f11" => 0x67,
+            "f10" => 0x6D,
+            "f12" => 0x6F,
+            "f4" => 0x76,
+            "f2" => 0x78,
+            "f1" => 0x7A,
+            "left" => 0x7B,
+            "right" => 0x7C,
+            "down" => 0x7D,
+            "up" => 0x7E,
+            _ => return None,
+        };
+        Some(key_code)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::ClipboardItem;
@@ -1,6 +1,6 @@
 use crate::{
-    point, px, size, Bounds, DevicePixels, Font, FontFeatures, FontId, FontMetrics, FontRun,
-    FontStyle, FontWeight, GlyphId, LineLayout, Pixels, PlatformTextSystem, Point,
+    point, px, size, Bounds, DevicePixels, Font, FontFallbacks, FontFeatures, FontId, FontMetrics,
+    FontRun, FontStyle, FontWeight, GlyphId, LineLayout, Pixels, PlatformTextSystem, Point,
     RenderGlyphParams, Result, ShapedGlyph, ShapedRun, SharedString, Size, SUBPIXEL_VARIANTS,
 };
 use anyhow::anyhow;
@@ -17,7 +17,9 @@ use core_graphics::{
     color_space::CGColorSpace,
     context::CGContext,
 };
-use core_text::{font::CTFont, line::CTLine, string_attributes::kCTFontAttributeName};
+use core_text::{
+    font::CTFont, line::CTLine, run::CTRunRef, string_attributes::kCTFontAttributeName,
+};
 use font_kit::{
     font::Font as FontKitFont,
     handle::Handle,
@@ -49,7 +51,7 @@ struct MacTextSystemState {
     fonts: Vec<FontKitFont>,
     font_selections: HashMap<Font, FontId>,
     font_ids_by_postscript_name: HashMap<String, FontId>,
-    font_ids_by_family_name: HashMap<SharedString, SmallVec<[FontId; 4]>>,
+    font_ids_by_family_name: HashMap<(SharedString, FontFeatures), SmallVec<[FontId; 4]>>,
     postscript_names_by_font_id: HashMap<FontId, String>,
 }
 
@@ -107,14 +109,16 @@ impl PlatformTextSystem for MacTextSystem {
             Ok(*font_id)
         } else {
             let mut lock = RwLockUpgradableReadGuard::upgrade(lock);
-            let candidates = if let Some(font_ids) = lock.font_ids_by_family_name.get(&font.family)
-            {
+            // Fonts are loaded once for each set of features, since they're applied to the
+            // loaded fonts.
+            let family_key = (font.family.clone(), font.features.clone());
+            let candidates = if let Some(font_ids) = lock.font_ids_by_family_name.get(&family_key) {
                 font_ids.as_slice()
             } else {
-                let font_ids = lock.load_family(&font.family, font.features)?;
+                let font_ids = lock.load_family(&font.family, &font.features)?;
                 lock.font_ids_by_family_name
-                    .insert(font.family.clone(), font_ids);
-                lock.font_ids_by_family_name[&font.family].as_ref()
+                    .insert(family_key.clone(), font_ids);
+                lock.font_ids_by_family_name[&family_key].as_ref()
             };
 
             let candidate_properties = candidates
@@ -131,7 +135,10 @@ impl PlatformTextSystem for MacTextSystem {
                 },
             )?;
 
-            let font_id = candidates[ix];
+            let mut font_id = candidates[ix];
+            if let Some(fallbacks) = &font.fallbacks {
+                font_id = lock.font_with_fallbacks(font_id, fallbacks);
+            }
             lock.font_selections.insert(font.clone(), font_id);
             Ok(font_id)
         }
@@ -206,7 +213,7 @@ impl MacTextSystemState {
     fn load_family(
         &mut self,
         name: &SharedString,
-        features: FontFeatures,
+        features: &FontFeatures,
     ) -> Result<SmallVec<[FontId; 4]>> {
         let mut font_ids = SmallVec::new();
         let family = self
@@ -231,6 +238,18 @@ impl MacTextSystemState {
         Ok(font_ids)
     }
 
+    /// Adds a copy of the given font that takes the glyphs it's missing from the given
+    /// fallback fonts.
+    fn font_with_fallbacks(&mut self, font_id: FontId, fallbacks: &FontFallbacks) -> FontId {
+        let mut font = self.fonts[font_id.0].clone();
+        open_type::apply_fallbacks(&mut font, fallbacks);
+        let font_id = FontId(self.fonts.len());
+        self.postscript_names_by_font_id
+            .insert(font_id, font.postscript_name().unwrap());
+        self.fonts.push(font);
+        font_id
+    }
+
     fn advance(&self, font_id: FontId, glyph_id: GlyphId) -> Result<Size<f32>> {
         Ok(self.fonts[font_id.0].advance(glyph_id.0)?.into())
     }
@@ -418,6 +437,8 @@ impl MacTextSystemState {
                     .unwrap()
             };
             let font_id = self.id_for_native_font(font);
+            let is_rtl =
+                unsafe { CTRunGetStatus(run.as_concrete_TypeRef()) } & kCTRunStatusRightToLeft != 0;
 
             let mut ix_converter = StringIndexConverter::new(text);
             let mut glyphs = SmallVec::new();
@@ -437,7 +458,11 @@ impl MacTextSystemState {
                 });
             }
 
-            runs.push(ShapedRun { font_id, glyphs })
+            runs.push(ShapedRun {
+                font_id,
+                glyphs,
+                is_rtl,
+            })
         }
 
         let typographic_bounds = line.get_typographic_bounds();
@@ -517,6 +542,12 @@ impl<'a> StringIndexConverter<'a> {
     }
 
     fn advance_to_utf16_ix(&mut self, utf16_target: usize) {
+        // The glyphs of right-to-left runs refer to decreasing string indices.
+        if utf16_target < self.utf16_ix {
+            self.utf8_ix = 0;
+            self.utf16_ix = 0;
+        }
+
         for (ix, c) in self.text[self.utf8_ix..].char_indices() {
             if self.utf16_ix >= utf16_target {
                 self.utf8_ix += ix;
@@ -533,6 +564,9 @@ pub(crate) struct __CFTypesetter(c_void);
 
 type CTTypesetterRef = *const __CFTypesetter;
 
+#[allow(non_upper_case_globals)]
+const kCTRunStatusRightToLeft: u32 = 1 << 0;
+
 #[link(name = "CoreText", kind = "framework")]
 extern "C" {
     fn CTTypesetterCreateWithAttributedString(string: CFAttributedStringRef) -> CTTypesetterRef;
@@ -542,6 +576,8 @@ extern "C" {
         start_index: CFIndex,
         width: f64,
     ) -> CFIndex;
+
+    fn CTRunGetStatus(run: CTRunRef) -> u32;
 }
 
 impl From<Metrics> for FontMetrics {
@@ -705,4 +741,39 @@ mod tests {
                                                                  // There's no glyph for \u{feff}
         assert_eq!(layout.runs[0].glyphs[1].id, GlyphId(69u32)); // b
     }
+
+    #[test]
+    fn test_layout_line_rtl() {
+        let fonts = MacTextSystem::new();
+        let font_id = fonts.font_id(&font("Helvetica")).unwrap();
+        let line = "ab שלום cd";
+        let style = FontRun {
+            font_id,
+            len: line.len(),
+        };
+
+        let layout = fonts.layout_line(line, px(16.), &[style]);
+        let mut indices = Vec::new();
+        for run in &layout.runs {
+            let run_indices = run
+                .glyphs
+                .iter()
+                .map(|glyph| glyph.index)
+                .collect::<Vec<_>>();
+            if run.is_rtl {
+                assert!(run_indices.windows(2).all(|pair| pair[0] > pair[1]));
+            } else {
+                assert!(run_indices.windows(2).all(|pair| pair[0] < pair[1]));
+            }
+            indices.extend(run_indices);
+        }
+        assert!(layout.runs.iter().any(|run| run.is_rtl));
+
+        // Every character has a glyph, in both directions.
+        indices.sort();
+        assert_eq!(
+            indices,
+            line.char_indices().map(|(ix, _)| ix).collect::<Vec<_>>()
+        );
+    }
 }
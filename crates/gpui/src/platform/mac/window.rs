@@ -1,10 +1,11 @@
 use super::{global_bounds_from_ns_rect, ns_string, MacDisplay, MetalRenderer, NSRange};
 use crate::{
-    global_bounds_to_ns_rect, platform::PlatformInputHandler, point, px, size, AnyWindowHandle,
-    Bounds, ExternalPaths, FileDropEvent, ForegroundExecutor, GlobalPixels, KeyDownEvent,
-    Keystroke, Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent,
-    MouseUpEvent, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformWindow, Point,
-    PromptLevel, Size, Timer, WindowAppearance, WindowBounds, WindowKind, WindowOptions,
+    global_bounds_to_ns_rect, platform::PlatformInputHandler, point, px, size, AccessibilityNodeId,
+    AccessibilityProperties, AccessibilityRole, AccessibilityTree, AnyWindowHandle, Bounds,
+    ExternalPaths, FileDropEvent, ForegroundExecutor, GlobalPixels, KeyDownEvent, Keystroke,
+    Modifiers, ModifiersChangedEvent, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    Pixels, PlatformAtlas, PlatformDisplay, PlatformInput, PlatformWindow, Point, PromptLevel,
+    Size, Timer, WindowAppearance, WindowBounds, WindowKind, WindowOptions,
 };
 use block::ConcreteBlock;
 use cocoa::{
@@ -20,6 +21,7 @@ use cocoa::{
         NSSize, NSString, NSUInteger,
     },
 };
+use collections::HashMap;
 use core_graphics::display::CGRect;
 use ctor::ctor;
 use foreign_types::ForeignTypeRef;
@@ -72,6 +74,12 @@ const NSTrackingInVisibleRect: NSUInteger = 0x200;
 const NSWindowAnimationBehaviorUtilityWindow: NSInteger = 4;
 #[allow(non_upper_case_globals)]
 const NSViewLayerContentsRedrawDuringViewResize: NSInteger = 2;
+#[allow(non_upper_case_globals)]
+const NSAccessibilityFocusedUIElementChangedNotification: &str = "AXFocusedUIElementChanged";
+#[allow(non_upper_case_globals)]
+const NSAccessibilityValueChangedNotification: &str = "AXValueChanged";
+#[allow(non_upper_case_globals)]
+const NSAccessibilitySelectedTextChangedNotification: &str = "AXSelectedTextChanged";
 // https://developer.apple.com/documentation/appkit/nsdragoperation
 type NSDragOperation = NSUInteger;
 #[allow(non_upper_case_globals)]
@@ -137,6 +145,14 @@ unsafe fn build_classes() {
             sel!(scrollWheel:),
             handle_view_event as extern "C" fn(&Object, Sel, id),
         );
+        decl.add_method(
+            sel!(magnifyWithEvent:),
+            handle_view_event as extern "C" fn(&Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(swipeWithEvent:),
+            handle_view_event as extern "C" fn(&Object, Sel, id),
+        );
         decl.add_method(
             sel!(flagsChanged:),
             handle_view_event as extern "C" fn(&Object, Sel, id),
@@ -216,6 +232,11 @@ unsafe fn build_classes() {
             accepts_first_mouse as extern "C" fn(&Object, Sel, id) -> BOOL,
         );
 
+        decl.add_method(
+            sel!(accessibilityFocusedUIElement),
+            accessibility_focused_ui_element as extern "C" fn(&Object, Sel) -> id,
+        );
+
         decl.register()
     };
 }
@@ -339,6 +360,14 @@ struct MacWindowState {
     // Retains the last IME Text
     ime_text: Option<String>,
     external_files_dragged: bool,
+    accessibility_elements: HashMap<AccessibilityNodeId, AccessibilityElement>,
+    focused_accessibility_element: Option<id>,
+}
+
+struct AccessibilityElement {
+    native_element: id,
+    properties: AccessibilityProperties,
+    bounds: Bounds<Pixels>,
 }
 
 impl MacWindowState {
@@ -442,6 +471,110 @@ impl MacWindowState {
             msg_send![self.native_window, convertPointToScreen: point]
         }
     }
+
+    /// Mirror the given tree with `NSAccessibilityElement`s under the window's view, reusing
+    /// the elements of nodes that were already in the tree. Returns the notifications to post
+    /// about the changes.
+    unsafe fn update_accessibility_elements(
+        &mut self,
+        tree: &AccessibilityTree,
+    ) -> Vec<(id, &'static str)> {
+        let native_view = self.native_window.contentView();
+        let view_bounds = Bounds {
+            origin: Point::default(),
+            size: self.content_size(),
+        };
+        let focused_id = tree.focused().map(|node| node.id);
+        let mut notifications = Vec::new();
+        let mut elements = HashMap::<AccessibilityNodeId, AccessibilityElement>::default();
+        let mut children = HashMap::<Option<AccessibilityNodeId>, Vec<id>>::default();
+
+        // Parents always come before their children in the tree's nodes.
+        for node in tree.nodes() {
+            let parent = node.parent.filter(|parent| elements.contains_key(parent));
+            let (native_element, previous_properties) =
+                match self.accessibility_elements.remove(&node.id) {
+                    Some(element) => (element.native_element, Some(element.properties)),
+                    None => (msg_send![class!(NSAccessibilityElement), new], None),
+                };
+
+            if previous_properties.as_ref() != Some(&node.properties) {
+                set_accessibility_properties(native_element, &node.properties);
+                if let Some(previous_properties) = previous_properties {
+                    if previous_properties.value != node.properties.value {
+                        notifications
+                            .push((native_element, NSAccessibilityValueChangedNotification));
+                    }
+                    if previous_properties.selected_range != node.properties.selected_range {
+                        notifications.push((
+                            native_element,
+                            NSAccessibilitySelectedTextChangedNotification,
+                        ));
+                    }
+                }
+            }
+
+            // Frames are relative to the bottom left corner of the parent.
+            let parent_bounds = parent.map_or(view_bounds, |parent| elements[&parent].bounds);
+            let frame = NSRect::new(
+                NSPoint::new(
+                    (node.bounds.left() - parent_bounds.left()).into(),
+                    (parent_bounds.bottom() - node.bounds.bottom()).into(),
+                ),
+                NSSize::new(
+                    node.bounds.size.width.into(),
+                    node.bounds.size.height.into(),
+                ),
+            );
+            let _: () = msg_send![native_element, setAccessibilityFrameInParentSpace: frame];
+            let native_parent =
+                parent.map_or(native_view, |parent| elements[&parent].native_element);
+            let _: () = msg_send![native_element, setAccessibilityParent: native_parent];
+            let focused = if focused_id == Some(node.id) { YES } else { NO };
+            let _: () = msg_send![native_element, setAccessibilityFocused: focused];
+
+            children.entry(parent).or_default().push(native_element);
+            elements.insert(
+                node.id,
+                AccessibilityElement {
+                    native_element,
+                    properties: node.properties.clone(),
+                    bounds: node.bounds,
+                },
+            );
+        }
+
+        // The elements of nodes that are no longer in the tree stay alive until they're
+        // removed from their parent's children below.
+        for (_, element) in self.accessibility_elements.drain() {
+            let _: () = msg_send![element.native_element, release];
+        }
+        for (node_id, element) in &elements {
+            let element_children = children.remove(&Some(*node_id)).unwrap_or_default();
+            let _: () = msg_send![
+                element.native_element,
+                setAccessibilityChildren: NSArray::arrayWithObjects(nil, &element_children)
+            ];
+        }
+        let root_children = children.remove(&None).unwrap_or_default();
+        let _: () = msg_send![
+            native_view,
+            setAccessibilityChildren: NSArray::arrayWithObjects(nil, &root_children)
+        ];
+
+        let focused_element = focused_id.map(|id| elements[&id].native_element);
+        if focused_element != self.focused_accessibility_element {
+            if let Some(focused_element) = focused_element {
+                notifications.push((
+                    focused_element,
+                    NSAccessibilityFocusedUIElementChangedNotification,
+                ));
+            }
+        }
+        self.focused_accessibility_element = focused_element;
+        self.accessibility_elements = elements;
+        notifications
+    }
 }
 
 unsafe impl Send for MacWindowState {}
@@ -547,6 +680,8 @@ impl MacWindow {
                 ime_state: ImeState::None,
                 ime_text: None,
                 external_files_dragged: false,
+                accessibility_elements: HashMap::default(),
+                focused_accessibility_element: None,
             })));
 
             (*native_window).set_ivar(
@@ -679,7 +814,13 @@ impl MacWindow {
 
 impl Drop for MacWindow {
     fn drop(&mut self) {
-        let this = self.0.lock();
+        let mut this = self.0.lock();
+        this.focused_accessibility_element = None;
+        for (_, element) in this.accessibility_elements.drain() {
+            unsafe {
+                let _: () = msg_send![element.native_element, release];
+            }
+        }
         let window = this.native_window;
         this.executor
             .spawn(async move {
@@ -1006,6 +1147,17 @@ impl PlatformWindow for MacWindow {
         this.renderer.draw(scene);
     }
 
+    fn update_accessibility_tree(&self, tree: &AccessibilityTree) {
+        let notifications = unsafe { self.0.lock().update_accessibility_elements(tree) };
+        // Post notifications without holding the lock, as assistive technologies may
+        // query the view in response.
+        for (element, notification) in notifications {
+            unsafe {
+                NSAccessibilityPostNotification(element, ns_string(notification));
+            }
+        }
+    }
+
     fn sprite_atlas(&self) -> Arc<dyn PlatformAtlas> {
         self.0.lock().renderer.sprite_atlas().clone()
     }
@@ -1502,9 +1654,11 @@ extern "C" fn first_rect_for_character_range(
     range: NSRange,
     _: id,
 ) -> NSRect {
+    // Bounds are relative to the content view, which doesn't include the titlebar unless
+    // the window draws its own.
     let frame = unsafe {
         let window = get_window_state(this).lock().native_window;
-        NSView::frame(window)
+        window.contentRectForFrameRect_(NSWindow::frame(window))
     };
     with_input_handler(this, |input_handler| {
         input_handler.bounds_for_range(range.to_range()?)
@@ -1669,6 +1823,53 @@ extern "C" fn accepts_first_mouse(this: &Object, _: Sel, _: id) -> BOOL {
     }
 }
 
+extern "C" fn accessibility_focused_ui_element(this: &Object, _: Sel) -> id {
+    unsafe {
+        let state = get_window_state(this);
+        let lock = state.as_ref().lock();
+        lock.focused_accessibility_element
+            .unwrap_or(this as *const Object as id)
+    }
+}
+
+unsafe fn set_accessibility_properties(element: id, properties: &AccessibilityProperties) {
+    let role = match properties.role {
+        AccessibilityRole::Group | AccessibilityRole::ListItem => "AXGroup",
+        AccessibilityRole::Toolbar => "AXToolbar",
+        AccessibilityRole::Button => "AXButton",
+        AccessibilityRole::Label => "AXStaticText",
+        AccessibilityRole::TextField => "AXTextField",
+        AccessibilityRole::TextArea => "AXTextArea",
+        AccessibilityRole::List => "AXList",
+        AccessibilityRole::TabList => "AXTabGroup",
+        AccessibilityRole::Tab => "AXRadioButton",
+    };
+    let _: () = msg_send![element, setAccessibilityRole: ns_string(role)];
+    let label = properties
+        .label
+        .as_ref()
+        .map_or(nil, |label| ns_string(label));
+    let _: () = msg_send![element, setAccessibilityLabel: label];
+    let value = properties
+        .value
+        .as_ref()
+        .map_or(nil, |value| ns_string(value));
+    let _: () = msg_send![element, setAccessibilityValue: value];
+    if let Some(selected_range) = properties.selected_range.clone() {
+        let _: () = msg_send![
+            element,
+            setAccessibilitySelectedTextRange: NSRange::from(selected_range)
+        ];
+    }
+    let selected = if properties.selected { YES } else { NO };
+    let _: () = msg_send![element, setAccessibilitySelected: selected];
+}
+
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    fn NSAccessibilityPostNotification(element: id, notification: id);
+}
+
 extern "C" fn dragging_entered(this: &Object, _: Sel, dragging_info: id) -> NSDragOperation {
     let window_state = unsafe { get_window_state(this) };
     if send_new_event(&window_state, {
@@ -229,6 +229,12 @@ impl Platform for TestPlatform {
         unimplemented!()
     }
 
+    fn set_global_hotkey(&self, _keystroke: Option<&crate::Keystroke>) -> Result<()> {
+        Ok(())
+    }
+
+    fn on_global_hotkey(&self, _callback: Box<dyn FnMut()>) {}
+
     fn on_event(&self, _callback: Box<dyn FnMut(crate::PlatformInput) -> bool>) {
         unimplemented!()
     }
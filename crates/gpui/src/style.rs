@@ -2,9 +2,9 @@ use std::{iter, mem, ops::Range};
 
 use crate::{
     black, phi, point, quad, rems, AbsoluteLength, Bounds, ContentMask, Corners, CornersRefinement,
-    CursorStyle, DefiniteLength, Edges, EdgesRefinement, ElementContext, Font, FontFeatures,
-    FontStyle, FontWeight, Hsla, Length, Pixels, Point, PointRefinement, Rgba, SharedString, Size,
-    SizeRefinement, Styled, TextRun,
+    CursorStyle, DefiniteLength, Edges, EdgesRefinement, ElementContext, Font, FontFallbacks,
+    FontFeatures, FontStyle, FontWeight, Hsla, Length, Pixels, Point, PointRefinement, Rgba,
+    SharedString, Size, SizeRefinement, Styled, TextRun,
 };
 use collections::HashSet;
 use refineable::Refineable;
@@ -176,6 +176,9 @@ pub struct TextStyle {
     /// The font features to use
     pub font_features: FontFeatures,
 
+    /// The fonts to take glyphs missing from the font family from
+    pub font_fallbacks: Option<FontFallbacks>,
+
     /// The font size to use, in pixels or rems.
     pub font_size: AbsoluteLength,
 
@@ -205,6 +208,7 @@ impl Default for TextStyle {
             // Helvetica is a web safe font, so it should be available
             font_family: "Helvetica".into(),
             font_features: FontFeatures::default(),
+            font_fallbacks: None,
             font_size: rems(1.).into(),
             line_height: phi(),
             font_weight: FontWeight::default(),
@@ -250,7 +254,8 @@ impl TextStyle {
     pub fn font(&self) -> Font {
         Font {
             family: self.font_family.clone(),
-            features: self.font_features,
+            features: self.font_features.clone(),
+            fallbacks: self.font_fallbacks.clone(),
             weight: self.font_weight,
             style: self.font_style,
         }
@@ -265,12 +270,7 @@ impl TextStyle {
     pub fn to_run(&self, len: usize) -> TextRun {
         TextRun {
             len,
-            font: Font {
-                family: self.font_family.clone(),
-                features: Default::default(),
-                weight: self.font_weight,
-                style: self.font_style,
-            },
+            font: self.font(),
             color: self.color,
             background_color: self.background_color,
             underline: self.underline,
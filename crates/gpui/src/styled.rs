@@ -1,6 +1,6 @@
 use crate::{
     self as gpui, hsla, point, px, relative, rems, AbsoluteLength, AlignItems, CursorStyle,
-    DefiniteLength, Fill, FlexDirection, FontWeight, Hsla, JustifyContent, Length, Position,
+    DefiniteLength, Fill, FlexDirection, Font, FontWeight, Hsla, JustifyContent, Length, Position,
     SharedString, StyleRefinement, Visibility, WhiteSpace,
 };
 use crate::{BoxShadow, TextStyleRefinement};
@@ -694,6 +694,18 @@ pub trait Styled: Sized {
         self
     }
 
+    /// Change the font on this element and it's children, along with its features, fallbacks,
+    /// weight and style.
+    fn text_font(mut self, font: Font) -> Self {
+        let text_style = self.text_style().get_or_insert_with(Default::default);
+        text_style.font_family = Some(font.family);
+        text_style.font_features = Some(font.features);
+        text_style.font_fallbacks = font.fallbacks;
+        text_style.font_weight = Some(font.weight);
+        text_style.font_style = Some(font.style);
+        self
+    }
+
     /// Set the line height on this element and it's children.
     fn line_height(mut self, line_height: impl Into<DefiniteLength>) -> Self {
         self.text_style()
@@ -639,6 +639,9 @@ pub struct Font {
     /// The font features to use.
     pub features: FontFeatures,
 
+    /// The fonts to take glyphs missing from this font from.
+    pub fallbacks: Option<FontFallbacks>,
+
     /// The font weight.
     pub weight: FontWeight,
 
@@ -651,6 +654,7 @@ pub fn font(family: impl Into<SharedString>) -> Font {
     Font {
         family: family.into(),
         features: FontFeatures::default(),
+        fallbacks: None,
         weight: FontWeight::default(),
         style: FontStyle::default(),
     }
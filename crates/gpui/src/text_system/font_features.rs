@@ -1,165 +1,187 @@
+use std::{collections::BTreeMap, sync::Arc};
+
 use schemars::{
-    schema::{InstanceType, Schema, SchemaObject, SingleOrVec},
+    schema::{InstanceType, Schema, SchemaObject, SingleOrVec, SubschemaValidation},
     JsonSchema,
 };
+use serde_derive::{Deserialize, Serialize};
+
+/// The OpenType features that can be configured for a given font, such as `calt` for
+/// contextual alternates or `ss01` for the first stylistic set.
+///
+/// Each feature's tag is mapped to the value it's set to: `0` disables the feature, `1`
+/// enables it, and larger values pick one of several alternates for features that have
+/// them, like `cv01`.
+#[derive(Default, Clone, Eq, PartialEq, Hash)]
+pub struct FontFeatures(pub Arc<Vec<(String, u32)>>);
+
+impl FontFeatures {
+    /// Get the tags and values of the configured features, ordered by tag.
+    pub fn tag_value_list(&self) -> &[(String, u32)] {
+        self.0.as_slice()
+    }
+
+    /// Get the value the feature with the given tag is set to, if it's configured.
+    pub fn value(&self, tag: &str) -> Option<u32> {
+        self.0
+            .iter()
+            .find(|(feature_tag, _)| feature_tag == tag)
+            .map(|(_, value)| *value)
+    }
+
+    /// Whether the given string is a valid OpenType feature tag: four printable ASCII
+    /// characters.
+    pub fn is_valid_tag(tag: &str) -> bool {
+        tag.len() == 4 && tag.bytes().all(|byte| byte.is_ascii_graphic())
+    }
+}
 
-macro_rules! create_definitions {
-    ($($(#[$meta:meta])* ($name:ident, $idx:expr)),* $(,)?) => {
-
-        /// The OpenType features that can be configured for a given font.
-        #[derive(Default, Copy, Clone, Eq, PartialEq, Hash)]
-        pub struct FontFeatures {
-            enabled: u64,
-            disabled: u64,
-        }
-
-        impl FontFeatures {
-            $(
-                /// Get the current value of the corresponding OpenType feature
-                pub fn $name(&self) -> Option<bool> {
-                    if (self.enabled & (1 << $idx)) != 0 {
-                        Some(true)
-                    } else if (self.disabled & (1 << $idx)) != 0 {
-                        Some(false)
-                    } else {
-                        None
-                    }
-                }
-            )*
-        }
-
-        impl std::fmt::Debug for FontFeatures {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                let mut debug = f.debug_struct("FontFeatures");
-                $(
-                    if let Some(value) = self.$name() {
-                        debug.field(stringify!($name), &value);
-                    };
-                )*
-                debug.finish()
-            }
-        }
-
-        impl<'de> serde::Deserialize<'de> for FontFeatures {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: serde::Deserializer<'de>,
-            {
-                use serde::de::{MapAccess, Visitor};
-                use std::fmt;
-
-                struct FontFeaturesVisitor;
-
-                impl<'de> Visitor<'de> for FontFeaturesVisitor {
-                    type Value = FontFeatures;
-
-                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                        formatter.write_str("a map of font features")
-                    }
-
-                    fn visit_map<M>(self, mut access: M) -> Result<Self::Value, M::Error>
-                    where
-                        M: MapAccess<'de>,
-                    {
-                        let mut enabled: u64 = 0;
-                        let mut disabled: u64 = 0;
-
-                        while let Some((key, value)) = access.next_entry::<String, Option<bool>>()? {
-                            let idx = match key.as_str() {
-                                $(stringify!($name) => $idx,)*
-                                _ => continue,
-                            };
-                            match value {
-                                Some(true) => enabled |= 1 << idx,
-                                Some(false) => disabled |= 1 << idx,
-                                None => {}
-                            };
-                        }
-                        Ok(FontFeatures { enabled, disabled })
-                    }
-                }
-
-                let features = deserializer.deserialize_map(FontFeaturesVisitor)?;
-                Ok(features)
-            }
+impl std::fmt::Debug for FontFeatures {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut debug = f.debug_struct("FontFeatures");
+        for (tag, value) in self.tag_value_list() {
+            debug.field(tag, value);
         }
+        debug.finish()
+    }
+}
 
-        impl serde::Serialize for FontFeatures {
-            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
-            where
-                S: serde::Serializer,
-            {
-                use serde::ser::SerializeMap;
-
-                let mut map = serializer.serialize_map(None)?;
+#[derive(Deserialize, Serialize)]
+#[serde(untagged)]
+enum FeatureValue {
+    Bool(bool),
+    Number(u32),
+}
 
-                $(
-                    let feature = stringify!($name);
-                    if let Some(value) = self.$name() {
-                        map.serialize_entry(feature, &value)?;
-                    }
-                )*
+impl<'de> serde::Deserialize<'de> for FontFeatures {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let features = <BTreeMap<String, Option<FeatureValue>> as serde::Deserialize>::deserialize(
+            deserializer,
+        )?;
+        let features = features
+            .into_iter()
+            .filter(|(tag, _)| Self::is_valid_tag(tag))
+            .filter_map(|(tag, value)| {
+                let value = match value? {
+                    FeatureValue::Bool(enabled) => enabled as u32,
+                    FeatureValue::Number(value) => value,
+                };
+                Some((tag, value))
+            })
+            .collect();
+        Ok(FontFeatures(Arc::new(features)))
+    }
+}
 
-                map.end()
+impl serde::Serialize for FontFeatures {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeMap;
+
+        let mut map = serializer.serialize_map(Some(self.0.len()))?;
+        for (tag, value) in self.tag_value_list() {
+            match value {
+                0 | 1 => map.serialize_entry(tag, &FeatureValue::Bool(*value == 1))?,
+                _ => map.serialize_entry(tag, &FeatureValue::Number(*value))?,
             }
         }
+        map.end()
+    }
+}
 
-        impl JsonSchema for FontFeatures {
-            fn schema_name() -> String {
-                "FontFeatures".into()
-            }
+impl JsonSchema for FontFeatures {
+    fn schema_name() -> String {
+        "FontFeatures".into()
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> Schema {
+        let mut schema = SchemaObject::default();
+        let feature_schema = Schema::Object(SchemaObject {
+            subschemas: Some(Box::new(SubschemaValidation {
+                any_of: Some(vec![
+                    Schema::Object(SchemaObject {
+                        instance_type: Some(SingleOrVec::Single(Box::new(InstanceType::Boolean))),
+                        ..Default::default()
+                    }),
+                    Schema::Object(SchemaObject {
+                        instance_type: Some(SingleOrVec::Single(Box::new(InstanceType::Integer))),
+                        ..Default::default()
+                    }),
+                ]),
+                ..Default::default()
+            })),
+            ..Default::default()
+        });
+        let object = schema.object();
+        object
+            .pattern_properties
+            .insert("^[\\x21-\\x7e]{4}$".into(), feature_schema);
+        object.additional_properties = Some(Box::new(Schema::Bool(false)));
+        schema.into()
+    }
+}
 
-            fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> Schema {
-                let mut schema = SchemaObject::default();
-                let properties = &mut schema.object().properties;
-                let feature_schema = Schema::Object(SchemaObject {
-                    instance_type: Some(SingleOrVec::Single(Box::new(InstanceType::Boolean))),
-                    ..Default::default()
-                });
+/// The fonts to take glyphs from, in order, when a font doesn't have a glyph for a
+/// character, such as CJK characters or emoji.
+#[derive(Default, Clone, Debug, Eq, PartialEq, Hash, Deserialize, Serialize)]
+pub struct FontFallbacks(pub Arc<Vec<String>>);
+
+impl FontFallbacks {
+    /// Create a list of fallbacks from the given font family names.
+    pub fn from_fonts(fonts: Vec<String>) -> Self {
+        FontFallbacks(Arc::new(fonts))
+    }
+
+    /// Get the font family names of the fallbacks, in order.
+    pub fn fallback_list(&self) -> &[String] {
+        self.0.as_slice()
+    }
+}
 
-                $(
-                    properties.insert(stringify!($name).to_owned(), feature_schema.clone());
-                )*
+impl JsonSchema for FontFallbacks {
+    fn schema_name() -> String {
+        "FontFallbacks".into()
+    }
 
-                schema.into()
-            }
-        }
-    };
+    fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> Schema {
+        Vec::<String>::json_schema(generator)
+    }
 }
 
-create_definitions!(
-    (calt, 0),
-    (case, 1),
-    (cpsp, 2),
-    (frac, 3),
-    (liga, 4),
-    (onum, 5),
-    (ordn, 6),
-    (pnum, 7),
-    (ss01, 8),
-    (ss02, 9),
-    (ss03, 10),
-    (ss04, 11),
-    (ss05, 12),
-    (ss06, 13),
-    (ss07, 14),
-    (ss08, 15),
-    (ss09, 16),
-    (ss10, 17),
-    (ss11, 18),
-    (ss12, 19),
-    (ss13, 20),
-    (ss14, 21),
-    (ss15, 22),
-    (ss16, 23),
-    (ss17, 24),
-    (ss18, 25),
-    (ss19, 26),
-    (ss20, 27),
-    (subs, 28),
-    (sups, 29),
-    (swsh, 30),
-    (titl, 31),
-    (tnum, 32),
-    (zero, 33)
-);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_features_serialization() {
+        let features: FontFeatures = serde_json::from_str(
+            r#"{ "ss01": true, "calt": false, "cv05": 3, "liga": null, "toolong": true }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            features.tag_value_list(),
+            &[
+                ("calt".to_string(), 0),
+                ("cv05".to_string(), 3),
+                ("ss01".to_string(), 1),
+            ]
+        );
+        assert_eq!(features.value("cv05"), Some(3));
+        assert_eq!(features.value("liga"), None);
+
+        let json = serde_json::to_value(&features).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "calt": false, "cv05": 3, "ss01": true })
+        );
+        assert_eq!(
+            serde_json::from_value::<FontFeatures>(json).unwrap(),
+            features
+        );
+    }
+}
@@ -108,6 +108,7 @@ fn paint_line(
 ) -> Result<()> {
     let padding_top = (line_height - layout.ascent - layout.descent) / 2.;
     let baseline_offset = point(px(0.), padding_top + layout.ascent);
+    let all_decoration_runs = decoration_runs;
     let mut decoration_runs = decoration_runs.iter();
     let mut wraps = wrap_boundaries.iter().peekable();
     let mut run_end = 0;
@@ -226,6 +227,11 @@ fn paint_line(
                         layout.font_size,
                     )?;
                 } else {
+                    let color = if run.is_rtl {
+                        decoration_color_at(all_decoration_runs, glyph.index).unwrap_or(color)
+                    } else {
+                        color
+                    };
                     cx.paint_glyph(
                         glyph_origin + baseline_offset,
                         run.font_id,
@@ -265,3 +271,16 @@ fn paint_line(
 
     Ok(())
 }
+
+/// The color of the decoration run containing the given index. Right-to-left runs visit
+/// their glyphs in decreasing index order, so their colors can't be tracked as they go.
+fn decoration_color_at(decoration_runs: &[DecorationRun], index: usize) -> Option<Hsla> {
+    let mut run_end = 0;
+    decoration_runs
+        .iter()
+        .find(|run| {
+            run_end += run.len as usize;
+            index < run_end
+        })
+        .map(|run| run.color)
+}
@@ -1,10 +1,11 @@
 use crate::{px, EntityId, FontId, GlyphId, Pixels, PlatformTextSystem, Point, Size};
 use collections::{FxHashMap, FxHashSet};
 use parking_lot::{Mutex, RwLock, RwLockUpgradableReadGuard};
-use smallvec::SmallVec;
+use smallvec::{smallvec, SmallVec};
 use std::{
     borrow::Borrow,
     hash::{Hash, Hasher},
+    ops::Range,
     sync::Arc,
 };
 
@@ -32,6 +33,9 @@ pub struct ShapedRun {
     pub font_id: FontId,
     /// The glyphs that make up this run
     pub glyphs: SmallVec<[ShapedGlyph; 8]>,
+    /// Whether this run is laid out right to left, in which case the indices of its
+    /// glyphs decrease from left to right.
+    pub is_rtl: bool,
 }
 
 /// A single glyph, ready to paint.
@@ -50,11 +54,34 @@ pub struct ShapedGlyph {
     pub is_emoji: bool,
 }
 
+/// The horizontal extent of a glyph.
+#[derive(Clone, Debug)]
+struct GlyphExtent {
+    index: usize,
+    start_x: Pixels,
+    end_x: Pixels,
+    is_rtl: bool,
+}
+
 impl LineLayout {
+    /// Whether any of this line's text is laid out right to left.
+    pub fn has_rtl_runs(&self) -> bool {
+        self.runs.iter().any(|run| run.is_rtl)
+    }
+
     /// The index for the character at the given x coordinate
     pub fn index_for_x(&self, x: Pixels) -> Option<usize> {
         if x >= self.width {
             None
+        } else if self.has_rtl_runs() {
+            let extents = self.glyph_extents();
+            Some(
+                extents
+                    .iter()
+                    .rev()
+                    .find(|extent| extent.start_x <= x)
+                    .map_or(0, |extent| extent.index),
+            )
         } else {
             for run in self.runs.iter().rev() {
                 for glyph in run.glyphs.iter().rev() {
@@ -70,6 +97,27 @@ impl LineLayout {
     /// closest_index_for_x returns the character boundary closest to the given x coordinate
     /// (e.g. to handle aligning up/down arrow keys)
     pub fn closest_index_for_x(&self, x: Pixels) -> usize {
+        if self.has_rtl_runs() {
+            let extents = self.glyph_extents();
+            let Some(extent) = extents.iter().rev().find(|extent| extent.start_x <= x) else {
+                return extents.first().map_or(0, |extent| {
+                    if extent.is_rtl {
+                        self.next_index(&extents, extent.index)
+                    } else {
+                        extent.index
+                    }
+                });
+            };
+
+            // The character's logical start is on its right in right-to-left text.
+            let closer_to_left_edge = x - extent.start_x < extent.end_x - x;
+            return if closer_to_left_edge != extent.is_rtl {
+                extent.index
+            } else {
+                self.next_index(&extents, extent.index)
+            };
+        }
+
         let mut prev_index = 0;
         let mut prev_x = px(0.);
 
@@ -92,6 +140,33 @@ impl LineLayout {
 
     /// The x position of the character at the given index
     pub fn x_for_index(&self, index: usize) -> Pixels {
+        if self.has_rtl_runs() {
+            // The caret goes before the character at the index, which is on its right in
+            // right-to-left text. At the end of the line, it goes after the last character.
+            let extents = self.glyph_extents();
+            if let Some(extent) = extents
+                .iter()
+                .filter(|extent| extent.index >= index)
+                .min_by_key(|extent| extent.index)
+            {
+                return if extent.is_rtl {
+                    extent.end_x
+                } else {
+                    extent.start_x
+                };
+            }
+            return extents
+                .iter()
+                .max_by_key(|extent| extent.index)
+                .map_or(self.width, |extent| {
+                    if extent.is_rtl {
+                        extent.start_x
+                    } else {
+                        extent.end_x
+                    }
+                });
+        }
+
         for run in &self.runs {
             for glyph in &run.glyphs {
                 if glyph.index >= index {
@@ -102,6 +177,61 @@ impl LineLayout {
         self.width
     }
 
+    /// The horizontal ranges covered by the characters in the given range, from left to
+    /// right. Text mixing both directions can make a single range of characters cover
+    /// several disjoint ranges on screen.
+    pub fn x_ranges_for_range(&self, range: Range<usize>) -> SmallVec<[Range<Pixels>; 1]> {
+        if !self.has_rtl_runs() {
+            return smallvec![self.x_for_index(range.start)..self.x_for_index(range.end)];
+        }
+
+        let mut x_ranges = SmallVec::<[Range<Pixels>; 1]>::new();
+        for extent in self.glyph_extents() {
+            if !range.contains(&extent.index) {
+                continue;
+            }
+            match x_ranges.last_mut() {
+                Some(x_range) if x_range.end == extent.start_x => x_range.end = extent.end_x,
+                _ => x_ranges.push(extent.start_x..extent.end_x),
+            }
+        }
+        x_ranges
+    }
+
+    /// The extents of every glyph in the line, from left to right.
+    fn glyph_extents(&self) -> Vec<GlyphExtent> {
+        let mut extents = self
+            .runs
+            .iter()
+            .flat_map(|run| {
+                run.glyphs.iter().map(|glyph| GlyphExtent {
+                    index: glyph.index,
+                    start_x: glyph.position.x,
+                    end_x: glyph.position.x,
+                    is_rtl: run.is_rtl,
+                })
+            })
+            .collect::<Vec<_>>();
+        extents.sort_by_key(|extent| extent.start_x);
+
+        let mut end_x = self.width;
+        for extent in extents.iter_mut().rev() {
+            extent.end_x = end_x.max(extent.start_x);
+            end_x = extent.start_x;
+        }
+        extents
+    }
+
+    /// The index of the character following the one at the given index.
+    fn next_index(&self, extents: &[GlyphExtent], index: usize) -> usize {
+        extents
+            .iter()
+            .map(|extent| extent.index)
+            .filter(|ix| *ix > index)
+            .min()
+            .unwrap_or(self.len)
+    }
+
     fn compute_wrap_boundaries(
         &self,
         text: &str,
@@ -494,3 +624,64 @@ impl<'a> AsCacheKeyRef for CacheKeyRef<'a> {
         *self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::point;
+
+    #[test]
+    fn test_mixed_direction_layout() {
+        // "ab אב": the Hebrew letters are laid out right to left, after the space.
+        let glyph = |index: usize, x: f32| ShapedGlyph {
+            id: GlyphId(0),
+            position: point(px(x), px(0.)),
+            index,
+            is_emoji: false,
+        };
+        let layout = LineLayout {
+            width: px(50.),
+            runs: vec![
+                ShapedRun {
+                    font_id: FontId(0),
+                    glyphs: smallvec![glyph(0, 0.), glyph(1, 10.), glyph(2, 20.)],
+                    is_rtl: false,
+                },
+                ShapedRun {
+                    font_id: FontId(0),
+                    glyphs: smallvec![glyph(5, 30.), glyph(3, 40.)],
+                    is_rtl: true,
+                },
+            ],
+            len: "ab אב".len(),
+            ..Default::default()
+        };
+        assert!(layout.has_rtl_runs());
+
+        assert_eq!(layout.x_for_index(0), px(0.));
+        assert_eq!(layout.x_for_index(2), px(20.));
+        assert_eq!(layout.x_for_index(3), px(50.));
+        assert_eq!(layout.x_for_index(5), px(40.));
+        assert_eq!(layout.x_for_index(7), px(30.));
+
+        assert_eq!(layout.index_for_x(px(15.)), Some(1));
+        assert_eq!(layout.index_for_x(px(45.)), Some(3));
+        assert_eq!(layout.index_for_x(px(35.)), Some(5));
+        assert_eq!(layout.index_for_x(px(60.)), None);
+
+        assert_eq!(layout.closest_index_for_x(px(12.)), 1);
+        assert_eq!(layout.closest_index_for_x(px(48.)), 3);
+        assert_eq!(layout.closest_index_for_x(px(42.)), 5);
+        assert_eq!(layout.closest_index_for_x(px(31.)), 7);
+        assert_eq!(layout.closest_index_for_x(px(60.)), 3);
+
+        assert_eq!(
+            layout.x_ranges_for_range(1..5).as_slice(),
+            &[px(10.)..px(30.), px(40.)..px(50.)]
+        );
+        assert_eq!(
+            layout.x_ranges_for_range(0..layout.len).as_slice(),
+            &[px(0.)..px(50.)]
+        );
+    }
+}
@@ -29,17 +29,36 @@ impl LineWrapper {
         }
     }
 
-    /// Wrap a line of text to the given width with this wrapper's font and font size.
+    /// Wrap a line of text to the given width with this wrapper's font and font size,
+    /// indenting wrapped lines as far as the line itself.
     pub fn wrap_line<'a>(
         &'a mut self,
         line: &'a str,
         wrap_width: Pixels,
+    ) -> impl Iterator<Item = Boundary> + 'a {
+        self.wrap_line_with_indent(line, wrap_width, WrapIndent::Same)
+    }
+
+    /// Wrap a line of text to the given width with this wrapper's font and font size,
+    /// indenting wrapped lines as specified.
+    ///
+    /// Lines are wrapped after whitespace where possible, and between characters of
+    /// CJK text, which isn't separated by spaces. A token too long to fit on a line is
+    /// wrapped after punctuation such as `/` or `-` if it contains any, and otherwise
+    /// at the last character that fits.
+    pub fn wrap_line_with_indent<'a>(
+        &'a mut self,
+        line: &'a str,
+        wrap_width: Pixels,
+        wrap_indent: WrapIndent,
     ) -> impl Iterator<Item = Boundary> + 'a {
         let mut width = px(0.);
         let mut first_non_whitespace_ix = None;
         let mut indent = None;
         let mut last_candidate_ix = 0;
         let mut last_candidate_width = px(0.);
+        let mut last_token_candidate_ix = 0;
+        let mut last_token_candidate_width = px(0.);
         let mut last_wrap_ix = 0;
         let mut prev_c = '\0';
         let mut char_indices = line.char_indices();
@@ -49,9 +68,14 @@ impl LineWrapper {
                     continue;
                 }
 
-                if prev_c == ' ' && c != ' ' && first_non_whitespace_ix.is_some() {
-                    last_candidate_ix = ix;
-                    last_candidate_width = width;
+                if first_non_whitespace_ix.is_some() && ix > last_wrap_ix {
+                    if (prev_c == ' ' && c != ' ') || is_cjk_break(prev_c, c) {
+                        last_candidate_ix = ix;
+                        last_candidate_width = width;
+                    } else if is_token_break(prev_c, c) {
+                        last_token_candidate_ix = ix;
+                        last_token_candidate_width = width;
+                    }
                 }
 
                 if c != ' ' && first_non_whitespace_ix.is_none() {
@@ -63,19 +87,26 @@ impl LineWrapper {
                 if width > wrap_width && ix > last_wrap_ix {
                     if let (None, Some(first_non_whitespace_ix)) = (indent, first_non_whitespace_ix)
                     {
-                        indent = Some(
-                            Self::MAX_INDENT.min((first_non_whitespace_ix - last_wrap_ix) as u32),
-                        );
+                        let line_indent = (first_non_whitespace_ix - last_wrap_ix) as u32;
+                        indent = Some(Self::MAX_INDENT.min(match wrap_indent {
+                            WrapIndent::None => 0,
+                            WrapIndent::Same => line_indent,
+                            WrapIndent::Extra(extra) => line_indent + extra,
+                        }));
                     }
 
                     if last_candidate_ix > 0 {
                         last_wrap_ix = last_candidate_ix;
                         width -= last_candidate_width;
-                        last_candidate_ix = 0;
+                    } else if last_token_candidate_ix > 0 {
+                        last_wrap_ix = last_token_candidate_ix;
+                        width -= last_token_candidate_width;
                     } else {
                         last_wrap_ix = ix;
                         width = char_width;
                     }
+                    last_candidate_ix = 0;
+                    last_token_candidate_ix = 0;
 
                     if let Some(indent) = indent {
                         width += self.width_for_char(' ') * indent as f32;
@@ -125,6 +156,56 @@ impl LineWrapper {
     }
 }
 
+/// How the lines that a line of text is wrapped onto are indented.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum WrapIndent {
+    /// Wrapped lines aren't indented.
+    None,
+    /// Wrapped lines are indented as far as the line they were wrapped from.
+    #[default]
+    Same,
+    /// Wrapped lines are indented further than the line they were wrapped from, by the
+    /// given number of columns.
+    Extra(u32),
+}
+
+/// Whether a line can be wrapped between two characters of CJK text, or between CJK
+/// text and other text. Lines aren't wrapped before closing punctuation or after
+/// opening punctuation, so that it stays attached to the text it encloses.
+fn is_cjk_break(prev: char, next: char) -> bool {
+    (is_cjk(prev) || is_cjk(next))
+        && next != ' '
+        && prev != ' '
+        && !"、。，．：；！？）」』】〕〉》ー々…,.!?;:)]}".contains(next)
+        && !"（「『【〔〈《([{".contains(prev)
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1100..=0x11FF // Hangul Jamo
+            | 0x2E80..=0x2FFF // CJK radicals
+            | 0x3000..=0x303F // CJK symbols and punctuation
+            | 0x3040..=0x30FF // Hiragana and Katakana
+            | 0x3100..=0x31FF // Bopomofo, Hangul compatibility Jamo and Katakana extensions
+            | 0x3400..=0x4DBF // CJK unified ideographs extension A
+            | 0x4E00..=0x9FFF // CJK unified ideographs
+            | 0xAC00..=0xD7AF // Hangul syllables
+            | 0xF900..=0xFAFF // CJK compatibility ideographs
+            | 0xFF00..=0xFFEF // Halfwidth and fullwidth forms
+            | 0x20000..=0x3FFFF // CJK unified ideographs extensions B and beyond
+    )
+}
+
+/// Whether a token that's too long to fit on a line can be wrapped between two
+/// characters, such as after a path separator or a hyphen.
+fn is_token_break(prev: char, next: char) -> bool {
+    matches!(
+        prev,
+        '/' | '\\' | '-' | '.' | ',' | ':' | ';' | '?' | '&' | '=' | '_'
+    ) && next.is_alphanumeric()
+}
+
 /// A boundary between two lines of text.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub struct Boundary {
@@ -209,9 +290,50 @@ mod tests {
                     Boundary::new(22, 3),
                 ]
             );
+
+            // Long tokens are wrapped after punctuation where possible.
+            assert_eq!(
+                wrapper
+                    .wrap_line("aaaaa/bbbb-ccccc", px(72.))
+                    .collect::<Vec<_>>(),
+                &[Boundary::new(6, 0), Boundary::new(11, 0)]
+            );
+
+            // Wrapped lines can be indented further than the line, or not at all.
+            assert_eq!(
+                wrapper
+                    .wrap_line_with_indent("  aa bb cc dd", px(72.), WrapIndent::Extra(2))
+                    .collect::<Vec<_>>(),
+                &[
+                    Boundary::new(5, 4),
+                    Boundary::new(8, 4),
+                    Boundary::new(11, 4)
+                ]
+            );
+            assert_eq!(
+                wrapper
+                    .wrap_line_with_indent("  aa bb cc dd", px(72.), WrapIndent::None)
+                    .collect::<Vec<_>>(),
+                &[Boundary::new(5, 0), Boundary::new(11, 0)]
+            );
         });
     }
 
+    #[test]
+    fn test_cjk_breaks() {
+        assert!(is_cjk_break('中', '文'));
+        assert!(is_cjk_break('a', '中'));
+        assert!(is_cjk_break('。', '中'));
+        assert!(!is_cjk_break('a', 'b'));
+        assert!(!is_cjk_break(' ', '中'));
+
+        // Punctuation stays with the text it follows or precedes.
+        assert!(!is_cjk_break('中', '。'));
+        assert!(!is_cjk_break('中', '，'));
+        assert!(!is_cjk_break('「', '中'));
+        assert!(!is_cjk_break('中', ')'));
+    }
+
     // For compatibility with the test macro
     use crate as gpui;
 
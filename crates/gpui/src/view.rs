@@ -9,6 +9,7 @@ use std::{
     any::{type_name, TypeId},
     fmt,
     hash::{Hash, Hasher},
+    time::{Duration, Instant},
 };
 
 /// A view is a piece of state that can be presented on screen by implementing the [Render] trait.
@@ -261,6 +262,30 @@ impl AnyView {
             });
         })
     }
+
+    /// Draws the view like [`Self::draw`], returning how long it took to lay it out and
+    /// how long it took to paint it.
+    pub(crate) fn draw_timed(
+        &self,
+        origin: Point<Pixels>,
+        available_space: Size<AvailableSpace>,
+        cx: &mut ElementContext,
+    ) -> (Duration, Duration) {
+        let (mut layout_time, mut paint_time) = (Duration::ZERO, Duration::ZERO);
+        cx.paint_view(self.entity_id(), |cx| {
+            cx.with_absolute_element_offset(origin, |cx| {
+                let layout_start = Instant::now();
+                let (layout_id, mut rendered_element) = (self.request_layout)(self, cx);
+                cx.compute_layout(layout_id, available_space);
+                layout_time = layout_start.elapsed();
+
+                let paint_start = Instant::now();
+                rendered_element.paint(cx);
+                paint_time = paint_start.elapsed();
+            });
+        });
+        (layout_time, paint_time)
+    }
 }
 
 impl<V: Render> From<View<V>> for AnyView {
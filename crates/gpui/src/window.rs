@@ -1,13 +1,14 @@
 use crate::{
-    px, size, transparent_black, Action, AnyDrag, AnyView, AppContext, Arena, AsyncWindowContext,
-    AvailableSpace, Bounds, Context, Corners, CursorStyle, DispatchActionListener, DispatchNodeId,
-    DispatchTree, DisplayId, Edges, Effect, Entity, EntityId, EventEmitter, FileDropEvent, Flatten,
-    GlobalElementId, Hsla, KeyBinding, KeyContext, KeyDownEvent, KeyMatch, KeymatchMode,
-    KeymatchResult, Keystroke, KeystrokeEvent, Model, ModelContext, Modifiers, MouseButton,
-    MouseMoveEvent, MouseUpEvent, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput,
+    px, size, transparent_black, AccessibilityTree, Action, AnyDrag, AnyView, AppContext, Arena,
+    AsyncWindowContext, AvailableSpace, Bounds, Context, Corners, CursorStyle,
+    DispatchActionListener, DispatchNodeId, DispatchTree, DisplayId, Edges, Effect, Entity,
+    EntityId, EventEmitter, FileDropEvent, Flatten, GlobalElementId, Hsla, KeyBinding, KeyContext,
+    KeyDownEvent, KeyMatch, KeymatchMode, KeymatchResult, Keystroke, KeystrokeEvent, Model,
+    ModelContext, Modifiers, MouseBindingTarget, MouseButton, MouseDownEvent, MouseMoveEvent,
+    MouseStroke, MouseUpEvent, NoAction, Pixels, PlatformAtlas, PlatformDisplay, PlatformInput,
     PlatformWindow, Point, PromptLevel, Render, ScaledPixels, SharedString, Size, SubscriberSet,
-    Subscription, TaffyLayoutEngine, Task, View, VisualContext, WeakView, WindowBounds,
-    WindowOptions,
+    Subscription, TaffyLayoutEngine, Task, View, VisualContext, WeakView, WindowAppearance,
+    WindowBounds, WindowOptions,
 };
 use anyhow::{anyhow, Context as _, Result};
 use collections::FxHashSet;
@@ -34,12 +35,14 @@ use std::{
         atomic::{AtomicUsize, Ordering::SeqCst},
         Arc,
     },
-    time::Duration,
+    time::{Duration, Instant},
 };
 use util::{measure, ResultExt};
 
 mod element_cx;
+mod frame_timings;
 pub use element_cx::*;
+pub use frame_timings::*;
 
 const ACTIVE_DRAG_Z_INDEX: u16 = 1;
 
@@ -259,6 +262,7 @@ pub struct Window {
     pub(crate) rendered_frame: Frame,
     pub(crate) next_frame: Frame,
     pub(crate) dirty_views: FxHashSet<EntityId>,
+    frame_timings: FrameTimings,
     pub(crate) focus_handles: Arc<RwLock<SlotMap<FocusId, AtomicUsize>>>,
     focus_listeners: SubscriberSet<(), AnyWindowFocusListener>,
     focus_lost_listeners: SubscriberSet<(), AnyObserver>,
@@ -266,8 +270,11 @@ pub struct Window {
     mouse_position: Point<Pixels>,
     modifiers: Modifiers,
     scale_factor: f32,
+    ui_scale: f32,
     bounds: WindowBounds,
     bounds_observers: SubscriberSet<(), AnyObserver>,
+    appearance: WindowAppearance,
+    appearance_observers: SubscriberSet<(), AnyObserver>,
     active: bool,
     pub(crate) dirty: bool,
     pub(crate) refreshing: bool,
@@ -276,6 +283,8 @@ pub struct Window {
     pub(crate) focus: Option<FocusId>,
     focus_enabled: bool,
     pending_input: Option<PendingInput>,
+    keystroke_recorder: Option<FocusId>,
+    mouse_binding_target: Option<MouseBindingTarget>,
 
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) focus_invalidated: bool,
@@ -337,6 +346,7 @@ impl Window {
         let content_size = platform_window.content_size();
         let scale_factor = platform_window.scale_factor();
         let bounds = platform_window.bounds();
+        let appearance = platform_window.appearance();
 
         platform_window.on_request_frame(Box::new({
             let mut cx = cx.to_async();
@@ -362,6 +372,14 @@ impl Window {
                     .log_err();
             }
         }));
+        platform_window.on_appearance_changed(Box::new({
+            let mut cx = cx.to_async();
+            move || {
+                handle
+                    .update(&mut cx, |_, cx| cx.window_appearance_changed())
+                    .log_err();
+            }
+        }));
         platform_window.on_active_status_change(Box::new({
             let mut cx = cx.to_async();
             move |active| {
@@ -401,6 +419,7 @@ impl Window {
             rendered_frame: Frame::new(DispatchTree::new(cx.keymap.clone(), cx.actions.clone())),
             next_frame: Frame::new(DispatchTree::new(cx.keymap.clone(), cx.actions.clone())),
             dirty_views: FxHashSet::default(),
+            frame_timings: FrameTimings::default(),
             focus_handles: Arc::new(RwLock::new(SlotMap::with_key())),
             focus_listeners: SubscriberSet::new(),
             focus_lost_listeners: SubscriberSet::new(),
@@ -408,8 +427,11 @@ impl Window {
             mouse_position,
             modifiers,
             scale_factor,
+            ui_scale: 1.,
             bounds,
             bounds_observers: SubscriberSet::new(),
+            appearance,
+            appearance_observers: SubscriberSet::new(),
             active: false,
             dirty: false,
             refreshing: false,
@@ -418,6 +440,8 @@ impl Window {
             focus: None,
             focus_enabled: true,
             pending_input: None,
+            keystroke_recorder: None,
+            mouse_binding_target: None,
 
             #[cfg(any(test, feature = "test-support"))]
             focus_invalidated: false,
@@ -717,8 +741,7 @@ impl<'a> WindowContext<'a> {
     }
 
     fn window_bounds_changed(&mut self) {
-        self.window.scale_factor = self.window.platform_window.scale_factor();
-        self.window.viewport_size = self.window.platform_window.content_size();
+        self.update_scale();
         self.window.bounds = self.window.platform_window.bounds();
         self.window.display_id = self.window.platform_window.display().id();
         self.refresh();
@@ -729,6 +752,21 @@ impl<'a> WindowContext<'a> {
             .retain(&(), |callback| callback(self));
     }
 
+    fn window_appearance_changed(&mut self) {
+        self.window.appearance = self.window.platform_window.appearance();
+        self.refresh();
+
+        self.window
+            .appearance_observers
+            .clone()
+            .retain(&(), |callback| callback(self));
+    }
+
+    /// Returns the appearance of the current window, as determined by the operating system.
+    pub fn appearance(&self) -> WindowAppearance {
+        self.window.appearance
+    }
+
     /// Returns the bounds of the current window in the global coordinate space, which could span across multiple displays.
     pub fn window_bounds(&self) -> WindowBounds {
         self.window.bounds
@@ -739,6 +777,17 @@ impl<'a> WindowContext<'a> {
         self.window.viewport_size
     }
 
+    /// Returns how long the window's most recent frames took to draw.
+    pub fn frame_timings(&self) -> &FrameTimings {
+        &self.window.frame_timings
+    }
+
+    /// The elements of this window that were exposed to assistive technologies when it was
+    /// last drawn.
+    pub fn accessibility_tree(&self) -> &AccessibilityTree {
+        &self.window.rendered_frame.accessibility_tree
+    }
+
     /// Returns whether this window is focused by the operating system (receiving key events).
     pub fn is_window_active(&self) -> bool {
         self.window.active
@@ -772,13 +821,41 @@ impl<'a> WindowContext<'a> {
         self.window.platform_window.show_character_palette();
     }
 
-    /// The scale factor of the display associated with the window. For example, it could
-    /// return 2.0 for a "retina" display, indicating that each logical pixel should actually
-    /// be rendered as two pixels on screen.
+    /// The scale factor of the display associated with the window, multiplied by the window's
+    /// [`ui_scale`](Self::ui_scale). For example, it could return 2.0 for a "retina" display,
+    /// indicating that each logical pixel should actually be rendered as two pixels on screen.
     pub fn scale_factor(&self) -> f32 {
         self.window.scale_factor
     }
 
+    /// The factor by which everything in the window is scaled, on top of the scale factor of
+    /// its display. Unlike the rem size, this also scales sizes given in pixels.
+    pub fn ui_scale(&self) -> f32 {
+        self.window.ui_scale
+    }
+
+    /// Sets the factor by which everything in the window is scaled, on top of the scale factor
+    /// of its display. Unlike the rem size, this also scales sizes given in pixels.
+    pub fn set_ui_scale(&mut self, ui_scale: f32) {
+        if ui_scale != self.window.ui_scale {
+            self.window.ui_scale = ui_scale;
+            self.update_scale();
+            self.refresh();
+        }
+    }
+
+    /// Recomputes the scale factor and the size of the viewport, which are both given in
+    /// scaled pixels.
+    fn update_scale(&mut self) {
+        let ui_scale = self.window.ui_scale;
+        self.window.scale_factor = self.window.platform_window.scale_factor() * ui_scale;
+        self.window.viewport_size = self
+            .window
+            .platform_window
+            .content_size()
+            .map(|dimension| dimension * (1. / ui_scale));
+    }
+
     /// The size of an em for the base font of the application. Adjusting this value allows the
     /// UI to scale, just like zooming a web page.
     pub fn rem_size(&self) -> Pixels {
@@ -929,6 +1006,7 @@ impl<'a> WindowContext<'a> {
 
     /// Draw pixels to the display for this window based on the contents of its scene.
     pub(crate) fn draw(&mut self) {
+        let frame_start = Instant::now();
         self.window.dirty = false;
         self.window.drawing = true;
 
@@ -944,6 +1022,7 @@ impl<'a> WindowContext<'a> {
         }
 
         let root_view = self.window.root_view.take().unwrap();
+        let (mut layout_time, mut paint_time) = (Duration::ZERO, Duration::ZERO);
         self.with_element_context(|cx| {
             cx.with_z_index(0, |cx| {
                 cx.with_key_dispatch(Some(KeyContext::default()), None, |_, cx| {
@@ -962,7 +1041,8 @@ impl<'a> WindowContext<'a> {
                     }
 
                     let available_space = cx.window.viewport_size.map(Into::into);
-                    root_view.draw(Point::default(), available_space, cx);
+                    (layout_time, paint_time) =
+                        root_view.draw_timed(Point::default(), available_space, cx);
                 })
             })
         });
@@ -1002,6 +1082,10 @@ impl<'a> WindowContext<'a> {
             );
         self.window.next_frame.focus = self.window.focus;
         self.window.next_frame.window_active = self.window.active;
+        self.window
+            .next_frame
+            .accessibility_tree
+            .finish(self.window.focus);
         self.window.root_view = Some(root_view);
 
         // Set the cursor only if we're the active window.
@@ -1039,6 +1123,13 @@ impl<'a> WindowContext<'a> {
         let previous_focus_path = self.window.rendered_frame.focus_path();
         let previous_window_active = self.window.rendered_frame.window_active;
         mem::swap(&mut self.window.rendered_frame, &mut self.window.next_frame);
+        if self.window.rendered_frame.accessibility_tree
+            != self.window.next_frame.accessibility_tree
+        {
+            self.window
+                .platform_window
+                .update_accessibility_tree(&self.window.rendered_frame.accessibility_tree);
+        }
         self.window.next_frame.clear();
         let current_focus_path = self.window.rendered_frame.focus_path();
         let current_window_active = self.window.rendered_frame.window_active;
@@ -1076,6 +1167,11 @@ impl<'a> WindowContext<'a> {
             .draw(&self.window.rendered_frame.scene);
         self.window.refreshing = false;
         self.window.drawing = false;
+        self.window.frame_timings.push(FrameTiming {
+            layout: layout_time,
+            paint: paint_time,
+            total: frame_start.elapsed(),
+        });
     }
 
     /// Dispatch a mouse or keyboard event on the window.
@@ -1085,6 +1181,13 @@ impl<'a> WindowContext<'a> {
         // Handlers may set this to true by calling `prevent_default`.
         self.window.default_prevented = false;
 
+        // The platform gives the positions of events in unscaled pixels.
+        let event = if self.window.ui_scale == 1. {
+            event
+        } else {
+            event.scale(1. / self.window.ui_scale)
+        };
+
         let event = match event {
             // Track the mouse position with our own state, since accessing the platform
             // API for the mouse position can only occur on the main thread.
@@ -1116,6 +1219,16 @@ impl<'a> WindowContext<'a> {
                 self.window.modifiers = scroll_wheel.modifiers;
                 PlatformInput::ScrollWheel(scroll_wheel)
             }
+            PlatformInput::Pinch(pinch) => {
+                self.window.mouse_position = pinch.position;
+                self.window.modifiers = pinch.modifiers;
+                PlatformInput::Pinch(pinch)
+            }
+            PlatformInput::Swipe(swipe) => {
+                self.window.mouse_position = swipe.position;
+                self.window.modifiers = swipe.modifiers;
+                PlatformInput::Swipe(swipe)
+            }
             // Translate dragging and dropping of external files from the operating system
             // to internal drag and drop events.
             PlatformInput::FileDrop(file_drop) => match file_drop {
@@ -1171,6 +1284,33 @@ impl<'a> WindowContext<'a> {
         !self.app.propagate_event
     }
 
+    /// Dispatch a keystroke as though the user had typed it, inserting its text into the
+    /// focused input when no binding handles it. Returns whether the keystroke was handled.
+    ///
+    /// The window is drawn first if it has changed, so that the keystroke is matched against
+    /// the key contexts of its current state rather than those of the last frame.
+    pub fn dispatch_keystroke(&mut self, keystroke: Keystroke) -> bool {
+        if self.window.dirty {
+            self.draw();
+        }
+
+        if self.dispatch_event(PlatformInput::KeyDown(KeyDownEvent {
+            keystroke: keystroke.clone(),
+            is_held: false,
+        })) {
+            return true;
+        }
+
+        if let Some(input) = keystroke.ime_key.as_ref() {
+            if let Some(mut input_handler) = self.window.platform_window.take_input_handler() {
+                input_handler.flush_pending_input(input, self);
+                self.window.platform_window.set_input_handler(input_handler);
+                return true;
+            }
+        }
+        false
+    }
+
     fn dispatch_mouse_event(&mut self, event: &dyn Any) {
         if let Some(mut handlers) = self
             .window
@@ -1180,6 +1320,7 @@ impl<'a> WindowContext<'a> {
         {
             // Because handlers may add other handlers, we sort every time.
             handlers.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+            self.window.mouse_binding_target = None;
 
             // Capture phase, events bubble from back to front. Handlers for this phase are used for
             // special purposes, such as detecting events outside of a given Bounds.
@@ -1192,6 +1333,13 @@ impl<'a> WindowContext<'a> {
                 }
             }
 
+            // Mouse bindings take precedence over the elements' own handling of the button.
+            if self.app.propagate_event {
+                if let Some(mouse_down) = event.downcast_ref::<MouseDownEvent>() {
+                    self.dispatch_mouse_binding(mouse_down);
+                }
+            }
+
             // Bubble phase, where most normal handlers do their work.
             if self.app.propagate_event {
                 for (_, _, handler) in handlers.iter_mut().rev() {
@@ -1224,6 +1372,44 @@ impl<'a> WindowContext<'a> {
         }
     }
 
+    fn dispatch_mouse_binding(&mut self, event: &MouseDownEvent) {
+        let target = self.window.mouse_binding_target.take().unwrap_or_default();
+        let stroke = MouseStroke {
+            button: event.button,
+            modifiers: event.modifiers,
+        };
+        let Some(action) = self
+            .app
+            .keymap
+            .borrow()
+            .mouse_binding(&stroke, &target.context_stack)
+            .map(|binding| binding.action().boxed_clone())
+        else {
+            return;
+        };
+
+        // A binding to `NoAction` only suppresses the button's default behavior.
+        self.app.propagate_event = false;
+        if action.as_any().is::<NoAction>() {
+            return;
+        }
+
+        self.defer(move |cx| {
+            let node_id = target
+                .focus_id
+                .and_then(|focus_id| {
+                    cx.window
+                        .rendered_frame
+                        .dispatch_tree
+                        .focusable_node_id(focus_id)
+                })
+                .unwrap_or_else(|| cx.window.rendered_frame.dispatch_tree.root_node_id());
+
+            cx.propagate_event = true;
+            cx.dispatch_action_on_node(node_id, action);
+        })
+    }
+
     fn dispatch_key_event(&mut self, event: &dyn Any) {
         let node_id = self
             .window
@@ -1242,7 +1428,12 @@ impl<'a> WindowContext<'a> {
             .dispatch_tree
             .dispatch_path(node_id);
 
-        if let Some(key_down_event) = event.downcast_ref::<KeyDownEvent>() {
+        let recording_keystrokes =
+            self.window.focus.is_some() && self.window.focus == self.window.keystroke_recorder;
+        if let Some(key_down_event) = event
+            .downcast_ref::<KeyDownEvent>()
+            .filter(|_| !recording_keystrokes)
+        {
             let KeymatchResult {
                 bindings,
                 mut pending,
@@ -1274,13 +1465,15 @@ impl<'a> WindowContext<'a> {
 
                 // for vim compatibility, we also should check "is input handler enabled"
                 if !currently_pending.is_noop() {
+                    let timeout = self.app.keystroke_timeout;
                     currently_pending.timer = Some(self.spawn(|mut cx| async move {
-                        cx.background_executor.timer(Duration::from_secs(1)).await;
+                        cx.background_executor.timer(timeout).await;
                         cx.update(move |cx| {
                             cx.clear_pending_keystrokes();
                             let Some(currently_pending) = cx.window.pending_input.take() else {
                                 return;
                             };
+                            cx.refresh();
                             cx.replay_pending_input(currently_pending)
                         })
                         .log_err();
@@ -1289,10 +1482,12 @@ impl<'a> WindowContext<'a> {
                     currently_pending.timer = None;
                 }
                 self.window.pending_input = Some(currently_pending);
+                self.refresh();
 
                 self.propagate_event = false;
                 return;
             } else if let Some(currently_pending) = self.window.pending_input.take() {
+                self.refresh();
                 if bindings
                     .iter()
                     .all(|binding| !currently_pending.used_by_binding(binding))
@@ -1346,6 +1541,14 @@ impl<'a> WindowContext<'a> {
         self.dispatch_keystroke_observers(event, None);
     }
 
+    /// While the given focus handle is focused, deliver key events straight to the key listeners
+    /// of its element, without matching them against key bindings. This lets an element record
+    /// keystrokes that are bound to actions. Pass `None` to stop.
+    pub fn record_keystrokes(&mut self, focus_handle: Option<&FocusHandle>) {
+        self.window.keystroke_recorder = focus_handle.map(|handle| handle.id);
+        self.clear_pending_keystrokes();
+    }
+
     /// Determine whether a potential multi-stroke key binding is in progress on this window.
     pub fn has_pending_keystrokes(&self) -> bool {
         self.window
@@ -1522,15 +1725,71 @@ impl<'a> WindowContext<'a> {
     ) -> Vec<KeyBinding> {
         let dispatch_tree = &self.window.rendered_frame.dispatch_tree;
 
+        if dispatch_tree.focusable_node_id(focus_handle.id).is_none() {
+            return vec![];
+        }
+        dispatch_tree.bindings_for_action(action, &self.context_stack_in(focus_handle))
+    }
+
+    /// Returns the key contexts along the dispatch path to the given focus handle, from the
+    /// root of the window to the focused element.
+    pub fn context_stack_in(&self, focus_handle: &FocusHandle) -> Vec<KeyContext> {
+        let dispatch_tree = &self.window.rendered_frame.dispatch_tree;
+
         let Some(node_id) = dispatch_tree.focusable_node_id(focus_handle.id) else {
             return vec![];
         };
+        dispatch_tree
+            .dispatch_path(node_id)
+            .into_iter()
+            .filter_map(|node_id| dispatch_tree.node(node_id).context.clone())
+            .collect()
+    }
+
+    /// Returns the keystrokes typed so far towards a multi-stroke binding, if any.
+    pub fn pending_keystrokes(&self) -> Option<&[Keystroke]> {
+        self.window
+            .pending_input
+            .as_ref()
+            .map(|pending_input| pending_input.keystrokes.as_slice())
+            .filter(|keystrokes| !keystrokes.is_empty())
+    }
+
+    /// Returns the bindings that the pending keystrokes could still go on to complete on the
+    /// currently focused element. Where several bindings share the same keystrokes, only the
+    /// one that would be dispatched is returned.
+    pub fn pending_bindings(&self) -> Vec<KeyBinding> {
+        let Some(pending_keystrokes) = self.pending_keystrokes() else {
+            return Vec::new();
+        };
+        let dispatch_tree = &self.window.rendered_frame.dispatch_tree;
+        let node_id = self
+            .window
+            .focus
+            .and_then(|focus_id| dispatch_tree.focusable_node_id(focus_id))
+            .unwrap_or_else(|| dispatch_tree.root_node_id());
         let context_stack = dispatch_tree
             .dispatch_path(node_id)
             .into_iter()
             .filter_map(|node_id| dispatch_tree.node(node_id).context.clone())
-            .collect();
-        dispatch_tree.bindings_for_action(action, &context_stack)
+            .collect::<Vec<_>>();
+
+        // Deeper contexts take precedence, followed by bindings that were added later.
+        let keymap = self.app.keymap.borrow();
+        let mut bindings: Vec<KeyBinding> = Vec::new();
+        for depth in (0..context_stack.len()).rev() {
+            for binding in keymap.bindings().rev() {
+                if binding.match_keystrokes(pending_keystrokes) == KeyMatch::Pending
+                    && keymap.binding_enabled(binding, &context_stack[0..=depth])
+                    && !bindings
+                        .iter()
+                        .any(|existing| existing.keystrokes() == binding.keystrokes())
+                {
+                    bindings.push(binding.clone());
+                }
+            }
+        }
+        bindings
     }
 
     /// Returns a generic event listener that invokes the given listener with the view and context associated with the given view handle.
@@ -2044,6 +2303,21 @@ impl<'a, V: 'static> ViewContext<'a, V> {
         subscription
     }
 
+    /// Register a callback to be invoked when the window's appearance changes, for
+    /// example when the operating system switches between light and dark mode.
+    pub fn observe_window_appearance(
+        &mut self,
+        mut callback: impl FnMut(&mut V, &mut ViewContext<V>) + 'static,
+    ) -> Subscription {
+        let view = self.view.downgrade();
+        let (subscription, activate) = self.window.appearance_observers.insert(
+            (),
+            Box::new(move |cx| view.update(cx, |view, cx| callback(view, cx)).is_ok()),
+        );
+        activate();
+        subscription
+    }
+
     /// Register a callback to be invoked when the window is activated or deactivated.
     pub fn observe_window_activation(
         &mut self,
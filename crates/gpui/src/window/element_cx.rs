@@ -28,11 +28,12 @@ use smallvec::SmallVec;
 use util::post_inc;
 
 use crate::{
-    prelude::*, size, AnyTooltip, AppContext, AvailableSpace, Bounds, BoxShadow, ContentMask,
-    Corners, CursorStyle, DevicePixels, DispatchPhase, DispatchTree, ElementId, ElementStateBox,
-    EntityId, FocusHandle, FocusId, FontId, GlobalElementId, GlyphId, Hsla, ImageData,
-    InputHandler, IsZero, KeyContext, KeyEvent, KeymatchMode, LayoutId, MonochromeSprite,
-    MouseEvent, PaintQuad, Path, Pixels, PlatformInputHandler, Point, PolychromeSprite, Quad,
+    prelude::*, size, AccessibilityProperties, AccessibilityTree, AnyTooltip, AppContext,
+    AvailableSpace, Bounds, BoxShadow, ContentMask, Corners, CursorStyle, DevicePixels,
+    DispatchPhase, DispatchTree, ElementId, ElementStateBox, EntityId, FocusHandle, FocusId,
+    FontId, GlobalElementId, GlyphId, Hsla, ImageData, InputHandler, InteractiveBounds, IsZero,
+    KeyContext, KeyEvent, KeymatchMode, LayoutId, MonochromeSprite, MouseDownEvent, MouseEvent,
+    PaintQuad, Path, Pixels, PlatformInputHandler, Point, PolychromeSprite, Quad,
     RenderGlyphParams, RenderImageParams, RenderSvgParams, Scene, Shadow, SharedString, Size,
     StackingContext, StackingOrder, Style, Surface, TextStyleRefinement, Underline, UnderlineStyle,
     Window, WindowContext, SUBPIXEL_VARIANTS,
@@ -69,6 +70,7 @@ pub(crate) struct Frame {
     pub(crate) requested_cursor_style: Option<CursorStyle>,
     pub(crate) view_stack: Vec<EntityId>,
     pub(crate) reused_views: FxHashSet<EntityId>,
+    pub(crate) accessibility_tree: AccessibilityTree,
 
     #[cfg(any(test, feature = "test-support"))]
     pub(crate) debug_bounds: collections::FxHashMap<String, Bounds<Pixels>>,
@@ -95,6 +97,7 @@ impl Frame {
             requested_cursor_style: None,
             view_stack: Vec::new(),
             reused_views: FxHashSet::default(),
+            accessibility_tree: AccessibilityTree::default(),
 
             #[cfg(any(test, feature = "test-support"))]
             debug_bounds: FxHashMap::default(),
@@ -114,6 +117,7 @@ impl Frame {
         self.tooltip_request.take();
         self.cursor_styles.clear();
         self.requested_cursor_style.take();
+        self.accessibility_tree.clear();
         debug_assert_eq!(self.view_stack.len(), 0);
     }
 
@@ -317,6 +321,13 @@ impl<'a> ElementContext<'a> {
             .next_frame
             .dispatch_tree
             .reuse_view(view_id, &mut self.cx.window.rendered_frame.dispatch_tree);
+
+        // Reuse the accessibility nodes previously added during painting of the reused views.
+        self.cx.window.next_frame.accessibility_tree.reuse_views(
+            &grafted_view_ids,
+            &self.cx.window.rendered_frame.accessibility_tree,
+        );
+
         for view_id in grafted_view_ids {
             assert!(self.window.next_frame.reused_views.insert(view_id));
 
@@ -437,6 +448,34 @@ impl<'a> ElementContext<'a> {
         }
     }
 
+    /// Add a node describing the element being painted to the window's accessibility tree,
+    /// and invoke the given function with it on the stack, so that the nodes added while
+    /// painting the element's children become its children in the tree.
+    pub fn with_accessibility_node<R>(
+        &mut self,
+        properties: Option<AccessibilityProperties>,
+        bounds: Bounds<Pixels>,
+        focus_handle: Option<&FocusHandle>,
+        f: impl FnOnce(&mut Self) -> R,
+    ) -> R {
+        let Some(properties) = properties else {
+            return f(self);
+        };
+
+        let view_id = self.parent_view_id();
+        let window = &mut *self.cx.window;
+        window.next_frame.accessibility_tree.push_node(
+            view_id,
+            &window.element_id_stack,
+            bounds,
+            properties,
+            focus_handle.map(|focus_handle| focus_handle.id),
+        );
+        let result = f(self);
+        self.window.next_frame.accessibility_tree.pop_node();
+        result
+    }
+
     /// Invoke the given function with the content mask reset to that
     /// of the window.
     pub fn break_content_mask<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
@@ -1177,6 +1216,26 @@ impl<'a> ElementContext<'a> {
             ))
     }
 
+    /// Make mouse bindings for the current key context apply when a mouse button is pressed
+    /// within the given bounds. Elements that push a key context with
+    /// [`Self::with_key_dispatch`] should call this with their bounds.
+    pub fn register_mouse_binding_target(&mut self, bounds: Bounds<Pixels>) {
+        let target = self.window.next_frame.dispatch_tree.mouse_binding_target();
+        let interactive_bounds = InteractiveBounds {
+            bounds: bounds.intersect(&self.content_mask().bounds),
+            stacking_order: self.stacking_order().clone(),
+        };
+        self.on_mouse_event(move |event: &MouseDownEvent, phase, cx| {
+            // Capture listeners run from back to front, so the innermost element under the
+            // mouse is the last one to set itself as the target.
+            if phase == DispatchPhase::Capture
+                && interactive_bounds.visibly_contains(&event.position, cx)
+            {
+                cx.window.mouse_binding_target = Some(target.clone());
+            }
+        });
+    }
+
     /// Register a key event listener on the window for the next frame. The type of event
     /// is determined by the first parameter of the given listener. When the next frame is rendered
     /// the listener will be cleared.
@@ -0,0 +1,96 @@
+use std::{collections::VecDeque, time::Duration};
+
+/// How long it took to draw a single frame, broken down by phase.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FrameTiming {
+    /// Time spent rendering views and laying out their elements.
+    pub layout: Duration,
+    /// Time spent painting elements into the scene.
+    pub paint: Duration,
+    /// Time spent on the whole frame, including overlays, dispatch and bookkeeping.
+    pub total: Duration,
+}
+
+/// The timings of the frames a window has drawn most recently.
+#[derive(Clone, Debug, Default)]
+pub struct FrameTimings {
+    frames: VecDeque<FrameTiming>,
+}
+
+impl FrameTimings {
+    /// The number of frames whose timings are kept.
+    pub const CAPACITY: usize = 120;
+
+    pub(crate) fn push(&mut self, timing: FrameTiming) {
+        if self.frames.len() == Self::CAPACITY {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(timing);
+    }
+
+    /// The number of frames recorded, up to [`Self::CAPACITY`].
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Whether no frames have been drawn yet.
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The timing of the most recently drawn frame.
+    pub fn last(&self) -> Option<FrameTiming> {
+        self.frames.back().copied()
+    }
+
+    /// The average time spent on each phase over the recorded frames.
+    pub fn average(&self) -> FrameTiming {
+        let mut sum = FrameTiming::default();
+        for frame in &self.frames {
+            sum.layout += frame.layout;
+            sum.paint += frame.paint;
+            sum.total += frame.total;
+        }
+        let count = self.frames.len().max(1) as u32;
+        FrameTiming {
+            layout: sum.layout / count,
+            paint: sum.paint / count,
+            total: sum.total / count,
+        }
+    }
+
+    /// The recorded frame that took the longest to draw.
+    pub fn slowest(&self) -> Option<FrameTiming> {
+        self.frames.iter().copied().max_by_key(|frame| frame.total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_timings() {
+        let mut timings = FrameTimings::default();
+        assert_eq!(timings.average(), FrameTiming::default());
+        assert_eq!(timings.slowest(), None);
+
+        for millis in 0..FrameTimings::CAPACITY as u64 + 10 {
+            timings.push(FrameTiming {
+                layout: Duration::from_millis(millis),
+                paint: Duration::from_millis(1),
+                total: Duration::from_millis(millis + 2),
+            });
+        }
+
+        // The oldest frames are dropped once the capacity is reached.
+        assert_eq!(timings.len(), FrameTimings::CAPACITY);
+        assert_eq!(timings.average().layout, Duration::from_micros(69_500));
+        assert_eq!(timings.average().paint, Duration::from_millis(1));
+        assert_eq!(
+            timings.slowest().map(|frame| frame.total),
+            Some(Duration::from_millis(131))
+        );
+        assert_eq!(timings.last(), timings.slowest());
+    }
+}
@@ -0,0 +1,81 @@
+use editor::Editor;
+use gpui::{div, IntoElement, ParentElement, Render, Subscription, View, ViewContext, WeakView};
+use ui::{Button, ButtonCommon, Clickable, FluentBuilder, LabelSize, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView, Workspace};
+
+use crate::{indent_size_label, IndentationSelector};
+
+pub struct ActiveBufferIndentation {
+    active_indentation: Option<String>,
+    workspace: WeakView<Workspace>,
+    _observe_active_editor: Option<Subscription>,
+    _observe_active_buffer: Option<Subscription>,
+}
+
+impl ActiveBufferIndentation {
+    pub fn new(workspace: &Workspace) -> Self {
+        Self {
+            active_indentation: None,
+            workspace: workspace.weak_handle(),
+            _observe_active_editor: None,
+            _observe_active_buffer: None,
+        }
+    }
+
+    fn update_indentation(&mut self, editor: View<Editor>, cx: &mut ViewContext<Self>) {
+        self.active_indentation = None;
+        self._observe_active_buffer = None;
+
+        if let Some((_, buffer, _)) = editor.read(cx).active_excerpt(cx) {
+            let indent_size = buffer.read(cx).snapshot().language_indent_size_at(0, cx);
+            self.active_indentation = Some(indent_size_label(indent_size));
+            self._observe_active_buffer = Some(cx.observe(&buffer, move |this, _, cx| {
+                this.update_indentation(editor.clone(), cx)
+            }));
+        }
+
+        cx.notify();
+    }
+}
+
+impl Render for ActiveBufferIndentation {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div().when_some(self.active_indentation.clone(), |el, active_indentation| {
+            el.child(
+                Button::new("change-indentation", active_indentation)
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, cx| {
+                        if let Some(workspace) = this.workspace.upgrade() {
+                            workspace.update(cx, |workspace, cx| {
+                                IndentationSelector::toggle(workspace, cx)
+                            });
+                        }
+                    }))
+                    .tooltip(|cx| Tooltip::text("Select Indentation", cx)),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveBufferIndentation {
+    fn status_item_id(&self) -> &'static str {
+        "indentation"
+    }
+
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.act_as::<Editor>(cx)) {
+            self._observe_active_editor = Some(cx.observe(&editor, Self::update_indentation));
+            self.update_indentation(editor, cx);
+        } else {
+            self.active_indentation = None;
+            self._observe_active_editor = None;
+            self._observe_active_buffer = None;
+        }
+
+        cx.notify();
+    }
+}
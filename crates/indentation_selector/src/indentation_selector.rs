@@ -0,0 +1,214 @@
+mod active_buffer_indentation;
+
+pub use active_buffer_indentation::ActiveBufferIndentation;
+use editor::Editor;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Model,
+    ParentElement, Render, Styled, View, ViewContext, VisualContext, WeakView,
+};
+use language::{Buffer, IndentKind, IndentSize};
+use picker::{Picker, PickerDelegate};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+actions!(indentation_selector, [Toggle]);
+
+/// The widest indentation with spaces that can be chosen.
+const MAX_SPACES: u32 = 8;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(IndentationSelector::register).detach();
+}
+
+pub struct IndentationSelector {
+    picker: View<Picker<IndentationSelectorDelegate>>,
+}
+
+impl IndentationSelector {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(move |workspace, _: &Toggle, cx| {
+            Self::toggle(workspace, cx);
+        });
+    }
+
+    fn toggle(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> Option<()> {
+        let (_, buffer, _) = workspace
+            .active_item(cx)?
+            .act_as::<Editor>(cx)?
+            .read(cx)
+            .active_excerpt(cx)?;
+        workspace.toggle_modal(cx, move |cx| IndentationSelector::new(buffer, cx));
+        Some(())
+    }
+
+    fn new(buffer: Model<Buffer>, cx: &mut ViewContext<Self>) -> Self {
+        let delegate = IndentationSelectorDelegate::new(cx.view().downgrade(), buffer);
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for IndentationSelector {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for IndentationSelector {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for IndentationSelector {}
+impl ModalView for IndentationSelector {}
+
+/// Describes a unit of indentation, such as "Spaces: 4".
+pub fn indent_size_label(indent_size: IndentSize) -> String {
+    match indent_size.kind {
+        IndentKind::Space => format!("Spaces: {}", indent_size.len),
+        IndentKind::Tab => "Tabs".to_string(),
+    }
+}
+
+pub struct IndentationSelectorDelegate {
+    indentation_selector: WeakView<IndentationSelector>,
+    buffer: Model<Buffer>,
+    /// The indentations to choose from, followed by `None` for following the
+    /// language settings.
+    indent_sizes: Vec<Option<IndentSize>>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl IndentationSelectorDelegate {
+    fn new(indentation_selector: WeakView<IndentationSelector>, buffer: Model<Buffer>) -> Self {
+        let indent_sizes = [Some(IndentSize::tab())]
+            .into_iter()
+            .chain((1..=MAX_SPACES).map(|len| Some(IndentSize::spaces(len))))
+            .chain([None])
+            .collect::<Vec<_>>();
+        let candidates = indent_sizes
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, indent_size)| {
+                let label = indent_size
+                    .map_or_else(|| "Use Language Settings".to_string(), indent_size_label);
+                StringMatchCandidate::new(candidate_id, label)
+            })
+            .collect();
+
+        Self {
+            indentation_selector,
+            buffer,
+            indent_sizes,
+            candidates,
+            matches: vec![],
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for IndentationSelectorDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Select indentation...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let indent_size = self.indent_sizes[mat.candidate_id];
+            self.buffer.update(cx, |buffer, cx| {
+                buffer.set_indent_size(indent_size, cx);
+            });
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.indentation_selector
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let mut label = mat.string.clone();
+        if self.indent_sizes[mat.candidate_id] == self.buffer.read(cx).indent_size() {
+            label.push_str(" (current)");
+        }
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(label, mat.positions.clone())),
+        )
+    }
+}
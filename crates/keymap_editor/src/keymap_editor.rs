@@ -0,0 +1,675 @@
+use std::sync::Arc;
+
+use collections::{HashMap, HashSet};
+use editor::{Editor, EditorEvent};
+use fs::Fs;
+use gpui::{
+    actions, div, uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, KeyDownEvent, KeymapVersion, Keystroke, NoAction,
+    ParentElement, Render, SharedString, Styled, Subscription, UniformListScrollHandle, View,
+    ViewContext, VisualContext, WindowContext,
+};
+use settings::update_binding_in_json_text;
+use ui::{prelude::*, KeyBinding, ListItem, ListItemSpacing};
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace, WorkspaceId,
+};
+
+actions!(keymap_editor, [Deploy, RemoveBinding]);
+
+/// The indentation of the blocks the keymap editor adds to `keymap.json`.
+const KEYMAP_TAB_SIZE: usize = 2;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &Deploy, cx| {
+            if let Some(existing) = workspace.item_of_type::<KeymapEditor>(cx) {
+                workspace.activate_item(&existing, cx);
+            } else {
+                let fs = workspace.app_state().fs.clone();
+                let keymap_editor = cx.new_view(|cx| KeymapEditor::new(fs, cx));
+                workspace.add_item(Box::new(keymap_editor), cx);
+            }
+        });
+    })
+    .detach();
+}
+
+/// A row of the keymap editor: an action, with one of its bindings if it has any.
+#[derive(Clone, Debug, PartialEq)]
+struct KeymapEntry {
+    action_name: SharedString,
+    keystrokes: Vec<Keystroke>,
+    context: Option<SharedString>,
+    /// Whether the action can be bound by name alone. Actions that take arguments
+    /// have to be bound in `keymap.json`.
+    bindable: bool,
+}
+
+impl KeymapEntry {
+    fn keystrokes_text(&self) -> String {
+        unparse_keystrokes(&self.keystrokes)
+    }
+
+    fn matches_query(&self, query: &str) -> bool {
+        let keystrokes = self.keystrokes_text();
+        query.split_whitespace().all(|term| {
+            self.action_name.to_lowercase().contains(term)
+                || keystrokes.contains(term)
+                || self
+                    .context
+                    .as_ref()
+                    .map_or(false, |context| context.to_lowercase().contains(term))
+        })
+    }
+}
+
+fn unparse_keystrokes(keystrokes: &[Keystroke]) -> String {
+    keystrokes
+        .iter()
+        .map(Keystroke::unparse)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Lists every registered action, once per binding, with the actions that have no bindings
+/// listed once without keystrokes.
+fn keymap_entries(cx: &AppContext) -> Vec<KeymapEntry> {
+    let keymap = cx.key_bindings();
+    let keymap = keymap.borrow();
+    let mut bindings_by_action = HashMap::<&str, Vec<_>>::default();
+    for binding in keymap
+        .bindings()
+        .filter(|binding| !keymap.binding_disabled(binding))
+    {
+        bindings_by_action
+            .entry(binding.action().name())
+            .or_default()
+            .push(binding);
+    }
+
+    let mut action_names = cx.all_action_names().to_vec();
+    action_names.sort();
+    let no_action_name = NoAction.name();
+
+    let mut entries = Vec::new();
+    for action_name in action_names {
+        let name: &str = &action_name;
+        if name == no_action_name {
+            continue;
+        }
+        let bindable = cx.build_action(name, None).is_ok();
+        let mut seen = HashSet::default();
+        let bindings = bindings_by_action
+            .get(name)
+            .map_or(&[] as &[_], Vec::as_slice);
+        for binding in bindings {
+            let context = binding
+                .predicate()
+                .map(|predicate| SharedString::from(predicate.to_string()));
+            if seen.insert((binding.keystrokes().to_vec(), context.clone())) {
+                entries.push(KeymapEntry {
+                    action_name: action_name.clone(),
+                    keystrokes: binding.keystrokes().to_vec(),
+                    context,
+                    bindable,
+                });
+            }
+        }
+        if bindings.is_empty() {
+            entries.push(KeymapEntry {
+                action_name,
+                keystrokes: Vec::new(),
+                context: None,
+                bindable,
+            });
+        }
+    }
+    entries
+}
+
+/// Returns the entries whose bindings would clash with binding `keystrokes` in `context`:
+/// those with the same keystrokes, or with keystrokes that start with them or that they start
+/// with, in the same context or in any context when either binding has none.
+fn conflicting_entries(
+    entries: &[KeymapEntry],
+    keystrokes: &[Keystroke],
+    context: Option<&str>,
+    except: usize,
+) -> Vec<usize> {
+    if keystrokes.is_empty() {
+        return Vec::new();
+    }
+    entries
+        .iter()
+        .enumerate()
+        .filter(|(ix, entry)| {
+            *ix != except
+                && !entry.keystrokes.is_empty()
+                && (entry.keystrokes.starts_with(keystrokes)
+                    || keystrokes.starts_with(&entry.keystrokes))
+                && (context.is_none()
+                    || entry.context.is_none()
+                    || entry.context.as_deref() == context)
+        })
+        .map(|(ix, _)| ix)
+        .collect()
+}
+
+/// Keystrokes being recorded for an entry, until they are confirmed with enter
+/// or discarded with escape.
+struct Recording {
+    entry_ix: usize,
+    keystrokes: Vec<Keystroke>,
+    focus_handle: FocusHandle,
+    _blur_subscription: Subscription,
+}
+
+/// Lists every action with its key bindings, and records new bindings for them into the
+/// user's `keymap.json`.
+pub struct KeymapEditor {
+    focus_handle: FocusHandle,
+    fs: Arc<dyn Fs>,
+    filter_editor: View<Editor>,
+    entries: Vec<KeymapEntry>,
+    matches: Vec<usize>,
+    selected_index: usize,
+    keymap_version: Option<KeymapVersion>,
+    recording: Option<Recording>,
+    error: Option<SharedString>,
+    scroll_handle: UniformListScrollHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl KeymapEditor {
+    fn new(fs: Arc<dyn Fs>, cx: &mut ViewContext<Self>) -> Self {
+        let filter_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter actions, keystrokes and contexts...", cx);
+            editor
+        });
+        let subscriptions = vec![cx.subscribe(&filter_editor, |this, _, event, cx| {
+            if let EditorEvent::BufferEdited = event {
+                this.update_matches(cx);
+            }
+        })];
+
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            fs,
+            filter_editor,
+            entries: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+            keymap_version: None,
+            recording: None,
+            error: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            _subscriptions: subscriptions,
+        };
+        this.refresh_entries(cx);
+        this
+    }
+
+    fn refresh_entries(&mut self, cx: &mut ViewContext<Self>) {
+        let version = cx.key_bindings().borrow().version();
+        if self.keymap_version == Some(version) {
+            return;
+        }
+        self.keymap_version = Some(version);
+        self.entries = keymap_entries(cx);
+        self.update_matches(cx);
+    }
+
+    fn update_matches(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.filter_editor.read(cx).text(cx).to_lowercase();
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.matches_query(&query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+        cx.notify();
+    }
+
+    fn select_index(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_index = ix;
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, cx: &mut ViewContext<Self>) {
+        if !self.matches.is_empty() {
+            self.select_index((self.selected_index + 1) % self.matches.len(), cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &menu::SelectPrev, cx: &mut ViewContext<Self>) {
+        if !self.matches.is_empty() {
+            let count = self.matches.len();
+            self.select_index((self.selected_index + count - 1) % count, cx);
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(&entry_ix) = self.matches.get(self.selected_index) {
+            self.start_recording(entry_ix, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        if self.error.take().is_some() {
+            cx.notify();
+        } else {
+            cx.propagate();
+        }
+    }
+
+    fn start_recording(&mut self, entry_ix: usize, cx: &mut ViewContext<Self>) {
+        let entry = &self.entries[entry_ix];
+        if !entry.bindable {
+            self.error = Some(
+                format!(
+                    "{} takes arguments, so it has to be bound in keymap.json",
+                    entry.action_name
+                )
+                .into(),
+            );
+            cx.notify();
+            return;
+        }
+
+        let focus_handle = cx.focus_handle();
+        let blur_subscription = cx.on_blur(&focus_handle, |this, cx| {
+            this.stop_recording(cx);
+        });
+        cx.focus(&focus_handle);
+        cx.record_keystrokes(Some(&focus_handle));
+        self.error = None;
+        self.recording = Some(Recording {
+            entry_ix,
+            keystrokes: Vec::new(),
+            focus_handle,
+            _blur_subscription: blur_subscription,
+        });
+        cx.notify();
+    }
+
+    fn stop_recording(&mut self, cx: &mut ViewContext<Self>) -> Option<Recording> {
+        let recording = self.recording.take()?;
+        cx.record_keystrokes(None);
+        if recording.focus_handle.is_focused(cx) {
+            self.filter_editor.focus_handle(cx).focus(cx);
+        }
+        cx.notify();
+        Some(recording)
+    }
+
+    fn handle_recorded_key_down(&mut self, event: &KeyDownEvent, cx: &mut ViewContext<Self>) {
+        cx.stop_propagation();
+        let Some(recording) = self.recording.as_mut() else {
+            return;
+        };
+        let keystroke = Keystroke {
+            ime_key: None,
+            ..event.keystroke.clone()
+        };
+        let unmodified = keystroke.modifiers == Default::default();
+        match keystroke.key.as_str() {
+            "enter" if unmodified && !recording.keystrokes.is_empty() => self.save_recording(cx),
+            "escape" if unmodified => {
+                self.stop_recording(cx);
+            }
+            _ => {
+                recording.keystrokes.push(keystroke);
+                cx.notify();
+            }
+        }
+    }
+
+    fn save_recording(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(recording) = self.stop_recording(cx) else {
+            return;
+        };
+        let entry = self.entries[recording.entry_ix].clone();
+        let new_keystrokes = unparse_keystrokes(&recording.keystrokes);
+        let old_keystrokes = entry.keystrokes_text();
+        self.update_keymap_file(cx, move |text| {
+            let context = entry.context.as_deref();
+            let mut text = text;
+            if !old_keystrokes.is_empty() && old_keystrokes != new_keystrokes {
+                text = update_binding_in_json_text(
+                    &text,
+                    context,
+                    &old_keystrokes,
+                    None,
+                    KEYMAP_TAB_SIZE,
+                )?;
+            }
+            update_binding_in_json_text(
+                &text,
+                context,
+                &new_keystrokes,
+                Some(&entry.action_name),
+                KEYMAP_TAB_SIZE,
+            )
+        });
+    }
+
+    fn remove_binding(&mut self, _: &RemoveBinding, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .map(|ix| self.entries[*ix].clone())
+        else {
+            return;
+        };
+        if entry.keystrokes.is_empty() {
+            return;
+        }
+        self.update_keymap_file(cx, move |text| {
+            update_binding_in_json_text(
+                &text,
+                entry.context.as_deref(),
+                &entry.keystrokes_text(),
+                None,
+                KEYMAP_TAB_SIZE,
+            )
+        });
+    }
+
+    fn update_keymap_file(
+        &mut self,
+        cx: &mut ViewContext<Self>,
+        update: impl 'static + Send + FnOnce(String) -> anyhow::Result<String>,
+    ) {
+        let task = settings::update_keymap_file(self.fs.clone(), cx, update);
+        cx.spawn(|this, mut cx| async move {
+            if let Err(error) = task.await {
+                this.update(&mut cx, |this, cx| {
+                    this.error = Some(format!("Failed to update keymap.json: {error}").into());
+                    cx.notify();
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_keystrokes(keystrokes: &[Keystroke]) -> Option<KeyBinding> {
+        let keystrokes = unparse_keystrokes(keystrokes);
+        let binding = gpui::KeyBinding::load(&keystrokes, NoAction.boxed_clone(), None).ok()?;
+        Some(KeyBinding::new(binding))
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut ViewContext<Self>) -> ListItem {
+        let entry = &self.entries[self.matches[ix]];
+        let keystrokes = match &self.recording {
+            Some(recording) if recording.entry_ix == self.matches[ix] => div()
+                .track_focus(&recording.focus_handle)
+                .on_key_down(cx.listener(Self::handle_recorded_key_down))
+                .child(if recording.keystrokes.is_empty() {
+                    Label::new("Press keys, then enter to save or escape to cancel")
+                        .color(Color::Accent)
+                        .into_any_element()
+                } else {
+                    div()
+                        .children(Self::render_keystrokes(&recording.keystrokes))
+                        .into_any_element()
+                })
+                .into_any_element(),
+            _ => div()
+                .children(Self::render_keystrokes(&entry.keystrokes))
+                .into_any_element(),
+        };
+
+        ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(ix == self.selected_index)
+            .on_click(cx.listener(move |this, _, cx| {
+                this.select_index(ix, cx);
+                if let Some(&entry_ix) = this.matches.get(ix) {
+                    this.start_recording(entry_ix, cx);
+                }
+            }))
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_4()
+                    .child(div().w_2_5().child(Label::new(entry.action_name.clone())))
+                    .child(div().w_1_4().child(keystrokes))
+                    .child(div().flex_1().child(
+                        Label::new(entry.context.clone().unwrap_or_default()).color(Color::Muted),
+                    )),
+            )
+    }
+
+    fn render_conflicts(&self) -> Option<AnyElement> {
+        let recording = self.recording.as_ref()?;
+        let entry = &self.entries[recording.entry_ix];
+        let conflicts = conflicting_entries(
+            &self.entries,
+            &recording.keystrokes,
+            entry.context.as_deref(),
+            recording.entry_ix,
+        );
+        if conflicts.is_empty() {
+            return None;
+        }
+        Some(
+            v_flex()
+                .px_4()
+                .py_2()
+                .children(conflicts.into_iter().map(|ix| {
+                    let conflict = &self.entries[ix];
+                    let description = match &conflict.context {
+                        Some(context) => format!(
+                            "{} is bound to {} in {}",
+                            conflict.keystrokes_text(),
+                            conflict.action_name,
+                            context
+                        ),
+                        None => format!(
+                            "{} is bound to {}",
+                            conflict.keystrokes_text(),
+                            conflict.action_name
+                        ),
+                    };
+                    Label::new(description).color(Color::Warning)
+                }))
+                .into_any_element(),
+        )
+    }
+}
+
+impl Render for KeymapEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        self.refresh_entries(cx);
+
+        v_flex()
+            .key_context("KeymapEditor")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::remove_binding))
+            .child(
+                h_flex()
+                    .h_9()
+                    .px_4()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.filter_editor.clone()),
+            )
+            .children(self.error.clone().map(|error| {
+                div()
+                    .px_4()
+                    .py_2()
+                    .child(Label::new(error).color(Color::Error))
+            }))
+            .children(self.render_conflicts())
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "keymap-entries",
+                    self.matches.len(),
+                    |this, range, cx| range.map(|ix| this.render_entry(ix, cx)).collect(),
+                )
+                .track_scroll(self.scroll_handle.clone())
+                .flex_grow(),
+            )
+    }
+}
+
+impl FocusableView for KeymapEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        match &self.recording {
+            Some(recording) => recording.focus_handle.clone(),
+            None => self.filter_editor.focus_handle(cx),
+        }
+    }
+}
+
+impl EventEmitter<ItemEvent> for KeymapEditor {}
+
+impl Item for KeymapEditor {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        Label::new("Key Bindings")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("keymap editor")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        let fs = self.fs.clone();
+        Some(cx.new_view(|cx| KeymapEditor::new(fs, cx)))
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{actions, KeyBinding};
+
+    actions!(keymap_editor_test, [Save, Close, Quit]);
+
+    fn entry(
+        action_name: &'static str,
+        keystrokes: &str,
+        context: Option<&'static str>,
+    ) -> KeymapEntry {
+        KeymapEntry {
+            action_name: action_name.into(),
+            keystrokes: keystrokes
+                .split_whitespace()
+                .map(|keystroke| Keystroke::parse(keystroke).unwrap())
+                .collect(),
+            context: context.map(Into::into),
+            bindable: true,
+        }
+    }
+
+    #[gpui::test]
+    fn test_keymap_entries(cx: &mut AppContext) {
+        cx.bind_keys([
+            KeyBinding::new("cmd-s", Save, Some("Workspace")),
+            KeyBinding::new("ctrl-x ctrl-s", Save, Some("Workspace")),
+            KeyBinding::new("cmd-w", Close, Some("Pane && !(mode == modal)")),
+            KeyBinding::new("cmd-w", Close, Some("Pane && !(mode == modal)")),
+            KeyBinding::new("cmd-q", Quit, None),
+            KeyBinding::new("cmd-q", NoAction, None),
+        ]);
+
+        // Duplicate and disabled bindings are left out.
+        let entries = keymap_entries(cx)
+            .into_iter()
+            .filter(|entry| entry.action_name.starts_with("keymap_editor_test::"))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entries,
+            [
+                entry(
+                    "keymap_editor_test::Close",
+                    "cmd-w",
+                    Some("Pane && !(mode == modal)")
+                ),
+                entry("keymap_editor_test::Quit", "", None),
+                entry("keymap_editor_test::Save", "cmd-s", Some("Workspace")),
+                entry(
+                    "keymap_editor_test::Save",
+                    "ctrl-x ctrl-s",
+                    Some("Workspace")
+                ),
+            ]
+        );
+        assert!(entries[1].matches_query("quit"));
+        assert!(entries[3].matches_query("workspace ctrl-x"));
+        assert!(!entries[3].matches_query("pane"));
+    }
+
+    #[test]
+    fn test_conflicting_entries() {
+        let entries = [
+            entry("editor::Cut", "ctrl-x", Some("Editor")),
+            entry("workspace::Save", "ctrl-x ctrl-s", Some("Workspace")),
+            entry("zed::Quit", "ctrl-x ctrl-c", None),
+            entry("editor::Copy", "ctrl-c", Some("Editor")),
+            entry("editor::Paste", "", None),
+        ];
+        let keystrokes = |source: &str| {
+            source
+                .split_whitespace()
+                .map(|keystroke| Keystroke::parse(keystroke).unwrap())
+                .collect::<Vec<_>>()
+        };
+
+        // Prefixes clash in either direction, in the same context or without one.
+        assert_eq!(
+            conflicting_entries(&entries, &keystrokes("ctrl-x ctrl-f"), Some("Editor"), 4),
+            [0]
+        );
+        assert_eq!(
+            conflicting_entries(&entries, &keystrokes("ctrl-x"), Some("Workspace"), 4),
+            [1, 2]
+        );
+        assert_eq!(
+            conflicting_entries(&entries, &keystrokes("ctrl-c"), None, 4),
+            [3]
+        );
+        // An entry doesn't conflict with its own binding.
+        assert_eq!(
+            conflicting_entries(&entries, &keystrokes("ctrl-c"), Some("Editor"), 3),
+            Vec::<usize>::new()
+        );
+    }
+}
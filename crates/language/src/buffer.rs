@@ -6,17 +6,18 @@ pub use crate::{
 };
 use crate::{
     diagnostic_set::{DiagnosticEntry, DiagnosticGroup},
-    language_settings::{language_settings, LanguageSettings},
+    language_settings::{editorconfig_sets_indentation, language_settings, LanguageSettings},
     markdown::parse_markdown,
     outline::OutlineItem,
     syntax_map::{
         SyntaxLayer, SyntaxMap, SyntaxMapCapture, SyntaxMapCaptures, SyntaxMapMatches,
         SyntaxSnapshot, ToTreeSitterPoint,
     },
-    CodeLabel, LanguageScope, Outline,
+    CodeLabel, LanguageScope, Outline, TextObject,
 };
 use anyhow::{anyhow, Context, Result};
 pub use clock::ReplicaId;
+use collections::HashSet;
 use futures::channel::oneshot;
 use gpui::{AppContext, EventEmitter, HighlightStyle, ModelContext, Task, TaskLabel};
 use lazy_static::lazy_static;
@@ -65,6 +66,10 @@ lazy_static! {
     pub static ref BUFFER_DIFF_TASK: TaskLabel = TaskLabel::new();
 }
 
+/// The size above which a buffer's root syntax layer is parsed, and highlighted, before
+/// any languages injected into it.
+const PARTIAL_PARSE_MIN_LEN: usize = 256 * 1024;
+
 /// Indicate whether a [Buffer] has permissions to edit.
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum Capability {
@@ -89,6 +94,18 @@ pub struct Buffer {
     saved_version: clock::Global,
     /// A hash of the current contents of the buffer's file.
     file_fingerprint: RopeFingerprint,
+    /// The line ending of the buffer's file when it was last loaded from
+    /// or saved to disk.
+    saved_line_ending: LineEnding,
+    /// The encoding that the buffer's file is decoded from and saved with.
+    encoding: Encoding,
+    /// The encoding of the buffer's file when it was last loaded from or
+    /// saved to disk.
+    saved_encoding: Encoding,
+    /// The indentation detected in the file's text when this buffer was
+    /// loaded, or chosen by the user, which new edits use instead of the
+    /// language settings' `hard_tabs` and `tab_size`.
+    indent_size: Option<IndentSize>,
     transaction_depth: usize,
     was_dirty_before_starting_transaction: Option<bool>,
     reload_task: Option<Task<Result<()>>>,
@@ -126,6 +143,7 @@ pub struct BufferSnapshot {
     remote_selections: TreeMap<ReplicaId, SelectionSet>,
     selections_update_count: usize,
     language: Option<Arc<Language>>,
+    indent_size: Option<IndentSize>,
     parse_count: usize,
 }
 
@@ -390,8 +408,8 @@ pub trait LocalFile: File {
     /// Returns the absolute path of this file.
     fn abs_path(&self, cx: &AppContext) -> PathBuf;
 
-    /// Loads the file's contents from disk.
-    fn load(&self, cx: &AppContext) -> Task<Result<String>>;
+    /// Loads the file's contents from disk, decoding them with the given encoding.
+    fn load(&self, encoding: Encoding, cx: &AppContext) -> Task<Result<String>>;
 
     /// Called when the buffer is reloaded from disk.
     fn buffer_reloaded(
@@ -562,6 +580,7 @@ impl Buffer {
             rpc::proto::LineEnding::from_i32(message.line_ending)
                 .ok_or_else(|| anyhow!("missing line_ending"))?,
         ));
+        this.saved_line_ending = this.line_ending();
         this.saved_version = proto::deserialize_version(&message.saved_version);
         this.file_fingerprint = proto::deserialize_fingerprint(&message.saved_version_fingerprint)?;
         this.saved_mtime = message
@@ -638,6 +657,13 @@ impl Buffer {
         self
     }
 
+    /// Assign the encoding that the buffer's file was loaded with, returning the buffer.
+    pub fn with_encoding(mut self, encoding: Encoding) -> Self {
+        self.encoding = encoding;
+        self.saved_encoding = encoding;
+        self
+    }
+
     /// Returns the [Capability] of this buffer.
     pub fn capability(&self) -> Capability {
         self.capability
@@ -661,10 +687,20 @@ impl Buffer {
             UNIX_EPOCH
         };
 
+        // Only files have existing indentation that edits should be consistent with.
+        let indent_size = file
+            .is_some()
+            .then(|| detect_indent_size(&buffer))
+            .flatten();
+
         Self {
             saved_mtime,
             saved_version: buffer.version(),
             file_fingerprint: buffer.as_rope().fingerprint(),
+            saved_line_ending: buffer.line_ending(),
+            encoding: Encoding::default(),
+            saved_encoding: Encoding::default(),
+            indent_size,
             reload_task: None,
             transaction_depth: 0,
             was_dirty_before_starting_transaction: None,
@@ -712,6 +748,7 @@ impl Buffer {
             file_update_count: self.file_update_count,
             git_diff_update_count: self.git_diff_update_count,
             language: self.language.clone(),
+            indent_size: self.indent_size,
             parse_count: self.parse_count,
             selections_update_count: self.selections_update_count,
         }
@@ -780,6 +817,8 @@ impl Buffer {
     ) {
         self.saved_version = version;
         self.file_fingerprint = fingerprint;
+        self.saved_line_ending = self.line_ending();
+        self.saved_encoding = self.encoding;
         self.saved_mtime = mtime;
         cx.emit(Event::Saved);
         cx.notify();
@@ -795,7 +834,7 @@ impl Buffer {
         self.reload_task = Some(cx.spawn(|this, mut cx| async move {
             let Some((new_mtime, new_text)) = this.update(&mut cx, |this, cx| {
                 let file = this.file.as_ref()?.as_local()?;
-                Some((file.mtime(), file.load(cx)))
+                Some((file.mtime(), file.load(this.encoding, cx)))
             })?
             else {
                 return Ok(());
@@ -834,6 +873,18 @@ impl Buffer {
         rx
     }
 
+    /// Reloads the contents of the buffer from disk, decoding them with the
+    /// given encoding, which the buffer is then saved with.
+    pub fn reload_with_encoding(
+        &mut self,
+        encoding: Encoding,
+        cx: &mut ModelContext<Self>,
+    ) -> oneshot::Receiver<Option<Transaction>> {
+        self.encoding = encoding;
+        self.saved_encoding = encoding;
+        self.reload(cx)
+    }
+
     /// This method is called to signal that the buffer has been reloaded.
     pub fn did_reload(
         &mut self,
@@ -846,6 +897,7 @@ impl Buffer {
         self.saved_version = version;
         self.file_fingerprint = fingerprint;
         self.text.set_line_ending(line_ending);
+        self.saved_line_ending = line_ending;
         self.saved_mtime = mtime;
         if let Some(file) = self.file.as_ref().and_then(|f| f.as_local()) {
             file.buffer_reloaded(
@@ -950,6 +1002,48 @@ impl Buffer {
             .or_else(|| self.language.clone())
     }
 
+    /// Returns the indentation that new edits use instead of the language
+    /// settings, if it was detected when loading the buffer or chosen since.
+    pub fn indent_size(&self) -> Option<IndentSize> {
+        self.indent_size
+    }
+
+    /// Sets the indentation that new edits use, or makes them follow the
+    /// language settings again when `None`.
+    pub fn set_indent_size(
+        &mut self,
+        indent_size: Option<IndentSize>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.indent_size = indent_size;
+        cx.notify();
+    }
+
+    /// Changes the line ending that the buffer is saved with.
+    pub fn set_line_ending(&mut self, line_ending: LineEnding, cx: &mut ModelContext<Self>) {
+        let was_dirty = self.is_dirty();
+        self.text.set_line_ending(line_ending);
+        if self.is_dirty() != was_dirty {
+            cx.emit(Event::DirtyChanged);
+        }
+        cx.notify();
+    }
+
+    /// Returns the encoding that the buffer's file is saved with.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    /// Changes the encoding that the buffer's file is saved with.
+    pub fn set_encoding(&mut self, encoding: Encoding, cx: &mut ModelContext<Self>) {
+        let was_dirty = self.is_dirty();
+        self.encoding = encoding;
+        if self.is_dirty() != was_dirty {
+            cx.emit(Event::DirtyChanged);
+        }
+        cx.notify();
+    }
+
     /// The number of times the buffer was parsed.
     pub fn parse_count(&self) -> usize {
         self.parse_count
@@ -1034,11 +1128,30 @@ impl Buffer {
         let mut syntax_snapshot = syntax_map.snapshot();
         drop(syntax_map);
 
+        // In large buffers with injected languages, parse the root layer first, so that
+        // it can be highlighted without waiting for all of the injections to be parsed.
+        let parse_root_layer_first = text.len() >= PARTIAL_PARSE_MIN_LEN
+            && language
+                .grammar()
+                .map_or(false, |grammar| grammar.injection_config.is_some());
+        let (partial_parse_tx, partial_parse_rx) = oneshot::channel();
         let parse_task = cx.background_executor().spawn({
             let language = language.clone();
             let language_registry = language_registry.clone();
             async move {
-                syntax_snapshot.reparse(&text, language_registry, language);
+                if parse_root_layer_first {
+                    let changed_ranges =
+                        syntax_snapshot.reparse_root_layer(&text, language.clone());
+                    partial_parse_tx.send(syntax_snapshot.clone()).ok();
+                    syntax_snapshot.reparse_injections(
+                        &text,
+                        language_registry,
+                        language,
+                        changed_ranges,
+                    );
+                } else {
+                    syntax_snapshot.reparse(&text, language_registry, language);
+                }
                 syntax_snapshot
             }
         });
@@ -1054,6 +1167,13 @@ impl Buffer {
             Err(parse_task) => {
                 self.parsing_in_background = true;
                 cx.spawn(move |this, mut cx| async move {
+                    if let Ok(partial_syntax_map) = partial_parse_rx.await {
+                        this.update(&mut cx, |this, cx| {
+                            this.did_finish_partial_parse(partial_syntax_map, cx)
+                        })
+                        .ok();
+                    }
+
                     let new_syntax_map = parse_task.await;
                     this.update(&mut cx, move |this, cx| {
                         let grammar_changed =
@@ -1081,6 +1201,20 @@ impl Buffer {
         }
     }
 
+    /// Shows the highlights of a parse whose injections are still being parsed. The
+    /// snapshot is interpolated like any other, so it stays valid if the buffer is edited
+    /// before the parse completes.
+    fn did_finish_partial_parse(
+        &mut self,
+        syntax_snapshot: SyntaxSnapshot,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.parse_count += 1;
+        self.syntax_map.lock().did_parse(syntax_snapshot);
+        cx.emit(Event::Reparsed);
+        cx.notify();
+    }
+
     fn did_finish_parsing(&mut self, syntax_snapshot: SyntaxSnapshot, cx: &mut ModelContext<Self>) {
         self.parse_count += 1;
         self.syntax_map.lock().did_parse(syntax_snapshot);
@@ -1435,6 +1569,7 @@ impl Buffer {
         let old_text = self.as_rope().clone();
         let line_ending = self.line_ending();
         let base_version = self.version();
+        let cursor_rows = self.local_cursor_rows();
         cx.background_executor().spawn(async move {
             let ranges = trailing_whitespace_ranges(&old_text);
             let empty = Arc::<str>::from("");
@@ -1443,12 +1578,25 @@ impl Buffer {
                 line_ending,
                 edits: ranges
                     .into_iter()
+                    .filter(|range| {
+                        !cursor_rows.contains(&old_text.offset_to_point(range.start).row)
+                    })
                     .map(|range| (range, empty.clone()))
                     .collect(),
             }
         })
     }
 
+    /// The rows containing the heads of this replica's active selections.
+    fn local_cursor_rows(&self) -> HashSet<u32> {
+        self.remote_selections
+            .get(&self.text.replica_id())
+            .into_iter()
+            .flat_map(|set| set.selections.iter())
+            .map(|selection| selection.head().to_point(&self.text).row)
+            .collect()
+    }
+
     /// Ensures that the buffer ends with a single newline character, and
     /// no other whitespace.
     pub fn ensure_final_newline(&mut self, cx: &mut ModelContext<Self>) {
@@ -1512,6 +1660,8 @@ impl Buffer {
     /// Checks if the buffer has unsaved changes.
     pub fn is_dirty(&self) -> bool {
         self.file_fingerprint != self.as_rope().fingerprint()
+            || self.saved_line_ending != self.line_ending()
+            || self.saved_encoding != self.encoding
             || self.file.as_ref().map_or(false, |file| file.is_deleted())
     }
 
@@ -2159,9 +2309,16 @@ impl BufferSnapshot {
         indent_size_for_line(self, row)
     }
     /// Returns [`IndentSize`] for a given position that respects user settings
-    /// and language preferences.
+    /// and language preferences, unless the buffer has its own indentation that
+    /// the file's `.editorconfig` doesn't override.
     pub fn language_indent_size_at<T: ToOffset>(&self, position: T, cx: &AppContext) -> IndentSize {
-        let settings = language_settings(self.language_at(position), self.file(), cx);
+        let language = self.language_at(position);
+        if let Some(indent_size) = self.indent_size {
+            if !editorconfig_sets_indentation(language, self.file(), cx) {
+                return indent_size;
+            }
+        }
+        let settings = language_settings(language, self.file(), cx);
         if settings.hard_tabs {
             IndentSize::tab()
         } else {
@@ -2499,11 +2656,7 @@ impl BufferSnapshot {
     }
 
     /// Returns the settings for the language at the given location.
-    pub fn settings_at<'a, D: ToOffset>(
-        &self,
-        position: D,
-        cx: &'a AppContext,
-    ) -> &'a LanguageSettings {
+    pub fn settings_at<D: ToOffset>(&self, position: D, cx: &AppContext) -> Arc<LanguageSettings> {
         language_settings(self.language_at(position), self.file.as_ref(), cx)
     }
 
@@ -2513,7 +2666,9 @@ impl BufferSnapshot {
         let mut scope = None;
         let mut smallest_range: Option<Range<usize>> = None;
 
-        // Use the layer that has the smallest node intersecting the given point.
+        // Use the layer that has the smallest node intersecting the given point. Layers are
+        // yielded in order of depth, so when nodes from several layers have the same size, such
+        // as an injected language's node spanning the whole injection, the deepest one is used.
         for layer in self.syntax.layers_for_range(offset..offset, &self.text) {
             let mut cursor = layer.node().walk();
 
@@ -2533,7 +2688,7 @@ impl BufferSnapshot {
             if let Some(range) = range {
                 if smallest_range
                     .as_ref()
-                    .map_or(true, |smallest_range| range.len() < smallest_range.len())
+                    .map_or(true, |smallest_range| range.len() <= smallest_range.len())
                 {
                     smallest_range = Some(range);
                     scope = Some(LanguageScope {
@@ -2829,6 +2984,43 @@ impl BufferSnapshot {
         self.syntax.matches(range, self, query)
     }
 
+    /// Returns the ranges of the text objects captured by the language's text object
+    /// query, like functions, classes and parameters, that overlap `range`
+    pub fn text_object_ranges<'a, T: ToOffset>(
+        &'a self,
+        range: Range<T>,
+    ) -> impl Iterator<Item = (Range<usize>, TextObject)> + 'a {
+        let range = range.start.to_offset(self)..range.end.to_offset(self);
+
+        let mut matches = self.syntax.matches(range.clone(), &self.text, |grammar| {
+            grammar.text_object_config.as_ref().map(|c| &c.query)
+        });
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.text_object_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut captures = Vec::new();
+        iter::from_fn(move || loop {
+            if let Some(capture) = captures.pop() {
+                return Some(capture);
+            }
+            let mat = matches.peek()?;
+            let config = &configs[mat.grammar_index];
+            for capture in mat.captures {
+                let text_object = config
+                    .text_objects_by_capture_ix
+                    .iter()
+                    .find(|(ix, _)| *ix == capture.index);
+                if let Some((_, text_object)) = text_object {
+                    captures.push((capture.node.byte_range(), *text_object));
+                }
+            }
+            matches.advance();
+        })
+    }
+
     /// Returns bracket range pairs overlapping or adjacent to `range`
     pub fn bracket_ranges<'a, T: ToOffset>(
         &'a self,
@@ -3072,6 +3264,52 @@ fn indent_size_for_line(text: &text::BufferSnapshot, row: u32) -> IndentSize {
     indent_size_for_text(text.chars_at(Point::new(row, 0)))
 }
 
+/// Infers the unit of indentation used by the given text from its first
+/// lines: tabs when more lines are indented with tabs than with spaces, and
+/// otherwise the most common difference in indentation between consecutive
+/// lines.
+pub(crate) fn detect_indent_size(text: &text::BufferSnapshot) -> Option<IndentSize> {
+    const MAX_ROWS: u32 = 1000;
+    const MAX_WIDTH: usize = 8;
+
+    let mut tab_rows = 0;
+    let mut space_rows = 0;
+    let mut width_counts = [0; MAX_WIDTH + 1];
+    let mut previous_space_indent = 0;
+    for row in 0..=text.max_point().row.min(MAX_ROWS) {
+        if text.is_line_blank(row) {
+            continue;
+        }
+        let indent = indent_size_for_line(text, row);
+        if indent.kind == IndentKind::Tab && indent.len > 0 {
+            tab_rows += 1;
+            continue;
+        }
+        if indent.len > 0 {
+            space_rows += 1;
+        }
+        // Indentation by a single space is usually alignment, such as
+        // within block comments, rather than a level of indentation.
+        let width = indent.len.abs_diff(previous_space_indent) as usize;
+        if (2..=MAX_WIDTH).contains(&width) {
+            width_counts[width] += 1;
+        }
+        previous_space_indent = indent.len;
+    }
+
+    if tab_rows > space_rows {
+        Some(IndentSize::tab())
+    } else {
+        // Ties go to the narrower width.
+        let (width, count) = width_counts
+            .iter()
+            .enumerate()
+            .rev()
+            .max_by_key(|(_, count)| **count)?;
+        (*count > 0).then(|| IndentSize::spaces(width as u32))
+    }
+}
+
 fn indent_size_for_text(text: impl Iterator<Item = char>) -> IndentSize {
     let mut result = IndentSize::spaces(0);
     for c in text {
@@ -3102,6 +3340,7 @@ impl Clone for BufferSnapshot {
             file_update_count: self.file_update_count,
             git_diff_update_count: self.git_diff_update_count,
             language: self.language.clone(),
+            indent_size: self.indent_size,
             parse_count: self.parse_count,
         }
     }
@@ -3400,6 +3639,15 @@ impl Completion {
     pub fn is_snippet(&self) -> bool {
         self.lsp_completion.insert_text_format == Some(lsp::InsertTextFormat::SNIPPET)
     }
+
+    /// Whether confirming this completion also edits the buffer elsewhere, such as to
+    /// add an import.
+    pub fn has_additional_edits(&self) -> bool {
+        self.lsp_completion
+            .additional_text_edits
+            .as_ref()
+            .map_or(false, |edits| !edits.is_empty())
+    }
 }
 
 pub(crate) fn contiguous_ranges(
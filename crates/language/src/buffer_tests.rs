@@ -319,6 +319,33 @@ async fn test_normalize_whitespace(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_remove_trailing_whitespace_keeps_cursor_lines(cx: &mut gpui::TestAppContext) {
+    let text = ["one  ", "two  ", "three  "].join("\n");
+    let buffer =
+        cx.new_model(|cx| Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), text));
+
+    buffer.update(cx, |buffer, cx| {
+        let cursor = buffer.anchor_before(Point::new(1, 5));
+        let selections: Arc<[Selection<Anchor>]> = vec![Selection {
+            id: 0,
+            start: cursor,
+            end: cursor,
+            reversed: false,
+            goal: SelectionGoal::None,
+        }]
+        .into();
+        buffer.set_active_selections(selections, false, Default::default(), cx);
+    });
+    let diff = buffer
+        .update(cx, |buffer, cx| buffer.remove_trailing_whitespace(cx))
+        .await;
+    buffer.update(cx, |buffer, cx| {
+        buffer.apply_diff(diff, cx);
+        assert_eq!(buffer.text(), "one\ntwo  \nthree");
+    });
+}
+
 #[gpui::test]
 async fn test_reparse(cx: &mut gpui::TestAppContext) {
     let text = "fn a() {}";
@@ -744,6 +771,49 @@ async fn test_symbols_containing(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+fn test_text_object_ranges(cx: &mut AppContext) {
+    let (text, ranges) = marked_text_ranges(
+        indoc! {"
+            struct A {
+                b: u32,
+            }
+
+            fn c(d: u32) {
+                e(fˇ, d);
+            }"},
+        false,
+    );
+    let offset = ranges[0].start;
+    let buffer = cx.new_model(|cx| {
+        Buffer::new(
+            0,
+            BufferId::new(cx.entity_id().as_u64()).unwrap(),
+            text.clone(),
+        )
+        .with_language(Arc::new(rust_lang()), cx)
+    });
+    let snapshot = buffer.read(cx).snapshot();
+
+    let mut text_objects = snapshot
+        .text_object_ranges(offset..offset)
+        .filter(|(range, _)| range.contains(&offset))
+        .map(|(range, text_object)| (&text[range], text_object))
+        .collect::<Vec<_>>();
+    text_objects.sort_by_key(|(text, _)| text.len());
+    assert_eq!(
+        text_objects,
+        &[
+            ("f", TextObject::InsideParameter),
+            ("{\n    e(f, d);\n}", TextObject::InsideFunction),
+            (
+                "fn c(d: u32) {\n    e(f, d);\n}",
+                TextObject::AroundFunction
+            ),
+        ]
+    );
+}
+
 #[gpui::test]
 fn test_enclosing_bracket_ranges(cx: &mut AppContext) {
     let mut assert = |selection_text, range_markers| {
@@ -1015,6 +1085,70 @@ fn test_autoindent_with_hard_tabs(cx: &mut AppContext) {
     });
 }
 
+#[gpui::test]
+fn test_autoindent_with_buffer_indent_size(cx: &mut AppContext) {
+    init_settings(cx, |_| {});
+
+    cx.new_model(|cx| {
+        let text = "fn a() {}";
+        let mut buffer = Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), text)
+            .with_language(Arc::new(rust_lang()), cx);
+
+        // The buffer's own indentation takes precedence over the settings.
+        buffer.set_indent_size(Some(IndentSize::tab()), cx);
+        buffer.edit([(8..8, "\n\n")], Some(AutoindentMode::EachLine), cx);
+        assert_eq!(buffer.text(), "fn a() {\n\t\n}");
+
+        buffer
+    });
+}
+
+#[test]
+fn test_detect_indent_size() {
+    fn detect(text: &str) -> Option<IndentSize> {
+        let buffer = text::Buffer::new(0, BufferId::new(1).unwrap(), text.into());
+        buffer::detect_indent_size(&buffer)
+    }
+
+    assert_eq!(
+        detect("fn a() {\n    b();\n    if c {\n        d();\n    }\n}\n"),
+        Some(IndentSize::spaces(4))
+    );
+    assert_eq!(
+        detect("a:\n  b:\n    c: 1\n  d: 2\n"),
+        Some(IndentSize::spaces(2))
+    );
+    assert_eq!(
+        detect("fn a() {\n\tb();\n\tif c {\n\t\td();\n\t}\n}\n"),
+        Some(IndentSize::tab())
+    );
+    // Single spaces aligning block comments aren't a level of indentation.
+    assert_eq!(
+        detect("/**\n * Docs\n */\nfn a() {\n  b();\n}\n"),
+        Some(IndentSize::spaces(2))
+    );
+    assert_eq!(detect("one\ntwo\n"), None);
+}
+
+#[gpui::test]
+fn test_changing_line_ending(cx: &mut AppContext) {
+    init_settings(cx, |_| {});
+
+    cx.new_model(|cx| {
+        let mut buffer = Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), "a\nb");
+        assert!(!buffer.is_dirty());
+
+        buffer.set_line_ending(LineEnding::Windows, cx);
+        assert_eq!(buffer.line_ending(), LineEnding::Windows);
+        assert!(buffer.is_dirty());
+
+        buffer.set_line_ending(LineEnding::Unix, cx);
+        assert!(!buffer.is_dirty());
+
+        buffer
+    });
+}
+
 #[gpui::test]
 fn test_autoindent_does_not_adjust_lines_with_unchanged_suggestion(cx: &mut AppContext) {
     init_settings(cx, |_| {});
@@ -1867,6 +2001,8 @@ fn test_language_scope_at_with_rust(cx: &mut AppContext) {
             string_config.brackets().map(|e| e.1).collect::<Vec<_>>(),
             &[true, false]
         );
+        assert_eq!(string_config.override_name(), Some("string"));
+        assert_eq!(config.override_name(), None);
 
         buffer
     });
@@ -2444,6 +2580,17 @@ fn rust_lang() -> Language {
         "#,
     )
     .unwrap()
+    .with_text_object_query(
+        r#"
+        (function_item
+            body: (_) @function.inside) @function.around
+        (struct_item
+            body: (_) @class.inside) @class.around
+        (parameters (_) @parameter.inside)
+        (arguments (_) @parameter.inside)
+        "#,
+    )
+    .unwrap()
 }
 
 fn json_lang() -> Language {
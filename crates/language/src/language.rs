@@ -63,7 +63,7 @@ pub use diagnostic_set::DiagnosticEntry;
 pub use lsp::LanguageServerId;
 pub use outline::{Outline, OutlineItem};
 pub use syntax_map::{OwnedSyntaxLayer, SyntaxLayer};
-pub use text::LineEnding;
+pub use text::{Encoding, LineEnding};
 pub use tree_sitter::{Parser, Tree};
 
 /// Initializes the `language` crate.
@@ -425,6 +425,14 @@ pub struct LanguageConfig {
     /// Starting and closing characters of a block comment.
     #[serde(default)]
     pub block_comment: Option<(Arc<str>, Arc<str>)>,
+    /// How lines are continued when a newline is inserted within a block comment, such as a
+    /// ` * ` leader within `/* ... */` and `/** ... */` comments.
+    #[serde(default)]
+    pub block_comment_continuation: Option<BlockCommentContinuation>,
+    /// The text that's removed where lines are joined, such as comment leaders and string
+    /// continuations.
+    #[serde(default)]
+    pub join_lines: JoinLinesConfig,
     /// A list of language servers that are allowed to run on subranges of a given language.
     #[serde(default)]
     pub scope_opt_in_language_servers: Vec<String>,
@@ -440,6 +448,37 @@ pub struct LanguageConfig {
     pub prettier_parser_name: Option<String>,
 }
 
+/// The delimiters of a block comment whose lines each begin with a leader, such as
+/// `/*`, `*/` and `* `.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct BlockCommentContinuation {
+    /// The text that opens the comment.
+    pub start: Arc<str>,
+    /// The text that closes the comment.
+    pub end: Arc<str>,
+    /// The text that is inserted at the start of each continued line, after the indentation.
+    pub prefix: Arc<str>,
+}
+
+/// Patterns for the text that's removed when a line is joined onto the previous one.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct JoinLinesConfig {
+    /// Matches the start of the joined line, after its indentation, such as a `//` or ` * `
+    /// comment leader. It's only removed when the previous line ends within a comment.
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    pub line_start_pattern: Option<Regex>,
+    /// Matches the end of the previous line, such as a `\` line continuation. When the previous
+    /// line ends within a string, the lines are joined without a space between them, so that the
+    /// string's contents are kept.
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    pub line_end_pattern: Option<Regex>,
+    /// Whether the indentation of a line joined onto a string is removed as well, for languages
+    /// like Rust where a `\` at the end of a line within a string skips the next line's leading
+    /// whitespace. Otherwise, it's kept as part of the string's contents.
+    #[serde(default)]
+    pub string_continuation_skips_whitespace: bool,
+}
+
 /// Tree-sitter language queries for a given language.
 #[derive(Debug, Default)]
 pub struct LanguageQueries {
@@ -450,6 +489,7 @@ pub struct LanguageQueries {
     pub embedding: Option<Cow<'static, str>>,
     pub injections: Option<Cow<'static, str>>,
     pub overrides: Option<Cow<'static, str>>,
+    pub text_objects: Option<Cow<'static, str>>,
 }
 
 /// Represents a language for the given range. Some languages (e.g. HTML)
@@ -512,6 +552,8 @@ impl Default for LanguageConfig {
             autoclose_before: Default::default(),
             line_comments: Default::default(),
             block_comment: Default::default(),
+            block_comment_continuation: Default::default(),
+            join_lines: Default::default(),
             scope_opt_in_language_servers: Default::default(),
             overrides: Default::default(),
             word_characters: Default::default(),
@@ -525,6 +567,31 @@ fn auto_indent_using_last_non_empty_line_default() -> bool {
     true
 }
 
+/// The parts of a [`LanguageConfig`] that are needed to choose a language for a file,
+/// which can be read without loading the rest of the language's configuration.
+#[derive(Clone, Debug, Deserialize)]
+pub struct LanguageMatcher {
+    /// Human-readable name of the language.
+    pub name: Arc<str>,
+    /// The path suffixes of files in this language. See [`LanguageConfig::path_suffixes`].
+    #[serde(default)]
+    pub path_suffixes: Vec<String>,
+    /// A pattern matching the first line of files in this language.
+    /// See [`LanguageConfig::first_line_pattern`].
+    #[serde(default, deserialize_with = "deserialize_regex")]
+    pub first_line_pattern: Option<Regex>,
+}
+
+impl LanguageMatcher {
+    fn for_config(config: &LanguageConfig) -> Self {
+        Self {
+            name: config.name.clone(),
+            path_suffixes: config.path_suffixes.clone(),
+            first_line_pattern: config.first_line_pattern.clone(),
+        }
+    }
+}
+
 fn deserialize_regex<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Regex>, D::Error> {
     let source = Option::<String>::deserialize(d)?;
     if let Some(source) = source {
@@ -604,6 +671,7 @@ pub struct BracketPair {
 
 pub struct Language {
     pub(crate) config: LanguageConfig,
+    matcher: LanguageMatcher,
     pub(crate) grammar: Option<Arc<Grammar>>,
     pub(crate) adapters: Vec<Arc<CachedLspAdapter>>,
 
@@ -625,6 +693,7 @@ pub struct Grammar {
     pub embedding_config: Option<EmbeddingConfig>,
     pub(crate) injection_config: Option<InjectionConfig>,
     pub(crate) override_config: Option<OverrideConfig>,
+    pub(crate) text_object_config: Option<TextObjectConfig>,
     pub(crate) highlight_map: Mutex<HighlightMap>,
 }
 
@@ -678,6 +747,39 @@ struct BracketConfig {
     close_capture_ix: u32,
 }
 
+struct TextObjectConfig {
+    query: Query,
+    text_objects_by_capture_ix: Vec<(u32, TextObject)>,
+}
+
+/// A structural region of code, captured by a language's text object query.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TextObject {
+    /// The body of a function, captured as `@function.inside`.
+    InsideFunction,
+    /// A whole function, captured as `@function.around`.
+    AroundFunction,
+    /// The body of a class or other type definition, captured as `@class.inside`.
+    InsideClass,
+    /// A whole class or other type definition, captured as `@class.around`.
+    AroundClass,
+    /// A single parameter or argument, without its separator, captured as `@parameter.inside`.
+    InsideParameter,
+}
+
+impl TextObject {
+    fn from_capture_name(name: &str) -> Option<Self> {
+        match name {
+            "function.inside" => Some(Self::InsideFunction),
+            "function.around" => Some(Self::AroundFunction),
+            "class.inside" => Some(Self::InsideClass),
+            "class.around" => Some(Self::AroundClass),
+            "parameter.inside" => Some(Self::InsideParameter),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub enum LanguageServerBinaryStatus {
     CheckingForUpdate,
@@ -692,12 +794,20 @@ type AvailableLanguageId = usize;
 #[derive(Clone)]
 struct AvailableLanguage {
     id: AvailableLanguageId,
-    config: LanguageConfig,
+    matcher: LanguageMatcher,
+    config: AvailableConfig,
     grammar: AvailableGrammar,
     lsp_adapters: Vec<Arc<dyn LspAdapter>>,
     loaded: bool,
 }
 
+#[derive(Clone)]
+enum AvailableConfig {
+    Loaded(LanguageConfig),
+    /// Read from the language's asset directory when the language is first used.
+    Lazy(fn(&str) -> Result<LanguageConfig>),
+}
+
 #[derive(Clone)]
 enum AvailableGrammar {
     Native {
@@ -787,10 +897,52 @@ impl LanguageRegistry {
         grammar: tree_sitter::Language,
         lsp_adapters: Vec<Arc<dyn LspAdapter>>,
         get_queries: fn(&str) -> LanguageQueries,
+    ) {
+        self.register_native(
+            asset_dir,
+            LanguageMatcher::for_config(&config),
+            AvailableConfig::Loaded(config),
+            grammar,
+            lsp_adapters,
+            get_queries,
+        );
+    }
+
+    /// Registers a language whose configuration is only loaded, using `load_config`,
+    /// when the language is first used. Until then, the matcher is used to list the
+    /// language and to choose it for files.
+    pub fn register_lazy(
+        &self,
+        asset_dir: &'static str,
+        matcher: LanguageMatcher,
+        grammar: tree_sitter::Language,
+        lsp_adapters: Vec<Arc<dyn LspAdapter>>,
+        load_config: fn(&str) -> Result<LanguageConfig>,
+        get_queries: fn(&str) -> LanguageQueries,
+    ) {
+        self.register_native(
+            asset_dir,
+            matcher,
+            AvailableConfig::Lazy(load_config),
+            grammar,
+            lsp_adapters,
+            get_queries,
+        );
+    }
+
+    fn register_native(
+        &self,
+        asset_dir: &'static str,
+        matcher: LanguageMatcher,
+        config: AvailableConfig,
+        grammar: tree_sitter::Language,
+        lsp_adapters: Vec<Arc<dyn LspAdapter>>,
+        get_queries: fn(&str) -> LanguageQueries,
     ) {
         let state = &mut *self.state.write();
         state.available_languages.push(AvailableLanguage {
             id: post_inc(&mut state.next_available_language_id),
+            matcher,
             config,
             grammar: AvailableGrammar::Native {
                 grammar,
@@ -806,7 +958,8 @@ impl LanguageRegistry {
         let state = &mut *self.state.write();
         state.available_languages.push(AvailableLanguage {
             id: post_inc(&mut state.next_available_language_id),
-            config,
+            matcher: LanguageMatcher::for_config(&config),
+            config: AvailableConfig::Loaded(config),
             grammar: AvailableGrammar::Wasm {
                 _grammar_name: grammar_name,
                 _path: path,
@@ -821,7 +974,7 @@ impl LanguageRegistry {
         let mut result = state
             .available_languages
             .iter()
-            .filter_map(|l| l.loaded.not().then_some(l.config.name.to_string()))
+            .filter_map(|l| l.loaded.not().then_some(l.matcher.name.to_string()))
             .chain(state.languages.iter().map(|l| l.config.name.to_string()))
             .collect::<Vec<_>>();
         result.sort_unstable_by_key(|language_name| language_name.to_lowercase());
@@ -864,7 +1017,7 @@ impl LanguageRegistry {
         name: &str,
     ) -> UnwrapFuture<oneshot::Receiver<Result<Arc<Language>>>> {
         let name = UniCase::new(name);
-        self.get_or_load_language(|config| UniCase::new(config.name.as_ref()) == name)
+        self.get_or_load_language(|matcher| UniCase::new(matcher.name.as_ref()) == name)
     }
 
     pub fn language_for_name_or_extension(
@@ -872,9 +1025,9 @@ impl LanguageRegistry {
         string: &str,
     ) -> UnwrapFuture<oneshot::Receiver<Result<Arc<Language>>>> {
         let string = UniCase::new(string);
-        self.get_or_load_language(|config| {
-            UniCase::new(config.name.as_ref()) == string
-                || config
+        self.get_or_load_language(|matcher| {
+            UniCase::new(matcher.name.as_ref()) == string
+                || matcher
                     .path_suffixes
                     .iter()
                     .any(|suffix| UniCase::new(suffix) == string)
@@ -890,12 +1043,12 @@ impl LanguageRegistry {
         let filename = path.file_name().and_then(|name| name.to_str());
         let extension = path.extension_or_hidden_file_name();
         let path_suffixes = [extension, filename];
-        self.get_or_load_language(|config| {
-            let path_matches = config
+        self.get_or_load_language(|matcher| {
+            let path_matches = matcher
                 .path_suffixes
                 .iter()
                 .any(|suffix| path_suffixes.contains(&Some(suffix.as_str())));
-            let content_matches = content.zip(config.first_line_pattern.as_ref()).map_or(
+            let content_matches = content.zip(matcher.first_line_pattern.as_ref()).map_or(
                 false,
                 |(content, pattern)| {
                     let end = content.clip_point(Point::new(0, 256), Bias::Left);
@@ -910,7 +1063,7 @@ impl LanguageRegistry {
 
     fn get_or_load_language(
         self: &Arc<Self>,
-        callback: impl Fn(&LanguageConfig) -> bool,
+        callback: impl Fn(&LanguageMatcher) -> bool,
     ) -> UnwrapFuture<oneshot::Receiver<Result<Arc<Language>>>> {
         let (tx, rx) = oneshot::channel();
 
@@ -918,14 +1071,14 @@ impl LanguageRegistry {
         if let Some(language) = state
             .languages
             .iter()
-            .find(|language| callback(&language.config))
+            .find(|language| callback(&language.matcher))
         {
             let _ = tx.send(Ok(language.clone()));
         } else if let Some(executor) = self.executor.clone() {
             if let Some(language) = state
                 .available_languages
                 .iter()
-                .find(|l| !l.loaded && callback(&l.config))
+                .find(|l| !l.loaded && callback(&l.matcher))
                 .cloned()
             {
                 let txs = state
@@ -936,19 +1089,27 @@ impl LanguageRegistry {
                         executor
                             .spawn(async move {
                                 let id = language.id;
-                                let name = language.config.name.clone();
+                                let name = language.matcher.name.clone();
                                 let language = async {
-                                    let (grammar, queries) = match language.grammar {
+                                    let (config, grammar, queries) = match language.grammar {
                                         AvailableGrammar::Native {
                                             grammar,
                                             asset_dir,
                                             get_queries,
-                                        } => (grammar, (get_queries)(asset_dir)),
+                                        } => {
+                                            let config = match language.config {
+                                                AvailableConfig::Loaded(config) => config,
+                                                AvailableConfig::Lazy(load_config) => {
+                                                    load_config(asset_dir)?
+                                                }
+                                            };
+                                            (config, grammar, (get_queries)(asset_dir))
+                                        }
                                         AvailableGrammar::Wasm { .. } => {
                                             Err(anyhow!("not supported"))?
                                         }
                                     };
-                                    Language::new(language.config, Some(grammar))
+                                    Language::new(config, Some(grammar))
                                         .with_lsp_adapters(language.lsp_adapters)
                                         .await
                                         .with_queries(queries)
@@ -1009,6 +1170,7 @@ impl LanguageRegistry {
         language: Arc<Language>,
         adapter: Arc<CachedLspAdapter>,
         root_path: Arc<Path>,
+        environment: Task<Option<HashMap<String, String>>>,
         delegate: Arc<dyn LspAdapterDelegate>,
         cx: &mut AppContext,
     ) -> Option<PendingLanguageServer> {
@@ -1109,6 +1271,7 @@ impl LanguageRegistry {
                     stderr_capture,
                     server_id,
                     binary,
+                    environment.await,
                     &root_path,
                     adapter.code_action_kinds(),
                     cx,
@@ -1276,6 +1439,7 @@ async fn fetch_latest_binary(
 impl Language {
     pub fn new(config: LanguageConfig, ts_language: Option<tree_sitter::Language>) -> Self {
         Self {
+            matcher: LanguageMatcher::for_config(&config),
             config,
             grammar: ts_language.map(|ts_language| {
                 Arc::new(Grammar {
@@ -1287,6 +1451,7 @@ impl Language {
                     indents_config: None,
                     injection_config: None,
                     override_config: None,
+                    text_object_config: None,
                     error_query: Query::new(&ts_language, "(ERROR) @error").unwrap(),
                     ts_language,
                     highlight_map: Default::default(),
@@ -1343,6 +1508,11 @@ impl Language {
                 .with_override_query(query.as_ref())
                 .context("Error loading override query")?;
         }
+        if let Some(query) = queries.text_objects {
+            self = self
+                .with_text_object_query(query.as_ref())
+                .context("Error loading text object query")?;
+        }
         Ok(self)
     }
 
@@ -1411,6 +1581,24 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_text_object_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self.grammar_mut();
+        let query = Query::new(&grammar.ts_language, source)?;
+        let text_objects_by_capture_ix = query
+            .capture_names()
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, name)| Some((ix as u32, TextObject::from_capture_name(name)?)))
+            .collect::<Vec<_>>();
+        if !text_objects_by_capture_ix.is_empty() {
+            grammar.text_object_config = Some(TextObjectConfig {
+                query,
+                text_objects_by_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
     pub fn with_brackets_query(mut self, source: &str) -> Result<Self> {
         let grammar = self.grammar_mut();
         let query = Query::new(&grammar.ts_language, source)?;
@@ -1729,6 +1917,25 @@ impl LanguageScope {
         .map(|e| (&e.0, &e.1))
     }
 
+    /// Returns the patterns for the text that's removed when joining lines.
+    pub fn join_lines_config(&self) -> &JoinLinesConfig {
+        &self.language.config.join_lines
+    }
+
+    /// Returns the delimiters used to continue block comments onto new lines.
+    pub fn block_comment_continuation(&self) -> Option<&BlockCommentContinuation> {
+        self.language.config.block_comment_continuation.as_ref()
+    }
+
+    /// The name of the override that applies to this scope, such as `comment` or `string`,
+    /// as captured in the language's `overrides.scm` query.
+    pub fn override_name(&self) -> Option<&str> {
+        let id = self.override_id?;
+        let grammar = self.language.grammar.as_ref()?;
+        let override_config = grammar.override_config.as_ref()?;
+        override_config.values.get(&id).map(|e| e.0.as_str())
+    }
+
     /// Returns a list of language-specific word characters.
     ///
     /// By default, Zed treats alphanumeric characters (and '_') as word characters for
@@ -2017,6 +2224,53 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_lazy_language_config(cx: &mut TestAppContext) {
+        static CONFIG_LOADS: AtomicUsize = AtomicUsize::new(0);
+
+        let mut languages = LanguageRegistry::test();
+        languages.set_executor(cx.executor());
+        let languages = Arc::new(languages);
+        languages.register_lazy(
+            "/rust",
+            LanguageMatcher {
+                name: "Rust".into(),
+                path_suffixes: vec!["rs".into()],
+                first_line_pattern: None,
+            },
+            tree_sitter_rust::language(),
+            vec![],
+            |asset_dir| {
+                assert_eq!(asset_dir, "/rust");
+                CONFIG_LOADS.fetch_add(1, SeqCst);
+                Ok(LanguageConfig {
+                    name: "Rust".into(),
+                    path_suffixes: vec!["rs".into()],
+                    line_comments: vec!["// ".into()],
+                    ..Default::default()
+                })
+            },
+            |_| Default::default(),
+        );
+
+        // The language can be listed without loading its configuration.
+        assert_eq!(
+            languages.language_names(),
+            &["Plain Text".to_string(), "Rust".to_string()]
+        );
+        assert_eq!(CONFIG_LOADS.load(SeqCst), 0);
+
+        let rust = languages.language_for_file("lib.rs", None).await.unwrap();
+        assert_eq!(
+            rust.config.line_comments.as_slice(),
+            &[Arc::<str>::from("// ")]
+        );
+        assert_eq!(CONFIG_LOADS.load(SeqCst), 1);
+
+        languages.language_for_name("Rust").await.unwrap();
+        assert_eq!(CONFIG_LOADS.load(SeqCst), 1);
+    }
+
     #[gpui::test(iterations = 10)]
     async fn test_language_loading(cx: &mut TestAppContext) {
         let mut languages = LanguageRegistry::test();
@@ -1,6 +1,6 @@
 //! Provides `language`-related settings.
 
-use crate::{File, Language};
+use crate::{File, Language, LineEnding};
 use anyhow::Result;
 use collections::{HashMap, HashSet};
 use globset::GlobMatcher;
@@ -10,22 +10,99 @@ use schemars::{
     JsonSchema,
 };
 use serde::{Deserialize, Serialize};
-use settings::Settings;
-use std::{num::NonZeroU32, path::Path, sync::Arc};
+use settings::{
+    editorconfig::{EndOfLine, IndentStyle},
+    EditorconfigProperties, Settings, SettingsStore,
+};
+use std::{cell::RefCell, num::NonZeroU32, path::Path, sync::Arc};
 
 /// Initializes the language settings.
 pub fn init(cx: &mut AppContext) {
     AllLanguageSettings::register(cx);
+    cx.set_global(FileLanguageSettings::default());
+    cx.observe_global::<SettingsStore>(|cx| {
+        cx.global::<FileLanguageSettings>()
+            .by_file
+            .borrow_mut()
+            .clear();
+    })
+    .detach();
 }
 
-/// Returns the settings for the specified language from the provided file.
-pub fn language_settings<'a>(
+/// Returns the settings for the specified language from the provided file,
+/// with the properties of the `.editorconfig` files that apply to it.
+pub fn language_settings(
     language: Option<&Arc<Language>>,
     file: Option<&Arc<dyn File>>,
-    cx: &'a AppContext,
-) -> &'a LanguageSettings {
+    cx: &AppContext,
+) -> Arc<LanguageSettings> {
+    file_language_settings(language, file, cx).settings
+}
+
+/// Returns whether the `.editorconfig` files that apply to the provided file set its
+/// indentation, which then takes precedence over the indentation detected in the file.
+pub fn editorconfig_sets_indentation(
+    language: Option<&Arc<Language>>,
+    file: Option<&Arc<dyn File>>,
+    cx: &AppContext,
+) -> bool {
+    file_language_settings(language, file, cx).editorconfig_sets_indentation
+}
+
+/// The language settings of files, with their `.editorconfig` properties merged in, which
+/// are kept until the settings change so that they aren't merged on every lookup.
+#[derive(Default)]
+struct FileLanguageSettings {
+    by_file: RefCell<HashMap<(usize, Arc<Path>, Option<Arc<str>>), FileLanguageSettingsEntry>>,
+}
+
+#[derive(Clone)]
+struct FileLanguageSettingsEntry {
+    settings: Arc<LanguageSettings>,
+    editorconfig_sets_indentation: bool,
+}
+
+fn file_language_settings(
+    language: Option<&Arc<Language>>,
+    file: Option<&Arc<dyn File>>,
+    cx: &AppContext,
+) -> FileLanguageSettingsEntry {
     let language_name = language.map(|l| l.name());
-    all_language_settings(file, cx).language(language_name.as_deref())
+    let settings = all_language_settings(file, cx).language(language_name.as_deref());
+    let Some(file) = file else {
+        return FileLanguageSettingsEntry {
+            settings: settings.clone(),
+            editorconfig_sets_indentation: false,
+        };
+    };
+
+    let key = (file.worktree_id(), file.path().clone(), language_name);
+    let cache = cx.try_global::<FileLanguageSettings>();
+    if let Some(entry) = cache.and_then(|cache| cache.by_file.borrow().get(&key).cloned()) {
+        return entry;
+    }
+    let entry = match cx
+        .global::<SettingsStore>()
+        .editorconfig_properties(file.worktree_id(), file.path())
+    {
+        Some(editorconfig) => {
+            let mut settings = LanguageSettings::clone(settings);
+            merge_editorconfig(&mut settings, &editorconfig);
+            FileLanguageSettingsEntry {
+                settings: Arc::new(settings),
+                editorconfig_sets_indentation: editorconfig.indent_style.is_some()
+                    || editorconfig.indent_width().is_some(),
+            }
+        }
+        None => FileLanguageSettingsEntry {
+            settings: settings.clone(),
+            editorconfig_sets_indentation: false,
+        },
+    };
+    if let Some(cache) = cache {
+        cache.by_file.borrow_mut().insert(key, entry.clone());
+    }
+    entry
 }
 
 /// Returns the settings for all languages from the provided file.
@@ -42,8 +119,8 @@ pub fn all_language_settings<'a>(
 pub struct AllLanguageSettings {
     /// The settings for GitHub Copilot.
     pub copilot: CopilotSettings,
-    defaults: LanguageSettings,
-    languages: HashMap<Arc<str>, LanguageSettings>,
+    defaults: Arc<LanguageSettings>,
+    languages: HashMap<Arc<str>, Arc<LanguageSettings>>,
 }
 
 /// The settings for a particular language.
@@ -69,7 +146,7 @@ pub struct LanguageSettings {
     /// Whether or not to perform a buffer format before saving.
     pub format_on_save: FormatOnSave,
     /// Whether or not to remove any trailing whitespace from lines of a buffer
-    /// before saving it.
+    /// before saving it, other than the lines with a cursor.
     pub remove_trailing_whitespace_on_save: bool,
     /// Whether or not to ensure there's a single newline at the end of a buffer
     /// when saving it.
@@ -87,12 +164,43 @@ pub struct LanguageSettings {
     pub show_copilot_suggestions: bool,
     /// Whether to show tabs and spaces in the editor.
     pub show_whitespaces: ShowWhitespaceSetting,
-    /// Whether to start a new line with a comment when a previous line is a comment as well.
+    /// Whether to highlight whitespace at the end of lines, other than the
+    /// ones with a cursor.
+    pub highlight_trailing_whitespace: bool,
+    /// Whether to start a new line with a comment when a previous line is a comment as well,
+    /// continuing the leader (such as ` * `) of an unclosed block comment.
     pub extend_comment_on_newline: bool,
     /// Inlay hint related settings.
     pub inlay_hints: InlayHintSettings,
     /// Whether to automatically close brackets.
     pub use_autoclose: bool,
+    /// Whether typing an opening bracket or quote while text is selected
+    /// surrounds the selection with the pair.
+    pub use_auto_surround: bool,
+    /// Whether to automatically close each bracket, by opening bracket,
+    /// overriding whether the language closes it.
+    pub autoclose_brackets: HashMap<String, bool>,
+    /// Whether typing a closing bracket types over any identical closing
+    /// bracket after the cursor, rather than only auto-inserted ones.
+    pub always_type_over_closing_brackets: bool,
+    /// Which keys accept the selected completion.
+    pub accept_completion_with: AcceptCompletionWith,
+    /// Whether the selected completion is accepted when nothing has been typed
+    /// since the completions menu was shown, such as right after typing `.`.
+    pub accept_completion_without_typing: bool,
+    /// Whether typing one of a completion's commit characters, as given by the
+    /// language server, accepts it before inserting the character.
+    pub use_completion_commit_characters: bool,
+    /// Whether to complete the words in open buffers of the same language, for
+    /// buffers without a language server.
+    pub word_completions: bool,
+    /// Whether to offer expanding the Emmet abbreviation before the cursor as
+    /// the first completion.
+    pub emmet: bool,
+    /// The line ending that files are saved with, which is only set by
+    /// EditorConfig. Files keep their own line endings otherwise.
+    #[serde(skip)]
+    pub line_ending: Option<LineEnding>,
 }
 
 /// The settings for [GitHub Copilot](https://github.com/features/copilot).
@@ -165,7 +273,7 @@ pub struct LanguageSettingsContent {
     #[serde(default)]
     pub format_on_save: Option<FormatOnSave>,
     /// Whether or not to remove any trailing whitespace from lines of a buffer
-    /// before saving it.
+    /// before saving it, other than the lines with a cursor.
     ///
     /// Default: true
     #[serde(default)]
@@ -202,7 +310,14 @@ pub struct LanguageSettingsContent {
     /// Whether to show tabs and spaces in the editor.
     #[serde(default)]
     pub show_whitespaces: Option<ShowWhitespaceSetting>,
-    /// Whether to start a new line with a comment when a previous line is a comment as well.
+    /// Whether to highlight whitespace at the end of lines, other than the
+    /// ones with a cursor.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub highlight_trailing_whitespace: Option<bool>,
+    /// Whether to start a new line with a comment when a previous line is a comment as well,
+    /// continuing the leader (such as ` * `) of an unclosed block comment.
     ///
     /// Default: true
     #[serde(default)]
@@ -215,6 +330,55 @@ pub struct LanguageSettingsContent {
     ///
     /// Default: true
     pub use_autoclose: Option<bool>,
+    /// Whether typing an opening bracket or quote while text is selected
+    /// surrounds the selection with the pair, rather than replacing it.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub use_auto_surround: Option<bool>,
+    /// Whether to automatically close each bracket, by opening bracket,
+    /// overriding whether the language closes it. For example, `{ "'": false }`
+    /// stops closing single quotes.
+    ///
+    /// Default: {}
+    #[serde(default)]
+    pub autoclose_brackets: Option<HashMap<String, bool>>,
+    /// Whether typing a closing bracket types over any identical closing
+    /// bracket after the cursor, rather than only auto-inserted ones.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub always_type_over_closing_brackets: Option<bool>,
+    /// Which keys accept the selected completion.
+    ///
+    /// Default: tab_and_enter
+    #[serde(default)]
+    pub accept_completion_with: Option<AcceptCompletionWith>,
+    /// Whether the selected completion is accepted when nothing has been typed
+    /// since the completions menu was shown, such as right after typing `.`.
+    /// Otherwise, the keys that accept completions do what they'd do without the menu.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub accept_completion_without_typing: Option<bool>,
+    /// Whether typing one of a completion's commit characters, as given by the
+    /// language server, accepts it before inserting the character.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub use_completion_commit_characters: Option<bool>,
+    /// Whether to complete the words in open buffers of the same language, for
+    /// buffers without a language server.
+    ///
+    /// Default: true
+    #[serde(default)]
+    pub word_completions: Option<bool>,
+    /// Whether to offer expanding the Emmet abbreviation before the cursor as
+    /// the first completion, such as `ul>li*3` or `m10`.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub emmet: Option<bool>,
 }
 
 /// The contents of the GitHub Copilot settings.
@@ -264,6 +428,31 @@ pub enum FormatOnSave {
     },
 }
 
+/// Controls which keys accept the selected completion.
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AcceptCompletionWith {
+    /// Accept completions with either tab or enter.
+    #[default]
+    TabAndEnter,
+    /// Only accept completions with tab, so enter always starts a new line.
+    Tab,
+    /// Only accept completions with enter, so tab always indents.
+    Enter,
+}
+
+impl AcceptCompletionWith {
+    /// Whether tab accepts completions.
+    pub fn tab(&self) -> bool {
+        matches!(self, Self::TabAndEnter | Self::Tab)
+    }
+
+    /// Whether enter accepts completions.
+    pub fn enter(&self) -> bool {
+        matches!(self, Self::TabAndEnter | Self::Enter)
+    }
+}
+
 /// Controls how whitespace should be displayedin the editor.
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
@@ -345,7 +534,7 @@ impl InlayHintSettings {
 
 impl AllLanguageSettings {
     /// Returns the [`LanguageSettings`] for the language with the specified name.
-    pub fn language<'a>(&'a self, language_name: Option<&str>) -> &'a LanguageSettings {
+    pub fn language<'a>(&'a self, language_name: Option<&str>) -> &'a Arc<LanguageSettings> {
         if let Some(name) = language_name {
             if let Some(overrides) = self.languages.get(name) {
                 return overrides;
@@ -481,8 +670,11 @@ impl settings::Settings for AllLanguageSettings {
                     .filter_map(|g| Some(globset::Glob::new(g).ok()?.compile_matcher()))
                     .collect(),
             },
-            defaults,
-            languages,
+            defaults: Arc::new(defaults),
+            languages: languages
+                .into_iter()
+                .map(|(language_name, settings)| (language_name, Arc::new(settings)))
+                .collect(),
         })
     }
 
@@ -543,11 +735,41 @@ impl settings::Settings for AllLanguageSettings {
     }
 }
 
+fn merge_editorconfig(settings: &mut LanguageSettings, editorconfig: &EditorconfigProperties) {
+    if let Some(indent_style) = editorconfig.indent_style {
+        settings.hard_tabs = indent_style == IndentStyle::Tab;
+    }
+    if let Some(indent_width) = editorconfig.indent_width().and_then(NonZeroU32::new) {
+        settings.tab_size = indent_width;
+    }
+    match editorconfig.end_of_line {
+        Some(EndOfLine::Lf) => settings.line_ending = Some(LineEnding::Unix),
+        Some(EndOfLine::Crlf) => settings.line_ending = Some(LineEnding::Windows),
+        // Lone carriage returns aren't supported as line endings.
+        Some(EndOfLine::Cr) | None => {}
+    }
+    if let Some(trim_trailing_whitespace) = editorconfig.trim_trailing_whitespace {
+        settings.remove_trailing_whitespace_on_save = trim_trailing_whitespace;
+    }
+    if let Some(insert_final_newline) = editorconfig.insert_final_newline {
+        settings.ensure_final_newline_on_save = insert_final_newline;
+    }
+}
+
 fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent) {
     merge(&mut settings.tab_size, src.tab_size);
     merge(&mut settings.hard_tabs, src.hard_tabs);
     merge(&mut settings.soft_wrap, src.soft_wrap);
     merge(&mut settings.use_autoclose, src.use_autoclose);
+    merge(&mut settings.use_auto_surround, src.use_auto_surround);
+    merge(
+        &mut settings.autoclose_brackets,
+        src.autoclose_brackets.clone(),
+    );
+    merge(
+        &mut settings.always_type_over_closing_brackets,
+        src.always_type_over_closing_brackets,
+    );
     merge(&mut settings.show_wrap_guides, src.show_wrap_guides);
     merge(&mut settings.wrap_guides, src.wrap_guides.clone());
 
@@ -575,11 +797,29 @@ fn merge_settings(settings: &mut LanguageSettings, src: &LanguageSettingsContent
         src.show_copilot_suggestions,
     );
     merge(&mut settings.show_whitespaces, src.show_whitespaces);
+    merge(
+        &mut settings.highlight_trailing_whitespace,
+        src.highlight_trailing_whitespace,
+    );
     merge(
         &mut settings.extend_comment_on_newline,
         src.extend_comment_on_newline,
     );
     merge(&mut settings.inlay_hints, src.inlay_hints);
+    merge(
+        &mut settings.accept_completion_with,
+        src.accept_completion_with,
+    );
+    merge(
+        &mut settings.accept_completion_without_typing,
+        src.accept_completion_without_typing,
+    );
+    merge(
+        &mut settings.use_completion_commit_characters,
+        src.use_completion_commit_characters,
+    );
+    merge(&mut settings.word_completions, src.word_completions);
+    merge(&mut settings.emmet, src.emmet);
     fn merge<T>(target: &mut T, value: Option<T>) {
         if let Some(value) = value {
             *target = value;
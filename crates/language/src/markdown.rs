@@ -5,7 +5,7 @@ use std::{ops::Range, path::PathBuf};
 
 use crate::{HighlightId, Language, LanguageRegistry};
 use gpui::{px, FontStyle, FontWeight, HighlightStyle, UnderlineStyle};
-use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
+use pulldown_cmark::{Alignment, CodeBlockKind, Event, Options, Parser, Tag};
 
 /// Parsed Markdown content.
 #[derive(Debug, Clone)]
@@ -77,6 +77,8 @@ pub struct ParsedRegion {
     pub code: bool,
     /// The link contained in this region, if it has one.
     pub link: Option<Link>,
+    /// Whether the region is the alternative text of an image, which is found at its link.
+    pub image: bool,
 }
 
 /// A Markdown link.
@@ -100,6 +102,15 @@ impl Link {
             return Some(Link::Web { url: text });
         }
 
+        // Language servers link to files with `file://` URIs, often with a fragment for the
+        // line, which isn't used.
+        if let Some(path) = text.strip_prefix("file://") {
+            let path = path.split_once('#').map_or(path, |(path, _)| path);
+            return Some(Link::Path {
+                path: PathBuf::from(path),
+            });
+        }
+
         let path = PathBuf::from(text);
         if path.is_absolute() {
             return Some(Link::Path { path });
@@ -152,15 +163,26 @@ pub async fn parse_markdown_block(
     let mut bold_depth = 0;
     let mut italic_depth = 0;
     let mut link_url = None;
+    let mut image_start = None;
     let mut current_language = None;
+    let mut code_block_start = None;
     let mut list_stack = Vec::new();
+    let mut table: Option<Table> = None;
 
     for event in Parser::new_ext(markdown, Options::all()) {
         let prev_len = text.len();
         match event {
+            Event::Text(t) | Event::Code(t) if table.is_some() => {
+                if let Some(table) = table.as_mut() {
+                    table.push_str(t.as_ref());
+                }
+            }
+
             Event::Text(t) => {
                 if let Some(language) = &current_language {
                     highlight_code(text, highlights, t.as_ref(), language);
+                } else if code_block_start.is_some() {
+                    text.push_str(t.as_ref());
                 } else {
                     text.push_str(t.as_ref());
 
@@ -179,6 +201,7 @@ pub async fn parse_markdown_block(
                         regions.push(ParsedRegion {
                             code: false,
                             link: Some(link),
+                            image: image_start.is_some(),
                         });
                         style.underline = true;
                     }
@@ -215,7 +238,11 @@ pub async fn parse_markdown_block(
                         }),
                     ));
                 }
-                regions.push(ParsedRegion { code: true, link });
+                regions.push(ParsedRegion {
+                    code: true,
+                    link,
+                    image: false,
+                });
             }
 
             Event::Start(tag) => match tag {
@@ -228,13 +255,16 @@ pub async fn parse_markdown_block(
 
                 Tag::CodeBlock(kind) => {
                     new_paragraph(text, &mut list_stack);
-                    current_language = if let CodeBlockKind::Fenced(language) = kind {
-                        language_registry
-                            .language_for_name(language.as_ref())
-                            .await
-                            .ok()
-                    } else {
-                        language.clone()
+                    code_block_start = Some(text.len());
+                    current_language = match kind {
+                        // Fences name their language either by name or by file extension.
+                        CodeBlockKind::Fenced(fence_language) if !fence_language.is_empty() => {
+                            language_registry
+                                .language_for_name_or_extension(fence_language.as_ref())
+                                .await
+                                .ok()
+                        }
+                        _ => language.clone(),
                     }
                 }
 
@@ -244,6 +274,31 @@ pub async fn parse_markdown_block(
 
                 Tag::Link(_, url, _) => link_url = Some(url.to_string()),
 
+                Tag::Image(_, url, _) => {
+                    image_start = Some(text.len());
+                    link_url = Some(url.to_string());
+                }
+
+                Tag::Table(alignments) => {
+                    new_paragraph(text, &mut list_stack);
+                    table = Some(Table {
+                        alignments,
+                        rows: Vec::new(),
+                    });
+                }
+
+                Tag::TableHead | Tag::TableRow => {
+                    if let Some(table) = table.as_mut() {
+                        table.rows.push(Vec::new());
+                    }
+                }
+
+                Tag::TableCell => {
+                    if let Some(row) = table.as_mut().and_then(|table| table.rows.last_mut()) {
+                        row.push(String::new());
+                    }
+                }
+
                 Tag::List(number) => {
                     list_stack.push((number, false));
                 }
@@ -273,16 +328,63 @@ pub async fn parse_markdown_block(
 
             Event::End(tag) => match tag {
                 Tag::Heading(_, _, _) => bold_depth -= 1,
-                Tag::CodeBlock(_) => current_language = None,
+                Tag::CodeBlock(_) => {
+                    current_language = None;
+                    if let Some(start) = code_block_start.take() {
+                        let end = text.trim_end_matches('\n').len();
+                        if start < end {
+                            region_ranges.push(start..end);
+                            regions.push(ParsedRegion {
+                                code: true,
+                                link: None,
+                                image: false,
+                            });
+                        }
+                    }
+                }
                 Tag::Emphasis => italic_depth -= 1,
                 Tag::Strong => bold_depth -= 1,
                 Tag::Link(_, _, _) => link_url = None,
+                Tag::Image(_, url, _) => {
+                    // Images without alternative text are shown as their URL.
+                    if image_start == Some(text.len()) {
+                        text.push_str(url.as_ref());
+                        if let Some(link) = Link::identify(url.to_string()) {
+                            region_ranges.push(prev_len..text.len());
+                            regions.push(ParsedRegion {
+                                code: false,
+                                link: Some(link),
+                                image: true,
+                            });
+                            highlights.push((
+                                prev_len..text.len(),
+                                MarkdownHighlight::Style(MarkdownHighlightStyle {
+                                    underline: true,
+                                    ..Default::default()
+                                }),
+                            ));
+                        }
+                    }
+                    image_start = None;
+                    link_url = None;
+                }
+                Tag::Table(_) => {
+                    if let Some(table) = table.take() {
+                        table.render(text, highlights);
+                    }
+                }
                 Tag::List(_) => drop(list_stack.pop()),
                 _ => {}
             },
 
             Event::HardBreak => text.push('\n'),
 
+            Event::SoftBreak if table.is_some() => {
+                if let Some(table) = table.as_mut() {
+                    table.push_str(" ");
+                }
+            }
+
             Event::SoftBreak => text.push(' '),
 
             _ => {}
@@ -290,6 +392,72 @@ pub async fn parse_markdown_block(
     }
 }
 
+/// A table being parsed, which is rendered once all of its cells are known so that its columns
+/// can be aligned.
+struct Table {
+    alignments: Vec<Alignment>,
+    /// The text of each row's cells, starting with the header row.
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    fn push_str(&mut self, content: &str) {
+        if let Some(cell) = self.rows.last_mut().and_then(|row| row.last_mut()) {
+            cell.push_str(content);
+        }
+    }
+
+    /// Appends the table to the provided `text` buffer, with its columns padded to the same
+    /// width and its header row emboldened.
+    fn render(self, text: &mut String, highlights: &mut Vec<(Range<usize>, MarkdownHighlight)>) {
+        let column_count = self.rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut widths = vec![0; column_count];
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        for (row_ix, row) in self.rows.iter().enumerate() {
+            if row_ix > 0 {
+                text.push('\n');
+            }
+            let row_start = text.len();
+            for (column_ix, width) in widths.iter().enumerate() {
+                if column_ix > 0 {
+                    text.push_str(" │ ");
+                }
+                let cell = row.get(column_ix).map_or("", String::as_str);
+                let padding = width - cell.chars().count();
+                let (left, right) = match self.alignments.get(column_ix) {
+                    Some(Alignment::Right) => (padding, 0),
+                    Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+                    _ => (0, padding),
+                };
+                text.extend(std::iter::repeat(' ').take(left));
+                text.push_str(cell);
+                // Don't leave trailing whitespace after the last column.
+                if column_ix + 1 < column_count {
+                    text.extend(std::iter::repeat(' ').take(right));
+                }
+            }
+
+            if row_ix == 0 {
+                highlights.push((
+                    row_start..text.len(),
+                    MarkdownHighlight::Style(MarkdownHighlightStyle {
+                        weight: FontWeight::BOLD,
+                        ..Default::default()
+                    }),
+                ));
+                text.push('\n');
+                let separators = widths.iter().map(|width| "─".repeat(*width));
+                text.push_str(&separators.collect::<Vec<_>>().join("─┼─"));
+            }
+        }
+    }
+}
+
 /// Appends a highlighted run of text to the provided `text` buffer.
 pub fn highlight_code(
     text: &mut String,
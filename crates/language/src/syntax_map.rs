@@ -383,7 +383,41 @@ impl SyntaxSnapshot {
             .edits_since::<usize>(&self.parsed_version)
             .map(|edit| edit.new)
             .collect::<Vec<_>>();
-        self.reparse_with_ranges(text, root_language.clone(), edit_ranges, registry.as_ref());
+        self.reparse_injections(text, registry, root_language, edit_ranges);
+    }
+
+    /// Reparses only the root layer, leaving the injected layers interpolated until a
+    /// subsequent call to [`Self::reparse_injections`]. Returns the byte ranges in which
+    /// the root layer changed, which that call needs to find the injections to reparse.
+    ///
+    /// This lets the buffer show highlights for the root language sooner when parsing
+    /// the injections of a large file takes a long time.
+    pub fn reparse_root_layer(
+        &mut self,
+        text: &BufferSnapshot,
+        root_language: Arc<Language>,
+    ) -> Vec<Range<usize>> {
+        let edit_ranges = text
+            .edits_since::<usize>(&self.parsed_version)
+            .map(|edit| edit.new)
+            .collect::<Vec<_>>();
+        self.reparse_with_ranges(text, root_language, edit_ranges, None)
+    }
+
+    /// Reparses the layers injected into the given byte ranges of the root layer.
+    pub fn reparse_injections(
+        &mut self,
+        text: &BufferSnapshot,
+        registry: Option<Arc<LanguageRegistry>>,
+        root_language: Arc<Language>,
+        changed_ranges: Vec<Range<usize>>,
+    ) {
+        self.reparse_with_ranges(
+            text,
+            root_language.clone(),
+            changed_ranges,
+            registry.as_ref(),
+        );
 
         if let Some(registry) = registry {
             if registry.version() != self.language_registry_version {
@@ -422,15 +456,18 @@ impl SyntaxSnapshot {
         }
     }
 
+    /// Reparses the layers intersecting the given ranges, returning the byte ranges in
+    /// which the root layer changed. Injections are only reparsed if a registry is given.
     fn reparse_with_ranges(
         &mut self,
         text: &BufferSnapshot,
         root_language: Arc<Language>,
         invalidated_ranges: Vec<Range<usize>>,
         registry: Option<&Arc<LanguageRegistry>>,
-    ) {
+    ) -> Vec<Range<usize>> {
         log::trace!("reparse. invalidated ranges:{:?}", invalidated_ranges);
 
+        let mut root_changed_ranges = Vec::new();
         let max_depth = self.layers.summary().max_depth;
         let mut cursor = self.layers.cursor::<SyntaxLayerSummary>();
         cursor.next(text);
@@ -657,6 +694,9 @@ impl SyntaxSnapshot {
                         );
                         changed_ranges = vec![step_start_byte..step_end_byte];
                     }
+                    if step.depth == 0 {
+                        root_changed_ranges = changed_ranges.clone();
+                    }
 
                     if let (Some((config, registry)), false) = (
                         grammar.injection_config.as_ref().zip(registry.as_ref()),
@@ -711,6 +751,7 @@ impl SyntaxSnapshot {
         self.parsed_version = text.version.clone();
         #[cfg(debug_assertions)]
         self.check_invariants(text);
+        root_changed_ranges
     }
 
     #[cfg(debug_assertions)]
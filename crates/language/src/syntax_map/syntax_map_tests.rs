@@ -253,6 +253,92 @@ fn test_dynamic_language_injection() {
     assert!(!syntax_map.contains_unknown_injections());
 }
 
+#[gpui::test]
+fn test_reparsing_root_layer_before_injections() {
+    let registry = Arc::new(LanguageRegistry::test());
+    let markdown = Arc::new(markdown_lang());
+    registry.add(markdown.clone());
+    registry.add(Arc::new(rust_lang()));
+
+    let mut buffer = Buffer::new(
+        0,
+        BufferId::new(1).unwrap(),
+        r#"
+            This is a code block:
+
+            ```rs
+            fn foo() {}
+            ```
+        "#
+        .unindent(),
+    );
+
+    // The first parse of the root layer doesn't include any injections.
+    let mut syntax_map = SyntaxMap::new();
+    syntax_map.set_language_registry(registry.clone());
+    let mut snapshot = syntax_map.snapshot();
+    let changed_ranges = snapshot.reparse_root_layer(&buffer, markdown.clone());
+    syntax_map.did_parse(snapshot.clone());
+    assert_layers_for_range(
+        &syntax_map,
+        &buffer,
+        Point::new(3, 0)..Point::new(3, 0),
+        &["...(fenced_code_block (fenced_code_block_delimiter) (info_string (language)) (code_fence_content) (fenced_code_block_delimiter..."],
+    );
+
+    snapshot.reparse_injections(
+        &buffer,
+        Some(registry.clone()),
+        markdown.clone(),
+        changed_ranges,
+    );
+    syntax_map.did_parse(snapshot);
+    assert_layers_for_range(
+        &syntax_map,
+        &buffer,
+        Point::new(3, 0)..Point::new(3, 0),
+        &[
+            "...(fenced_code_block (fenced_code_block_delimiter) (info_string (language)) (code_fence_content) (fenced_code_block_delimiter...",
+            "...(function_item name: (identifier) parameters: (parameters) body: (block)...",
+        ],
+    );
+
+    // After an edit, reparsing the root layer keeps the interpolated injection around
+    // until the injections are reparsed.
+    let parameters_range = range_for_text(&buffer, "()");
+    buffer.edit([(parameters_range, "(bar: u32)")]);
+    syntax_map.interpolate(&buffer);
+    let mut snapshot = syntax_map.snapshot();
+    let changed_ranges = snapshot.reparse_root_layer(&buffer, markdown.clone());
+    syntax_map.did_parse(snapshot.clone());
+    assert_layers_for_range(
+        &syntax_map,
+        &buffer,
+        Point::new(3, 0)..Point::new(3, 0),
+        &[
+            "...(fenced_code_block (fenced_code_block_delimiter) (info_string (language)) (code_fence_content) (fenced_code_block_delimiter...",
+            "...(function_item name: (identifier) parameters: (parameters) body: (block)...",
+        ],
+    );
+
+    snapshot.reparse_injections(
+        &buffer,
+        Some(registry.clone()),
+        markdown.clone(),
+        changed_ranges,
+    );
+    syntax_map.did_parse(snapshot);
+    assert_layers_for_range(
+        &syntax_map,
+        &buffer,
+        Point::new(3, 0)..Point::new(3, 0),
+        &[
+            "...(fenced_code_block (fenced_code_block_delimiter) (info_string (language)) (code_fence_content) (fenced_code_block_delimiter...",
+            "...(function_item name: (identifier) parameters: (parameters (parameter pattern: (identifier) type: (primitive_type))) body: (block)...",
+        ],
+    );
+}
+
 #[gpui::test]
 fn test_typing_multiple_new_injections() {
     let (buffer, syntax_map) = test_edit_sequence(
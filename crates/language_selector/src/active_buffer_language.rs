@@ -61,6 +61,10 @@ impl Render for ActiveBufferLanguage {
 }
 
 impl StatusItemView for ActiveBufferLanguage {
+    fn status_item_id(&self) -> &'static str {
+        "language"
+    }
+
     fn set_active_pane_item(
         &mut self,
         active_pane_item: Option<&dyn ItemHandle>,
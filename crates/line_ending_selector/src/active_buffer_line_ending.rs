@@ -0,0 +1,80 @@
+use editor::Editor;
+use gpui::{div, IntoElement, ParentElement, Render, Subscription, View, ViewContext, WeakView};
+use ui::{Button, ButtonCommon, Clickable, FluentBuilder, LabelSize, Tooltip};
+use workspace::{item::ItemHandle, StatusItemView, Workspace};
+
+use crate::{line_ending_label, LineEndingSelector};
+
+pub struct ActiveBufferLineEnding {
+    active_line_ending: Option<&'static str>,
+    workspace: WeakView<Workspace>,
+    _observe_active_editor: Option<Subscription>,
+    _observe_active_buffer: Option<Subscription>,
+}
+
+impl ActiveBufferLineEnding {
+    pub fn new(workspace: &Workspace) -> Self {
+        Self {
+            active_line_ending: None,
+            workspace: workspace.weak_handle(),
+            _observe_active_editor: None,
+            _observe_active_buffer: None,
+        }
+    }
+
+    fn update_line_ending(&mut self, editor: View<Editor>, cx: &mut ViewContext<Self>) {
+        self.active_line_ending = None;
+        self._observe_active_buffer = None;
+
+        if let Some((_, buffer, _)) = editor.read(cx).active_excerpt(cx) {
+            self.active_line_ending = Some(line_ending_label(buffer.read(cx).line_ending()));
+            self._observe_active_buffer = Some(cx.observe(&buffer, move |this, _, cx| {
+                this.update_line_ending(editor.clone(), cx)
+            }));
+        }
+
+        cx.notify();
+    }
+}
+
+impl Render for ActiveBufferLineEnding {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        div().when_some(self.active_line_ending, |el, active_line_ending| {
+            el.child(
+                Button::new("change-line-ending", active_line_ending)
+                    .label_size(LabelSize::Small)
+                    .on_click(cx.listener(|this, _, cx| {
+                        if let Some(workspace) = this.workspace.upgrade() {
+                            workspace.update(cx, |workspace, cx| {
+                                LineEndingSelector::toggle(workspace, cx)
+                            });
+                        }
+                    }))
+                    .tooltip(|cx| Tooltip::text("Select Line Ending", cx)),
+            )
+        })
+    }
+}
+
+impl StatusItemView for ActiveBufferLineEnding {
+    fn status_item_id(&self) -> &'static str {
+        "line_ending"
+    }
+
+    fn set_active_pane_item(
+        &mut self,
+        active_pane_item: Option<&dyn ItemHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(editor) = active_pane_item.and_then(|item| item.act_as::<Editor>(cx)) {
+            self._observe_active_editor = Some(cx.observe(&editor, Self::update_line_ending));
+            self.update_line_ending(editor, cx);
+        } else {
+            self.active_line_ending = None;
+            self._observe_active_editor = None;
+            self._observe_active_buffer = None;
+        }
+
+        cx.notify();
+    }
+}
@@ -175,10 +175,12 @@ public func LKRoomDisconnect(room: UnsafeRawPointer) {
 }
 
 @_cdecl("LKRoomPublishVideoTrack")
-public func LKRoomPublishVideoTrack(room: UnsafeRawPointer, track: UnsafeRawPointer, callback: @escaping @convention(c) (UnsafeRawPointer, UnsafeMutableRawPointer?, CFString?) -> Void, callback_data: UnsafeRawPointer) {
+public func LKRoomPublishVideoTrack(room: UnsafeRawPointer, track: UnsafeRawPointer, maxBitrate: UInt32, maxFramerate: UInt32, callback: @escaping @convention(c) (UnsafeRawPointer, UnsafeMutableRawPointer?, CFString?) -> Void, callback_data: UnsafeRawPointer) {
     let room = Unmanaged<Room>.fromOpaque(room).takeUnretainedValue()
     let track = Unmanaged<LocalVideoTrack>.fromOpaque(track).takeUnretainedValue()
-    room.localParticipant?.publishVideoTrack(track: track).then { publication in
+    let encoding = VideoEncoding(maxBitrate: Int(maxBitrate), maxFps: Int(maxFramerate))
+    let options = VideoPublishOptions(encoding: encoding, screenShareEncoding: encoding)
+    room.localParticipant?.publishVideoTrack(track: track, publishOptions: options).then { publication in
         callback(callback_data, Unmanaged.passRetained(publication).toOpaque(), nil)
     }.catch { error in
         callback(callback_data, nil, error.localizedDescription as CFString)
@@ -14,6 +14,14 @@ pub use test::*;
 
 pub type Sid = String;
 
+/// The limits a published video track is encoded within.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VideoEncoding {
+    /// In bits per second.
+    pub max_bitrate: u32,
+    pub max_framerate: u32,
+}
+
 #[derive(Clone, Eq, PartialEq)]
 pub enum ConnectionState {
     Disconnected,
@@ -1,4 +1,4 @@
-use crate::{ConnectionState, RoomUpdate, Sid};
+use crate::{ConnectionState, RoomUpdate, Sid, VideoEncoding};
 use anyhow::{anyhow, Context, Result};
 use core_foundation::{
     array::{CFArray, CFArrayRef},
@@ -99,6 +99,8 @@ extern "C" {
     fn LKRoomPublishVideoTrack(
         room: swift::Room,
         track: swift::LocalVideoTrack,
+        max_bitrate: u32,
+        max_framerate: u32,
         callback: extern "C" fn(*mut c_void, swift::LocalTrackPublication, CFStringRef),
         callback_data: *mut c_void,
     );
@@ -253,6 +255,7 @@ impl Room {
     pub fn publish_video_track(
         self: &Arc<Self>,
         track: LocalVideoTrack,
+        encoding: VideoEncoding,
     ) -> impl Future<Output = Result<LocalTrackPublication>> {
         let (tx, rx) = oneshot::channel::<Result<LocalTrackPublication>>();
         extern "C" fn callback(
@@ -273,6 +276,8 @@ impl Room {
             LKRoomPublishVideoTrack(
                 self.native_room,
                 track.0,
+                encoding.max_bitrate,
+                encoding.max_framerate,
                 callback,
                 Box::into_raw(Box::new(tx)) as *mut c_void,
             );
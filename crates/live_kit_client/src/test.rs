@@ -1,4 +1,4 @@
-use crate::{ConnectionState, RoomUpdate, Sid};
+use crate::{ConnectionState, RoomUpdate, Sid, VideoEncoding};
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use collections::{BTreeMap, HashMap, HashSet};
@@ -549,6 +549,7 @@ impl Room {
     pub fn publish_video_track(
         self: &Arc<Self>,
         track: LocalVideoTrack,
+        _encoding: VideoEncoding,
     ) -> impl Future<Output = Result<LocalTrackPublication>> {
         let this = self.clone();
         let track = track.clone();
@@ -66,6 +66,7 @@ pub struct LanguageServer {
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<usize, ResponseHandler>>>>,
     io_handlers: Arc<Mutex<HashMap<usize, IoHandler>>>,
+    request_latencies: Arc<Mutex<HashMap<&'static str, RequestLatency>>>,
     executor: BackgroundExecutor,
     #[allow(clippy::type_complexity)]
     io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
@@ -74,6 +75,32 @@ pub struct LanguageServer {
     _server: Option<Mutex<Child>>,
 }
 
+/// How long a language server has taken to respond to requests of a given method.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RequestLatency {
+    /// The number of requests that have received a response.
+    pub count: usize,
+    /// The time taken by the most recent request.
+    pub last: Duration,
+    /// The time taken by the slowest request.
+    pub max: Duration,
+    total: Duration,
+}
+
+impl RequestLatency {
+    fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.last = elapsed;
+        self.max = self.max.max(elapsed);
+        self.total += elapsed;
+    }
+
+    /// The average time taken to respond to a request.
+    pub fn average(&self) -> Duration {
+        self.total / self.count.max(1) as u32
+    }
+}
+
 /// Identifies a running language server.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[repr(transparent)]
@@ -157,6 +184,7 @@ impl LanguageServer {
         stderr_capture: Arc<Mutex<Option<String>>>,
         server_id: LanguageServerId,
         binary: LanguageServerBinary,
+        env: Option<HashMap<String, String>>,
         root_path: &Path,
         code_action_kinds: Option<Vec<CodeActionKind>>,
         cx: AsyncAppContext,
@@ -170,6 +198,7 @@ impl LanguageServer {
         let mut server = process::Command::new(&binary.path)
             .current_dir(working_dir)
             .args(binary.arguments)
+            .envs(env.unwrap_or_default())
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -282,6 +311,7 @@ impl LanguageServer {
             notification_handlers,
             response_handlers,
             io_handlers,
+            request_latencies: Default::default(),
             name: Default::default(),
             capabilities: Default::default(),
             code_action_kinds,
@@ -469,6 +499,11 @@ impl LanguageServer {
                         dynamic_registration: Some(true),
                     }),
                     workspace_folders: Some(true),
+                    file_operations: Some(WorkspaceFileOperationsClientCapabilities {
+                        will_rename: Some(true),
+                        did_rename: Some(true),
+                        ..WorkspaceFileOperationsClientCapabilities::default()
+                    }),
                     symbol: Some(WorkspaceSymbolClientCapabilities {
                         resolve_support: None,
                         ..WorkspaceSymbolClientCapabilities::default()
@@ -514,6 +549,7 @@ impl LanguageServer {
                     completion: Some(CompletionClientCapabilities {
                         completion_item: Some(CompletionItemCapability {
                             snippet_support: Some(true),
+                            commit_characters_support: Some(true),
                             resolve_support: Some(CompletionItemCapabilityResolveSupport {
                                 properties: vec!["additionalTextEdits".to_string()],
                             }),
@@ -537,6 +573,20 @@ impl LanguageServer {
                         content_format: Some(vec![MarkupKind::Markdown]),
                         dynamic_registration: None,
                     }),
+                    signature_help: Some(SignatureHelpClientCapabilities {
+                        signature_information: Some(SignatureInformationSettings {
+                            documentation_format: Some(vec![
+                                MarkupKind::Markdown,
+                                MarkupKind::PlainText,
+                            ]),
+                            parameter_information: Some(ParameterInformationSettings {
+                                label_offset_support: Some(true),
+                            }),
+                            active_parameter_support: Some(true),
+                        }),
+                        context_support: Some(true),
+                        dynamic_registration: None,
+                    }),
                     inlay_hint: Some(InlayHintClientCapabilities {
                         resolve_support: Some(InlayHintResolveClientCapabilities {
                             properties: vec![
@@ -801,6 +851,16 @@ impl LanguageServer {
         self.server_id
     }
 
+    /// Get the id of the language server's process, if Zed launched it.
+    pub fn process_id(&self) -> Option<u32> {
+        self._server.as_ref().map(|server| server.lock().id())
+    }
+
+    /// Get how long the language server has taken to respond to requests, by method.
+    pub fn request_latencies(&self) -> HashMap<&'static str, RequestLatency> {
+        self.request_latencies.lock().clone()
+    }
+
     /// Get the root path of the project the language server is running against.
     pub fn root_path(&self) -> &PathBuf {
         &self.root_path
@@ -816,13 +876,26 @@ impl LanguageServer {
     where
         T::Result: 'static + Send,
     {
-        Self::request_internal::<T>(
+        let request_latencies = self.request_latencies.clone();
+        let started = Instant::now();
+        let response = Self::request_internal::<T>(
             &self.next_id,
             &self.response_handlers,
             &self.outbound_tx,
             &self.executor,
             params,
-        )
+        );
+        async move {
+            let response = response.await;
+            if response.is_ok() {
+                request_latencies
+                    .lock()
+                    .entry(T::METHOD)
+                    .or_default()
+                    .record(started.elapsed());
+            }
+            response
+        }
     }
 
     fn request_internal<T: request::Request>(
@@ -1269,4 +1342,36 @@ mod tests {
         drop(server);
         fake.receive_notification::<notification::Exit>().await;
     }
+
+    #[gpui::test]
+    async fn test_request_latencies(cx: &mut TestAppContext) {
+        let (server, fake) =
+            FakeLanguageServer::new("the-lsp".to_string(), Default::default(), cx.to_async());
+        let server = server.initialize(None).await.unwrap();
+
+        fake.handle_request::<request::Shutdown, _, _>(|_, _| async move { Ok(()) });
+        fake.handle_request::<request::Completion, _, _>(|_, _| async move {
+            Err(anyhow!("no completions"))
+        });
+        server.request::<request::Shutdown>(()).await.unwrap();
+        server.request::<request::Shutdown>(()).await.unwrap();
+        server
+            .request::<request::Completion>(CompletionParams {
+                text_document_position: TextDocumentPositionParams::new(
+                    TextDocumentIdentifier::new(Url::from_str("file://a/b").unwrap()),
+                    Position::new(0, 0),
+                ),
+                work_done_progress_params: Default::default(),
+                partial_result_params: Default::default(),
+                context: None,
+            })
+            .await
+            .unwrap_err();
+
+        // Only requests that received a successful response are recorded.
+        let latencies = server.request_latencies();
+        assert_eq!(latencies["initialize"].count, 1);
+        assert_eq!(latencies["shutdown"].count, 2);
+        assert!(!latencies.contains_key("textDocument/completion"));
+    }
 }
@@ -162,6 +162,15 @@ impl NodeRuntime for RealNodeRuntime {
                 "--globalconfig".into(),
                 installation_path.join("blank_global_npmrc"),
             ]);
+            // npm can only be pointed at HTTP proxies.
+            if let Some(proxy) = self
+                .http
+                .proxy()
+                .filter(|proxy| matches!(proxy.scheme_str(), Some("http") | Some("https")))
+            {
+                let proxy = proxy.to_string();
+                command.args(["--proxy", &proxy, "--https-proxy", &proxy]);
+            }
             command.args(args);
 
             if let Some(directory) = directory {
@@ -11,7 +11,6 @@ use gpui::{
 use language::Outline;
 use ordered_float::OrderedFloat;
 use picker::{Picker, PickerDelegate};
-use settings::Settings;
 use std::{
     cmp::{self, Reverse},
     sync::Arc,
@@ -267,7 +266,7 @@ impl PickerDelegate for OutlineViewDelegate {
         selected: bool,
         cx: &mut ViewContext<Picker<Self>>,
     ) -> Option<Self::ListItem> {
-        let settings = ThemeSettings::get_global(cx);
+        let settings = ThemeSettings::for_window(cx);
 
         // TODO: We probably shouldn't need to build a whole new text style here
         // but I'm not sure how to get the current one and modify it.
@@ -275,7 +274,8 @@ impl PickerDelegate for OutlineViewDelegate {
         let text_style = TextStyle {
             color: cx.theme().colors().text,
             font_family: settings.buffer_font.family.clone(),
-            font_features: settings.buffer_font.features,
+            font_features: settings.buffer_font.features.clone(),
+            font_fallbacks: settings.buffer_font.fallbacks.clone(),
             font_size: settings.buffer_font_size(cx).into(),
             font_weight: FontWeight::NORMAL,
             font_style: FontStyle::Normal,
@@ -226,8 +226,10 @@ impl<D: PickerDelegate> Picker<D> {
     }
 
     pub fn set_query(&self, query: impl Into<Arc<str>>, cx: &mut ViewContext<Self>) {
-        self.editor
-            .update(cx, |editor, cx| editor.set_text(query, cx));
+        self.editor.update(cx, |editor, cx| {
+            editor.set_text(query, cx);
+            editor.move_to_end(&Default::default(), cx);
+        });
     }
 }
 
@@ -265,6 +267,7 @@ impl<D: PickerDelegate> Render for Picker<D> {
             .when(self.delegate.match_count() > 0, |el| {
                 el.child(
                     v_flex()
+                        .accessibility_role(AccessibilityRole::List)
                         .flex_grow()
                         .py_2()
                         .max_h(self.max_height.unwrap_or(rems(18.).into()))
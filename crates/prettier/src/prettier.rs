@@ -193,6 +193,7 @@ impl Prettier {
                 path: node_path,
                 arguments: vec![prettier_server.into(), prettier_dir.as_path().into()],
             },
+            None,
             Path::new("/"),
             None,
             cx,
@@ -1,7 +1,7 @@
 use crate::{
     DocumentHighlight, Hover, HoverBlock, HoverBlockKind, InlayHint, InlayHintLabel,
     InlayHintLabelPart, InlayHintLabelPartTooltip, InlayHintTooltip, Location, LocationLink,
-    MarkupContent, Project, ProjectTransaction, ResolveState,
+    MarkupContent, Project, ProjectTransaction, ResolveState, Signature, SignatureHelp,
 };
 use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
@@ -117,6 +117,12 @@ pub(crate) struct GetHover {
     pub position: PointUtf16,
 }
 
+pub(crate) struct GetSignatureHelp {
+    pub position: PointUtf16,
+    pub trigger_character: Option<String>,
+    pub is_retrigger: bool,
+}
+
 pub(crate) struct GetCompletions {
     pub position: PointUtf16,
 }
@@ -1320,6 +1326,279 @@ impl LspCommand for GetHover {
     }
 }
 
+#[async_trait(?Send)]
+impl LspCommand for GetSignatureHelp {
+    type Response = Option<SignatureHelp>;
+    type LspRequest = lsp::request::SignatureHelpRequest;
+    type ProtoRequest = proto::GetSignatureHelp;
+
+    fn check_capabilities(&self, capabilities: &ServerCapabilities) -> bool {
+        capabilities.signature_help_provider.is_some()
+    }
+
+    fn to_lsp(
+        &self,
+        path: &Path,
+        _: &Buffer,
+        _: &Arc<LanguageServer>,
+        _: &AppContext,
+    ) -> lsp::SignatureHelpParams {
+        let trigger_kind = if self.trigger_character.is_some() {
+            lsp::SignatureHelpTriggerKind::TRIGGER_CHARACTER
+        } else if self.is_retrigger {
+            lsp::SignatureHelpTriggerKind::CONTENT_CHANGE
+        } else {
+            lsp::SignatureHelpTriggerKind::INVOKED
+        };
+        lsp::SignatureHelpParams {
+            text_document_position_params: lsp::TextDocumentPositionParams {
+                text_document: lsp::TextDocumentIdentifier {
+                    uri: lsp::Url::from_file_path(path).unwrap(),
+                },
+                position: point_to_lsp(self.position),
+            },
+            context: Some(lsp::SignatureHelpContext {
+                trigger_kind,
+                trigger_character: self.trigger_character.clone(),
+                is_retrigger: self.is_retrigger,
+                active_signature_help: None,
+            }),
+            work_done_progress_params: Default::default(),
+        }
+    }
+
+    async fn response_from_lsp(
+        self,
+        message: Option<lsp::SignatureHelp>,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: LanguageServerId,
+        _: AsyncAppContext,
+    ) -> Result<Self::Response> {
+        let Some(help) = message.filter(|help| !help.signatures.is_empty()) else {
+            return Ok(None);
+        };
+
+        let signature_count = help.signatures.len();
+        let signatures = help
+            .signatures
+            .into_iter()
+            .map(|signature| {
+                // Parameters are looked for in order after the opening parenthesis, so that one
+                // named like the function or an earlier parameter isn't found in their place.
+                let mut search_start = signature.label.find('(').map_or(0, |ix| ix + 1);
+                let parameters = signature
+                    .parameters
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|parameter| {
+                        let range = signature_parameter_range(
+                            &signature.label,
+                            search_start,
+                            parameter.label,
+                        )?;
+                        search_start = range.end;
+                        Some(range)
+                    })
+                    .collect();
+                // A signature's own active parameter takes precedence over the response's.
+                let active_parameter = signature
+                    .active_parameter
+                    .or(help.active_parameter)
+                    .map(|ix| ix as usize);
+                let documentation =
+                    signature
+                        .documentation
+                        .map(|documentation| match documentation {
+                            lsp::Documentation::String(text) => HoverBlock {
+                                text,
+                                kind: HoverBlockKind::PlainText,
+                            },
+                            lsp::Documentation::MarkupContent(content) => HoverBlock {
+                                text: content.value,
+                                kind: if content.kind == lsp::MarkupKind::Markdown {
+                                    HoverBlockKind::Markdown
+                                } else {
+                                    HoverBlockKind::PlainText
+                                },
+                            },
+                        });
+                Signature {
+                    label: signature.label,
+                    parameters,
+                    active_parameter,
+                    documentation,
+                }
+            })
+            .collect();
+
+        Ok(Some(SignatureHelp {
+            signatures,
+            active_signature: (help.active_signature.unwrap_or(0) as usize)
+                .min(signature_count - 1),
+        }))
+    }
+
+    fn to_proto(&self, project_id: u64, buffer: &Buffer) -> Self::ProtoRequest {
+        proto::GetSignatureHelp {
+            project_id,
+            buffer_id: buffer.remote_id().into(),
+            position: Some(language::proto::serialize_anchor(
+                &buffer.anchor_before(self.position),
+            )),
+            version: serialize_version(&buffer.version),
+            trigger_character: self.trigger_character.clone(),
+            is_retrigger: self.is_retrigger,
+        }
+    }
+
+    async fn from_proto(
+        message: Self::ProtoRequest,
+        _: Model<Project>,
+        buffer: Model<Buffer>,
+        mut cx: AsyncAppContext,
+    ) -> Result<Self> {
+        let position = message
+            .position
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid position"))?;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&message.version))
+            })?
+            .await?;
+        Ok(Self {
+            position: buffer.update(&mut cx, |buffer, _| position.to_point_utf16(buffer))?,
+            trigger_character: message.trigger_character,
+            is_retrigger: message.is_retrigger,
+        })
+    }
+
+    fn response_to_proto(
+        response: Self::Response,
+        _: &mut Project,
+        _: PeerId,
+        _: &clock::Global,
+        _: &mut AppContext,
+    ) -> proto::GetSignatureHelpResponse {
+        let Some(response) = response else {
+            return proto::GetSignatureHelpResponse {
+                signatures: Vec::new(),
+                active_signature: 0,
+            };
+        };
+
+        proto::GetSignatureHelpResponse {
+            signatures: response
+                .signatures
+                .into_iter()
+                .map(|signature| proto::Signature {
+                    label: signature.label,
+                    parameters: signature
+                        .parameters
+                        .into_iter()
+                        .map(|range| proto::SignatureParameter {
+                            start: range.as_ref().map(|range| range.start as u64),
+                            end: range.map(|range| range.end as u64),
+                        })
+                        .collect(),
+                    active_parameter: signature.active_parameter.map(|ix| ix as u64),
+                    documentation: signature.documentation.map(|block| proto::HoverBlock {
+                        text: block.text,
+                        is_markdown: block.kind == HoverBlockKind::Markdown,
+                        language: if let HoverBlockKind::Code { language } = block.kind {
+                            Some(language)
+                        } else {
+                            None
+                        },
+                    }),
+                })
+                .collect(),
+            active_signature: response.active_signature as u64,
+        }
+    }
+
+    async fn response_from_proto(
+        self,
+        message: proto::GetSignatureHelpResponse,
+        _: Model<Project>,
+        _: Model<Buffer>,
+        _: AsyncAppContext,
+    ) -> Result<Self::Response> {
+        if message.signatures.is_empty() {
+            return Ok(None);
+        }
+
+        let signature_count = message.signatures.len();
+        let signatures = message
+            .signatures
+            .into_iter()
+            .map(|signature| Signature {
+                parameters: signature
+                    .parameters
+                    .into_iter()
+                    .map(|range| {
+                        let range = range.start? as usize..range.end? as usize;
+                        (range.start <= range.end && signature.label.get(range.clone()).is_some())
+                            .then_some(range)
+                    })
+                    .collect(),
+                label: signature.label,
+                active_parameter: signature.active_parameter.map(|ix| ix as usize),
+                documentation: signature.documentation.map(|block| HoverBlock {
+                    text: block.text,
+                    kind: if let Some(language) = block.language {
+                        HoverBlockKind::Code { language }
+                    } else if block.is_markdown {
+                        HoverBlockKind::Markdown
+                    } else {
+                        HoverBlockKind::PlainText
+                    },
+                }),
+            })
+            .collect();
+
+        Ok(Some(SignatureHelp {
+            signatures,
+            active_signature: (message.active_signature as usize).min(signature_count - 1),
+        }))
+    }
+
+    fn buffer_id_from_proto(message: &Self::ProtoRequest) -> Result<BufferId> {
+        BufferId::new(message.buffer_id)
+    }
+}
+
+/// Finds the byte range of a parameter within its signature's label, which language servers
+/// give either as a substring of the label or as offsets in UTF-16 code units. Substrings are
+/// searched for from `search_start` onwards.
+fn signature_parameter_range(
+    label: &str,
+    search_start: usize,
+    parameter: lsp::ParameterLabel,
+) -> Option<Range<usize>> {
+    match parameter {
+        lsp::ParameterLabel::Simple(name) => {
+            let start = search_start + label.get(search_start..)?.find(name.as_str())?;
+            Some(start..start + name.len())
+        }
+        lsp::ParameterLabel::LabelOffsets([start, end]) => {
+            let mut utf16_offset = 0;
+            let mut range_start = None;
+            for (offset, char) in label.char_indices() {
+                if utf16_offset == start as usize {
+                    range_start = Some(offset);
+                }
+                if utf16_offset == end as usize {
+                    return Some(range_start?..offset);
+                }
+                utf16_offset += char.len_utf16();
+            }
+            (utf16_offset == end as usize).then_some(range_start?..label.len())
+        }
+    }
+}
+
 #[async_trait(?Send)]
 impl LspCommand for GetCompletions {
     type Response = Vec<Completion>;
@@ -1367,6 +1646,21 @@ impl LspCommand for GetCompletions {
             Default::default()
         };
 
+        // Commit characters can also be given for all of a response's completions, or for all of
+        // a language server's.
+        let default_commit_characters = match response_list
+            .as_ref()
+            .and_then(|list| list.item_defaults.as_ref())
+            .and_then(|defaults| defaults.commit_characters.clone())
+        {
+            Some(commit_characters) => Some(commit_characters),
+            None => project.update(&mut cx, |project, _| {
+                let server = project.language_server_for_id(server_id)?;
+                let options = server.capabilities().completion_provider.clone()?;
+                options.all_commit_characters
+            })?,
+        };
+
         let completions = buffer.update(&mut cx, |buffer, cx| {
             let language_registry = project.read(cx).languages().clone();
             let language = buffer.language().cloned();
@@ -1377,6 +1671,10 @@ impl LspCommand for GetCompletions {
             completions
                 .into_iter()
                 .filter_map(move |mut lsp_completion| {
+                    if lsp_completion.commit_characters.is_none() {
+                        lsp_completion.commit_characters = default_commit_characters.clone();
+                    }
+
                     let (old_range, mut new_text) = match lsp_completion.text_edit.as_ref() {
                         // If the language server provides a range to overwrite, then
                         // check that the range is valid.
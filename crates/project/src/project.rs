@@ -2,8 +2,11 @@ mod ignore;
 pub mod lsp_command;
 pub mod lsp_ext_command;
 mod prettier_support;
+pub mod project_environment;
 pub mod project_settings;
+pub mod project_trust;
 pub mod search;
+mod search_index;
 pub mod terminals;
 pub mod worktree;
 
@@ -26,7 +29,7 @@ use futures::{
     stream::FuturesUnordered,
     AsyncWriteExt, Future, FutureExt, StreamExt, TryFutureExt,
 };
-use globset::{Glob, GlobSet, GlobSetBuilder};
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
 use gpui::{
     AnyModel, AppContext, AsyncAppContext, BackgroundExecutor, Context, Entity, EventEmitter,
     Model, ModelContext, Task, WeakModel,
@@ -55,11 +58,14 @@ use node_runtime::NodeRuntime;
 use parking_lot::{Mutex, RwLock};
 use postage::watch;
 use prettier_support::{DefaultPrettier, PrettierInstance};
+use project_environment::ProjectEnvironment;
 use project_settings::{LspSettings, ProjectSettings};
+use project_trust::ProjectTrust;
 use rand::prelude::*;
 use search::SearchQuery;
+use search_index::SearchIndex;
 use serde::Serialize;
-use settings::{Settings, SettingsStore};
+use settings::{Settings, SettingsStore, EDITORCONFIG_FILE_NAME};
 use sha2::{Digest, Sha256};
 use similar::{ChangeTag, TextDiff};
 use smol::channel::{Receiver, Sender};
@@ -67,6 +73,7 @@ use smol::lock::Semaphore;
 use std::{
     cmp::{self, Ordering},
     convert::TryInto,
+    ffi::OsStr,
     hash::Hash,
     mem,
     num::NonZeroU32,
@@ -138,6 +145,7 @@ pub struct Project {
     /// Used for re-issuing buffer requests when peers temporarily disconnect
     incomplete_remote_buffers: HashMap<BufferId, Option<Model<Buffer>>>,
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
+    buffers_last_used: HashMap<BufferId, Instant>,
     buffers_being_formatted: HashSet<BufferId>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DelayedDebounced,
@@ -152,6 +160,10 @@ pub struct Project {
     default_prettier: DefaultPrettier,
     prettiers_per_worktree: HashMap<WorktreeId, HashSet<Option<PathBuf>>>,
     prettier_instances: HashMap<PathBuf, PrettierInstance>,
+    search_index: Arc<SearchIndex>,
+    /// Worktrees whose trust has been requested, so that the user is only asked once.
+    worktree_trust_requests: HashSet<WorktreeId>,
+    environments: HashMap<WorktreeId, Shared<Task<Option<Arc<ProjectEnvironment>>>>>,
 }
 
 struct DelayedDebounced {
@@ -289,6 +301,9 @@ pub enum Event {
     CollaboratorLeft(proto::PeerId),
     RefreshInlayHints,
     RevealInProjectPanel(ProjectEntryId),
+    /// A worktree contains project settings or environment files, but the user hasn't decided
+    /// whether to trust it.
+    WorktreeTrustRequested(WorktreeId),
 }
 
 pub enum LanguageServerState {
@@ -445,6 +460,24 @@ impl Hover {
     }
 }
 
+/// The signatures of the call surrounding a position, such as the overloads of a function.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SignatureHelp {
+    pub signatures: Vec<Signature>,
+    /// The index of the signature that best matches the call's arguments.
+    pub active_signature: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Signature {
+    pub label: String,
+    /// The byte range of each of the signature's parameters within its label, if it was found.
+    pub parameters: Vec<Option<Range<usize>>>,
+    /// The index of the parameter the position is within.
+    pub active_parameter: Option<usize>,
+    pub documentation: Option<HoverBlock>,
+}
+
 #[derive(Default)]
 pub struct ProjectTransaction(pub HashMap<Model<Buffer>, language::Transaction>);
 
@@ -602,6 +635,7 @@ impl Project {
         client.add_model_request_handler(Self::handle_lsp_command::<GetCodeActions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetHover>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSignatureHelp>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetTypeDefinition>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentHighlights>);
@@ -645,6 +679,7 @@ impl Project {
                 local_buffer_ids_by_path: Default::default(),
                 local_buffer_ids_by_entry_id: Default::default(),
                 buffer_snapshots: Default::default(),
+                buffers_last_used: Default::default(),
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 opened_buffer: watch::channel(),
@@ -682,6 +717,9 @@ impl Project {
                 default_prettier: DefaultPrettier::default(),
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
+                search_index: Default::default(),
+                worktree_trust_requests: Default::default(),
+                environments: Default::default(),
             }
         })
     }
@@ -776,6 +814,7 @@ impl Project {
                 buffers_needing_diff: Default::default(),
                 git_diff_debouncer: DelayedDebounced::new(),
                 buffer_snapshots: Default::default(),
+                buffers_last_used: Default::default(),
                 nonce: StdRng::from_entropy().gen(),
                 terminals: Terminals {
                     local_handles: Vec::new(),
@@ -787,6 +826,9 @@ impl Project {
                 default_prettier: DefaultPrettier::default(),
                 prettiers_per_worktree: HashMap::default(),
                 prettier_instances: HashMap::default(),
+                search_index: Default::default(),
+                worktree_trust_requests: Default::default(),
+                environments: Default::default(),
             };
             this.set_role(role, cx);
             for worktree in worktrees {
@@ -904,7 +946,7 @@ impl Project {
                     language_formatters_to_check.push((
                         buffer_file.map(|f| f.worktree_id(cx)),
                         Arc::clone(language),
-                        settings.clone(),
+                        settings,
                     ));
                 }
             }
@@ -1245,11 +1287,84 @@ impl Project {
         };
         let new_path = new_path.into();
         if self.is_local() {
-            worktree.update(cx, |worktree, cx| {
-                worktree
-                    .as_local_mut()
-                    .unwrap()
-                    .rename_entry(entry_id, new_path, cx)
+            let worktree_id = worktree.read(cx).id();
+            let worktree_abs_path = worktree.read(cx).abs_path();
+            let Some(entry) = worktree.read(cx).entry_for_id(entry_id) else {
+                return Task::ready(Ok(None));
+            };
+            let is_dir = entry.is_dir();
+            let old_abs_path = worktree_abs_path.join(&entry.path);
+            let file_rename = lsp::Url::from_file_path(&old_abs_path)
+                .ok()
+                .zip(lsp::Url::from_file_path(worktree_abs_path.join(&new_path)).ok())
+                .map(|(old_uri, new_uri)| lsp::FileRename {
+                    old_uri: old_uri.to_string(),
+                    new_uri: new_uri.to_string(),
+                });
+
+            // Language servers that registered for renames of this path are asked for the
+            // edits that keep references to it working, such as updated imports, before the
+            // rename, and are notified after it.
+            let mut will_rename_servers = Vec::new();
+            let mut did_rename_servers = Vec::new();
+            if file_rename.is_some() {
+                for (adapter, _, server) in self.language_servers_for_worktree(worktree_id) {
+                    let Some(file_operations) = server
+                        .capabilities()
+                        .workspace
+                        .as_ref()
+                        .and_then(|workspace| workspace.file_operations.as_ref())
+                    else {
+                        continue;
+                    };
+                    let matches = |options: &Option<lsp::FileOperationRegistrationOptions>| {
+                        options.as_ref().map_or(false, |options| {
+                            file_operation_filters_match(&options.filters, &old_abs_path, is_dir)
+                        })
+                    };
+                    if matches(&file_operations.will_rename) {
+                        will_rename_servers.push((adapter.clone(), server.clone()));
+                    }
+                    if matches(&file_operations.did_rename) {
+                        did_rename_servers.push(server.clone());
+                    }
+                }
+            }
+
+            cx.spawn(move |this, mut cx| async move {
+                if let Some(file_rename) = &file_rename {
+                    for (adapter, server) in will_rename_servers {
+                        Self::will_rename_file(
+                            this.clone(),
+                            file_rename.clone(),
+                            adapter,
+                            server,
+                            &mut cx,
+                        )
+                        .await
+                        .log_err();
+                    }
+                }
+
+                let entry = worktree
+                    .update(&mut cx, |worktree, cx| {
+                        worktree
+                            .as_local_mut()
+                            .unwrap()
+                            .rename_entry(entry_id, new_path, cx)
+                    })?
+                    .await?;
+
+                if let Some(file_rename) = file_rename {
+                    for server in did_rename_servers {
+                        server
+                            .notify::<lsp::notification::DidRenameFiles>(lsp::RenameFilesParams {
+                                files: vec![file_rename.clone()],
+                            })
+                            .log_err();
+                    }
+                }
+                Ok(entry)
             })
         } else {
             let client = self.client.clone();
@@ -1766,6 +1881,8 @@ impl Project {
         // If there is already a buffer for the given path, then return it.
         let existing_buffer = self.get_open_buffer(&project_path, cx);
         if let Some(existing_buffer) = existing_buffer {
+            self.buffers_last_used
+                .insert(existing_buffer.read(cx).remote_id(), Instant::now());
             return Task::ready(Ok(existing_buffer));
         }
 
@@ -2063,7 +2180,11 @@ impl Project {
         self.detect_language_for_buffer(buffer, cx);
         self.register_buffer_with_language_servers(buffer, cx);
         self.register_buffer_with_copilot(buffer, cx);
+        self.buffers_last_used.insert(remote_id, Instant::now());
+        self.evict_inactive_buffer_caches(cx);
         cx.observe_release(buffer, |this, buffer, cx| {
+            this.buffer_snapshots.remove(&buffer.remote_id());
+            this.buffers_last_used.remove(&buffer.remote_id());
             if let Some(file) = File::from_dyn(buffer.file()) {
                 if file.is_local() {
                     let uri = lsp::Url::from_file_path(file.abs_path(cx)).unwrap();
@@ -2086,6 +2207,50 @@ impl Project {
         Ok(())
     }
 
+    /// Drops all but the latest language server snapshot of buffers that haven't been
+    /// opened or edited recently, least recently used first, until the snapshots kept
+    /// for the remaining inactive buffers fit within the configured memory budget.
+    fn evict_inactive_buffer_caches(&mut self, cx: &mut ModelContext<Self>) {
+        let settings = &ProjectSettings::get_global(cx).inactive_buffers;
+        let idle_time = Duration::from_secs(settings.idle_minutes * 60);
+        let memory_budget = settings.memory_budget_mb * 1024 * 1024;
+        let now = Instant::now();
+
+        let mut inactive_buffers = self
+            .buffers_last_used
+            .iter()
+            .filter(|(_, last_used)| now.duration_since(**last_used) >= idle_time)
+            .filter_map(|(buffer_id, last_used)| {
+                let size = self
+                    .buffer_snapshots
+                    .get(buffer_id)?
+                    .values()
+                    .flat_map(|snapshots| &snapshots[..snapshots.len().saturating_sub(1)])
+                    .map(|snapshot| snapshot.snapshot.len())
+                    .sum::<usize>();
+                Some((*buffer_id, *last_used, size))
+            })
+            .collect::<Vec<_>>();
+        let mut total_size = inactive_buffers
+            .iter()
+            .map(|(_, _, size)| size)
+            .sum::<usize>();
+        inactive_buffers.sort_by_key(|(_, last_used, _)| *last_used);
+
+        for (buffer_id, _, size) in inactive_buffers {
+            if total_size <= memory_budget {
+                break;
+            }
+            if let Some(snapshots) = self.buffer_snapshots.get_mut(&buffer_id) {
+                for snapshots in snapshots.values_mut() {
+                    let old_snapshot_count = snapshots.len().saturating_sub(1);
+                    snapshots.drain(..old_snapshot_count);
+                }
+            }
+            total_size -= size;
+        }
+    }
+
     fn register_buffer_with_language_servers(
         &mut self,
         buffer_handle: &Model<Buffer>,
@@ -2348,6 +2513,8 @@ impl Project {
 
             BufferEvent::Edited { .. } => {
                 let buffer = buffer.read(cx);
+                self.buffers_last_used
+                    .insert(buffer.remote_id(), Instant::now());
                 let file = File::from_dyn(buffer.file())?;
                 let abs_path = file.as_local()?.abs_path(cx);
                 let uri = lsp::Url::from_file_path(abs_path).unwrap();
@@ -2765,7 +2932,7 @@ impl Project {
         });
 
         let buffer_file = buffer.read(cx).file().cloned();
-        let settings = language_settings(Some(&new_language), buffer_file.as_ref(), cx).clone();
+        let settings = language_settings(Some(&new_language), buffer_file.as_ref(), cx);
         let buffer_file = File::from_dyn(buffer_file.as_ref());
         let worktree = buffer_file.as_ref().map(|f| f.worktree_id(cx));
         if let Some(prettier_plugins) =
@@ -2817,12 +2984,20 @@ impl Project {
             return;
         }
 
+        let environment = self.worktree_environment(worktree_id, cx);
+        let environment = cx.background_executor().spawn(async move {
+            environment
+                .await
+                .map(|environment| environment.vars.clone())
+        });
+
         let stderr_capture = Arc::new(Mutex::new(Some(String::new())));
         let pending_server = match self.languages.create_pending_language_server(
             stderr_capture.clone(),
             language.clone(),
             adapter.clone(),
             Arc::clone(&worktree_path),
+            environment,
             ProjectLspAdapterDelegate::new(self, cx),
             cx,
         ) {
@@ -3811,6 +3986,45 @@ impl Project {
         })
     }
 
+    /// Sends a `workspace/willRenameFiles` request for the given rename, and applies the
+    /// edits that the language server responds with.
+    async fn will_rename_file(
+        this: WeakModel<Self>,
+        file_rename: lsp::FileRename,
+        adapter: Arc<CachedLspAdapter>,
+        language_server: Arc<LanguageServer>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        const WILL_RENAME_FILES_TIMEOUT: Duration = Duration::from_secs(5);
+
+        let mut timeout = cx
+            .background_executor()
+            .timer(WILL_RENAME_FILES_TIMEOUT)
+            .fuse();
+        let request =
+            language_server.request::<lsp::request::WillRenameFiles>(lsp::RenameFilesParams {
+                files: vec![file_rename],
+            });
+        let edit = futures::select! {
+            edit = request.fuse() => edit?,
+            _ = timeout => {
+                return Err(anyhow!(
+                    "language server {} timed out preparing a file rename",
+                    language_server.name()
+                ));
+            }
+        };
+
+        if let Some(edit) = edit {
+            let this = this
+                .upgrade()
+                .ok_or_else(|| anyhow!("project was dropped"))?;
+            Self::deserialize_workspace_edit(this, edit, true, adapter, language_server, cx)
+                .await?;
+        }
+        Ok(())
+    }
+
     pub fn language_server_statuses(
         &self,
     ) -> impl DoubleEndedIterator<Item = &LanguageServerStatus> {
@@ -4163,7 +4377,7 @@ impl Project {
                 let mut project_transaction = ProjectTransaction::default();
                 for (buffer, buffer_abs_path, language_server) in &buffers_with_paths_and_servers {
                     let settings = buffer.update(&mut cx, |buffer, cx| {
-                        language_settings(buffer.language(), buffer.file(), cx).clone()
+                        language_settings(buffer.language(), buffer.file(), cx)
                     })?;
 
                     let remove_trailing_whitespace = settings.remove_trailing_whitespace_on_save;
@@ -4189,6 +4403,9 @@ impl Project {
                         if ensure_final_newline {
                             buffer.ensure_final_newline(cx);
                         }
+                        if let Some(line_ending) = settings.line_ending {
+                            buffer.set_line_ending(line_ending, cx);
+                        }
                         buffer.end_transaction(cx)
                     })?;
 
@@ -4750,6 +4967,63 @@ impl Project {
         )
     }
 
+    /// Requests the signatures of the call surrounding the position. `trigger_character` is the
+    /// character whose insertion triggered the request, if any, and `is_retrigger` is whether
+    /// signature help was already being shown.
+    pub fn signature_help<T: ToPointUtf16>(
+        &self,
+        buffer: &Model<Buffer>,
+        position: T,
+        trigger_character: Option<String>,
+        is_retrigger: bool,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<SignatureHelp>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSignatureHelp {
+                position,
+                trigger_character,
+                is_retrigger,
+            },
+            cx,
+        )
+    }
+
+    /// Whether typing the text requests signature help, going by the trigger characters of the
+    /// buffer's primary language server, and also its retrigger characters while signature help
+    /// is shown. Remote projects don't know the host's server capabilities, so they trigger on
+    /// the characters that start or continue the arguments of a call.
+    pub fn is_signature_help_trigger(
+        &self,
+        buffer: &Buffer,
+        text: &str,
+        is_retrigger: bool,
+        cx: &AppContext,
+    ) -> bool {
+        if self.is_remote() {
+            return ["(", ","].contains(&text);
+        }
+        let Some(provider) = self
+            .primary_language_server_for_buffer(buffer, cx)
+            .and_then(|(_, server)| server.capabilities().signature_help_provider.as_ref())
+        else {
+            return false;
+        };
+        let retrigger_characters = provider
+            .retrigger_characters
+            .iter()
+            .flatten()
+            .filter(|_| is_retrigger);
+        provider
+            .trigger_characters
+            .iter()
+            .flatten()
+            .chain(retrigger_characters)
+            .any(|character| character == text)
+    }
+
     pub fn completions<T: ToOffset + ToPointUtf16>(
         &self,
         buffer: &Model<Buffer>,
@@ -4905,6 +5179,14 @@ impl Project {
             return;
         };
 
+        // Servers often only provide additional edits, such as imports, once a completion is
+        // resolved. Keep them, so the completions menu can indicate them.
+        if let Some(additional_text_edits) = completion_item.additional_text_edits {
+            completions.write()[completion_index]
+                .lsp_completion
+                .additional_text_edits = Some(additional_text_edits);
+        }
+
         if let Some(lsp_documentation) = completion_item.documentation {
             let documentation = language::prepare_completion_documentation(
                 &lsp_documentation,
@@ -5114,29 +5396,15 @@ impl Project {
             let range = action.range.to_point_utf16(buffer);
 
             cx.spawn(move |this, mut cx| async move {
-                if let Some(lsp_range) = action
-                    .lsp_action
-                    .data
-                    .as_mut()
-                    .and_then(|d| d.get_mut("codeActionParams"))
-                    .and_then(|d| d.get_mut("range"))
-                {
-                    *lsp_range = serde_json::to_value(&range_to_lsp(range)).unwrap();
-                    action.lsp_action = lang_server
-                        .request::<lsp::request::CodeActionResolveRequest>(action.lsp_action)
-                        .await?;
-                } else {
-                    let actions = this
-                        .update(&mut cx, |this, cx| {
-                            this.code_actions(&buffer_handle, action.range, cx)
-                        })?
-                        .await?;
-                    action.lsp_action = actions
-                        .into_iter()
-                        .find(|a| a.lsp_action.title == action.lsp_action.title)
-                        .ok_or_else(|| anyhow!("code action is outdated"))?
-                        .lsp_action;
-                }
+                Self::resolve_code_action(
+                    &this,
+                    &buffer_handle,
+                    &mut action,
+                    range,
+                    &lang_server,
+                    &mut cx,
+                )
+                .await?;
 
                 if let Some(edit) = action.lsp_action.edit {
                     if edit.changes.is_some() || edit.document_changes.is_some() {
@@ -5307,6 +5575,147 @@ impl Project {
         Ok(transaction)
     }
 
+    /// Returns the text edits that applying the code action would make, for each buffer, without
+    /// applying them. Returns `None` when the action can't be applied as text edits alone, because
+    /// it runs a command or creates, renames or deletes files, or when the project is remote.
+    pub fn code_action_edits(
+        &self,
+        buffer_handle: Model<Buffer>,
+        mut action: CodeAction,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<Vec<(Model<Buffer>, Vec<(Range<Anchor>, String)>)>>>> {
+        if !self.is_local() {
+            return Task::ready(Ok(None));
+        }
+        let buffer = buffer_handle.read(cx);
+        let Some((lsp_adapter, lang_server)) = self
+            .language_server_for_buffer(buffer, action.server_id, cx)
+            .map(|(adapter, server)| (adapter.clone(), server.clone()))
+        else {
+            return Task::ready(Ok(None));
+        };
+        let range = action.range.to_point_utf16(buffer);
+
+        cx.spawn(move |this, mut cx| async move {
+            Self::resolve_code_action(
+                &this,
+                &buffer_handle,
+                &mut action,
+                range,
+                &lang_server,
+                &mut cx,
+            )
+            .await?;
+            if action.lsp_action.command.is_some() {
+                return Ok(None);
+            }
+            let Some(edit) = action.lsp_action.edit else {
+                return Ok(Some(Vec::new()));
+            };
+
+            let mut text_document_edits = Vec::new();
+            if let Some(document_changes) = edit.document_changes {
+                match document_changes {
+                    lsp::DocumentChanges::Edits(edits) => text_document_edits = edits,
+                    lsp::DocumentChanges::Operations(operations) => {
+                        for operation in operations {
+                            match operation {
+                                lsp::DocumentChangeOperation::Edit(edit) => {
+                                    text_document_edits.push(edit)
+                                }
+                                lsp::DocumentChangeOperation::Op(_) => return Ok(None),
+                            }
+                        }
+                    }
+                }
+            } else if let Some(changes) = edit.changes {
+                text_document_edits.extend(changes.into_iter().map(|(uri, edits)| {
+                    lsp::TextDocumentEdit {
+                        text_document: lsp::OptionalVersionedTextDocumentIdentifier {
+                            uri,
+                            version: None,
+                        },
+                        edits: edits.into_iter().map(OneOf::Left).collect(),
+                    }
+                }));
+            }
+
+            let mut edits_by_buffer: Vec<(Model<Buffer>, Vec<(Range<Anchor>, String)>)> =
+                Vec::new();
+            for text_document_edit in text_document_edits {
+                let buffer = this
+                    .update(&mut cx, |this, cx| {
+                        this.open_local_buffer_via_lsp(
+                            text_document_edit.text_document.uri,
+                            lang_server.server_id(),
+                            lsp_adapter.name.clone(),
+                            cx,
+                        )
+                    })?
+                    .await?;
+                let edits = this
+                    .update(&mut cx, |this, cx| {
+                        let edits = text_document_edit.edits.into_iter().map(|edit| match edit {
+                            OneOf::Left(edit) => edit,
+                            OneOf::Right(edit) => edit.text_edit,
+                        });
+                        this.edits_from_lsp(
+                            &buffer,
+                            edits,
+                            lang_server.server_id(),
+                            text_document_edit.text_document.version,
+                            cx,
+                        )
+                    })?
+                    .await?;
+                match edits_by_buffer
+                    .iter_mut()
+                    .find(|(existing_buffer, _)| *existing_buffer == buffer)
+                {
+                    Some((_, buffer_edits)) => buffer_edits.extend(edits),
+                    None => edits_by_buffer.push((buffer, edits)),
+                }
+            }
+            Ok(Some(edits_by_buffer))
+        })
+    }
+
+    /// Fills in the details of a code action, such as its edit, which language servers can leave
+    /// out of the code actions they list.
+    async fn resolve_code_action(
+        this: &WeakModel<Self>,
+        buffer_handle: &Model<Buffer>,
+        action: &mut CodeAction,
+        range: Range<PointUtf16>,
+        lang_server: &Arc<LanguageServer>,
+        cx: &mut AsyncAppContext,
+    ) -> Result<()> {
+        if let Some(lsp_range) = action
+            .lsp_action
+            .data
+            .as_mut()
+            .and_then(|d| d.get_mut("codeActionParams"))
+            .and_then(|d| d.get_mut("range"))
+        {
+            *lsp_range = serde_json::to_value(&range_to_lsp(range)).unwrap();
+            action.lsp_action = lang_server
+                .request::<lsp::request::CodeActionResolveRequest>(action.lsp_action.clone())
+                .await?;
+        } else {
+            let actions = this
+                .update(cx, |this, cx| {
+                    this.code_actions(buffer_handle, action.range.clone(), cx)
+                })?
+                .await?;
+            action.lsp_action = actions
+                .into_iter()
+                .find(|a| a.lsp_action.title == action.lsp_action.title)
+                .ok_or_else(|| anyhow!("code action is outdated"))?
+                .lsp_action;
+        }
+        Ok(())
+    }
+
     async fn deserialize_workspace_edit(
         this: Model<Self>,
         edit: lsp::WorkspaceEdit,
@@ -5752,7 +6161,20 @@ impl Project {
             return rx;
         }
         let workers = background.num_cpus().min(path_count);
+        let search_index = ProjectSettings::get_global(cx)
+            .search_index
+            .enabled
+            .then(|| self.search_index.clone());
         let (matching_paths_tx, matching_paths_rx) = smol::channel::bounded(1024);
+        // Searches scoped to the open buffers or to files with git changes are narrowed down
+        // here, so that they find the same matches whether they're run locally or by a guest.
+        let open_buffer_ids = query.open_buffers_only().then(|| {
+            self.opened_buffers
+                .values()
+                .filter_map(|buffer| Some(buffer.upgrade()?.entity_id()))
+                .collect::<HashSet<_>>()
+        });
+        let modified_files_snapshots = query.modified_files_only().then(|| snapshots.clone());
         let mut unnamed_files = vec![];
         let opened_buffers = self
             .opened_buffers
@@ -5784,6 +6206,7 @@ impl Project {
                 self.fs.clone(),
                 workers,
                 query.clone(),
+                search_index,
                 path_count,
                 snapshots,
                 matching_paths_tx,
@@ -5804,6 +6227,22 @@ impl Project {
                     return;
                 }
                 let query = &query;
+                let in_scope = |buffer: &Model<Buffer>, snapshot: &BufferSnapshot| {
+                    let is_open = open_buffer_ids
+                        .as_ref()
+                        .map_or(true, |ids| ids.contains(&buffer.entity_id()));
+                    let is_modified = modified_files_snapshots.as_ref().map_or(true, |snapshots| {
+                        snapshot.file().map_or(false, |file| {
+                            snapshots
+                                .iter()
+                                .find(|snapshot| snapshot.id().to_usize() == file.worktree_id())
+                                .and_then(|snapshot| snapshot.entry_for_path(file.path()))
+                                .map_or(false, |entry| entry.git_status.is_some())
+                        })
+                    });
+                    is_open && is_modified
+                };
+                let in_scope = &in_scope;
                 let (finished_tx, mut finished_rx) = smol::channel::unbounded();
                 background
                     .scoped(|scope| {
@@ -5818,11 +6257,14 @@ impl Project {
                             let mut buffers_rx = buffers_rx.clone();
                             scope.spawn(async move {
                                 while let Some((entry, buffer_index)) = buffers_rx.next().await {
-                                    let buffer_matches = if let Some((_, snapshot)) = entry.as_ref()
+                                    let buffer_matches = if let Some((buffer, snapshot)) =
+                                        entry.as_ref()
                                     {
-                                        if query.file_matches(
-                                            snapshot.file().map(|file| file.path().as_ref()),
-                                        ) {
+                                        if in_scope(buffer, snapshot)
+                                            && query.file_matches(
+                                                snapshot.file().map(|file| file.path().as_ref()),
+                                            )
+                                        {
                                             query
                                                 .search(snapshot, None)
                                                 .await
@@ -5905,12 +6347,22 @@ impl Project {
         fs: Arc<dyn Fs>,
         workers: usize,
         query: SearchQuery,
+        search_index: Option<Arc<SearchIndex>>,
         path_count: usize,
         snapshots: Vec<LocalSnapshot>,
         matching_paths_tx: Sender<SearchMatchCandidate>,
     ) {
         let fs = &fs;
         let query = &query;
+        let query_trigrams = search_index::query_trigrams(query);
+        let index_rules_out =
+            |worktree_id: WorktreeId, entry: &Entry| match (&search_index, &query_trigrams) {
+                (Some(search_index), Some(query_trigrams)) => {
+                    search_index.rules_out(worktree_id, &entry.path, entry.mtime, query_trigrams)
+                }
+                _ => false,
+            };
+        let index_rules_out = &index_rules_out;
         let matching_paths_tx = &matching_paths_tx;
         let snapshots = &snapshots;
         let paths_per_worker = (path_count + workers - 1) / workers;
@@ -5973,7 +6425,9 @@ impl Project {
                                     if unnamed_buffers.contains_key(&entry.path) {
                                         continue;
                                     }
-                                    let matches = if query.file_matches(Some(&entry.path)) {
+                                    let matches = if query.file_matches(Some(&entry.path))
+                                        && !index_rules_out(snapshot.id(), entry)
+                                    {
                                         abs_path.clear();
                                         abs_path.push(&snapshot.abs_path());
                                         abs_path.push(&entry.path);
@@ -6358,6 +6812,8 @@ impl Project {
             }
         }
         servers_to_remove.retain(|server_id, _| !servers_to_preserve.contains(server_id));
+        self.search_index.remove_worktree(id_to_remove);
+        self.environments.remove(&id_to_remove);
         for (server_id_to_remove, server_name) in servers_to_remove {
             self.language_server_ids
                 .remove(&(id_to_remove, server_name));
@@ -6421,7 +6877,9 @@ impl Project {
                     this.update_local_worktree_buffers(&worktree, changes, cx);
                     this.update_local_worktree_language_servers(&worktree, changes, cx);
                     this.update_local_worktree_settings(&worktree, changes, cx);
+                    this.update_local_worktree_environment(&worktree, changes, cx);
                     this.update_prettier_settings(&worktree, changes, cx);
+                    this.update_search_index(&worktree, changes, cx);
                     cx.emit(Event::WorktreeUpdatedEntries(
                         worktree.read(cx).id(),
                         changes.clone(),
@@ -6745,6 +7203,54 @@ impl Project {
         .detach();
     }
 
+    /// Re-indexes the files that changed, so that project search can skip the ones that
+    /// can't contain a match.
+    fn update_search_index(
+        &mut self,
+        worktree: &Model<Worktree>,
+        changes: &UpdatedEntriesSet,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let settings = ProjectSettings::get_global(cx).search_index;
+        if !settings.enabled {
+            self.search_index.clear();
+            return;
+        }
+
+        let worktree = worktree.read(cx).as_local().unwrap();
+        let worktree_id = worktree.id();
+        let mut files_to_index = Vec::new();
+        for (path, _, change) in changes.iter() {
+            match worktree.entry_for_path(path) {
+                Some(entry)
+                    if *change != PathChange::Removed && entry.is_file() && !entry.is_ignored =>
+                {
+                    if let Some(abs_path) = worktree.absolutize(path).log_err() {
+                        files_to_index.push((path.clone(), abs_path, entry.mtime));
+                    }
+                }
+                _ => self.search_index.remove(worktree_id, path),
+            }
+        }
+        if files_to_index.is_empty() {
+            return;
+        }
+
+        let fs = self.fs.clone();
+        let search_index = self.search_index.clone();
+        cx.background_executor()
+            .spawn(async move {
+                for (path, abs_path, mtime) in files_to_index {
+                    // Files that can't be loaded as text, such as binary files, are searched
+                    // without the index.
+                    if let Ok(text) = fs.load(&abs_path).await {
+                        search_index.insert(worktree_id, path, mtime, &text, &settings);
+                    }
+                }
+            })
+            .detach();
+    }
+
     fn update_local_worktree_settings(
         &mut self,
         worktree: &Model<Worktree>,
@@ -6755,17 +7261,38 @@ impl Project {
         let worktree_id = worktree.entity_id();
         let worktree = worktree.read(cx).as_local().unwrap();
         let remote_worktree_id = worktree.id();
+        let is_trusted = ProjectTrust::is_path_trusted(worktree.abs_path(), cx);
 
         let mut settings_contents = Vec::new();
+        let mut editorconfig_contents = Vec::new();
         for (path, _, change) in changes.iter() {
-            if path.ends_with(&*LOCAL_SETTINGS_RELATIVE_PATH) {
+            if path.file_name() == Some(OsStr::new(EDITORCONFIG_FILE_NAME)) {
+                let Some(editorconfig_dir) = path.parent() else {
+                    continue;
+                };
+                let editorconfig_dir = Arc::from(editorconfig_dir);
+                let fs = self.fs.clone();
+                let removed = *change == PathChange::Removed;
+                let abs_path = worktree.absolutize(path);
+                editorconfig_contents.push(async move {
+                    (
+                        editorconfig_dir,
+                        if removed {
+                            None
+                        } else {
+                            Some(async move { fs.load(&abs_path?).await }.await)
+                        },
+                    )
+                });
+            } else if path.ends_with(&*LOCAL_SETTINGS_RELATIVE_PATH) {
                 let settings_dir = Arc::from(
                     path.ancestors()
                         .nth(LOCAL_SETTINGS_RELATIVE_PATH.components().count())
                         .unwrap(),
                 );
                 let fs = self.fs.clone();
-                let removed = *change == PathChange::Removed;
+                // The settings of untrusted worktrees are treated as if they didn't exist.
+                let removed = *change == PathChange::Removed || is_trusted != Some(true);
                 let abs_path = worktree.absolutize(path);
                 settings_contents.push(async move {
                     (
@@ -6780,16 +7307,29 @@ impl Project {
             }
         }
 
-        if settings_contents.is_empty() {
+        if settings_contents.is_empty() && editorconfig_contents.is_empty() {
             return;
         }
+        if !settings_contents.is_empty() {
+            self.request_worktree_trust(remote_worktree_id, is_trusted, cx);
+        }
 
         let client = self.client.clone();
         cx.spawn(move |_, cx| async move {
             let settings_contents: Vec<(Arc<Path>, _)> =
                 futures::future::join_all(settings_contents).await;
+            let editorconfig_contents: Vec<(Arc<Path>, _)> =
+                futures::future::join_all(editorconfig_contents).await;
             cx.update(|cx| {
                 cx.update_global::<SettingsStore, _>(|store, cx| {
+                    for (directory, file_content) in editorconfig_contents {
+                        let file_content = file_content.and_then(|content| content.log_err());
+                        store.set_local_editorconfig(
+                            worktree_id.as_u64() as usize,
+                            directory,
+                            file_content.as_deref(),
+                        );
+                    }
                     for (directory, file_content) in settings_contents {
                         let file_content = file_content.and_then(|content| content.log_err());
                         store
@@ -6818,6 +7358,117 @@ impl Project {
         .detach();
     }
 
+    /// Asks the user whether they trust a worktree, if they haven't decided or been asked yet.
+    fn request_worktree_trust(
+        &mut self,
+        worktree_id: WorktreeId,
+        is_trusted: Option<bool>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if is_trusted.is_none() && self.worktree_trust_requests.insert(worktree_id) {
+            cx.emit(Event::WorktreeTrustRequested(worktree_id));
+        }
+    }
+
+    /// Records whether the user trusts a local worktree, applying or removing the project
+    /// settings within it accordingly.
+    pub fn set_worktree_trusted(
+        &mut self,
+        worktree_id: WorktreeId,
+        trusted: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(worktree) = self.worktree_for_id(worktree_id, cx) else {
+            return;
+        };
+        let Some(local_worktree) = worktree.read(cx).as_local() else {
+            return;
+        };
+        let abs_path = local_worktree.abs_path().to_path_buf();
+        let settings_changes = local_worktree
+            .entries(true)
+            .filter(|entry| entry.path.ends_with(&*LOCAL_SETTINGS_RELATIVE_PATH))
+            .map(|entry| (entry.path.clone(), entry.id, PathChange::Loaded))
+            .collect::<Vec<_>>();
+
+        ProjectTrust::set_path_trusted(abs_path, trusted, cx);
+        self.update_local_worktree_settings(&worktree, &settings_changes.into(), cx);
+        let environment = self.load_worktree_environment(worktree_id, cx);
+        self.environments.insert(worktree_id, environment);
+    }
+
+    /// The environment that a local worktree defines for the programs run within it,
+    /// which is loaded the first time it's needed.
+    pub fn worktree_environment(
+        &mut self,
+        worktree_id: WorktreeId,
+        cx: &mut ModelContext<Self>,
+    ) -> Shared<Task<Option<Arc<ProjectEnvironment>>>> {
+        if let Some(environment) = self.environments.get(&worktree_id) {
+            return environment.clone();
+        }
+        let environment = self.load_worktree_environment(worktree_id, cx);
+        self.environments.insert(worktree_id, environment.clone());
+        environment
+    }
+
+    /// The environment of a local worktree, if it has finished loading.
+    pub fn loaded_worktree_environment(
+        &self,
+        worktree_id: WorktreeId,
+    ) -> Option<Arc<ProjectEnvironment>> {
+        self.environments
+            .get(&worktree_id)?
+            .clone()
+            .now_or_never()
+            .flatten()
+    }
+
+    fn load_worktree_environment(
+        &mut self,
+        worktree_id: WorktreeId,
+        cx: &mut ModelContext<Self>,
+    ) -> Shared<Task<Option<Arc<ProjectEnvironment>>>> {
+        // Loading the environment can run the project's `.envrc`.
+        let abs_path = self
+            .worktree_for_id(worktree_id, cx)
+            .filter(|worktree| worktree.read(cx).is_local())
+            .map(|worktree| worktree.read(cx).abs_path())
+            .filter(|abs_path| ProjectTrust::is_path_trusted(abs_path, cx) == Some(true));
+        let settings = ProjectSettings::get_global(cx).project_environment;
+        let fs = self.fs.clone();
+        cx.spawn(move |this, mut cx| async move {
+            let environment =
+                project_environment::load_environment(&abs_path?, settings, fs.as_ref())
+                    .await
+                    .log_err()
+                    .flatten()
+                    .map(Arc::new);
+            this.update(&mut cx, |_, cx| cx.notify()).ok();
+            environment
+        })
+        .shared()
+    }
+
+    fn update_local_worktree_environment(
+        &mut self,
+        worktree: &Model<Worktree>,
+        changes: &UpdatedEntriesSet,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if changes
+            .iter()
+            .any(|(path, _, _)| project_environment::is_environment_file(path))
+        {
+            let worktree_id = worktree.read(cx).id();
+            // The environment is only loaded from trusted worktrees.
+            let is_trusted = ProjectTrust::is_path_trusted(worktree.read(cx).abs_path(), cx);
+            self.request_worktree_trust(worktree_id, is_trusted, cx);
+            let environment = self.load_worktree_environment(worktree_id, cx);
+            self.environments.insert(worktree_id, environment);
+        }
+    }
+
     pub fn set_active_path(&mut self, entry: Option<ProjectPath>, cx: &mut ModelContext<Self>) {
         let new_active_entry = entry.and_then(|project_path| {
             let worktree = self.worktree_for_id(project_path.worktree_id, cx)?;
@@ -7144,13 +7795,10 @@ impl Project {
                 .ok_or_else(|| anyhow!("worktree not found"))
         })??;
         let worktree_scan_id = worktree.update(&mut cx, |worktree, _| worktree.scan_id())?;
-        let entry = worktree
-            .update(&mut cx, |worktree, cx| {
+        let entry = this
+            .update(&mut cx, |this, cx| {
                 let new_path = PathBuf::from(envelope.payload.new_path);
-                worktree
-                    .as_local_mut()
-                    .unwrap()
-                    .rename_entry(entry_id, new_path, cx)
+                this.rename_entry(entry_id, new_path, cx)
             })?
             .await?;
         Ok(proto::ProjectEntryResponse {
@@ -9008,6 +9656,40 @@ async fn wait_for_loading_buffer(
     }
 }
 
+/// Whether a file operation on the given path matches any of the filters that a language server
+/// registered for that operation.
+fn file_operation_filters_match(
+    filters: &[lsp::FileOperationFilter],
+    abs_path: &Path,
+    is_dir: bool,
+) -> bool {
+    filters.iter().any(|filter| {
+        if filter
+            .scheme
+            .as_deref()
+            .map_or(false, |scheme| scheme != "file")
+        {
+            return false;
+        }
+        let kind_matches = match filter.pattern.matches {
+            Some(lsp::FileOperationPatternKind::File) => !is_dir,
+            Some(lsp::FileOperationPatternKind::Folder) => is_dir,
+            None => true,
+        };
+        let ignore_case = filter
+            .pattern
+            .options
+            .as_ref()
+            .and_then(|options| options.ignore_case)
+            .unwrap_or(false);
+        kind_matches
+            && GlobBuilder::new(&filter.pattern.glob)
+                .case_insensitive(ignore_case)
+                .build()
+                .map_or(false, |glob| glob.compile_matcher().is_match(abs_path))
+    })
+}
+
 fn include_text(server: &lsp::LanguageServer) -> bool {
     server
         .capabilities()
@@ -0,0 +1,162 @@
+use crate::project_settings::ProjectEnvironmentSettings;
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use fs::Fs;
+use std::path::Path;
+
+const ENVRC_FILE_NAME: &str = ".envrc";
+const DOT_ENV_FILE_NAME: &str = ".env";
+
+/// Environment variables that a project defines for the programs run within it,
+/// such as terminals and language servers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectEnvironment {
+    pub source: EnvironmentSource,
+    pub vars: HashMap<String, String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EnvironmentSource {
+    /// The project's `.envrc`, evaluated by direnv.
+    Direnv,
+    /// The project's `.env` file.
+    DotEnv,
+}
+
+impl EnvironmentSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EnvironmentSource::Direnv => "direnv",
+            EnvironmentSource::DotEnv => ".env",
+        }
+    }
+}
+
+/// Whether a change to the worktree path could change the environment loaded for it.
+pub(crate) fn is_environment_file(path: &Path) -> bool {
+    path == Path::new(ENVRC_FILE_NAME) || path == Path::new(DOT_ENV_FILE_NAME)
+}
+
+/// Loads the environment defined in a directory, preferring direnv's evaluation of an
+/// `.envrc` to the variables in a `.env` file.
+pub async fn load_environment(
+    dir: &Path,
+    settings: ProjectEnvironmentSettings,
+    fs: &dyn Fs,
+) -> Result<Option<ProjectEnvironment>> {
+    if settings.direnv && fs.is_file(&dir.join(ENVRC_FILE_NAME)).await {
+        let output = smol::process::Command::new("direnv")
+            .args(["export", "json"])
+            .current_dir(dir)
+            .output()
+            .await
+            .map_err(|error| anyhow!("failed to run direnv: {error}"))?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "direnv failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        // direnv prints nothing when the environment is already loaded.
+        if output.stdout.is_empty() {
+            return Ok(None);
+        }
+        // Variables that direnv unsets are null, and are left as they are.
+        let vars: HashMap<String, Option<String>> = serde_json::from_slice(&output.stdout)?;
+        return Ok(Some(ProjectEnvironment {
+            source: EnvironmentSource::Direnv,
+            vars: vars
+                .into_iter()
+                .filter_map(|(name, value)| Some((name, value?)))
+                .collect(),
+        }));
+    }
+
+    if settings.dot_env {
+        let dot_env_path = dir.join(DOT_ENV_FILE_NAME);
+        if fs.is_file(&dot_env_path).await {
+            let text = fs.load(&dot_env_path).await?;
+            return Ok(Some(ProjectEnvironment {
+                source: EnvironmentSource::DotEnv,
+                vars: parse_dot_env(&text),
+            }));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Parses the `NAME=value` lines of a `.env` file, which may be prefixed with `export`,
+/// and whose values may be quoted. Blank lines and comments are skipped.
+fn parse_dot_env(text: &str) -> HashMap<String, String> {
+    let mut vars = HashMap::default();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((name, value)) = line.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+
+        let value = value.trim();
+        let value = if let Some(value) = value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+        {
+            value.replace("\\n", "\n").replace("\\\"", "\"")
+        } else if let Some(value) = value
+            .strip_prefix('\'')
+            .and_then(|value| value.strip_suffix('\''))
+        {
+            value.to_string()
+        } else {
+            // Unquoted values end at a comment.
+            match value.split_once(" #") {
+                Some((value, _)) => value.trim_end().to_string(),
+                None => value.to_string(),
+            }
+        };
+        vars.insert(name.to_string(), value);
+    }
+    vars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dot_env() {
+        let vars = parse_dot_env(
+            r#"
+            # Database settings
+            DATABASE_URL=postgres://localhost/app
+            export API_KEY = abc123 # not a secret
+            GREETING="hello \"world\"\nbye"
+            RAW='$HOME is not expanded'
+            EMPTY=
+            not a variable
+            "#,
+        );
+
+        let mut vars = vars.into_iter().collect::<Vec<_>>();
+        vars.sort();
+        assert_eq!(
+            vars,
+            [
+                ("API_KEY", "abc123"),
+                ("DATABASE_URL", "postgres://localhost/app"),
+                ("EMPTY", ""),
+                ("GREETING", "hello \"world\"\nbye"),
+                ("RAW", "$HOME is not expanded"),
+            ]
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+        );
+    }
+}
@@ -34,6 +34,161 @@ pub struct ProjectSettings {
     /// ]
     #[serde(default)]
     pub file_scan_exclusions: Option<Vec<String>>,
+
+    /// Skip directories matching these globs when scanning, until they're expanded in the
+    /// project panel, or searched by the file finder with `file_finder: toggle search
+    /// excluded`. This saves memory in huge repositories.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub file_scan_deferred_directories: Option<Vec<String>>,
+
+    /// Scan symlinked directories outside of the project like any other directory, and
+    /// watch their targets for changes. Otherwise they're only loaded once expanded in the
+    /// project panel, and are left out of searches.
+    ///
+    /// Default: false
+    #[serde(default)]
+    pub follow_symlinks: bool,
+
+    /// Open files matching these globs as read-only, until they're explicitly made writable.
+    /// Files that can't be written to are always opened as read-only.
+    ///
+    /// Default: [
+    ///   "**/node_modules/**",
+    ///   "**/target/**"
+    /// ]
+    #[serde(default)]
+    pub read_only_files: Option<Vec<String>>,
+
+    /// Configuration for the index that speeds up project search.
+    #[serde(default)]
+    pub search_index: SearchIndexSettings,
+
+    /// Configuration for how changes that other programs make to files are noticed.
+    #[serde(default)]
+    pub file_watcher: FileWatcherSettings,
+
+    /// Configuration for the environment variables that projects define for the terminals
+    /// and language servers run within them.
+    #[serde(default)]
+    pub project_environment: ProjectEnvironmentSettings,
+
+    /// Configuration for the caches of buffers that haven't been opened or edited recently.
+    #[serde(default)]
+    pub inactive_buffers: InactiveBufferSettings,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct SearchIndexSettings {
+    /// Whether to keep an index of the trigrams in the project's files, which lets text
+    /// searches skip the files that can't contain a match.
+    ///
+    /// Default: true
+    pub enabled: bool,
+    /// The most memory that the index may take up, in megabytes. Files that don't fit are
+    /// searched without the index.
+    ///
+    /// Default: 256
+    pub max_size_mb: usize,
+}
+
+impl Default for SearchIndexSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_size_mb: 256,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct FileWatcherSettings {
+    /// How worktrees are watched for changes.
+    ///
+    /// Default: auto
+    pub mode: FileWatcherMode,
+    /// Globs of worktree paths that are always polled, such as network mounts that
+    /// aren't recognized as one.
+    ///
+    /// Default: []
+    pub polling_paths: Vec<String>,
+    /// How often polled worktrees are scanned for changes, in milliseconds.
+    ///
+    /// Default: 1000
+    pub poll_interval_ms: u64,
+}
+
+impl Default for FileWatcherSettings {
+    fn default() -> Self {
+        Self {
+            mode: FileWatcherMode::Auto,
+            polling_paths: Vec::new(),
+            poll_interval_ms: 1000,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileWatcherMode {
+    /// Use the platform's file watcher, except for worktrees on network filesystems,
+    /// which are polled.
+    #[default]
+    Auto,
+    /// Always use the platform's file watcher.
+    Native,
+    /// Always poll, scanning worktrees for changes at an interval.
+    Polling,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct ProjectEnvironmentSettings {
+    /// Whether to load the environment that direnv evaluates from a project's `.envrc`.
+    ///
+    /// Default: true
+    pub direnv: bool,
+    /// Whether to load the variables in a project's `.env` file, if it has no `.envrc`.
+    ///
+    /// Default: true
+    pub dot_env: bool,
+}
+
+impl Default for ProjectEnvironmentSettings {
+    fn default() -> Self {
+        Self {
+            direnv: true,
+            dot_env: true,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct InactiveBufferSettings {
+    /// How long a buffer must go without being opened or edited before it's considered
+    /// inactive, in minutes.
+    ///
+    /// Default: 10
+    pub idle_minutes: u64,
+    /// The most memory that the language server snapshots kept for inactive buffers may
+    /// take up, in megabytes. Beyond this, the snapshots of the least recently used
+    /// buffers are dropped.
+    ///
+    /// Default: 64
+    pub memory_budget_mb: usize,
+}
+
+impl Default for InactiveBufferSettings {
+    fn default() -> Self {
+        Self {
+            idle_minutes: 10,
+            memory_budget_mb: 64,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -1,11 +1,11 @@
-use crate::{Event, *};
+use crate::{project_environment::EnvironmentSource, Event, *};
 use fs::FakeFs;
 use futures::{future, StreamExt};
 use gpui::AppContext;
 use language::{
     language_settings::{AllLanguageSettings, LanguageSettingsContent},
-    tree_sitter_rust, tree_sitter_typescript, Diagnostic, FakeLspAdapter, LanguageConfig,
-    LineEnding, OffsetRangeExt, Point, ToPoint,
+    tree_sitter_rust, tree_sitter_typescript, Capability, Diagnostic, Encoding, FakeLspAdapter,
+    LanguageConfig, LineEnding, OffsetRangeExt, Point, ToPoint,
 };
 use lsp::Url;
 use parking_lot::Mutex;
@@ -142,6 +142,243 @@ async fn test_managing_project_specific_settings(cx: &mut gpui::TestAppContext)
     });
 }
 
+#[gpui::test]
+async fn test_untrusted_project_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| cx.set_global(ProjectTrust::default()));
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".zed": {
+                "settings.json": r#"{ "tab_size": 8 }"#
+            },
+            "a.rs": "fn a() {\n    A\n}",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let worktree = project.update(cx, |project, _| project.worktrees().next().unwrap());
+    let worktree_id = worktree.read_with(cx, |tree, _| tree.id());
+    let tab_size = |cx: &mut gpui::TestAppContext| {
+        cx.update(|cx| {
+            let file = File::for_entry(
+                worktree.read(cx).entry_for_path("a.rs").unwrap().clone(),
+                worktree.clone(),
+            );
+            language_settings(None, Some(&(file as _)), cx)
+                .tab_size
+                .get()
+        })
+    };
+
+    // The settings of a project that hasn't been trusted aren't applied.
+    cx.executor().run_until_parked();
+    assert_eq!(tab_size(cx), 4);
+    project.read_with(cx, |project, _| {
+        assert!(project.worktree_trust_requests.contains(&worktree_id));
+    });
+
+    project.update(cx, |project, cx| {
+        project.set_worktree_trusted(worktree_id, true, cx)
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(tab_size(cx), 8);
+    cx.update(|cx| {
+        assert_eq!(
+            ProjectTrust::is_path_trusted(Path::new("/the-root/a.rs"), cx),
+            Some(true)
+        );
+    });
+
+    project.update(cx, |project, cx| {
+        project.set_worktree_trusted(worktree_id, false, cx)
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(tab_size(cx), 4);
+}
+
+#[gpui::test]
+async fn test_worktree_environment(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".env": "DATABASE_URL=postgres://localhost/app\n",
+            "a.rs": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+
+    let environment = project
+        .update(cx, |project, cx| {
+            project.worktree_environment(worktree_id, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(environment.source, EnvironmentSource::DotEnv);
+    assert_eq!(
+        environment.vars.get("DATABASE_URL").map(String::as_str),
+        Some("postgres://localhost/app")
+    );
+
+    // Changing the `.env` file reloads the environment.
+    fs.save(
+        "/the-root/.env".as_ref(),
+        &"DATABASE_URL=postgres://localhost/test\n".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    project.read_with(cx, |project, _| {
+        let environment = project.loaded_worktree_environment(worktree_id).unwrap();
+        assert_eq!(
+            environment.vars.get("DATABASE_URL").map(String::as_str),
+            Some("postgres://localhost/test")
+        );
+    });
+}
+
+#[gpui::test]
+async fn test_untrusted_worktree_environment(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| cx.set_global(ProjectTrust::default()));
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".env": "DATABASE_URL=postgres://localhost/app\n",
+            "a.rs": "",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees().next().unwrap().read(cx).id()
+    });
+
+    // A project with only an environment file is asked to be trusted, and its
+    // environment isn't loaded until it is.
+    cx.executor().run_until_parked();
+    project.read_with(cx, |project, _| {
+        assert!(project.worktree_trust_requests.contains(&worktree_id));
+    });
+    let environment = project
+        .update(cx, |project, cx| {
+            project.worktree_environment(worktree_id, cx)
+        })
+        .await;
+    assert!(environment.is_none());
+
+    project.update(cx, |project, cx| {
+        project.set_worktree_trusted(worktree_id, true, cx)
+    });
+    let environment = project
+        .update(cx, |project, cx| {
+            project.worktree_environment(worktree_id, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        environment.vars.get("DATABASE_URL").map(String::as_str),
+        Some("postgres://localhost/app")
+    );
+}
+
+#[gpui::test]
+async fn test_editorconfig_settings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/the-root",
+        json!({
+            ".editorconfig": "root = true\n[*]\nindent_size = 8\n[*.md]\ntrim_trailing_whitespace = false\n",
+            ".zed": {
+                "settings.json": r#"{ "remove_trailing_whitespace_on_save": true }"#
+            },
+            "a": {
+                "a.rs": "fn a() {\n    A\n}",
+                "a.md": "# A",
+            },
+            "b": {
+                ".editorconfig": "[*.rs]\nindent_style = tab\nend_of_line = crlf\n",
+                "b.rs": "fn b() {\n\tB\n}"
+            }
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/the-root".as_ref()], cx).await;
+    let worktree = project.update(cx, |project, _| project.worktrees().next().unwrap());
+
+    cx.executor().run_until_parked();
+    cx.update(|cx| {
+        let tree = worktree.read(cx);
+        let settings_for = |path: &str| {
+            language_settings(
+                None,
+                Some(
+                    &(File::for_entry(tree.entry_for_path(path).unwrap().clone(), worktree.clone())
+                        as _),
+                ),
+                cx,
+            )
+        };
+
+        let settings_a_rs = settings_for("a/a.rs");
+        assert_eq!(settings_a_rs.tab_size.get(), 8);
+        assert!(!settings_a_rs.hard_tabs);
+        assert!(settings_a_rs.remove_trailing_whitespace_on_save);
+        assert_eq!(settings_a_rs.line_ending, None);
+
+        let settings_a_md = settings_for("a/a.md");
+        assert!(!settings_a_md.remove_trailing_whitespace_on_save);
+
+        let settings_b = settings_for("b/b.rs");
+        assert_eq!(settings_b.tab_size.get(), 8);
+        assert!(settings_b.hard_tabs);
+        assert_eq!(settings_b.line_ending, Some(LineEnding::Windows));
+    });
+
+    // The indentation set by the `.editorconfig` takes precedence over the indentation
+    // detected in the file, and follows changes to the `.editorconfig`.
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/the-root/a/a.rs", cx)
+        })
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, cx| {
+        assert_eq!(buffer.indent_size().map(|size| size.len), Some(4));
+        assert_eq!(buffer.snapshot().language_indent_size_at(0, cx).len, 8);
+    });
+
+    fs.save(
+        "/the-root/.editorconfig".as_ref(),
+        &"root = true\n[*]\nindent_size = 2\n".into(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    cx.executor().run_until_parked();
+    buffer.read_with(cx, |buffer, cx| {
+        assert_eq!(buffer.snapshot().language_indent_size_at(0, cx).len, 2);
+    });
+}
+
 #[gpui::test]
 async fn test_managing_language_servers(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -1315,6 +1552,79 @@ async fn test_restarted_server_reporting_invalid_buffer_version(cx: &mut gpui::T
     assert_eq!(notification.version, 0);
 }
 
+#[gpui::test]
+async fn test_evicting_inactive_buffer_caches(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global(|settings: &mut SettingsStore, cx| {
+            settings.update_user_settings::<ProjectSettings>(cx, |settings| {
+                settings.inactive_buffers = project_settings::InactiveBufferSettings {
+                    idle_minutes: 0,
+                    memory_budget_mb: 0,
+                };
+            });
+        })
+    });
+
+    let mut language = Language::new(
+        LanguageConfig {
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        None,
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            name: "the-lsp",
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "a.rs": "", "b.rs": "" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+
+    let buffer_a = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/a.rs", cx))
+        .await
+        .unwrap();
+    let _fake_server = fake_servers.next().await.unwrap();
+    for text in ["one", "two", "three"] {
+        buffer_a.update(cx, |buffer, cx| buffer.edit([(0..0, text)], None, cx));
+    }
+    let buffer_a_id = buffer_a.read_with(cx, |buffer, _| buffer.remote_id());
+    let snapshot_counts = |project: &Project| {
+        project
+            .buffer_snapshots
+            .get(&buffer_a_id)
+            .map(|snapshots| snapshots.values().map(Vec::len).sum::<usize>())
+    };
+    project.read_with(cx, |project, _| {
+        assert_eq!(snapshot_counts(project), Some(4));
+    });
+
+    // Opening another buffer drops the old snapshots of inactive buffers, keeping
+    // the latest one, which the next change is computed from.
+    let _buffer_b = project
+        .update(cx, |project, cx| project.open_local_buffer("/dir/b.rs", cx))
+        .await
+        .unwrap();
+    project.read_with(cx, |project, _| {
+        assert_eq!(snapshot_counts(project), Some(1));
+    });
+
+    // Releasing a buffer drops all of its snapshots.
+    drop(buffer_a);
+    cx.executor().run_until_parked();
+    project.read_with(cx, |project, _| {
+        assert_eq!(snapshot_counts(project), None);
+        assert!(!project.buffers_last_used.contains_key(&buffer_a_id));
+    });
+}
+
 #[gpui::test]
 async fn test_toggling_enable_language_server(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -2656,6 +2966,134 @@ async fn test_save_file(cx: &mut gpui::TestAppContext) {
     assert_eq!(new_text, buffer.update(cx, |buffer, _| buffer.text()));
 }
 
+#[gpui::test]
+async fn test_file_encodings(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({})).await;
+    fs.insert_file_bytes("/dir/utf16", b"\xFF\xFEh\0\xE9\0\n\0".to_vec())
+        .await;
+    fs.insert_file_bytes("/dir/latin1", b"caf\xE9\n".to_vec())
+        .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let utf16_buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/utf16", cx))
+        .await
+        .unwrap();
+    utf16_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "hé\n");
+        assert_eq!(buffer.encoding(), Encoding::Utf16Le);
+    });
+
+    let latin1_buffer = project
+        .update(cx, |p, cx| p.open_local_buffer("/dir/latin1", cx))
+        .await
+        .unwrap();
+    latin1_buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.text(), "café\n");
+        assert_eq!(buffer.encoding(), Encoding::Latin1);
+        buffer.set_encoding(Encoding::Utf8, cx);
+        assert!(buffer.is_dirty());
+    });
+
+    // Saving converts the file to the buffer's encoding.
+    project
+        .update(cx, |project, cx| {
+            project.save_buffer(latin1_buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load_bytes(Path::new("/dir/latin1")).await.unwrap(),
+        "café\n".as_bytes()
+    );
+    latin1_buffer.update(cx, |buffer, _| assert!(!buffer.is_dirty()));
+
+    // Reopening decodes the file with the chosen encoding instead.
+    latin1_buffer
+        .update(cx, |buffer, cx| {
+            buffer.reload_with_encoding(Encoding::Latin1, cx)
+        })
+        .await
+        .unwrap();
+    latin1_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.text(), "cafÃ©\n");
+        assert_eq!(buffer.encoding(), Encoding::Latin1);
+    });
+
+    // Text that the encoding can't represent isn't saved.
+    utf16_buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "日本")], None, cx);
+        buffer.set_encoding(Encoding::Latin1, cx);
+    });
+    assert!(project
+        .update(cx, |project, cx| project
+            .save_buffer(utf16_buffer.clone(), cx))
+        .await
+        .is_err());
+}
+
+#[gpui::test]
+async fn test_read_only_files(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "node_modules": {
+                "dep": {
+                    "index.js": "module.exports = 1;\n",
+                },
+            },
+            "src": {
+                "main.js": "require('dep');\n",
+                "locked.js": "// locked\n",
+            },
+        }),
+    )
+    .await;
+    fs.set_read_only("/dir/src/locked.js", true).await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let open = |path: &'static str, cx: &mut gpui::TestAppContext| {
+        project.update(cx, |project, cx| project.open_local_buffer(path, cx))
+    };
+
+    let main_buffer = open("/dir/src/main.js", cx).await.unwrap();
+    main_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.capability(), Capability::ReadWrite)
+    });
+
+    // Files matching the `read_only_files` settings are read-only until made writable.
+    let dependency_buffer = open("/dir/node_modules/dep/index.js", cx).await.unwrap();
+    dependency_buffer.update(cx, |buffer, cx| {
+        assert_eq!(buffer.capability(), Capability::ReadOnly);
+        buffer.set_capability(Capability::ReadWrite, cx);
+        buffer.edit([(0..0, "// patched\n")], None, cx);
+    });
+    project
+        .update(cx, |project, cx| {
+            project.save_buffer(dependency_buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        fs.load(Path::new("/dir/node_modules/dep/index.js"))
+            .await
+            .unwrap(),
+        "// patched\nmodule.exports = 1;\n"
+    );
+
+    // So are files without write permission.
+    let locked_buffer = open("/dir/src/locked.js", cx).await.unwrap();
+    locked_buffer.update(cx, |buffer, _| {
+        assert_eq!(buffer.capability(), Capability::ReadOnly)
+    });
+}
+
 #[gpui::test(iterations = 30)]
 async fn test_file_changes_multiple_times_on_disk(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -3780,6 +4218,128 @@ async fn test_rename(cx: &mut gpui::TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_rename_entry_updates_references(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let rust_files = || lsp::FileOperationRegistrationOptions {
+        filters: vec![lsp::FileOperationFilter {
+            scheme: Some("file".to_string()),
+            pattern: lsp::FileOperationPattern {
+                glob: "**/*.rs".to_string(),
+                matches: None,
+                options: None,
+            },
+        }],
+    };
+    let mut language = Language::new(
+        LanguageConfig {
+            name: "Rust".into(),
+            path_suffixes: vec!["rs".to_string()],
+            ..Default::default()
+        },
+        Some(tree_sitter_rust::language()),
+    );
+    let mut fake_servers = language
+        .set_fake_lsp_adapter(Arc::new(FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                workspace: Some(lsp::WorkspaceServerCapabilities {
+                    workspace_folders: None,
+                    file_operations: Some(lsp::WorkspaceFileOperationsServerCapabilities {
+                        will_rename: Some(rust_files()),
+                        did_rename: Some(rust_files()),
+                        ..Default::default()
+                    }),
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }))
+        .await;
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "lib.rs": "mod one;\nfn main() { one::f(); }",
+            "one.rs": "pub fn f() {}"
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| project.languages.add(Arc::new(language)));
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/lib.rs", cx)
+        })
+        .await
+        .unwrap();
+    let mut fake_server = fake_servers.next().await.unwrap();
+
+    let worktree = project.update(cx, |project, _| project.worktrees().next().unwrap());
+    let entry_id = worktree.update(cx, |worktree, _| {
+        worktree.entry_for_path("one.rs").unwrap().id
+    });
+    let rename = project.update(cx, |project, cx| {
+        project.rename_entry(entry_id, Path::new("two.rs"), cx)
+    });
+
+    // The language server's edits are applied before the file is renamed.
+    fake_server
+        .handle_request::<lsp::request::WillRenameFiles, _, _>(|params, _| async move {
+            assert_eq!(
+                params.files,
+                [lsp::FileRename {
+                    old_uri: "file:///dir/one.rs".to_string(),
+                    new_uri: "file:///dir/two.rs".to_string(),
+                }]
+            );
+            Ok(Some(lsp::WorkspaceEdit {
+                changes: Some(
+                    [(
+                        lsp::Url::from_file_path("/dir/lib.rs").unwrap(),
+                        vec![
+                            lsp::TextEdit::new(
+                                lsp::Range::new(lsp::Position::new(0, 4), lsp::Position::new(0, 7)),
+                                "two".to_string(),
+                            ),
+                            lsp::TextEdit::new(
+                                lsp::Range::new(
+                                    lsp::Position::new(1, 12),
+                                    lsp::Position::new(1, 15),
+                                ),
+                                "two".to_string(),
+                            ),
+                        ],
+                    )]
+                    .into_iter()
+                    .collect(),
+                ),
+                ..Default::default()
+            }))
+        })
+        .next()
+        .await
+        .unwrap();
+    rename.await.unwrap();
+
+    let notification = fake_server
+        .receive_notification::<lsp::notification::DidRenameFiles>()
+        .await;
+    assert_eq!(notification.files[0].new_uri, "file:///dir/two.rs");
+
+    cx.executor().run_until_parked();
+    assert_eq!(
+        buffer.update(cx, |buffer, _| buffer.text()),
+        "mod two;\nfn main() { two::f(); }"
+    );
+    worktree.update(cx, |worktree, _| {
+        assert!(worktree.entry_for_path("one.rs").is_none());
+        assert!(worktree.entry_for_path("two.rs").is_some());
+    });
+}
+
 #[gpui::test]
 async fn test_search(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -4182,6 +4742,108 @@ async fn test_search_with_exclusions_and_inclusions(cx: &mut gpui::TestAppContex
     );
 }
 
+#[gpui::test]
+async fn test_syntax_search(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": "fn one() { two(1); three(2, 3); }",
+            "two.rs": "fn two(x: usize) {}",
+            "notes.txt": "two(1)",
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    project.update(cx, |project, _| {
+        project.languages.add(Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                path_suffixes: vec!["rs".to_string()],
+                ..Default::default()
+            },
+            Some(tree_sitter_rust::language()),
+        )));
+    });
+
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::syntax(
+                "(call_expression function: (identifier) @match)",
+                false,
+                Vec::new(),
+                Vec::new()
+            )
+            .unwrap(),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("one.rs".to_string(), vec![11..14, 19..24])]),
+        "Only the captured nodes of files with a grammar should match"
+    );
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::syntax("(call_expression)", false, Vec::new(), Vec::new()).unwrap(),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::from_iter([("one.rs".to_string(), vec![11..17, 19..30])]),
+        "A pattern without captures should match as a whole"
+    );
+    assert_eq!(
+        search(
+            &project,
+            SearchQuery::syntax("(not_a_node)", false, Vec::new(), Vec::new()).unwrap(),
+            cx
+        )
+        .await
+        .unwrap(),
+        HashMap::default(),
+        "Queries that are invalid for a language should not match"
+    );
+}
+
+#[gpui::test]
+async fn test_search_in_open_buffers(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "one.rs": r#"// Rust file one"#,
+            "two.rs": r#"// Rust file two"#,
+        }),
+    )
+    .await;
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let _buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/one.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let query = SearchQuery::text("file", false, true, false, Vec::new(), Vec::new())
+        .unwrap()
+        .with_scope(true, false);
+    // Guests send the scope along with the query, for the host to narrow the search down.
+    let query = SearchQuery::from_proto(query.to_proto(0)).unwrap();
+    assert!(query.open_buffers_only());
+    assert!(!query.modified_files_only());
+    assert_eq!(
+        search(&project, query, cx).await.unwrap(),
+        HashMap::from_iter([("one.rs".to_string(), vec![8..12])]),
+        "Only the open buffer should be searched"
+    );
+}
+
 #[gpui::test]
 async fn test_search_in_gitignored_dirs(cx: &mut gpui::TestAppContext) {
     init_test(cx);
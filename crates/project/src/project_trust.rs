@@ -0,0 +1,63 @@
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use gpui::AppContext;
+use std::path::{Path, PathBuf};
+use util::ResultExt;
+
+const PROJECT_TRUST_KEY: &str = "project_trust";
+
+/// The decisions the user has made about whether to trust local projects, by path.
+///
+/// Until a project's path is trusted, the settings in its `.zed/settings.json` files,
+/// which can specify commands to run, aren't applied, its environment isn't loaded from
+/// its `.envrc` or `.env` files, and Python virtual environments within it aren't activated
+/// in terminals. Trust is only enforced once this global has
+/// been initialized, so that projects without a user to ask, like those in tests, are
+/// always trusted.
+#[derive(Default)]
+pub struct ProjectTrust {
+    decisions: HashMap<PathBuf, bool>,
+}
+
+pub fn init(cx: &mut AppContext) {
+    let decisions = KEY_VALUE_STORE
+        .read_kvp(PROJECT_TRUST_KEY)
+        .log_err()
+        .flatten()
+        .and_then(|decisions| serde_json::from_str(&decisions).log_err())
+        .unwrap_or_default();
+    cx.set_global(ProjectTrust { decisions });
+}
+
+impl ProjectTrust {
+    /// Whether the given path is trusted, going by the decision made for it or for the
+    /// closest directory containing it. Returns `None` if no decision has been made.
+    pub fn is_path_trusted(abs_path: &Path, cx: &AppContext) -> Option<bool> {
+        let Some(this) = cx.try_global::<Self>() else {
+            return Some(true);
+        };
+        abs_path
+            .ancestors()
+            .find_map(|ancestor| this.decisions.get(ancestor).copied())
+    }
+
+    /// Records whether the given path is trusted, persisting the decision.
+    pub fn set_path_trusted(abs_path: PathBuf, trusted: bool, cx: &mut AppContext) {
+        if !cx.has_global::<Self>() {
+            return;
+        }
+        let this = cx.global_mut::<Self>();
+        this.decisions.insert(abs_path, trusted);
+        let Some(decisions) = serde_json::to_string(&this.decisions).log_err() else {
+            return;
+        };
+        cx.background_executor()
+            .spawn(async move {
+                KEY_VALUE_STORE
+                    .write_kvp(PROJECT_TRUST_KEY.into(), decisions)
+                    .await
+                    .log_err();
+            })
+            .detach();
+    }
+}
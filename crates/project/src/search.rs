@@ -1,17 +1,21 @@
 use aho_corasick::{AhoCorasick, AhoCorasickBuilder};
 use anyhow::{Context, Result};
 use client::proto;
+use collections::HashMap;
 use itertools::Itertools;
-use language::{char_kind, BufferSnapshot};
+use language::{char_kind, BufferSnapshot, Grammar, Parser};
+use parking_lot::Mutex;
 use regex::{Regex, RegexBuilder};
 use smol::future::yield_now;
 use std::{
     borrow::Cow,
+    collections::BTreeSet,
     io::{BufRead, BufReader, Read},
     ops::Range,
     path::Path,
     sync::Arc,
 };
+use tree_sitter::{Query, QueryCursor};
 use util::paths::PathMatcher;
 
 #[derive(Clone, Debug)]
@@ -19,6 +23,8 @@ pub struct SearchInputs {
     query: Arc<str>,
     files_to_include: Vec<PathMatcher>,
     files_to_exclude: Vec<PathMatcher>,
+    open_buffers_only: bool,
+    modified_files_only: bool,
 }
 
 impl SearchInputs {
@@ -31,6 +37,12 @@ impl SearchInputs {
     pub fn files_to_exclude(&self) -> &[PathMatcher] {
         &self.files_to_exclude
     }
+    pub fn open_buffers_only(&self) -> bool {
+        self.open_buffers_only
+    }
+    pub fn modified_files_only(&self) -> bool {
+        self.modified_files_only
+    }
 }
 #[derive(Clone, Debug)]
 pub enum SearchQuery {
@@ -52,6 +64,17 @@ pub enum SearchQuery {
         include_ignored: bool,
         inner: SearchInputs,
     },
+
+    /// A tree-sitter query, matching the nodes captured as `@match`, or the nodes of
+    /// the query's first capture. Queries without captures match their outermost pattern.
+    Syntax {
+        /// The query compiled for each grammar it has run against, by grammar id, or `None`
+        /// where it isn't valid for the grammar.
+        queries: Arc<Mutex<HashMap<usize, Option<Arc<Query>>>>>,
+        replacement: Option<String>,
+        include_ignored: bool,
+        inner: SearchInputs,
+    },
 }
 
 impl SearchQuery {
@@ -71,6 +94,8 @@ impl SearchQuery {
             query: query.into(),
             files_to_exclude,
             files_to_include,
+            open_buffers_only: false,
+            modified_files_only: false,
         };
         Ok(Self::Text {
             search: Arc::new(search),
@@ -109,6 +134,8 @@ impl SearchQuery {
             query: initial_query,
             files_to_exclude,
             files_to_include,
+            open_buffers_only: false,
+            modified_files_only: false,
         };
         Ok(Self::Regex {
             regex,
@@ -121,8 +148,38 @@ impl SearchQuery {
         })
     }
 
+    pub fn syntax(
+        query: impl ToString,
+        include_ignored: bool,
+        files_to_include: Vec<PathMatcher>,
+        files_to_exclude: Vec<PathMatcher>,
+    ) -> Result<Self> {
+        let query = query.to_string();
+        validate_syntax_query(&query)?;
+        let inner = SearchInputs {
+            query: query.into(),
+            files_to_exclude,
+            files_to_include,
+            open_buffers_only: false,
+            modified_files_only: false,
+        };
+        Ok(Self::Syntax {
+            queries: Default::default(),
+            replacement: None,
+            include_ignored,
+            inner,
+        })
+    }
+
     pub fn from_proto(message: proto::SearchProject) -> Result<Self> {
-        if message.regex {
+        let query = if message.syntax {
+            Self::syntax(
+                message.query,
+                message.include_ignored,
+                deserialize_path_matches(&message.files_to_include)?,
+                deserialize_path_matches(&message.files_to_exclude)?,
+            )
+        } else if message.regex {
             Self::regex(
                 message.query,
                 message.whole_word,
@@ -140,7 +197,8 @@ impl SearchQuery {
                 deserialize_path_matches(&message.files_to_include)?,
                 deserialize_path_matches(&message.files_to_exclude)?,
             )
-        }
+        }?;
+        Ok(query.with_scope(message.open_buffers_only, message.modified_files_only))
     }
     pub fn with_replacement(mut self, new_replacement: String) -> Self {
         match self {
@@ -151,20 +209,40 @@ impl SearchQuery {
             | Self::Regex {
                 ref mut replacement,
                 ..
+            }
+            | Self::Syntax {
+                ref mut replacement,
+                ..
             } => {
                 *replacement = Some(new_replacement);
                 self
             }
         }
     }
+    /// Narrows the search down to the buffers that are open, or to the files that have git
+    /// changes.
+    pub fn with_scope(mut self, open_buffers_only: bool, modified_files_only: bool) -> Self {
+        match self {
+            Self::Text { ref mut inner, .. }
+            | Self::Regex { ref mut inner, .. }
+            | Self::Syntax { ref mut inner, .. } => {
+                inner.open_buffers_only = open_buffers_only;
+                inner.modified_files_only = modified_files_only;
+                self
+            }
+        }
+    }
     pub fn to_proto(&self, project_id: u64) -> proto::SearchProject {
         proto::SearchProject {
             project_id,
             query: self.as_str().to_string(),
             regex: self.is_regex(),
+            syntax: self.is_syntax(),
             whole_word: self.whole_word(),
             case_sensitive: self.case_sensitive(),
             include_ignored: self.include_ignored(),
+            open_buffers_only: self.open_buffers_only(),
+            modified_files_only: self.modified_files_only(),
             files_to_include: self
                 .files_to_include()
                 .iter()
@@ -213,20 +291,31 @@ impl SearchQuery {
                     Ok(false)
                 }
             }
+            // Whether a file matches depends on its syntax tree, which can only be known
+            // once it has been opened and parsed. Like line-by-line regex searches, skip
+            // files that aren't text, such as binary files.
+            Self::Syntax { .. } => {
+                for line in BufReader::new(stream).lines() {
+                    line?;
+                }
+                Ok(true)
+            }
         }
     }
     /// Returns the replacement text for this `SearchQuery`.
     pub fn replacement(&self) -> Option<&str> {
         match self {
-            SearchQuery::Text { replacement, .. } | SearchQuery::Regex { replacement, .. } => {
-                replacement.as_deref()
-            }
+            SearchQuery::Text { replacement, .. }
+            | SearchQuery::Regex { replacement, .. }
+            | SearchQuery::Syntax { replacement, .. } => replacement.as_deref(),
         }
     }
     /// Replaces search hits if replacement is set. `text` is assumed to be a string that matches this `SearchQuery` exactly, without any leftovers on either side.
     pub fn replacement_for<'a>(&self, text: &'a str) -> Option<Cow<'a, str>> {
         match self {
-            SearchQuery::Text { replacement, .. } => replacement.clone().map(Cow::from),
+            SearchQuery::Text { replacement, .. } | SearchQuery::Syntax { replacement, .. } => {
+                replacement.clone().map(Cow::from)
+            }
             SearchQuery::Regex {
                 regex, replacement, ..
             } => {
@@ -248,6 +337,9 @@ impl SearchQuery {
         if self.as_str().is_empty() {
             return Default::default();
         }
+        if let Self::Syntax { .. } = self {
+            return self.search_syntax(buffer, subrange);
+        }
 
         let range_offset = subrange.as_ref().map(|r| r.start).unwrap_or(0);
         let rope = if let Some(range) = subrange {
@@ -322,11 +414,69 @@ impl SearchQuery {
                     }
                 }
             }
+
+            Self::Syntax { .. } => {}
         }
 
         matches
     }
 
+    /// Parses the buffer and returns the ranges of the nodes that the query captures,
+    /// relative to the subrange. Nothing matches in buffers without a grammar, or when
+    /// the query isn't valid for the buffer's language.
+    fn search_syntax(
+        &self,
+        buffer: &BufferSnapshot,
+        subrange: Option<Range<usize>>,
+    ) -> Vec<Range<usize>> {
+        let Some(grammar) = buffer.language().and_then(|language| language.grammar()) else {
+            return Vec::new();
+        };
+        let Some(query) = self.syntax_query(grammar) else {
+            return Vec::new();
+        };
+        let mut parser = Parser::new();
+        if parser.set_language(grammar.ts_language).is_err() {
+            return Vec::new();
+        }
+        let text = buffer.text();
+        let Some(tree) = parser.parse(&text, None) else {
+            return Vec::new();
+        };
+
+        let subrange = subrange.unwrap_or(0..text.len());
+        let match_capture_ix = query.capture_index_for_name("match").unwrap_or(0);
+        let mut cursor = QueryCursor::new();
+        cursor.set_byte_range(subrange.clone());
+        let mut matches = BTreeSet::new();
+        for mat in cursor.matches(&query, tree.root_node(), text.as_bytes()) {
+            for capture in mat.captures {
+                let range = capture.node.byte_range();
+                if capture.index == match_capture_ix
+                    && !range.is_empty()
+                    && subrange.start <= range.start
+                    && range.end <= subrange.end
+                {
+                    matches.insert((range.start - subrange.start, range.end - subrange.start));
+                }
+            }
+        }
+        matches.into_iter().map(|(start, end)| start..end).collect()
+    }
+
+    /// Returns the syntax query compiled for the grammar, compiling it the first time it's
+    /// run against the grammar.
+    fn syntax_query(&self, grammar: &Grammar) -> Option<Arc<Query>> {
+        let Self::Syntax { queries, .. } = self else {
+            return None;
+        };
+        queries
+            .lock()
+            .entry(grammar.id())
+            .or_insert_with(|| syntax_query(self.as_str(), grammar.ts_language).map(Arc::new))
+            .clone()
+    }
+
     pub fn as_str(&self) -> &str {
         self.as_inner().as_str()
     }
@@ -335,6 +485,7 @@ impl SearchQuery {
         match self {
             Self::Text { whole_word, .. } => *whole_word,
             Self::Regex { whole_word, .. } => *whole_word,
+            Self::Syntax { .. } => false,
         }
     }
 
@@ -342,6 +493,7 @@ impl SearchQuery {
         match self {
             Self::Text { case_sensitive, .. } => *case_sensitive,
             Self::Regex { case_sensitive, .. } => *case_sensitive,
+            Self::Syntax { .. } => true,
         }
     }
 
@@ -353,6 +505,9 @@ impl SearchQuery {
             Self::Regex {
                 include_ignored, ..
             } => *include_ignored,
+            Self::Syntax {
+                include_ignored, ..
+            } => *include_ignored,
         }
     }
 
@@ -360,6 +515,10 @@ impl SearchQuery {
         matches!(self, Self::Regex { .. })
     }
 
+    pub fn is_syntax(&self) -> bool {
+        matches!(self, Self::Syntax { .. })
+    }
+
     pub fn files_to_include(&self) -> &[PathMatcher] {
         self.as_inner().files_to_include()
     }
@@ -368,6 +527,14 @@ impl SearchQuery {
         self.as_inner().files_to_exclude()
     }
 
+    pub fn open_buffers_only(&self) -> bool {
+        self.as_inner().open_buffers_only()
+    }
+
+    pub fn modified_files_only(&self) -> bool {
+        self.as_inner().modified_files_only()
+    }
+
     pub fn file_matches(&self, file_path: Option<&Path>) -> bool {
         match file_path {
             Some(file_path) => {
@@ -396,9 +563,71 @@ impl SearchQuery {
     }
     pub fn as_inner(&self) -> &SearchInputs {
         match self {
-            Self::Regex { inner, .. } | Self::Text { inner, .. } => inner,
+            Self::Regex { inner, .. } | Self::Text { inner, .. } | Self::Syntax { inner, .. } => {
+                inner
+            }
+        }
+    }
+}
+
+/// Compiles a syntax search query for the given language. A pattern without any captures
+/// is captured as a whole, so that `(call_expression)` finds every call.
+fn syntax_query(source: &str, language: tree_sitter::Language) -> Option<Query> {
+    let query = Query::new(language, source).ok()?;
+    if query.capture_names().is_empty() {
+        Query::new(language, &format!("{source} @match")).ok()
+    } else {
+        Some(query)
+    }
+}
+
+/// Checks that a syntax search query is well-formed, returning an error for unbalanced
+/// parentheses or brackets and unterminated strings. Whether its node and field names are
+/// valid depends on the language it runs against, so those are only checked when it's
+/// compiled for each language.
+fn validate_syntax_query(source: &str) -> Result<()> {
+    let mut open_delimiters = Vec::new();
+    let mut chars = source.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        match c {
+            ';' => {
+                for (_, c) in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            '"' => {
+                let mut terminated = false;
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => {
+                            terminated = true;
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                anyhow::ensure!(terminated, "unterminated string at offset {ix}");
+            }
+            '(' | '[' => open_delimiters.push(c),
+            ')' | ']' => {
+                let opening = if c == ')' { '(' } else { '[' };
+                anyhow::ensure!(
+                    open_delimiters.pop() == Some(opening),
+                    "unexpected `{c}` at offset {ix}"
+                );
+            }
+            _ => {}
         }
     }
+    if let Some(c) = open_delimiters.pop() {
+        anyhow::bail!("unclosed `{c}`");
+    }
+    Ok(())
 }
 
 fn deserialize_path_matches(glob_set: &str) -> anyhow::Result<Vec<PathMatcher>> {
@@ -460,4 +689,38 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn syntax_query_validation() {
+        for valid_query in [
+            "(call_expression)",
+            "(call_expression function: (identifier) @match)",
+            "[(identifier) (field_identifier)] @match",
+            "((identifier) @match (#eq? @match \"a)\"))",
+            "; a comment (\n(identifier)",
+        ] {
+            if let Err(e) = SearchQuery::syntax(valid_query, false, Vec::new(), Vec::new()) {
+                panic!("Valid query {valid_query} should be accepted, but got: {e}");
+            }
+        }
+
+        for invalid_query in [
+            "(call_expression",
+            "(call_expression))",
+            "[(identifier) (field_identifier)",
+            "(identifier]",
+            "((identifier) @match (#eq? @match \"a))",
+        ] {
+            if SearchQuery::syntax(invalid_query, false, Vec::new(), Vec::new()).is_ok() {
+                panic!("Invalid query {invalid_query} should not be accepted");
+            }
+        }
+    }
+
+    #[test]
+    fn syntax_query_skips_binary_files() {
+        let query = SearchQuery::syntax("(identifier)", false, Vec::new(), Vec::new()).unwrap();
+        assert!(query.detect("fn main() {}".as_bytes()).unwrap());
+        assert!(query.detect(&[0xff, 0xfe, 0x00, 0x01][..]).is_err());
+    }
 }
@@ -0,0 +1,237 @@
+//! An index of the trigrams in the project's files, kept up to date as the files change, which
+//! lets text searches skip the files that can't contain a match without reading them.
+
+use crate::{project_settings::SearchIndexSettings, search::SearchQuery, WorktreeId};
+use collections::HashMap;
+use parking_lot::RwLock;
+use std::{mem, path::Path, sync::Arc, time::SystemTime};
+
+const MEGABYTE: usize = 1024 * 1024;
+
+/// The trigrams of a file's contents, as of the file's modification time.
+struct IndexedFile {
+    mtime: SystemTime,
+    /// Sorted and deduplicated.
+    trigrams: Vec<u32>,
+}
+
+impl IndexedFile {
+    fn size(&self) -> usize {
+        mem::size_of::<Self>() + self.trigrams.len() * mem::size_of::<u32>()
+    }
+}
+
+#[derive(Default)]
+struct IndexState {
+    files: HashMap<(WorktreeId, Arc<Path>), IndexedFile>,
+    size: usize,
+}
+
+#[derive(Default)]
+pub struct SearchIndex {
+    state: RwLock<IndexState>,
+}
+
+impl SearchIndex {
+    /// Indexes the contents of a file, replacing its previous contents. Files that don't fit
+    /// in the index's size limit are left out, and are searched without it.
+    pub fn insert(
+        &self,
+        worktree_id: WorktreeId,
+        path: Arc<Path>,
+        mtime: SystemTime,
+        text: &str,
+        settings: &SearchIndexSettings,
+    ) {
+        let file = IndexedFile {
+            mtime,
+            trigrams: trigrams(text.as_bytes()),
+        };
+        let mut state = self.state.write();
+        let key = (worktree_id, path);
+        if let Some(previous) = state.files.remove(&key) {
+            state.size -= previous.size();
+        }
+        if state.size + file.size() <= settings.max_size_mb * MEGABYTE {
+            state.size += file.size();
+            state.files.insert(key, file);
+        }
+    }
+
+    pub fn remove(&self, worktree_id: WorktreeId, path: &Path) {
+        let mut state = self.state.write();
+        if let Some(file) = state.files.remove(&(worktree_id, Arc::from(path))) {
+            state.size -= file.size();
+        }
+    }
+
+    pub fn remove_worktree(&self, worktree_id: WorktreeId) {
+        let mut state = self.state.write();
+        let mut removed_size = 0;
+        state.files.retain(|(file_worktree_id, _), file| {
+            if *file_worktree_id == worktree_id {
+                removed_size += file.size();
+                false
+            } else {
+                true
+            }
+        });
+        state.size -= removed_size;
+    }
+
+    pub fn clear(&self) {
+        *self.state.write() = IndexState::default();
+    }
+
+    /// Whether the index shows that a file, as of the given modification time, contains none
+    /// of the matches of a query with the given trigrams. Files that aren't indexed, or that
+    /// changed since they were indexed, are never ruled out.
+    pub fn rules_out(
+        &self,
+        worktree_id: WorktreeId,
+        path: &Arc<Path>,
+        mtime: SystemTime,
+        query_trigrams: &[u32],
+    ) -> bool {
+        if query_trigrams.is_empty() {
+            return false;
+        }
+        let state = self.state.read();
+        let Some(file) = state.files.get(&(worktree_id, path.clone())) else {
+            return false;
+        };
+        file.mtime == mtime
+            && query_trigrams
+                .iter()
+                .any(|trigram| file.trigrams.binary_search(trigram).is_err())
+    }
+}
+
+/// The trigrams that every match of the query contains, or `None` when the index can't be
+/// used for the query.
+pub fn query_trigrams(query: &SearchQuery) -> Option<Vec<u32>> {
+    match query {
+        SearchQuery::Text { .. } => {
+            let trigrams = trigrams(query.as_str().as_bytes());
+            (!trigrams.is_empty()).then_some(trigrams)
+        }
+        SearchQuery::Regex { .. } | SearchQuery::Syntax { .. } => None,
+    }
+}
+
+/// Returns the sorted, distinct trigrams of the given bytes. ASCII letters are lowercased,
+/// so that the trigrams serve both case-sensitive and case-insensitive searches.
+fn trigrams(bytes: &[u8]) -> Vec<u32> {
+    let mut trigrams = bytes
+        .windows(3)
+        .map(|window| {
+            u32::from_le_bytes([
+                window[0].to_ascii_lowercase(),
+                window[1].to_ascii_lowercase(),
+                window[2].to_ascii_lowercase(),
+                0,
+            ])
+        })
+        .collect::<Vec<_>>();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_out() {
+        let settings = SearchIndexSettings {
+            enabled: true,
+            max_size_mb: 1,
+        };
+        let index = SearchIndex::default();
+        let worktree_id = WorktreeId::from_usize(1);
+        let path: Arc<Path> = Path::new("src/main.rs").into();
+        let mtime = SystemTime::UNIX_EPOCH;
+        index.insert(
+            worktree_id,
+            path.clone(),
+            mtime,
+            "fn main() { println!(\"Hello\"); }",
+            &settings,
+        );
+
+        let rules_out = |query: &str| {
+            let query =
+                SearchQuery::text(query, false, false, false, Vec::new(), Vec::new()).unwrap();
+            index.rules_out(
+                worktree_id,
+                &path,
+                mtime,
+                &query_trigrams(&query).unwrap_or_default(),
+            )
+        };
+        assert!(!rules_out("println"));
+        assert!(!rules_out("HELLO"), "trigrams should ignore ASCII case");
+        assert!(rules_out("eprintln"));
+        assert!(
+            !rules_out("ep"),
+            "queries shorter than a trigram can't be ruled out"
+        );
+
+        let query =
+            SearchQuery::text("eprintln", false, false, false, Vec::new(), Vec::new()).unwrap();
+        let query_trigrams = query_trigrams(&query).unwrap();
+        assert!(
+            !index.rules_out(
+                worktree_id,
+                &path,
+                mtime + std::time::Duration::from_secs(1),
+                &query_trigrams
+            ),
+            "files that changed since they were indexed should be searched"
+        );
+
+        index.remove(worktree_id, &path);
+        assert!(!index.rules_out(worktree_id, &path, mtime, &query_trigrams));
+        assert_eq!(index.state.read().size, 0);
+    }
+
+    #[test]
+    fn test_size_limit() {
+        let settings = SearchIndexSettings {
+            enabled: true,
+            max_size_mb: 1,
+        };
+        let index = SearchIndex::default();
+        let worktree_id = WorktreeId::from_usize(1);
+        // Pseudo-random printable ASCII, whose distinct trigrams take up more than a megabyte.
+        let mut seed = 1_u64;
+        let large_text = (0..MEGABYTE)
+            .map(|_| {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                char::from(b' ' + (seed >> 33) as u8 % 95)
+            })
+            .collect::<String>();
+        index.insert(
+            worktree_id,
+            Path::new("large.txt").into(),
+            SystemTime::UNIX_EPOCH,
+            &large_text,
+            &settings,
+        );
+        index.insert(
+            worktree_id,
+            Path::new("small.txt").into(),
+            SystemTime::UNIX_EPOCH,
+            "small",
+            &settings,
+        );
+
+        let state = index.state.read();
+        assert_eq!(state.files.len(), 1);
+        assert!(state
+            .files
+            .contains_key(&(worktree_id, Path::new("small.txt").into())));
+        assert!(state.size <= MEGABYTE);
+    }
+}
@@ -1,9 +1,15 @@
-use crate::Project;
-use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, WeakModel};
+use crate::{project_environment::ProjectEnvironment, project_trust::ProjectTrust, Project};
+use gpui::{AnyWindowHandle, Context, Entity, Model, ModelContext, Task, WeakModel};
 use settings::Settings;
-use std::path::{Path, PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use terminal::{
-    terminal_settings::{self, TerminalSettings, VenvSettingsContent},
+    terminal_settings::{
+        self, TerminalProfile, TerminalSettings, VenvSettings, VenvSettingsContent,
+    },
     Terminal, TerminalBuilder,
 };
 
@@ -20,6 +26,45 @@ impl Project {
         working_directory: Option<PathBuf>,
         window: AnyWindowHandle,
         cx: &mut ModelContext<Self>,
+    ) -> Task<anyhow::Result<Model<Terminal>>> {
+        self.create_terminal_with_profile(working_directory, None, window, cx)
+    }
+
+    /// Creates a terminal using the profile's shell and environment in place of the
+    /// terminal settings', where it sets them.
+    ///
+    /// The terminal is created once the environment of the worktree containing the working
+    /// directory has loaded, so that the shell starts with it.
+    pub fn create_terminal_with_profile(
+        &mut self,
+        working_directory: Option<PathBuf>,
+        profile: Option<&TerminalProfile>,
+        window: AnyWindowHandle,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<anyhow::Result<Model<Terminal>>> {
+        let environment = working_directory
+            .as_deref()
+            .and_then(|directory| self.find_local_worktree(directory, cx))
+            .map(|(worktree, _)| self.worktree_environment(worktree.read(cx).id(), cx));
+        let profile = profile.cloned();
+        cx.spawn(|this, mut cx| async move {
+            let environment = match environment {
+                Some(environment) => environment.await,
+                None => None,
+            };
+            this.update(&mut cx, |this, cx| {
+                this.build_terminal(working_directory, profile.as_ref(), environment, window, cx)
+            })?
+        })
+    }
+
+    fn build_terminal(
+        &mut self,
+        working_directory: Option<PathBuf>,
+        profile: Option<&TerminalProfile>,
+        environment: Option<Arc<ProjectEnvironment>>,
+        window: AnyWindowHandle,
+        cx: &mut ModelContext<Self>,
     ) -> anyhow::Result<Model<Terminal>> {
         if self.is_remote() {
             return Err(anyhow::anyhow!(
@@ -27,15 +72,32 @@ impl Project {
             ));
         } else {
             let settings = TerminalSettings::get_global(cx);
-            let python_settings = settings.detect_venv.clone();
-            let shell = settings.shell.clone();
+            // Activating a virtual environment runs a script from the project.
+            let python_settings = if working_directory.as_deref().map_or(true, |directory| {
+                ProjectTrust::is_path_trusted(directory, cx) == Some(true)
+            }) {
+                settings.detect_venv.clone()
+            } else {
+                VenvSettings::Off
+            };
+            let shell = profile
+                .and_then(|profile| profile.shell.clone())
+                .unwrap_or_else(|| settings.shell.clone());
+            let mut env = environment
+                .map(|environment| environment.vars.clone().into_iter().collect())
+                .unwrap_or_else(HashMap::new);
+            env.extend(settings.env.clone());
+            if let Some(profile) = profile {
+                env.extend(profile.env.clone());
+            }
 
             let terminal = TerminalBuilder::new(
                 working_directory.clone(),
                 shell.clone(),
-                settings.env.clone(),
+                env,
                 Some(settings.blinking.clone()),
                 settings.alternate_scroll,
+                settings.shell_integration,
                 window,
             )
             .map(|builder| {
@@ -1,6 +1,8 @@
 use crate::{
-    copy_recursive, ignore::IgnoreStack, project_settings::ProjectSettings, DiagnosticSummary,
-    ProjectEntryId, RemoveOptions,
+    copy_recursive,
+    ignore::IgnoreStack,
+    project_settings::{FileWatcherMode, FileWatcherSettings, ProjectSettings},
+    DiagnosticSummary, ProjectEntryId, RemoveOptions,
 };
 use ::ignore::gitignore::{Gitignore, GitignoreBuilder};
 use anyhow::{anyhow, Context as _, Result};
@@ -32,8 +34,8 @@ use language::{
         deserialize_fingerprint, deserialize_version, serialize_fingerprint, serialize_line_ending,
         serialize_version,
     },
-    Buffer, Capability, DiagnosticEntry, File as _, LineEnding, PointUtf16, Rope, RopeFingerprint,
-    Unclipped,
+    Buffer, Capability, DiagnosticEntry, Encoding, File as _, LineEnding, PointUtf16, Rope,
+    RopeFingerprint, Unclipped,
 };
 use lsp::LanguageServerId;
 use parking_lot::Mutex;
@@ -81,6 +83,7 @@ pub struct LocalWorktree {
     scan_requests_tx: channel::Sender<ScanRequest>,
     path_prefixes_to_scan_tx: channel::Sender<Arc<Path>>,
     is_scanning: (watch::Sender<bool>, watch::Receiver<bool>),
+    file_watcher: FileWatcherSettings,
     _background_scanner_tasks: Vec<Task<()>>,
     share: Option<ShareState>,
     diagnostics: HashMap<
@@ -230,6 +233,10 @@ pub struct LocalSnapshot {
     /// id of their parent directory.
     git_repositories: TreeMap<ProjectEntryId, LocalRepositoryEntry>,
     file_scan_exclusions: Vec<PathMatcher>,
+    file_scan_deferred_directories: Vec<PathMatcher>,
+    /// Whether symlinks to directories outside of the worktree are scanned
+    /// like any other directory, rather than only once they're expanded.
+    follow_symlinks: bool,
 }
 
 struct BackgroundScannerState {
@@ -244,6 +251,11 @@ struct BackgroundScannerState {
     removed_entry_ids: HashMap<u64, ProjectEntryId>,
     changed_paths: Vec<Arc<Path>>,
     prev_snapshot: Snapshot,
+    /// The worktree paths of followed symlinks, indexed by the canonical
+    /// paths of their targets outside of the worktree.
+    symlink_targets: HashMap<PathBuf, Arc<Path>>,
+    /// Symlink targets that have been discovered but aren't watched yet.
+    unwatched_symlink_targets: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -319,10 +331,23 @@ impl Worktree {
         cx.new_model(move |cx: &mut ModelContext<Worktree>| {
             cx.observe_global::<SettingsStore>(move |this, cx| {
                 if let Self::Local(this) = this {
-                    let new_file_scan_exclusions =
-                        file_scan_exclusions(ProjectSettings::get_global(cx));
-                    if new_file_scan_exclusions != this.snapshot.file_scan_exclusions {
+                    let project_settings = ProjectSettings::get_global(cx);
+                    let new_file_scan_exclusions = file_scan_exclusions(project_settings);
+                    let new_file_scan_deferred_directories =
+                        file_scan_deferred_directories(project_settings);
+                    let new_file_watcher = project_settings.file_watcher.clone();
+                    let new_follow_symlinks = project_settings.follow_symlinks;
+                    if new_file_scan_exclusions != this.snapshot.file_scan_exclusions
+                        || new_file_scan_deferred_directories
+                            != this.snapshot.file_scan_deferred_directories
+                        || new_file_watcher != this.file_watcher
+                        || new_follow_symlinks != this.snapshot.follow_symlinks
+                    {
                         this.snapshot.file_scan_exclusions = new_file_scan_exclusions;
+                        this.snapshot.file_scan_deferred_directories =
+                            new_file_scan_deferred_directories;
+                        this.snapshot.follow_symlinks = new_follow_symlinks;
+                        this.file_watcher = new_file_watcher;
                         log::info!(
                             "Re-scanning directories, new scan exclude files: {:?}",
                             this.snapshot
@@ -358,6 +383,10 @@ impl Worktree {
 
             let mut snapshot = LocalSnapshot {
                 file_scan_exclusions: file_scan_exclusions(ProjectSettings::get_global(cx)),
+                file_scan_deferred_directories: file_scan_deferred_directories(
+                    ProjectSettings::get_global(cx),
+                ),
+                follow_symlinks: ProjectSettings::get_global(cx).follow_symlinks,
                 ignores_by_parent_abs_path: Default::default(),
                 git_repositories: Default::default(),
                 snapshot: Snapshot {
@@ -394,6 +423,7 @@ impl Worktree {
                 share: None,
                 scan_requests_tx,
                 path_prefixes_to_scan_tx,
+                file_watcher: ProjectSettings::get_global(cx).file_watcher.clone(),
                 _background_scanner_tasks: start_background_scan_tasks(
                     &abs_path,
                     task_snapshot,
@@ -605,11 +635,15 @@ fn start_background_scan_tasks(
     cx: &mut ModelContext<'_, Worktree>,
 ) -> Vec<Task<()>> {
     let (scan_states_tx, mut scan_states_rx) = mpsc::unbounded();
+    let file_watcher = ProjectSettings::get_global(cx).file_watcher.clone();
     let background_scanner = cx.background_executor().spawn({
         let abs_path = abs_path.to_path_buf();
         let background = cx.background_executor().clone();
         async move {
-            let events = fs.watch(&abs_path, Duration::from_millis(100)).await;
+            let events = match poll_interval(&file_watcher, &abs_path, fs.as_ref()).await {
+                Some(interval) => fs.poll(&abs_path, interval).await,
+                None => fs.watch(&abs_path, Duration::from_millis(100)).await,
+            };
             BackgroundScanner::new(
                 snapshot,
                 next_entry_id,
@@ -650,15 +684,50 @@ fn start_background_scan_tasks(
     vec![background_scanner, scan_state_updater]
 }
 
+/// How often a worktree is scanned for changes, if it's polled rather than watched with
+/// the platform's file watcher.
+async fn poll_interval(
+    file_watcher: &FileWatcherSettings,
+    abs_path: &Path,
+    fs: &dyn Fs,
+) -> Option<Duration> {
+    let is_polling_path = file_watcher
+        .polling_paths
+        .iter()
+        .filter_map(|pattern| PathMatcher::new(pattern).log_err())
+        .any(|matcher| matcher.is_match(abs_path));
+    let poll = is_polling_path
+        || match file_watcher.mode {
+            FileWatcherMode::Auto => fs.is_network_filesystem(abs_path).await,
+            FileWatcherMode::Native => false,
+            FileWatcherMode::Polling => true,
+        };
+    poll.then(|| Duration::from_millis(file_watcher.poll_interval_ms))
+}
+
 fn file_scan_exclusions(project_settings: &ProjectSettings) -> Vec<PathMatcher> {
-    project_settings.file_scan_exclusions.as_deref().unwrap_or(&[]).iter()
+    path_matchers(
+        project_settings.file_scan_exclusions.as_deref(),
+        "file_scan_exclusions",
+    )
+}
+
+fn file_scan_deferred_directories(project_settings: &ProjectSettings) -> Vec<PathMatcher> {
+    path_matchers(
+        project_settings.file_scan_deferred_directories.as_deref(),
+        "file_scan_deferred_directories",
+    )
+}
+
+fn path_matchers(patterns: Option<&[String]>, setting_name: &str) -> Vec<PathMatcher> {
+    patterns.unwrap_or(&[]).iter()
     .sorted()
     .filter_map(|pattern| {
         PathMatcher::new(pattern)
             .map(Some)
             .unwrap_or_else(|e| {
                 log::error!(
-                    "Skipping pattern {pattern} in `file_scan_exclusions` project settings due to parsing error: {e:#}"
+                    "Skipping pattern {pattern} in `{setting_name}` project settings due to parsing error: {e:#}"
                 );
                 None
             })
@@ -666,6 +735,17 @@ fn file_scan_exclusions(project_settings: &ProjectSettings) -> Vec<PathMatcher>
     .collect()
 }
 
+/// Whether a file is opened as read-only because its path matches the `read_only_files`
+/// project settings.
+fn is_read_only_path(project_settings: &ProjectSettings, path: &Path) -> bool {
+    project_settings
+        .read_only_files
+        .iter()
+        .flatten()
+        .filter_map(|pattern| PathMatcher::new(pattern).log_err())
+        .any(|matcher| matcher.is_match(path))
+}
+
 impl LocalWorktree {
     pub fn contains_abs_path(&self, path: &Path) -> bool {
         path.starts_with(&self.abs_path)
@@ -678,21 +758,29 @@ impl LocalWorktree {
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<Model<Buffer>>> {
         let path = Arc::from(path);
+        let abs_path = self.absolutize(&path);
+        let fs = self.fs.clone();
+        let is_read_only_path = is_read_only_path(ProjectSettings::get_global(cx), &path);
         cx.spawn(move |this, mut cx| async move {
-            let (file, contents, diff_base) = this
+            let (file, contents, encoding, diff_base) = this
                 .update(&mut cx, |t, cx| t.as_local().unwrap().load(&path, cx))?
                 .await?;
+            let is_read_only_file = fs
+                .metadata(&abs_path?)
+                .await?
+                .map_or(false, |metadata| metadata.is_read_only);
+            let capability = if is_read_only_path || is_read_only_file {
+                Capability::ReadOnly
+            } else {
+                Capability::ReadWrite
+            };
             let text_buffer = cx
                 .background_executor()
                 .spawn(async move { text::Buffer::new(0, id, contents) })
                 .await;
             cx.new_model(|_| {
-                Buffer::build(
-                    text_buffer,
-                    diff_base,
-                    Some(Arc::new(file)),
-                    Capability::ReadWrite,
-                )
+                Buffer::build(text_buffer, diff_base, Some(Arc::new(file)), capability)
+                    .with_encoding(encoding)
             })
         })
     }
@@ -961,7 +1049,7 @@ impl LocalWorktree {
         &self,
         path: &Path,
         cx: &mut ModelContext<Worktree>,
-    ) -> Task<Result<(File, String, Option<String>)>> {
+    ) -> Task<Result<(File, String, Encoding, Option<String>)>> {
         let path = Arc::from(path);
         let abs_path = self.absolutize(&path);
         let fs = self.fs.clone();
@@ -969,7 +1057,7 @@ impl LocalWorktree {
 
         cx.spawn(|this, mut cx| async move {
             let abs_path = abs_path?;
-            let text = fs.load(&abs_path).await?;
+            let (text, encoding) = fs.load_with_encoding(&abs_path, None).await?;
             let mut index_task = None;
             let snapshot = this.update(&mut cx, |this, _| this.as_local().unwrap().snapshot())?;
             if let Some(repo) = snapshot.repository_for_path(&path) {
@@ -1005,6 +1093,7 @@ impl LocalWorktree {
                         is_deleted: false,
                     },
                     text,
+                    encoding,
                     diff_base,
                 )),
                 None => {
@@ -1027,6 +1116,7 @@ impl LocalWorktree {
                             is_deleted: false,
                         },
                         text,
+                        encoding,
                         diff_base,
                     ))
                 }
@@ -1050,7 +1140,13 @@ impl LocalWorktree {
         let text = buffer.as_rope().clone();
         let fingerprint = text.fingerprint();
         let version = buffer.version();
-        let save = self.write_file(path.as_ref(), text, buffer.line_ending(), cx);
+        let save = self.write_file(
+            path.as_ref(),
+            text,
+            buffer.line_ending(),
+            buffer.encoding(),
+            cx,
+        );
         let fs = Arc::clone(&self.fs);
         let abs_path = self.absolutize(&path);
 
@@ -1188,14 +1284,16 @@ impl LocalWorktree {
         path: impl Into<Arc<Path>>,
         text: Rope,
         line_ending: LineEnding,
+        encoding: Encoding,
         cx: &mut ModelContext<Worktree>,
     ) -> Task<Result<Option<Entry>>> {
         let path: Arc<Path> = path.into();
         let abs_path = self.absolutize(&path);
         let fs = self.fs.clone();
-        let write = cx
-            .background_executor()
-            .spawn(async move { fs.save(&abs_path?, &text, line_ending).await });
+        let write = cx.background_executor().spawn(async move {
+            fs.save_with_encoding(&abs_path?, &text, line_ending, encoding)
+                .await
+        });
 
         cx.spawn(|this, mut cx| async move {
             write.await?;
@@ -1337,6 +1435,22 @@ impl LocalWorktree {
         self.path_prefixes_to_scan_tx.try_send(path_prefix).ok();
     }
 
+    /// Scans the directories that are skipped because they match the
+    /// `file_scan_deferred_directories` project settings, along with everything in them.
+    pub fn load_deferred_directories(&self) {
+        for entry in self.snapshot.entries(false) {
+            if entry.kind == EntryKind::UnloadedDir
+                && self
+                    .snapshot
+                    .file_scan_deferred_directories
+                    .iter()
+                    .any(|matcher| matcher.is_match(&entry.path))
+            {
+                self.add_path_prefix_to_scan(entry.path.clone());
+            }
+        }
+    }
+
     fn refresh_entry(
         &self,
         path: Arc<Path>,
@@ -1937,7 +2051,7 @@ impl Snapshot {
             .map(|entry| &entry.path)
     }
 
-    fn child_entries<'a>(&'a self, parent_path: &'a Path) -> ChildEntriesIter<'a> {
+    pub fn child_entries<'a>(&'a self, parent_path: &'a Path) -> ChildEntriesIter<'a> {
         let mut cursor = self.entries_by_path.cursor();
         cursor.seek(&TraversalTarget::Path(parent_path), Bias::Right, &());
         let traversal = Traversal {
@@ -2018,6 +2132,18 @@ impl Snapshot {
     pub fn inode_for_path(&self, path: impl AsRef<Path>) -> Option<u64> {
         self.entry_for_path(path.as_ref()).map(|e| e.inode)
     }
+
+    /// Whether the entry is a symlink to one of the directories containing it,
+    /// which is never scanned.
+    pub fn is_symlink_cycle(&self, entry: &Entry) -> bool {
+        entry.is_symlink
+            && entry.is_dir()
+            && entry
+                .path
+                .ancestors()
+                .skip(1)
+                .any(|ancestor| self.inode_for_path(ancestor) == Some(entry.inode))
+    }
 }
 
 impl LocalSnapshot {
@@ -2295,6 +2421,16 @@ impl LocalSnapshot {
         paths
     }
 
+    /// Whether the path is within a directory that's only scanned on demand, because it
+    /// matches the `file_scan_deferred_directories` project settings.
+    pub fn is_path_deferred(&self, path: &Path) -> bool {
+        path.ancestors().any(|ancestor| {
+            self.file_scan_deferred_directories
+                .iter()
+                .any(|matcher| matcher.is_match(ancestor))
+        })
+    }
+
     pub fn is_path_excluded(&self, mut path: PathBuf) -> bool {
         loop {
             if self
@@ -2312,8 +2448,32 @@ impl LocalSnapshot {
 }
 
 impl BackgroundScannerState {
+    fn watch_symlink_target(&mut self, target: PathBuf, path: Arc<Path>) {
+        if self.symlink_targets.insert(target.clone(), path).is_none() {
+            self.unwatched_symlink_targets.push(target);
+        }
+    }
+
+    /// Maps a path within the target of a followed symlink to the path it's
+    /// visible at through the symlink, within the worktree's root.
+    fn abs_path_via_symlink(&self, abs_path: &Path, root_canonical_path: &Path) -> Option<PathBuf> {
+        if abs_path.starts_with(root_canonical_path) {
+            return None;
+        }
+        let (target, path) = self
+            .symlink_targets
+            .iter()
+            .find(|(target, _)| abs_path.starts_with(target))?;
+        let mut abs_path_via_symlink = root_canonical_path.join(path);
+        let suffix = abs_path.strip_prefix(target).ok()?;
+        if suffix.file_name().is_some() {
+            abs_path_via_symlink.push(suffix);
+        }
+        Some(abs_path_via_symlink)
+    }
+
     fn should_scan_directory(&self, entry: &Entry) -> bool {
-        (!entry.is_external && !entry.is_ignored)
+        (!entry.is_external && !entry.is_ignored && !self.snapshot.is_path_deferred(&entry.path))
             || entry.path.file_name() == Some(*DOT_GIT)
             || self.scanned_dirs.contains(&entry.id) // If we've ever scanned it, keep scanning
             || self
@@ -2831,12 +2991,14 @@ impl language::LocalFile for File {
         }
     }
 
-    fn load(&self, cx: &AppContext) -> Task<Result<String>> {
+    fn load(&self, encoding: Encoding, cx: &AppContext) -> Task<Result<String>> {
         let worktree = self.worktree.read(cx).as_local().unwrap();
         let abs_path = worktree.absolutize(&self.path);
         let fs = worktree.fs.clone();
-        cx.background_executor()
-            .spawn(async move { fs.load(&abs_path?).await })
+        cx.background_executor().spawn(async move {
+            let (text, _) = fs.load_with_encoding(&abs_path?, Some(encoding)).await?;
+            Ok(text)
+        })
     }
 
     fn buffer_reloaded(
@@ -2940,7 +3102,8 @@ pub struct Entry {
     ///
     /// We only scan entries outside of the worktree once the symlinked
     /// directory is expanded. External entries are treated like gitignored
-    /// entries in that they are not included in searches.
+    /// entries in that they are not included in searches. When the
+    /// `follow_symlinks` setting is enabled, no entries are external.
     pub is_external: bool,
     pub git_status: Option<GitFileStatus>,
 }
@@ -3180,6 +3343,9 @@ struct BackgroundScanner {
     executor: BackgroundExecutor,
     scan_requests_rx: channel::Receiver<ScanRequest>,
     path_prefixes_to_scan_rx: channel::Receiver<Arc<Path>>,
+    symlink_events_tx: channel::Sender<Vec<PathBuf>>,
+    symlink_events_rx: channel::Receiver<Vec<PathBuf>>,
+    symlink_target_watches: Vec<Task<()>>,
     next_entry_id: Arc<AtomicUsize>,
     phase: BackgroundScannerPhase,
 }
@@ -3201,12 +3367,16 @@ impl BackgroundScanner {
         scan_requests_rx: channel::Receiver<ScanRequest>,
         path_prefixes_to_scan_rx: channel::Receiver<Arc<Path>>,
     ) -> Self {
+        let (symlink_events_tx, symlink_events_rx) = channel::unbounded();
         Self {
             fs,
             status_updates_tx,
             executor,
             scan_requests_rx,
             path_prefixes_to_scan_rx,
+            symlink_events_tx,
+            symlink_events_rx,
+            symlink_target_watches: Vec::new(),
             next_entry_id,
             state: Mutex::new(BackgroundScannerState {
                 prev_snapshot: snapshot.snapshot.clone(),
@@ -3216,6 +3386,8 @@ impl BackgroundScanner {
                 paths_to_scan: Default::default(),
                 removed_entry_ids: Default::default(),
                 changed_paths: Default::default(),
+                symlink_targets: Default::default(),
+                unwatched_symlink_targets: Default::default(),
             }),
             phase: BackgroundScannerPhase::InitialScan,
         }
@@ -3279,6 +3451,8 @@ impl BackgroundScanner {
         // Continue processing events until the worktree is dropped.
         self.phase = BackgroundScannerPhase::Events;
         loop {
+            self.watch_symlink_targets().await;
+
             select_biased! {
                 // Process any path refresh requests from the worktree. Prioritize
                 // these before handling changes reported by the filesystem.
@@ -3317,10 +3491,41 @@ impl BackgroundScanner {
                     }
                     self.process_events(paths.clone()).await;
                 }
+
+                paths = self.symlink_events_rx.recv().fuse() => {
+                    let Ok(mut paths) = paths else { break };
+                    while let Ok(more_paths) = self.symlink_events_rx.try_recv() {
+                        paths.extend(more_paths);
+                    }
+                    self.process_events(paths).await;
+                }
             }
         }
     }
 
+    /// Starts watching the targets of any symlinks that were followed outside
+    /// of the worktree, since changes there aren't reported for the worktree's root.
+    async fn watch_symlink_targets(&mut self) {
+        let targets = mem::take(&mut self.state.lock().unwatched_symlink_targets);
+        for target in targets {
+            log::debug!("watching symlink target {target:?}");
+            let mut events = self.fs.watch(&target, Duration::from_millis(100)).await;
+            let symlink_events_tx = self.symlink_events_tx.clone();
+            self.symlink_target_watches
+                .push(self.executor.spawn(async move {
+                    while let Some(events) = events.next().await {
+                        if symlink_events_tx
+                            .send(fs::fs_events_paths(events))
+                            .await
+                            .is_err()
+                        {
+                            break;
+                        }
+                    }
+                }));
+        }
+    }
+
     async fn process_scan_request(&self, mut request: ScanRequest, scanning: bool) -> bool {
         log::debug!("rescanning paths {:?}", request.relative_paths);
 
@@ -3368,6 +3573,15 @@ impl BackgroundScanner {
             }
         };
 
+        {
+            let state = self.state.lock();
+            for abs_path in &mut abs_paths {
+                if let Some(path) = state.abs_path_via_symlink(abs_path, &root_canonical_path) {
+                    *abs_path = path;
+                }
+            }
+        }
+
         let mut relative_paths = Vec::with_capacity(abs_paths.len());
         let mut dot_git_paths_to_reload = HashSet::default();
         abs_paths.sort_unstable();
@@ -3589,6 +3803,7 @@ impl BackgroundScanner {
         let mut new_ignore;
         let root_char_bag;
         let next_entry_id;
+        let follow_symlinks;
         {
             let state = self.state.lock();
             let snapshot = &state.snapshot;
@@ -3601,12 +3816,14 @@ impl BackgroundScanner {
             ignore_stack = job.ignore_stack.clone();
             new_ignore = None;
             root_char_bag = snapshot.root_char_bag;
+            follow_symlinks = snapshot.follow_symlinks;
             next_entry_id = self.next_entry_id.clone();
             drop(state);
         }
 
         let mut dotgit_path = None;
         let mut root_canonical_path = None;
+        let mut symlink_targets = HashMap::default();
         let mut new_entries: Vec<Entry> = Vec::new();
         let mut new_jobs: Vec<Option<ScanJob>> = Vec::new();
         let mut child_paths = self.fs.read_dir(&job.abs_path).await?;
@@ -3719,7 +3936,11 @@ impl BackgroundScanner {
                 };
 
                 if !canonical_path.starts_with(root_canonical_path) {
-                    child_entry.is_external = true;
+                    if follow_symlinks {
+                        symlink_targets.insert(child_path.clone(), canonical_path);
+                    } else {
+                        child_entry.is_external = true;
+                    }
                 }
             }
 
@@ -3745,6 +3966,8 @@ impl BackgroundScanner {
                         containing_repository: job.containing_repository.clone(),
                     }));
                 } else {
+                    log::debug!("skipping symlink cycle at {:?}", child_entry.path);
+                    child_entry.kind = EntryKind::UnloadedDir;
                     new_jobs.push(None);
                 }
             } else {
@@ -3777,6 +4000,9 @@ impl BackgroundScanner {
             state.reuse_entry_id(entry);
             if entry.is_dir() {
                 if state.should_scan_directory(entry) {
+                    if let Some(target) = symlink_targets.remove(&entry.path) {
+                        state.watch_symlink_target(target, entry.path.clone());
+                    }
                     job_ix += 1;
                 } else {
                     log::debug!("defer scanning directory {:?}", entry.path);
@@ -3865,7 +4091,8 @@ impl BackgroundScanner {
                     );
                     let is_dir = fs_entry.is_dir();
                     fs_entry.is_ignored = ignore_stack.is_abs_path_ignored(&abs_path, is_dir);
-                    fs_entry.is_external = !canonical_path.starts_with(&root_canonical_path);
+                    let is_outside_root = !canonical_path.starts_with(&root_canonical_path);
+                    fs_entry.is_external = is_outside_root && !state.snapshot.follow_symlinks;
 
                     if !is_dir && !fs_entry.is_ignored {
                         if let Some((work_dir, repo)) = state.snapshot.local_repo_for_path(path) {
@@ -3879,6 +4106,9 @@ impl BackgroundScanner {
 
                     if let (Some(scan_queue_tx), true) = (&scan_queue_tx, fs_entry.is_dir()) {
                         if state.should_scan_directory(&fs_entry) {
+                            if fs_entry.is_symlink && is_outside_root {
+                                state.watch_symlink_target(canonical_path.clone(), path.clone());
+                            }
                             state.enqueue_scan_dir(abs_path, &fs_entry, scan_queue_tx);
                         } else {
                             fs_entry.kind = EntryKind::UnloadedDir;
@@ -4471,7 +4701,7 @@ impl<'a, 'b> SeekTarget<'a, EntrySummary, (TraversalProgress<'a>, GitStatuses)>
     }
 }
 
-struct ChildEntriesIter<'a> {
+pub struct ChildEntriesIter<'a> {
     parent_path: &'a Path,
     traversal: Traversal<'a>,
 }
@@ -876,6 +876,7 @@ async fn test_write_file(cx: &mut TestAppContext) {
             Path::new("tracked-dir/file.txt"),
             "hello".into(),
             Default::default(),
+            Default::default(),
             cx,
         )
     })
@@ -886,6 +887,7 @@ async fn test_write_file(cx: &mut TestAppContext) {
             Path::new("ignored-dir/file.txt"),
             "world".into(),
             Default::default(),
+            Default::default(),
             cx,
         )
     })
@@ -997,6 +999,147 @@ async fn test_file_scan_exclusions(cx: &mut TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_file_scan_deferred_directories(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<ProjectSettings>(cx, |project_settings| {
+                project_settings.file_scan_deferred_directories =
+                    Some(vec!["**/node_modules".to_string()]);
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "node_modules": {
+                "dep": {
+                    "index.js": "",
+                },
+            },
+            "src": {
+                "lib.rs": "",
+            },
+        }),
+    )
+    .await;
+
+    let tree = Worktree::local(
+        build_client(cx),
+        Path::new("/root"),
+        true,
+        fs,
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    // Deferred directories are listed, but their contents aren't scanned.
+    tree.read_with(cx, |tree, _| {
+        assert_eq!(
+            tree.entry_for_path("node_modules").unwrap().kind,
+            EntryKind::UnloadedDir
+        );
+        assert!(tree.entry_for_path("node_modules/dep").is_none());
+        assert!(tree.entry_for_path("src/lib.rs").is_some());
+    });
+
+    // Loading them scans everything within them.
+    tree.read_with(cx, |tree, _| {
+        tree.as_local().unwrap().load_deferred_directories()
+    });
+    cx.executor().run_until_parked();
+    tree.read_with(cx, |tree, _| {
+        assert_eq!(
+            tree.entry_for_path("node_modules").unwrap().kind,
+            EntryKind::Dir
+        );
+        assert!(tree.entry_for_path("node_modules/dep/index.js").is_some());
+    });
+}
+
+#[gpui::test]
+async fn test_follow_symlinks(cx: &mut TestAppContext) {
+    init_test(cx);
+    cx.update(|cx| {
+        cx.update_global::<SettingsStore, _>(|store, cx| {
+            store.update_user_settings::<ProjectSettings>(cx, |project_settings| {
+                project_settings.follow_symlinks = true;
+            });
+        });
+    });
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/root",
+        json!({
+            "project": {
+                "deps": {},
+                "src": {
+                    "main.rs": "",
+                },
+            },
+            "shared": {
+                "lib.rs": "",
+            },
+        }),
+    )
+    .await;
+    fs.insert_symlink("/root/project/deps/shared", "/root/shared".into())
+        .await;
+    fs.insert_symlink("/root/project/src/cycle", "/root/project".into())
+        .await;
+
+    let tree = Worktree::local(
+        build_client(cx),
+        Path::new("/root/project"),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+
+    // Symlinks outside of the worktree are scanned, but cycles aren't.
+    tree.read_with(cx, |tree, _| {
+        assert_eq!(
+            tree.entries(true)
+                .map(|entry| (entry.path.as_ref(), entry.is_external))
+                .collect::<Vec<_>>(),
+            vec![
+                (Path::new(""), false),
+                (Path::new("deps"), false),
+                (Path::new("deps/shared"), false),
+                (Path::new("deps/shared/lib.rs"), false),
+                (Path::new("src"), false),
+                (Path::new("src/cycle"), false),
+                (Path::new("src/main.rs"), false),
+            ]
+        );
+
+        let cycle = tree.entry_for_path("src/cycle").unwrap();
+        assert_eq!(cycle.kind, EntryKind::UnloadedDir);
+        assert!(tree.is_symlink_cycle(cycle));
+        assert!(!tree.is_symlink_cycle(tree.entry_for_path("deps/shared").unwrap()));
+    });
+
+    // Changes to the symlink's target are noticed.
+    fs.insert_file("/root/shared/mod.rs", "".into()).await;
+    cx.executor().run_until_parked();
+    tree.read_with(cx, |tree, _| {
+        assert!(tree.entry_for_path("deps/shared/mod.rs").is_some());
+    });
+}
+
 #[gpui::test]
 async fn test_fs_events_in_exclusions(cx: &mut TestAppContext) {
     init_test(cx);
@@ -1654,8 +1797,13 @@ fn randomly_mutate_worktree(
                 })
             } else {
                 log::info!("overwriting file {:?} ({})", entry.path, entry.id.0);
-                let task =
-                    worktree.write_file(entry.path.clone(), "".into(), Default::default(), cx);
+                let task = worktree.write_file(
+                    entry.path.clone(),
+                    "".into(),
+                    Default::default(),
+                    Default::default(),
+                    cx,
+                );
                 cx.background_executor().spawn(async move {
                     task.await?;
                     Ok(())
@@ -0,0 +1,619 @@
+//! Scratch buffers are throwaway buffers for notes and snippets that don't belong to any project.
+//! Each one is a file in the scratch directory of Zed's support directory, named after the order
+//! in which it was created and given the file extension of the language picked for it, and is
+//! saved automatically shortly after every edit.
+
+use anyhow::{anyhow, Result};
+use collections::HashSet;
+use editor::Editor;
+use fs::{CreateOptions, Fs};
+use futures::StreamExt;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    AppContext, AsyncWindowContext, DismissEvent, EntityId, EventEmitter, FocusHandle,
+    FocusableView, Model, Render, Task, View, ViewContext, VisualContext, WeakView,
+};
+use language::{Buffer, Language, LanguageRegistry};
+use picker::{Picker, PickerDelegate};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::{paths, ResultExt};
+use workspace::{ModalView, NewScratchBuffer, OpenScratchBuffer, Workspace};
+
+/// How long to wait after an edit before saving a scratch buffer.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The file name prefix shared by all scratch buffers, followed by their number.
+const FILE_NAME_PREFIX: &str = "scratch-";
+
+/// The extension given to scratch buffers whose language doesn't have one.
+const DEFAULT_EXTENSION: &str = "txt";
+
+/// The longest title shown for a scratch buffer in the picker.
+const MAX_TITLE_LEN: usize = 80;
+
+/// The scratch buffers that are already saved automatically, so that reopening one doesn't
+/// subscribe to its edits twice.
+#[derive(Default)]
+struct AutosavedBuffers(HashSet<EntityId>);
+
+pub fn init(cx: &mut AppContext) {
+    cx.set_global(AutosavedBuffers::default());
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(toggle_language_picker);
+            workspace.register_action(toggle_scratch_buffer_picker);
+        },
+    )
+    .detach();
+}
+
+fn toggle_language_picker(
+    workspace: &mut Workspace,
+    _: &NewScratchBuffer,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let app_state = workspace.app_state().clone();
+    let weak_workspace = cx.view().downgrade();
+    toggle_modal(workspace, cx, move |modal| {
+        LanguagePickerDelegate::new(
+            modal,
+            weak_workspace,
+            app_state.fs.clone(),
+            &app_state.languages,
+        )
+    });
+}
+
+fn toggle_scratch_buffer_picker(
+    workspace: &mut Workspace,
+    _: &OpenScratchBuffer,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let fs = workspace.app_state().fs.clone();
+    cx.spawn(|workspace, mut cx| async move {
+        let scratch_buffers = scratch_buffers(fs.as_ref()).await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            let weak_workspace = cx.view().downgrade();
+            toggle_modal(workspace, cx, move |modal| {
+                ScratchBufferPickerDelegate::new(modal, weak_workspace, scratch_buffers)
+            });
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+fn toggle_modal<D: PickerDelegate>(
+    workspace: &mut Workspace,
+    cx: &mut ViewContext<Workspace>,
+    build_delegate: impl FnOnce(WeakView<ScratchBufferModal<D>>) -> D,
+) {
+    workspace.toggle_modal(cx, |cx| {
+        let delegate = build_delegate(cx.view().downgrade());
+        ScratchBufferModal {
+            picker: cx.new_view(|cx| Picker::new(delegate, cx)),
+        }
+    });
+}
+
+/// Creates a scratch buffer in the given language and opens it in the workspace.
+pub fn new_scratch_buffer(
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    language: Arc<Language>,
+    cx: &mut WindowContext,
+) -> Task<Result<Model<Buffer>>> {
+    cx.spawn(|mut cx| async move {
+        fs.create_dir(&paths::SCRATCH_BUFFERS_DIR).await?;
+        let number = scratch_buffers(fs.as_ref())
+            .await?
+            .iter()
+            .filter_map(|scratch_buffer| scratch_buffer_number(&scratch_buffer.path))
+            .max()
+            .unwrap_or(0)
+            + 1;
+        let extension = language
+            .path_suffixes()
+            .first()
+            .map_or(DEFAULT_EXTENSION, String::as_str);
+        let path =
+            paths::SCRATCH_BUFFERS_DIR.join(format!("{FILE_NAME_PREFIX}{number}.{extension}"));
+        fs.create_file(&path, CreateOptions::default()).await?;
+
+        let buffer = open_scratch_buffer(workspace.clone(), path, &mut cx).await?;
+        // Some languages are detected from whole file names rather than extensions, so the
+        // language is set explicitly rather than relying on the path to select it.
+        workspace.update(&mut cx, |workspace, cx| {
+            workspace.project().update(cx, |project, cx| {
+                project.set_language_for_buffer(&buffer, language, cx)
+            })
+        })?;
+        Ok(buffer)
+    })
+}
+
+async fn open_scratch_buffer(
+    workspace: WeakView<Workspace>,
+    path: PathBuf,
+    cx: &mut AsyncWindowContext,
+) -> Result<Model<Buffer>> {
+    let item = workspace
+        .update(cx, |workspace, cx| workspace.open_abs_path(path, false, cx))?
+        .await?;
+    workspace.update(cx, |workspace, cx| {
+        let buffer = item
+            .downcast::<Editor>()
+            .and_then(|editor| editor.read(cx).buffer().read(cx).as_singleton())
+            .ok_or_else(|| anyhow!("scratch buffer was not opened in an editor"))?;
+        autosave(workspace, buffer.clone(), cx);
+        Ok(buffer)
+    })?
+}
+
+/// Saves the buffer shortly after each edit, for as long as both it and the workspace exist.
+fn autosave(workspace: &mut Workspace, buffer: Model<Buffer>, cx: &mut ViewContext<Workspace>) {
+    let buffer_id = buffer.entity_id();
+    if !cx.global_mut::<AutosavedBuffers>().0.insert(buffer_id) {
+        return;
+    }
+
+    let project = workspace.project().downgrade();
+    let mut _save_task = None;
+    cx.subscribe(&buffer, move |_, buffer, event, cx| {
+        if let language::Event::Edited = event {
+            let project = project.clone();
+            _save_task = Some(cx.spawn(|_, mut cx| async move {
+                cx.background_executor().timer(SAVE_DEBOUNCE).await;
+                let save = project.update(&mut cx, |project, cx| {
+                    buffer
+                        .read(cx)
+                        .is_dirty()
+                        .then(|| project.save_buffer(buffer, cx))
+                });
+                if let Some(Some(save)) = save.log_err() {
+                    save.await.log_err();
+                }
+            }));
+        }
+    })
+    .detach();
+    cx.observe_release(&buffer, move |_, _, cx| {
+        cx.global_mut::<AutosavedBuffers>().0.remove(&buffer_id);
+    })
+    .detach();
+}
+
+struct ScratchBuffer {
+    path: PathBuf,
+    /// The buffer's first non-blank line.
+    title: String,
+    mtime: SystemTime,
+}
+
+/// Lists the scratch buffers, most recently modified first.
+async fn scratch_buffers(fs: &dyn Fs) -> Result<Vec<ScratchBuffer>> {
+    let dir: &Path = &paths::SCRATCH_BUFFERS_DIR;
+    if fs.metadata(dir).await?.is_none() {
+        return Ok(Vec::new());
+    }
+
+    let mut paths = fs.read_dir(dir).await?;
+    let mut scratch_buffers = Vec::new();
+    while let Some(path) = paths.next().await {
+        let path = path?;
+        let Some(metadata) = fs.metadata(&path).await? else {
+            continue;
+        };
+        if metadata.is_dir || scratch_buffer_number(&path).is_none() {
+            continue;
+        }
+        let title = title_for_text(&fs.load(&path).await?);
+        scratch_buffers.push(ScratchBuffer {
+            path,
+            title,
+            mtime: metadata.mtime,
+        });
+    }
+    scratch_buffers.sort_by(|a, b| b.mtime.cmp(&a.mtime));
+    Ok(scratch_buffers)
+}
+
+fn scratch_buffer_number(path: &Path) -> Option<usize> {
+    path.file_stem()?
+        .to_str()?
+        .strip_prefix(FILE_NAME_PREFIX)?
+        .parse()
+        .ok()
+}
+
+fn title_for_text(text: &str) -> String {
+    let line = text
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or("Empty");
+    match line.char_indices().nth(MAX_TITLE_LEN) {
+        Some((ix, _)) => format!("{}…", &line[..ix]),
+        None => line.to_string(),
+    }
+}
+
+pub struct ScratchBufferModal<D: PickerDelegate> {
+    picker: View<Picker<D>>,
+}
+
+impl<D: PickerDelegate> ModalView for ScratchBufferModal<D> {}
+
+impl<D: PickerDelegate> EventEmitter<DismissEvent> for ScratchBufferModal<D> {}
+
+impl<D: PickerDelegate> FocusableView for ScratchBufferModal<D> {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl<D: PickerDelegate> Render for ScratchBufferModal<D> {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+/// Picks the language of a new scratch buffer.
+pub struct LanguagePickerDelegate {
+    modal: WeakView<ScratchBufferModal<Self>>,
+    workspace: WeakView<Workspace>,
+    fs: Arc<dyn Fs>,
+    languages: Arc<LanguageRegistry>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl LanguagePickerDelegate {
+    fn new(
+        modal: WeakView<ScratchBufferModal<Self>>,
+        workspace: WeakView<Workspace>,
+        fs: Arc<dyn Fs>,
+        languages: &Arc<LanguageRegistry>,
+    ) -> Self {
+        let candidates = languages
+            .language_names()
+            .into_iter()
+            .enumerate()
+            .map(|(candidate_id, name)| StringMatchCandidate::new(candidate_id, name))
+            .collect();
+        Self {
+            modal,
+            workspace,
+            fs,
+            languages: languages.clone(),
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for LanguagePickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Select a language for the scratch buffer...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let language = self
+                .languages
+                .language_for_name(&self.candidates[mat.candidate_id].string);
+            let workspace = self.workspace.clone();
+            let fs = self.fs.clone();
+            cx.spawn(|_, mut cx| async move {
+                let language = language.await?;
+                cx.update(|cx| new_scratch_buffer(workspace, fs, language, cx))?
+                    .await?;
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.modal
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self.candidates.clone();
+        let matches = fuzzy_matches(candidates, query, cx);
+        cx.spawn(|this, mut cx| async move {
+            let matches = matches.await;
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                )),
+        )
+    }
+}
+
+/// Picks an existing scratch buffer to open, matching against the buffers' titles.
+pub struct ScratchBufferPickerDelegate {
+    modal: WeakView<ScratchBufferModal<Self>>,
+    workspace: WeakView<Workspace>,
+    scratch_buffers: Vec<ScratchBuffer>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl ScratchBufferPickerDelegate {
+    fn new(
+        modal: WeakView<ScratchBufferModal<Self>>,
+        workspace: WeakView<Workspace>,
+        scratch_buffers: Vec<ScratchBuffer>,
+    ) -> Self {
+        Self {
+            modal,
+            workspace,
+            scratch_buffers,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for ScratchBufferPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Open a scratch buffer...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let path = self.scratch_buffers[mat.candidate_id].path.clone();
+            let workspace = self.workspace.clone();
+            cx.spawn(|_, mut cx| async move {
+                open_scratch_buffer(workspace, path, &mut cx).await?;
+                anyhow::Ok(())
+            })
+            .detach_and_log_err(cx);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.modal
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .scratch_buffers
+            .iter()
+            .enumerate()
+            .map(|(id, scratch_buffer)| StringMatchCandidate::new(id, scratch_buffer.title.clone()))
+            .collect();
+        let matches = fuzzy_matches(candidates, query, cx);
+        cx.spawn(|this, mut cx| async move {
+            let matches = matches.await;
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        let file_name = self.scratch_buffers[mat.candidate_id]
+            .path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                ))
+                .end_slot(Label::new(file_name).color(Color::Muted)),
+        )
+    }
+}
+
+/// Matches the query against the candidates, keeping their order when the query is empty.
+fn fuzzy_matches<D: PickerDelegate>(
+    candidates: Vec<StringMatchCandidate>,
+    query: String,
+    cx: &mut ViewContext<Picker<D>>,
+) -> Task<Vec<StringMatch>> {
+    let background = cx.background_executor().clone();
+    cx.background_executor().spawn(async move {
+        if query.is_empty() {
+            candidates
+                .into_iter()
+                .map(|candidate| StringMatch {
+                    candidate_id: candidate.id,
+                    string: candidate.string,
+                    positions: Vec::new(),
+                    score: 0.0,
+                })
+                .collect()
+        } else {
+            match_strings(
+                &candidates,
+                &query,
+                false,
+                100,
+                &Default::default(),
+                background,
+            )
+            .await
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use language::LanguageConfig;
+    use project::Project;
+    use workspace::AppState;
+
+    #[gpui::test]
+    async fn test_scratch_buffers(cx: &mut TestAppContext) {
+        let app_state = cx.update(|cx| {
+            let app_state = AppState::test(cx);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            init(cx);
+            app_state
+        });
+        let rust = Arc::new(Language::new(
+            LanguageConfig {
+                name: "Rust".into(),
+                path_suffixes: vec!["rs".into()],
+                ..Default::default()
+            },
+            None,
+        ));
+        app_state.languages.add(rust.clone());
+
+        let project = Project::test(app_state.fs.clone(), [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let buffer = cx
+            .update(|cx| {
+                new_scratch_buffer(
+                    workspace.downgrade(),
+                    app_state.fs.clone(),
+                    rust.clone(),
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        let path = paths::SCRATCH_BUFFERS_DIR.join("scratch-1.rs");
+        buffer.read_with(cx, |buffer, _| {
+            assert_eq!(
+                buffer.language().map(|language| language.name()),
+                Some("Rust".into())
+            );
+        });
+
+        // Edits are saved without being asked to.
+        buffer.update(cx, |buffer, cx| {
+            buffer.edit([(0..0, "fn main() {}")], None, cx)
+        });
+        cx.executor().advance_clock(SAVE_DEBOUNCE);
+        cx.run_until_parked();
+        assert_eq!(app_state.fs.load(&path).await.unwrap(), "fn main() {}");
+        assert!(!buffer.read_with(cx, |buffer, _| buffer.is_dirty()));
+
+        // New scratch buffers are numbered after the existing ones, and listed most recently
+        // modified first.
+        let buffer = cx
+            .update(|cx| new_scratch_buffer(workspace.downgrade(), app_state.fs.clone(), rust, cx))
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "// Notes")], None, cx));
+        cx.executor().advance_clock(SAVE_DEBOUNCE);
+        cx.run_until_parked();
+        let scratch_buffers = scratch_buffers(app_state.fs.as_ref()).await.unwrap();
+        assert_eq!(
+            scratch_buffers
+                .iter()
+                .map(|scratch_buffer| (scratch_buffer.path.clone(), scratch_buffer.title.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (paths::SCRATCH_BUFFERS_DIR.join("scratch-2.rs"), "// Notes"),
+                (path, "fn main() {}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_title_for_text() {
+        assert_eq!(title_for_text(""), "Empty");
+        assert_eq!(
+            title_for_text("\n  \n  # Groceries  \n- eggs"),
+            "# Groceries"
+        );
+        assert_eq!(
+            title_for_text(&"a".repeat(MAX_TITLE_LEN + 1)),
+            format!("{}…", "a".repeat(MAX_TITLE_LEN))
+        );
+    }
+}
@@ -1,7 +1,7 @@
 mod registrar;
 
 use crate::{
-    history::SearchHistory,
+    history::{SearchHistory, SearchHistoryKind},
     mode::{next_mode, SearchMode},
     search_bar::render_nav_button,
     ActivateRegexMode, ActivateTextMode, CycleMode, NextHistoryQuery, PreviousHistoryQuery,
@@ -73,7 +73,7 @@ pub struct BufferSearchBar {
 
 impl BufferSearchBar {
     fn render_text_input(&self, editor: &View<Editor>, cx: &ViewContext<Self>) -> impl IntoElement {
-        let settings = ThemeSettings::get_global(cx);
+        let settings = ThemeSettings::for_window(cx);
         let text_style = TextStyle {
             color: if editor.read(cx).read_only(cx) {
                 cx.theme().colors().text_disabled
@@ -81,7 +81,8 @@ impl BufferSearchBar {
                 cx.theme().colors().text
             },
             font_family: settings.ui_font.family.clone(),
-            font_features: settings.ui_font.features,
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
             font_size: rems(0.875).into(),
             font_weight: FontWeight::NORMAL,
             font_style: FontStyle::Normal,
@@ -501,7 +502,7 @@ impl BufferSearchBar {
             pending_search: None,
             query_contains_error: false,
             dismissed: true,
-            search_history: SearchHistory::default(),
+            search_history: SearchHistory::restore(SearchHistoryKind::Buffer, cx),
             current_mode: SearchMode::default(),
             active_search: None,
             replace_enabled: false,
@@ -672,6 +673,11 @@ impl BufferSearchBar {
             SearchMode::Semantic,
             "Semantic search is not supported in buffer search"
         );
+        assert_ne!(
+            mode,
+            SearchMode::Syntax,
+            "Syntax search is not supported in buffer search"
+        );
         if mode == self.current_mode {
             return;
         }
@@ -874,7 +880,11 @@ impl BufferSearchBar {
                                 .insert(active_searchable_item.downgrade(), matches);
 
                             this.update_match_index(cx);
-                            this.search_history.add(query_text);
+                            this.search_history.add_and_persist(
+                                SearchHistoryKind::Buffer,
+                                query_text,
+                                cx,
+                            );
                             if !this.dismissed {
                                 let matches = this
                                     .searchable_items_with_matches
@@ -939,7 +949,7 @@ impl BufferSearchBar {
         }
     }
     fn cycle_mode(&mut self, _: &CycleMode, cx: &mut ViewContext<Self>) {
-        self.activate_search_mode(next_mode(&self.current_mode, false), cx);
+        self.activate_search_mode(next_mode(&self.current_mode, false, false), cx);
     }
     fn toggle_replace(&mut self, _: &ToggleReplace, cx: &mut ViewContext<Self>) {
         if let Some(_) = &self.active_searchable_item {
@@ -1,6 +1,66 @@
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use gpui::AppContext;
 use smallvec::SmallVec;
+use util::ResultExt;
+
 const SEARCH_HISTORY_LIMIT: usize = 20;
 
+/// The kinds of search that keep their history across sessions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SearchHistoryKind {
+    Buffer,
+    Project,
+    /// The globs of the files to search, entered in the project search's filters.
+    ProjectInclude,
+    /// The globs of the files not to search, entered in the project search's filters.
+    ProjectExclude,
+}
+
+impl SearchHistoryKind {
+    const ALL: [SearchHistoryKind; 4] = [
+        SearchHistoryKind::Buffer,
+        SearchHistoryKind::Project,
+        SearchHistoryKind::ProjectInclude,
+        SearchHistoryKind::ProjectExclude,
+    ];
+
+    fn key(self) -> &'static str {
+        match self {
+            SearchHistoryKind::Buffer => "buffer-search-history",
+            SearchHistoryKind::Project => "project-search-history",
+            SearchHistoryKind::ProjectInclude => "project-search-include-history",
+            SearchHistoryKind::ProjectExclude => "project-search-exclude-history",
+        }
+    }
+}
+
+/// The latest search history of each kind, which new searches start from.
+#[derive(Default)]
+struct PersistedSearchHistories(HashMap<SearchHistoryKind, Vec<String>>);
+
+pub fn init(cx: &mut AppContext) {
+    cx.set_global(PersistedSearchHistories::default());
+    cx.spawn(|mut cx| async move {
+        for kind in SearchHistoryKind::ALL {
+            let history = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(kind.key()) })
+                .await
+                .log_err()
+                .flatten()
+                .and_then(|history| serde_json::from_str::<Vec<String>>(&history).log_err());
+            if let Some(history) = history {
+                cx.update_global(|histories: &mut PersistedSearchHistories, _| {
+                    histories.0.entry(kind).or_insert(history);
+                })
+                .log_err();
+            }
+        }
+    })
+    .detach();
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct SearchHistory {
     history: SmallVec<[String; SEARCH_HISTORY_LIMIT]>,
@@ -8,6 +68,41 @@ pub struct SearchHistory {
 }
 
 impl SearchHistory {
+    /// Creates a history that starts with the latest searches of the given kind, including
+    /// those from earlier sessions.
+    pub fn restore(kind: SearchHistoryKind, cx: &AppContext) -> Self {
+        let history = cx
+            .try_global::<PersistedSearchHistories>()
+            .and_then(|histories| histories.0.get(&kind))
+            .map(|history| history.iter().cloned().collect())
+            .unwrap_or_default();
+        Self {
+            history,
+            selected: None,
+        }
+    }
+
+    /// Adds a search, keeping the resulting history as the latest of its kind so that
+    /// later searches and sessions start from it.
+    pub fn add_and_persist(
+        &mut self,
+        kind: SearchHistoryKind,
+        search_string: String,
+        cx: &mut AppContext,
+    ) {
+        self.add(search_string);
+        let history = self.history.to_vec();
+        let Some(json) = serde_json::to_string(&history).log_err() else {
+            return;
+        };
+        cx.default_global::<PersistedSearchHistories>()
+            .0
+            .insert(kind, history);
+        cx.background_executor()
+            .spawn(KEY_VALUE_STORE.write_kvp(kind.key().to_string(), json))
+            .detach_and_log_err(cx);
+    }
+
     pub fn add(&mut self, search_string: String) {
         if let Some(i) = self.selected {
             if search_string == self.history[i] {
@@ -157,6 +252,26 @@ mod tests {
         assert_eq!(search_history.current(), Some("TypeScript"));
     }
 
+    #[gpui::test]
+    fn test_restore(cx: &mut gpui::AppContext) {
+        init(cx);
+
+        let mut project_history = SearchHistory::restore(SearchHistoryKind::Project, cx);
+        assert_eq!(project_history.current(), None);
+        project_history.add_and_persist(SearchHistoryKind::Project, "Rust".to_string(), cx);
+        project_history.add_and_persist(SearchHistoryKind::Project, "Zig".to_string(), cx);
+
+        let mut restored_history = SearchHistory::restore(SearchHistoryKind::Project, cx);
+        assert_eq!(restored_history.current(), None);
+        assert_eq!(restored_history.previous(), Some("Zig"));
+        assert_eq!(restored_history.previous(), Some("Rust"));
+
+        let mut buffer_history = SearchHistory::restore(SearchHistoryKind::Buffer, cx);
+        assert_eq!(buffer_history.previous(), None);
+        let mut include_history = SearchHistory::restore(SearchHistoryKind::ProjectInclude, cx);
+        assert_eq!(include_history.previous(), None);
+    }
+
     #[test]
     fn test_reset_selection() {
         let mut search_history = SearchHistory::default();
@@ -1,6 +1,6 @@
 use gpui::{Action, SharedString};
 
-use crate::{ActivateRegexMode, ActivateSemanticMode, ActivateTextMode};
+use crate::{ActivateRegexMode, ActivateSemanticMode, ActivateSyntaxMode, ActivateTextMode};
 
 // TODO: Update the default search mode to get from config
 #[derive(Copy, Clone, Debug, Default, PartialEq)]
@@ -9,6 +9,7 @@ pub enum SearchMode {
     Text,
     Semantic,
     Regex,
+    Syntax,
 }
 
 impl SearchMode {
@@ -17,6 +18,7 @@ impl SearchMode {
             SearchMode::Text => "Text",
             SearchMode::Semantic => "Semantic",
             SearchMode::Regex => "Regex",
+            SearchMode::Syntax => "Syntax",
         }
     }
     pub(crate) fn tooltip(&self) -> SharedString {
@@ -27,14 +29,20 @@ impl SearchMode {
             SearchMode::Text => ActivateTextMode.boxed_clone(),
             SearchMode::Semantic => ActivateSemanticMode.boxed_clone(),
             SearchMode::Regex => ActivateRegexMode.boxed_clone(),
+            SearchMode::Syntax => ActivateSyntaxMode.boxed_clone(),
         }
     }
 }
 
-pub(crate) fn next_mode(mode: &SearchMode, semantic_enabled: bool) -> SearchMode {
+pub(crate) fn next_mode(
+    mode: &SearchMode,
+    syntax_enabled: bool,
+    semantic_enabled: bool,
+) -> SearchMode {
     match mode {
         SearchMode::Text => SearchMode::Regex,
-        SearchMode::Regex => {
+        SearchMode::Regex if syntax_enabled => SearchMode::Syntax,
+        SearchMode::Regex | SearchMode::Syntax => {
             if semantic_enabled {
                 SearchMode::Semantic
             } else {
@@ -1,14 +1,23 @@
 use crate::{
-    history::SearchHistory, mode::SearchMode, ActivateRegexMode, ActivateSemanticMode,
-    ActivateTextMode, CycleMode, NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext,
-    SearchOptions, SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
-    ToggleReplace, ToggleWholeWord,
+    history::{SearchHistory, SearchHistoryKind},
+    mode::SearchMode,
+    saved_searches::SavedSearch,
+    ActivateRegexMode, ActivateSemanticMode, ActivateSyntaxMode, ActivateTextMode, CycleMode,
+    NextHistoryQuery, PreviousHistoryQuery, ReplaceAll, ReplaceNext, SearchOptions,
+    SelectNextMatch, SelectPrevMatch, ToggleCaseSensitive, ToggleIncludeIgnored,
+    ToggleModifiedFilesOnly, ToggleOpenBuffersOnly, ToggleReplace, ToggleWholeWord,
 };
-use anyhow::{Context as _, Result};
+use anyhow::{anyhow, Context as _, Result};
+use client::proto::{self, PeerId};
 use collections::HashMap;
 use editor::{
-    actions::SelectAll, items::active_match_index, scroll::Autoscroll, Anchor, Editor, EditorEvent,
-    MultiBuffer, MAX_TAB_TITLE_LEN,
+    actions::SelectAll,
+    display_map::{
+        BlockContext, BlockDisposition, BlockId, BlockProperties, BlockStyle, RenderBlock,
+    },
+    items::active_match_index,
+    scroll::Autoscroll,
+    Anchor, Editor, EditorEvent, ExcerptId, MultiBuffer, MAX_TAB_TITLE_LEN,
 };
 use editor::{EditorElement, EditorStyle};
 use gpui::{
@@ -18,10 +27,11 @@ use gpui::{
     Styled, Subscription, Task, TextStyle, View, ViewContext, VisualContext, WeakModel, WeakView,
     WhiteSpace, WindowContext,
 };
+use language::Buffer;
 use menu::Confirm;
 use project::{
     search::{SearchInputs, SearchQuery},
-    Entry, Project,
+    Entry, Item as _, Project,
 };
 use semantic_index::{SemanticIndex, SemanticIndexStatus};
 
@@ -33,21 +43,23 @@ use std::{
     mem,
     ops::{Not, Range},
     path::PathBuf,
-    time::{Duration, Instant},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
 };
 use theme::ThemeSettings;
 use workspace::{DeploySearch, NewSearch};
 
 use ui::{
-    h_flex, prelude::*, v_flex, Icon, IconButton, IconName, Label, LabelCommon, LabelSize,
-    Selectable, ToggleButton, Tooltip,
+    h_flex, prelude::*, v_flex, Checkbox, Icon, IconButton, IconName, Label, LabelCommon,
+    LabelSize, Selectable, Selection, ToggleButton, Tooltip,
 };
 use util::{paths::PathMatcher, ResultExt as _};
 use workspace::{
-    item::{BreadcrumbText, Item, ItemEvent, ItemHandle},
+    item::{BreadcrumbText, FollowEvent, FollowableItem, Item, ItemEvent, ItemHandle},
+    register_followable_item,
     searchable::{Direction, SearchableItem, SearchableItemHandle},
-    ItemNavHistory, Pane, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, Workspace,
-    WorkspaceId,
+    ItemNavHistory, Pane, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView, ViewId,
+    Workspace, WorkspaceId,
 };
 
 actions!(
@@ -60,6 +72,7 @@ struct ActiveSettings(HashMap<WeakModel<Project>, ProjectSearchSettings>);
 
 pub fn init(cx: &mut AppContext) {
     cx.set_global(ActiveSettings::default());
+    register_followable_item::<ProjectSearchView>(cx);
     cx.observe_new_views(|workspace: &mut Workspace, _cx| {
         register_workspace_action(workspace, move |search_bar, _: &ToggleFilters, cx| {
             search_bar.toggle_filters(cx);
@@ -79,6 +92,9 @@ pub fn init(cx: &mut AppContext) {
         register_workspace_action(workspace, move |search_bar, _: &ActivateTextMode, cx| {
             search_bar.activate_search_mode(SearchMode::Text, cx)
         });
+        register_workspace_action(workspace, move |search_bar, _: &ActivateSyntaxMode, cx| {
+            search_bar.activate_search_mode(SearchMode::Syntax, cx)
+        });
         register_workspace_action(
             workspace,
             move |search_bar, _: &ActivateSemanticMode, cx| {
@@ -135,7 +151,13 @@ struct ProjectSearch {
     active_query: Option<SearchQuery>,
     search_id: usize,
     search_history: SearchHistory,
+    include_history: SearchHistory,
+    exclude_history: SearchHistory,
     no_results: Option<bool>,
+    /// The modification times of the files of the buffers with results, as of the search.
+    buffer_mtimes: HashMap<WeakModel<Buffer>, Option<SystemTime>>,
+    /// Keeps [`Self::buffer_mtimes`] current when the buffers are saved from within the editor.
+    buffer_save_subscriptions: Vec<Subscription>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -162,7 +184,12 @@ pub struct ProjectSearchView {
     excluded_files_editor: View<Editor>,
     filters_enabled: bool,
     replace_enabled: bool,
+    /// The ranges of the matches that replacing skips, unchecked in the replacement previews.
+    excluded_matches: HashSet<Range<Anchor>>,
+    replacement_preview_blocks: collections::HashSet<BlockId>,
     current_mode: SearchMode,
+    remote_id: Option<ViewId>,
+    leader_peer_id: Option<PeerId>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -196,39 +223,81 @@ impl ProjectSearch {
             match_ranges: Default::default(),
             active_query: None,
             search_id: 0,
-            search_history: SearchHistory::default(),
+            search_history: SearchHistory::restore(SearchHistoryKind::Project, cx),
+            include_history: SearchHistory::restore(SearchHistoryKind::ProjectInclude, cx),
+            exclude_history: SearchHistory::restore(SearchHistoryKind::ProjectExclude, cx),
             no_results: None,
+            buffer_mtimes: Default::default(),
+            buffer_save_subscriptions: Vec::new(),
         }
     }
 
     fn clone(&self, cx: &mut ModelContext<Self>) -> Model<Self> {
-        cx.new_model(|cx| Self {
-            project: self.project.clone(),
-            excerpts: self
-                .excerpts
-                .update(cx, |excerpts, cx| cx.new_model(|cx| excerpts.clone(cx))),
-            pending_search: Default::default(),
-            match_ranges: self.match_ranges.clone(),
-            active_query: self.active_query.clone(),
-            search_id: self.search_id,
-            search_history: self.search_history.clone(),
-            no_results: self.no_results.clone(),
+        cx.new_model(|cx| {
+            let mut this = Self {
+                project: self.project.clone(),
+                excerpts: self
+                    .excerpts
+                    .update(cx, |excerpts, cx| cx.new_model(|cx| excerpts.clone(cx))),
+                pending_search: Default::default(),
+                match_ranges: self.match_ranges.clone(),
+                active_query: self.active_query.clone(),
+                search_id: self.search_id,
+                search_history: self.search_history.clone(),
+                include_history: self.include_history.clone(),
+                exclude_history: self.exclude_history.clone(),
+                no_results: self.no_results.clone(),
+                buffer_mtimes: self.buffer_mtimes.clone(),
+                buffer_save_subscriptions: Vec::new(),
+            };
+            for buffer in self
+                .buffer_mtimes
+                .keys()
+                .filter_map(|buffer| buffer.upgrade())
+            {
+                this.observe_buffer_saves(&buffer, cx);
+            }
+            this
         })
     }
 
+    /// Saving a buffer changes its file's modification time without changing its contents,
+    /// so record the new time rather than treating the buffer as changed since the search.
+    fn observe_buffer_saves(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        self.buffer_save_subscriptions
+            .push(cx.subscribe(buffer, |this, buffer, event, cx| {
+                if let language::Event::Saved = event {
+                    if let Some(mtime) = this.buffer_mtimes.get_mut(&buffer.downgrade()) {
+                        *mtime = Some(buffer.read(cx).saved_mtime());
+                    }
+                }
+            }));
+    }
+
     fn search(&mut self, query: SearchQuery, cx: &mut ModelContext<Self>) {
+        // Drop the previous results first, so that the buffers they keep open don't count as
+        // open buffers.
+        self.excerpts.update(cx, |excerpts, cx| excerpts.clear(cx));
         let search = self
             .project
             .update(cx, |project, cx| project.search(query.clone(), cx));
         self.search_id += 1;
-        self.search_history.add(query.as_str().to_string());
+        self.search_history.add_and_persist(
+            SearchHistoryKind::Project,
+            query.as_str().to_string(),
+            cx,
+        );
         self.active_query = Some(query);
         self.match_ranges.clear();
+        self.buffer_mtimes.clear();
+        self.buffer_save_subscriptions.clear();
         self.pending_search = Some(cx.spawn(|this, mut cx| async move {
             let mut matches = search;
             let this = this.upgrade()?;
             this.update(&mut cx, |this, cx| {
                 this.match_ranges.clear();
+                this.buffer_mtimes.clear();
+                this.buffer_save_subscriptions.clear();
                 this.excerpts.update(cx, |this, cx| this.clear(cx));
                 this.no_results = Some(true);
             })
@@ -238,6 +307,9 @@ impl ProjectSearch {
                 let mut ranges = this
                     .update(&mut cx, |this, cx| {
                         this.no_results = Some(false);
+                        let mtime = buffer.read(cx).file().map(|file| file.mtime());
+                        this.buffer_mtimes.insert(buffer.downgrade(), mtime);
+                        this.observe_buffer_saves(&buffer, cx);
                         this.excerpts.update(cx, |excerpts, cx| {
                             excerpts.stream_excerpts_with_context_lines(buffer, anchors, 1, cx)
                         })
@@ -262,6 +334,16 @@ impl ProjectSearch {
         cx.notify();
     }
 
+    /// The history of the filter input for the given panel.
+    fn filter_history_mut(&mut self, panel: InputPanel) -> Option<&mut SearchHistory> {
+        match panel {
+            InputPanel::Include => Some(&mut self.include_history),
+            InputPanel::Exclude => Some(&mut self.exclude_history),
+            InputPanel::Query => None,
+        }
+    }
+
+    /// Whether git reports the buffer's file as added, modified or conflicted.
     fn semantic_search(&mut self, inputs: &SearchInputs, cx: &mut ModelContext<Self>) {
         let search = SemanticIndex::global(cx).map(|index| {
             index.update(cx, |semantic_index, cx| {
@@ -340,10 +422,14 @@ impl Render for ProjectSearchView {
         const PLEASE_AUTHENTICATE: &str = "API Key Missing: Please set 'OPENAI_API_KEY' in Environment Variables. If you authenticated using the Assistant Panel, please restart Zed to Authenticate.";
 
         if self.has_matches() {
-            div()
+            let changed_buffer_count = self.buffers_changed_since_search(cx).len();
+            v_flex()
                 .flex_1()
                 .size_full()
                 .track_focus(&self.focus_handle)
+                .when(changed_buffer_count > 0, |this| {
+                    this.child(self.render_changed_files_banner(changed_buffer_count, cx))
+                })
                 .child(self.results_editor.clone())
         } else {
             let model = self.model.read(cx);
@@ -467,6 +553,11 @@ impl Item for ProjectSearchView {
         }
     }
 
+    // Buffer search within the results searches and replaces only the text of the results.
+    fn as_searchable(&self, _: &View<Self>) -> Option<Box<dyn SearchableItemHandle>> {
+        Some(Box::new(self.results_editor.clone()))
+    }
+
     fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
         self.results_editor
             .update(cx, |editor, cx| editor.deactivated(cx));
@@ -626,6 +717,186 @@ impl Item for ProjectSearchView {
     }
 }
 
+impl FollowableItem for ProjectSearchView {
+    fn remote_id(&self) -> Option<ViewId> {
+        self.remote_id
+    }
+
+    fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant> {
+        let Some(proto::view::Variant::Editor(editor)) =
+            self.results_editor.read(cx).to_state_proto(cx)
+        else {
+            return None;
+        };
+        Some(proto::view::Variant::ProjectSearch(
+            proto::view::ProjectSearch {
+                query: Some(self.saved_search(String::new(), cx).to_proto()),
+                editor: Some(editor),
+            },
+        ))
+    }
+
+    fn from_state_proto(
+        _pane: View<Pane>,
+        workspace: View<Workspace>,
+        remote_id: ViewId,
+        state: &mut Option<proto::view::Variant>,
+        cx: &mut WindowContext,
+    ) -> Option<Task<Result<View<Self>>>> {
+        let Some(proto::view::Variant::ProjectSearch(_)) = state else {
+            return None;
+        };
+        let Some(proto::view::Variant::ProjectSearch(state)) = state.take() else {
+            unreachable!()
+        };
+
+        // Insert the leader's results after one another, keeping the leader's excerpt ids so
+        // that its later updates apply to them.
+        let editor = state.editor.map(|editor| {
+            let mut previous_excerpt_id = ExcerptId::min().to_proto();
+            proto::update_view::Editor {
+                inserted_excerpts: editor
+                    .excerpts
+                    .into_iter()
+                    .map(|excerpt| proto::ExcerptInsertion {
+                        previous_excerpt_id: Some(mem::replace(
+                            &mut previous_excerpt_id,
+                            excerpt.id,
+                        )),
+                        excerpt: Some(excerpt),
+                    })
+                    .collect(),
+                selections: editor.selections,
+                pending_selection: editor.pending_selection,
+                scroll_top_anchor: editor.scroll_top_anchor,
+                scroll_x: editor.scroll_x,
+                scroll_y: editor.scroll_y,
+                ..Default::default()
+            }
+        });
+
+        let project = workspace.read(cx).project().clone();
+        let model = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let search = cx.new_view(|cx| {
+            let mut search = ProjectSearchView::new(model, cx, None);
+            search.remote_id = Some(remote_id);
+            search
+        });
+        let update = search.update(cx, |search, cx| {
+            search.apply_update_proto(
+                &project,
+                proto::update_view::Variant::ProjectSearch(proto::update_view::ProjectSearch {
+                    query: state.query,
+                    editor,
+                }),
+                cx,
+            )
+        });
+
+        Some(cx.spawn(|_| async move {
+            update.await?;
+            Ok(search)
+        }))
+    }
+
+    fn to_follow_event(event: &Self::Event) -> Option<FollowEvent> {
+        match event {
+            ViewEvent::EditorEvent(event) => Editor::to_follow_event(event),
+            _ => None,
+        }
+    }
+
+    fn add_event_to_update_proto(
+        &self,
+        event: &Self::Event,
+        update: &mut Option<proto::update_view::Variant>,
+        cx: &WindowContext,
+    ) -> bool {
+        let ViewEvent::EditorEvent(event) = event else {
+            return false;
+        };
+        let update = update
+            .get_or_insert_with(|| proto::update_view::Variant::ProjectSearch(Default::default()));
+        let proto::update_view::Variant::ProjectSearch(update) = update else {
+            return false;
+        };
+
+        let mut editor_update = update
+            .editor
+            .take()
+            .map(proto::update_view::Variant::Editor);
+        let results_changed =
+            self.results_editor
+                .read(cx)
+                .add_event_to_update_proto(event, &mut editor_update, cx);
+        if let Some(proto::update_view::Variant::Editor(editor_update)) = editor_update {
+            update.editor = Some(editor_update);
+        }
+        update.query = Some(self.saved_search(String::new(), cx).to_proto());
+        results_changed || matches!(event, EditorEvent::Edited)
+    }
+
+    fn apply_update_proto(
+        &mut self,
+        project: &Model<Project>,
+        message: proto::update_view::Variant,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let proto::update_view::Variant::ProjectSearch(message) = message else {
+            return Task::ready(Err(anyhow!(
+                "project searches can only apply project search updates"
+            )));
+        };
+
+        let query_changed = message.query.map_or(false, |query| {
+            self.set_saved_search(&SavedSearch::from_proto(query), cx)
+        });
+        let results_changed = message.editor.as_ref().map_or(false, |editor| {
+            !editor.inserted_excerpts.is_empty() || !editor.deleted_excerpts.is_empty()
+        });
+        let update_results = message.editor.map(|editor| {
+            self.results_editor.update(cx, |results_editor, cx| {
+                results_editor.apply_update_proto(
+                    project,
+                    proto::update_view::Variant::Editor(editor),
+                    cx,
+                )
+            })
+        });
+
+        cx.spawn(|this, mut cx| async move {
+            if let Some(update_results) = update_results {
+                update_results.await?;
+            }
+            if query_changed || results_changed {
+                this.update(&mut cx, |this, cx| this.refresh_followed_matches(cx))?
+                    .await;
+            }
+            Ok(())
+        })
+    }
+
+    fn is_project_item(&self, _cx: &WindowContext) -> bool {
+        true
+    }
+
+    fn set_leader_peer_id(&mut self, leader_peer_id: Option<PeerId>, cx: &mut ViewContext<Self>) {
+        self.leader_peer_id = leader_peer_id;
+        for editor in [
+            &self.query_editor,
+            &self.included_files_editor,
+            &self.excluded_files_editor,
+        ] {
+            editor.update(cx, |editor, _| {
+                editor.set_read_only(leader_peer_id.is_some())
+            });
+        }
+        self.results_editor.update(cx, |editor, cx| {
+            editor.set_leader_peer_id(leader_peer_id, cx)
+        });
+    }
+}
+
 impl ProjectSearchView {
     fn toggle_filters(&mut self, cx: &mut ViewContext<Self>) {
         self.filters_enabled = !self.filters_enabled;
@@ -778,7 +1049,7 @@ impl ProjectSearchView {
                     anyhow::Ok(())
                 }).detach_and_log_err(cx);
             }
-            SearchMode::Regex | SearchMode::Text => {
+            SearchMode::Regex | SearchMode::Text | SearchMode::Syntax => {
                 self.semantic_state = None;
                 self.active_match_index = None;
                 self.search(cx);
@@ -801,6 +1072,22 @@ impl ProjectSearchView {
                 return;
             }
             if let Some(active_index) = self.active_match_index {
+                let changed_buffer_ids = self
+                    .buffers_changed_since_search(cx)
+                    .into_iter()
+                    .map(|buffer| buffer.read(cx).remote_id())
+                    .collect::<HashSet<_>>();
+                let is_in_changed_buffer = model.match_ranges[active_index]
+                    .start
+                    .buffer_id
+                    .map_or(false, |buffer_id| changed_buffer_ids.contains(&buffer_id));
+                let is_excluded = self
+                    .excluded_matches
+                    .contains(&model.match_ranges[active_index]);
+                if is_excluded || is_in_changed_buffer {
+                    self.select_match(Direction::Next, cx);
+                    return;
+                }
                 let query = query.clone().with_replacement(self.replacement(cx));
                 self.results_editor.replace(
                     &(Box::new(model.match_ranges[active_index].clone()) as _),
@@ -822,9 +1109,21 @@ impl ProjectSearchView {
             }
             if self.active_match_index.is_some() {
                 let query = query.clone().with_replacement(self.replacement(cx));
+                let changed_buffer_ids = self
+                    .buffers_changed_since_search(cx)
+                    .into_iter()
+                    .map(|buffer| buffer.read(cx).remote_id())
+                    .collect::<HashSet<_>>();
                 let matches = model
                     .match_ranges
                     .iter()
+                    .filter(|range| !self.excluded_matches.contains(range))
+                    .filter(|range| {
+                        range
+                            .start
+                            .buffer_id
+                            .map_or(true, |buffer_id| !changed_buffer_ids.contains(&buffer_id))
+                    })
                     .map(|item| Box::new(item.clone()) as _)
                     .collect::<Vec<_>>();
                 for item in matches {
@@ -834,6 +1133,111 @@ impl ProjectSearchView {
         }
     }
 
+    /// Returns the buffers with results whose files were changed on disk by something other
+    /// than the editor since the search ran, or that conflict with their files. Their matches may no longer be where the search
+    /// found them, so replacing skips them until the search is run again.
+    fn buffers_changed_since_search(&self, cx: &AppContext) -> Vec<Model<Buffer>> {
+        self.model
+            .read(cx)
+            .buffer_mtimes
+            .iter()
+            .filter_map(|(buffer, mtime)| {
+                let buffer = buffer.upgrade()?;
+                let current = buffer.read(cx);
+                let changed =
+                    current.has_conflict() || current.file().map(|file| file.mtime()) != *mtime;
+                changed.then_some(buffer)
+            })
+            .collect()
+    }
+
+    fn render_changed_files_banner(
+        &self,
+        changed_buffer_count: usize,
+        cx: &mut ViewContext<Self>,
+    ) -> impl IntoElement {
+        let message = if changed_buffer_count == 1 {
+            "1 file changed since the search ran. Replacing skips its matches.".to_string()
+        } else {
+            format!(
+                "{changed_buffer_count} files changed since the search ran. Replacing skips their matches."
+            )
+        };
+        h_flex()
+            .w_full()
+            .px_2()
+            .py_1()
+            .gap_2()
+            .bg(cx.theme().status().warning_background)
+            .child(Icon::new(IconName::ExclamationTriangle).color(Color::Warning))
+            .child(div().flex_1().child(Label::new(message)))
+            .child(
+                Button::new("search-again", "Search Again")
+                    .on_click(cx.listener(|this, _, cx| this.search(cx))),
+            )
+    }
+
+    fn toggle_match_exclusion(&mut self, match_range: Range<Anchor>, cx: &mut ViewContext<Self>) {
+        if !self.excluded_matches.remove(&match_range) {
+            self.excluded_matches.insert(match_range);
+        }
+        self.update_replacement_previews(cx);
+    }
+
+    /// Shows what each match would be replaced with below its line while replacing is
+    /// enabled, along with a checkbox to leave the match out of the replacement.
+    fn update_replacement_previews(&mut self, cx: &mut ViewContext<Self>) {
+        let model = self.model.read(cx);
+        let previews = match model.active_query.as_ref() {
+            Some(query) if self.replace_enabled => {
+                let query = query.clone().with_replacement(self.replacement(cx));
+                let snapshot = model.excerpts.read(cx).snapshot(cx);
+                model
+                    .match_ranges
+                    .iter()
+                    .enumerate()
+                    .map(|(match_ix, range)| {
+                        let text = snapshot.text_for_range(range.clone()).collect::<String>();
+                        let replacement = query
+                            .replacement_for(&text)
+                            .map(|replacement| replacement.into_owned())
+                            .unwrap_or_default();
+                        (match_ix, range.clone(), text, replacement)
+                    })
+                    .collect::<Vec<_>>()
+            }
+            _ => Vec::new(),
+        };
+
+        if previews.is_empty() && self.replacement_preview_blocks.is_empty() {
+            return;
+        }
+
+        let view = cx.view().downgrade();
+        let blocks = previews
+            .into_iter()
+            .map(|(match_ix, range, text, replacement)| BlockProperties {
+                position: range.start,
+                height: 1,
+                style: BlockStyle::Sticky,
+                render: replacement_preview_renderer(
+                    match_ix,
+                    text,
+                    replacement,
+                    !self.excluded_matches.contains(&range),
+                    range,
+                    view.clone(),
+                ),
+                disposition: BlockDisposition::Below,
+            })
+            .collect::<Vec<_>>();
+        let old_blocks = mem::take(&mut self.replacement_preview_blocks);
+        self.replacement_preview_blocks = self.results_editor.update(cx, |editor, cx| {
+            editor.remove_blocks(old_blocks, None, cx);
+            editor.insert_blocks(blocks, None, cx).into_iter().collect()
+        });
+    }
+
     fn new(
         model: Model<ProjectSearch>,
         cx: &mut ViewContext<Self>,
@@ -876,8 +1280,8 @@ impl ProjectSearchView {
         });
         // Subscribe to query_editor in order to reraise editor events for workspace item activation purposes
         subscriptions.push(
-            cx.subscribe(&query_editor, |_, _, event: &EditorEvent, cx| {
-                cx.emit(ViewEvent::EditorEvent(event.clone()))
+            cx.subscribe(&query_editor, |this, _, event: &EditorEvent, cx| {
+                this.reraise_input_event(event, cx)
             }),
         );
         let replacement_editor = cx.new_view(|cx| {
@@ -888,6 +1292,14 @@ impl ProjectSearchView {
             }
             editor
         });
+        subscriptions.push(cx.subscribe(
+            &replacement_editor,
+            |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::Edited = event {
+                    this.update_replacement_previews(cx);
+                }
+            },
+        ));
         let results_editor = cx.new_view(|cx| {
             let mut editor = Editor::for_multibuffer(excerpts, Some(project.clone()), cx);
             editor.set_searchable(false);
@@ -897,8 +1309,13 @@ impl ProjectSearchView {
 
         subscriptions.push(
             cx.subscribe(&results_editor, |this, _, event: &EditorEvent, cx| {
-                if matches!(event, editor::EditorEvent::SelectionsChanged { .. }) {
-                    this.update_match_index(cx);
+                match event {
+                    EditorEvent::SelectionsChanged { .. } => this.update_match_index(cx),
+                    // Files being reloaded or saved may change which results are stale.
+                    EditorEvent::TitleChanged | EditorEvent::DirtyChanged | EditorEvent::Saved => {
+                        cx.notify()
+                    }
+                    _ => {}
                 }
                 // Reraise editor events for workspace item activation purposes
                 cx.emit(ViewEvent::EditorEvent(event.clone()));
@@ -912,11 +1329,10 @@ impl ProjectSearchView {
             editor
         });
         // Subscribe to include_files_editor in order to reraise editor events for workspace item activation purposes
-        subscriptions.push(
-            cx.subscribe(&included_files_editor, |_, _, event: &EditorEvent, cx| {
-                cx.emit(ViewEvent::EditorEvent(event.clone()))
-            }),
-        );
+        subscriptions.push(cx.subscribe(
+            &included_files_editor,
+            |this, _, event: &EditorEvent, cx| this.reraise_input_event(event, cx),
+        ));
 
         let excluded_files_editor = cx.new_view(|cx| {
             let mut editor = Editor::single_line(cx);
@@ -925,11 +1341,10 @@ impl ProjectSearchView {
             editor
         });
         // Subscribe to excluded_files_editor in order to reraise editor events for workspace item activation purposes
-        subscriptions.push(
-            cx.subscribe(&excluded_files_editor, |_, _, event: &EditorEvent, cx| {
-                cx.emit(ViewEvent::EditorEvent(event.clone()))
-            }),
-        );
+        subscriptions.push(cx.subscribe(
+            &excluded_files_editor,
+            |this, _, event: &EditorEvent, cx| this.reraise_input_event(event, cx),
+        ));
 
         let focus_handle = cx.focus_handle();
         subscriptions.push(cx.on_focus_in(&focus_handle, |this, cx| {
@@ -961,12 +1376,24 @@ impl ProjectSearchView {
             filters_enabled,
             current_mode,
             replace_enabled: false,
+            excluded_matches: HashSet::new(),
+            replacement_preview_blocks: Default::default(),
+            remote_id: None,
+            leader_peer_id: None,
             _subscriptions: subscriptions,
         };
         this.model_changed(cx);
         this
     }
 
+    /// Reraises the events of the query and filter inputs, unless they come from following
+    /// a leader's search, as those events would otherwise stop the following.
+    fn reraise_input_event(&mut self, event: &EditorEvent, cx: &mut ViewContext<Self>) {
+        if self.leader_peer_id.is_none() {
+            cx.emit(ViewEvent::EditorEvent(event.clone()))
+        }
+    }
+
     fn semantic_permissioned(&mut self, cx: &mut ViewContext<Self>) -> Task<Result<bool>> {
         if let Some(value) = self.semantic_permissioned {
             return Task::ready(Ok(value));
@@ -1004,6 +1431,94 @@ impl ProjectSearchView {
         });
     }
 
+    /// Describes the search in this view under the given name, so that it can be run again.
+    pub(crate) fn saved_search(&self, name: String, cx: &AppContext) -> SavedSearch {
+        SavedSearch {
+            name,
+            query: self.query_editor.read(cx).text(cx),
+            regex: self.current_mode == SearchMode::Regex,
+            syntax: self.current_mode == SearchMode::Syntax,
+            case_sensitive: self.search_options.contains(SearchOptions::CASE_SENSITIVE),
+            whole_word: self.search_options.contains(SearchOptions::WHOLE_WORD),
+            include_ignored: self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+            include: self.included_files_editor.read(cx).text(cx),
+            exclude: self.excluded_files_editor.read(cx).text(cx),
+        }
+    }
+
+    /// Opens a new search with the query, options and filters of a saved search, and runs it.
+    pub(crate) fn run_saved_search(
+        workspace: &mut Workspace,
+        saved_search: &SavedSearch,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let model = cx.new_model(|cx| ProjectSearch::new(workspace.project().clone(), cx));
+        let search = cx.new_view(|cx| ProjectSearchView::new(model, cx, None));
+        workspace.add_item(Box::new(search.clone()), cx);
+        search.update(cx, |search, cx| {
+            search.set_saved_search(saved_search, cx);
+            search.search(cx);
+        });
+    }
+
+    /// Fills in the query, options and filters of a saved search without running it, and
+    /// returns whether any of them changed.
+    fn set_saved_search(&mut self, saved_search: &SavedSearch, cx: &mut ViewContext<Self>) -> bool {
+        if self.saved_search(saved_search.name.clone(), cx) == *saved_search {
+            return false;
+        }
+
+        self.search_options
+            .set(SearchOptions::CASE_SENSITIVE, saved_search.case_sensitive);
+        self.search_options
+            .set(SearchOptions::WHOLE_WORD, saved_search.whole_word);
+        self.search_options
+            .set(SearchOptions::INCLUDE_IGNORED, saved_search.include_ignored);
+        self.current_mode = if saved_search.syntax {
+            SearchMode::Syntax
+        } else if saved_search.regex {
+            SearchMode::Regex
+        } else {
+            SearchMode::Text
+        };
+        self.filters_enabled |=
+            !saved_search.include.is_empty() || !saved_search.exclude.is_empty();
+        for (editor, text) in [
+            (&self.query_editor, &saved_search.query),
+            (&self.included_files_editor, &saved_search.include),
+            (&self.excluded_files_editor, &saved_search.exclude),
+        ] {
+            if editor.read(cx).text(cx) != *text {
+                editor.update(cx, |editor, cx| editor.set_text(text.as_str(), cx));
+            }
+        }
+        cx.notify();
+        true
+    }
+
+    /// Highlights the matches of the query in the results, which a follower receives from
+    /// its leader rather than searching for them.
+    fn refresh_followed_matches(&mut self, cx: &mut ViewContext<Self>) -> Task<()> {
+        let Some(query) = self.build_search_query(cx) else {
+            return Task::ready(());
+        };
+        let find_matches = self.results_editor.update(cx, |editor, cx| {
+            editor.find_matches(Arc::new(query.clone()), cx)
+        });
+        let model = self.model.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            let match_ranges = find_matches.await;
+            model
+                .update(&mut cx, |model, cx| {
+                    model.no_results = Some(match_ranges.is_empty());
+                    model.match_ranges = match_ranges;
+                    model.active_query = Some(query);
+                    cx.notify();
+                })
+                .ok();
+        })
+    }
+
     // Re-activate the most recently activated search in this pane or the most recent if it has been closed.
     // If no search exists in the workspace, create a new one.
     fn deploy_search(
@@ -1119,7 +1634,25 @@ impl ProjectSearchView {
 
             _ => {
                 if let Some(query) = self.build_search_query(cx) {
-                    self.model.update(cx, |model, cx| model.search(query, cx));
+                    let include = self.included_files_editor.read(cx).text(cx);
+                    let exclude = self.excluded_files_editor.read(cx).text(cx);
+                    self.model.update(cx, |model, cx| {
+                        if !include.is_empty() {
+                            model.include_history.add_and_persist(
+                                SearchHistoryKind::ProjectInclude,
+                                include,
+                                cx,
+                            );
+                        }
+                        if !exclude.is_empty() {
+                            model.exclude_history.add_and_persist(
+                                SearchHistoryKind::ProjectExclude,
+                                exclude,
+                                cx,
+                            );
+                        }
+                        model.search(query, cx)
+                    });
                 }
             }
         }
@@ -1194,6 +1727,29 @@ impl ProjectSearchView {
                     }
                 }
             }
+            SearchMode::Syntax => match SearchQuery::syntax(
+                text,
+                self.search_options.contains(SearchOptions::INCLUDE_IGNORED),
+                included_files,
+                excluded_files,
+            ) {
+                Ok(query) => {
+                    let should_unmark_error = self.panels_with_errors.remove(&InputPanel::Query);
+                    if should_unmark_error {
+                        cx.notify();
+                    }
+
+                    Some(query)
+                }
+                Err(_e) => {
+                    let should_mark_error = self.panels_with_errors.insert(InputPanel::Query);
+                    if should_mark_error {
+                        cx.notify();
+                    }
+
+                    None
+                }
+            },
             _ => match SearchQuery::text(
                 text,
                 self.search_options.contains(SearchOptions::WHOLE_WORD),
@@ -1223,7 +1779,14 @@ impl ProjectSearchView {
         if !self.panels_with_errors.is_empty() {
             return None;
         }
-        query
+        query.map(|query| {
+            query.with_scope(
+                self.search_options
+                    .contains(SearchOptions::OPEN_BUFFERS_ONLY),
+                self.search_options
+                    .contains(SearchOptions::MODIFIED_FILES_ONLY),
+            )
+        })
     }
 
     fn parse_path_matches(text: &str) -> anyhow::Result<Vec<PathMatcher>> {
@@ -1269,6 +1832,15 @@ impl ProjectSearchView {
             .update(cx, |query_editor, cx| query_editor.set_text(query, cx));
     }
 
+    fn set_filter(&mut self, panel: InputPanel, filter: &str, cx: &mut ViewContext<Self>) {
+        let editor = match panel {
+            InputPanel::Include => &self.included_files_editor,
+            InputPanel::Exclude => &self.excluded_files_editor,
+            InputPanel::Query => &self.query_editor,
+        };
+        editor.update(cx, |editor, cx| editor.set_text(filter, cx));
+    }
+
     fn focus_results_editor(&mut self, cx: &mut ViewContext<Self>) {
         self.query_editor.update(cx, |query_editor, cx| {
             let cursor = query_editor.selections.newest_anchor().head();
@@ -1288,6 +1860,9 @@ impl ProjectSearchView {
             self.update_match_index(cx);
             let prev_search_id = mem::replace(&mut self.search_id, self.model.read(cx).search_id);
             let is_new_search = self.search_id != prev_search_id;
+            if is_new_search {
+                self.excluded_matches.clear();
+            }
             self.results_editor.update(cx, |editor, cx| {
                 if is_new_search {
                     let range_to_select = match_ranges
@@ -1308,6 +1883,7 @@ impl ProjectSearchView {
                 self.focus_results_editor(cx);
             }
         }
+        self.update_replacement_previews(cx);
 
         cx.emit(ViewEvent::UpdateTab);
         cx.notify();
@@ -1330,9 +1906,15 @@ impl ProjectSearchView {
         self.active_match_index.is_some()
     }
 
+    /// The editor that shows the search's results.
+    pub fn results_editor(&self) -> &View<Editor> {
+        &self.results_editor
+    }
+
     fn landing_text_minor(&self) -> SharedString {
         match self.current_mode {
             SearchMode::Text | SearchMode::Regex => "Include/exclude specific paths with the filter option. Matching exact word and/or casing is available too.".into(),
+            SearchMode::Syntax => "\nMatch code by its syntax tree with a tree-sitter query. ex. '(call_expression function: (identifier) @match)'".into(),
             SearchMode::Semantic => "\nSimply explain the code you are looking to find. ex. 'prompt user for permissions to index their project'".into()
         }
     }
@@ -1353,6 +1935,54 @@ impl ProjectSearchView {
     }
 }
 
+fn replacement_preview_renderer(
+    match_ix: usize,
+    text: String,
+    replacement: String,
+    included: bool,
+    match_range: Range<Anchor>,
+    view: WeakView<ProjectSearchView>,
+) -> RenderBlock {
+    // Matches can span lines, but previews are kept to the line of the block.
+    let text = SharedString::from(text.replace('\n', "⏎"));
+    let replacement = SharedString::from(replacement.replace('\n', "⏎"));
+    Arc::new(move |cx: &mut BlockContext| {
+        h_flex()
+            .id(("replacement-preview", match_ix))
+            .w(cx.max_width + cx.gutter_width)
+            .pl(cx.anchor_x)
+            .gap_1()
+            .child(
+                Checkbox::new(
+                    ("replace-match", match_ix),
+                    if included {
+                        Selection::Selected
+                    } else {
+                        Selection::Unselected
+                    },
+                )
+                .on_click({
+                    let view = view.clone();
+                    let match_range = match_range.clone();
+                    move |_, cx| {
+                        view.update(cx, |this, cx| {
+                            this.toggle_match_exclusion(match_range.clone(), cx)
+                        })
+                        .ok();
+                    }
+                }),
+            )
+            .child(Label::new(text.clone()).color(Color::Muted))
+            .child(Label::new("→").color(Color::Muted))
+            .child(Label::new(replacement.clone()).color(if included {
+                Color::Created
+            } else {
+                Color::Muted
+            }))
+            .into_any_element()
+    })
+}
+
 impl ProjectSearchBar {
     pub fn new() -> Self {
         Self {
@@ -1365,7 +1995,7 @@ impl ProjectSearchBar {
         if let Some(view) = self.active_project_search.as_ref() {
             view.update(cx, |this, cx| {
                 let new_mode =
-                    crate::mode::next_mode(&this.current_mode, SemanticIndex::enabled(cx));
+                    crate::mode::next_mode(&this.current_mode, true, SemanticIndex::enabled(cx));
                 this.activate_search_mode(new_mode, cx);
                 let editor_handle = this.query_editor.focus_handle(cx);
                 cx.focus(&editor_handle);
@@ -1457,6 +2087,7 @@ impl ProjectSearchBar {
         if let Some(search) = &self.active_project_search {
             search.update(cx, |this, cx| {
                 this.replace_enabled = !this.replace_enabled;
+                this.update_replacement_previews(cx);
                 let editor_to_focus = if !this.replace_enabled {
                     this.query_editor.focus_handle(cx)
                 } else {
@@ -1556,6 +2187,39 @@ impl ProjectSearchBar {
         }
     }
 
+    fn previous_filter_history(&mut self, panel: InputPanel, cx: &mut ViewContext<Self>) {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| {
+                let previous = search_view.model.update(cx, |model, _| {
+                    model
+                        .filter_history_mut(panel)?
+                        .previous()
+                        .map(str::to_string)
+                });
+                if let Some(previous) = previous {
+                    search_view.set_filter(panel, &previous, cx);
+                }
+            });
+        }
+    }
+
+    fn next_filter_history(&mut self, panel: InputPanel, cx: &mut ViewContext<Self>) {
+        if let Some(search_view) = self.active_project_search.as_ref() {
+            search_view.update(cx, |search_view, cx| {
+                let next = search_view.model.update(cx, |model, _| {
+                    let history = model.filter_history_mut(panel)?;
+                    Some(history.next().map(str::to_string).unwrap_or_else(|| {
+                        history.reset_selection();
+                        String::new()
+                    }))
+                });
+                if let Some(next) = next {
+                    search_view.set_filter(panel, &next, cx);
+                }
+            });
+        }
+    }
+
     fn select_next_match(&mut self, _: &SelectNextMatch, cx: &mut ViewContext<Self>) {
         if let Some(search) = self.active_project_search.as_ref() {
             search.update(cx, |this, cx| {
@@ -1615,7 +2279,7 @@ impl ProjectSearchBar {
     }
 
     fn render_text_input(&self, editor: &View<Editor>, cx: &ViewContext<Self>) -> impl IntoElement {
-        let settings = ThemeSettings::get_global(cx);
+        let settings = ThemeSettings::for_window(cx);
         let text_style = TextStyle {
             color: if editor.read(cx).read_only(cx) {
                 cx.theme().colors().text_disabled
@@ -1623,7 +2287,8 @@ impl ProjectSearchBar {
                 cx.theme().colors().text
             },
             font_family: settings.ui_font.family.clone(),
-            font_features: settings.ui_font.features,
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
             font_size: rems(0.875).into(),
             font_weight: FontWeight::NORMAL,
             font_style: FontStyle::Normal,
@@ -1661,6 +2326,10 @@ impl Render for ProjectSearchBar {
         }
         let search = search.read(cx);
         let semantic_is_available = SemanticIndex::enabled(cx);
+        let text_options_available = !matches!(
+            search.current_mode,
+            SearchMode::Semantic | SearchMode::Syntax
+        );
 
         let query_column = v_flex().child(
             h_flex()
@@ -1694,7 +2363,7 @@ impl Render for ProjectSearchBar {
                                         .unwrap_or_default(),
                                 ),
                         )
-                        .when(search.current_mode != SearchMode::Semantic, |this| {
+                        .when(text_options_available, |this| {
                             this.child(
                                 IconButton::new(
                                     "project-search-case-sensitive",
@@ -1768,6 +2437,23 @@ impl Render for ProjectSearchBar {
                                         cx,
                                     )
                                 })
+                                .middle(),
+                        )
+                        .child(
+                            ToggleButton::new("project-search-syntax-button", "Syntax")
+                                .style(ButtonStyle::Filled)
+                                .size(ButtonSize::Large)
+                                .selected(search.current_mode == SearchMode::Syntax)
+                                .on_click(cx.listener(|this, _, cx| {
+                                    this.activate_search_mode(SearchMode::Syntax, cx)
+                                }))
+                                .tooltip(|cx| {
+                                    Tooltip::for_action(
+                                        "Toggle syntax tree search",
+                                        &ActivateSyntaxMode,
+                                        cx,
+                                    )
+                                })
                                 .map(|this| {
                                     if semantic_is_available {
                                         this.middle()
@@ -1895,6 +2581,9 @@ impl Render for ProjectSearchBar {
             .on_action(cx.listener(|this, _: &ActivateRegexMode, cx| {
                 this.activate_search_mode(SearchMode::Regex, cx)
             }))
+            .on_action(cx.listener(|this, _: &ActivateSyntaxMode, cx| {
+                this.activate_search_mode(SearchMode::Syntax, cx)
+            }))
             .on_action(cx.listener(|this, _: &ActivateSemanticMode, cx| {
                 this.activate_search_mode(SearchMode::Semantic, cx)
             }))
@@ -1938,6 +2627,14 @@ impl Render for ProjectSearchBar {
                     this.on_action(cx.listener(|this, _: &ToggleIncludeIgnored, cx| {
                         this.toggle_search_option(SearchOptions::INCLUDE_IGNORED, cx);
                     }))
+                    .on_action(cx.listener(|this, _: &ToggleOpenBuffersOnly, cx| {
+                        this.toggle_search_option(SearchOptions::OPEN_BUFFERS_ONLY, cx);
+                    }))
+                    .on_action(cx.listener(
+                        |this, _: &ToggleModifiedFilesOnly, cx| {
+                            this.toggle_search_option(SearchOptions::MODIFIED_FILES_ONLY, cx);
+                        },
+                    ))
                 })
             })
             .on_action(cx.listener(Self::select_next_match))
@@ -1966,20 +2663,28 @@ impl Render for ProjectSearchBar {
                                 .border_1()
                                 .border_color(search.border_color_for(InputPanel::Include, cx))
                                 .rounded_lg()
+                                .on_action(cx.listener(|this, _: &PreviousHistoryQuery, cx| {
+                                    this.previous_filter_history(InputPanel::Include, cx)
+                                }))
+                                .on_action(cx.listener(|this, _: &NextHistoryQuery, cx| {
+                                    this.next_filter_history(InputPanel::Include, cx)
+                                }))
                                 .child(self.render_text_input(&search.included_files_editor, cx))
                                 .when(search.current_mode != SearchMode::Semantic, |this| {
-                                    this.child(
-                                        SearchOptions::INCLUDE_IGNORED.as_button(
-                                            search
-                                                .search_options
-                                                .contains(SearchOptions::INCLUDE_IGNORED),
-                                            cx.listener(|this, _, cx| {
-                                                this.toggle_search_option(
-                                                    SearchOptions::INCLUDE_IGNORED,
-                                                    cx,
-                                                );
-                                            }),
-                                        ),
+                                    this.children(
+                                        [
+                                            SearchOptions::INCLUDE_IGNORED,
+                                            SearchOptions::OPEN_BUFFERS_ONLY,
+                                            SearchOptions::MODIFIED_FILES_ONLY,
+                                        ]
+                                        .map(|option| {
+                                            option.as_button(
+                                                search.search_options.contains(option),
+                                                cx.listener(move |this, _, cx| {
+                                                    this.toggle_search_option(option, cx);
+                                                }),
+                                            )
+                                        }),
                                     )
                                 }),
                         )
@@ -1992,6 +2697,12 @@ impl Render for ProjectSearchBar {
                                 .border_1()
                                 .border_color(search.border_color_for(InputPanel::Exclude, cx))
                                 .rounded_lg()
+                                .on_action(cx.listener(|this, _: &PreviousHistoryQuery, cx| {
+                                    this.previous_filter_history(InputPanel::Exclude, cx)
+                                }))
+                                .on_action(cx.listener(|this, _: &NextHistoryQuery, cx| {
+                                    this.next_filter_history(InputPanel::Exclude, cx)
+                                }))
                                 .child(self.render_text_input(&search.excluded_files_editor, cx)),
                         ),
                 )
@@ -2231,6 +2942,207 @@ pub mod tests {
             .unwrap();
     }
 
+    #[gpui::test]
+    async fn test_replace_with_capture_groups_and_excluded_matches(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "let a = foo(1);\nlet b = foo(2);\nlet c = foo(3);",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let search = cx.new_model(|cx| ProjectSearch::new(project, cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.activate_search_mode(SearchMode::Regex, cx);
+                search_view.query_editor.update(cx, |query_editor, cx| {
+                    query_editor.set_text(r"foo\((\d)\)", cx)
+                });
+                search_view.search(cx);
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+
+        search_view
+            .update(cx, |search_view, cx| {
+                assert!(search_view.replacement_preview_blocks.is_empty());
+                search_view.replace_enabled = true;
+                search_view
+                    .replacement_editor
+                    .update(cx, |editor, cx| editor.set_text("bar($1)", cx));
+            })
+            .unwrap();
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(search_view.replacement_preview_blocks.len(), 3);
+
+                let second_match = search_view.model.read(cx).match_ranges[1].clone();
+                search_view.toggle_match_exclusion(second_match, cx);
+            })
+            .unwrap();
+
+        // The excluded match stays excluded when the matches before it change.
+        search.update(cx, |search, cx| {
+            search.match_ranges.remove(0);
+            cx.notify();
+        });
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(search_view.replacement_preview_blocks.len(), 2);
+
+                search_view.replace_all(&ReplaceAll, cx);
+                assert_eq!(
+                    search_view
+                        .results_editor
+                        .read(cx)
+                        .buffer()
+                        .read(cx)
+                        .snapshot(cx)
+                        .text(),
+                    "let a = foo(1);\nlet b = foo(2);\nlet c = bar(3);"
+                );
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_replace_skips_files_changed_since_search(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = ONE + ONE;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let search = cx.new_model(|cx| ProjectSearch::new(project.clone(), cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view
+                    .query_editor
+                    .update(cx, |query_editor, cx| query_editor.set_text("ONE", cx));
+                search_view
+                    .replacement_editor
+                    .update(cx, |editor, cx| editor.set_text("UNO", cx));
+                search_view.search(cx);
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+        search_view
+            .update(cx, |search_view, cx| {
+                assert!(search_view.buffers_changed_since_search(cx).is_empty());
+            })
+            .unwrap();
+
+        // Saving from within the editor doesn't make the results stale.
+        let two_buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/dir/two.rs", cx)
+            })
+            .await
+            .unwrap();
+        project
+            .update(cx, |project, cx| project.save_buffer(two_buffer, cx))
+            .await
+            .unwrap();
+        cx.background_executor.run_until_parked();
+        search_view
+            .update(cx, |search_view, cx| {
+                assert!(search_view.buffers_changed_since_search(cx).is_empty());
+            })
+            .unwrap();
+
+        fs.insert_file("/dir/one.rs", "const ONE: usize = 1; // one".to_string())
+            .await;
+        cx.background_executor.run_until_parked();
+        search_view
+            .update(cx, |search_view, cx| {
+                assert_eq!(search_view.buffers_changed_since_search(cx).len(), 1);
+                search_view.replace_all(&ReplaceAll, cx);
+                let results_text = search_view
+                    .results_editor
+                    .read(cx)
+                    .buffer()
+                    .read(cx)
+                    .snapshot(cx)
+                    .text();
+                assert!(results_text.contains("const ONE: usize = 1; // one"));
+                assert!(results_text.contains("const TWO: usize = UNO + UNO;"));
+            })
+            .unwrap();
+    }
+
+    #[gpui::test]
+    async fn test_search_only_open_buffers(cx: &mut TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.background_executor.clone());
+        fs.insert_tree(
+            "/dir",
+            json!({
+                "one.rs": "const ONE: usize = 1;",
+                "two.rs": "const TWO: usize = ONE + ONE;",
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+        let _open_buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/dir/two.rs", cx)
+            })
+            .await
+            .unwrap();
+        let search = cx.new_model(|cx| ProjectSearch::new(project, cx));
+        let search_view = cx.add_window(|cx| ProjectSearchView::new(search.clone(), cx, None));
+
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view
+                    .query_editor
+                    .update(cx, |query_editor, cx| query_editor.set_text("ONE", cx));
+                search_view.search(cx);
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+        assert_eq!(
+            search_view
+                .update(cx, |search_view, cx| search_view
+                    .results_editor
+                    .update(cx, |editor, cx| editor.display_text(cx)))
+                .unwrap(),
+            "\n\nconst ONE: usize = 1;\n\n\nconst TWO: usize = ONE + ONE;"
+        );
+
+        search_view
+            .update(cx, |search_view, cx| {
+                search_view.toggle_search_option(SearchOptions::OPEN_BUFFERS_ONLY, cx);
+                search_view.search(cx);
+            })
+            .unwrap();
+        cx.background_executor.run_until_parked();
+        assert_eq!(
+            search_view
+                .update(cx, |search_view, cx| search_view
+                    .results_editor
+                    .update(cx, |editor, cx| editor.display_text(cx)))
+                .unwrap(),
+            "\n\nconst TWO: usize = ONE + ONE;",
+            "Only the open buffer should have results, even though the previous search opened both"
+        );
+    }
+
     #[gpui::test]
     async fn test_deploy_project_search_focus(cx: &mut TestAppContext) {
         init_test(cx);
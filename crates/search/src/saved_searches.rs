@@ -0,0 +1,377 @@
+//! Named project searches, kept in the settings so that they can be run again from a picker.
+
+use crate::ProjectSearchView;
+use anyhow::Result;
+use client::proto;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Task,
+    View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{update_settings_file, Settings};
+use std::sync::Arc;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{notifications::Toast, ModalView, Workspace};
+
+actions!(project_search, [SaveSearch, OpenSavedSearch]);
+
+const SAVED_SEARCH_TOAST_ID: usize = 4097;
+
+pub fn init(cx: &mut AppContext) {
+    SavedSearches::register(cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(save_search);
+        workspace.register_action(open_saved_search);
+    })
+    .detach();
+}
+
+/// A project search, along with the options and filters it was run with.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SavedSearch {
+    /// The name the search is listed by.
+    pub name: String,
+    /// The text, the regular expression or the tree-sitter query to search for.
+    pub query: String,
+    /// Whether the query is a regular expression.
+    #[serde(default)]
+    pub regex: bool,
+    /// Whether the query is a tree-sitter query.
+    #[serde(default)]
+    pub syntax: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    #[serde(default)]
+    pub whole_word: bool,
+    #[serde(default)]
+    pub include_ignored: bool,
+    /// The globs of the files to search, separated by commas.
+    #[serde(default)]
+    pub include: String,
+    /// The globs of the files not to search, separated by commas.
+    #[serde(default)]
+    pub exclude: String,
+}
+
+impl SavedSearch {
+    /// Describes the search to the followers of a project search, who don't need its name.
+    pub(crate) fn to_proto(&self) -> proto::ProjectSearchQuery {
+        proto::ProjectSearchQuery {
+            query: self.query.clone(),
+            regex: self.regex,
+            syntax: self.syntax,
+            whole_word: self.whole_word,
+            case_sensitive: self.case_sensitive,
+            include_ignored: self.include_ignored,
+            files_to_include: self.include.clone(),
+            files_to_exclude: self.exclude.clone(),
+        }
+    }
+
+    pub(crate) fn from_proto(query: proto::ProjectSearchQuery) -> Self {
+        Self {
+            name: String::new(),
+            query: query.query,
+            regex: query.regex,
+            syntax: query.syntax,
+            case_sensitive: query.case_sensitive,
+            whole_word: query.whole_word,
+            include_ignored: query.include_ignored,
+            include: query.files_to_include,
+            exclude: query.files_to_exclude,
+        }
+    }
+}
+
+pub struct SavedSearches(pub Vec<SavedSearch>);
+
+impl Settings for SavedSearches {
+    const KEY: Option<&'static str> = Some("saved_searches");
+
+    type FileContent = Option<Vec<SavedSearch>>;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self> {
+        Ok(Self(
+            user_values
+                .iter()
+                .rev()
+                .find_map(|saved_searches| (*saved_searches).clone())
+                .or_else(|| default_value.clone())
+                .ok_or_else(Self::missing_default)?,
+        ))
+    }
+}
+
+/// Saves the active project search in the settings, named after its query. Saving a
+/// search with the same name replaces it.
+fn save_search(workspace: &mut Workspace, _: &SaveSearch, cx: &mut ViewContext<Workspace>) {
+    let Some(search_view) = workspace.active_item_as::<ProjectSearchView>(cx) else {
+        return;
+    };
+    let query = search_view.read(cx).saved_search(String::new(), cx).query;
+    if query.is_empty() {
+        return;
+    }
+    let saved_search = search_view.read(cx).saved_search(query.clone(), cx);
+
+    update_settings_file::<SavedSearches>(workspace.app_state().fs.clone(), cx, move |content| {
+        let saved_searches = content.get_or_insert_with(Vec::new);
+        saved_searches.retain(|search| search.name != saved_search.name);
+        saved_searches.push(saved_search);
+    });
+    workspace.show_toast(
+        Toast::new(
+            SAVED_SEARCH_TOAST_ID,
+            format!(
+                "Saved search \"{query}\", which can be renamed in the `saved_searches` setting"
+            ),
+        ),
+        cx,
+    );
+}
+
+fn open_saved_search(
+    workspace: &mut Workspace,
+    _: &OpenSavedSearch,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let saved_searches = SavedSearches::get_global(cx).0.clone();
+    if saved_searches.is_empty() {
+        workspace.show_toast(
+            Toast::new(
+                SAVED_SEARCH_TOAST_ID,
+                "There are no saved searches. Save a project search to run it again later.",
+            ),
+            cx,
+        );
+        return;
+    }
+
+    let workspace_handle = cx.view().downgrade();
+    workspace.toggle_modal(cx, |cx| {
+        SavedSearchPicker::new(
+            SavedSearchPickerDelegate::new(cx.view().downgrade(), workspace_handle, saved_searches),
+            cx,
+        )
+    });
+}
+
+pub struct SavedSearchPicker {
+    picker: View<Picker<SavedSearchPickerDelegate>>,
+}
+
+impl ModalView for SavedSearchPicker {}
+
+impl EventEmitter<DismissEvent> for SavedSearchPicker {}
+
+impl FocusableView for SavedSearchPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for SavedSearchPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl SavedSearchPicker {
+    fn new(delegate: SavedSearchPickerDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        Self { picker }
+    }
+}
+
+pub struct SavedSearchPickerDelegate {
+    view: WeakView<SavedSearchPicker>,
+    workspace: WeakView<Workspace>,
+    saved_searches: Vec<SavedSearch>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl SavedSearchPickerDelegate {
+    fn new(
+        view: WeakView<SavedSearchPicker>,
+        workspace: WeakView<Workspace>,
+        saved_searches: Vec<SavedSearch>,
+    ) -> Self {
+        let matches = saved_searches
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, saved_search)| StringMatch {
+                candidate_id,
+                score: 0.0,
+                positions: Default::default(),
+                string: saved_search.name.clone(),
+            })
+            .collect();
+        Self {
+            view,
+            workspace,
+            saved_searches,
+            matches,
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for SavedSearchPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Run a saved search...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .saved_searches
+            .iter()
+            .enumerate()
+            .map(|(id, saved_search)| StringMatchCandidate {
+                id,
+                char_bag: saved_search.name.as_str().into(),
+                string: saved_search.name.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, _| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = this
+                    .delegate
+                    .selected_index
+                    .min(this.delegate.matches.len().saturating_sub(1));
+            })
+            .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(saved_search) = self
+            .matches
+            .get(self.selected_index)
+            .map(|mat| self.saved_searches[mat.candidate_id].clone())
+        {
+            self.workspace
+                .update(cx, |workspace, cx| {
+                    ProjectSearchView::run_saved_search(workspace, &saved_search, cx)
+                })
+                .log_err();
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let saved_search_match = &self.matches[ix];
+        let saved_search = &self.saved_searches[saved_search_match.candidate_id];
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    saved_search_match.string.clone(),
+                    saved_search_match.positions.clone(),
+                ))
+                .end_slot(Label::new(saved_search.query.clone()).color(Color::Muted)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use settings::SettingsStore;
+
+    #[gpui::test]
+    fn test_saved_searches_setting(cx: &mut AppContext) {
+        let store = SettingsStore::test(cx);
+        cx.set_global(store);
+        SavedSearches::register(cx);
+        assert!(SavedSearches::get_global(cx).0.is_empty());
+
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store
+                .set_user_settings(
+                    r#"{
+                        "saved_searches": [
+                            { "name": "todos", "query": "TODO|FIXME", "regex": true, "include": "crates/**" }
+                        ]
+                    }"#,
+                    cx,
+                )
+                .unwrap();
+        });
+        assert_eq!(
+            SavedSearches::get_global(cx).0,
+            [SavedSearch {
+                name: "todos".into(),
+                query: "TODO|FIXME".into(),
+                regex: true,
+                syntax: false,
+                case_sensitive: false,
+                whole_word: false,
+                include_ignored: false,
+                include: "crates/**".into(),
+                exclude: String::new(),
+            }]
+        );
+    }
+}
@@ -4,6 +4,7 @@ use gpui::{actions, Action, AppContext, IntoElement};
 pub use mode::SearchMode;
 use project::search::SearchQuery;
 pub use project_search::ProjectSearchView;
+pub use saved_searches::{SavedSearch, SavedSearches};
 use ui::{prelude::*, Tooltip};
 use ui::{ButtonStyle, IconButton};
 
@@ -11,12 +12,15 @@ pub mod buffer_search;
 mod history;
 mod mode;
 pub mod project_search;
+mod saved_searches;
 pub(crate) mod search_bar;
 
 pub fn init(cx: &mut AppContext) {
     menu::init();
+    history::init(cx);
     buffer_search::init(cx);
     project_search::init(cx);
+    saved_searches::init(cx);
 }
 
 actions!(
@@ -26,6 +30,8 @@ actions!(
         ToggleWholeWord,
         ToggleCaseSensitive,
         ToggleIncludeIgnored,
+        ToggleOpenBuffersOnly,
+        ToggleModifiedFilesOnly,
         ToggleReplace,
         SelectNextMatch,
         SelectPrevMatch,
@@ -35,6 +41,7 @@ actions!(
         ActivateTextMode,
         ActivateSemanticMode,
         ActivateRegexMode,
+        ActivateSyntaxMode,
         ReplaceAll,
         ReplaceNext,
     ]
@@ -47,6 +54,8 @@ bitflags! {
         const WHOLE_WORD = 0b001;
         const CASE_SENSITIVE = 0b010;
         const INCLUDE_IGNORED = 0b100;
+        const OPEN_BUFFERS_ONLY = 0b1000;
+        const MODIFIED_FILES_ONLY = 0b10000;
     }
 }
 
@@ -56,6 +65,8 @@ impl SearchOptions {
             SearchOptions::WHOLE_WORD => "Match Whole Word",
             SearchOptions::CASE_SENSITIVE => "Match Case",
             SearchOptions::INCLUDE_IGNORED => "Include ignored",
+            SearchOptions::OPEN_BUFFERS_ONLY => "Only open files",
+            SearchOptions::MODIFIED_FILES_ONLY => "Only files with git changes",
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -65,6 +76,8 @@ impl SearchOptions {
             SearchOptions::WHOLE_WORD => ui::IconName::WholeWord,
             SearchOptions::CASE_SENSITIVE => ui::IconName::CaseSensitive,
             SearchOptions::INCLUDE_IGNORED => ui::IconName::FileGit,
+            SearchOptions::OPEN_BUFFERS_ONLY => ui::IconName::File,
+            SearchOptions::MODIFIED_FILES_ONLY => ui::IconName::Github,
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -74,6 +87,8 @@ impl SearchOptions {
             SearchOptions::WHOLE_WORD => Box::new(ToggleWholeWord),
             SearchOptions::CASE_SENSITIVE => Box::new(ToggleCaseSensitive),
             SearchOptions::INCLUDE_IGNORED => Box::new(ToggleIncludeIgnored),
+            SearchOptions::OPEN_BUFFERS_ONLY => Box::new(ToggleOpenBuffersOnly),
+            SearchOptions::MODIFIED_FILES_ONLY => Box::new(ToggleModifiedFilesOnly),
             _ => panic!("{:?} is not a named SearchOption", self),
         }
     }
@@ -87,6 +102,11 @@ impl SearchOptions {
         options.set(SearchOptions::WHOLE_WORD, query.whole_word());
         options.set(SearchOptions::CASE_SENSITIVE, query.case_sensitive());
         options.set(SearchOptions::INCLUDE_IGNORED, query.include_ignored());
+        options.set(SearchOptions::OPEN_BUFFERS_ONLY, query.open_buffers_only());
+        options.set(
+            SearchOptions::MODIFIED_FILES_ONLY,
+            query.modified_files_only(),
+        );
         options
     }
 
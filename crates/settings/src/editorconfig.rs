@@ -0,0 +1,236 @@
+//! Parsing of [EditorConfig](https://editorconfig.org) files, whose properties apply to the files
+//! matching the glob of the section they're in, relative to the directory of the file.
+
+use globset::{GlobBuilder, GlobMatcher};
+use std::path::Path;
+
+/// The name of the files that EditorConfig properties are read from.
+pub const EDITORCONFIG_FILE_NAME: &str = ".editorconfig";
+
+/// The contents of an `.editorconfig` file.
+#[derive(Debug, Clone, Default)]
+pub struct Editorconfig {
+    /// Whether files in parent directories are ignored.
+    pub root: bool,
+    sections: Vec<(GlobMatcher, EditorconfigProperties)>,
+}
+
+/// The EditorConfig properties that apply to a file, where `None` leaves
+/// the corresponding setting alone.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditorconfigProperties {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_size: Option<IndentSize>,
+    pub tab_width: Option<u32>,
+    pub end_of_line: Option<EndOfLine>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    Tab,
+    Space,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentSize {
+    Columns(u32),
+    /// The width of a tab, given by `tab_width`.
+    Tab,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EndOfLine {
+    Lf,
+    Crlf,
+    Cr,
+}
+
+impl Editorconfig {
+    /// Parses an `.editorconfig` file, ignoring properties, values and
+    /// sections that aren't understood.
+    pub fn parse(content: &str) -> Self {
+        let mut editorconfig = Self::default();
+        let mut section = None;
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(glob) = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+            {
+                if let Some((matcher, properties)) = section.take() {
+                    editorconfig.sections.push((matcher, properties));
+                }
+                section = glob_matcher(glob).map(|matcher| (matcher, Default::default()));
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim().to_lowercase();
+            match &mut section {
+                Some((_, properties)) => properties.set(&key, &value),
+                None if key == "root" => editorconfig.root = value == "true",
+                None => {}
+            }
+        }
+        editorconfig.sections.extend(section);
+        editorconfig
+    }
+
+    /// Returns the properties for the file at the given path, relative to the
+    /// directory containing the `.editorconfig` file, applying later sections
+    /// over earlier ones.
+    pub fn properties_for_path(&self, path: &Path) -> Option<EditorconfigProperties> {
+        let mut result = None;
+        for (matcher, properties) in &self.sections {
+            if matcher.is_match(path) {
+                result
+                    .get_or_insert_with(EditorconfigProperties::default)
+                    .merge(properties);
+            }
+        }
+        result
+    }
+}
+
+impl EditorconfigProperties {
+    fn set(&mut self, key: &str, value: &str) {
+        match key {
+            "indent_style" => {
+                self.indent_style = match value {
+                    "tab" => Some(IndentStyle::Tab),
+                    "space" => Some(IndentStyle::Space),
+                    _ => None,
+                }
+            }
+            "indent_size" => {
+                self.indent_size = match value {
+                    "tab" => Some(IndentSize::Tab),
+                    _ => value.parse().ok().map(IndentSize::Columns),
+                }
+            }
+            "tab_width" => self.tab_width = value.parse().ok(),
+            "end_of_line" => {
+                self.end_of_line = match value {
+                    "lf" => Some(EndOfLine::Lf),
+                    "crlf" => Some(EndOfLine::Crlf),
+                    "cr" => Some(EndOfLine::Cr),
+                    _ => None,
+                }
+            }
+            "trim_trailing_whitespace" => self.trim_trailing_whitespace = parse_bool(value),
+            "insert_final_newline" => self.insert_final_newline = parse_bool(value),
+            _ => {}
+        }
+    }
+
+    /// Applies the given properties over these ones.
+    pub fn merge(&mut self, other: &Self) {
+        self.indent_style = other.indent_style.or(self.indent_style);
+        self.indent_size = other.indent_size.or(self.indent_size);
+        self.tab_width = other.tab_width.or(self.tab_width);
+        self.end_of_line = other.end_of_line.or(self.end_of_line);
+        self.trim_trailing_whitespace = other
+            .trim_trailing_whitespace
+            .or(self.trim_trailing_whitespace);
+        self.insert_final_newline = other.insert_final_newline.or(self.insert_final_newline);
+    }
+
+    /// The number of columns in a level of indentation, which defaults to the
+    /// width of a tab and the other way around.
+    pub fn indent_width(&self) -> Option<u32> {
+        match self.indent_size {
+            Some(IndentSize::Columns(columns)) => Some(columns),
+            Some(IndentSize::Tab) | None => self.tab_width,
+        }
+    }
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// Globs without a slash match files with that name in any directory, and
+/// others match paths relative to the `.editorconfig` file's directory.
+fn glob_matcher(glob: &str) -> Option<GlobMatcher> {
+    let glob = if glob.contains('/') {
+        glob.trim_start_matches('/').to_string()
+    } else {
+        format!("**/{glob}")
+    };
+    GlobBuilder::new(&glob)
+        .literal_separator(true)
+        .build()
+        .ok()
+        .map(|glob| glob.compile_matcher())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+
+    #[test]
+    fn test_editorconfig_properties() {
+        let editorconfig = Editorconfig::parse(indoc! {"
+            # Top-most EditorConfig file
+            root = true
+
+            [*]
+            end_of_line = lf
+            insert_final_newline = true
+            indent_style = space
+            indent_size = 4
+
+            [*.{js,json}]
+            indent_size = 2
+
+            [Makefile]
+            indent_style = tab
+            indent_size = tab
+            tab_width = 8
+
+            [lib/**/*.js]
+            trim_trailing_whitespace = true
+        "});
+        assert!(editorconfig.root);
+
+        let defaults = EditorconfigProperties {
+            indent_style: Some(IndentStyle::Space),
+            indent_size: Some(IndentSize::Columns(4)),
+            end_of_line: Some(EndOfLine::Lf),
+            insert_final_newline: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            editorconfig.properties_for_path(Path::new("src/main.rs")),
+            Some(defaults)
+        );
+        assert_eq!(
+            editorconfig.properties_for_path(Path::new("lib/a/b.js")),
+            Some(EditorconfigProperties {
+                indent_size: Some(IndentSize::Columns(2)),
+                trim_trailing_whitespace: Some(true),
+                ..defaults
+            })
+        );
+
+        let makefile = editorconfig
+            .properties_for_path(Path::new("build/Makefile"))
+            .unwrap();
+        assert_eq!(makefile.indent_style, Some(IndentStyle::Tab));
+        assert_eq!(makefile.indent_width(), Some(8));
+    }
+}
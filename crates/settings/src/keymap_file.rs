@@ -1,13 +1,16 @@
-use crate::{settings_store::parse_json_with_comments, SettingsAssets};
+use crate::{
+    settings_store::{parse_json_with_comments, replace_value_in_json_text, to_pretty_json},
+    SettingsAssets,
+};
 use anyhow::{anyhow, Context, Result};
 use collections::BTreeMap;
-use gpui::{Action, AppContext, KeyBinding, SharedString};
+use gpui::{Action, AppContext, KeyBinding, MouseBinding, SharedString};
 use schemars::{
     gen::{SchemaGenerator, SchemaSettings},
     schema::{InstanceType, Schema, SchemaObject, SingleOrVec, SubschemaValidation},
     JsonSchema,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use util::{asset_str, ResultExt};
 
@@ -19,7 +22,13 @@ pub struct KeymapFile(Vec<KeymapBlock>);
 pub struct KeymapBlock {
     #[serde(default)]
     context: Option<String>,
+    #[serde(default)]
     bindings: BTreeMap<String, KeymapAction>,
+    /// Actions to run when a mouse button is pressed over an element in the context,
+    /// instead of the button's default behavior. Buttons are written like keystrokes,
+    /// such as `middle`, `ctrl-left` or `back`.
+    #[serde(default)]
+    mouse_bindings: BTreeMap<String, KeymapAction>,
 }
 
 #[derive(Debug, Deserialize, Default, Clone)]
@@ -39,6 +48,13 @@ impl JsonSchema for KeymapAction {
 #[derive(Deserialize)]
 struct ActionWithData(Box<str>, Value);
 
+#[derive(Serialize)]
+struct NewKeymapBlock<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    context: Option<&'a str>,
+    bindings: BTreeMap<&'a str, &'a Value>,
+}
+
 impl KeymapFile {
     pub fn load_asset(asset_path: &str, cx: &mut AppContext) -> Result<()> {
         let content = asset_str::<SettingsAssets>(asset_path);
@@ -51,47 +67,43 @@ impl KeymapFile {
     }
 
     pub fn add_to_cx(self, cx: &mut AppContext) -> Result<()> {
-        for KeymapBlock { context, bindings } in self.0 {
+        for KeymapBlock {
+            context,
+            bindings,
+            mouse_bindings,
+        } in self.0
+        {
             let bindings = bindings
                 .into_iter()
                 .filter_map(|(keystroke, action)| {
-                    let action = action.0;
-
-                    // This is a workaround for a limitation in serde: serde-rs/json#497
-                    // We want to deserialize the action data as a `RawValue` so that we can
-                    // deserialize the action itself dynamically directly from the JSON
-                    // string. But `RawValue` currently does not work inside of an untagged enum.
-                    match action {
-                        Value::Array(items) => {
-                            let Ok([name, data]): Result<[serde_json::Value; 2], _> =
-                                items.try_into()
-                            else {
-                                return Some(Err(anyhow!("Expected array of length 2")));
-                            };
-                            let serde_json::Value::String(name) = name else {
-                                return Some(Err(anyhow!(
-                                    "Expected first item in array to be a string."
-                                )));
-                            };
-                            cx.build_action(&name, Some(data))
-                        }
-                        Value::String(name) => cx.build_action(&name, None),
-                        Value::Null => Ok(no_action()),
-                        _ => {
-                            return Some(Err(anyhow!("Expected two-element array, got {action:?}")))
-                        }
-                    }
-                    .with_context(|| {
-                        format!(
-                            "invalid binding value for keystroke {keystroke}, context {context:?}"
-                        )
-                    })
-                    .log_err()
-                    .map(|action| KeyBinding::load(&keystroke, action, context.as_deref()))
+                    build_action(action, cx)
+                        .with_context(|| {
+                            format!(
+                                "invalid binding value for keystroke {keystroke}, context {context:?}"
+                            )
+                        })
+                        .log_err()
+                        .map(|action| KeyBinding::load(&keystroke, action, context.as_deref()))
                 })
                 .collect::<Result<Vec<_>>>()?;
 
             cx.bind_keys(bindings);
+
+            let mouse_bindings = mouse_bindings
+                .into_iter()
+                .filter_map(|(stroke, action)| {
+                    build_action(action, cx)
+                        .with_context(|| {
+                            format!(
+                                "invalid binding value for mouse button {stroke}, context {context:?}"
+                            )
+                        })
+                        .log_err()
+                        .map(|action| MouseBinding::load(&stroke, action, context.as_deref()))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            cx.bind_mouse(mouse_bindings);
         }
         Ok(())
     }
@@ -137,13 +149,116 @@ impl KeymapFile {
     }
 }
 
+/// Binds `keystrokes` to the action named `action` in the text of a keymap file, or disables
+/// them when `action` is `None`. The binding goes in the last block with the given context,
+/// or in a new block at the end, leaving the rest of the text and its comments untouched.
+pub fn update_binding_in_json_text(
+    text: &str,
+    context: Option<&str>,
+    keystrokes: &str,
+    action: Option<&str>,
+    tab_size: usize,
+) -> Result<String> {
+    let value = action.map_or(Value::Null, |action| Value::String(action.to_string()));
+
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&tree_sitter_json::language()).unwrap();
+    let syntax_tree = parser.parse(text, None).context("failed to parse keymap")?;
+    let root = syntax_tree.root_node();
+    let mut cursor = root.walk();
+    let array = root
+        .named_children(&mut cursor)
+        .find(|node| node.kind() == "array");
+
+    let new_block = NewKeymapBlock {
+        context,
+        bindings: BTreeMap::from_iter([(keystrokes, &value)]),
+    };
+    let indent = " ".repeat(tab_size);
+    let Some(array) = array else {
+        if !text.trim().is_empty() {
+            return Err(anyhow!("the keymap must be an array of blocks"));
+        }
+        let new_block = to_pretty_json(&new_block, tab_size, tab_size);
+        return Ok(format!("[\n{indent}{new_block}\n]\n"));
+    };
+
+    let mut cursor = array.walk();
+    let blocks = array
+        .named_children(&mut cursor)
+        .filter(|node| node.kind() == "object")
+        .collect::<Vec<_>>();
+    let mut new_text = text.to_string();
+    if let Some(block) = blocks
+        .iter()
+        .rev()
+        .find(|block| block_context(text, block).as_deref() == context)
+    {
+        let block_range = block.byte_range();
+        let (range, replacement) = replace_value_in_json_text(
+            &text[block_range.clone()],
+            &["bindings", keystrokes],
+            tab_size,
+            &value,
+        );
+        new_text.replace_range(
+            block_range.start + range.start..block_range.start + range.end,
+            &replacement,
+        );
+    } else {
+        let new_block = to_pretty_json(&new_block, tab_size, tab_size);
+        match blocks.last() {
+            Some(last_block) => {
+                new_text.insert_str(last_block.end_byte(), &format!(",\n{indent}{new_block}"))
+            }
+            None => {
+                new_text.insert_str(array.start_byte() + 1, &format!("\n{indent}{new_block}\n"))
+            }
+        }
+    }
+    Ok(new_text)
+}
+
+fn block_context(text: &str, block: &tree_sitter::Node) -> Option<String> {
+    let mut cursor = block.walk();
+    let context = block.named_children(&mut cursor).find_map(|pair| {
+        let key = pair.child_by_field_name("key")?;
+        (&text[key.byte_range()] == "\"context\"").then(|| pair.child_by_field_name("value"))?
+    })?;
+    serde_json::from_str(&text[context.byte_range()]).ok()
+}
+
+fn build_action(action: KeymapAction, cx: &AppContext) -> Result<Box<dyn gpui::Action>> {
+    // This is a workaround for a limitation in serde: serde-rs/json#497
+    // We want to deserialize the action data as a `RawValue` so that we can
+    // deserialize the action itself dynamically directly from the JSON
+    // string. But `RawValue` currently does not work inside of an untagged enum.
+    match action.0 {
+        Value::Array(items) => {
+            let Ok([name, data]): Result<[serde_json::Value; 2], _> = items.try_into() else {
+                return Err(anyhow!("Expected array of length 2"));
+            };
+            let serde_json::Value::String(name) = name else {
+                return Err(anyhow!("Expected first item in array to be a string."));
+            };
+            cx.build_action(&name, Some(data))
+        }
+        Value::String(name) => cx.build_action(&name, None),
+        Value::Null => Ok(no_action()),
+        action => Err(anyhow!("Expected two-element array, got {action:?}")),
+    }
+}
+
 fn no_action() -> Box<dyn gpui::Action> {
     gpui::NoAction.boxed_clone()
 }
 
 #[cfg(test)]
 mod tests {
+    use super::update_binding_in_json_text;
     use crate::KeymapFile;
+    use indoc::indoc;
+    use pretty_assertions::assert_eq;
 
     #[test]
     fn can_deserialize_keymap_with_trailing_comma() {
@@ -160,4 +275,115 @@ mod tests {
         };
         KeymapFile::parse(json).unwrap();
     }
+
+    #[test]
+    fn can_deserialize_keymap_with_only_mouse_bindings() {
+        let json = indoc! {r#"
+            [
+              {
+                "context": "Pane",
+                "mouse_bindings": {
+                  "middle": null,
+                  "ctrl-back": "pane::ActivatePrevItem"
+                }
+              }
+            ]
+        "#};
+        let keymap = KeymapFile::parse(json).unwrap();
+        assert!(keymap.0[0].bindings.is_empty());
+        assert_eq!(
+            keymap.0[0].mouse_bindings.keys().collect::<Vec<_>>(),
+            ["ctrl-back", "middle"]
+        );
+    }
+
+    #[test]
+    fn test_update_binding_in_json_text() {
+        let text = indoc! {r#"
+            // My bindings
+            [
+                {
+                    "context": "Editor",
+                    "bindings": {
+                        "ctrl-k": "editor::DeleteLine" // Like in my old editor
+                    }
+                }
+            ]
+        "#};
+
+        let text = update_binding_in_json_text(
+            text,
+            Some("Editor"),
+            "ctrl-j",
+            Some("editor::JoinLines"),
+            4,
+        )
+        .unwrap();
+        assert_eq!(
+            text,
+            indoc! {r#"
+                // My bindings
+                [
+                    {
+                        "context": "Editor",
+                        "bindings": {
+                            "ctrl-j": "editor::JoinLines",
+                            "ctrl-k": "editor::DeleteLine" // Like in my old editor
+                        }
+                    }
+                ]
+            "#}
+        );
+
+        let text = update_binding_in_json_text(&text, Some("Editor"), "ctrl-k", None, 4).unwrap();
+        let text =
+            update_binding_in_json_text(&text, None, "ctrl-x ctrl-s", Some("workspace::Save"), 4)
+                .unwrap();
+        assert_eq!(
+            text,
+            indoc! {r#"
+                // My bindings
+                [
+                    {
+                        "context": "Editor",
+                        "bindings": {
+                            "ctrl-j": "editor::JoinLines",
+                            "ctrl-k": null // Like in my old editor
+                        }
+                    },
+                    {
+                        "bindings": {
+                            "ctrl-x ctrl-s": "workspace::Save"
+                        }
+                    }
+                ]
+            "#}
+        );
+        KeymapFile::parse(&text).unwrap();
+
+        assert_eq!(
+            update_binding_in_json_text(
+                "",
+                Some("Pane"),
+                "ctrl-w",
+                Some("pane::CloseActiveItem"),
+                2
+            )
+            .unwrap(),
+            indoc! {r#"
+                [
+                  {
+                    "context": "Pane",
+                    "bindings": {
+                      "ctrl-w": "pane::CloseActiveItem"
+                    }
+                  }
+                ]
+            "#}
+        );
+        assert_eq!(
+            update_binding_in_json_text("[]", None, "f1", Some("zed::About"), 2).unwrap(),
+            "[\n  {\n    \"bindings\": {\n      \"f1\": \"zed::About\"\n    }\n  }\n]"
+        );
+    }
 }
@@ -1,3 +1,4 @@
+pub mod editorconfig;
 mod keymap_file;
 mod settings_file;
 mod settings_store;
@@ -6,9 +7,13 @@ use rust_embed::RustEmbed;
 use std::{borrow::Cow, str};
 use util::asset_str;
 
-pub use keymap_file::KeymapFile;
+pub use editorconfig::{Editorconfig, EditorconfigProperties, EDITORCONFIG_FILE_NAME};
+pub use keymap_file::{update_binding_in_json_text, KeymapFile};
 pub use settings_file::*;
-pub use settings_store::{Settings, SettingsJsonSchemaParams, SettingsStore};
+pub use settings_store::{
+    parse_json_with_comments, update_setting_in_json_text, Settings, SettingsJsonSchemaParams,
+    SettingsStore,
+};
 
 #[derive(RustEmbed)]
 #[folder = "../../assets"]
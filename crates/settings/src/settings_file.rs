@@ -2,7 +2,7 @@ use crate::{settings_store::SettingsStore, Settings};
 use anyhow::Result;
 use fs::Fs;
 use futures::{channel::mpsc, StreamExt};
-use gpui::{AppContext, BackgroundExecutor};
+use gpui::{AppContext, BackgroundExecutor, Task};
 use std::{io::ErrorKind, path::PathBuf, str, sync::Arc, time::Duration};
 use util::{paths, ResultExt};
 
@@ -105,6 +105,20 @@ async fn load_settings(fs: &Arc<dyn Fs>) -> Result<String> {
     }
 }
 
+async fn load_keymap(fs: &Arc<dyn Fs>) -> Result<String> {
+    match fs.load(&paths::KEYMAP).await {
+        result @ Ok(_) => result,
+        Err(err) => {
+            if let Some(e) = err.downcast_ref::<std::io::Error>() {
+                if e.kind() == ErrorKind::NotFound {
+                    return Ok(String::new());
+                }
+            }
+            return Err(err);
+        }
+    }
+}
+
 pub fn update_settings_file<T: Settings>(
     fs: Arc<dyn Fs>,
     cx: &mut AppContext,
@@ -120,3 +134,31 @@ pub fn update_settings_file<T: Settings>(
     })
     .detach_and_log_err(cx);
 }
+
+/// Rewrites the user's settings file with the text returned by `update`.
+pub fn update_user_settings_file(
+    fs: Arc<dyn Fs>,
+    cx: &mut AppContext,
+    update: impl 'static + Send + FnOnce(String) -> Result<String>,
+) -> Task<Result<()>> {
+    cx.background_executor().spawn(async move {
+        let old_text = load_settings(&fs).await?;
+        let new_text = update(old_text)?;
+        fs.atomic_write(paths::SETTINGS.clone(), new_text).await?;
+        Ok(())
+    })
+}
+
+/// Rewrites the user's keymap file with the text returned by `update`.
+pub fn update_keymap_file(
+    fs: Arc<dyn Fs>,
+    cx: &mut AppContext,
+    update: impl 'static + Send + FnOnce(String) -> Result<String>,
+) -> Task<Result<()>> {
+    cx.background_executor().spawn(async move {
+        let old_text = load_keymap(&fs).await?;
+        let new_text = update(old_text)?;
+        fs.atomic_write(paths::KEYMAP.clone(), new_text).await?;
+        Ok(())
+    })
+}
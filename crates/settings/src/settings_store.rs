@@ -1,3 +1,4 @@
+use crate::{Editorconfig, EditorconfigProperties};
 use anyhow::{anyhow, Context, Result};
 use collections::{btree_map, hash_map, BTreeMap, HashMap};
 use gpui::AppContext;
@@ -7,6 +8,7 @@ use serde::{de::DeserializeOwned, Deserialize as _, Serialize};
 use smallvec::SmallVec;
 use std::{
     any::{type_name, Any, TypeId},
+    borrow::Cow,
     fmt::Debug,
     ops::Range,
     path::Path,
@@ -117,12 +119,18 @@ pub struct SettingsJsonSchemaParams<'a> {
     pub font_names: &'a [String],
 }
 
+/// The key of the user's named sets of setting overrides.
+const PROFILES_KEY: &str = "profiles";
+/// The key of the name of the profile whose overrides apply.
+const ACTIVE_PROFILE_KEY: &str = "active_profile";
+
 /// A set of strongly-typed setting values defined via multiple JSON files.
 pub struct SettingsStore {
     setting_values: HashMap<TypeId, Box<dyn AnySettingValue>>,
     raw_default_settings: serde_json::Value,
     raw_user_settings: serde_json::Value,
     raw_local_settings: BTreeMap<(usize, Arc<Path>), serde_json::Value>,
+    editorconfigs: BTreeMap<(usize, Arc<Path>), Editorconfig>,
     tab_size_callback: Option<(
         TypeId,
         Box<dyn Fn(&dyn Any) -> Option<usize> + Send + Sync + 'static>,
@@ -136,6 +144,7 @@ impl Default for SettingsStore {
             raw_default_settings: serde_json::json!({}),
             raw_user_settings: serde_json::json!({}),
             raw_local_settings: Default::default(),
+            editorconfigs: Default::default(),
             tab_size_callback: Default::default(),
         }
     }
@@ -191,7 +200,7 @@ impl SettingsStore {
             let mut user_values_stack = Vec::new();
 
             if let Some(user_settings) = setting_value
-                .deserialize_setting(&self.raw_user_settings)
+                .deserialize_setting(&user_settings_with_profile(&self.raw_user_settings))
                 .log_err()
             {
                 user_values_stack = vec![user_settings];
@@ -238,6 +247,48 @@ impl SettingsStore {
         &self.raw_user_settings
     }
 
+    /// Get the names of the settings profiles defined in the user's settings.
+    pub fn profile_names(&self) -> Vec<String> {
+        self.raw_user_settings
+            .get(PROFILES_KEY)
+            .and_then(serde_json::Value::as_object)
+            .map(|profiles| profiles.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Get the name of the settings profile whose overrides apply on top of the user's settings.
+    pub fn active_profile(&self) -> Option<&str> {
+        active_profile(&self.raw_user_settings)
+    }
+
+    /// Activate the settings profile with the given name, or deactivate the active profile,
+    /// until the user's settings are next set.
+    pub fn set_active_profile(&mut self, profile: Option<&str>, cx: &mut AppContext) -> Result<()> {
+        let settings = self
+            .raw_user_settings
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("settings must be an object"))?;
+        match profile {
+            Some(profile) => {
+                settings.insert(ACTIVE_PROFILE_KEY.to_string(), profile.into());
+            }
+            None => {
+                settings.remove(ACTIVE_PROFILE_KEY);
+            }
+        }
+        self.recompute_values(None, cx)
+    }
+
+    /// Get the default settings as a raw JSON value.
+    pub fn raw_default_settings(&self) -> &serde_json::Value {
+        &self.raw_default_settings
+    }
+
+    /// Get the settings from the local settings file in the given directory as a raw JSON value.
+    pub fn raw_local_settings(&self, root_id: usize, path: &Path) -> Option<&serde_json::Value> {
+        self.raw_local_settings.get(&(root_id, Arc::from(path)))
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn test(cx: &mut AppContext) -> Self {
         let mut this = Self::default();
@@ -331,7 +382,8 @@ impl SettingsStore {
         ));
     }
 
-    fn json_tab_size(&self) -> usize {
+    /// The indentation used when updating JSON files.
+    pub fn json_tab_size(&self) -> usize {
         const DEFAULT_JSON_TAB_SIZE: usize = 2;
 
         if let Some((setting_type_id, callback)) = &self.tab_size_callback {
@@ -400,10 +452,56 @@ impl SettingsStore {
     /// Add or remove a set of local settings via a JSON string.
     pub fn clear_local_settings(&mut self, root_id: usize, cx: &mut AppContext) -> Result<()> {
         self.raw_local_settings.retain(|k, _| k.0 != root_id);
+        self.editorconfigs.retain(|k, _| k.0 != root_id);
         self.recompute_values(Some((root_id, "".as_ref())), cx)?;
         Ok(())
     }
 
+    /// Add or remove the `.editorconfig` file in the given directory.
+    pub fn set_local_editorconfig(
+        &mut self,
+        root_id: usize,
+        directory: Arc<Path>,
+        editorconfig_content: Option<&str>,
+    ) {
+        if let Some(content) = editorconfig_content {
+            self.editorconfigs
+                .insert((root_id, directory), Editorconfig::parse(content));
+        } else {
+            self.editorconfigs.remove(&(root_id, directory));
+        }
+    }
+
+    /// Returns the EditorConfig properties for the file at the given path,
+    /// from the `.editorconfig` files in its directory and the ones above it,
+    /// up to the closest one marked as the root.
+    pub fn editorconfig_properties(
+        &self,
+        root_id: usize,
+        path: &Path,
+    ) -> Option<EditorconfigProperties> {
+        let editorconfigs = self
+            .editorconfigs
+            .range((root_id, Path::new("").into())..(root_id + 1, Path::new("").into()))
+            .filter(|((_, directory), _)| path.starts_with(directory))
+            .collect::<Vec<_>>();
+        let start = editorconfigs
+            .iter()
+            .rposition(|(_, editorconfig)| editorconfig.root)
+            .unwrap_or(0);
+
+        let mut result = None;
+        for ((_, directory), editorconfig) in &editorconfigs[start..] {
+            let relative_path = path.strip_prefix(directory).unwrap();
+            if let Some(properties) = editorconfig.properties_for_path(relative_path) {
+                result
+                    .get_or_insert_with(EditorconfigProperties::default)
+                    .merge(&properties);
+            }
+        }
+        result
+    }
+
     pub fn local_settings(&self, root_id: usize) -> impl '_ + Iterator<Item = (Arc<Path>, String)> {
         self.raw_local_settings
             .range((root_id, Path::new("").into())..(root_id + 1, Path::new("").into()))
@@ -417,7 +515,7 @@ impl SettingsStore {
     ) -> serde_json::Value {
         use schemars::{
             gen::SchemaSettings,
-            schema::{Schema, SchemaObject},
+            schema::{InstanceType, Metadata, ObjectValidation, Schema, SchemaObject},
         };
 
         let settings = SchemaSettings::draft07().with(|settings| {
@@ -451,6 +549,39 @@ impl SettingsStore {
             merge_schema(target_schema, setting_schema.schema);
         }
 
+        let root_properties = &mut combined_schema.schema.object().properties;
+        root_properties.insert(
+            PROFILES_KEY.to_string(),
+            SchemaObject {
+                metadata: Some(Box::new(Metadata {
+                    description: Some(
+                        "Named sets of settings that override the ones above while active."
+                            .to_string(),
+                    ),
+                    ..Default::default()
+                })),
+                instance_type: Some(InstanceType::Object.into()),
+                object: Some(Box::new(ObjectValidation {
+                    additional_properties: Some(Box::new(Schema::new_ref("#".to_string()))),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            }
+            .into(),
+        );
+        root_properties.insert(
+            ACTIVE_PROFILE_KEY.to_string(),
+            SchemaObject {
+                metadata: Some(Box::new(Metadata {
+                    description: Some("The name of the active settings profile.".to_string()),
+                    ..Default::default()
+                })),
+                instance_type: Some(InstanceType::String.into()),
+                ..Default::default()
+            }
+            .into(),
+        );
+
         fn merge_schema(target: &mut SchemaObject, source: SchemaObject) {
             if let Some(source) = source.object {
                 let target_properties = &mut target.object().properties;
@@ -495,6 +626,7 @@ impl SettingsStore {
         // Reload the global and local values for every setting.
         let mut user_settings_stack = Vec::<DeserializedSetting>::new();
         let mut paths_stack = Vec::<Option<(usize, &Path)>>::new();
+        let raw_user_settings = user_settings_with_profile(&self.raw_user_settings);
         for setting_value in self.setting_values.values_mut() {
             let default_settings = setting_value.deserialize_setting(&self.raw_default_settings)?;
 
@@ -502,7 +634,7 @@ impl SettingsStore {
             paths_stack.clear();
 
             if let Some(user_settings) = setting_value
-                .deserialize_setting(&self.raw_user_settings)
+                .deserialize_setting(&raw_user_settings)
                 .log_err()
             {
                 user_settings_stack.push(user_settings);
@@ -704,7 +836,87 @@ fn update_value_in_json_text<'a>(
     }
 }
 
-fn replace_value_in_json_text(
+fn active_profile(raw_user_settings: &serde_json::Value) -> Option<&str> {
+    raw_user_settings.get(ACTIVE_PROFILE_KEY)?.as_str()
+}
+
+/// Applies the overrides of the active settings profile, if there is one, to the user's settings.
+fn user_settings_with_profile(raw_user_settings: &serde_json::Value) -> Cow<serde_json::Value> {
+    let Some(profile) = active_profile(raw_user_settings)
+        .and_then(|name| raw_user_settings.get(PROFILES_KEY)?.get(name))
+    else {
+        return Cow::Borrowed(raw_user_settings);
+    };
+    let mut settings = raw_user_settings.clone();
+    merge_non_null_json_value_into(profile.clone(), &mut settings);
+    Cow::Owned(settings)
+}
+
+/// Sets the value at `key_path` in the text of a settings file, or removes it when `value`
+/// is `None`, preserving the formatting and comments of the rest of the file.
+pub fn update_setting_in_json_text(
+    text: &str,
+    key_path: &[&str],
+    value: Option<&serde_json::Value>,
+    tab_size: usize,
+) -> String {
+    let mut new_text = text.to_string();
+    if let Some(value) = value {
+        let (range, replacement) = replace_value_in_json_text(text, key_path, tab_size, value);
+        new_text.replace_range(range, &replacement);
+    } else if let Some(range) = json_pair_range(text, key_path) {
+        new_text.replace_range(range, "");
+    }
+    new_text
+}
+
+/// Returns the range of the key and value at `key_path`, along with the comma separating
+/// them from a neighbouring pair.
+fn json_pair_range(text: &str, key_path: &[&str]) -> Option<Range<usize>> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&tree_sitter_json::language()).unwrap();
+    let syntax_tree = parser.parse(text, None)?;
+    let root = syntax_tree.root_node();
+    let mut cursor = root.walk();
+    let mut object = root
+        .named_children(&mut cursor)
+        .find(|node| node.kind() == "object")?;
+
+    let mut pair = None;
+    for key in key_path {
+        if let Some(pair) = pair {
+            object = pair.child_by_field_name("value")?;
+            if object.kind() != "object" {
+                return None;
+            }
+        }
+        let quoted_key = format!("\"{key}\"");
+        let mut cursor = object.walk();
+        let found = object.named_children(&mut cursor).find(|node| {
+            node.kind() == "pair"
+                && node
+                    .child_by_field_name("key")
+                    .and_then(|key| text.get(key.byte_range()))
+                    == Some(quoted_key.as_str())
+        })?;
+        pair = Some(found);
+    }
+
+    let pair = pair?;
+    let mut range = pair.byte_range();
+    if let Some(comma) = pair.next_sibling().filter(|node| node.kind() == ",") {
+        range.end = comma
+            .next_sibling()
+            .map_or(comma.end_byte(), |node| node.start_byte());
+    } else if let Some(comma) = pair.prev_sibling().filter(|node| node.kind() == ",") {
+        range.start = comma.start_byte();
+    } else {
+        range = object.start_byte() + 1..object.end_byte() - 1;
+    }
+    Some(range)
+}
+
+pub(crate) fn replace_value_in_json_text(
     text: &str,
     key_path: &[&str],
     tab_size: usize,
@@ -843,7 +1055,11 @@ fn replace_value_in_json_text(
     }
 }
 
-fn to_pretty_json(value: &impl Serialize, indent_size: usize, indent_prefix_len: usize) -> String {
+pub(crate) fn to_pretty_json(
+    value: &impl Serialize,
+    indent_size: usize,
+    indent_prefix_len: usize,
+) -> String {
     const SPACES: [u8; 32] = [b' '; 32];
 
     debug_assert!(indent_size <= SPACES.len());
@@ -1160,6 +1376,179 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_settings_profiles(cx: &mut AppContext) {
+        let mut store = SettingsStore::default();
+        store.register_setting::<UserSettings>(cx);
+        store.register_setting::<TurboSetting>(cx);
+        store
+            .set_default_settings(
+                r#"{
+                    "turbo": false,
+                    "user": {
+                        "name": "John Doe",
+                        "age": 30,
+                        "staff": false
+                    }
+                }"#,
+                cx,
+            )
+            .unwrap();
+        store
+            .set_user_settings(
+                r#"{
+                    "user": { "age": 31 },
+                    "profiles": {
+                        "fast": { "turbo": true },
+                        "staff": { "user": { "staff": true } }
+                    },
+                    "active_profile": "fast"
+                }"#,
+                cx,
+            )
+            .unwrap();
+
+        assert_eq!(store.profile_names(), ["fast", "staff"]);
+        assert_eq!(store.active_profile(), Some("fast"));
+        assert_eq!(store.get::<TurboSetting>(None), &TurboSetting(true));
+
+        // A profile's overrides are merged into the user's settings.
+        store.set_active_profile(Some("staff"), cx).unwrap();
+        assert_eq!(store.get::<TurboSetting>(None), &TurboSetting(false));
+        assert_eq!(
+            store.get::<UserSettings>(None),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 31,
+                staff: true
+            }
+        );
+
+        store.set_active_profile(None, cx).unwrap();
+        assert_eq!(store.active_profile(), None);
+        assert_eq!(
+            store.get::<UserSettings>(None),
+            &UserSettings {
+                name: "John Doe".to_string(),
+                age: 31,
+                staff: false
+            }
+        );
+
+        // An unknown profile has no overrides.
+        store.set_active_profile(Some("slow"), cx).unwrap();
+        assert_eq!(store.get::<TurboSetting>(None), &TurboSetting(false));
+    }
+
+    #[test]
+    fn test_editorconfig_properties() {
+        use crate::editorconfig::IndentStyle;
+
+        let mut store = SettingsStore::default();
+        store.set_local_editorconfig(
+            1,
+            Path::new("").into(),
+            Some("[*]\nindent_style = tab\ninsert_final_newline = true\n"),
+        );
+        store.set_local_editorconfig(
+            1,
+            Path::new("a").into(),
+            Some("[*.rs]\nindent_style = space\n"),
+        );
+        store.set_local_editorconfig(
+            1,
+            Path::new("b").into(),
+            Some("root = true\n[*]\ntrim_trailing_whitespace = true\n"),
+        );
+
+        // Files closer to the file apply over the ones above it.
+        let properties = store
+            .editorconfig_properties(1, Path::new("a/c/d.rs"))
+            .unwrap();
+        assert_eq!(properties.indent_style, Some(IndentStyle::Space));
+        assert_eq!(properties.insert_final_newline, Some(true));
+        let properties = store
+            .editorconfig_properties(1, Path::new("a/d.py"))
+            .unwrap();
+        assert_eq!(properties.indent_style, Some(IndentStyle::Tab));
+
+        // Files above a root one are ignored.
+        let properties = store
+            .editorconfig_properties(1, Path::new("b/e.rs"))
+            .unwrap();
+        assert_eq!(properties.indent_style, None);
+        assert_eq!(properties.trim_trailing_whitespace, Some(true));
+
+        assert_eq!(store.editorconfig_properties(2, Path::new("a/d.rs")), None);
+        store.set_local_editorconfig(1, Path::new("").into(), None);
+        let properties = store.editorconfig_properties(1, Path::new("a/d.py"));
+        assert_eq!(properties, None);
+    }
+
+    #[test]
+    fn test_update_setting_in_json_text() {
+        let text = r#"{
+            // Settings for the user
+            "user": {
+                "name": "Max",
+                "age": 31
+            },
+            "turbo": true
+        }"#
+        .unindent();
+
+        pretty_assertions::assert_eq!(
+            update_setting_in_json_text(&text, &["user", "age"], Some(&serde_json::json!(32)), 4),
+            r#"{
+                // Settings for the user
+                "user": {
+                    "name": "Max",
+                    "age": 32
+                },
+                "turbo": true
+            }"#
+            .unindent()
+        );
+
+        // Removing a value also removes the comma that separated it from its neighbour.
+        pretty_assertions::assert_eq!(
+            update_setting_in_json_text(&text, &["user", "age"], None, 4),
+            r#"{
+                // Settings for the user
+                "user": {
+                    "name": "Max"
+                },
+                "turbo": true
+            }"#
+            .unindent()
+        );
+        pretty_assertions::assert_eq!(
+            update_setting_in_json_text(&text, &["user"], None, 4),
+            r#"{
+                // Settings for the user
+                "turbo": true
+            }"#
+            .unindent()
+        );
+        pretty_assertions::assert_eq!(
+            update_setting_in_json_text(&text, &["turbo"], None, 4),
+            r#"{
+                // Settings for the user
+                "user": {
+                    "name": "Max",
+                    "age": 31
+                }
+            }"#
+            .unindent()
+        );
+
+        // Removing a value that isn't there leaves the text as it was.
+        pretty_assertions::assert_eq!(
+            update_setting_in_json_text(&text, &["user", "staff"], None, 4),
+            text
+        );
+    }
+
     fn check_settings_update<T: Settings>(
         store: &mut SettingsStore,
         old_json: String,
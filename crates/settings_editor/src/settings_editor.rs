@@ -0,0 +1,883 @@
+use std::{path::Path, sync::Arc};
+
+use editor::{Editor, EditorEvent};
+use fs::Fs;
+use gpui::{
+    actions, div, uniform_list, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, Model, ParentElement, Render, SharedString, Styled,
+    Subscription, Task, UniformListScrollHandle, View, ViewContext, VisualContext, WindowContext,
+};
+use project::Project;
+use serde_json::Value;
+use settings::{
+    parse_json_with_comments, update_setting_in_json_text, SettingsJsonSchemaParams, SettingsStore,
+};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::paths::LOCAL_SETTINGS_RELATIVE_PATH;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace, WorkspaceId,
+};
+
+actions!(settings_editor, [Deploy, ResetSetting, ToggleScope]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &Deploy, cx| {
+            if let Some(existing) = workspace.item_of_type::<SettingsEditor>(cx) {
+                workspace.activate_item(&existing, cx);
+            } else {
+                let fs = workspace.app_state().fs.clone();
+                let project = workspace.project().clone();
+                let settings_editor = cx.new_view(|cx| SettingsEditor::new(fs, project, cx));
+                workspace.add_item(Box::new(settings_editor), cx);
+            }
+        });
+    })
+    .detach();
+}
+
+/// The kind of value a setting takes, which determines how it is edited.
+#[derive(Clone, Debug, PartialEq)]
+enum SettingKind {
+    Boolean,
+    Integer,
+    Number,
+    String,
+    /// One of a fixed set of values.
+    Enum(Vec<Value>),
+    /// Anything else, like maps and lists, which are edited as JSON.
+    Json,
+}
+
+/// A row of the settings editor: a setting that isn't itself an object of other settings.
+#[derive(Clone, Debug, PartialEq)]
+struct SettingEntry {
+    key_path: Vec<String>,
+    description: Option<SharedString>,
+    kind: SettingKind,
+}
+
+impl SettingEntry {
+    fn key(&self) -> String {
+        self.key_path.join(".")
+    }
+
+    fn matches_query(&self, query: &str) -> bool {
+        let key = self.key().to_lowercase();
+        let description = self
+            .description
+            .as_ref()
+            .map(|description| description.to_lowercase())
+            .unwrap_or_default();
+        query
+            .split_whitespace()
+            .all(|term| key.contains(term) || description.contains(term))
+    }
+
+    /// Parses the text typed into the settings editor as a value for this setting.
+    fn parse_value(&self, text: &str) -> Result<Value, String> {
+        let text = text.trim();
+        match &self.kind {
+            SettingKind::Boolean => text
+                .parse::<bool>()
+                .map(Value::from)
+                .map_err(|_| format!("{} must be true or false", self.key())),
+            SettingKind::Integer => text
+                .parse::<i64>()
+                .map(Value::from)
+                .map_err(|_| format!("{} must be a whole number", self.key())),
+            SettingKind::Number => text
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number)
+                .ok_or_else(|| format!("{} must be a number", self.key())),
+            SettingKind::String => Ok(Value::String(text.to_string())),
+            SettingKind::Enum(values) => {
+                let value = parse_json_with_comments::<Value>(text)
+                    .unwrap_or_else(|_| Value::String(text.to_string()));
+                if values.contains(&value) {
+                    Ok(value)
+                } else {
+                    Err(format!(
+                        "{} must be one of {}",
+                        self.key(),
+                        values.iter().map(value_text).collect::<Vec<_>>().join(", ")
+                    ))
+                }
+            }
+            SettingKind::Json => parse_json_with_comments::<Value>(text)
+                .map_err(|error| format!("{} must be valid JSON: {error}", self.key())),
+        }
+    }
+
+    /// The text a value is edited as. Strings are edited without their quotes.
+    fn editable_text(&self, value: Option<&Value>) -> String {
+        match value {
+            Some(Value::String(text)) if self.kind == SettingKind::String => text.clone(),
+            Some(value) => value_text(value),
+            None => String::new(),
+        }
+    }
+}
+
+fn value_text(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn value_at_path<'a>(value: &'a Value, key_path: &[String]) -> Option<&'a Value> {
+    key_path.iter().try_fold(value, |value, key| value.get(key))
+}
+
+/// Lists the settings described by the combined JSON schema of every registered setting,
+/// ordered by key.
+fn setting_entries(schema: &Value) -> Vec<SettingEntry> {
+    let definitions = schema.get("definitions").and_then(Value::as_object);
+    let mut entries = Vec::new();
+    collect_setting_entries(schema, definitions, &mut Vec::new(), &mut entries);
+    entries.sort_by(|a, b| a.key_path.cmp(&b.key_path));
+    entries
+}
+
+fn collect_setting_entries(
+    schema: &Value,
+    definitions: Option<&serde_json::Map<String, Value>>,
+    key_path: &mut Vec<String>,
+    entries: &mut Vec<SettingEntry>,
+) {
+    let resolved = resolve_schema(schema, definitions);
+    if let Some(properties) = resolved
+        .get("properties")
+        .and_then(Value::as_object)
+        .filter(|properties| !properties.is_empty())
+    {
+        for (key, property) in properties {
+            key_path.push(key.clone());
+            collect_setting_entries(property, definitions, key_path, entries);
+            key_path.pop();
+        }
+    } else if !key_path.is_empty() {
+        let description = schema
+            .get("description")
+            .or_else(|| resolved.get("description"))
+            .and_then(Value::as_str)
+            .map(|description| SharedString::from(description.to_string()));
+        entries.push(SettingEntry {
+            key_path: key_path.clone(),
+            description,
+            kind: setting_kind(resolved, definitions),
+        });
+    }
+}
+
+/// Follows references to definitions, including those wrapped in an `allOf` to give them
+/// a description.
+fn resolve_schema<'a>(
+    mut schema: &'a Value,
+    definitions: Option<&'a serde_json::Map<String, Value>>,
+) -> &'a Value {
+    loop {
+        if let Some(definition) = schema
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix("#/definitions/"))
+            .and_then(|name| definitions?.get(name))
+        {
+            schema = definition;
+        } else if let Some([inner]) = schema
+            .get("allOf")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+        {
+            schema = inner;
+        } else {
+            return schema;
+        }
+    }
+}
+
+fn setting_kind(
+    schema: &Value,
+    definitions: Option<&serde_json::Map<String, Value>>,
+) -> SettingKind {
+    if let Some(values) = enum_values(schema, definitions) {
+        return SettingKind::Enum(values);
+    }
+    match schema.get("type").and_then(Value::as_str) {
+        Some("boolean") => SettingKind::Boolean,
+        Some("integer") => SettingKind::Integer,
+        Some("number") => SettingKind::Number,
+        Some("string") => SettingKind::String,
+        _ => SettingKind::Json,
+    }
+}
+
+/// Returns the values an enum can take, whether they're listed directly or as alternatives
+/// that each allow a single value, as serde's documented enums are.
+fn enum_values(
+    schema: &Value,
+    definitions: Option<&serde_json::Map<String, Value>>,
+) -> Option<Vec<Value>> {
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return Some(values.clone()).filter(|values| !values.is_empty());
+    }
+    let variants = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))?
+        .as_array()?;
+    let mut values = Vec::new();
+    for variant in variants {
+        let variant = resolve_schema(variant, definitions);
+        if let Some(value) = variant.get("const") {
+            values.push(value.clone());
+        } else {
+            values.extend(variant.get("enum")?.as_array()?.iter().cloned());
+        }
+    }
+    Some(values).filter(|values| !values.is_empty())
+}
+
+/// The settings file that the settings editor writes to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SettingsScope {
+    User,
+    Project,
+}
+
+/// A value being typed for an entry, until it is confirmed with enter or discarded
+/// with escape.
+struct Edit {
+    entry_ix: usize,
+    editor: View<Editor>,
+}
+
+/// Lists every registered setting with its default, user and project values, and writes
+/// changes to them into the corresponding settings file.
+pub struct SettingsEditor {
+    focus_handle: FocusHandle,
+    fs: Arc<dyn Fs>,
+    project: Model<Project>,
+    filter_editor: View<Editor>,
+    entries: Vec<SettingEntry>,
+    matches: Vec<usize>,
+    selected_index: usize,
+    scope: SettingsScope,
+    edit: Option<Edit>,
+    error: Option<SharedString>,
+    scroll_handle: UniformListScrollHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl SettingsEditor {
+    fn new(fs: Arc<dyn Fs>, project: Model<Project>, cx: &mut ViewContext<Self>) -> Self {
+        let filter_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter settings...", cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe(&filter_editor, |this, _, event, cx| {
+                if let EditorEvent::BufferEdited = event {
+                    this.update_matches(cx);
+                }
+            }),
+            cx.observe_global::<SettingsStore>(|_, cx| cx.notify()),
+        ];
+
+        let font_names = cx.text_system().all_font_names();
+        let schema = cx.global::<SettingsStore>().json_schema(
+            &SettingsJsonSchemaParams {
+                staff_mode: false,
+                language_names: &[],
+                font_names: &font_names,
+            },
+            cx,
+        );
+
+        let mut this = Self {
+            focus_handle: cx.focus_handle(),
+            fs,
+            project,
+            filter_editor,
+            entries: setting_entries(&schema),
+            matches: Vec::new(),
+            selected_index: 0,
+            scope: SettingsScope::User,
+            edit: None,
+            error: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            _subscriptions: subscriptions,
+        };
+        this.update_matches(cx);
+        this
+    }
+
+    fn update_matches(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.filter_editor.read(cx).text(cx).to_lowercase();
+        self.matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| entry.matches_query(&query))
+            .map(|(ix, _)| ix)
+            .collect();
+        self.selected_index = self
+            .selected_index
+            .min(self.matches.len().saturating_sub(1));
+        cx.notify();
+    }
+
+    /// The root of the first local worktree in the project, whose `.zed/settings.json` holds
+    /// the project's settings, along with the id its settings are stored under.
+    fn project_settings_root(&self, cx: &AppContext) -> Option<(usize, Arc<Path>)> {
+        let project = self.project.read(cx);
+        project.visible_worktrees(cx).find_map(|worktree| {
+            let root_id = worktree.entity_id().as_u64() as usize;
+            let worktree = worktree.read(cx).as_local()?;
+            Some((root_id, worktree.abs_path().clone()))
+        })
+    }
+
+    /// Returns a setting's value in the default, user and project settings files.
+    fn values<'a>(&self, entry: &SettingEntry, cx: &'a AppContext) -> [Option<&'a Value>; 3] {
+        let store = cx.global::<SettingsStore>();
+        let project_settings = self
+            .project_settings_root(cx)
+            .and_then(|(root_id, _)| store.raw_local_settings(root_id, Path::new("")));
+        [
+            value_at_path(store.raw_default_settings(), &entry.key_path),
+            value_at_path(store.raw_user_settings(), &entry.key_path),
+            project_settings.and_then(|settings| value_at_path(settings, &entry.key_path)),
+        ]
+    }
+
+    /// Returns the value a setting has in the current scope, falling back to the value
+    /// it inherits from the less specific scopes.
+    fn effective_value(&self, entry: &SettingEntry, cx: &AppContext) -> Option<Value> {
+        let [default, user, project] = self.values(entry, cx);
+        let value = match self.scope {
+            SettingsScope::User => user.or(default),
+            SettingsScope::Project => project.or(user).or(default),
+        };
+        value.cloned()
+    }
+
+    fn select_index(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_index = ix;
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, cx: &mut ViewContext<Self>) {
+        if !self.matches.is_empty() {
+            self.select_index((self.selected_index + 1) % self.matches.len(), cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &menu::SelectPrev, cx: &mut ViewContext<Self>) {
+        if !self.matches.is_empty() {
+            let count = self.matches.len();
+            self.select_index((self.selected_index + count - 1) % count, cx);
+        }
+    }
+
+    fn toggle_scope(&mut self, _: &ToggleScope, cx: &mut ViewContext<Self>) {
+        let scope = match self.scope {
+            SettingsScope::User => SettingsScope::Project,
+            SettingsScope::Project => SettingsScope::User,
+        };
+        self.set_scope(scope, cx);
+    }
+
+    fn set_scope(&mut self, scope: SettingsScope, cx: &mut ViewContext<Self>) {
+        if scope == SettingsScope::Project && self.project_settings_root(cx).is_none() {
+            self.error = Some("Open a local folder to edit its project settings".into());
+        } else {
+            self.scope = scope;
+            self.error = None;
+        }
+        self.stop_editing(cx);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if self.edit.is_some() {
+            self.save_edit(cx);
+        } else if let Some(&entry_ix) = self.matches.get(self.selected_index) {
+            self.activate_entry(entry_ix, cx);
+        }
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        if self.edit.is_some() {
+            self.stop_editing(cx);
+        } else if self.error.take().is_some() {
+            cx.notify();
+        } else {
+            cx.propagate();
+        }
+    }
+
+    /// Toggles booleans and steps through the values of enums, and starts typing a new
+    /// value for any other kind of setting.
+    fn activate_entry(&mut self, entry_ix: usize, cx: &mut ViewContext<Self>) {
+        let entry = self.entries[entry_ix].clone();
+        let value = self.effective_value(&entry, cx);
+        match &entry.kind {
+            SettingKind::Boolean => {
+                let enabled = value.as_ref().and_then(Value::as_bool).unwrap_or(false);
+                self.write_value(entry_ix, Some(Value::Bool(!enabled)), cx);
+            }
+            SettingKind::Enum(values) => {
+                let next_ix = value
+                    .and_then(|value| values.iter().position(|candidate| *candidate == value))
+                    .map_or(0, |ix| (ix + 1) % values.len());
+                self.write_value(entry_ix, Some(values[next_ix].clone()), cx);
+            }
+            _ => self.start_editing(entry_ix, cx),
+        }
+    }
+
+    fn start_editing(&mut self, entry_ix: usize, cx: &mut ViewContext<Self>) {
+        let entry = &self.entries[entry_ix];
+        let text = entry.editable_text(self.effective_value(entry, cx).as_ref());
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_text(text, cx);
+            editor.select_all(&editor::actions::SelectAll, cx);
+            editor
+        });
+        cx.focus_view(&editor);
+        self.error = None;
+        self.edit = Some(Edit { entry_ix, editor });
+        cx.notify();
+    }
+
+    fn stop_editing(&mut self, cx: &mut ViewContext<Self>) -> Option<Edit> {
+        let edit = self.edit.take()?;
+        if edit.editor.focus_handle(cx).is_focused(cx) {
+            self.filter_editor.focus_handle(cx).focus(cx);
+        }
+        cx.notify();
+        Some(edit)
+    }
+
+    fn save_edit(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(edit) = self.edit.as_ref() else {
+            return;
+        };
+        let text = edit.editor.read(cx).text(cx);
+        match self.entries[edit.entry_ix].parse_value(&text) {
+            Ok(value) => {
+                if let Some(edit) = self.stop_editing(cx) {
+                    self.write_value(edit.entry_ix, Some(value), cx);
+                }
+            }
+            Err(error) => {
+                self.error = Some(error.into());
+                cx.notify();
+            }
+        }
+    }
+
+    fn reset_setting(&mut self, _: &ResetSetting, cx: &mut ViewContext<Self>) {
+        if let Some(&entry_ix) = self.matches.get(self.selected_index) {
+            self.stop_editing(cx);
+            self.write_value(entry_ix, None, cx);
+        }
+    }
+
+    /// Sets a setting in the settings file for the current scope, or removes it from that
+    /// file when `value` is `None`.
+    fn write_value(&mut self, entry_ix: usize, value: Option<Value>, cx: &mut ViewContext<Self>) {
+        let key_path = self.entries[entry_ix].key_path.clone();
+        let tab_size = cx.global::<SettingsStore>().json_tab_size();
+        let update = move |text: String| -> anyhow::Result<String> {
+            let key_path = key_path.iter().map(String::as_str).collect::<Vec<_>>();
+            Ok(update_setting_in_json_text(
+                &text,
+                &key_path,
+                value.as_ref(),
+                tab_size,
+            ))
+        };
+
+        let (file_name, task): (&str, Task<anyhow::Result<()>>) = match self.scope {
+            SettingsScope::User => (
+                "settings.json",
+                settings::update_user_settings_file(self.fs.clone(), cx, update),
+            ),
+            SettingsScope::Project => {
+                let Some((_, root)) = self.project_settings_root(cx) else {
+                    return;
+                };
+                let fs = self.fs.clone();
+                let path = root.join(&*LOCAL_SETTINGS_RELATIVE_PATH);
+                let task = cx.background_executor().spawn(async move {
+                    let old_text = if fs.is_file(&path).await {
+                        fs.load(&path).await?
+                    } else {
+                        String::new()
+                    };
+                    let new_text = update(old_text)?;
+                    if let Some(directory) = path.parent() {
+                        fs.create_dir(directory).await?;
+                    }
+                    fs.atomic_write(path, new_text).await
+                });
+                (".zed/settings.json", task)
+            }
+        };
+
+        cx.spawn(|this, mut cx| async move {
+            if let Err(error) = task.await {
+                this.update(&mut cx, |this, cx| {
+                    this.error = Some(format!("Failed to update {file_name}: {error}").into());
+                    cx.notify();
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_value(
+        &self,
+        ix: usize,
+        scope: Option<SettingsScope>,
+        value: Option<&Value>,
+        cx: &mut ViewContext<Self>,
+    ) -> AnyElement {
+        let entry_ix = self.matches[ix];
+        let is_current_scope = scope.is_some() && scope == Some(self.scope);
+        if let Some(edit) = self
+            .edit
+            .as_ref()
+            .filter(|edit| is_current_scope && edit.entry_ix == entry_ix)
+        {
+            return div().w_1_6().child(edit.editor.clone()).into_any_element();
+        }
+
+        let label = Label::new(value.map(value_text).unwrap_or_default()).color(
+            if is_current_scope && ix == self.selected_index {
+                Color::Accent
+            } else if scope.is_some() {
+                Color::Default
+            } else {
+                Color::Muted
+            },
+        );
+        let cell = div()
+            .id((
+                "setting-value",
+                ix * 3 + scope.map_or(0, |scope| scope as usize + 1),
+            ))
+            .w_1_6()
+            .overflow_hidden()
+            .whitespace_nowrap()
+            .child(label);
+        match scope {
+            Some(scope) => cell
+                .on_click(cx.listener(move |this, _, cx| {
+                    this.select_index(ix, cx);
+                    if this.scope != scope {
+                        this.set_scope(scope, cx);
+                    }
+                    if this.scope == scope {
+                        this.activate_entry(entry_ix, cx);
+                    }
+                }))
+                .into_any_element(),
+            None => cell.into_any_element(),
+        }
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut ViewContext<Self>) -> ListItem {
+        let entry = &self.entries[self.matches[ix]];
+        let [default, user, project] = self.values(entry, cx);
+        let (default, user, project) = (default.cloned(), user.cloned(), project.cloned());
+
+        ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .selected(ix == self.selected_index)
+            .on_click(cx.listener(move |this, _, cx| this.select_index(ix, cx)))
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_4()
+                    .child(
+                        v_flex()
+                            .flex_1()
+                            .overflow_hidden()
+                            .child(Label::new(entry.key()))
+                            .child(
+                                div().whitespace_nowrap().child(
+                                    Label::new(entry.description.clone().unwrap_or_default())
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                ),
+                            ),
+                    )
+                    .child(self.render_value(ix, None, default.as_ref(), cx))
+                    .child(self.render_value(ix, Some(SettingsScope::User), user.as_ref(), cx))
+                    .child(self.render_value(
+                        ix,
+                        Some(SettingsScope::Project),
+                        project.as_ref(),
+                        cx,
+                    )),
+            )
+    }
+
+    fn render_header(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let scope_button = |id: &'static str, label: &'static str, scope: SettingsScope| {
+            Button::new(id, label)
+                .selected(self.scope == scope)
+                .on_click(cx.listener(move |this, _, cx| this.set_scope(scope, cx)))
+        };
+        h_flex()
+            .px_4()
+            .py_1()
+            .gap_4()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                div()
+                    .flex_1()
+                    .child(Label::new("Setting").color(Color::Muted)),
+            )
+            .child(
+                div()
+                    .w_1_6()
+                    .child(Label::new("Default").color(Color::Muted)),
+            )
+            .child(
+                div()
+                    .w_1_6()
+                    .child(scope_button("user-scope", "User", SettingsScope::User)),
+            )
+            .child(div().w_1_6().child(scope_button(
+                "project-scope",
+                "Project",
+                SettingsScope::Project,
+            )))
+    }
+}
+
+impl Render for SettingsEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("SettingsEditor")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::reset_setting))
+            .on_action(cx.listener(Self::toggle_scope))
+            .child(
+                h_flex()
+                    .h_9()
+                    .px_4()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border)
+                    .child(self.filter_editor.clone()),
+            )
+            .child(self.render_header(cx))
+            .children(self.error.clone().map(|error| {
+                div()
+                    .px_4()
+                    .py_2()
+                    .child(Label::new(error).color(Color::Error))
+            }))
+            .child(
+                uniform_list(
+                    cx.view().clone(),
+                    "setting-entries",
+                    self.matches.len(),
+                    |this, range, cx| range.map(|ix| this.render_entry(ix, cx)).collect(),
+                )
+                .track_scroll(self.scroll_handle.clone())
+                .flex_grow(),
+            )
+    }
+}
+
+impl FocusableView for SettingsEditor {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        match &self.edit {
+            Some(edit) => edit.editor.focus_handle(cx),
+            None => self.filter_editor.focus_handle(cx),
+        }
+    }
+}
+
+impl EventEmitter<ItemEvent> for SettingsEditor {}
+
+impl Item for SettingsEditor {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        Label::new("Settings")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("settings editor")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        let fs = self.fs.clone();
+        let project = self.project.clone();
+        Some(cx.new_view(|cx| SettingsEditor::new(fs, project, cx)))
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_setting_entries() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "vim_mode": {
+                    "description": "Whether to enable vim modes and key bindings.",
+                    "type": "boolean"
+                },
+                "tab_size": { "type": "integer" },
+                "buffer_font_size": { "type": "number" },
+                "theme": { "$ref": "#/definitions/ThemeName" },
+                "autosave": {
+                    "description": "When to automatically save edited buffers.",
+                    "allOf": [{ "$ref": "#/definitions/AutosaveSetting" }]
+                },
+                "tabs": {
+                    "type": "object",
+                    "properties": {
+                        "close_position": { "$ref": "#/definitions/ClosePosition" }
+                    }
+                },
+                "file_types": {
+                    "type": "object",
+                    "additionalProperties": { "type": "array" }
+                }
+            },
+            "definitions": {
+                "ThemeName": { "type": "string", "enum": ["One Dark", "One Light"] },
+                "AutosaveSetting": {
+                    "oneOf": [
+                        { "type": "string", "enum": ["off"] },
+                        {
+                            "type": "object",
+                            "properties": { "after_delay": { "type": "object" } }
+                        }
+                    ]
+                },
+                "ClosePosition": {
+                    "oneOf": [
+                        { "description": "Left", "type": "string", "enum": ["left"] },
+                        { "description": "Right", "type": "string", "enum": ["right"] }
+                    ]
+                }
+            }
+        });
+
+        let entry = |key_path: &[&str], description: Option<&'static str>, kind| SettingEntry {
+            key_path: key_path.iter().map(|key| key.to_string()).collect(),
+            description: description.map(Into::into),
+            kind,
+        };
+        assert_eq!(
+            setting_entries(&schema),
+            [
+                entry(
+                    &["autosave"],
+                    Some("When to automatically save edited buffers."),
+                    SettingKind::Json
+                ),
+                entry(&["buffer_font_size"], None, SettingKind::Number),
+                entry(&["file_types"], None, SettingKind::Json),
+                entry(&["tab_size"], None, SettingKind::Integer),
+                entry(
+                    &["tabs", "close_position"],
+                    None,
+                    SettingKind::Enum(vec![json!("left"), json!("right")])
+                ),
+                entry(
+                    &["theme"],
+                    None,
+                    SettingKind::Enum(vec![json!("One Dark"), json!("One Light")])
+                ),
+                entry(
+                    &["vim_mode"],
+                    Some("Whether to enable vim modes and key bindings."),
+                    SettingKind::Boolean
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_value() {
+        let entry = |kind| SettingEntry {
+            key_path: vec!["setting".into()],
+            description: None,
+            kind,
+        };
+
+        assert_eq!(
+            entry(SettingKind::Boolean).parse_value(" true "),
+            Ok(json!(true))
+        );
+        assert!(entry(SettingKind::Boolean).parse_value("yes").is_err());
+        assert_eq!(entry(SettingKind::Integer).parse_value("4"), Ok(json!(4)));
+        assert!(entry(SettingKind::Integer).parse_value("4.5").is_err());
+        assert_eq!(
+            entry(SettingKind::Number).parse_value("4.5"),
+            Ok(json!(4.5))
+        );
+        assert_eq!(
+            entry(SettingKind::String).parse_value("Zed Mono"),
+            Ok(json!("Zed Mono"))
+        );
+
+        // Enum values can be typed with or without quotes, but must be one of the options.
+        let close_position = entry(SettingKind::Enum(vec![json!("left"), json!("right")]));
+        assert_eq!(close_position.parse_value("left"), Ok(json!("left")));
+        assert_eq!(close_position.parse_value("\"right\""), Ok(json!("right")));
+        assert_eq!(
+            close_position.parse_value("middle"),
+            Err("setting must be one of \"left\", \"right\"".to_string())
+        );
+
+        assert_eq!(
+            entry(SettingKind::Json).parse_value(r#"{ "after_delay": { "milliseconds": 500 } }"#),
+            Ok(json!({ "after_delay": { "milliseconds": 500 } }))
+        );
+        assert!(entry(SettingKind::Json).parse_value("{").is_err());
+    }
+}
@@ -0,0 +1,273 @@
+use fs::Fs;
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, AppContext, DismissEvent, EventEmitter, FocusableView, Render, View, ViewContext,
+    VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use settings::{update_setting_in_json_text, update_user_settings_file, SettingsStore};
+use std::sync::Arc;
+use ui::{prelude::*, v_flex, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ui::HighlightedLabel, ModalView, Workspace};
+
+actions!(settings_profile_selector, [Toggle]);
+
+/// The label of the entry that deactivates the active profile. The entry is also shown muted,
+/// so that it stands apart from a profile that happens to have the same name.
+const NO_PROFILE_LABEL: &str = "No Profile";
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(toggle);
+        },
+    )
+    .detach();
+}
+
+pub fn toggle(workspace: &mut Workspace, _: &Toggle, cx: &mut ViewContext<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    workspace.toggle_modal(cx, |cx| {
+        SettingsProfileSelector::new(
+            SettingsProfileSelectorDelegate::new(cx.view().downgrade(), fs, cx),
+            cx,
+        )
+    });
+}
+
+impl ModalView for SettingsProfileSelector {}
+
+pub struct SettingsProfileSelector {
+    picker: View<Picker<SettingsProfileSelectorDelegate>>,
+}
+
+impl EventEmitter<DismissEvent> for SettingsProfileSelector {}
+
+impl FocusableView for SettingsProfileSelector {
+    fn focus_handle(&self, cx: &AppContext) -> gpui::FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for SettingsProfileSelector {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl SettingsProfileSelector {
+    pub fn new(delegate: SettingsProfileSelectorDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        Self { picker }
+    }
+}
+
+pub struct SettingsProfileSelectorDelegate {
+    fs: Arc<dyn Fs>,
+    /// The profiles to choose from, preceded by `None` for using no profile.
+    profiles: Vec<Option<String>>,
+    matches: Vec<StringMatch>,
+    original_profile: Option<String>,
+    selection_completed: bool,
+    selected_index: usize,
+    view: WeakView<SettingsProfileSelector>,
+}
+
+impl SettingsProfileSelectorDelegate {
+    fn new(
+        weak_view: WeakView<SettingsProfileSelector>,
+        fs: Arc<dyn Fs>,
+        cx: &mut ViewContext<SettingsProfileSelector>,
+    ) -> Self {
+        let store = cx.global::<SettingsStore>();
+        let original_profile = store.active_profile().map(str::to_string);
+        let profiles = [None]
+            .into_iter()
+            .chain(store.profile_names().into_iter().map(Some))
+            .collect::<Vec<_>>();
+        let matches = profiles
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, profile)| StringMatch {
+                candidate_id,
+                score: 0.0,
+                positions: Default::default(),
+                string: profile_label(profile.as_deref()).to_string(),
+            })
+            .collect();
+        let selected_index = profiles
+            .iter()
+            .position(|profile| *profile == original_profile)
+            .unwrap_or(0);
+        Self {
+            fs,
+            profiles,
+            matches,
+            original_profile,
+            selection_completed: false,
+            selected_index,
+            view: weak_view,
+        }
+    }
+
+    fn selected_profile(&self) -> Option<Option<String>> {
+        let mat = self.matches.get(self.selected_index)?;
+        Some(self.profiles[mat.candidate_id].clone())
+    }
+
+    fn show_selected_profile(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(profile) = self.selected_profile() {
+            Self::set_profile(profile.as_deref(), cx);
+        }
+    }
+
+    fn set_profile(profile: Option<&str>, cx: &mut AppContext) {
+        cx.update_global(|store: &mut SettingsStore, cx| {
+            store.set_active_profile(profile, cx).log_err();
+            cx.refresh();
+        });
+    }
+}
+
+fn profile_label(profile: Option<&str>) -> &str {
+    profile.unwrap_or(NO_PROFILE_LABEL)
+}
+
+impl PickerDelegate for SettingsProfileSelectorDelegate {
+    type ListItem = ui::ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Select Settings Profile...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        self.selection_completed = true;
+
+        if let Some(profile) = self.selected_profile() {
+            let tab_size = cx.global::<SettingsStore>().json_tab_size();
+            update_user_settings_file(self.fs.clone(), cx, move |text| {
+                Ok(update_setting_in_json_text(
+                    &text,
+                    &["active_profile"],
+                    profile.map(serde_json::Value::from).as_ref(),
+                    tab_size,
+                ))
+            })
+            .detach_and_log_err(cx);
+        }
+
+        self.view
+            .update(cx, |_, cx| {
+                cx.emit(DismissEvent);
+            })
+            .ok();
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        if !self.selection_completed {
+            Self::set_profile(self.original_profile.as_deref(), cx);
+            self.selection_completed = true;
+        }
+
+        self.view
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, cx: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+        self.show_selected_profile(cx);
+    }
+
+    fn update_matches(
+        &mut self,
+        query: String,
+        cx: &mut ViewContext<Picker<Self>>,
+    ) -> gpui::Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(id, profile)| {
+                let label = profile_label(profile.as_deref());
+                StringMatchCandidate {
+                    id,
+                    char_bag: label.into(),
+                    string: label.to_string(),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = this
+                    .delegate
+                    .selected_index
+                    .min(this.delegate.matches.len().saturating_sub(1));
+                this.delegate.show_selected_profile(cx);
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let profile_match = &self.matches[ix];
+        let is_no_profile = self.profiles[profile_match.candidate_id].is_none();
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(
+                    HighlightedLabel::new(
+                        profile_match.string.clone(),
+                        profile_match.positions.clone(),
+                    )
+                    .color(if is_no_profile {
+                        Color::Muted
+                    } else {
+                        Color::Default
+                    }),
+                ),
+        )
+    }
+}
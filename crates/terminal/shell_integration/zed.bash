@@ -0,0 +1,46 @@
+# Zed shell integration for bash, loaded with `bash --init-file`.
+#
+# Reports prompt and command boundaries as semantic prompt marks (OSC 133 A-D),
+# sent through the window title sequence so that Zed can read them back.
+
+if [ -n "$ZED_LOGIN_SHELL" ]; then
+    unset ZED_LOGIN_SHELL
+    [ -r /etc/profile ] && . /etc/profile
+    if [ -r ~/.bash_profile ]; then
+        . ~/.bash_profile
+    elif [ -r ~/.bash_login ]; then
+        . ~/.bash_login
+    elif [ -r ~/.profile ]; then
+        . ~/.profile
+    fi
+elif [ -r ~/.bashrc ]; then
+    . ~/.bashrc
+fi
+
+__zed_mark() {
+    builtin printf '\033]2;zed-shell-integration;133;%s\007' "$1"
+}
+
+__zed_prompt_start() {
+    local zed_status=$?
+    if [ -n "$__zed_prompt_shown" ]; then
+        __zed_mark "D;$zed_status"
+    fi
+    __zed_prompt_shown=1
+    __zed_mark A
+    return $zed_status
+}
+
+# Runs after the user's own prompt commands, which may rebuild PS1.
+__zed_prompt_end() {
+    local zed_status=$?
+    case "$PS1" in
+        *'133;B'*) ;;
+        *) PS1="$PS1"'\[\033]2;zed-shell-integration;133;B\007\]' ;;
+    esac
+    return $zed_status
+}
+
+# PS0 is only supported from bash 4.4, older versions report no command start.
+PS0='\033]2;zed-shell-integration;133;C\007'
+PROMPT_COMMAND="__zed_prompt_start${PROMPT_COMMAND:+; $PROMPT_COMMAND}; __zed_prompt_end"
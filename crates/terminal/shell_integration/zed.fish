@@ -0,0 +1,30 @@
+# Zed shell integration for fish, loaded with `fish --init-command`.
+#
+# Reports prompt and command boundaries as semantic prompt marks (OSC 133 A-D),
+# sent through the window title sequence so that Zed can read them back.
+
+function __zed_mark
+    builtin printf '\e]2;zed-shell-integration;133;%s\a' $argv[1]
+end
+
+function __zed_preexec --on-event fish_preexec
+    set -g __zed_command_running 1
+    __zed_mark C
+end
+
+function __zed_postexec --on-event fish_postexec
+    set -l zed_status $status
+    if set -q __zed_command_running
+        set -e __zed_command_running
+        __zed_mark "D;$zed_status"
+    end
+end
+
+if functions -q fish_prompt
+    functions -c fish_prompt __zed_user_fish_prompt
+    function fish_prompt
+        __zed_mark A
+        __zed_user_fish_prompt
+        __zed_mark B
+    end
+end
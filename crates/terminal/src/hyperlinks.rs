@@ -0,0 +1,199 @@
+//! Detection of paths in terminal output that a single word match can't find on its own.
+
+use std::ops::Range;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    /// `File "path", line 42`, as printed in Python tracebacks.
+    static ref PYTHON_TRACEBACK_REGEX: Regex =
+        Regex::new(r#"File "([^"]+)", line (\d+)"#).unwrap();
+    /// `path(row,column)`, as printed by tsc and MSBuild.
+    static ref PARENTHESIZED_POSITION_REGEX: Regex =
+        Regex::new(r#"([^\s"'`()]+)\((\d+),(\d+)\)"#).unwrap();
+    /// Quoted paths, which may contain spaces, with an optional `:row:column` suffix.
+    static ref QUOTED_PATH_REGEX: Regex = Regex::new(
+        r#""([^"]+)"(:\d+(?::\d+)?)?|'([^']+)'(:\d+(?::\d+)?)?|`([^`]+)`(:\d+(?::\d+)?)?"#
+    )
+    .unwrap();
+    /// Paths with spaces escaped by a backslash, as shells print them.
+    static ref ESCAPED_PATH_REGEX: Regex =
+        Regex::new(r#"(?:[\w.\[\]:/@\-~]|\\ )*\\ (?:[\w.\[\]:/@\-~]|\\ )*"#).unwrap();
+}
+
+/// Finds a path in `line` around the char at `index`, that needs more context than the
+/// surrounding word to be detected. Returns the range of chars to highlight, and the path,
+/// with the position it points at, if any, as a `:row:column` suffix.
+pub(crate) fn path_like_at(line: &str, index: usize) -> Option<(Range<usize>, String)> {
+    let byte_index = line.char_indices().nth(index)?.0;
+    let char_range = |range: Range<usize>| {
+        let start = line[..range.start].chars().count();
+        start..start + line[range].chars().count()
+    };
+
+    if let Some(captures) = PYTHON_TRACEBACK_REGEX
+        .captures_iter(line)
+        .find(|captures| captures.get(0).unwrap().range().contains(&byte_index))
+    {
+        let path_like = format!("{}:{}", &captures[1], &captures[2]);
+        return Some((char_range(captures.get(0).unwrap().range()), path_like));
+    }
+
+    if let Some(captures) = PARENTHESIZED_POSITION_REGEX
+        .captures_iter(line)
+        .find(|captures| captures.get(0).unwrap().range().contains(&byte_index))
+    {
+        let path_like = format!("{}:{}:{}", &captures[1], &captures[2], &captures[3]);
+        return Some((char_range(captures.get(0).unwrap().range()), path_like));
+    }
+
+    if let Some(captures) = QUOTED_PATH_REGEX
+        .captures_iter(line)
+        .find(|captures| captures.get(0).unwrap().range().contains(&byte_index))
+    {
+        let (path, position) =
+            [(1, 2), (3, 4), (5, 6)]
+                .into_iter()
+                .find_map(|(path_group, position_group)| {
+                    Some((
+                        captures.get(path_group)?.as_str(),
+                        captures
+                            .get(position_group)
+                            .map_or("", |position| position.as_str()),
+                    ))
+                })?;
+        if looks_like_path(path) {
+            return Some((
+                char_range(captures.get(0).unwrap().range()),
+                format!("{path}{position}"),
+            ));
+        }
+    }
+
+    if let Some(escaped_match) = ESCAPED_PATH_REGEX
+        .find_iter(line)
+        .find(|escaped_match| escaped_match.range().contains(&byte_index))
+    {
+        let path_like = escaped_match.as_str().replace("\\ ", " ");
+        if looks_like_path(&path_like) {
+            return Some((char_range(escaped_match.range()), path_like));
+        }
+    }
+
+    None
+}
+
+fn looks_like_path(text: &str) -> bool {
+    let has_extension = text
+        .rsplit_once('.')
+        .map_or(false, |(_, extension)| !extension.is_empty());
+    !text.contains("://") && text.trim() == text && (text.contains('/') || has_extension)
+}
+
+/// Converts a `file://` URI, as used in OSC 8 hyperlinks, to a path. A `#row` fragment is
+/// converted to a `:row` suffix.
+pub(crate) fn path_like_from_file_uri(uri: &str) -> Option<String> {
+    // Programs may put their hostname before the path, which is always this machine
+    let uri = uri.strip_prefix("file://")?;
+    let uri = &uri[uri.find('/')?..];
+    let (path, fragment) = match uri.split_once('#') {
+        Some((path, fragment)) => (path, Some(fragment)),
+        None => (uri, None),
+    };
+    let path = urlencoding::decode(path).ok()?;
+    match fragment.and_then(|fragment| fragment.trim_start_matches('L').parse::<u32>().ok()) {
+        Some(row) => Some(format!("{path}:{row}")),
+        None => Some(path.into_owned()),
+    }
+}
+
+/// Strips what commonly trails a path in tool output without being a part of it:
+/// pytest's `::test_name` node ids and sentence punctuation.
+pub(crate) fn trim_path_like(word: &str) -> &str {
+    let word = match word.find("::") {
+        Some(ix) if ix > 0 => &word[..ix],
+        _ => word,
+    };
+    let trimmed = word.trim_end_matches('.');
+    if trimmed.is_empty() || trimmed.ends_with('/') {
+        word
+    } else {
+        trimmed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path_like_at_marker(line_with_marker: &str) -> Option<(String, String)> {
+        let index = line_with_marker.chars().position(|c| c == '|').unwrap();
+        let line = line_with_marker.replace('|', "");
+        path_like_at(&line, index).map(|(range, path_like)| {
+            let matched = line
+                .chars()
+                .skip(range.start)
+                .take(range.len())
+                .collect::<String>();
+            (matched, path_like)
+        })
+    }
+
+    #[test]
+    fn test_path_like_at() {
+        assert_eq!(
+            path_like_at_marker(r#"  File "/tmp/my project/|app.py", line 42, in <module>"#),
+            Some((
+                r#"File "/tmp/my project/app.py", line 42"#.to_string(),
+                "/tmp/my project/app.py:42".to_string()
+            ))
+        );
+        assert_eq!(
+            path_like_at_marker("src/ind|ex.ts(10,5): error TS2322"),
+            Some((
+                "src/index.ts(10,5)".to_string(),
+                "src/index.ts:10:5".to_string()
+            ))
+        );
+        assert_eq!(
+            path_like_at_marker("error in 'my |dir/main.rs':3:7 here"),
+            Some((
+                "'my dir/main.rs':3:7".to_string(),
+                "my dir/main.rs:3:7".to_string()
+            ))
+        );
+        assert_eq!(
+            path_like_at_marker(r"ls: My\ Doc|uments/notes.txt"),
+            Some((
+                r"My\ Documents/notes.txt".to_string(),
+                "My Documents/notes.txt".to_string()
+            ))
+        );
+        assert_eq!(path_like_at_marker("it's a 'wonder|ful' day"), None);
+        assert_eq!(path_like_at_marker("see \"https://zed|.dev\""), None);
+        assert_eq!(path_like_at_marker("plain src/ma|in.rs:1:2"), None);
+    }
+
+    #[test]
+    fn test_path_like_from_file_uri() {
+        assert_eq!(
+            path_like_from_file_uri("file:///tmp/a%20b.rs"),
+            Some("/tmp/a b.rs".to_string())
+        );
+        assert_eq!(
+            path_like_from_file_uri("file://my-host/tmp/main.rs#12"),
+            Some("/tmp/main.rs:12".to_string())
+        );
+        assert_eq!(path_like_from_file_uri("https://zed.dev"), None);
+    }
+
+    #[test]
+    fn test_trim_path_like() {
+        assert_eq!(trim_path_like("tests/test_a.py::test_b"), "tests/test_a.py");
+        assert_eq!(trim_path_like("src/main.rs."), "src/main.rs");
+        assert_eq!(trim_path_like("src/main.rs:10:5"), "src/main.rs:10:5");
+        assert_eq!(trim_path_like(".."), "..");
+        assert_eq!(trim_path_like("../"), "../");
+    }
+}
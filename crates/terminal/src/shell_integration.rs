@@ -0,0 +1,204 @@
+//! Shell integration, letting the terminal know where each command starts and ends.
+//!
+//! The scripts in `shell_integration/` report semantic prompt marks, as defined by OSC 133:
+//! `A` when the prompt starts, `B` when the user's input starts, `C` when the command starts
+//! running and `D;<exit status>` when it finishes. Alacritty drops OSC sequences it doesn't
+//! handle, so the scripts send the marks as window titles starting with [`MARK_TITLE_PREFIX`],
+//! which are forwarded to us as [`alacritty_terminal::event::Event::Title`].
+
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::Result;
+use util::{paths::SUPPORT_DIR, ResultExt};
+
+use crate::terminal_settings::Shell;
+
+const MARK_TITLE_PREFIX: &str = "zed-shell-integration;133;";
+
+const SCRIPTS: &[(&str, &str)] = &[
+    ("zed.bash", include_str!("../shell_integration/zed.bash")),
+    ("zed.fish", include_str!("../shell_integration/zed.fish")),
+    (
+        "zsh/.zshenv",
+        include_str!("../shell_integration/zsh/.zshenv"),
+    ),
+    (
+        "zsh/zed.zsh",
+        include_str!("../shell_integration/zsh/zed.zsh"),
+    ),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SemanticMark {
+    PromptStart,
+    CommandStart,
+    CommandExecuted,
+    CommandFinished(Option<i32>),
+}
+
+impl SemanticMark {
+    /// Parses a mark out of a title set by the shell integration scripts,
+    /// returns `None` for regular titles.
+    pub fn from_title(title: &str) -> Option<Self> {
+        let mut params = title.strip_prefix(MARK_TITLE_PREFIX)?.split(';');
+        match params.next()? {
+            "A" => Some(Self::PromptStart),
+            "B" => Some(Self::CommandStart),
+            "C" => Some(Self::CommandExecuted),
+            "D" => Some(Self::CommandFinished(
+                params.next().and_then(|status| status.parse().ok()),
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// A prompt shown by the shell and the command run from it, if any.
+#[derive(Clone, Debug)]
+pub struct CommandMark {
+    /// The row of the prompt, counted from the top of the scrollback.
+    pub prompt_row: usize,
+    /// The row and column where the user's input starts, right after the prompt.
+    pub input_start: Option<(usize, usize)>,
+    /// The command line, read from the grid once the command has been run.
+    pub command: Option<String>,
+    pub executed: bool,
+    pub finished: bool,
+    pub exit_status: Option<i32>,
+}
+
+impl CommandMark {
+    pub fn new(prompt_row: usize) -> Self {
+        Self {
+            prompt_row,
+            input_start: None,
+            command: None,
+            executed: false,
+            finished: false,
+            exit_status: None,
+        }
+    }
+
+    /// Whether the shell is still waiting for input at this prompt.
+    pub fn is_pending(&self) -> bool {
+        !self.executed && !self.finished
+    }
+
+    /// Whether the command is still running.
+    pub fn is_running(&self) -> bool {
+        self.executed && !self.finished
+    }
+}
+
+/// The shell to spawn with the integration script loaded, and the environment it needs.
+pub struct ShellIntegration {
+    pub shell: Shell,
+    pub env: HashMap<String, String>,
+}
+
+/// Returns the given shell set up to load Zed's integration script, or `None` if the shell
+/// isn't supported. Shells configured with explicit arguments are left untouched, as the
+/// arguments we'd add could conflict with them.
+pub fn inject(shell: &Shell) -> Option<ShellIntegration> {
+    let (program, login) = match shell {
+        Shell::System => (std::env::var("SHELL").ok()?, true),
+        Shell::Program(program) => (program.clone(), false),
+        Shell::WithArguments { .. } => return None,
+    };
+    let shell_name = PathBuf::from(&program)
+        .file_name()?
+        .to_string_lossy()
+        .into_owned();
+
+    let mut env = HashMap::default();
+    let mut args = Vec::new();
+    match shell_name.as_str() {
+        "bash" => {
+            // Bash doesn't read `--init-file` in login shells, so the script loads the
+            // login profile itself.
+            if login {
+                env.insert("ZED_LOGIN_SHELL".to_string(), "1".to_string());
+            }
+            let script = install_scripts().log_err()?.join("zed.bash");
+            args.push("--init-file".to_string());
+            args.push(script.to_string_lossy().into_owned());
+        }
+        "zsh" => {
+            let zdotdir = install_scripts().log_err()?.join("zsh");
+            if let Ok(user_zdotdir) = std::env::var("ZDOTDIR") {
+                env.insert("ZED_USER_ZDOTDIR".to_string(), user_zdotdir);
+            }
+            env.insert(
+                "ZDOTDIR".to_string(),
+                zdotdir.to_string_lossy().into_owned(),
+            );
+            if login {
+                args.push("-l".to_string());
+            }
+        }
+        "fish" => {
+            let script = install_scripts().log_err()?.join("zed.fish");
+            if login {
+                args.push("-l".to_string());
+            }
+            args.push("--init-command".to_string());
+            args.push(format!("source {}", shell_quote(&script.to_string_lossy())));
+        }
+        _ => return None,
+    }
+
+    Some(ShellIntegration {
+        shell: Shell::WithArguments { program, args },
+        env,
+    })
+}
+
+/// Writes the integration scripts to the support directory, returning the directory.
+fn install_scripts() -> Result<PathBuf> {
+    let dir = SUPPORT_DIR.join("shell_integration");
+    for (path, contents) in SCRIPTS {
+        let path = dir.join(path);
+        if fs::read_to_string(&path).ok().as_deref() == Some(*contents) {
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, contents)?;
+    }
+    Ok(dir)
+}
+
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SemanticMark;
+
+    #[test]
+    fn test_semantic_mark_from_title() {
+        assert_eq!(
+            SemanticMark::from_title("zed-shell-integration;133;A"),
+            Some(SemanticMark::PromptStart)
+        );
+        assert_eq!(
+            SemanticMark::from_title("zed-shell-integration;133;C"),
+            Some(SemanticMark::CommandExecuted)
+        );
+        assert_eq!(
+            SemanticMark::from_title("zed-shell-integration;133;D;127"),
+            Some(SemanticMark::CommandFinished(Some(127)))
+        );
+        assert_eq!(
+            SemanticMark::from_title("zed-shell-integration;133;D"),
+            Some(SemanticMark::CommandFinished(None))
+        );
+        assert_eq!(
+            SemanticMark::from_title("zed-shell-integration;133;Z"),
+            None
+        );
+        assert_eq!(SemanticMark::from_title("vim ~/.zshrc"), None);
+    }
+}
@@ -1,5 +1,7 @@
+mod hyperlinks;
 pub mod mappings;
 pub use alacritty_terminal;
+pub mod shell_integration;
 pub mod terminal_settings;
 
 use alacritty_terminal::{
@@ -10,12 +12,12 @@ use alacritty_terminal::{
     selection::{Selection, SelectionRange, SelectionType},
     sync::FairMutex,
     term::{
-        cell::Cell,
+        cell::{Cell, Flags},
         search::{Match, RegexIter, RegexSearch},
         Config, RenderableCursor, TermMode,
     },
     tty::{self, setup_env},
-    vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Rgb},
+    vte::ansi::{ClearMode, Handler, NamedPrivateMode, PrivateMode, Processor, Rgb},
     Term,
 };
 use anyhow::{bail, Result};
@@ -33,6 +35,7 @@ use mappings::mouse::{
 use procinfo::LocalProcessInfo;
 use serde::{Deserialize, Serialize};
 use settings::Settings;
+use shell_integration::{CommandMark, SemanticMark};
 use terminal_settings::{AlternateScroll, Shell, TerminalBlink, TerminalSettings};
 use theme::{ActiveTheme, Theme};
 use util::truncate_and_trailoff;
@@ -59,7 +62,16 @@ use crate::mappings::{colors::to_alac_rgb, keys::to_esc_str};
 
 actions!(
     terminal,
-    [Clear, Copy, Paste, ShowCharacterPalette, SearchTest,]
+    [
+        Clear,
+        Copy,
+        Paste,
+        ShowCharacterPalette,
+        SearchTest,
+        ScrollToPreviousCommand,
+        ScrollToNextCommand,
+        RerunLastCommand,
+    ]
 );
 
 ///Scrolling is unbearably sluggish by default. Alacritty supports a configurable
@@ -67,6 +79,7 @@ actions!(
 ///Implement scroll bars.
 const SCROLL_MULTIPLIER: f32 = 4.;
 const MAX_SEARCH_LINES: usize = 100;
+const MAX_COMMAND_MARKS: usize = 1000;
 const DEBUG_TERMINAL_WIDTH: Pixels = px(500.);
 const DEBUG_TERMINAL_HEIGHT: Pixels = px(30.);
 const DEBUG_CELL_WIDTH: Pixels = px(5.);
@@ -280,10 +293,20 @@ impl TerminalBuilder {
         env: HashMap<String, String>,
         blink_settings: Option<TerminalBlink>,
         alternate_scroll: AlternateScroll,
+        shell_integration: bool,
         window: AnyWindowHandle,
     ) -> Result<TerminalBuilder> {
+        let integration = if shell_integration {
+            shell_integration::inject(&shell)
+        } else {
+            None
+        };
+
         let pty_options = {
-            let alac_shell = match shell.clone() {
+            let spawned_shell = integration
+                .as_ref()
+                .map_or(&shell, |integration| &integration.shell);
+            let alac_shell = match spawned_shell.clone() {
                 Shell::System => None,
                 Shell::Program(program) => {
                     Some(alacritty_terminal::tty::Shell::new(program, Vec::new()))
@@ -311,6 +334,19 @@ impl TerminalBuilder {
         std::env::set_var("LC_ALL", "en_US.UTF-8");
         std::env::set_var("ZED_TERM", "true");
 
+        //The shell integration's variables only apply to the shell we're about to spawn,
+        //so their previous values are restored right after spawning it
+        let previous_env = integration
+            .map(|integration| integration.env)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(key, value)| {
+                let previous = std::env::var(&key).ok();
+                std::env::set_var(&key, value);
+                (key, previous)
+            })
+            .collect::<Vec<_>>();
+
         let config = Config {
             scrolling_history: 10000,
             ..Default::default()
@@ -339,11 +375,20 @@ impl TerminalBuilder {
         let term = Arc::new(FairMutex::new(term));
 
         //Setup the pty...
-        let pty = match tty::new(
+        let pty = tty::new(
             &pty_options,
             TerminalSize::default().into(),
             window.window_id().as_u64(),
-        ) {
+        );
+
+        for (key, previous) in previous_env {
+            match previous {
+                Some(value) => std::env::set_var(key, value),
+                None => std::env::remove_var(key),
+            }
+        }
+
+        let pty = match pty {
             Ok(pty) => pty,
             Err(error) => {
                 bail!(TerminalError {
@@ -393,6 +438,7 @@ impl TerminalBuilder {
             hovered_word: false,
             url_regex,
             word_regex,
+            command_marks: Vec::new(),
         };
 
         Ok(TerminalBuilder {
@@ -493,6 +539,15 @@ pub struct TerminalContent {
     pub cursor_char: char,
     pub size: TerminalSize,
     pub last_hovered_word: Option<HoveredWord>,
+    pub command_decorations: Vec<CommandDecoration>,
+}
+
+/// The exit status of a finished command, shown next to its prompt.
+#[derive(Clone, Debug)]
+pub struct CommandDecoration {
+    /// The prompt's line in the viewport
+    pub line: i32,
+    pub exit_status: i32,
 }
 
 #[derive(Clone)]
@@ -517,6 +572,7 @@ impl Default for TerminalContent {
             cursor_char: Default::default(),
             size: Default::default(),
             last_hovered_word: None,
+            command_decorations: Vec::new(),
         }
     }
 }
@@ -549,12 +605,17 @@ pub struct Terminal {
     hovered_word: bool,
     url_regex: RegexSearch,
     word_regex: RegexSearch,
+    command_marks: Vec<CommandMark>,
 }
 
 impl Terminal {
     fn process_event(&mut self, event: &AlacTermEvent, cx: &mut ModelContext<Self>) {
         match event {
             AlacTermEvent::Title(title) => {
+                if let Some(mark) = SemanticMark::from_title(title) {
+                    self.process_semantic_mark(mark);
+                    return;
+                }
                 self.breadcrumb_text = title.to_string();
                 cx.emit(Event::BreadcrumbsChanged);
             }
@@ -651,6 +712,18 @@ impl Terminal {
                 term.resize(new_size);
             }
             InternalEvent::Clear => {
+                // Only the line with the cursor is kept, along with the prompt it may belong to
+                let cursor_row = command_row(term, term.grid().cursor.point.line);
+                let pending_mark = self.command_marks.pop().filter(CommandMark::is_pending);
+                self.command_marks.clear();
+                if let Some(mut mark) = pending_mark {
+                    mark.prompt_row = mark.prompt_row.saturating_sub(cursor_row);
+                    mark.input_start = mark
+                        .input_start
+                        .map(|(row, column)| (row.saturating_sub(cursor_row), column));
+                    self.command_marks.push(mark);
+                }
+
                 // Clear back buffer
                 term.clear_screen(ClearMode::Saved);
 
@@ -755,10 +828,15 @@ impl Terminal {
                         }
                     }
 
-                    let url = link.unwrap().uri().to_owned();
-                    let url_match = min_index..=max_index;
+                    let uri = link.unwrap().uri().to_owned();
+                    let uri_match = min_index..=max_index;
 
-                    Some((url, true, url_match))
+                    match hyperlinks::path_like_from_file_uri(&uri) {
+                        Some(path_like) => Some((path_like, false, uri_match)),
+                        None => Some((uri, true, uri_match)),
+                    }
+                } else if let Some((path_match, path_like)) = path_like_match_at(term, point) {
+                    Some((path_like, false, path_match))
                 } else if let Some(word_match) = regex_match_at(term, point, &mut self.word_regex) {
                     let maybe_url_or_path =
                         term.bounds_to_string(*word_match.start(), *word_match.end());
@@ -800,7 +878,9 @@ impl Terminal {
                             let target = if is_url {
                                 MaybeNavigationTarget::Url(maybe_url_or_path)
                             } else {
-                                MaybeNavigationTarget::PathLike(maybe_url_or_path)
+                                MaybeNavigationTarget::PathLike(
+                                    hyperlinks::trim_path_like(&maybe_url_or_path).to_string(),
+                                )
                             };
                             cx.emit(Event::Open(target));
                         } else {
@@ -984,10 +1064,14 @@ impl Terminal {
             self.process_terminal_event(&e, &mut terminal, cx)
         }
 
-        self.last_content = Self::make_content(&terminal, &self.last_content);
+        self.last_content = Self::make_content(&terminal, &self.last_content, &self.command_marks);
     }
 
-    fn make_content(term: &Term<ZedListener>, last_content: &TerminalContent) -> TerminalContent {
+    fn make_content(
+        term: &Term<ZedListener>,
+        last_content: &TerminalContent,
+        command_marks: &[CommandMark],
+    ) -> TerminalContent {
         let content = term.renderable_content();
         TerminalContent {
             cells: content
@@ -1012,6 +1096,7 @@ impl Terminal {
             cursor_char: term.grid()[content.cursor.point].c,
             size: last_content.size,
             last_hovered_word: last_content.last_hovered_word.clone(),
+            command_decorations: command_decorations(term, command_marks),
         }
     }
 
@@ -1285,6 +1370,155 @@ impl Terminal {
         })
     }
 
+    /// Returns the plain text of the primary screen's history, ending at the line above the
+    /// cursor and keeping at most `max_lines` of the most recent lines.
+    /// Returns `None` while the alternate screen is active, as its contents are transient.
+    pub fn scrollback_text(&self, max_lines: usize) -> Option<String> {
+        if max_lines == 0 {
+            return None;
+        }
+        let term = self.term.lock();
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return None;
+        }
+
+        let end_line = term.grid().cursor.point.line - 1;
+        let start_line = cmp::max(term.topmost_line(), end_line - (max_lines - 1));
+        if end_line < start_line {
+            return Some(String::new());
+        }
+
+        let text = term.bounds_to_string(
+            AlacPoint::new(start_line, Column(0)),
+            AlacPoint::new(end_line, term.last_column()),
+        );
+        Some(text.trim_end().to_string())
+    }
+
+    /// Writes previously saved scrollback into the terminal's display, without sending it to the
+    /// shell. Should be called right after the terminal is created, before the shell draws its prompt.
+    pub fn restore_scrollback(&mut self, scrollback: &str) {
+        if scrollback.is_empty() {
+            return;
+        }
+        let mut term = self.term.lock();
+        let mut processor: Processor = Processor::new();
+        let restored = format!("{}\r\n", scrollback.replace('\n', "\r\n"));
+        for byte in restored.bytes() {
+            processor.advance(&mut *term, byte);
+        }
+    }
+
+    fn process_semantic_mark(&mut self, mark: SemanticMark) {
+        let term = self.term.lock();
+        //Full screen programs don't run commands from the shell's prompt
+        if term.mode().contains(TermMode::ALT_SCREEN) {
+            return;
+        }
+
+        let cursor = term.grid().cursor.point;
+        let cursor_row = command_row(&term, cursor.line);
+        let last_mark = self.command_marks.last_mut();
+        match mark {
+            SemanticMark::PromptStart => match last_mark {
+                //The prompt was drawn again without running a command
+                Some(last_mark) if last_mark.is_pending() => {
+                    *last_mark = CommandMark::new(cursor_row);
+                }
+                _ => {
+                    if self.command_marks.len() == MAX_COMMAND_MARKS {
+                        self.command_marks.remove(0);
+                    }
+                    self.command_marks.push(CommandMark::new(cursor_row));
+                }
+            },
+            SemanticMark::CommandStart => {
+                if let Some(last_mark) = last_mark.filter(|mark| mark.is_pending()) {
+                    last_mark.input_start = Some((cursor_row, cursor.column.0));
+                }
+            }
+            SemanticMark::CommandExecuted => {
+                if let Some(last_mark) = last_mark.filter(|mark| mark.is_pending()) {
+                    last_mark.executed = true;
+                    last_mark.command = command_text(&term, last_mark);
+                }
+            }
+            SemanticMark::CommandFinished(exit_status) => {
+                if let Some(last_mark) = last_mark.filter(|mark| !mark.finished) {
+                    //Not every shell reports when commands start running
+                    if last_mark.command.is_none() {
+                        last_mark.command = command_text(&term, last_mark);
+                    }
+                    last_mark.finished = true;
+                    last_mark.exit_status = exit_status;
+                }
+            }
+        }
+    }
+
+    /// Scrolls up to the closest prompt above the viewport, as reported by the shell integration.
+    pub fn scroll_to_previous_command(&mut self) {
+        let term = self.term.lock();
+        let display_offset = term.grid().display_offset() as i32;
+        let top_row = command_row(&term, Line(-display_offset));
+        let target = self
+            .command_marks
+            .iter()
+            .rev()
+            .find(|mark| mark.prompt_row < top_row)
+            .map(|mark| command_line(&term, mark.prompt_row));
+        drop(term);
+
+        if let Some(line) = target {
+            self.events
+                .push_back(InternalEvent::Scroll(AlacScroll::Delta(
+                    -line.0 - display_offset,
+                )));
+        }
+    }
+
+    /// Scrolls down to the closest prompt below the top of the viewport, or to the bottom
+    /// if there's none.
+    pub fn scroll_to_next_command(&mut self) {
+        let term = self.term.lock();
+        let display_offset = term.grid().display_offset() as i32;
+        let top_row = command_row(&term, Line(-display_offset));
+        let target = self
+            .command_marks
+            .iter()
+            .find(|mark| mark.prompt_row > top_row)
+            .map(|mark| command_line(&term, mark.prompt_row));
+        drop(term);
+
+        let scroll = match target {
+            Some(line) if line.0 < 0 => AlacScroll::Delta(-line.0 - display_offset),
+            _ => AlacScroll::Bottom,
+        };
+        self.events.push_back(InternalEvent::Scroll(scroll));
+    }
+
+    /// Runs the most recent command again, unless one is still running.
+    pub fn rerun_last_command(&mut self) {
+        if self.last_content.mode.contains(TermMode::ALT_SCREEN)
+            || self
+                .command_marks
+                .last()
+                .map_or(false, CommandMark::is_running)
+        {
+            return;
+        }
+
+        if let Some(command) = self
+            .command_marks
+            .iter()
+            .rev()
+            .find_map(|mark| mark.command.clone())
+        {
+            //Clear whatever was typed at the prompt first
+            self.input(format!("\x15{command}\r"));
+        }
+    }
+
     pub fn title(&self, truncate: bool) -> String {
         self.foreground_process_info
             .as_ref()
@@ -1329,6 +1563,38 @@ impl Drop for Terminal {
 
 impl EventEmitter<Event> for Terminal {}
 
+/// Finds paths around the point that the word regex can't match, such as quoted paths
+/// containing spaces, see [`hyperlinks::path_like_at`].
+fn path_like_match_at<T>(term: &Term<T>, point: AlacPoint) -> Option<(Match, String)> {
+    let (line, points) = line_text_at(term, point);
+    let index = points.iter().rposition(|line_point| *line_point <= point)?;
+    let (range, path_like) = hyperlinks::path_like_at(&line, index)?;
+    Some((points[range.start]..=points[range.end - 1], path_like))
+}
+
+/// Returns the text of the wrapped line containing the point, along with the grid point of each char.
+fn line_text_at<T>(term: &Term<T>, point: AlacPoint) -> (String, Vec<AlacPoint>) {
+    let end = term.line_search_right(point);
+    let mut current = term.line_search_left(point);
+    let mut text = String::new();
+    let mut points = Vec::new();
+    loop {
+        let cell = &term.grid()[current];
+        if !cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+        {
+            text.push(cell.c);
+            points.push(current);
+        }
+        if current >= end {
+            break;
+        }
+        current = current.add(term, Boundary::Grid, 1);
+    }
+    (text, points)
+}
+
 /// Based on alacritty/src/display/hint.rs > regex_match_at
 /// Retrieve the match, if the specified point is inside the content matching the regex.
 fn regex_match_at<T>(term: &Term<T>, point: AlacPoint, regex: &mut RegexSearch) -> Option<Match> {
@@ -1353,6 +1619,51 @@ pub fn visible_regex_match_iter<'a, T>(
         .take_while(move |rm| rm.start().line <= viewport_end)
 }
 
+/// Converts a grid line to a row counted from the top of the scrollback, which doesn't change
+/// as new output scrolls lines into the history, until the history is full.
+fn command_row<T>(term: &Term<T>, line: Line) -> usize {
+    cmp::max(term.grid().history_size() as i32 + line.0, 0) as usize
+}
+
+fn command_line<T>(term: &Term<T>, row: usize) -> Line {
+    Line(row as i32 - term.grid().history_size() as i32)
+}
+
+/// Reads the command line typed after the given prompt, following wrapped lines.
+fn command_text<T>(term: &Term<T>, mark: &CommandMark) -> Option<String> {
+    let (row, column) = mark.input_start?;
+    let line = command_line(term, row);
+    if line < term.topmost_line() || line > term.bottommost_line() {
+        return None;
+    }
+
+    let start = AlacPoint::new(line, min(Column(column), term.last_column()));
+    let end = term.line_search_right(start);
+    let text = term.bounds_to_string(start, end);
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn command_decorations<T>(term: &Term<T>, command_marks: &[CommandMark]) -> Vec<CommandDecoration> {
+    if term.mode().contains(TermMode::ALT_SCREEN) {
+        return Vec::new();
+    }
+
+    let display_offset = term.grid().display_offset() as i32;
+    let screen_lines = term.screen_lines() as i32;
+    command_marks
+        .iter()
+        .filter(|mark| mark.command.is_some())
+        .filter_map(|mark| {
+            let exit_status = mark.exit_status?;
+            let line = command_line(term, mark.prompt_row).0 + display_offset;
+            (0..screen_lines)
+                .contains(&line)
+                .then_some(CommandDecoration { line, exit_status })
+        })
+        .collect()
+}
+
 fn make_selection(range: &RangeInclusive<AlacPoint>) -> Selection {
     let mut selection = Selection::new(SelectionType::Simple, *range.start(), AlacDirection::Left);
     selection.update(*range.end(), AlacDirection::Right);
@@ -1,4 +1,4 @@
-use gpui::{px, AbsoluteLength, AppContext, FontFeatures, Pixels};
+use gpui::{px, AbsoluteLength, AppContext, FontFallbacks, FontFeatures, Pixels};
 use schemars::{
     gen::SchemaGenerator,
     schema::{InstanceType, RootSchema, Schema, SchemaObject},
@@ -25,6 +25,7 @@ pub struct TerminalSettings {
     pub font_family: Option<String>,
     pub line_height: TerminalLineHeight,
     pub font_features: Option<FontFeatures>,
+    pub font_fallbacks: Option<FontFallbacks>,
     pub env: HashMap<String, String>,
     pub blinking: TerminalBlink,
     pub alternate_scroll: AlternateScroll,
@@ -34,6 +35,26 @@ pub struct TerminalSettings {
     pub default_width: Pixels,
     pub default_height: Pixels,
     pub detect_venv: VenvSettings,
+    pub persist_scrollback: bool,
+    pub max_persisted_scrollback_lines: usize,
+    pub shell_integration: bool,
+    pub profiles: Vec<TerminalProfile>,
+}
+
+/// A terminal configuration that can be launched from the terminal panel's new terminal menu.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct TerminalProfile {
+    /// The name shown in the menu.
+    pub name: String,
+    /// The shell to launch, falls back to the `shell` setting.
+    pub shell: Option<Shell>,
+    /// Environment variables to add to the ones from the `env` setting.
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Where to open the terminal, falls back to the `working_directory` setting.
+    pub working_directory: Option<WorkingDirectory>,
+    /// The name of an icon to show next to the profile, such as "terminal" or "file_rust".
+    pub icon: Option<String>,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -104,7 +125,16 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: comfortable
     pub line_height: Option<TerminalLineHeight>,
+    /// Sets the terminal's OpenType font features.
+    ///
+    /// If this option is not included,
+    /// the terminal will default to matching the buffer's font features.
     pub font_features: Option<FontFeatures>,
+    /// Sets the fonts that glyphs missing from the terminal's font are taken from.
+    ///
+    /// If this option is not included,
+    /// the terminal will default to matching the buffer's font fallbacks.
+    pub font_fallbacks: Option<FontFallbacks>,
     /// Any key-value pairs added to this list will be added to the terminal's
     /// environment. Use `:` to separate multiple values.
     ///
@@ -145,6 +175,27 @@ pub struct TerminalSettingsContent {
     ///
     /// Default: on
     pub detect_venv: Option<VenvSettings>,
+    /// Whether to save each terminal's scrollback along with the workspace,
+    /// and replay it when the terminal is restored.
+    ///
+    /// Default: false
+    pub persist_scrollback: Option<bool>,
+    /// The maximum number of scrollback lines to save per terminal when
+    /// `persist_scrollback` is enabled.
+    ///
+    /// Default: 1000
+    pub max_persisted_scrollback_lines: Option<usize>,
+    /// Whether to load Zed's shell integration into bash, zsh and fish, which
+    /// reports where each command starts and how it exited. This enables
+    /// jumping between commands and re-running the last one.
+    ///
+    /// Default: true
+    pub shell_integration: Option<bool>,
+    /// Terminal configurations that can be launched from the terminal panel's
+    /// new terminal menu, each with its own shell, environment and working directory.
+    ///
+    /// Default: []
+    pub profiles: Option<Vec<TerminalProfile>>,
 }
 
 impl settings::Settings for TerminalSettings {
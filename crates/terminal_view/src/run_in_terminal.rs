@@ -0,0 +1,144 @@
+//! Extracting code from an editor to run it in a terminal, for a lightweight REPL workflow.
+
+use std::ops::Range;
+
+use editor::Editor;
+use gpui::{AppContext, Model};
+use language::Point;
+use terminal::{alacritty_terminal::term::TermMode, Terminal};
+
+/// Comment prefixes that can start a `%%` cell marker, covering Python and shell (`#`),
+/// SQL (`--`) and C-like languages (`//`).
+const CELL_MARKER_PREFIXES: &[&str] = &["#", "--", "//"];
+
+/// Returns the code to run for the editor's newest selection: the selected text, or the
+/// cell around the cursor when nothing is selected, falling back to the cursor's line.
+pub(crate) fn code_to_run(editor: &Editor, cx: &AppContext) -> Option<String> {
+    let selection = editor.selections.newest::<Point>(cx);
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let code = if selection.is_empty() {
+        let text = snapshot.text();
+        let row = selection.head().row as usize;
+        let rows = cell_rows(&text, row).unwrap_or(row..row + 1);
+        text.lines()
+            .skip(rows.start)
+            .take(rows.len())
+            .collect::<Vec<_>>()
+            .join("\n")
+    } else {
+        snapshot
+            .text_for_range(selection.start..selection.end)
+            .collect::<String>()
+    };
+    (!code.trim().is_empty()).then_some(code)
+}
+
+/// Sends the code to the terminal, running it as if it had been typed at the prompt.
+pub(crate) fn send_to_terminal(terminal: &Model<Terminal>, code: &str, cx: &mut AppContext) {
+    terminal.update(cx, |terminal, _| {
+        let bracketed_paste = terminal
+            .last_content()
+            .mode
+            .contains(TermMode::BRACKETED_PASTE);
+        terminal.paste(&code_for_terminal(code, bracketed_paste));
+        terminal.input("\r".to_string());
+    });
+}
+
+fn is_cell_marker(line: &str) -> bool {
+    let line = line.trim_start();
+    CELL_MARKER_PREFIXES.iter().any(|prefix| {
+        line.strip_prefix(prefix)
+            .map_or(false, |rest| rest.trim_start().starts_with("%%"))
+    })
+}
+
+/// Returns the rows of the cell containing `row`, excluding the markers delimiting it,
+/// or `None` if the text has no cell markers.
+fn cell_rows(text: &str, row: usize) -> Option<Range<usize>> {
+    let lines = text.lines().collect::<Vec<_>>();
+    if !lines.iter().any(|line| is_cell_marker(line)) {
+        return None;
+    }
+
+    let row = row.min(lines.len().saturating_sub(1));
+    let start = if is_cell_marker(lines.get(row)?) {
+        row + 1
+    } else {
+        lines[..row]
+            .iter()
+            .rposition(|line| is_cell_marker(line))
+            .map_or(0, |marker_row| marker_row + 1)
+    };
+    let end = lines[start..]
+        .iter()
+        .position(|line| is_cell_marker(line))
+        .map_or(lines.len(), |offset| start + offset);
+    Some(start..end)
+}
+
+/// Prepares code to be pasted at an interactive prompt. The common indentation is removed,
+/// so that a nested block can be run on its own. Interpreters such as Python's end a block
+/// at the first blank line they read, so blank lines are dropped unless the terminal
+/// receives the code as a single bracketed paste, and an indented last line is followed by
+/// an empty one to end its block.
+fn code_for_terminal(code: &str, bracketed_paste: bool) -> String {
+    let lines = code
+        .lines()
+        .filter(|line| bracketed_paste || !line.trim().is_empty())
+        .collect::<Vec<_>>();
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut lines = lines
+        .into_iter()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>();
+    while lines.last().map_or(false, |line| line.is_empty()) {
+        lines.pop();
+    }
+    if lines
+        .last()
+        .map_or(false, |line| line.starts_with(char::is_whitespace))
+    {
+        lines.push("");
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cell_rows() {
+        let text = "import os\n# %%\na = 1\nb = 2\n# %% second cell\nprint(a)\n";
+        assert_eq!(cell_rows(text, 0), Some(0..1));
+        assert_eq!(cell_rows(text, 1), Some(2..4));
+        assert_eq!(cell_rows(text, 3), Some(2..4));
+        assert_eq!(cell_rows(text, 5), Some(5..6));
+        assert_eq!(cell_rows(text, 10), Some(5..6));
+
+        assert_eq!(cell_rows("select 1;\n--%%\nselect 2;", 2), Some(2..3));
+        assert_eq!(cell_rows("a = 1\nb = 2", 1), None);
+        assert_eq!(cell_rows("# 100%% sure\na = 1", 1), None);
+    }
+
+    #[test]
+    fn test_code_for_terminal() {
+        assert_eq!(
+            code_for_terminal("    if x:\n\n        y()\n", false),
+            "if x:\n    y()\n"
+        );
+        assert_eq!(
+            code_for_terminal("    if x:\n\n        y()\n", true),
+            "if x:\n\n    y()\n"
+        );
+        assert_eq!(code_for_terminal("echo hi  \n\n", false), "echo hi");
+        assert_eq!(code_for_terminal("", false), "");
+    }
+}
@@ -1,11 +1,11 @@
 use editor::{Cursor, HighlightedRange, HighlightedRangeLine};
 use gpui::{
-    div, fill, point, px, relative, AnyElement, AvailableSpace, Bounds, DispatchPhase, Element,
-    ElementContext, ElementId, FocusHandle, Font, FontStyle, FontWeight, HighlightStyle, Hsla,
-    InputHandler, InteractiveBounds, InteractiveElement, InteractiveElementState, Interactivity,
-    IntoElement, LayoutId, Model, ModelContext, ModifiersChangedEvent, MouseButton, MouseMoveEvent,
-    Pixels, Point, ShapedLine, StatefulInteractiveElement, Styled, TextRun, TextStyle, TextSystem,
-    UnderlineStyle, WeakView, WhiteSpace, WindowContext,
+    div, fill, point, px, relative, size, AnyElement, AvailableSpace, Bounds, DispatchPhase,
+    Element, ElementContext, ElementId, FocusHandle, Font, FontStyle, FontWeight, HighlightStyle,
+    Hsla, InputHandler, InteractiveBounds, InteractiveElement, InteractiveElementState,
+    Interactivity, IntoElement, LayoutId, Model, ModelContext, ModifiersChangedEvent, MouseButton,
+    MouseMoveEvent, Pixels, Point, ShapedLine, StatefulInteractiveElement, Styled, TextRun,
+    TextStyle, TextSystem, UnderlineStyle, View, WeakView, WhiteSpace, WindowContext,
 };
 use itertools::Itertools;
 use language::CursorShape;
@@ -27,18 +27,24 @@ use workspace::Workspace;
 use std::mem;
 use std::{fmt::Debug, ops::RangeInclusive};
 
+use crate::TerminalView;
+
 /// The information generated during layout that is necessary for painting.
 pub struct LayoutState {
     cells: Vec<LayoutCell>,
     rects: Vec<LayoutRect>,
     relative_highlighted_ranges: Vec<(RangeInclusive<AlacPoint>, Hsla)>,
     cursor: Option<Cursor>,
+    /// The text being composed with an input method and where it's shown, relative to the
+    /// origin of the grid.
+    marked_text: Option<(Point<Pixels>, ShapedLine)>,
     background_color: Hsla,
     dimensions: TerminalSize,
     mode: TermMode,
     display_offset: usize,
     hyperlink_tooltip: Option<AnyElement>,
     gutter: Pixels,
+    command_decorations: Vec<(i32, Hsla)>,
 }
 
 /// Helper struct for converting data between Alacritty's cursor points, and displayed cursor points.
@@ -140,6 +146,7 @@ impl LayoutRect {
 /// The GPUI element that paints the terminal.
 /// We need to keep a reference to the view for mouse events, do we need it for any other terminal stuff, or can we move that to connection?
 pub struct TerminalElement {
+    terminal_view: View<TerminalView>,
     terminal: Model<Terminal>,
     workspace: WeakView<Workspace>,
     focus: FocusHandle,
@@ -159,6 +166,7 @@ impl StatefulInteractiveElement for TerminalElement {}
 
 impl TerminalElement {
     pub fn new(
+        terminal_view: View<TerminalView>,
         terminal: Model<Terminal>,
         workspace: WeakView<Workspace>,
         focus: FocusHandle,
@@ -167,6 +175,7 @@ impl TerminalElement {
         can_navigate_to_selected_word: bool,
     ) -> TerminalElement {
         TerminalElement {
+            terminal_view,
             terminal,
             workspace,
             focused,
@@ -365,7 +374,7 @@ impl TerminalElement {
     }
 
     fn compute_layout(&self, bounds: Bounds<gpui::Pixels>, cx: &mut ElementContext) -> LayoutState {
-        let settings = ThemeSettings::get_global(cx).clone();
+        let settings = ThemeSettings::for_window(cx).clone();
 
         let buffer_font_size = settings.buffer_font_size(cx);
 
@@ -381,6 +390,11 @@ impl TerminalElement {
             .clone()
             .unwrap_or(settings.buffer_font.features.clone());
 
+        let font_fallbacks = terminal_settings
+            .font_fallbacks
+            .clone()
+            .or(settings.buffer_font.fallbacks.clone());
+
         let line_height = terminal_settings.line_height.value();
         let font_size = terminal_settings.font_size.clone();
 
@@ -405,6 +419,7 @@ impl TerminalElement {
         let text_style = TextStyle {
             font_family,
             font_features,
+            font_fallbacks,
             font_size: font_size.into(),
             font_style: FontStyle::Normal,
             line_height: line_height.into(),
@@ -479,9 +494,22 @@ impl TerminalElement {
             cursor_char,
             selection,
             cursor,
+            command_decorations,
             ..
         } = &self.terminal.read(cx).last_content;
 
+        let command_decorations = command_decorations
+            .iter()
+            .map(|decoration| {
+                let color = if decoration.exit_status == 0 {
+                    theme.status().success
+                } else {
+                    theme.status().error
+                };
+                (decoration.line, color)
+            })
+            .collect();
+
         // searches, highlights to a single range representations
         let mut relative_highlighted_ranges = Vec::new();
         for search_match in search_matches {
@@ -504,6 +532,36 @@ impl TerminalElement {
             cx,
         );
 
+        // Show the text being composed with an input method at the cursor, even if the
+        // cursor itself is hidden, so it's clear what will be committed.
+        let marked_text = self.terminal_view.read(cx).ime_state().map(|ime_state| {
+            let cursor_point = DisplayCursor::from(cursor.point, *display_offset);
+            let position = point(
+                dimensions.cell_width * cursor_point.col() as f32,
+                dimensions.line_height * cursor_point.line() as f32,
+            );
+            let len = ime_state.marked_text.len();
+            let text = cx
+                .text_system()
+                .shape_line(
+                    ime_state.marked_text.clone().into(),
+                    text_style.font_size.to_pixels(cx.rem_size()),
+                    &[TextRun {
+                        len,
+                        font: text_style.font(),
+                        color: theme.colors().terminal_foreground,
+                        background_color: None,
+                        underline: Some(UnderlineStyle {
+                            thickness: px(1.),
+                            color: None,
+                            wavy: false,
+                        }),
+                    }],
+                )
+                .unwrap();
+            (position, text)
+        });
+
         // Layout cursor. Rectangle is used for IME, so we should lay it out even
         // if we don't end up showing it.
         let cursor = if let AlacCursorShape::Hidden = cursor.shape {
@@ -557,6 +615,7 @@ impl TerminalElement {
         LayoutState {
             cells,
             cursor,
+            marked_text,
             background_color,
             dimensions,
             rects,
@@ -565,6 +624,7 @@ impl TerminalElement {
             display_offset: *display_offset,
             hyperlink_tooltip,
             gutter,
+            command_decorations,
         }
     }
 
@@ -748,11 +808,17 @@ impl Element for TerminalElement {
         let origin = bounds.origin + Point::new(layout.gutter, px(0.));
 
         let terminal_input_handler = TerminalInputHandler {
+            terminal_view: self.terminal_view.clone(),
             terminal: self.terminal.clone(),
             cursor_bounds: layout
                 .cursor
                 .as_ref()
                 .map(|cursor| cursor.bounding_rect(origin)),
+            marked_text: layout
+                .marked_text
+                .as_ref()
+                .map(|(position, text)| (origin + *position, text.clone())),
+            line_height: layout.dimensions.line_height,
             workspace: self.workspace.clone(),
         };
 
@@ -783,6 +849,19 @@ impl Element for TerminalElement {
                     rect.paint(origin, &layout, cx);
                 }
 
+                // Mark each finished command's prompt in the gutter with its exit status
+                for (line, color) in &layout.command_decorations {
+                    let marker_width = (layout.gutter / 4.).round();
+                    let position = point(
+                        bounds.origin.x + (layout.gutter - marker_width) / 2.,
+                        origin.y + *line as f32 * layout.dimensions.line_height,
+                    );
+                    cx.paint_quad(fill(
+                        Bounds::new(position, size(marker_width, layout.dimensions.line_height)),
+                        *color,
+                    ));
+                }
+
                 cx.with_z_index(1, |cx| {
                     for (relative_highlighted_range, color) in
                         layout.relative_highlighted_ranges.iter()
@@ -809,7 +888,23 @@ impl Element for TerminalElement {
                     }
                 });
 
-                if self.cursor_visible {
+                if let Some((position, marked_text)) = &layout.marked_text {
+                    // Cover the cells underneath, and leave the cursor out while composing
+                    // since the underline shows where the text goes.
+                    cx.with_z_index(3, |cx| {
+                        let text_origin = origin + *position;
+                        cx.paint_quad(fill(
+                            Bounds::new(
+                                text_origin,
+                                size(marked_text.width, layout.dimensions.line_height),
+                            ),
+                            layout.background_color,
+                        ));
+                        marked_text
+                            .paint(text_origin, layout.dimensions.line_height, cx)
+                            .ok();
+                    });
+                } else if self.cursor_visible {
                     cx.with_z_index(3, |cx| {
                         if let Some(cursor) = &layout.cursor {
                             cursor.paint(origin, cx);
@@ -837,13 +932,23 @@ impl IntoElement for TerminalElement {
 }
 
 struct TerminalInputHandler {
+    terminal_view: View<TerminalView>,
     terminal: Model<Terminal>,
     workspace: WeakView<Workspace>,
     cursor_bounds: Option<Bounds<Pixels>>,
+    /// The text being composed with an input method and where it's painted, in window
+    /// coordinates.
+    marked_text: Option<(Point<Pixels>, ShapedLine)>,
+    line_height: Pixels,
 }
 
 impl InputHandler for TerminalInputHandler {
     fn selected_text_range(&mut self, cx: &mut WindowContext) -> Option<std::ops::Range<usize>> {
+        if let Some(ime_state) = self.terminal_view.read(cx).ime_state() {
+            let len = ime_state.marked_text.encode_utf16().count();
+            return Some(ime_state.selected_range.clone().unwrap_or(len..len));
+        }
+
         if self
             .terminal
             .read(cx)
@@ -857,16 +962,21 @@ impl InputHandler for TerminalInputHandler {
         }
     }
 
-    fn marked_text_range(&mut self, _: &mut WindowContext) -> Option<std::ops::Range<usize>> {
-        None
+    fn marked_text_range(&mut self, cx: &mut WindowContext) -> Option<std::ops::Range<usize>> {
+        let ime_state = self.terminal_view.read(cx).ime_state()?;
+        Some(0..ime_state.marked_text.encode_utf16().count())
     }
 
     fn text_for_range(
         &mut self,
-        _: std::ops::Range<usize>,
-        _: &mut WindowContext,
+        range_utf16: std::ops::Range<usize>,
+        cx: &mut WindowContext,
     ) -> Option<String> {
-        None
+        let ime_state = self.terminal_view.read(cx).ime_state()?;
+        let text = &ime_state.marked_text;
+        let range = utf16_to_utf8_offset(text, range_utf16.start)
+            ..utf16_to_utf8_offset(text, range_utf16.end);
+        text.get(range).map(ToString::to_string)
     }
 
     fn replace_text_in_range(
@@ -875,6 +985,10 @@ impl InputHandler for TerminalInputHandler {
         text: &str,
         cx: &mut WindowContext,
     ) {
+        // Committing text ends any composition, whose text is replaced by what's committed.
+        self.terminal_view
+            .update(cx, |terminal_view, cx| terminal_view.clear_marked_text(cx));
+
         self.terminal.update(cx, |terminal, _| {
             terminal.input(text.into());
         });
@@ -890,21 +1004,51 @@ impl InputHandler for TerminalInputHandler {
     fn replace_and_mark_text_in_range(
         &mut self,
         _range_utf16: Option<std::ops::Range<usize>>,
-        _new_text: &str,
-        _new_selected_range: Option<std::ops::Range<usize>>,
-        _: &mut WindowContext,
+        new_text: &str,
+        new_selected_range: Option<std::ops::Range<usize>>,
+        cx: &mut WindowContext,
     ) {
+        // Nothing is sent to the shell until the composition is committed, so the marked
+        // text is always replaced as a whole.
+        self.terminal_view.update(cx, |terminal_view, cx| {
+            terminal_view.set_marked_text(new_text.to_string(), new_selected_range, cx)
+        });
     }
 
-    fn unmark_text(&mut self, _: &mut WindowContext) {}
+    fn unmark_text(&mut self, cx: &mut WindowContext) {
+        self.terminal_view
+            .update(cx, |terminal_view, cx| terminal_view.clear_marked_text(cx));
+    }
 
     fn bounds_for_range(
         &mut self,
-        _range_utf16: std::ops::Range<usize>,
+        range_utf16: std::ops::Range<usize>,
         _: &mut WindowContext,
     ) -> Option<Bounds<Pixels>> {
-        self.cursor_bounds
+        let Some((origin, marked_text)) = &self.marked_text else {
+            return self.cursor_bounds;
+        };
+
+        let start_x =
+            marked_text.x_for_index(utf16_to_utf8_offset(&marked_text.text, range_utf16.start));
+        let end_x =
+            marked_text.x_for_index(utf16_to_utf8_offset(&marked_text.text, range_utf16.end));
+        Some(Bounds::new(
+            point(origin.x + start_x, origin.y),
+            size(end_x - start_x, self.line_height),
+        ))
+    }
+}
+
+fn utf16_to_utf8_offset(text: &str, utf16_offset: usize) -> usize {
+    let mut utf16_ix = 0;
+    for (ix, ch) in text.char_indices() {
+        if utf16_ix >= utf16_offset {
+            return ix;
+        }
+        utf16_ix += ch.len_utf16();
     }
+    text.len()
 }
 
 fn is_blank(cell: &IndexedCell) -> bool {
@@ -1,19 +1,26 @@
 use std::{ops::ControlFlow, path::PathBuf, sync::Arc};
 
-use crate::TerminalView;
+use crate::{run_in_terminal, TerminalView};
 use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
 use gpui::{
-    actions, AppContext, AsyncWindowContext, Entity, EventEmitter, ExternalPaths, FocusHandle,
-    FocusableView, IntoElement, ParentElement, Pixels, Render, Styled, Subscription, Task, View,
-    ViewContext, VisualContext, WeakView, WindowContext,
+    actions, div, impl_actions, px, relative, Along, AppContext, AsyncWindowContext, Axis,
+    DragMoveEvent, Entity, EventEmitter, ExternalPaths, FocusHandle, FocusableView,
+    InteractiveElement, IntoElement, Model, ParentElement, Pixels, Render,
+    StatefulInteractiveElement, Styled, Subscription, Task, View, ViewContext, VisualContext,
+    WeakView, WindowContext,
 };
 use itertools::Itertools;
-use project::{Fs, ProjectEntryId};
+use project::{Fs, Project, ProjectEntryId};
 use search::{buffer_search::DivRegistrar, BufferSearchBar};
 use serde::{Deserialize, Serialize};
 use settings::Settings;
-use terminal::terminal_settings::{TerminalDockPosition, TerminalSettings};
-use ui::{h_flex, ButtonCommon, Clickable, IconButton, IconSize, Selectable, Tooltip};
+use strum::IntoEnumIterator;
+use terminal::terminal_settings::{TerminalDockPosition, TerminalProfile, TerminalSettings};
+use ui::{
+    h_flex, popover_menu, prelude::*, ButtonCommon, Clickable, ContextMenu, IconButton, IconSize,
+    Selectable, Tooltip,
+};
 use util::{ResultExt, TryFutureExt};
 use workspace::{
     dock::{DockPosition, Panel, PanelEvent},
@@ -26,14 +33,41 @@ use workspace::{
 use anyhow::Result;
 
 const TERMINAL_PANEL_KEY: &'static str = "TerminalPanel";
+const SPLIT_RESIZE_HANDLE_SIZE: Pixels = px(6.);
+const MIN_SPLIT_SIZE: Pixels = px(80.);
+
+actions!(
+    terminal_panel,
+    [ToggleFocus, Split, ActivateNextSplit, ActivatePrevSplit]
+);
+
+/// Runs the selected text, or the cell or line under the cursor, in a terminal.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RunInTerminal {
+    #[serde(default)]
+    pub target: RunInTerminalTarget,
+}
+
+/// The terminal that [`RunInTerminal`] runs code in.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum RunInTerminalTarget {
+    /// The active terminal of the panel, or a new one if there's none.
+    #[default]
+    ActiveTerminal,
+    /// A new terminal in the panel's active split.
+    NewTerminal,
+}
 
-actions!(terminal_panel, [ToggleFocus]);
+impl_actions!(terminal_panel, [RunInTerminal]);
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
         |workspace: &mut Workspace, _: &mut ViewContext<Workspace>| {
             workspace.register_action(TerminalPanel::new_terminal);
             workspace.register_action(TerminalPanel::open_terminal);
+            workspace.register_action(TerminalPanel::run_in_terminal);
             workspace.register_action(|workspace, _: &ToggleFocus, cx| {
                 workspace.toggle_panel_focus::<TerminalPanel>(cx);
             });
@@ -43,42 +77,138 @@ pub fn init(cx: &mut AppContext) {
 }
 
 pub struct TerminalPanel {
-    pane: View<Pane>,
+    /// The splits of the panel, each with its subscriptions to the pane's changes and events,
+    /// which are dropped along with the split.
+    panes: Vec<(View<Pane>, [Subscription; 2])>,
+    /// The relative size of each pane, in the same order as `panes`.
+    flexes: Vec<f32>,
+    active_pane_ix: usize,
     fs: Arc<dyn Fs>,
     workspace: WeakView<Workspace>,
     width: Option<Pixels>,
     height: Option<Pixels>,
     pending_serialization: Task<Option<()>>,
-    _subscriptions: Vec<Subscription>,
+    /// The number of terminals being created, which keeps activating the panel from
+    /// creating another one.
+    pending_terminals: usize,
 }
 
+#[derive(Clone, Render)]
+struct DraggedSplitHandle(usize);
+
 impl TerminalPanel {
     fn new(workspace: &Workspace, cx: &mut ViewContext<Self>) -> Self {
+        let mut this = Self {
+            panes: Vec::new(),
+            flexes: Vec::new(),
+            active_pane_ix: 0,
+            fs: workspace.app_state().fs.clone(),
+            workspace: workspace.weak_handle(),
+            pending_serialization: Task::ready(None),
+            width: None,
+            height: None,
+            pending_terminals: 0,
+        };
+        let pane = this.new_pane(workspace.weak_handle(), workspace.project().clone(), cx);
+        this.panes.push(pane);
+        this.flexes.push(1.);
+        this
+    }
+
+    fn new_pane(
+        &mut self,
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> (View<Pane>, [Subscription; 2]) {
         let terminal_panel = cx.view().downgrade();
         let pane = cx.new_view(|cx| {
-            let mut pane = Pane::new(
-                workspace.weak_handle(),
-                workspace.project().clone(),
-                Default::default(),
-                None,
-                cx,
-            );
+            let mut pane = Pane::new(workspace.clone(), project, Default::default(), None, cx);
             pane.set_can_split(false, cx);
             pane.set_can_navigate(false, cx);
             pane.display_nav_history_buttons(false);
             pane.set_render_tab_bar_buttons(cx, move |pane, cx| {
                 let terminal_panel = terminal_panel.clone();
+                let this_pane = cx.view().downgrade();
+                let new_terminal = {
+                    let terminal_panel = terminal_panel.clone();
+                    let this_pane = this_pane.clone();
+                    move |profile: Option<TerminalProfile>, cx: &mut WindowContext| {
+                        terminal_panel
+                            .update(cx, |panel, cx| {
+                                if let Some(pane) = this_pane.upgrade() {
+                                    panel.activate_pane(&pane);
+                                }
+                                panel.add_terminal(None, profile, cx).detach_and_log_err(cx)
+                            })
+                            .log_err();
+                    }
+                };
+                let profiles = TerminalSettings::get_global(cx).profiles.clone();
+                let new_terminal_button = if profiles.is_empty() {
+                    IconButton::new("plus", IconName::Plus)
+                        .icon_size(IconSize::Small)
+                        .on_click(move |_, cx| new_terminal(None, cx))
+                        .tooltip(|cx| Tooltip::text("New Terminal", cx))
+                        .into_any_element()
+                } else {
+                    popover_menu("terminal-profiles")
+                        .trigger(
+                            IconButton::new("plus", IconName::Plus)
+                                .icon_size(IconSize::Small)
+                                .tooltip(|cx| Tooltip::text("New Terminal...", cx)),
+                        )
+                        .anchor(gpui::AnchorCorner::TopRight)
+                        .menu(move |cx| {
+                            let new_terminal = new_terminal.clone();
+                            let profiles = profiles.clone();
+                            Some(ContextMenu::build(cx, move |menu, _| {
+                                let menu = menu
+                                    .entry("New Terminal", None, {
+                                        let new_terminal = new_terminal.clone();
+                                        move |cx| new_terminal(None, cx)
+                                    })
+                                    .separator();
+                                profiles.into_iter().fold(menu, |menu, profile| {
+                                    let new_terminal = new_terminal.clone();
+                                    let icon = profile_icon(&profile);
+                                    let name = SharedString::from(profile.name.clone());
+                                    menu.custom_entry(
+                                        move |_| {
+                                            h_flex()
+                                                .gap_2()
+                                                .child(
+                                                    Icon::new(icon)
+                                                        .size(IconSize::Small)
+                                                        .color(Color::Muted),
+                                                )
+                                                .child(Label::new(name.clone()))
+                                                .into_any_element()
+                                        },
+                                        move |cx| new_terminal(Some(profile.clone()), cx),
+                                    )
+                                })
+                            }))
+                        })
+                        .into_any_element()
+                };
                 h_flex()
                     .gap_2()
+                    .child(new_terminal_button)
                     .child(
-                        IconButton::new("plus", IconName::Plus)
+                        IconButton::new("split", IconName::Split)
                             .icon_size(IconSize::Small)
                             .on_click(move |_, cx| {
                                 terminal_panel
-                                    .update(cx, |panel, cx| panel.add_terminal(None, cx))
+                                    .update(cx, |panel, cx| {
+                                        if let Some(pane) = this_pane.upgrade() {
+                                            panel.activate_pane(&pane);
+                                        }
+                                        panel.split(&Split, cx)
+                                    })
                                     .log_err();
                             })
-                            .tooltip(|cx| Tooltip::text("New Terminal", cx)),
+                            .tooltip(|cx| Tooltip::for_action("Split Terminal", &Split, cx)),
                     )
                     .child({
                         let zoomed = pane.is_zoomed();
@@ -96,7 +226,6 @@ impl TerminalPanel {
                     .into_any_element()
             });
 
-            let workspace = workspace.weak_handle();
             pane.set_custom_drop_handle(cx, move |pane, dropped_item, cx| {
                 if let Some(tab) = dropped_item.downcast_ref::<DraggedTab>() {
                     let item = if &tab.pane == cx.view() {
@@ -146,20 +275,11 @@ impl TerminalPanel {
                 .update(cx, |toolbar, cx| toolbar.add_item(buffer_search_bar, cx));
             pane
         });
-        let subscriptions = vec![
+        let subscriptions = [
             cx.observe(&pane, |_, _, cx| cx.notify()),
             cx.subscribe(&pane, Self::handle_pane_event),
         ];
-        let this = Self {
-            pane,
-            fs: workspace.app_state().fs.clone(),
-            workspace: workspace.weak_handle(),
-            pending_serialization: Task::ready(None),
-            width: None,
-            height: None,
-            _subscriptions: subscriptions,
-        };
-        this
+        (pane, subscriptions)
     }
 
     pub async fn load(
@@ -177,55 +297,92 @@ impl TerminalPanel {
             .log_err()
             .flatten();
 
-        let (panel, pane, items) = workspace.update(&mut cx, |workspace, cx| {
+        let (panel, panes_with_items) = workspace.update(&mut cx, |workspace, cx| {
             let panel = cx.new_view(|cx| TerminalPanel::new(workspace, cx));
-            let items = if let Some(serialized_panel) = serialized_panel.as_ref() {
+            let mut panes_with_items = Vec::new();
+            if let Some(serialized_panel) = serialized_panel.as_ref() {
                 panel.update(cx, |panel, cx| {
                     cx.notify();
                     panel.height = serialized_panel.height;
                     panel.width = serialized_panel.width;
-                    panel.pane.update(cx, |_, cx| {
-                        serialized_panel
-                            .items
-                            .iter()
-                            .map(|item_id| {
-                                TerminalView::deserialize(
-                                    workspace.project().clone(),
-                                    workspace.weak_handle(),
-                                    workspace.database_id(),
-                                    *item_id,
-                                    cx,
-                                )
-                            })
-                            .collect::<Vec<_>>()
-                    })
+
+                    let serialized_panes = serialized_panel.panes();
+                    for (ix, serialized_pane) in serialized_panes.iter().enumerate() {
+                        let pane = if ix == 0 {
+                            panel.panes[0].0.clone()
+                        } else {
+                            let (pane, subscriptions) = panel.new_pane(
+                                workspace.weak_handle(),
+                                workspace.project().clone(),
+                                cx,
+                            );
+                            panel.panes.push((pane.clone(), subscriptions));
+                            pane
+                        };
+                        if ix == 0 {
+                            panel.flexes[0] = serialized_pane.flex;
+                        } else {
+                            panel.flexes.push(serialized_pane.flex);
+                        }
+
+                        let items = pane.update(cx, |_, cx| {
+                            serialized_pane
+                                .items
+                                .iter()
+                                .map(|item_id| {
+                                    TerminalView::deserialize(
+                                        workspace.project().clone(),
+                                        workspace.weak_handle(),
+                                        workspace.database_id(),
+                                        *item_id,
+                                        cx,
+                                    )
+                                })
+                                .collect::<Vec<_>>()
+                        });
+                        panes_with_items.push((
+                            pane.downgrade(),
+                            serialized_pane.active_item_id,
+                            items,
+                        ));
+                    }
+                    panel.active_pane_ix = serialized_panel
+                        .active_pane_ix
+                        .min(panel.panes.len().saturating_sub(1));
                 })
-            } else {
-                Default::default()
-            };
-            let pane = panel.read(cx).pane.clone();
-            (panel, pane, items)
+            }
+            (panel, panes_with_items)
         })?;
 
-        let pane = pane.downgrade();
-        let items = futures::future::join_all(items).await;
-        pane.update(&mut cx, |pane, cx| {
-            let active_item_id = serialized_panel
-                .as_ref()
-                .and_then(|panel| panel.active_item_id);
-            let mut active_ix = None;
-            for item in items {
-                if let Some(item) = item.log_err() {
-                    let item_id = item.entity_id().as_u64();
-                    pane.add_item(Box::new(item), false, false, None, cx);
-                    if Some(item_id) == active_item_id {
-                        active_ix = Some(pane.items_len() - 1);
+        for (pane, active_item_id, items) in panes_with_items {
+            let items = futures::future::join_all(items).await;
+            pane.update(&mut cx, |pane, cx| {
+                let mut active_ix = None;
+                for item in items {
+                    if let Some(item) = item.log_err() {
+                        let item_id = item.entity_id().as_u64();
+                        pane.add_item(Box::new(item), false, false, None, cx);
+                        if Some(item_id) == active_item_id {
+                            active_ix = Some(pane.items_len() - 1);
+                        }
                     }
                 }
-            }
 
-            if let Some(active_ix) = active_ix {
-                pane.activate_item(active_ix, false, false, cx)
+                if let Some(active_ix) = active_ix {
+                    pane.activate_item(active_ix, false, false, cx)
+                }
+            })?;
+        }
+
+        // Splits whose terminals all failed to restore are dropped.
+        panel.update(&mut cx, |panel, cx| {
+            let mut ix = 0;
+            while panel.panes.len() > 1 && ix < panel.panes.len() {
+                if panel.panes[ix].0.read(cx).items_len() == 0 {
+                    panel.remove_pane_at(ix);
+                } else {
+                    ix += 1;
+                }
             }
         })?;
 
@@ -234,20 +391,32 @@ impl TerminalPanel {
 
     fn handle_pane_event(
         &mut self,
-        _pane: View<Pane>,
+        pane: View<Pane>,
         event: &pane::Event,
         cx: &mut ViewContext<Self>,
     ) {
         match event {
             pane::Event::ActivateItem { .. } => self.serialize(cx),
             pane::Event::RemoveItem { .. } => self.serialize(cx),
-            pane::Event::Remove => cx.emit(PanelEvent::Close),
+            pane::Event::Remove => {
+                if self.panes.len() > 1 {
+                    if let Some(ix) = self.panes.iter().position(|(p, _)| p == &pane) {
+                        self.remove_pane_at(ix);
+                        self.active_pane()
+                            .update(cx, |pane, cx| pane.focus_active_item(cx));
+                        self.serialize(cx);
+                        cx.notify();
+                    }
+                } else {
+                    cx.emit(PanelEvent::Close)
+                }
+            }
+            pane::Event::Focus => self.activate_pane(&pane),
             pane::Event::ZoomIn => cx.emit(PanelEvent::ZoomIn),
             pane::Event::ZoomOut => cx.emit(PanelEvent::ZoomOut),
 
             pane::Event::AddItem { item } => {
                 if let Some(workspace) = self.workspace.upgrade() {
-                    let pane = self.pane.clone();
                     workspace.update(cx, |workspace, cx| item.added_to_pane(workspace, pane, cx))
                 }
             }
@@ -256,6 +425,118 @@ impl TerminalPanel {
         }
     }
 
+    fn active_pane(&self) -> &View<Pane> {
+        &self.panes[self.active_pane_ix].0
+    }
+
+    fn activate_pane(&mut self, pane: &View<Pane>) {
+        if let Some(ix) = self.panes.iter().position(|(p, _)| p == pane) {
+            self.active_pane_ix = ix;
+        }
+    }
+
+    /// Removes the split at the given index, giving its space to a neighbor, and drops its
+    /// subscriptions.
+    fn remove_pane_at(&mut self, ix: usize) {
+        self.panes.remove(ix);
+        let flex = self.flexes.remove(ix);
+        let neighbor_ix = ix.min(self.flexes.len() - 1);
+        self.flexes[neighbor_ix] += flex;
+        if self.active_pane_ix >= ix && self.active_pane_ix > 0 {
+            self.active_pane_ix -= 1;
+        }
+    }
+
+    /// Splits are laid out side by side when the panel is docked at the bottom,
+    /// and stacked when it is docked to a side.
+    fn split_axis(&self, cx: &WindowContext) -> Axis {
+        match self.position(cx) {
+            DockPosition::Bottom => Axis::Horizontal,
+            DockPosition::Left | DockPosition::Right => Axis::Vertical,
+        }
+    }
+
+    fn split(&mut self, _: &Split, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+
+        // New splits start in the directory of the terminal they were split from.
+        let working_directory = self
+            .active_pane()
+            .read(cx)
+            .active_item()
+            .and_then(|item| item.downcast::<TerminalView>())
+            .and_then(|terminal_view| {
+                terminal_view
+                    .read(cx)
+                    .terminal()
+                    .read(cx)
+                    .foreground_process_info
+                    .as_ref()
+                    .map(|info| info.cwd.clone())
+            });
+
+        self.insert_split(workspace, cx);
+        self.add_terminal(working_directory, None, cx)
+            .detach_and_log_err(cx);
+        cx.notify();
+    }
+
+    /// Adds an empty split after the active one, sharing its space, and activates it.
+    fn insert_split(&mut self, workspace: View<Workspace>, cx: &mut ViewContext<Self>) {
+        let project = workspace.read(cx).project().clone();
+        let pane = self.new_pane(workspace.downgrade(), project, cx);
+        let ix = self.active_pane_ix;
+        let flex = self.flexes[ix] / 2.;
+        self.flexes[ix] = flex;
+        self.flexes.insert(ix + 1, flex);
+        self.panes.insert(ix + 1, pane);
+        self.active_pane_ix = ix + 1;
+    }
+
+    fn activate_next_split(&mut self, _: &ActivateNextSplit, cx: &mut ViewContext<Self>) {
+        self.active_pane_ix = (self.active_pane_ix + 1) % self.panes.len();
+        self.active_pane()
+            .update(cx, |pane, cx| pane.focus_active_item(cx));
+    }
+
+    fn activate_prev_split(&mut self, _: &ActivatePrevSplit, cx: &mut ViewContext<Self>) {
+        self.active_pane_ix = self
+            .active_pane_ix
+            .checked_sub(1)
+            .unwrap_or(self.panes.len() - 1);
+        self.active_pane()
+            .update(cx, |pane, cx| pane.focus_active_item(cx));
+    }
+
+    fn resize_split(
+        &mut self,
+        handle_ix: usize,
+        event: &DragMoveEvent<DraggedSplitHandle>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let axis = self.split_axis(cx);
+        let container_size = event.bounds.size.along(axis);
+        if handle_ix + 1 >= self.flexes.len() || container_size <= px(0.) {
+            return;
+        }
+
+        let total_flex: f32 = self.flexes.iter().sum();
+        let pixels_per_flex = container_size / total_flex;
+        let split_start = event.bounds.origin.along(axis)
+            + pixels_per_flex * self.flexes[..handle_ix].iter().sum::<f32>();
+        let pair_flex = self.flexes[handle_ix] + self.flexes[handle_ix + 1];
+        let min_flex = (MIN_SPLIT_SIZE / pixels_per_flex).min(pair_flex / 2.);
+
+        let new_flex = ((event.event.position.along(axis) - split_start) / pixels_per_flex)
+            .clamp(min_flex, pair_flex - min_flex);
+        self.flexes[handle_ix] = new_flex;
+        self.flexes[handle_ix + 1] = pair_flex - new_flex;
+        self.serialize(cx);
+        cx.notify();
+    }
+
     pub fn open_terminal(
         workspace: &mut Workspace,
         action: &workspace::OpenTerminal,
@@ -266,7 +547,8 @@ impl TerminalPanel {
         };
 
         this.update(cx, |this, cx| {
-            this.add_terminal(Some(action.working_directory.clone()), cx)
+            this.add_terminal(Some(action.working_directory.clone()), None, cx)
+                .detach_and_log_err(cx)
         })
     }
 
@@ -280,60 +562,147 @@ impl TerminalPanel {
             return;
         };
 
-        this.update(cx, |this, cx| this.add_terminal(None, cx))
+        this.update(cx, |this, cx| {
+            this.add_terminal(None, None, cx).detach_and_log_err(cx)
+        })
     }
 
-    fn add_terminal(&mut self, working_directory: Option<PathBuf>, cx: &mut ViewContext<Self>) {
+    /// Runs the selected text, or the cell or line under the cursor, in the terminal
+    /// chosen by the action's target, creating one if needed.
+    fn run_in_terminal(
+        workspace: &mut Workspace,
+        action: &RunInTerminal,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let Some(editor) = workspace
+            .active_item(cx)
+            .and_then(|item| item.act_as::<Editor>(cx))
+        else {
+            return;
+        };
+        let Some(code) = run_in_terminal::code_to_run(editor.read(cx), cx) else {
+            return;
+        };
+        let Some(this) = workspace.panel::<Self>(cx) else {
+            return;
+        };
+
+        let terminal_view = match action.target {
+            RunInTerminalTarget::ActiveTerminal => this
+                .read(cx)
+                .active_pane()
+                .read(cx)
+                .active_item()
+                .and_then(|item| item.downcast::<TerminalView>()),
+            RunInTerminalTarget::NewTerminal => None,
+        };
+        // The terminal is created before the panel is opened, so that opening the panel
+        // doesn't create another one.
+        let new_terminal = terminal_view
+            .is_none()
+            .then(|| this.update(cx, |this, cx| this.add_terminal(None, None, cx)));
+        workspace.open_panel::<Self>(cx);
+
+        if let Some(terminal_view) = terminal_view {
+            let terminal = terminal_view.read(cx).terminal().clone();
+            run_in_terminal::send_to_terminal(&terminal, &code, cx);
+        } else if let Some(task) = new_terminal {
+            cx.spawn(|_, mut cx| async move {
+                let terminal_view = task.await?;
+                terminal_view.update(&mut cx, |terminal_view, cx| {
+                    let terminal = terminal_view.terminal().clone();
+                    run_in_terminal::send_to_terminal(&terminal, &code, cx);
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+    }
+
+    fn add_terminal(
+        &mut self,
+        working_directory: Option<PathBuf>,
+        profile: Option<TerminalProfile>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<View<TerminalView>>> {
         let workspace = self.workspace.clone();
+        self.pending_terminals += 1;
         cx.spawn(|this, mut cx| async move {
-            let pane = this.update(&mut cx, |this, _| this.pane.clone())?;
-            workspace.update(&mut cx, |workspace, cx| {
+            let (pane, focus) = this.update(&mut cx, |this, cx| {
+                let focus = this
+                    .panes
+                    .iter()
+                    .any(|(pane, _)| pane.read(cx).has_focus(cx));
+                (this.active_pane().clone(), focus)
+            })?;
+            let terminal = workspace.update(&mut cx, |workspace, cx| {
                 let working_directory = if let Some(working_directory) = working_directory {
                     Some(working_directory)
                 } else {
-                    let working_directory_strategy =
-                        TerminalSettings::get_global(cx).working_directory.clone();
+                    let working_directory_strategy = profile
+                        .as_ref()
+                        .and_then(|profile| profile.working_directory.clone())
+                        .unwrap_or_else(|| {
+                            TerminalSettings::get_global(cx).working_directory.clone()
+                        });
                     crate::get_working_directory(workspace, cx, working_directory_strategy)
                 };
 
                 let window = cx.window_handle();
-                if let Some(terminal) = workspace.project().update(cx, |project, cx| {
-                    project
-                        .create_terminal(working_directory, window, cx)
-                        .log_err()
-                }) {
-                    let terminal = Box::new(cx.new_view(|cx| {
+                workspace.project().update(cx, |project, cx| {
+                    project.create_terminal_with_profile(
+                        working_directory,
+                        profile.as_ref(),
+                        window,
+                        cx,
+                    )
+                })
+            });
+            let terminal_view = match terminal {
+                Ok(terminal) => terminal.await,
+                Err(error) => Err(error),
+            }
+            .and_then(|terminal| {
+                workspace.update(&mut cx, |workspace, cx| {
+                    let terminal_view = cx.new_view(|cx| {
                         TerminalView::new(
                             terminal,
                             workspace.weak_handle(),
                             workspace.database_id(),
                             cx,
                         )
-                    }));
+                    });
                     pane.update(cx, |pane, cx| {
-                        let focus = pane.has_focus(cx);
-                        pane.add_item(terminal, true, focus, None, cx);
+                        pane.add_item(Box::new(terminal_view.clone()), true, focus, None, cx);
                     });
-                }
+                    terminal_view
+                })
+            });
+            this.update(&mut cx, |this, cx| {
+                this.pending_terminals -= 1;
+                this.serialize(cx);
             })?;
-            this.update(&mut cx, |this, cx| this.serialize(cx))?;
-            anyhow::Ok(())
+            terminal_view
         })
-        .detach_and_log_err(cx);
     }
 
     fn serialize(&mut self, cx: &mut ViewContext<Self>) {
-        let items = self
-            .pane
-            .read(cx)
-            .items()
-            .map(|item| item.item_id().as_u64())
+        let panes = self
+            .panes
+            .iter()
+            .zip(self.flexes.iter())
+            .map(|((pane, _), flex)| {
+                let pane = pane.read(cx);
+                SerializedTerminalPane {
+                    items: pane
+                        .items()
+                        .map(|item| item.item_id().as_u64())
+                        .collect::<Vec<_>>(),
+                    active_item_id: pane.active_item().map(|item| item.item_id().as_u64()),
+                    flex: *flex,
+                }
+            })
             .collect::<Vec<_>>();
-        let active_item_id = self
-            .pane
-            .read(cx)
-            .active_item()
-            .map(|item| item.item_id().as_u64());
+        let active_pane_ix = self.active_pane_ix;
         let height = self.height;
         let width = self.width;
         self.pending_serialization = cx.background_executor().spawn(
@@ -342,8 +711,10 @@ impl TerminalPanel {
                     .write_kvp(
                         TERMINAL_PANEL_KEY.into(),
                         serde_json::to_string(&SerializedTerminalPanel {
-                            items,
-                            active_item_id,
+                            items: Vec::new(),
+                            active_item_id: None,
+                            panes,
+                            active_pane_ix,
                             height,
                             width,
                         })?,
@@ -372,6 +743,18 @@ fn add_paths_to_terminal(pane: &mut Pane, paths: &[PathBuf], cx: &mut ViewContex
     }
 }
 
+/// Looks up a profile's icon by the name of its file, defaulting to the terminal icon.
+fn profile_icon(profile: &TerminalProfile) -> IconName {
+    profile
+        .icon
+        .as_deref()
+        .and_then(|name| {
+            let path = format!("icons/{name}.svg");
+            IconName::iter().find(|icon| icon.path() == path)
+        })
+        .unwrap_or(IconName::Terminal)
+}
+
 impl EventEmitter<PanelEvent> for TerminalPanel {}
 
 impl Render for TerminalPanel {
@@ -379,7 +762,7 @@ impl Render for TerminalPanel {
         let mut registrar = DivRegistrar::new(
             |panel, cx| {
                 panel
-                    .pane
+                    .active_pane()
                     .read(cx)
                     .toolbar()
                     .read(cx)
@@ -388,13 +771,83 @@ impl Render for TerminalPanel {
             cx,
         );
         BufferSearchBar::register(&mut registrar);
-        registrar.into_div().size_full().child(self.pane.clone())
+
+        let axis = self.split_axis(cx);
+        let total_flex: f32 = self.flexes.iter().sum();
+        let pane_count = self.panes.len();
+        let border_color = cx.theme().colors().border;
+        registrar
+            .into_div()
+            .size_full()
+            .on_action(cx.listener(Self::split))
+            .on_action(cx.listener(Self::activate_next_split))
+            .on_action(cx.listener(Self::activate_prev_split))
+            .child(
+                div()
+                    .id("terminal-splits")
+                    .size_full()
+                    .flex()
+                    .map(|this| match axis {
+                        Axis::Horizontal => this.flex_row(),
+                        Axis::Vertical => this.flex_col(),
+                    })
+                    .on_drag_move(cx.listener(
+                        |this, event: &DragMoveEvent<DraggedSplitHandle>, cx| {
+                            let handle_ix = event.drag(cx).0;
+                            this.resize_split(handle_ix, event, cx);
+                        },
+                    ))
+                    .children(self.panes.iter().zip(self.flexes.iter()).enumerate().map(
+                        |(ix, ((pane, _), flex))| {
+                            let fraction = relative(*flex / total_flex);
+                            div()
+                                .relative()
+                                .map(|this| match axis {
+                                    Axis::Horizontal => this.h_full().w(fraction),
+                                    Axis::Vertical => this.w_full().h(fraction),
+                                })
+                                .when(ix > 0, |this| match axis {
+                                    Axis::Horizontal => this.border_l().border_color(border_color),
+                                    Axis::Vertical => this.border_t().border_color(border_color),
+                                })
+                                .child(pane.clone())
+                                .when(ix + 1 < pane_count, |this| {
+                                    this.child(render_split_resize_handle(ix, axis))
+                                })
+                        },
+                    )),
+            )
     }
 }
 
+fn render_split_resize_handle(ix: usize, axis: Axis) -> impl IntoElement {
+    div()
+        .id(("terminal-split-resize-handle", ix))
+        .on_drag(DraggedSplitHandle(ix), |handle, cx| {
+            cx.stop_propagation();
+            cx.new_view(|_| handle.clone())
+        })
+        .absolute()
+        .z_index(1)
+        .map(|this| match axis {
+            Axis::Horizontal => this
+                .top_0()
+                .right(-SPLIT_RESIZE_HANDLE_SIZE / 2.)
+                .h_full()
+                .w(SPLIT_RESIZE_HANDLE_SIZE)
+                .cursor_col_resize(),
+            Axis::Vertical => this
+                .left_0()
+                .bottom(-SPLIT_RESIZE_HANDLE_SIZE / 2.)
+                .w_full()
+                .h(SPLIT_RESIZE_HANDLE_SIZE)
+                .cursor_row_resize(),
+        })
+}
+
 impl FocusableView for TerminalPanel {
     fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
-        self.pane.focus_handle(cx)
+        self.active_pane().focus_handle(cx)
     }
 }
 
@@ -442,21 +895,27 @@ impl Panel for TerminalPanel {
     }
 
     fn is_zoomed(&self, cx: &WindowContext) -> bool {
-        self.pane.read(cx).is_zoomed()
+        self.panes.iter().any(|(pane, _)| pane.read(cx).is_zoomed())
     }
 
     fn set_zoomed(&mut self, zoomed: bool, cx: &mut ViewContext<Self>) {
-        self.pane.update(cx, |pane, cx| pane.set_zoomed(zoomed, cx));
+        for (pane, _) in &self.panes {
+            pane.update(cx, |pane, cx| pane.set_zoomed(zoomed, cx));
+        }
     }
 
     fn set_active(&mut self, active: bool, cx: &mut ViewContext<Self>) {
-        if active && self.pane.read(cx).items_len() == 0 {
-            self.add_terminal(None, cx)
+        if active && self.pending_terminals == 0 && self.active_pane().read(cx).items_len() == 0 {
+            self.add_terminal(None, None, cx).detach_and_log_err(cx)
         }
     }
 
     fn icon_label(&self, cx: &WindowContext) -> Option<String> {
-        let count = self.pane.read(cx).items_len();
+        let count = self
+            .panes
+            .iter()
+            .map(|(pane, _)| pane.read(cx).items_len())
+            .sum::<usize>();
         if count == 0 {
             None
         } else {
@@ -483,8 +942,113 @@ impl Panel for TerminalPanel {
 
 #[derive(Serialize, Deserialize)]
 struct SerializedTerminalPanel {
+    /// Terminals of a panel serialized before splits were supported.
+    #[serde(default)]
     items: Vec<u64>,
+    #[serde(default)]
     active_item_id: Option<u64>,
+    #[serde(default)]
+    panes: Vec<SerializedTerminalPane>,
+    #[serde(default)]
+    active_pane_ix: usize,
     width: Option<Pixels>,
     height: Option<Pixels>,
 }
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct SerializedTerminalPane {
+    items: Vec<u64>,
+    active_item_id: Option<u64>,
+    flex: f32,
+}
+
+impl SerializedTerminalPanel {
+    fn panes(&self) -> Vec<SerializedTerminalPane> {
+        if self.panes.is_empty() {
+            vec![SerializedTerminalPane {
+                items: self.items.clone(),
+                active_item_id: self.active_item_id,
+                flex: 1.,
+            }]
+        } else {
+            self.panes.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{TestAppContext, VisualTestContext};
+
+    #[gpui::test]
+    async fn test_split_and_remove_panes(cx: &mut TestAppContext) {
+        let (_, workspace) = crate::tests::init_test(cx).await;
+        cx.update(editor::init);
+        let cx = &mut VisualTestContext::from_window(cx.windows()[0], cx);
+        let panel = workspace.update(cx, |workspace, cx| {
+            cx.new_view(|cx| TerminalPanel::new(workspace, cx))
+        });
+
+        panel.update(cx, |panel, cx| {
+            panel.insert_split(workspace.clone(), cx);
+            panel.insert_split(workspace.clone(), cx);
+            assert_eq!(panel.panes.len(), 3);
+            assert_eq!(panel.flexes, [0.5, 0.25, 0.25]);
+            assert_eq!(panel.active_pane_ix, 2);
+
+            panel.remove_pane_at(1);
+            assert_eq!(panel.panes.len(), 2);
+            assert_eq!(panel.flexes, [0.5, 0.5]);
+            assert_eq!(panel.active_pane_ix, 1);
+
+            let first_pane = panel.panes[0].0.clone();
+            panel.activate_pane(&first_pane);
+            panel.remove_pane_at(0);
+            assert_eq!(panel.panes.len(), 1);
+            assert_eq!(panel.flexes, [1.]);
+            assert_eq!(panel.active_pane_ix, 0);
+        });
+    }
+
+    #[test]
+    fn test_serialized_panes() {
+        let panel = SerializedTerminalPanel {
+            items: Vec::new(),
+            active_item_id: None,
+            panes: vec![
+                SerializedTerminalPane {
+                    items: vec![1, 2],
+                    active_item_id: Some(2),
+                    flex: 0.75,
+                },
+                SerializedTerminalPane {
+                    items: vec![3],
+                    active_item_id: Some(3),
+                    flex: 0.25,
+                },
+            ],
+            active_pane_ix: 1,
+            width: None,
+            height: None,
+        };
+        let json = serde_json::to_string(&panel).unwrap();
+        let deserialized = serde_json::from_str::<SerializedTerminalPanel>(&json).unwrap();
+        assert_eq!(deserialized.panes(), panel.panes);
+        assert_eq!(deserialized.active_pane_ix, 1);
+
+        // Panels serialized before splits were supported have a single pane.
+        let deserialized = serde_json::from_str::<SerializedTerminalPanel>(
+            r#"{"items":[4,5],"active_item_id":5,"width":null,"height":null}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            deserialized.panes(),
+            [SerializedTerminalPane {
+                items: vec![4, 5],
+                active_item_id: Some(5),
+                flex: 1.,
+            }]
+        );
+    }
+}
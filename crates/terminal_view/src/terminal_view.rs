@@ -1,4 +1,5 @@
 mod persistence;
+mod run_in_terminal;
 pub mod terminal_element;
 pub mod terminal_panel;
 
@@ -17,14 +18,15 @@ use terminal::{
         term::{search::RegexSearch, TermMode},
     },
     terminal_settings::{TerminalBlink, TerminalSettings, WorkingDirectory},
-    Clear, Copy, Event, MaybeNavigationTarget, Paste, ShowCharacterPalette, Terminal,
+    Clear, Copy, Event, MaybeNavigationTarget, Paste, RerunLastCommand, ScrollToNextCommand,
+    ScrollToPreviousCommand, ShowCharacterPalette, Terminal,
 };
 use terminal_element::TerminalElement;
 use ui::{h_flex, prelude::*, ContextMenu, Icon, IconName, Label};
 use util::{paths::PathLikeWithPosition, ResultExt};
 use workspace::{
     item::{BreadcrumbText, Item, ItemEvent},
-    notifications::NotifyResultExt,
+    notifications::NotifyTaskExt,
     register_deserializable_item,
     searchable::{SearchEvent, SearchOptions, SearchableItem, SearchableItemHandle},
     CloseActiveItem, NewCenterTerminal, OpenVisible, Pane, ToolbarItemLocation, Workspace,
@@ -38,13 +40,14 @@ use settings::Settings;
 use smol::Timer;
 
 use std::{
-    ops::RangeInclusive,
+    ops::{Range, RangeInclusive},
     path::{Path, PathBuf},
     sync::Arc,
     time::Duration,
 };
 
 const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+const SCROLLBACK_SERIALIZATION_INTERVAL: Duration = Duration::from_secs(2);
 
 ///Event to transmit the scroll from the element to the view
 #[derive(Clone, Debug, PartialEq)]
@@ -56,6 +59,15 @@ pub struct SendText(String);
 #[derive(Clone, Debug, Default, Deserialize, PartialEq)]
 pub struct SendKeystroke(String);
 
+/// Text that's being composed with an input method, which is shown inline at the cursor
+/// until it's committed or cancelled.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ImeState {
+    pub marked_text: String,
+    /// The part of the marked text the input method has selected, in UTF-16 code units.
+    pub selected_range: Option<Range<usize>>,
+}
+
 impl_actions!(terminal, [SendText, SendKeystroke]);
 
 pub fn init(cx: &mut AppContext) {
@@ -85,6 +97,9 @@ pub struct TerminalView {
     blink_epoch: usize,
     can_navigate_to_selected_word: bool,
     workspace_id: WorkspaceId,
+    restored_title: Option<String>,
+    scrollback_serialization_pending: bool,
+    ime_state: Option<ImeState>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -110,24 +125,25 @@ impl TerminalView {
             get_working_directory(workspace, cx, strategy.working_directory.clone());
 
         let window = cx.window_handle();
-        let terminal = workspace
-            .project()
-            .update(cx, |project, cx| {
-                project.create_terminal(working_directory, window, cx)
+        let terminal = workspace.project().update(cx, |project, cx| {
+            project.create_terminal(working_directory, window, cx)
+        });
+
+        cx.spawn(|workspace, mut cx| async move {
+            let terminal = terminal.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let view = cx.new_view(|cx| {
+                    TerminalView::new(
+                        terminal,
+                        workspace.weak_handle(),
+                        workspace.database_id(),
+                        cx,
+                    )
+                });
+                workspace.add_item(Box::new(view), cx)
             })
-            .notify_err(workspace, cx);
-
-        if let Some(terminal) = terminal {
-            let view = cx.new_view(|cx| {
-                TerminalView::new(
-                    terminal,
-                    workspace.weak_handle(),
-                    workspace.database_id(),
-                    cx,
-                )
-            });
-            workspace.add_item(Box::new(view), cx)
-        }
+        })
+        .detach_and_notify_err(cx);
     }
 
     pub fn new(
@@ -147,6 +163,7 @@ impl TerminalView {
                 cx.emit(Event::Wakeup);
                 cx.emit(ItemEvent::UpdateTab);
                 cx.emit(SearchEvent::MatchesInvalidated);
+                this.schedule_scrollback_serialization(cx);
             }
 
             Event::Bell => {
@@ -157,16 +174,19 @@ impl TerminalView {
             Event::BlinkChanged => this.blinking_on = !this.blinking_on,
 
             Event::TitleChanged => {
+                this.restored_title = None;
                 cx.emit(ItemEvent::UpdateTab);
-                if let Some(foreground_info) = &this.terminal().read(cx).foreground_process_info {
+                let terminal = this.terminal().read(cx);
+                if let Some(foreground_info) = &terminal.foreground_process_info {
                     let cwd = foreground_info.cwd.clone();
+                    let title = terminal.title(false);
 
                     let item_id = cx.entity_id();
                     let workspace_id = this.workspace_id;
                     cx.background_executor()
                         .spawn(async move {
                             TERMINAL_DB
-                                .save_working_directory(item_id.as_u64(), workspace_id, cwd)
+                                .save_working_directory(item_id.as_u64(), workspace_id, cwd, title)
                                 .await
                                 .log_err();
                         })
@@ -178,7 +198,9 @@ impl TerminalView {
                 this.can_navigate_to_selected_word = match maybe_navigation_target {
                     Some(MaybeNavigationTarget::Url(_)) => true,
                     Some(MaybeNavigationTarget::PathLike(maybe_path)) => {
-                        !possible_open_targets(&workspace, maybe_path, cx).is_empty()
+                        let cwd = this.terminal_cwd(cx);
+                        !possible_open_targets(&workspace, cwd.as_deref(), maybe_path, cx)
+                            .is_empty()
                     }
                     None => false,
                 }
@@ -191,7 +213,9 @@ impl TerminalView {
                     if !this.can_navigate_to_selected_word {
                         return;
                     }
-                    let potential_abs_paths = possible_open_targets(&workspace, maybe_path, cx);
+                    let cwd = this.terminal_cwd(cx);
+                    let potential_abs_paths =
+                        possible_open_targets(&workspace, cwd.as_deref(), maybe_path, cx);
                     if let Some(path) = potential_abs_paths.into_iter().next() {
                         let task_workspace = workspace.clone();
                         cx.spawn(|_, mut cx| async move {
@@ -292,6 +316,9 @@ impl TerminalView {
             blink_epoch: 0,
             can_navigate_to_selected_word: false,
             workspace_id,
+            restored_title: None,
+            scrollback_serialization_pending: false,
+            ime_state: None,
             _subscriptions: vec![focus_in, focus_out],
         }
     }
@@ -313,6 +340,57 @@ impl TerminalView {
         cx.emit(Event::Wakeup);
     }
 
+    fn terminal_cwd(&self, cx: &AppContext) -> Option<PathBuf> {
+        self.terminal
+            .read(cx)
+            .foreground_process_info
+            .as_ref()
+            .map(|info| info.cwd.clone())
+    }
+
+    ///The tab title, falling back to the title saved with the workspace
+    ///until the restored terminal reports its own
+    fn title(&self, truncate: bool, cx: &AppContext) -> String {
+        match &self.restored_title {
+            Some(title) => title.clone(),
+            None => self.terminal.read(cx).title(truncate),
+        }
+    }
+
+    ///Saves the terminal's scrollback at most once per interval, if the user opted in
+    fn schedule_scrollback_serialization(&mut self, cx: &mut ViewContext<Self>) {
+        if self.scrollback_serialization_pending
+            || !TerminalSettings::get_global(cx).persist_scrollback
+        {
+            return;
+        }
+
+        self.scrollback_serialization_pending = true;
+        cx.spawn(|this, mut cx| async move {
+            Timer::after(SCROLLBACK_SERIALIZATION_INTERVAL).await;
+            this.update(&mut cx, |this, cx| {
+                this.scrollback_serialization_pending = false;
+                let max_lines = TerminalSettings::get_global(cx).max_persisted_scrollback_lines;
+                let Some(scrollback) = this.terminal.read(cx).scrollback_text(max_lines) else {
+                    return;
+                };
+
+                let item_id = cx.entity_id().as_u64();
+                let workspace_id = this.workspace_id;
+                cx.background_executor()
+                    .spawn(async move {
+                        TERMINAL_DB
+                            .save_scrollback(item_id, workspace_id, scrollback)
+                            .await
+                            .log_err();
+                    })
+                    .detach();
+            })
+            .ok();
+        })
+        .detach();
+    }
+
     pub fn deploy_context_menu(
         &mut self,
         position: gpui::Point<Pixels>,
@@ -367,6 +445,54 @@ impl TerminalView {
         cx.notify();
     }
 
+    fn scroll_to_previous_command(
+        &mut self,
+        _: &ScrollToPreviousCommand,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_previous_command());
+        cx.notify();
+    }
+
+    fn scroll_to_next_command(&mut self, _: &ScrollToNextCommand, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.scroll_to_next_command());
+        cx.notify();
+    }
+
+    fn rerun_last_command(&mut self, _: &RerunLastCommand, cx: &mut ViewContext<Self>) {
+        self.terminal
+            .update(cx, |term, _| term.rerun_last_command());
+    }
+
+    pub fn ime_state(&self) -> Option<&ImeState> {
+        self.ime_state.as_ref()
+    }
+
+    pub fn set_marked_text(
+        &mut self,
+        text: String,
+        selected_range: Option<Range<usize>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if text.is_empty() {
+            self.clear_marked_text(cx);
+            return;
+        }
+        self.ime_state = Some(ImeState {
+            marked_text: text,
+            selected_range,
+        });
+        cx.notify();
+    }
+
+    pub fn clear_marked_text(&mut self, cx: &mut ViewContext<Self>) {
+        if self.ime_state.take().is_some() {
+            cx.notify();
+        }
+    }
+
     pub fn should_show_cursor(&self, focused: bool, cx: &mut gpui::ViewContext<Self>) -> bool {
         //Don't blink the cursor when not focused, blinking is disabled, or paused
         if !focused
@@ -554,8 +680,11 @@ impl TerminalView {
     }
 }
 
+/// Resolves a path printed in the terminal, trying relative paths against the terminal's
+/// working directory first, and then against each worktree.
 fn possible_open_targets(
     workspace: &WeakView<Workspace>,
+    cwd: Option<&Path>,
     maybe_path: &String,
     cx: &mut ViewContext<'_, TerminalView>,
 ) -> Vec<PathLikeWithPosition<PathBuf>> {
@@ -576,15 +705,20 @@ fn possible_open_targets(
         } else {
             Vec::new()
         }
-    } else if let Some(workspace) = workspace.upgrade() {
-        workspace.update(cx, |workspace, cx| {
-            workspace
-                .worktrees(cx)
-                .map(|worktree| worktree.read(cx).abs_path().join(&maybe_path))
-                .collect()
-        })
     } else {
-        Vec::new()
+        let mut potential_abs_paths = cwd
+            .map(|cwd| vec![cwd.join(&maybe_path)])
+            .unwrap_or_default();
+        if let Some(workspace) = workspace.upgrade() {
+            workspace.update(cx, |workspace, cx| {
+                potential_abs_paths.extend(
+                    workspace
+                        .worktrees(cx)
+                        .map(|worktree| worktree.read(cx).abs_path().join(&maybe_path)),
+                );
+            });
+        }
+        potential_abs_paths
     };
 
     potential_abs_paths
@@ -599,12 +733,34 @@ fn possible_open_targets(
 }
 
 pub fn regex_search_for_query(query: &project::search::SearchQuery) -> Option<RegexSearch> {
-    let query = query.as_str();
-    if query == "." {
+    let pattern = search_pattern_for_query(query)?;
+    let searcher = RegexSearch::new(&pattern);
+    searcher.ok()
+}
+
+///Translates a search query into a pattern for Alacritty's regex engine.
+///Alacritty's DFAs are smart-case by default, so case sensitivity is always set explicitly,
+///and only ASCII word boundaries are supported by them.
+fn search_pattern_for_query(query: &project::search::SearchQuery) -> Option<String> {
+    let query_str = query.as_str();
+    if query_str.is_empty() || query_str == "." {
         return None;
     }
-    let searcher = RegexSearch::new(&query);
-    searcher.ok()
+
+    let mut pattern = if query.is_regex() {
+        query_str.to_string()
+    } else {
+        regex::escape(query_str)
+    };
+    if query.whole_word() {
+        pattern = format!("(?-u:\\b)(?:{pattern})(?-u:\\b)");
+    }
+    let case_flag = if query.case_sensitive() {
+        "(?-i)"
+    } else {
+        "(?i)"
+    };
+    Some(format!("{case_flag}{pattern}"))
 }
 
 impl TerminalView {
@@ -631,6 +787,7 @@ impl TerminalView {
         self.terminal.update(cx, |terminal, _| {
             terminal.focus_out();
         });
+        self.ime_state = None;
         cx.notify();
     }
 }
@@ -651,6 +808,9 @@ impl Render for TerminalView {
             .on_action(cx.listener(TerminalView::copy))
             .on_action(cx.listener(TerminalView::paste))
             .on_action(cx.listener(TerminalView::clear))
+            .on_action(cx.listener(TerminalView::scroll_to_previous_command))
+            .on_action(cx.listener(TerminalView::scroll_to_next_command))
+            .on_action(cx.listener(TerminalView::rerun_last_command))
             .on_action(cx.listener(TerminalView::show_character_palette))
             .on_action(cx.listener(TerminalView::select_all))
             .on_key_down(cx.listener(Self::key_down))
@@ -666,6 +826,7 @@ impl Render for TerminalView {
             .child(
                 // TODO: Oddly this wrapper div is needed for TerminalElement to not steal events from the context menu
                 div().size_full().child(TerminalElement::new(
+                    cx.view().clone(),
                     terminal_handle,
                     self.workspace.clone(),
                     self.focus_handle.clone(),
@@ -687,7 +848,7 @@ impl Item for TerminalView {
     type Event = ItemEvent;
 
     fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
-        Some(self.terminal().read(cx).title(false).into())
+        Some(self.title(false, cx).into())
     }
 
     fn tab_content(
@@ -696,7 +857,7 @@ impl Item for TerminalView {
         selected: bool,
         cx: &WindowContext,
     ) -> AnyElement {
-        let title = self.terminal().read(cx).title(true);
+        let title = self.title(true, cx);
         h_flex()
             .gap_2()
             .child(Icon::new(IconName::Terminal))
@@ -767,6 +928,20 @@ impl Item for TerminalView {
     ) -> Task<anyhow::Result<View<Self>>> {
         let window = cx.window_handle();
         cx.spawn(|pane, mut cx| async move {
+            let title = TERMINAL_DB
+                .get_title(item_id, workspace_id)
+                .log_err()
+                .flatten();
+            let scrollback = cx
+                .update(|cx| TerminalSettings::get_global(cx).persist_scrollback)
+                .ok()
+                .filter(|persist_scrollback| *persist_scrollback)
+                .and_then(|_| {
+                    TERMINAL_DB
+                        .get_scrollback(item_id, workspace_id)
+                        .log_err()
+                        .flatten()
+                });
             let cwd = TERMINAL_DB
                 .get_working_directory(item_id, workspace_id)
                 .log_err()
@@ -785,11 +960,22 @@ impl Item for TerminalView {
                     .flatten()
                 });
 
-            let terminal = project.update(&mut cx, |project, cx| {
-                project.create_terminal(cwd, window, cx)
-            })??;
+            let terminal = project
+                .update(&mut cx, |project, cx| {
+                    project.create_terminal(cwd, window, cx)
+                })?
+                .await?;
+            if let Some(scrollback) = scrollback {
+                terminal.update(&mut cx, |terminal, _| {
+                    terminal.restore_scrollback(&scrollback)
+                })?;
+            }
             pane.update(&mut cx, |_, cx| {
-                cx.new_view(|cx| TerminalView::new(terminal, workspace, workspace_id, cx))
+                cx.new_view(|cx| {
+                    let mut view = TerminalView::new(terminal, workspace, workspace_id, cx);
+                    view.restored_title = title;
+                    view
+                })
             })
         })
     }
@@ -815,9 +1001,9 @@ impl SearchableItem for TerminalView {
 
     fn supported_options() -> SearchOptions {
         SearchOptions {
-            case: false,
-            word: false,
-            regex: false,
+            case: true,
+            word: true,
+            regex: true,
             replacement: false,
         }
     }
@@ -1077,6 +1263,47 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_search_pattern_for_query() {
+        let text = |query: &str, whole_word, case_sensitive| {
+            SearchQuery::text(
+                query,
+                whole_word,
+                case_sensitive,
+                false,
+                Vec::new(),
+                Vec::new(),
+            )
+            .unwrap()
+        };
+        let regex = |query: &str, whole_word, case_sensitive| {
+            SearchQuery::regex(
+                query,
+                whole_word,
+                case_sensitive,
+                false,
+                Vec::new(),
+                Vec::new(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(search_pattern_for_query(&text("", false, false)), None);
+        assert_eq!(
+            search_pattern_for_query(&text("a.b", false, false)).as_deref(),
+            Some("(?i)a\\.b")
+        );
+        assert_eq!(
+            search_pattern_for_query(&text("Foo", true, true)).as_deref(),
+            Some("(?-i)(?-u:\\b)(?:Foo)(?-u:\\b)")
+        );
+        assert_eq!(
+            search_pattern_for_query(&regex("fo+|ba[rz]", false, true)).as_deref(),
+            Some("(?-i)fo+|ba[rz]")
+        );
+        assert!(regex_search_for_query(&regex("error: .*", true, false)).is_some());
+    }
+
     /// Creates a worktree with 1 file: /root.txt
     pub async fn init_test(cx: &mut TestAppContext) -> (Model<Project>, View<Workspace>) {
         let params = cx.update(AppState::test);
@@ -0,0 +1,242 @@
+use anyhow::{anyhow, Result};
+use std::borrow::Cow;
+
+const UTF_8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
+const UTF_16LE_BOM: &[u8] = &[0xFF, 0xFE];
+const UTF_16BE_BOM: &[u8] = &[0xFE, 0xFF];
+
+/// How many bytes are inspected when guessing the encoding of a file.
+const DETECTION_LEN: usize = 64 * 1024;
+
+/// The character encoding of a file's contents on disk.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    /// UTF-16 in little-endian byte order, which is saved with a byte order mark.
+    Utf16Le,
+    /// UTF-16 in big-endian byte order, which is saved with a byte order mark.
+    Utf16Be,
+    /// ISO 8859-1, in which every byte is the code point of the character.
+    Latin1,
+    ShiftJis,
+    Gbk,
+}
+
+impl Encoding {
+    pub const ALL: [Encoding; 6] = [
+        Encoding::Utf8,
+        Encoding::Utf16Le,
+        Encoding::Utf16Be,
+        Encoding::Latin1,
+        Encoding::ShiftJis,
+        Encoding::Gbk,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16 LE",
+            Encoding::Utf16Be => "UTF-16 BE",
+            Encoding::Latin1 => "Latin-1",
+            Encoding::ShiftJis => "Shift-JIS",
+            Encoding::Gbk => "GBK",
+        }
+    }
+
+    /// Guesses the encoding of a file's contents from its byte order mark or,
+    /// failing that, from the patterns of bytes in them. Returns `None` for
+    /// contents that look binary.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(UTF_8_BOM) {
+            return Some(Encoding::Utf8);
+        } else if bytes.starts_with(UTF_16LE_BOM) {
+            return Some(Encoding::Utf16Le);
+        } else if bytes.starts_with(UTF_16BE_BOM) {
+            return Some(Encoding::Utf16Be);
+        }
+
+        let sample = &bytes[..bytes.len().min(DETECTION_LEN)];
+        if let Some(encoding) = detect_utf16_without_bom(sample) {
+            return Some(encoding);
+        }
+        // None of the remaining encodings use zero bytes for text.
+        if sample.contains(&0) {
+            return None;
+        }
+        if std::str::from_utf8(bytes).is_ok() {
+            return Some(Encoding::Utf8);
+        }
+        // Accented letters in Western text are usually surrounded by ASCII,
+        // while multi-byte encodings produce runs of non-ASCII bytes.
+        if !sample
+            .windows(2)
+            .any(|pair| !pair[0].is_ascii() && !pair[1].is_ascii())
+        {
+            return Some(Encoding::Latin1);
+        }
+
+        let shift_jis =
+            encoding_rs::SHIFT_JIS.decode_without_bom_handling_and_without_replacement(bytes);
+        let gbk = encoding_rs::GBK.decode_without_bom_handling_and_without_replacement(bytes);
+        match (shift_jis, gbk) {
+            // Many byte sequences are valid in both, but only Japanese text
+            // is likely to contain kana.
+            (Some(shift_jis), Some(_)) if !contains_kana(&shift_jis) => Some(Encoding::Gbk),
+            (Some(_), _) => Some(Encoding::ShiftJis),
+            (None, Some(_)) => Some(Encoding::Gbk),
+            (None, None) => Some(Encoding::Latin1),
+        }
+    }
+
+    /// Decodes a file's contents, skipping any byte order mark for UTF-16 and
+    /// replacing invalid sequences with U+FFFD.
+    pub fn decode(&self, bytes: Vec<u8>) -> String {
+        match self {
+            Encoding::Utf8 => match String::from_utf8(bytes) {
+                Ok(text) => text,
+                Err(error) => String::from_utf8_lossy(error.as_bytes()).into_owned(),
+            },
+            Encoding::Utf16Le => {
+                let bytes = bytes.strip_prefix(UTF_16LE_BOM).unwrap_or(&bytes);
+                let (text, _) = encoding_rs::UTF_16LE.decode_without_bom_handling(bytes);
+                text.into_owned()
+            }
+            Encoding::Utf16Be => {
+                let bytes = bytes.strip_prefix(UTF_16BE_BOM).unwrap_or(&bytes);
+                let (text, _) = encoding_rs::UTF_16BE.decode_without_bom_handling(bytes);
+                text.into_owned()
+            }
+            Encoding::Latin1 => bytes.iter().map(|&byte| byte as char).collect(),
+            Encoding::ShiftJis => {
+                let (text, _) = encoding_rs::SHIFT_JIS.decode_without_bom_handling(&bytes);
+                text.into_owned()
+            }
+            Encoding::Gbk => {
+                let (text, _) = encoding_rs::GBK.decode_without_bom_handling(&bytes);
+                text.into_owned()
+            }
+        }
+    }
+
+    /// Encodes text to be written to a file, failing if it contains characters
+    /// that this encoding can't represent.
+    pub fn encode<'a>(&self, text: &'a str) -> Result<Cow<'a, [u8]>> {
+        let unencodable = || anyhow!("the text can't be encoded as {}", self.name());
+        match self {
+            Encoding::Utf8 => Ok(Cow::Borrowed(text.as_bytes())),
+            Encoding::Utf16Le => Ok(Cow::Owned(
+                UTF_16LE_BOM
+                    .iter()
+                    .copied()
+                    .chain(text.encode_utf16().flat_map(u16::to_le_bytes))
+                    .collect(),
+            )),
+            Encoding::Utf16Be => Ok(Cow::Owned(
+                UTF_16BE_BOM
+                    .iter()
+                    .copied()
+                    .chain(text.encode_utf16().flat_map(u16::to_be_bytes))
+                    .collect(),
+            )),
+            Encoding::Latin1 => text
+                .chars()
+                .map(|char| u8::try_from(char).ok())
+                .collect::<Option<Vec<_>>>()
+                .map(Cow::Owned)
+                .ok_or_else(unencodable),
+            Encoding::ShiftJis | Encoding::Gbk => {
+                let encoding = if *self == Encoding::ShiftJis {
+                    encoding_rs::SHIFT_JIS
+                } else {
+                    encoding_rs::GBK
+                };
+                let (bytes, _, had_unmappable_characters) = encoding.encode(text);
+                if had_unmappable_characters {
+                    Err(unencodable())
+                } else {
+                    Ok(bytes)
+                }
+            }
+        }
+    }
+}
+
+/// Recognizes UTF-16 text without a byte order mark by the zero bytes that
+/// ASCII characters have in every other position.
+fn detect_utf16_without_bom(bytes: &[u8]) -> Option<Encoding> {
+    if bytes.len() < 2 || bytes.len() % 2 != 0 {
+        return None;
+    }
+
+    let pairs = bytes.len() / 2;
+    let (mut even_zeros, mut odd_zeros) = (0, 0);
+    for pair in bytes.chunks_exact(2) {
+        even_zeros += (pair[0] == 0) as usize;
+        odd_zeros += (pair[1] == 0) as usize;
+    }
+    if odd_zeros * 2 > pairs && even_zeros == 0 {
+        Some(Encoding::Utf16Le)
+    } else if even_zeros * 2 > pairs && odd_zeros == 0 {
+        Some(Encoding::Utf16Be)
+    } else {
+        None
+    }
+}
+
+/// Whether the text contains hiragana or katakana. Half-width katakana are
+/// left out, since their bytes are common in GBK text.
+fn contains_kana(text: &str) -> bool {
+    text.chars()
+        .any(|char| matches!(char, '\u{3040}'..='\u{30FF}'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_encoding() {
+        assert_eq!(Encoding::detect(b"abc\n"), Some(Encoding::Utf8));
+        assert_eq!(Encoding::detect("héllo".as_bytes()), Some(Encoding::Utf8));
+        assert_eq!(Encoding::detect(b"\xEF\xBB\xBFabc"), Some(Encoding::Utf8));
+        assert_eq!(Encoding::detect(b"\xFF\xFEa\0b\0"), Some(Encoding::Utf16Le));
+        assert_eq!(Encoding::detect(b"\xFE\xFF\0a\0b"), Some(Encoding::Utf16Be));
+        assert_eq!(Encoding::detect(b"a\0b\0c\0"), Some(Encoding::Utf16Le));
+        assert_eq!(Encoding::detect(b"\0a\0b\0c"), Some(Encoding::Utf16Be));
+        assert_eq!(Encoding::detect(b"caf\xE9"), Some(Encoding::Latin1));
+        assert_eq!(
+            Encoding::detect(b"\x7F\x45\x4C\x46\x02\x01\x01\0\0\0"),
+            None
+        );
+
+        let japanese = Encoding::ShiftJis.encode("こんにちは、世界").unwrap();
+        assert_eq!(Encoding::detect(&japanese), Some(Encoding::ShiftJis));
+        let chinese = Encoding::Gbk.encode("你好，世界").unwrap();
+        assert_eq!(Encoding::detect(&chinese), Some(Encoding::Gbk));
+    }
+
+    #[test]
+    fn test_encoding_round_trip() {
+        let text = "héllo wörld\n";
+        for encoding in [
+            Encoding::Utf8,
+            Encoding::Utf16Le,
+            Encoding::Utf16Be,
+            Encoding::Latin1,
+        ] {
+            let bytes = encoding.encode(text).unwrap().into_owned();
+            assert_eq!(Encoding::detect(&bytes), Some(encoding));
+            assert_eq!(encoding.decode(bytes), text);
+        }
+
+        let text = "日本語のテキスト";
+        for encoding in [Encoding::ShiftJis, Encoding::Gbk] {
+            let bytes = encoding.encode(text).unwrap().into_owned();
+            assert_eq!(encoding.decode(bytes), text);
+        }
+
+        assert!(Encoding::Latin1.encode("日本").is_err());
+        assert!(Encoding::ShiftJis.encode("🦀").is_err());
+    }
+}
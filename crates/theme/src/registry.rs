@@ -253,19 +253,34 @@ impl ThemeRegistry {
                 continue;
             };
 
-            let Some(reader) = fs.open_sync(&theme_path).await.log_err() else {
-                continue;
-            };
-
-            let Some(theme) = serde_json::from_reader(reader).log_err() else {
-                continue;
-            };
-
-            self.insert_user_theme_families([theme]);
+            self.load_user_theme(&theme_path, fs.clone())
+                .await
+                .log_err();
         }
 
         Ok(())
     }
+
+    /// Loads the themes in the theme family at the specified path and adds them to the
+    /// registry, replacing any themes with the same names. Returns the names of the themes.
+    pub async fn load_user_theme(
+        &self,
+        theme_path: &Path,
+        fs: Arc<dyn Fs>,
+    ) -> Result<Vec<SharedString>> {
+        let reader = fs.open_sync(theme_path).await?;
+        let theme_family: ThemeFamilyContent = serde_json::from_reader(reader)
+            .with_context(|| format!("failed to parse theme at path {theme_path:?}"))?;
+        let theme_names = theme_family
+            .themes
+            .iter()
+            .map(|theme| SharedString::from(theme.name.clone()))
+            .collect();
+
+        self.insert_user_theme_families([theme_family]);
+
+        Ok(theme_names)
+    }
 }
 
 impl Default for ThemeRegistry {
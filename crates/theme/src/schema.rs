@@ -92,6 +92,8 @@ impl ThemeStyleContent {
                             .color
                             .as_ref()
                             .and_then(|color| try_parse_color(&color).ok()),
+                        font_style: style.font_style.map(Into::into),
+                        font_weight: style.font_weight.map(Into::into),
                         ..Default::default()
                     },
                 )
@@ -1,39 +1,81 @@
 use crate::one_themes::one_dark;
-use crate::{SyntaxTheme, Theme, ThemeRegistry, ThemeStyleContent};
+use crate::{Appearance, Theme, ThemeRegistry, ThemeStyleContent};
 use anyhow::Result;
+use chrono::{Local, NaiveTime};
+use collections::HashMap;
 use gpui::{
-    px, AppContext, Font, FontFeatures, FontStyle, FontWeight, Pixels, Subscription, ViewContext,
+    px, AnyWindowHandle, AppContext, Font, FontFallbacks, FontFeatures, FontStyle, FontWeight,
+    Pixels, Subscription, Task, ViewContext, WindowContext,
 };
-use refineable::Refineable;
 use schemars::{
     gen::SchemaGenerator,
-    schema::{InstanceType, Schema, SchemaObject},
+    schema::{ArrayValidation, InstanceType, Schema, SchemaObject},
     JsonSchema,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use settings::{Settings, SettingsJsonSchemaParams};
-use std::sync::Arc;
+use std::{path::Path, sync::Arc, time::Duration};
 use util::ResultExt as _;
 
 const MIN_FONT_SIZE: Pixels = px(6.0);
 const MIN_LINE_HEIGHT: f32 = 1.0;
+const MIN_UI_SCALE: f32 = 0.5;
+const MAX_UI_SCALE: f32 = 4.0;
 
 #[derive(Clone)]
 pub struct ThemeSettings {
     pub ui_font_size: Pixels,
     pub ui_font: Font,
+    pub ui_density: UiDensity,
+    pub ui_scale: f32,
     pub buffer_font: Font,
     pub buffer_font_size: Pixels,
     pub buffer_line_height: BufferLineHeight,
-    pub requested_theme: Option<String>,
+    pub theme_selection: Option<ThemeSelection>,
     pub active_theme: Arc<Theme>,
-    pub theme_overrides: Option<ThemeStyleContent>,
+    pub experimental_theme_overrides: Option<ThemeStyleContent>,
+    pub theme_overrides: HashMap<String, ThemeStyleContent>,
 }
 
 #[derive(Default)]
 pub(crate) struct AdjustedBufferFontSize(Pixels);
 
+/// The appearance of the operating system, which decides between the light and dark
+/// themes of a [`ThemeSelection`] that follows the system.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SystemAppearance(pub Appearance);
+
+impl Default for SystemAppearance {
+    fn default() -> Self {
+        Self(Appearance::Dark)
+    }
+}
+
+impl SystemAppearance {
+    pub fn global(cx: &AppContext) -> Self {
+        cx.try_global::<Self>().copied().unwrap_or_default()
+    }
+
+    /// Updates the system appearance, switching themes if the theme settings follow it.
+    pub fn set_global(appearance: Appearance, cx: &mut AppContext) {
+        if Self::global(cx).0 != appearance {
+            cx.set_global(Self(appearance));
+            reload_theme(cx);
+        }
+    }
+}
+
+/// The pending switch between light and dark themes of a [`ThemeSelection`] that follows
+/// a schedule.
+#[derive(Default)]
+struct ThemeScheduleTimer(Option<Task<()>>);
+
+/// The theme settings of each window showing a worktree with local settings, along
+/// with the location of those settings.
+#[derive(Default)]
+struct WindowThemeSettings(HashMap<AnyWindowHandle, ((usize, Arc<Path>), ThemeSettings)>);
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ThemeSettingsContent {
     #[serde(default)]
@@ -42,6 +84,20 @@ pub struct ThemeSettingsContent {
     pub ui_font_family: Option<String>,
     #[serde(default)]
     pub ui_font_features: Option<FontFeatures>,
+    /// The fonts to take glyphs missing from the UI font from, in order of preference.
+    #[serde(default)]
+    pub ui_font_fallbacks: Option<FontFallbacks>,
+    /// How much space to leave around and between the elements of the UI.
+    ///
+    /// Default: default
+    #[serde(default)]
+    pub ui_density: Option<UiDensity>,
+    /// The factor to scale everything in the UI by, including text in the editor, on top
+    /// of the scale factor of the display.
+    ///
+    /// Default: 1.0
+    #[serde(default)]
+    pub ui_scale: Option<f32>,
     #[serde(default)]
     pub buffer_font_family: Option<String>,
     #[serde(default)]
@@ -50,14 +106,197 @@ pub struct ThemeSettingsContent {
     pub buffer_line_height: Option<BufferLineHeight>,
     #[serde(default)]
     pub buffer_font_features: Option<FontFeatures>,
+    /// The fonts to take glyphs missing from the buffer font from, in order of preference.
+    #[serde(default)]
+    pub buffer_font_fallbacks: Option<FontFallbacks>,
     #[serde(default)]
-    pub theme: Option<String>,
+    pub theme: Option<ThemeSelection>,
 
     /// EXPERIMENTAL: Overrides for the current theme.
     ///
-    /// These values will override the ones on the current theme specified in `theme`.
+    /// These values will override the ones on the current theme specified in `theme`,
+    /// whichever theme that is. Prefer `theme_overrides`.
     #[serde(rename = "experimental.theme_overrides", default)]
-    pub theme_overrides: Option<ThemeStyleContent>,
+    pub experimental_theme_overrides: Option<ThemeStyleContent>,
+
+    /// Overrides for the colors and syntax styles of themes, keyed by theme name.
+    ///
+    /// These values will override the ones of the named theme while it is active.
+    #[serde(default)]
+    pub theme_overrides: HashMap<String, ThemeStyleContent>,
+}
+
+impl ThemeSettingsContent {
+    /// Sets the theme to use. When the settings select a pair of light and dark themes,
+    /// only the one for the given appearance is replaced.
+    pub fn set_theme(&mut self, theme_name: String, appearance: Appearance) {
+        match &mut self.theme {
+            Some(ThemeSelection::Dynamic { light, dark, .. }) => match appearance {
+                Appearance::Light => *light = theme_name,
+                Appearance::Dark => *dark = theme_name,
+            },
+            _ => self.theme = Some(ThemeSelection::Static(theme_name)),
+        }
+    }
+}
+
+/// The theme to use, either by name or as a pair of themes for light and dark mode.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum ThemeSelection {
+    Static(#[schemars(schema_with = "theme_name_ref")] String),
+    Dynamic {
+        /// How to choose between the light and dark themes.
+        ///
+        /// Default: system
+        #[serde(default)]
+        mode: ThemeMode,
+        /// The name of the theme to use in light mode.
+        #[schemars(schema_with = "theme_name_ref")]
+        light: String,
+        /// The name of the theme to use in dark mode.
+        #[schemars(schema_with = "theme_name_ref")]
+        dark: String,
+        /// When to switch between the light and dark themes if `mode` is `schedule`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        schedule: Option<ThemeSchedule>,
+    },
+}
+
+fn theme_name_ref(_: &mut SchemaGenerator) -> Schema {
+    Schema::new_ref("#/definitions/ThemeName".into())
+}
+
+impl ThemeSelection {
+    /// Returns whether the light or dark theme is selected, given the appearance of the
+    /// operating system and the local time of day.
+    pub fn appearance(&self, system_appearance: Appearance, time: NaiveTime) -> Appearance {
+        match self {
+            Self::Static(_) => system_appearance,
+            Self::Dynamic { mode, .. } => match mode {
+                ThemeMode::Light => Appearance::Light,
+                ThemeMode::Dark => Appearance::Dark,
+                ThemeMode::System => system_appearance,
+                ThemeMode::Schedule => self
+                    .schedule()
+                    .and_then(|schedule| schedule.appearance_at(time))
+                    .unwrap_or(system_appearance),
+            },
+        }
+    }
+
+    /// Returns the name of the selected theme, given the appearance of the operating
+    /// system and the local time of day.
+    pub fn theme(&self, system_appearance: Appearance, time: NaiveTime) -> &str {
+        match self {
+            Self::Static(theme) => theme,
+            Self::Dynamic { light, dark, .. } => match self.appearance(system_appearance, time) {
+                Appearance::Light => light,
+                Appearance::Dark => dark,
+            },
+        }
+    }
+
+    /// Returns the schedule to switch themes by, if the selection follows one.
+    pub fn schedule(&self) -> Option<ThemeSchedule> {
+        match self {
+            Self::Dynamic {
+                mode: ThemeMode::Schedule,
+                schedule,
+                ..
+            } => Some(schedule.clone().unwrap_or_default()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    /// Always use the light theme.
+    Light,
+    /// Always use the dark theme.
+    Dark,
+    /// Use the theme matching the light or dark mode of the operating system.
+    #[default]
+    System,
+    /// Use the light theme during the day and the dark theme at night, as set by
+    /// `schedule`.
+    Schedule,
+}
+
+/// The local times of day, as "HH:MM", at which to switch between the light and dark
+/// themes.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ThemeSchedule {
+    /// When to switch to the light theme.
+    ///
+    /// Default: "07:00"
+    #[serde(default = "ThemeSchedule::default_light")]
+    pub light: String,
+    /// When to switch to the dark theme.
+    ///
+    /// Default: "19:00"
+    #[serde(default = "ThemeSchedule::default_dark")]
+    pub dark: String,
+}
+
+impl Default for ThemeSchedule {
+    fn default() -> Self {
+        Self {
+            light: Self::default_light(),
+            dark: Self::default_dark(),
+        }
+    }
+}
+
+impl ThemeSchedule {
+    fn default_light() -> String {
+        "07:00".into()
+    }
+
+    fn default_dark() -> String {
+        "19:00".into()
+    }
+
+    fn times(&self) -> Option<(NaiveTime, NaiveTime)> {
+        let parse = |time: &str| NaiveTime::parse_from_str(time, "%H:%M").log_err();
+        Some((parse(&self.light)?, parse(&self.dark)?))
+    }
+
+    /// Returns the appearance scheduled for the given time of day, or `None` if the
+    /// schedule's times are invalid.
+    pub fn appearance_at(&self, time: NaiveTime) -> Option<Appearance> {
+        let (light, dark) = self.times()?;
+        let is_light = if light <= dark {
+            light <= time && time < dark
+        } else {
+            time >= light || time < dark
+        };
+        Some(if is_light {
+            Appearance::Light
+        } else {
+            Appearance::Dark
+        })
+    }
+
+    /// Returns how long after the given time of day the next switch is scheduled.
+    pub fn next_switch_after(&self, time: NaiveTime) -> Option<Duration> {
+        let (light, dark) = self.times()?;
+        [light, dark]
+            .into_iter()
+            .map(|switch_time| {
+                let delay = switch_time - time;
+                if delay <= chrono::Duration::zero() {
+                    delay + chrono::Duration::days(1)
+                } else {
+                    delay
+                }
+            })
+            .min()?
+            .to_std()
+            .ok()
+    }
 }
 
 #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, JsonSchema, Default)]
@@ -79,7 +318,54 @@ impl BufferLineHeight {
     }
 }
 
+/// How much space to leave around and between the elements of the UI.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UiDensity {
+    Compact,
+    #[default]
+    Default,
+    Comfortable,
+}
+
+impl UiDensity {
+    /// The factor to scale paddings and the heights of elements by.
+    pub fn spacing_ratio(&self) -> f32 {
+        match self {
+            UiDensity::Compact => 0.75,
+            UiDensity::Default => 1.0,
+            UiDensity::Comfortable => 1.25,
+        }
+    }
+}
+
 impl ThemeSettings {
+    /// Returns the theme settings for the given window, taking into account the
+    /// local settings of the project open in it.
+    pub fn for_window<'a>(cx: &'a WindowContext) -> &'a Self {
+        cx.try_global::<WindowThemeSettings>()
+            .and_then(|settings| settings.0.get(&cx.window_handle()))
+            .map_or_else(|| Self::get_global(cx), |(_, settings)| settings)
+    }
+
+    fn for_location(location: (usize, &Path), cx: &mut AppContext) -> Self {
+        let mut settings = Self::get(Some(location), cx).clone();
+        if let Some(selection) = settings.theme_selection.clone() {
+            let theme_name = selection.theme(SystemAppearance::global(cx).0, Local::now().time());
+            settings.switch_theme(theme_name, cx);
+        }
+        let global_theme = &Self::get_global(cx).active_theme;
+        if !Arc::ptr_eq(&settings.active_theme, global_theme) {
+            // Languages resolve their highlights against the global theme, so the syntax
+            // styles of this theme need to be listed in the same order.
+            let mut theme = (*settings.active_theme).clone();
+            theme.styles.syntax =
+                Arc::new(theme.styles.syntax.aligned_to(&global_theme.styles.syntax));
+            settings.active_theme = Arc::new(theme);
+        }
+        settings
+    }
+
     pub fn buffer_font_size(&self, cx: &AppContext) -> Pixels {
         cx.try_global::<AdjustedBufferFontSize>()
             .map_or(self.buffer_font_size, |size| size.0)
@@ -90,6 +376,16 @@ impl ThemeSettings {
         f32::max(self.buffer_line_height.value(), MIN_LINE_HEIGHT)
     }
 
+    /// Returns whether the theme settings currently select a light or a dark theme.
+    pub fn theme_appearance(&self, cx: &AppContext) -> Appearance {
+        match &self.theme_selection {
+            Some(selection @ ThemeSelection::Dynamic { .. }) => {
+                selection.appearance(SystemAppearance::global(cx).0, Local::now().time())
+            }
+            _ => self.active_theme.appearance,
+        }
+    }
+
     /// Switches to the theme with the given name, if it exists.
     ///
     /// Returns a `Some` containing the new theme if it was successful.
@@ -111,30 +407,102 @@ impl ThemeSettings {
 
     /// Applies the theme overrides, if there are any, to the current theme.
     pub fn apply_theme_overrides(&mut self) {
-        if let Some(theme_overrides) = &self.theme_overrides {
-            let mut base_theme = (*self.active_theme).clone();
-
-            base_theme
-                .styles
-                .colors
-                .refine(&theme_overrides.theme_colors_refinement());
-            base_theme
-                .styles
-                .status
-                .refine(&theme_overrides.status_colors_refinement());
-            base_theme.styles.syntax = Arc::new(SyntaxTheme {
-                highlights: {
-                    let mut highlights = base_theme.styles.syntax.highlights.clone();
-                    // Overrides come second in the highlight list so that they take precedence
-                    // over the ones in the base theme.
-                    highlights.extend(theme_overrides.syntax_overrides());
-                    highlights
-                },
-            });
-
-            self.active_theme = Arc::new(base_theme);
+        let theme_overrides = self
+            .experimental_theme_overrides
+            .iter()
+            .chain(self.theme_overrides.get(self.active_theme.name.as_ref()))
+            .collect::<Vec<_>>();
+        if theme_overrides.is_empty() {
+            return;
+        }
+
+        let mut base_theme = (*self.active_theme).clone();
+        for theme_overrides in theme_overrides {
+            base_theme.apply_style_overrides(theme_overrides);
+        }
+        self.active_theme = Arc::new(base_theme);
+    }
+}
+
+/// Sets the worktree whose local settings override the theme and fonts of the given
+/// window, or clears it when `location` is `None`.
+pub fn set_window_settings_location(
+    window: AnyWindowHandle,
+    location: Option<(usize, Arc<Path>)>,
+    cx: &mut AppContext,
+) {
+    let current_location = cx
+        .try_global::<WindowThemeSettings>()
+        .and_then(|settings| settings.0.get(&window))
+        .map(|(location, _)| location);
+    if current_location == location.as_ref() {
+        return;
+    }
+
+    let settings = location.map(|location| {
+        let settings = ThemeSettings::for_location((location.0, &location.1), cx);
+        (location, settings)
+    });
+    let window_settings = &mut cx.default_global::<WindowThemeSettings>().0;
+    match settings {
+        Some(settings) => window_settings.insert(window, settings),
+        None => window_settings.remove(&window),
+    };
+    cx.refresh();
+}
+
+/// Switches to the theme selected by the theme settings, for when that selection depends
+/// on something other than the settings files, such as the system appearance.
+pub fn reload_theme(cx: &mut AppContext) {
+    let mut theme_settings = ThemeSettings::get_global(cx).clone();
+    if let Some(selection) = theme_settings.theme_selection.clone() {
+        let theme_name = selection.theme(SystemAppearance::global(cx).0, Local::now().time());
+        if theme_settings.switch_theme(theme_name, cx).is_some() {
+            ThemeSettings::override_global(theme_settings, cx);
         }
     }
+    // The local settings of a window's project may select a theme the same way.
+    reload_window_theme_settings(cx);
+}
+
+/// Arranges for the theme to be reloaded at the next switch between the light and dark
+/// themes scheduled by the theme settings.
+pub(crate) fn update_theme_schedule(cx: &mut AppContext) {
+    let delay = ThemeSettings::get_global(cx)
+        .theme_selection
+        .as_ref()
+        .and_then(ThemeSelection::schedule)
+        .and_then(|schedule| schedule.next_switch_after(Local::now().time()));
+    let task = delay.map(|delay| {
+        cx.spawn(|mut cx| async move {
+            // Wake up just after the switch, so that the new theme is in effect.
+            cx.background_executor()
+                .timer(delay + Duration::from_secs(1))
+                .await;
+            cx.update(reload_theme).log_err();
+        })
+    });
+    cx.set_global(ThemeScheduleTimer(task));
+}
+
+pub(crate) fn reload_window_theme_settings(cx: &mut AppContext) {
+    let Some(window_settings) = cx.try_global::<WindowThemeSettings>() else {
+        return;
+    };
+    let locations = window_settings
+        .0
+        .iter()
+        .map(|(window, (location, _))| (*window, location.clone()))
+        .collect::<Vec<_>>();
+    let window_settings = locations
+        .into_iter()
+        .map(|(window, location)| {
+            let settings = ThemeSettings::for_location((location.0, &location.1), cx);
+            (window, (location, settings))
+        })
+        .collect();
+    cx.set_global(WindowThemeSettings(window_settings));
+    cx.refresh();
 }
 
 pub fn observe_buffer_font_size_adjustment<V: 'static>(
@@ -155,7 +523,7 @@ pub fn adjusted_font_size(size: Pixels, cx: &mut AppContext) -> Pixels {
     .max(MIN_FONT_SIZE)
 }
 
-pub fn adjust_font_size(cx: &mut AppContext, f: fn(&mut Pixels)) {
+pub fn adjust_font_size(cx: &mut AppContext, f: impl FnOnce(&mut Pixels)) {
     let buffer_font_size = ThemeSettings::get_global(cx).buffer_font_size;
     let mut adjusted_size = cx
         .try_global::<AdjustedBufferFontSize>()
@@ -185,29 +553,42 @@ impl settings::Settings for ThemeSettings {
         cx: &mut AppContext,
     ) -> Result<Self> {
         let themes = ThemeRegistry::default_global(cx);
+        let system_appearance = SystemAppearance::global(cx).0;
+        let now = Local::now().time();
 
         let mut this = Self {
             ui_font_size: defaults.ui_font_size.unwrap().into(),
             ui_font: Font {
                 family: defaults.ui_font_family.clone().unwrap().into(),
                 features: defaults.ui_font_features.clone().unwrap(),
+                fallbacks: defaults.ui_font_fallbacks.clone(),
                 weight: Default::default(),
                 style: Default::default(),
             },
+            ui_density: defaults.ui_density.unwrap(),
+            ui_scale: defaults.ui_scale.unwrap(),
             buffer_font: Font {
                 family: defaults.buffer_font_family.clone().unwrap().into(),
                 features: defaults.buffer_font_features.clone().unwrap(),
+                fallbacks: defaults.buffer_font_fallbacks.clone(),
                 weight: FontWeight::default(),
                 style: FontStyle::default(),
             },
             buffer_font_size: defaults.buffer_font_size.unwrap().into(),
             buffer_line_height: defaults.buffer_line_height.unwrap(),
-            requested_theme: defaults.theme.clone(),
+            theme_selection: defaults.theme.clone(),
             active_theme: themes
-                .get(defaults.theme.as_ref().unwrap())
+                .get(
+                    defaults
+                        .theme
+                        .as_ref()
+                        .unwrap()
+                        .theme(system_appearance, now),
+                )
                 .or(themes.get(&one_dark().name))
                 .unwrap(),
-            theme_overrides: None,
+            experimental_theme_overrides: None,
+            theme_overrides: HashMap::default(),
         };
 
         for value in user_values.into_iter().copied().cloned() {
@@ -217,6 +598,9 @@ impl settings::Settings for ThemeSettings {
             if let Some(value) = value.buffer_font_features {
                 this.buffer_font.features = value;
             }
+            if let Some(value) = value.buffer_font_fallbacks {
+                this.buffer_font.fallbacks = Some(value);
+            }
 
             if let Some(value) = value.ui_font_family {
                 this.ui_font.family = value.into();
@@ -224,19 +608,27 @@ impl settings::Settings for ThemeSettings {
             if let Some(value) = value.ui_font_features {
                 this.ui_font.features = value;
             }
+            if let Some(value) = value.ui_font_fallbacks {
+                this.ui_font.fallbacks = Some(value);
+            }
 
             if let Some(value) = &value.theme {
-                this.requested_theme = Some(value.clone());
+                this.theme_selection = Some(value.clone());
 
-                if let Some(theme) = themes.get(value).log_err() {
+                let theme_name = value.theme(system_appearance, now);
+                if let Some(theme) = themes.get(theme_name).log_err() {
                     this.active_theme = theme;
                 }
             }
 
-            this.theme_overrides = value.theme_overrides;
-            this.apply_theme_overrides();
+            if let Some(value) = value.experimental_theme_overrides {
+                this.experimental_theme_overrides = Some(value);
+            }
+            this.theme_overrides.extend(value.theme_overrides);
 
             merge(&mut this.ui_font_size, value.ui_font_size.map(Into::into));
+            merge(&mut this.ui_density, value.ui_density);
+            merge(&mut this.ui_scale, value.ui_scale);
             merge(
                 &mut this.buffer_font_size,
                 value.buffer_font_size.map(Into::into),
@@ -244,6 +636,9 @@ impl settings::Settings for ThemeSettings {
             merge(&mut this.buffer_line_height, value.buffer_line_height);
         }
 
+        this.ui_scale = this.ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+        this.apply_theme_overrides();
+
         Ok(this)
     }
 
@@ -288,10 +683,6 @@ impl settings::Settings for ThemeSettings {
             .unwrap()
             .properties
             .extend([
-                (
-                    "theme".to_owned(),
-                    Schema::new_ref("#/definitions/ThemeName".into()),
-                ),
                 (
                     "buffer_font_family".to_owned(),
                     Schema::new_ref("#/definitions/FontFamilies".into()),
@@ -300,14 +691,165 @@ impl settings::Settings for ThemeSettings {
                     "ui_font_family".to_owned(),
                     Schema::new_ref("#/definitions/FontFamilies".into()),
                 ),
+                ("buffer_font_fallbacks".to_owned(), font_fallbacks_schema()),
+                ("ui_font_fallbacks".to_owned(), font_fallbacks_schema()),
             ]);
 
+        if let Some(Schema::Object(theme_overrides_schema)) = root_schema
+            .schema
+            .object
+            .as_mut()
+            .unwrap()
+            .properties
+            .get_mut("theme_overrides")
+        {
+            theme_overrides_schema.object().property_names =
+                Some(Box::new(Schema::new_ref("#/definitions/ThemeName".into())));
+        }
+
         root_schema
     }
 }
 
+fn font_fallbacks_schema() -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(Schema::new_ref("#/definitions/FontFamilies".into()).into()),
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .into()
+}
+
 fn merge<T: Copy>(target: &mut T, value: Option<T>) {
     if let Some(value) = value {
         *target = value;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn time(time: &str) -> NaiveTime {
+        NaiveTime::parse_from_str(time, "%H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_theme_selection() {
+        let selection: ThemeSelection = serde_json::from_value(json!("One Dark")).unwrap();
+        assert_eq!(
+            selection.theme(Appearance::Light, time("12:00")),
+            "One Dark"
+        );
+
+        let selection: ThemeSelection = serde_json::from_value(json!({
+            "light": "One Light",
+            "dark": "One Dark"
+        }))
+        .unwrap();
+        assert_eq!(
+            selection.theme(Appearance::Light, time("12:00")),
+            "One Light"
+        );
+        assert_eq!(selection.theme(Appearance::Dark, time("12:00")), "One Dark");
+
+        let selection: ThemeSelection = serde_json::from_value(json!({
+            "mode": "dark",
+            "light": "One Light",
+            "dark": "One Dark"
+        }))
+        .unwrap();
+        assert_eq!(
+            selection.theme(Appearance::Light, time("12:00")),
+            "One Dark"
+        );
+
+        let selection: ThemeSelection = serde_json::from_value(json!({
+            "mode": "schedule",
+            "light": "One Light",
+            "dark": "One Dark",
+            "schedule": { "light": "08:30" }
+        }))
+        .unwrap();
+        assert_eq!(selection.theme(Appearance::Dark, time("08:00")), "One Dark");
+        assert_eq!(
+            selection.theme(Appearance::Dark, time("08:30")),
+            "One Light"
+        );
+        assert_eq!(
+            selection.theme(Appearance::Dark, time("18:59")),
+            "One Light"
+        );
+        assert_eq!(
+            selection.theme(Appearance::Light, time("19:00")),
+            "One Dark"
+        );
+    }
+
+    #[test]
+    fn test_theme_schedule() {
+        let schedule = ThemeSchedule {
+            light: "20:00".into(),
+            dark: "06:00".into(),
+        };
+        assert_eq!(
+            schedule.appearance_at(time("23:00")),
+            Some(Appearance::Light)
+        );
+        assert_eq!(
+            schedule.appearance_at(time("05:59")),
+            Some(Appearance::Light)
+        );
+        assert_eq!(
+            schedule.appearance_at(time("12:00")),
+            Some(Appearance::Dark)
+        );
+        assert_eq!(
+            schedule.next_switch_after(time("12:00")),
+            Some(Duration::from_secs(8 * 60 * 60))
+        );
+        assert_eq!(
+            schedule.next_switch_after(time("20:00")),
+            Some(Duration::from_secs(10 * 60 * 60))
+        );
+
+        let schedule = ThemeSchedule {
+            light: "7am".into(),
+            dark: "19:00".into(),
+        };
+        assert_eq!(schedule.appearance_at(time("12:00")), None);
+        assert_eq!(schedule.next_switch_after(time("12:00")), None);
+    }
+
+    #[test]
+    fn test_set_theme() {
+        let mut content = ThemeSettingsContent::default();
+        content.set_theme("One Light".into(), Appearance::Dark);
+        assert_eq!(
+            content.theme,
+            Some(ThemeSelection::Static("One Light".into()))
+        );
+
+        content.theme = Some(ThemeSelection::Dynamic {
+            mode: ThemeMode::System,
+            light: "One Light".into(),
+            dark: "One Dark".into(),
+            schedule: None,
+        });
+        content.set_theme("Andromeda".into(), Appearance::Dark);
+        assert_eq!(
+            content.theme,
+            Some(ThemeSelection::Dynamic {
+                mode: ThemeMode::System,
+                light: "One Light".into(),
+                dark: "Andromeda".into(),
+                schedule: None,
+            })
+        );
+    }
+}
@@ -233,6 +233,153 @@ pub struct ThemeColors {
     pub link_text_hover: Hsla,
 }
 
+impl ThemeColors {
+    /// Returns each of the colors along with its key in theme files, or its field name
+    /// for the colors that theme files can't set.
+    pub fn named_colors(&self) -> Vec<(&'static str, Hsla)> {
+        vec![
+            ("border", self.border),
+            ("border.variant", self.border_variant),
+            ("border.focused", self.border_focused),
+            ("border.selected", self.border_selected),
+            ("border.transparent", self.border_transparent),
+            ("border.disabled", self.border_disabled),
+            (
+                "elevated_surface.background",
+                self.elevated_surface_background,
+            ),
+            ("surface.background", self.surface_background),
+            ("background", self.background),
+            ("element.background", self.element_background),
+            ("element.hover", self.element_hover),
+            ("element.active", self.element_active),
+            ("element.selected", self.element_selected),
+            ("element.disabled", self.element_disabled),
+            ("drop_target.background", self.drop_target_background),
+            ("drop_target_border", self.drop_target_border),
+            ("ghost_element.background", self.ghost_element_background),
+            ("ghost_element.hover", self.ghost_element_hover),
+            ("ghost_element.active", self.ghost_element_active),
+            ("ghost_element.selected", self.ghost_element_selected),
+            ("ghost_element.disabled", self.ghost_element_disabled),
+            ("text", self.text),
+            ("text.muted", self.text_muted),
+            ("text.placeholder", self.text_placeholder),
+            ("text.disabled", self.text_disabled),
+            ("text.accent", self.text_accent),
+            ("icon", self.icon),
+            ("icon.muted", self.icon_muted),
+            ("icon.disabled", self.icon_disabled),
+            ("icon.placeholder", self.icon_placeholder),
+            ("icon.accent", self.icon_accent),
+            ("status_bar.background", self.status_bar_background),
+            ("title_bar.background", self.title_bar_background),
+            ("toolbar.background", self.toolbar_background),
+            ("tab_bar.background", self.tab_bar_background),
+            ("tab.inactive_background", self.tab_inactive_background),
+            ("tab.active_background", self.tab_active_background),
+            ("search.match_background", self.search_match_background),
+            ("panel.background", self.panel_background),
+            ("panel.focused_border", self.panel_focused_border),
+            ("pane.focused_border", self.pane_focused_border),
+            (
+                "scrollbar_thumb.background",
+                self.scrollbar_thumb_background,
+            ),
+            (
+                "scrollbar.thumb.hover_background",
+                self.scrollbar_thumb_hover_background,
+            ),
+            ("scrollbar.thumb.border", self.scrollbar_thumb_border),
+            (
+                "scrollbar.track.background",
+                self.scrollbar_track_background,
+            ),
+            ("scrollbar.track.border", self.scrollbar_track_border),
+            ("scrollbar_status_opacity", self.scrollbar_status_opacity),
+            ("editor.foreground", self.editor_foreground),
+            ("editor.background", self.editor_background),
+            (
+                "editor_inactive_background",
+                self.editor_inactive_background,
+            ),
+            ("editor.gutter.background", self.editor_gutter_background),
+            (
+                "editor.subheader.background",
+                self.editor_subheader_background,
+            ),
+            (
+                "editor.active_line.background",
+                self.editor_active_line_background,
+            ),
+            (
+                "editor.highlighted_line.background",
+                self.editor_highlighted_line_background,
+            ),
+            ("editor.line_number", self.editor_line_number),
+            ("editor.active_line_number", self.editor_active_line_number),
+            ("editor.invisible", self.editor_invisible),
+            ("editor.wrap_guide", self.editor_wrap_guide),
+            ("editor.active_wrap_guide", self.editor_active_wrap_guide),
+            (
+                "editor.document_highlight.read_background",
+                self.editor_document_highlight_read_background,
+            ),
+            (
+                "editor.document_highlight.write_background",
+                self.editor_document_highlight_write_background,
+            ),
+            ("terminal.background", self.terminal_background),
+            ("terminal.foreground", self.terminal_foreground),
+            (
+                "terminal.bright_foreground",
+                self.terminal_bright_foreground,
+            ),
+            ("terminal.dim_foreground", self.terminal_dim_foreground),
+            ("terminal.ansi.black", self.terminal_ansi_black),
+            (
+                "terminal.ansi.bright_black",
+                self.terminal_ansi_bright_black,
+            ),
+            ("terminal.ansi.dim_black", self.terminal_ansi_dim_black),
+            ("terminal.ansi.red", self.terminal_ansi_red),
+            ("terminal.ansi.bright_red", self.terminal_ansi_bright_red),
+            ("terminal.ansi.dim_red", self.terminal_ansi_dim_red),
+            ("terminal.ansi.green", self.terminal_ansi_green),
+            (
+                "terminal.ansi.bright_green",
+                self.terminal_ansi_bright_green,
+            ),
+            ("terminal.ansi.dim_green", self.terminal_ansi_dim_green),
+            ("terminal.ansi.yellow", self.terminal_ansi_yellow),
+            (
+                "terminal.ansi.bright_yellow",
+                self.terminal_ansi_bright_yellow,
+            ),
+            ("terminal.ansi.dim_yellow", self.terminal_ansi_dim_yellow),
+            ("terminal.ansi.blue", self.terminal_ansi_blue),
+            ("terminal.ansi.bright_blue", self.terminal_ansi_bright_blue),
+            ("terminal.ansi.dim_blue", self.terminal_ansi_dim_blue),
+            ("terminal.ansi.magenta", self.terminal_ansi_magenta),
+            (
+                "terminal.ansi.bright_magenta",
+                self.terminal_ansi_bright_magenta,
+            ),
+            ("terminal.ansi.dim_magenta", self.terminal_ansi_dim_magenta),
+            ("terminal.ansi.cyan", self.terminal_ansi_cyan),
+            ("terminal.ansi.bright_cyan", self.terminal_ansi_bright_cyan),
+            ("terminal.ansi.dim_cyan", self.terminal_ansi_dim_cyan),
+            ("terminal.ansi.white", self.terminal_ansi_white),
+            (
+                "terminal.ansi.bright_white",
+                self.terminal_ansi_bright_white,
+            ),
+            ("terminal.ansi.dim_white", self.terminal_ansi_dim_white),
+            ("link_text.hover", self.link_text_hover),
+        ]
+    }
+}
+
 #[derive(Refineable, Clone)]
 pub struct ThemeStyles {
     pub system: SystemColors,
@@ -78,6 +78,56 @@ pub struct StatusColors {
     pub warning_border: Hsla,
 }
 
+impl StatusColors {
+    /// Returns each of the colors along with its key in theme files.
+    pub fn named_colors(&self) -> Vec<(&'static str, Hsla)> {
+        vec![
+            ("conflict", self.conflict),
+            ("conflict.background", self.conflict_background),
+            ("conflict.border", self.conflict_border),
+            ("created", self.created),
+            ("created.background", self.created_background),
+            ("created.border", self.created_border),
+            ("deleted", self.deleted),
+            ("deleted.background", self.deleted_background),
+            ("deleted.border", self.deleted_border),
+            ("error", self.error),
+            ("error.background", self.error_background),
+            ("error.border", self.error_border),
+            ("hidden", self.hidden),
+            ("hidden.background", self.hidden_background),
+            ("hidden.border", self.hidden_border),
+            ("hint", self.hint),
+            ("hint.background", self.hint_background),
+            ("hint.border", self.hint_border),
+            ("ignored", self.ignored),
+            ("ignored.background", self.ignored_background),
+            ("ignored.border", self.ignored_border),
+            ("info", self.info),
+            ("info.background", self.info_background),
+            ("info.border", self.info_border),
+            ("modified", self.modified),
+            ("modified.background", self.modified_background),
+            ("modified.border", self.modified_border),
+            ("predictive", self.predictive),
+            ("predictive.background", self.predictive_background),
+            ("predictive.border", self.predictive_border),
+            ("renamed", self.renamed),
+            ("renamed.background", self.renamed_background),
+            ("renamed.border", self.renamed_border),
+            ("success", self.success),
+            ("success.background", self.success_background),
+            ("success.border", self.success_border),
+            ("unreachable", self.unreachable),
+            ("unreachable.background", self.unreachable_background),
+            ("unreachable.border", self.unreachable_border),
+            ("warning", self.warning),
+            ("warning.background", self.warning_background),
+            ("warning.border", self.warning_border),
+        ]
+    }
+}
+
 pub struct DiagnosticColors {
     pub error: Hsla,
     pub warning: Hsla,
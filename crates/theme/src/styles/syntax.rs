@@ -11,6 +11,37 @@ pub struct SyntaxTheme {
 }
 
 impl SyntaxTheme {
+    /// Returns the styles of this theme listed under the keys of `other`, in the same
+    /// order, so that highlights resolved against `other` can be styled with this theme.
+    ///
+    /// Each key takes the style of this theme's longest key whose components it contains.
+    pub fn aligned_to(&self, other: &SyntaxTheme) -> SyntaxTheme {
+        let highlights = other
+            .highlights
+            .iter()
+            .map(|(key, _)| {
+                let style = self
+                    .highlights
+                    .iter()
+                    .filter_map(|(own_key, style)| {
+                        let mut len = 0;
+                        for own_key_part in own_key.split('.') {
+                            if key.split('.').any(|part| part == own_key_part) {
+                                len += 1;
+                            } else {
+                                return None;
+                            }
+                        }
+                        Some((style, len))
+                    })
+                    .max_by_key(|(_, len)| *len)
+                    .map_or_else(HighlightStyle::default, |(style, _)| *style);
+                (key.clone(), style)
+            })
+            .collect();
+        SyntaxTheme { highlights }
+    }
+
     pub fn light() -> Self {
         Self {
             highlights: vec![
@@ -156,3 +187,40 @@ impl SyntaxTheme {
         self.get(name).color.unwrap_or_default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::{blue, green, red};
+
+    use super::*;
+
+    #[test]
+    fn test_aligned_to() {
+        let global = SyntaxTheme::new_test([
+            ("keyword", red()),
+            ("string", red()),
+            ("string.escape", red()),
+            ("comment", red()),
+        ]);
+        let local = SyntaxTheme::new_test([
+            ("comment", green()),
+            ("string", blue()),
+            ("keyword", green()),
+        ]);
+
+        let aligned = local.aligned_to(&global);
+        assert_eq!(
+            aligned
+                .highlights
+                .iter()
+                .map(|(key, style)| (key.as_str(), style.color))
+                .collect::<Vec<_>>(),
+            [
+                ("keyword", Some(green())),
+                ("string", Some(blue())),
+                ("string.escape", Some(blue())),
+                ("comment", Some(green())),
+            ]
+        );
+    }
+}
@@ -27,7 +27,8 @@ pub use schema::*;
 pub use settings::*;
 pub use styles::*;
 
-use gpui::{AppContext, AssetSource, Hsla, SharedString};
+use gpui::{AppContext, AssetSource, Hsla, SharedString, WindowAppearance, WindowContext};
+use refineable::Refineable;
 use serde::Deserialize;
 
 #[derive(Debug, PartialEq, Clone, Copy, Deserialize)]
@@ -45,6 +46,15 @@ impl Appearance {
     }
 }
 
+impl From<WindowAppearance> for Appearance {
+    fn from(value: WindowAppearance) -> Self {
+        match value {
+            WindowAppearance::Light | WindowAppearance::VibrantLight => Self::Light,
+            WindowAppearance::Dark | WindowAppearance::VibrantDark => Self::Dark,
+        }
+    }
+}
+
 pub enum LoadThemes {
     /// Only load the base theme.
     ///
@@ -68,8 +78,11 @@ pub fn init(themes_to_load: LoadThemes, cx: &mut AppContext) {
 
     ThemeSettings::register(cx);
 
+    update_theme_schedule(cx);
     let mut prev_buffer_font_size = ThemeSettings::get_global(cx).buffer_font_size;
     cx.observe_global::<SettingsStore>(move |cx| {
+        reload_window_theme_settings(cx);
+        update_theme_schedule(cx);
         let buffer_font_size = ThemeSettings::get_global(cx).buffer_font_size;
         if buffer_font_size != prev_buffer_font_size {
             prev_buffer_font_size = buffer_font_size;
@@ -89,6 +102,12 @@ impl ActiveTheme for AppContext {
     }
 }
 
+impl ActiveTheme for WindowContext<'_> {
+    fn theme(&self) -> &Arc<Theme> {
+        &ThemeSettings::for_window(self).active_theme
+    }
+}
+
 pub struct ThemeFamily {
     pub id: String,
     pub name: SharedString,
@@ -149,6 +168,58 @@ impl Theme {
     pub fn appearance(&self) -> Appearance {
         self.appearance
     }
+
+    /// Overrides the theme's colors, player colors and syntax styles with the ones
+    /// specified in `overrides`, leaving the rest as they are.
+    pub fn apply_style_overrides(&mut self, overrides: &ThemeStyleContent) {
+        self.styles
+            .colors
+            .refine(&overrides.theme_colors_refinement());
+        self.styles
+            .status
+            .refine(&overrides.status_colors_refinement());
+
+        for (ix, player_overrides) in overrides.players.iter().enumerate() {
+            if ix == self.styles.player.0.len() {
+                let player = self.styles.player.0.first().copied().unwrap_or_default();
+                self.styles.player.0.push(player);
+            }
+            let player = &mut self.styles.player.0[ix];
+            let parse = |color: &Option<String>| {
+                color.as_ref().and_then(|color| try_parse_color(color).ok())
+            };
+            if let Some(cursor) = parse(&player_overrides.cursor) {
+                player.cursor = cursor;
+            }
+            if let Some(background) = parse(&player_overrides.background) {
+                player.background = background;
+            }
+            if let Some(selection) = parse(&player_overrides.selection) {
+                player.selection = selection;
+            }
+        }
+
+        if !overrides.syntax.is_empty() {
+            let mut syntax = (*self.styles.syntax).clone();
+            for (key, style_overrides) in overrides.syntax_overrides() {
+                match syntax.highlights.iter_mut().find(|(name, _)| *name == key) {
+                    Some((_, style)) => {
+                        if style_overrides.color.is_some() {
+                            style.color = style_overrides.color;
+                        }
+                        if style_overrides.font_style.is_some() {
+                            style.font_style = style_overrides.font_style;
+                        }
+                        if style_overrides.font_weight.is_some() {
+                            style.font_weight = style_overrides.font_weight;
+                        }
+                    }
+                    None => syntax.highlights.push((key, style_overrides)),
+                }
+            }
+            self.styles.syntax = Arc::new(syntax);
+        }
+    }
 }
 
 pub fn color_alpha(color: Hsla, alpha: f32) -> Hsla {
@@ -156,3 +227,44 @@ pub fn color_alpha(color: Hsla, alpha: f32) -> Hsla {
     color.a = alpha;
     color
 }
+
+#[cfg(test)]
+mod tests {
+    use gpui::{FontStyle, FontWeight};
+    use serde_json::json;
+
+    use super::*;
+    use crate::one_themes::one_dark;
+
+    #[test]
+    fn apply_style_overrides() {
+        let mut theme = one_dark();
+        let original_background = theme.colors().background;
+        let syntax_len = theme.syntax().highlights.len();
+
+        let overrides: ThemeStyleContent = serde_json::from_value(json!({
+            "text": "#ff00ff",
+            "players": [{ "cursor": "#00ff00" }],
+            "syntax": {
+                "comment": { "font_style": "italic" },
+                "keyword": { "color": "#ff0000", "font_weight": 700 },
+                "lifetime": { "color": "#0000ff" }
+            }
+        }))
+        .unwrap();
+        theme.apply_style_overrides(&overrides);
+
+        assert_eq!(theme.colors().text, gpui::rgb(0xff00ff).into());
+        assert_eq!(theme.colors().background, original_background);
+        assert_eq!(theme.players().local().cursor, gpui::rgb(0x00ff00).into());
+
+        let comment = theme.syntax().get("comment");
+        assert_eq!(comment.font_style, Some(FontStyle::Italic));
+        assert!(comment.color.is_some());
+        let keyword = theme.syntax().get("keyword");
+        assert_eq!(keyword.color, Some(gpui::rgb(0xff0000).into()));
+        assert_eq!(keyword.font_weight, Some(FontWeight::BOLD));
+        assert_eq!(theme.syntax().color("lifetime"), gpui::rgb(0x0000ff).into());
+        assert_eq!(theme.syntax().highlights.len(), syntax_len + 1);
+    }
+}
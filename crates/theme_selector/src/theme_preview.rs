@@ -0,0 +1,184 @@
+use gpui::{
+    actions, AnyElement, AppContext, EventEmitter, FocusHandle, FocusableView, HighlightStyle,
+    Hsla, Render, StyledText, View, ViewContext, VisualContext,
+};
+use theme::ThemeSettings;
+use ui::prelude::*;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace, WorkspaceId,
+};
+
+actions!(theme, [Preview]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &Preview, cx| {
+            let theme_preview = cx.new_view(ThemePreview::new);
+            workspace.add_item(Box::new(theme_preview), cx);
+        });
+    })
+    .detach();
+}
+
+/// Shows every color and syntax style of the active theme along with their names, so
+/// that theme authors can see the effect of their changes at a glance.
+pub struct ThemePreview {
+    focus_handle: FocusHandle,
+}
+
+impl ThemePreview {
+    fn new(cx: &mut ViewContext<Self>) -> Self {
+        Self {
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn render_section(
+        title: &'static str,
+        children: impl IntoIterator<Item = AnyElement>,
+    ) -> impl IntoElement {
+        v_flex()
+            .gap_1()
+            .child(Headline::new(title).size(HeadlineSize::Small))
+            .children(children)
+    }
+
+    fn render_color(name: impl Into<SharedString>, color: Hsla, cx: &WindowContext) -> AnyElement {
+        h_flex()
+            .gap_2()
+            .child(
+                div()
+                    .size_4()
+                    .rounded_sm()
+                    .border_1()
+                    .border_color(cx.theme().colors().border)
+                    .bg(color),
+            )
+            .child(Label::new(name.into()))
+            .child(Label::new(format!("#{:08x}", u32::from(color.to_rgb()))).color(Color::Muted))
+            .into_any_element()
+    }
+
+    fn render_syntax_style(name: &str, style: HighlightStyle, cx: &WindowContext) -> AnyElement {
+        let name = SharedString::from(name.to_string());
+        let highlights = [(0..name.len(), style)];
+        h_flex()
+            .gap_2()
+            .child(StyledText::new(name).with_highlights(&cx.text_style(), highlights))
+            .when_some(style.color, |this, color| {
+                this.child(
+                    Label::new(format!("#{:08x}", u32::from(color.to_rgb()))).color(Color::Muted),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+impl Render for ThemePreview {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let theme = cx.theme().clone();
+        let buffer_font = ThemeSettings::for_window(cx).buffer_font.family.clone();
+
+        let colors = theme
+            .colors()
+            .named_colors()
+            .into_iter()
+            .map(|(name, color)| Self::render_color(name, color, cx))
+            .collect::<Vec<_>>();
+        let status_colors = theme
+            .status()
+            .named_colors()
+            .into_iter()
+            .map(|(name, color)| Self::render_color(name, color, cx))
+            .collect::<Vec<_>>();
+        let players = theme
+            .players()
+            .0
+            .iter()
+            .enumerate()
+            .flat_map(|(ix, player)| {
+                [
+                    (format!("players[{ix}].cursor"), player.cursor),
+                    (format!("players[{ix}].background"), player.background),
+                    (format!("players[{ix}].selection"), player.selection),
+                ]
+            })
+            .map(|(name, color)| Self::render_color(name, color, cx))
+            .collect::<Vec<_>>();
+        let syntax = theme
+            .syntax()
+            .highlights
+            .iter()
+            .map(|(name, style)| Self::render_syntax_style(name, *style, cx))
+            .collect::<Vec<_>>();
+
+        v_flex()
+            .id("theme-preview")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .overflow_y_scroll()
+            .p_4()
+            .gap_4()
+            .bg(theme.colors().editor_background)
+            .child(Headline::new(format!(
+                "{} ({})",
+                theme.name,
+                if theme.appearance().is_light() {
+                    "light"
+                } else {
+                    "dark"
+                }
+            )))
+            .child(Self::render_section("Colors", colors))
+            .child(Self::render_section("Status Colors", status_colors))
+            .child(Self::render_section("Players", players))
+            .child(
+                div()
+                    .font(buffer_font)
+                    .child(Self::render_section("Syntax", syntax)),
+            )
+    }
+}
+
+impl EventEmitter<ItemEvent> for ThemePreview {}
+
+impl FocusableView for ThemePreview {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ThemePreview {
+    type Event = ItemEvent;
+
+    fn tab_content(&self, _: Option<usize>, selected: bool, _: &WindowContext) -> AnyElement {
+        Label::new("Theme Preview")
+            .color(if selected {
+                Color::Default
+            } else {
+                Color::Muted
+            })
+            .into_any_element()
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("theme preview")
+    }
+
+    fn show_toolbar(&self) -> bool {
+        false
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: WorkspaceId,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>> {
+        Some(cx.new_view(Self::new))
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
@@ -1,25 +1,35 @@
+mod theme_preview;
+
 use client::telemetry::Telemetry;
 use feature_flags::FeatureFlagAppExt;
 use fs::Fs;
+use futures::StreamExt;
 use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
 use gpui::{
-    actions, AppContext, DismissEvent, EventEmitter, FocusableView, Render, View, ViewContext,
-    VisualContext, WeakView,
+    actions, AppContext, AsyncWindowContext, DismissEvent, EventEmitter, FocusableView,
+    PathPromptOptions, Render, Task, View, ViewContext, VisualContext, WeakView,
 };
 use picker::{Picker, PickerDelegate};
 use settings::{update_settings_file, SettingsStore};
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc, time::Duration};
 use theme::{Theme, ThemeMeta, ThemeRegistry, ThemeSettings};
 use ui::{prelude::*, v_flex, ListItem, ListItemSpacing};
 use util::ResultExt;
-use workspace::{ui::HighlightedLabel, ModalView, Workspace};
+use workspace::{ui::HighlightedLabel, ModalView, Toast, Workspace};
+
+pub use theme_preview::{Preview, ThemePreview};
 
 actions!(theme_selector, [Toggle, Reload]);
+actions!(theme, [WatchFile]);
+
+const THEME_FILE_ERROR_TOAST_ID: usize = 4096;
 
 pub fn init(cx: &mut AppContext) {
+    theme_preview::init(cx);
     cx.observe_new_views(
         |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
             workspace.register_action(toggle);
+            workspace.register_action(watch_file);
         },
     )
     .detach();
@@ -54,6 +64,82 @@ pub fn reload(cx: &mut AppContext) {
     }
 }
 
+/// The task that keeps the theme file being developed loaded, if any.
+struct ThemeFileWatcher(Option<Task<()>>);
+
+/// Loads the theme family in a file picked by the user and makes its first theme the
+/// active one, reloading it whenever the file changes so that theme authors can see their
+/// changes as they make them. Only one theme file is watched at a time.
+pub fn watch_file(workspace: &mut Workspace, _: &WatchFile, cx: &mut ViewContext<Workspace>) {
+    let fs = workspace.app_state().fs.clone();
+    let paths = cx.prompt_for_paths(PathPromptOptions {
+        files: true,
+        directories: false,
+        multiple: false,
+    });
+
+    cx.spawn(|workspace, mut cx| async move {
+        let Some(theme_path) = paths
+            .await
+            .log_err()
+            .flatten()
+            .and_then(|paths| paths.into_iter().next())
+        else {
+            return;
+        };
+
+        workspace
+            .update(&mut cx, |_, cx| {
+                let task =
+                    cx.spawn(|workspace, cx| watch_theme_file(theme_path, fs, workspace, cx));
+                cx.set_global(ThemeFileWatcher(Some(task)));
+            })
+            .log_err();
+    })
+    .detach();
+}
+
+async fn watch_theme_file(
+    theme_path: PathBuf,
+    fs: Arc<dyn Fs>,
+    workspace: WeakView<Workspace>,
+    mut cx: AsyncWindowContext,
+) {
+    let mut events = fs.watch(&theme_path, Duration::from_millis(100)).await;
+    loop {
+        let Ok(registry) = cx.update(|cx| ThemeRegistry::global(cx)) else {
+            return;
+        };
+
+        match registry.load_user_theme(&theme_path, fs.clone()).await {
+            Ok(theme_names) => {
+                let theme = theme_names.first().and_then(|name| registry.get(name).ok());
+                if let Some(theme) = theme {
+                    cx.update(|cx| ThemeSelectorDelegate::set_theme(theme, cx))
+                        .log_err();
+                }
+            }
+            Err(error) => {
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.show_toast(
+                            Toast::new(
+                                THEME_FILE_ERROR_TOAST_ID,
+                                format!("Failed to load theme: {error:#}"),
+                            ),
+                            cx,
+                        )
+                    })
+                    .ok();
+            }
+        }
+
+        if events.next().await.is_none() {
+            return;
+        }
+    }
+}
+
 impl ModalView for ThemeSelector {}
 
 pub struct ThemeSelector {
@@ -181,12 +267,13 @@ impl PickerDelegate for ThemeSelectorDelegate {
         self.selection_completed = true;
 
         let theme_name = cx.theme().name.clone();
+        let appearance = ThemeSettings::get_global(cx).theme_appearance(cx);
 
         self.telemetry
             .report_setting_event("theme", theme_name.to_string());
 
         update_settings_file::<ThemeSettings>(self.fs.clone(), cx, move |settings| {
-            settings.theme = Some(theme_name.to_string());
+            settings.set_theme(theme_name.to_string(), appearance);
         });
 
         self.view
@@ -0,0 +1,829 @@
+//! A panel listing the comments that are tagged with words like `TODO` or `FIXME`, grouped by
+//! file. The comments of open files are listed as they're edited, and those of the rest of
+//! the project can be found with project search when `scan_project` is enabled. Files that
+//! change on disk afterwards are scanned again individually.
+
+mod todo_panel_settings;
+
+use anyhow::Result;
+use collections::{BTreeMap, HashMap, HashSet};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, uniform_list, AppContext, AsyncWindowContext, EntityId, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, Model, ParentElement, Pixels, Render, SharedString, Styled,
+    Subscription, Task, UniformListScrollHandle, View, ViewContext, VisualContext, WeakView,
+    WindowContext,
+};
+use language::{Buffer, BufferSnapshot, Event as BufferEvent, LanguageScope};
+use project::{search::SearchQuery, Fs, Item as _, Project, ProjectPath};
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::{sync::Arc, time::Duration};
+use text::{Bias, Point};
+use ui::{prelude::*, ListItem};
+use util::ResultExt;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+pub use todo_panel_settings::TodoPanelSettings;
+
+const TODO_PANEL_KEY: &'static str = "TodoPanel";
+
+/// How long to wait after a buffer changes before scanning it again.
+const BUFFER_SCAN_DEBOUNCE: Duration = Duration::from_millis(300);
+
+/// How long to wait after files change on disk before scanning them again.
+const PROJECT_SCAN_DEBOUNCE: Duration = Duration::from_secs(1);
+
+actions!(todo_panel, [ToggleFocus]);
+
+pub fn init(cx: &mut AppContext) {
+    TodoPanelSettings::register(cx);
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+            workspace.toggle_panel_focus::<TodoPanel>(cx);
+        });
+    })
+    .detach();
+}
+
+/// A comment that starts with one of the configured tags.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TaggedComment {
+    pub tag: SharedString,
+    /// The rest of the comment's line after the tag.
+    pub text: SharedString,
+    pub row: u32,
+}
+
+pub struct TodoPanel {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    scroll_handle: UniformListScrollHandle,
+    width: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    tags: Vec<String>,
+    scan_project: bool,
+    comments_by_path: BTreeMap<ProjectPath, Vec<TaggedComment>>,
+    /// The open buffers whose comments are kept up to date, keyed by their entity ids.
+    buffers: HashMap<EntityId, TrackedBuffer>,
+    project_scan: Option<Task<Option<()>>>,
+    /// The paths that changed on disk and haven't been scanned again yet.
+    changed_paths: HashSet<ProjectPath>,
+    changed_paths_scan: Option<Task<Option<()>>>,
+    entries: Vec<ListEntry>,
+    selected_index: Option<usize>,
+    _subscriptions: Vec<Subscription>,
+}
+
+struct TrackedBuffer {
+    path: ProjectPath,
+    scan: Task<Option<()>>,
+    _subscriptions: [Subscription; 2],
+}
+
+/// A row of the panel: either a file, or one of the tagged comments in the file above it.
+enum ListEntry {
+    File {
+        path: ProjectPath,
+        comment_count: usize,
+    },
+    Comment {
+        path: ProjectPath,
+        comment: TaggedComment,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedTodoPanel {
+    width: Option<Pixels>,
+}
+
+impl TodoPanel {
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let project = workspace.project().clone();
+        let fs = workspace.app_state().fs.clone();
+        let workspace_handle = workspace.weak_handle();
+
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let settings = TodoPanelSettings::get_global(cx);
+            let tags = settings.tags.clone();
+            let scan_project = settings.scan_project;
+            let subscriptions = vec![
+                cx.subscribe(&project, |this, _, event, cx| match event {
+                    // Files are opened by activating them, at which point their buffers exist.
+                    project::Event::ActiveEntryChanged(_) => this.track_open_buffers(cx),
+                    project::Event::WorktreeUpdatedEntries(worktree_id, entries)
+                        if this.scan_project =>
+                    {
+                        this.changed_paths
+                            .extend(entries.iter().map(|(path, _, _)| ProjectPath {
+                                worktree_id: *worktree_id,
+                                path: path.clone(),
+                            }));
+                        this.scan_changed_paths(cx);
+                    }
+                    _ => {}
+                }),
+                cx.observe_global::<SettingsStore>(|this: &mut Self, cx| {
+                    let settings = TodoPanelSettings::get_global(cx);
+                    if settings.tags != this.tags || settings.scan_project != this.scan_project {
+                        this.tags = settings.tags.clone();
+                        this.scan_project = settings.scan_project;
+                        this.refresh(cx);
+                    }
+                    cx.notify();
+                }),
+            ];
+
+            let mut this = Self {
+                workspace: workspace_handle,
+                project,
+                fs,
+                focus_handle: cx.focus_handle(),
+                scroll_handle: UniformListScrollHandle::new(),
+                width: None,
+                pending_serialization: Task::ready(None),
+                tags,
+                scan_project,
+                comments_by_path: BTreeMap::default(),
+                buffers: HashMap::default(),
+                project_scan: None,
+                changed_paths: HashSet::default(),
+                changed_paths_scan: None,
+                entries: Vec::new(),
+                selected_index: None,
+                _subscriptions: subscriptions,
+            };
+            this.refresh(cx);
+            this
+        })
+    }
+
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = if let Some(panel) = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(TODO_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+            {
+                Some(serde_json::from_str::<SerializedTodoPanel>(&panel)?)
+            } else {
+                None
+            };
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.width = serialized_panel.width;
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let width = self.width;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        TODO_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedTodoPanel { width })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    /// Scans everything again, such as after the tags have changed.
+    fn refresh(&mut self, cx: &mut ViewContext<Self>) {
+        self.comments_by_path.clear();
+        self.update_entries(cx);
+        let buffers = self
+            .project
+            .read(cx)
+            .opened_buffers()
+            .into_iter()
+            .filter(|buffer| self.buffers.contains_key(&buffer.entity_id()))
+            .collect::<Vec<_>>();
+        for buffer in buffers {
+            self.scan_buffer(buffer, Duration::ZERO, cx);
+        }
+        self.track_open_buffers(cx);
+        if self.scan_project {
+            self.scan_project(cx);
+        } else {
+            self.project_scan = None;
+        }
+        self.changed_paths.clear();
+        self.changed_paths_scan = None;
+    }
+
+    fn track_open_buffers(&mut self, cx: &mut ViewContext<Self>) {
+        for buffer in self.project.read(cx).opened_buffers() {
+            self.track_buffer(buffer, cx);
+        }
+    }
+
+    /// Lists the buffer's tagged comments, and keeps them up to date until it's released.
+    fn track_buffer(&mut self, buffer: Model<Buffer>, cx: &mut ViewContext<Self>) {
+        let buffer_id = buffer.entity_id();
+        if self.buffers.contains_key(&buffer_id) {
+            return;
+        }
+        let Some(path) = buffer.read(cx).project_path(cx) else {
+            return;
+        };
+
+        let subscriptions = [
+            cx.subscribe(&buffer, |this, buffer, event, cx| match event {
+                // Comments are found using the syntax tree when there is one, so scan again
+                // once it's been updated too.
+                BufferEvent::Edited | BufferEvent::Reparsed => {
+                    this.scan_buffer(buffer, BUFFER_SCAN_DEBOUNCE, cx)
+                }
+                BufferEvent::FileHandleChanged => {
+                    let new_path = buffer.read(cx).project_path(cx);
+                    if let Some(tracked) = this.buffers.get_mut(&buffer.entity_id()) {
+                        if let Some(new_path) = new_path.filter(|path| *path != tracked.path) {
+                            let old_path = std::mem::replace(&mut tracked.path, new_path);
+                            this.comments_by_path.remove(&old_path);
+                            this.scan_buffer(buffer, Duration::ZERO, cx);
+                        }
+                    }
+                }
+                _ => {}
+            }),
+            cx.observe_release(&buffer, move |this, _, cx| {
+                if let Some(tracked) = this.buffers.remove(&buffer_id) {
+                    // The comments of closed files are only listed when scanning the project.
+                    if !this.scan_project {
+                        this.comments_by_path.remove(&tracked.path);
+                        this.update_entries(cx);
+                    }
+                }
+            }),
+        ];
+        self.buffers.insert(
+            buffer_id,
+            TrackedBuffer {
+                path,
+                scan: Task::ready(None),
+                _subscriptions: subscriptions,
+            },
+        );
+        self.scan_buffer(buffer, Duration::ZERO, cx);
+    }
+
+    fn scan_buffer(&mut self, buffer: Model<Buffer>, delay: Duration, cx: &mut ViewContext<Self>) {
+        let tags = self.tags.clone();
+        let Some(tracked) = self.buffers.get_mut(&buffer.entity_id()) else {
+            return;
+        };
+        let path = tracked.path.clone();
+        let buffer = buffer.downgrade();
+        tracked.scan = cx.spawn(|this, mut cx| async move {
+            if !delay.is_zero() {
+                cx.background_executor().timer(delay).await;
+            }
+            let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot()).ok()?;
+            let comments = cx
+                .background_executor()
+                .spawn(async move { tagged_comments(&snapshot, &tags) })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                if comments.is_empty() {
+                    this.comments_by_path.remove(&path);
+                } else {
+                    this.comments_by_path.insert(path, comments);
+                }
+                this.update_entries(cx);
+            })
+            .ok()
+        });
+    }
+
+    /// Finds the tagged comments of every file in the project, replacing those found before.
+    fn scan_project(&mut self, cx: &mut ViewContext<Self>) {
+        let Some(query) = search_query(&self.tags).log_err() else {
+            return;
+        };
+        let tags = self.tags.clone();
+        self.project_scan = Some(cx.spawn(|this, mut cx| async move {
+            let results = this
+                .update(&mut cx, |this, cx| {
+                    this.project
+                        .update(cx, |project, cx| project.search(query, cx))
+                })
+                .ok()?;
+            let mut buffers = Vec::new();
+            while let Ok((buffer, _)) = results.recv().await {
+                buffers.push(buffer);
+            }
+            let snapshots = this
+                .update(&mut cx, |_, cx| {
+                    buffers
+                        .iter()
+                        .filter_map(|buffer| {
+                            let buffer = buffer.read(cx);
+                            Some((buffer.project_path(cx)?, buffer.snapshot()))
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .ok()?;
+            drop(buffers);
+
+            let comments_by_path = cx
+                .background_executor()
+                .spawn(async move {
+                    snapshots
+                        .into_iter()
+                        .map(|(path, snapshot)| (path, tagged_comments(&snapshot, &tags)))
+                        .filter(|(_, comments)| !comments.is_empty())
+                        .collect()
+                })
+                .await;
+            this.update(&mut cx, |this, cx| {
+                this.comments_by_path = comments_by_path;
+                this.update_entries(cx);
+            })
+            .ok()
+        }));
+    }
+
+    /// Scans the files that changed on disk again, rather than the whole project. Open files
+    /// are skipped, since their buffers are scanned as they change.
+    fn scan_changed_paths(&mut self, cx: &mut ViewContext<Self>) {
+        if self.changed_paths_scan.is_some() {
+            return;
+        }
+        let Some(query) = search_query(&self.tags).log_err() else {
+            return;
+        };
+        let tags = self.tags.clone();
+        let fs = self.fs.clone();
+        self.changed_paths_scan = Some(cx.spawn(|this, mut cx| async move {
+            loop {
+                cx.background_executor().timer(PROJECT_SCAN_DEBOUNCE).await;
+                let paths = this
+                    .update(&mut cx, |this, cx| {
+                        let open_paths = this
+                            .buffers
+                            .values()
+                            .map(|tracked| &tracked.path)
+                            .collect::<HashSet<_>>();
+                        let project = this.project.read(cx);
+                        this.changed_paths
+                            .drain()
+                            .filter(|path| !open_paths.contains(path))
+                            .map(|path| {
+                                let abs_path = project
+                                    .entry_for_path(&path, cx)
+                                    .filter(|entry| entry.is_file() && !entry.is_ignored)
+                                    .and_then(|_| project.absolute_path(&path, cx));
+                                (path, abs_path)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                    .ok()?;
+
+                for (path, abs_path) in paths {
+                    // Like project search, only open the files whose contents match.
+                    let matches = match abs_path {
+                        Some(abs_path) => fs
+                            .load(&abs_path)
+                            .await
+                            .map_or(false, |text| query.detect(text.as_bytes()).unwrap_or(false)),
+                        None => false,
+                    };
+                    let mut comments = Vec::new();
+                    if matches {
+                        let buffer = this
+                            .update(&mut cx, |this, cx| {
+                                this.project
+                                    .update(cx, |project, cx| project.open_buffer(path.clone(), cx))
+                            })
+                            .ok()?
+                            .await
+                            .log_err();
+                        if let Some(buffer) = buffer {
+                            let snapshot =
+                                buffer.read_with(&cx, |buffer, _| buffer.snapshot()).ok()?;
+                            let tags = tags.clone();
+                            comments = cx
+                                .background_executor()
+                                .spawn(async move { tagged_comments(&snapshot, &tags) })
+                                .await;
+                        }
+                    }
+                    this.update(&mut cx, |this, cx| {
+                        if comments.is_empty() {
+                            this.comments_by_path.remove(&path);
+                        } else {
+                            this.comments_by_path.insert(path, comments);
+                        }
+                        this.update_entries(cx);
+                    })
+                    .ok()?;
+                }
+
+                let done = this
+                    .update(&mut cx, |this, _| {
+                        let done = this.changed_paths.is_empty();
+                        if done {
+                            this.changed_paths_scan = None;
+                        }
+                        done
+                    })
+                    .ok()?;
+                if done {
+                    return Some(());
+                }
+            }
+        }));
+    }
+
+    fn update_entries(&mut self, cx: &mut ViewContext<Self>) {
+        self.entries.clear();
+        for (path, comments) in &self.comments_by_path {
+            self.entries.push(ListEntry::File {
+                path: path.clone(),
+                comment_count: comments.len(),
+            });
+            self.entries
+                .extend(comments.iter().map(|comment| ListEntry::Comment {
+                    path: path.clone(),
+                    comment: comment.clone(),
+                }));
+        }
+        if let Some(selected_index) = self.selected_index.as_mut() {
+            *selected_index = (*selected_index).min(self.entries.len().saturating_sub(1));
+        }
+        cx.notify();
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, cx: &mut ViewContext<Self>) {
+        let ix = self.selected_index.map_or(0, |ix| ix + 1);
+        if ix < self.entries.len() {
+            self.select(ix, cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &menu::SelectPrev, cx: &mut ViewContext<Self>) {
+        let ix = self
+            .selected_index
+            .map_or(self.entries.len(), |ix| ix)
+            .saturating_sub(1);
+        if ix < self.entries.len() {
+            self.select(ix, cx);
+        }
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_index = Some(ix);
+        self.scroll_handle.scroll_to_item(ix);
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_index {
+            self.open_entry(ix, cx);
+        }
+    }
+
+    /// Opens the entry's file, at the comment or at the file's first comment.
+    fn open_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let (path, row) = match self.entries.get(ix) {
+            Some(ListEntry::File { path, .. }) => {
+                let row = self
+                    .comments_by_path
+                    .get(path)
+                    .and_then(|comments| comments.first())
+                    .map_or(0, |comment| comment.row);
+                (path.clone(), row)
+            }
+            Some(ListEntry::Comment { path, comment }) => (path.clone(), comment.row),
+            None => return,
+        };
+        let Some(workspace) = self.workspace.upgrade() else {
+            return;
+        };
+        let open = workspace.update(cx, |workspace, cx| {
+            workspace.open_path(path, None, true, cx)
+        });
+        cx.spawn(|_, mut cx| async move {
+            let item = open.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update(&mut cx, |editor, cx| {
+                    let snapshot = editor.buffer().read(cx).snapshot(cx);
+                    let point = snapshot.clip_point(Point::new(row, 0), Bias::Left);
+                    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+                        s.select_ranges([point..point])
+                    });
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut ViewContext<Self>) -> ListItem {
+        let item = ListItem::new(ix)
+            .selected(self.selected_index == Some(ix))
+            .on_click(cx.listener(move |this, _, cx| {
+                this.select(ix, cx);
+                this.open_entry(ix, cx);
+            }));
+        match &self.entries[ix] {
+            ListEntry::File {
+                path,
+                comment_count,
+            } => item
+                .start_slot(Icon::new(IconName::File).color(Color::Muted))
+                .child(Label::new(path.path.to_string_lossy().to_string()))
+                .end_slot(Label::new(comment_count.to_string()).color(Color::Muted)),
+            ListEntry::Comment { comment, .. } => item
+                .indent_level(1)
+                .child(
+                    h_flex()
+                        .gap_2()
+                        .child(Label::new(comment.tag.clone()).color(Color::Accent))
+                        .child(Label::new(comment.text.clone())),
+                )
+                .end_slot(Label::new((comment.row + 1).to_string()).color(Color::Muted)),
+        }
+    }
+}
+
+/// The query that finds the files containing any of the tags.
+fn search_query(tags: &[String]) -> Result<SearchQuery> {
+    let tags = tags
+        .iter()
+        .filter(|tag| !tag.is_empty())
+        .map(|tag| regex::escape(tag))
+        .collect::<Vec<_>>();
+    SearchQuery::regex(
+        format!("\\b(?:{})\\b", tags.join("|")),
+        false,
+        true,
+        false,
+        Vec::new(),
+        Vec::new(),
+    )
+}
+
+/// Finds the comments in the buffer that contain one of the tags as a whole word, taking at
+/// most one from each line.
+pub fn tagged_comments(snapshot: &BufferSnapshot, tags: &[String]) -> Vec<TaggedComment> {
+    let mut comments = Vec::new();
+    let mut line_offset = 0;
+    for (row, line) in snapshot.text().split('\n').enumerate() {
+        if let Some((column, tag)) = find_tag(line, tags) {
+            let scope = snapshot.language_scope_at(line_offset + column);
+            if is_comment(scope.as_ref(), &line[..column]) {
+                let mut text = line[column + tag.len()..]
+                    .trim_start_matches(|c: char| c == ':' || c.is_whitespace())
+                    .trim_end();
+                if let Some((_, end)) = scope.as_ref().and_then(|s| s.block_comment_delimiters()) {
+                    text = text.strip_suffix(end.trim()).unwrap_or(text).trim_end();
+                }
+                comments.push(TaggedComment {
+                    tag: tag.clone().into(),
+                    text: text.to_string().into(),
+                    row: row as u32,
+                });
+            }
+        }
+        line_offset += line.len() + 1;
+    }
+    comments
+}
+
+/// The first of the tags to appear in the line as a whole word, and where it starts.
+fn find_tag<'a>(line: &str, tags: &'a [String]) -> Option<(usize, &'a String)> {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    tags.iter()
+        .filter(|tag| !tag.is_empty())
+        .filter_map(|tag| {
+            line.match_indices(tag.as_str())
+                .map(|(ix, _)| ix)
+                .find(|&ix| {
+                    !line[..ix].chars().next_back().map_or(false, is_word_char)
+                        && !line[ix + tag.len()..]
+                            .chars()
+                            .next()
+                            .map_or(false, is_word_char)
+                })
+                .map(|ix| (ix, tag))
+        })
+        .min_by_key(|(ix, _)| *ix)
+}
+
+/// Whether a tag is within a comment, given the language scope at the tag and the text of its
+/// line before it.
+fn is_comment(scope: Option<&LanguageScope>, line_before_tag: &str) -> bool {
+    let Some(scope) = scope else {
+        return true;
+    };
+    // Languages whose syntax tree knows where comments are capture them as overrides.
+    if let Some(name) = scope.override_name() {
+        return name == "comment";
+    }
+    let mut comment_starts = scope
+        .line_comment_prefixes()
+        .into_iter()
+        .flatten()
+        .map(|prefix| prefix.trim())
+        .chain(
+            scope
+                .block_comment_delimiters()
+                .map(|(start, _)| start.trim()),
+        )
+        .filter(|start| !start.is_empty())
+        .peekable();
+    // Every tag counts in files without comments, like plain text.
+    if comment_starts.peek().is_none() {
+        return true;
+    }
+    comment_starts.any(|start| line_before_tag.contains(start))
+}
+
+impl EventEmitter<PanelEvent> for TodoPanel {}
+
+impl FocusableView for TodoPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TodoPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .id("todo-panel")
+            .key_context("TodoPanel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .map(|this| {
+                if self.entries.is_empty() {
+                    this.child(
+                        h_flex().size_full().justify_center().child(
+                            Label::new("No tagged comments")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        ),
+                    )
+                } else {
+                    this.child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "entries",
+                            self.entries.len(),
+                            |this, range, cx| range.map(|ix| this.render_entry(ix, cx)).collect(),
+                        )
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    )
+                }
+            })
+    }
+}
+
+impl Panel for TodoPanel {
+    fn persistent_name() -> &'static str {
+        "TodoPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        TodoPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, position: DockPosition) -> bool {
+        matches!(position, DockPosition::Left | DockPosition::Right)
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<TodoPanelSettings>(self.fs.clone(), cx, move |settings| {
+            settings.dock = Some(position)
+        });
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.width
+            .unwrap_or_else(|| TodoPanelSettings::get_global(cx).default_width)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.width = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        TodoPanelSettings::get_global(cx)
+            .button
+            .then_some(IconName::ListTodo)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Todo Panel")
+    }
+
+    fn icon_label(&self, _cx: &WindowContext) -> Option<String> {
+        let count = self
+            .comments_by_path
+            .values()
+            .map(|comments| comments.len())
+            .sum::<usize>();
+        (count > 0).then(|| count.to_string())
+    }
+
+    fn toggle_action(&self) -> Box<dyn gpui::Action> {
+        Box::new(ToggleFocus)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{Context, TestAppContext};
+    use language::{Language, LanguageConfig};
+    use text::BufferId;
+    use unindent::Unindent as _;
+
+    #[gpui::test]
+    fn test_tagged_comments(cx: &mut TestAppContext) {
+        let tags = ["TODO".to_string(), "FIXME".to_string()];
+        let language = Arc::new(Language::new(
+            LanguageConfig {
+                name: "Test".into(),
+                line_comments: vec!["// ".into()],
+                block_comment: Some(("/* ".into(), " */".into())),
+                ..Default::default()
+            },
+            None,
+        ));
+        let text = r#"
+            // TODO: first
+            let TODO = "not in a comment";
+            let todos = 1; // FIXME(someone) second
+            /* TODO third */
+            // TODOS is not a tag
+        "#
+        .unindent();
+
+        let buffer = cx.new_model(|cx| {
+            Buffer::new(0, BufferId::new(1).unwrap(), text.clone()).with_language(language, cx)
+        });
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        assert_eq!(
+            tagged_comments(&snapshot, &tags),
+            [
+                TaggedComment {
+                    tag: "TODO".into(),
+                    text: "first".into(),
+                    row: 0,
+                },
+                TaggedComment {
+                    tag: "FIXME".into(),
+                    text: "(someone) second".into(),
+                    row: 2,
+                },
+                TaggedComment {
+                    tag: "TODO".into(),
+                    text: "third".into(),
+                    row: 3,
+                },
+            ]
+        );
+
+        // Without a language, tags count wherever they are.
+        let buffer = cx.new_model(|_| Buffer::new(0, BufferId::new(2).unwrap(), text));
+        let snapshot = buffer.read_with(cx, |buffer, _| buffer.snapshot());
+        assert_eq!(
+            tagged_comments(&snapshot, &tags)
+                .iter()
+                .map(|comment| comment.row)
+                .collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+    }
+}
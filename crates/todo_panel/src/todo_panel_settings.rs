@@ -0,0 +1,54 @@
+use anyhow;
+use gpui::Pixels;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::Settings;
+use workspace::dock::DockPosition;
+
+#[derive(Deserialize, Debug)]
+pub struct TodoPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_width: Pixels,
+    pub tags: Vec<String>,
+    pub scan_project: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct TodoPanelSettingsContent {
+    /// Whether to show the panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the panel.
+    ///
+    /// Default: right
+    pub dock: Option<DockPosition>,
+    /// Default width of the panel in pixels.
+    ///
+    /// Default: 300
+    pub default_width: Option<f32>,
+    /// The words that mark a comment to be listed in the panel.
+    ///
+    /// Default: ["TODO", "FIXME", "HACK", "XXX"]
+    pub tags: Option<Vec<String>>,
+    /// Whether to list the tagged comments of every file in the project, rather than only
+    /// those of open files.
+    ///
+    /// Default: false
+    pub scan_project: Option<bool>,
+}
+
+impl Settings for TodoPanelSettings {
+    const KEY: Option<&'static str> = Some("todo_panel");
+
+    type FileContent = TodoPanelSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
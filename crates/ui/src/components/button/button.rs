@@ -361,7 +361,7 @@ impl RenderOnce for Button {
             self.label_color.unwrap_or_default()
         };
 
-        self.base.child(
+        self.base.accessibility_label(label.clone()).child(
             h_flex()
                 .gap_1()
                 .when(self.icon_position == Some(IconPosition::Start), |this| {
@@ -2,7 +2,7 @@ use gpui::{relative, DefiniteLength, MouseButton};
 use gpui::{rems, transparent_black, AnyElement, AnyView, ClickEvent, Hsla, Rems};
 use smallvec::SmallVec;
 
-use crate::prelude::*;
+use crate::{density_rems, prelude::*};
 
 /// A trait for buttons that can be Selected. Enables setting the [`ButtonStyle`] of a button when it is selected.
 pub trait SelectableButton: Selectable {
@@ -276,11 +276,11 @@ pub enum ButtonSize {
 }
 
 impl ButtonSize {
-    fn height(self) -> Rems {
+    fn height(self, cx: &WindowContext) -> Rems {
         match self {
-            ButtonSize::Large => rems(32. / 16.),
-            ButtonSize::Default => rems(22. / 16.),
-            ButtonSize::Compact => rems(18. / 16.),
+            ButtonSize::Large => density_rems(32. / 16., cx),
+            ButtonSize::Default => density_rems(22. / 16., cx),
+            ButtonSize::Compact => density_rems(18. / 16., cx),
             ButtonSize::None => rems(16. / 16.),
         }
     }
@@ -305,6 +305,7 @@ pub struct ButtonLike {
     rounding: Option<ButtonLikeRounding>,
     tooltip: Option<Box<dyn Fn(&mut WindowContext) -> AnyView>>,
     on_click: Option<Box<dyn Fn(&ClickEvent, &mut WindowContext) + 'static>>,
+    accessibility_label: Option<SharedString>,
     children: SmallVec<[AnyElement; 2]>,
 }
 
@@ -324,9 +325,15 @@ impl ButtonLike {
             tooltip: None,
             children: SmallVec::new(),
             on_click: None,
+            accessibility_label: None,
         }
     }
 
+    pub(crate) fn accessibility_label(mut self, label: SharedString) -> Self {
+        self.accessibility_label = Some(label);
+        self
+    }
+
     pub(crate) fn height(mut self, height: DefiniteLength) -> Self {
         self.height = Some(height);
         self
@@ -424,8 +431,12 @@ impl RenderOnce for ButtonLike {
             .id(self.id.clone())
             .group("")
             .flex_none()
-            .h(self.height.unwrap_or(self.size.height().into()))
+            .h(self.height.unwrap_or(self.size.height(cx).into()))
             .when_some(self.width, |this, width| this.w(width).justify_center())
+            .accessibility_role(AccessibilityRole::Button)
+            .when_some(self.accessibility_label, |this, label| {
+                this.accessibility_label(label)
+            })
             .when_some(self.rounding, |this, rounding| match rounding {
                 ButtonLikeRounding::All => this.rounded_md(),
                 ButtonLikeRounding::Left => this.rounded_l_md(),
@@ -433,8 +444,8 @@ impl RenderOnce for ButtonLike {
             })
             .gap_1()
             .map(|this| match self.size {
-                ButtonSize::Large => this.px_2(),
-                ButtonSize::Default | ButtonSize::Compact => this.px_1(),
+                ButtonSize::Large => this.px(density_rems(0.5, cx)),
+                ButtonSize::Default | ButtonSize::Compact => this.px(density_rems(0.25, cx)),
                 ButtonSize::None => this,
             })
             .bg(style.enabled(cx).background)
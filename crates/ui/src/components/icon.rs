@@ -41,6 +41,7 @@ pub enum IconName {
     BellRing,
     BellDot,
     Bolt,
+    Bookmark,
     CaseSensitive,
     Check,
     ChevronDown,
@@ -81,6 +82,7 @@ pub enum IconName {
     Hash,
     InlayHint,
     Link,
+    ListTodo,
     MagicWand,
     MagnifyingGlass,
     MailOpen,
@@ -133,6 +135,7 @@ impl IconName {
             IconName::BellRing => "icons/bell_ring.svg",
             IconName::BellDot => "icons/bell_dot.svg",
             IconName::Bolt => "icons/bolt.svg",
+            IconName::Bookmark => "icons/bookmark.svg",
             IconName::CaseSensitive => "icons/case_insensitive.svg",
             IconName::Check => "icons/check.svg",
             IconName::ChevronDown => "icons/chevron_down.svg",
@@ -173,6 +176,7 @@ impl IconName {
             IconName::Hash => "icons/hash.svg",
             IconName::InlayHint => "icons/inlay_hint.svg",
             IconName::Link => "icons/link.svg",
+            IconName::ListTodo => "icons/list_todo.svg",
             IconName::MagicWand => "icons/magic_wand.svg",
             IconName::MagnifyingGlass => "icons/magnifying_glass.svg",
             IconName::MailOpen => "icons/mail_open.svg",
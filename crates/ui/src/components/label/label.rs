@@ -114,6 +114,8 @@ impl LabelCommon for Label {
 
 impl RenderOnce for Label {
     fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
-        self.base.child(self.label)
+        self.base
+            .accessibility_label(self.label.clone())
+            .child(self.label)
     }
 }
@@ -40,6 +40,7 @@ pub struct LabelLike {
     line_height_style: LineHeightStyle,
     pub(crate) color: Color,
     strikethrough: bool,
+    accessibility_label: Option<SharedString>,
     children: SmallVec<[AnyElement; 2]>,
 }
 
@@ -50,9 +51,16 @@ impl LabelLike {
             line_height_style: LineHeightStyle::default(),
             color: Color::Default,
             strikethrough: false,
+            accessibility_label: None,
             children: SmallVec::new(),
         }
     }
+
+    /// Sets the text assistive technologies read for the label.
+    pub(crate) fn accessibility_label(mut self, label: SharedString) -> Self {
+        self.accessibility_label = Some(label);
+        self
+    }
 }
 
 impl LabelCommon for LabelLike {
@@ -105,6 +113,10 @@ impl RenderOnce for LabelLike {
                 this.line_height(relative(1.))
             })
             .text_color(self.color.color(cx))
+            .when_some(self.accessibility_label, |this, label| {
+                this.accessibility_role(AccessibilityRole::Label)
+                    .accessibility_label(label)
+            })
             .children(self.children)
     }
 }
@@ -1,7 +1,7 @@
 use gpui::AnyElement;
 use smallvec::SmallVec;
 
-use crate::{prelude::*, v_flex, Label, ListHeader};
+use crate::{density_rems, prelude::*, v_flex, Label, ListHeader};
 
 #[derive(IntoElement)]
 pub struct List {
@@ -46,13 +46,15 @@ impl ParentElement for List {
 }
 
 impl RenderOnce for List {
-    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
-        v_flex().w_full().py_1().children(self.header).map(|this| {
-            match (self.children.is_empty(), self.toggle) {
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        v_flex()
+            .w_full()
+            .py(density_rems(0.25, cx))
+            .children(self.header)
+            .map(|this| match (self.children.is_empty(), self.toggle) {
                 (false, _) => this.children(self.children),
                 (true, Some(false)) => this,
                 (true, _) => this.child(Label::new(self.empty_message.clone()).color(Color::Muted)),
-            }
-        })
+            })
     }
 }
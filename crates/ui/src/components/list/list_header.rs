@@ -1,6 +1,6 @@
 use std::sync::Arc;
 
-use crate::{h_flex, prelude::*, Disclosure, Label};
+use crate::{density_rems, h_flex, prelude::*, Disclosure, Label};
 use gpui::{AnyElement, ClickEvent};
 
 #[derive(IntoElement)]
@@ -85,7 +85,7 @@ impl RenderOnce for ListHeader {
             .group("list_header")
             .child(
                 div()
-                    .h_7()
+                    .h(density_rems(1.75, cx))
                     .when(self.inset, |this| this.px_2())
                     .when(self.selected, |this| {
                         this.bg(cx.theme().colors().ghost_element_selected)
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use gpui::{px, AnyElement, AnyView, ClickEvent, MouseButton, MouseDownEvent, Pixels};
 use smallvec::SmallVec;
 
-use crate::{prelude::*, Disclosure};
+use crate::{density_rems, prelude::*, Disclosure};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
 pub enum ListItemSpacing {
@@ -152,6 +152,8 @@ impl RenderOnce for ListItem {
             .id(self.id)
             .w_full()
             .relative()
+            .accessibility_role(AccessibilityRole::ListItem)
+            .accessibility_selected(self.selected)
             // When an item is inset draw the indent spacing outside of the item
             .when(self.inset, |this| {
                 this.ml(self.indent_level as f32 * self.indent_step_size)
@@ -176,10 +178,10 @@ impl RenderOnce for ListItem {
                     .w_full()
                     .relative()
                     .gap_1()
-                    .px_2()
+                    .px(density_rems(0.5, cx))
                     .map(|this| match self.spacing {
                         ListItemSpacing::Dense => this,
-                        ListItemSpacing::Sparse => this.py_1(),
+                        ListItemSpacing::Sparse => this.py(density_rems(0.25, cx)),
                     })
                     .group("list_item")
                     .when(self.inset && !self.disabled, |this| {
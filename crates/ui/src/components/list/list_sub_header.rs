@@ -1,5 +1,5 @@
 use crate::prelude::*;
-use crate::{h_flex, Icon, IconName, IconSize, Label};
+use crate::{density_rems, h_flex, Icon, IconName, IconSize, Label};
 
 #[derive(IntoElement)]
 pub struct ListSubHeader {
@@ -24,28 +24,34 @@ impl ListSubHeader {
 }
 
 impl RenderOnce for ListSubHeader {
-    fn render(self, _cx: &mut WindowContext) -> impl IntoElement {
-        h_flex().flex_1().w_full().relative().py_1().child(
-            div()
-                .h_6()
-                .when(self.inset, |this| this.px_2())
-                .flex()
-                .flex_1()
-                .w_full()
-                .gap_1()
-                .items_center()
-                .justify_between()
-                .child(
-                    div()
-                        .flex()
-                        .gap_1()
-                        .items_center()
-                        .children(
-                            self.start_slot
-                                .map(|i| Icon::new(i).color(Color::Muted).size(IconSize::Small)),
-                        )
-                        .child(Label::new(self.label.clone()).color(Color::Muted)),
-                ),
-        )
+    fn render(self, cx: &mut WindowContext) -> impl IntoElement {
+        h_flex()
+            .flex_1()
+            .w_full()
+            .relative()
+            .py(density_rems(0.25, cx))
+            .child(
+                div()
+                    .h_6()
+                    .when(self.inset, |this| this.px_2())
+                    .flex()
+                    .flex_1()
+                    .w_full()
+                    .gap_1()
+                    .items_center()
+                    .justify_between()
+                    .child(
+                        div()
+                            .flex()
+                            .gap_1()
+                            .items_center()
+                            .children(
+                                self.start_slot.map(|i| {
+                                    Icon::new(i).color(Color::Muted).size(IconSize::Small)
+                                }),
+                            )
+                            .child(Label::new(self.label.clone()).color(Color::Muted)),
+                    ),
+            )
     }
 }
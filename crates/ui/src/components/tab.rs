@@ -1,4 +1,4 @@
-use crate::prelude::*;
+use crate::{density_rems, prelude::*};
 use gpui::{AnyElement, IntoElement, Stateful};
 use smallvec::SmallVec;
 use std::cmp::Ordering;
@@ -116,7 +116,9 @@ impl RenderOnce for Tab {
         };
 
         self.div
-            .h(rems(Self::CONTAINER_HEIGHT_IN_REMS))
+            .accessibility_role(AccessibilityRole::Tab)
+            .accessibility_selected(self.selected)
+            .h(density_rems(Self::CONTAINER_HEIGHT_IN_REMS, cx))
             .bg(tab_bg)
             .border_color(cx.theme().colors().border)
             .map(|this| match self.position {
@@ -143,7 +145,7 @@ impl RenderOnce for Tab {
                 h_flex()
                     .group("")
                     .relative()
-                    .h(rems(Self::CONTENT_HEIGHT_IN_REMS))
+                    .h(density_rems(Self::CONTENT_HEIGHT_IN_REMS, cx))
                     .px_5()
                     .gap_1()
                     .text_color(text_color)
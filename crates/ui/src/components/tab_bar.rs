@@ -1,7 +1,7 @@
 use gpui::{AnyElement, ScrollHandle};
 use smallvec::SmallVec;
 
-use crate::prelude::*;
+use crate::{density_rems, prelude::*};
 
 #[derive(IntoElement)]
 pub struct TabBar {
@@ -98,7 +98,7 @@ impl RenderOnce for TabBar {
             .flex()
             .flex_none()
             .w_full()
-            .h(rems(HEIGHT_IN_REMS))
+            .h(density_rems(HEIGHT_IN_REMS, cx))
             .bg(cx.theme().colors().tab_bar_background)
             .when(!self.start_children.is_empty(), |this| {
                 this.child(
@@ -131,6 +131,7 @@ impl RenderOnce for TabBar {
                     .child(
                         h_flex()
                             .id("tabs")
+                            .accessibility_role(AccessibilityRole::TabList)
                             .z_index(2)
                             .flex_grow()
                             .overflow_x_scroll()
@@ -1,5 +1,4 @@
 use gpui::{overlay, Action, AnyView, IntoElement, Render, VisualContext};
-use settings::Settings;
 use theme::ThemeSettings;
 
 use crate::prelude::*;
@@ -89,7 +88,7 @@ pub fn tooltip_container<V>(
     cx: &mut ViewContext<V>,
     f: impl FnOnce(Div, &mut ViewContext<V>) -> Div,
 ) -> impl IntoElement {
-    let ui_font = ThemeSettings::get_global(cx).ui_font.family.clone();
+    let ui_font = ThemeSettings::for_window(cx).ui_font.family.clone();
     overlay().child(
         // padding to avoid mouse cursor
         div().pl_2().pt_2p5().child(
@@ -2,9 +2,9 @@
 
 pub use gpui::prelude::*;
 pub use gpui::{
-    div, px, relative, rems, AbsoluteLength, DefiniteLength, Div, Element, ElementContext,
-    ElementId, InteractiveElement, ParentElement, Pixels, Rems, RenderOnce, SharedString, Styled,
-    ViewContext, WindowContext,
+    div, px, relative, rems, AbsoluteLength, AccessibilityRole, DefiniteLength, Div, Element,
+    ElementContext, ElementId, InteractiveElement, ParentElement, Pixels, Rems, RenderOnce,
+    SharedString, Styled, ViewContext, WindowContext,
 };
 
 pub use crate::clickable::*;
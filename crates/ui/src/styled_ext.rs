@@ -1,5 +1,4 @@
 use gpui::{hsla, px, Styled, WindowContext};
-use settings::Settings;
 use theme::ThemeSettings;
 
 use crate::prelude::*;
@@ -75,7 +74,7 @@ pub trait StyledExt: Styled + Sized {
     /// This should only be used for text that is displayed in a buffer,
     /// or other places that text needs to match the user's buffer font size.
     fn text_buffer(self, cx: &mut WindowContext) -> Self {
-        let settings = ThemeSettings::get_global(cx);
+        let settings = ThemeSettings::for_window(cx);
         self.text_size(settings.buffer_font_size(cx))
     }
 
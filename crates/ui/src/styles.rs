@@ -1,9 +1,11 @@
 mod color;
 mod elevation;
+mod spacing;
 mod typography;
 mod units;
 
 pub use color::*;
 pub use elevation::*;
+pub use spacing::*;
 pub use typography::*;
 pub use units::*;
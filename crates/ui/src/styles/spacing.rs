@@ -0,0 +1,9 @@
+use gpui::{rems, Rems, WindowContext};
+use theme::ThemeSettings;
+
+/// Returns a padding or height, given in rems at the default UI density, adjusted for the
+/// user's `ui_density` setting.
+pub fn density_rems(rems_at_default_density: f32, cx: &WindowContext) -> Rems {
+    let ui_density = ThemeSettings::for_window(cx).ui_density;
+    rems(rems_at_default_density * ui_density.spacing_ratio())
+}
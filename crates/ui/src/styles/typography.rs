@@ -1,7 +1,6 @@
 use gpui::{
     div, rems, IntoElement, ParentElement, Rems, RenderOnce, SharedString, Styled, WindowContext,
 };
-use settings::Settings;
 use theme::{ActiveTheme, ThemeSettings};
 
 #[derive(Debug, Default, Clone)]
@@ -80,7 +79,7 @@ pub struct Headline {
 
 impl RenderOnce for Headline {
     fn render(self, cx: &mut WindowContext) -> impl IntoElement {
-        let ui_font = ThemeSettings::get_global(cx).ui_font.family.clone();
+        let ui_font = ThemeSettings::for_window(cx).ui_font.family.clone();
 
         div()
             .font(ui_font)
@@ -9,12 +9,25 @@ pub use isahc::{AsyncBody, Request, Response};
 use smol::future::FutureExt;
 #[cfg(feature = "test-support")]
 use std::fmt;
-use std::{sync::Arc, time::Duration};
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+    time::Duration,
+};
 pub use url::Url;
 
 pub trait HttpClient: Send + Sync {
     fn send(&self, req: Request<AsyncBody>) -> BoxFuture<Result<Response<AsyncBody>, Error>>;
 
+    /// The proxy that requests are sent through, if one has been configured. Other
+    /// connections to the network should go through it too.
+    fn proxy(&self) -> Option<Uri> {
+        None
+    }
+
     fn get<'a>(
         &'a self,
         uri: &str,
@@ -54,13 +67,18 @@ pub trait HttpClient: Send + Sync {
 }
 
 pub fn client() -> Arc<dyn HttpClient> {
-    Arc::new(
-        isahc::HttpClient::builder()
-            .connect_timeout(Duration::from_secs(5))
-            .low_speed_timeout(100, Duration::from_secs(5))
-            .build()
-            .unwrap(),
-    )
+    Arc::new(isahc_client(None))
+}
+
+fn isahc_client(proxy: Option<Uri>) -> isahc::HttpClient {
+    let mut builder = isahc::HttpClient::builder()
+        .connect_timeout(Duration::from_secs(5))
+        .low_speed_timeout(100, Duration::from_secs(5));
+    // Without a proxy, the one in the environment's `http_proxy` variables is used.
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(Some(proxy));
+    }
+    builder.build().unwrap()
 }
 
 impl HttpClient for isahc::HttpClient {
@@ -69,6 +87,54 @@ impl HttpClient for isahc::HttpClient {
     }
 }
 
+/// An HTTP client whose proxy can be changed while it's in use, such as when the
+/// user's settings change, and which can be taken offline.
+pub struct HttpClientWithProxy {
+    state: RwLock<(Option<Uri>, isahc::HttpClient)>,
+    offline: AtomicBool,
+}
+
+impl HttpClientWithProxy {
+    pub fn new(proxy: Option<Uri>) -> Self {
+        let client = isahc_client(proxy.clone());
+        Self {
+            state: RwLock::new((proxy, client)),
+            offline: AtomicBool::new(false),
+        }
+    }
+
+    /// Fails subsequent requests without sending them while offline.
+    pub fn set_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
+    /// Sends subsequent requests through the given proxy. Supports `http`, `https`,
+    /// `socks4`, `socks4a`, `socks5` and `socks5h` proxy URLs, which may include
+    /// credentials. The `proxy` setting leaves out `https`, which collaboration
+    /// connections can't be tunneled through.
+    pub fn set_proxy(&self, proxy: Option<Uri>) {
+        let mut state = self.state.write().unwrap();
+        if state.0 != proxy {
+            *state = (proxy.clone(), isahc_client(proxy));
+        }
+    }
+}
+
+impl HttpClient for HttpClientWithProxy {
+    fn send(&self, req: Request<AsyncBody>) -> BoxFuture<Result<Response<AsyncBody>, Error>> {
+        if self.offline.load(Ordering::SeqCst) {
+            let error = io::Error::new(io::ErrorKind::NotConnected, "offline mode is enabled");
+            return Box::pin(async move { Err(error.into()) });
+        }
+        let client = self.state.read().unwrap().1.clone();
+        Box::pin(async move { client.send_async(req).await })
+    }
+
+    fn proxy(&self) -> Option<Uri> {
+        self.state.read().unwrap().0.clone()
+    }
+}
+
 #[cfg(feature = "test-support")]
 pub struct FakeHttpClient {
     handler: Box<
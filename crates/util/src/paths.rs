@@ -9,6 +9,7 @@ lazy_static::lazy_static! {
     pub static ref CONVERSATIONS_DIR: PathBuf = HOME.join(".config/zed/conversations");
     pub static ref EMBEDDINGS_DIR: PathBuf = HOME.join(".config/zed/embeddings");
     pub static ref THEMES_DIR: PathBuf = HOME.join(".config/zed/themes");
+    pub static ref ICON_THEMES_DIR: PathBuf = HOME.join(".config/zed/icon_themes");
     pub static ref LOGS_DIR: PathBuf = HOME.join("Library/Logs/Zed");
     pub static ref SUPPORT_DIR: PathBuf = HOME.join("Library/Application Support/Zed");
     pub static ref PLUGINS_DIR: PathBuf = HOME.join("Library/Application Support/Zed/plugins");
@@ -16,11 +17,15 @@ lazy_static::lazy_static! {
     pub static ref COPILOT_DIR: PathBuf = HOME.join("Library/Application Support/Zed/copilot");
     pub static ref DEFAULT_PRETTIER_DIR: PathBuf = HOME.join("Library/Application Support/Zed/prettier");
     pub static ref DB_DIR: PathBuf = HOME.join("Library/Application Support/Zed/db");
+    pub static ref PREVIOUS_VERSION_DIR: PathBuf = HOME.join("Library/Application Support/Zed/previous_version");
+    pub static ref SESSION_JOURNAL: PathBuf = HOME.join("Library/Application Support/Zed/session_journal.json");
+    pub static ref SCRATCH_BUFFERS_DIR: PathBuf = HOME.join("Library/Application Support/Zed/scratch");
     pub static ref CRASHES_DIR: PathBuf = HOME.join("Library/Logs/DiagnosticReports");
     pub static ref CRASHES_RETIRED_DIR: PathBuf = HOME.join("Library/Logs/DiagnosticReports/Retired");
     pub static ref SETTINGS: PathBuf = CONFIG_DIR.join("settings.json");
     pub static ref KEYMAP: PathBuf = CONFIG_DIR.join("keymap.json");
     pub static ref LAST_USERNAME: PathBuf = CONFIG_DIR.join("last-username.txt");
+    pub static ref SCRATCHPAD: PathBuf = CONFIG_DIR.join("scratchpad.md");
     pub static ref LOG: PathBuf = LOGS_DIR.join("Zed.log");
     pub static ref OLD_LOG: PathBuf = LOGS_DIR.join("Zed.log.old");
     pub static ref LOCAL_SETTINGS_RELATIVE_PATH: &'static Path = Path::new(".zed/settings.json");
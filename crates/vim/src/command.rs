@@ -1,46 +1,390 @@
+use std::ops::Range;
+
 use command_palette::CommandInterceptResult;
-use editor::actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive};
-use gpui::{impl_actions, Action, AppContext, ViewContext};
+use editor::{
+    actions::{SortLinesCaseInsensitive, SortLinesCaseSensitive},
+    scroll::Autoscroll,
+    Bias, Editor,
+};
+use gpui::{
+    impl_actions, Action, AppContext, Keystroke, Modifiers, ViewContext, VisualContext,
+    WindowContext,
+};
+use language::{Point, ToPoint};
+use regex::Regex;
 use serde_derive::Deserialize;
 use workspace::{SaveIntent, Workspace};
 
 use crate::{
+    marks,
     motion::{EndOfDocument, Motion, StartOfDocument},
     normal::{
         move_cursor,
-        search::{FindCommand, ReplaceCommand},
+        search::{self, FindCommand, Replacement},
         JoinLines,
     },
+    register_picker::ShowRegisters,
     state::Mode,
     Vim,
 };
 
+/// A line in the range of a command, before its offset is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub enum Address {
+    /// A line number, counted from 1.
+    Line(u32),
+    /// `.`, the cursor's line.
+    CurrentLine,
+    /// `$`, the last line of the buffer.
+    LastLine,
+    /// `'a`, the line of a mark. `'<` and `'>` are the first and last lines of the selection.
+    Mark(char),
+}
+
+/// An address followed by any number of offsets, like `.+2` or `'a-1`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct LineAddress {
+    pub address: Address,
+    pub offset: i32,
+}
+
+/// The lines a command applies to, like `%`, `3,5` or `'<,'>`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct CommandRange {
+    pub start: LineAddress,
+    pub end: Option<LineAddress>,
+}
+
+impl CommandRange {
+    /// `%`, every line of the buffer.
+    pub const WHOLE_BUFFER: Self = Self {
+        start: LineAddress {
+            address: Address::Line(1),
+            offset: 0,
+        },
+        end: Some(LineAddress {
+            address: Address::LastLine,
+            offset: 0,
+        }),
+    };
+
+    /// Returns the rows of the active editor's buffer that the range covers. Like Vim, a
+    /// range given backwards covers the same rows.
+    fn buffer_rows(&self, vim: &Vim, cx: &mut WindowContext) -> Option<Range<u32>> {
+        vim.update_active_editor(cx, |editor, cx| {
+            let start = self.start.buffer_row(vim, editor, cx)?;
+            let end = match self.end {
+                Some(end) => end.buffer_row(vim, editor, cx)?,
+                None => start,
+            };
+            Some(start.min(end)..start.max(end) + 1)
+        })
+        .flatten()
+    }
+}
+
+impl LineAddress {
+    fn buffer_row(&self, vim: &Vim, editor: &Editor, cx: &AppContext) -> Option<u32> {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let selection = editor.selections.newest::<Point>(cx);
+        let row = match self.address {
+            Address::Line(line) => line.saturating_sub(1),
+            Address::CurrentLine => selection.head().row,
+            Address::LastLine => snapshot.max_point().row,
+            Address::Mark('<') => selection.start.row,
+            Address::Mark('>') => {
+                if selection.end.column == 0 && selection.end.row > selection.start.row {
+                    selection.end.row - 1
+                } else {
+                    selection.end.row
+                }
+            }
+            Address::Mark(mark) => {
+                let buffer = editor.buffer().read(cx).as_singleton()?;
+                let anchor = vim
+                    .workspace_state
+                    .buffer_marks
+                    .get(&buffer.entity_id())?
+                    .get(&mark)?;
+                anchor.to_point(&buffer.read(cx).snapshot()).row
+            }
+        };
+        let row = row as i64 + self.offset as i64;
+        Some(row.clamp(0, snapshot.max_point().row as i64) as u32)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct GoToLine {
-    pub line: u32,
+    pub range: CommandRange,
+}
+
+/// A command that edits whole lines, run on a range by [`WithRange`], or on the lines
+/// matching a pattern by [`OnMatchingLines`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub enum LineCommand {
+    Delete,
+    Join,
+    Sort {
+        case_sensitive: bool,
+    },
+    Substitute(Replacement),
+    /// Types the keys in normal mode, from the start of each line.
+    Normal(String),
+}
+
+/// Runs a command on a range of lines, or on the cursor's line if there is no range.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct WithRange {
+    pub range: Option<CommandRange>,
+    pub command: LineCommand,
+}
+
+/// `:g/pattern/command`, which runs a command on each line of the range that matches the
+/// pattern, or, when inverted like `:v`, on each line that doesn't. Without a command, it
+/// puts a cursor on each of those lines instead.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct OnMatchingLines {
+    pub range: CommandRange,
+    pub pattern: String,
+    pub invert: bool,
+    pub command: Option<LineCommand>,
 }
 
-impl_actions!(vim, [GoToLine]);
+impl_actions!(vim, [GoToLine, WithRange, OnMatchingLines]);
 
 pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(|_: &mut Workspace, action: &GoToLine, cx| {
         Vim::update(cx, |vim, cx| {
+            let Some(rows) = action.range.buffer_rows(vim, cx) else {
+                return;
+            };
             vim.switch_mode(Mode::Normal, false, cx);
-            move_cursor(vim, Motion::StartOfDocument, Some(action.line as usize), cx);
+            marks::record_jump(vim, cx);
+            move_cursor(vim, Motion::StartOfDocument, Some(rows.end as usize), cx);
+        });
+    });
+    workspace.register_action(with_range);
+    workspace.register_action(on_matching_lines);
+}
+
+fn with_range(workspace: &mut Workspace, action: &WithRange, cx: &mut ViewContext<Workspace>) {
+    let command = match &action.command {
+        LineCommand::Substitute(replacement) => {
+            let replacement = search::with_last_search(workspace, replacement.clone(), cx);
+            if !replacement.should_replace_all {
+                search::show_replacement(workspace, &replacement, cx);
+                return;
+            }
+            LineCommand::Substitute(replacement)
+        }
+        command => command.clone(),
+    };
+
+    Vim::update(cx, |vim, cx| {
+        let rows = match action.range {
+            Some(range) => match range.buffer_rows(vim, cx) {
+                Some(rows) => Some(vec![rows]),
+                None => return,
+            },
+            None => None,
+        };
+        run_line_command(vim, command, rows, cx)
+    })
+}
+
+fn on_matching_lines(
+    workspace: &mut Workspace,
+    action: &OnMatchingLines,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Ok(regex) = Regex::new(&action.pattern) else {
+        return;
+    };
+    let command = match &action.command {
+        Some(LineCommand::Substitute(replacement)) => Some(LineCommand::Substitute(
+            search::with_last_search(workspace, replacement.clone(), cx),
+        )),
+        command => command.clone(),
+    };
+
+    Vim::update(cx, |vim, cx| {
+        let Some(rows) = action.range.buffer_rows(vim, cx) else {
+            return;
+        };
+        let Some(matches) = vim.update_active_editor(cx, |editor, cx| {
+            let snapshot = editor.buffer().read(cx).snapshot(cx);
+            rows.filter_map(|row| {
+                let line = snapshot
+                    .text_for_range(Point::new(row, 0)..Point::new(row, snapshot.line_len(row)))
+                    .collect::<String>();
+                let column = regex.find(&line).map(|found| found.start() as u32);
+                (column.is_some() != action.invert).then(|| Point::new(row, column.unwrap_or(0)))
+            })
+            .collect::<Vec<_>>()
+        }) else {
+            return;
+        };
+        if matches.is_empty() {
+            return;
+        }
+
+        match command {
+            Some(command) => {
+                let rows = matches.iter().map(|point| point.row..point.row + 1);
+                run_line_command(vim, command, Some(rows.collect()), cx)
+            }
+            None => {
+                vim.switch_mode(Mode::Normal, false, cx);
+                vim.update_active_editor(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+                        s.select_ranges(matches.into_iter().map(|point| point..point))
+                    })
+                });
+            }
+        }
+    })
+}
+
+/// Runs a line command on each range of rows, or on the cursor's line when there are none.
+fn run_line_command(
+    vim: &mut Vim,
+    command: LineCommand,
+    rows: Option<Vec<Range<u32>>>,
+    cx: &mut WindowContext,
+) {
+    vim.switch_mode(Mode::Normal, false, cx);
+    if let LineCommand::Normal(keys) = command {
+        run_normal_keys(vim, &keys, rows, cx);
+        return;
+    }
+
+    vim.update_active_editor(cx, |editor, cx| {
+        let rows = rows.unwrap_or_else(|| {
+            let row = editor.selections.newest::<Point>(cx).head().row;
+            vec![row..row + 1]
         });
+        if let Some(row) = edit_lines(editor, command, rows, cx) {
+            move_to_first_non_blank(editor, row, cx);
+        }
     });
 }
 
+/// Applies a line command to the rows, and returns the row the cursor should end up on.
+fn edit_lines(
+    editor: &mut Editor,
+    command: LineCommand,
+    rows: Vec<Range<u32>>,
+    cx: &mut ViewContext<Editor>,
+) -> Option<u32> {
+    if let LineCommand::Substitute(replacement) = &command {
+        return search::substitute_lines(editor, rows.into_iter().flatten(), replacement, cx);
+    }
+
+    let first_row = rows.first()?.start;
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    editor.transact(cx, |editor, cx| {
+        editor.change_selections(None, cx, |s| {
+            s.select_ranges(rows.iter().map(|rows| {
+                let last_row = rows.end - 1;
+                Point::new(rows.start, 0)..Point::new(last_row, snapshot.line_len(last_row))
+            }))
+        });
+        match command {
+            LineCommand::Delete => editor.delete_line(&Default::default(), cx),
+            LineCommand::Join => editor.join_lines(&Default::default(), cx),
+            LineCommand::Sort {
+                case_sensitive: true,
+            } => editor.sort_lines_case_sensitive(&SortLinesCaseSensitive, cx),
+            LineCommand::Sort {
+                case_sensitive: false,
+            } => editor.sort_lines_case_insensitive(&SortLinesCaseInsensitive, cx),
+            LineCommand::Substitute(_) | LineCommand::Normal(_) => {}
+        }
+    });
+    Some(first_row)
+}
+
+pub(crate) fn move_to_first_non_blank(editor: &mut Editor, row: u32, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let row = row.min(snapshot.max_point().row);
+    let point = Point::new(row, snapshot.indent_size_for_line(row).len);
+    let point = snapshot.clip_point(point, Bias::Left);
+    editor.change_selections(Some(Autoscroll::fit()), cx, |s| {
+        s.select_ranges([point..point])
+    });
+}
+
+/// Types the keys in normal mode from the start of each row, or once at the cursor if there
+/// are no rows, like `:normal`. Each key is dispatched separately, after the previous one has
+/// been handled, so that it is interpreted in the mode that one left vim in.
+fn run_normal_keys(vim: &Vim, keys: &str, rows: Option<Vec<Range<u32>>>, cx: &mut WindowContext) {
+    let Some(editor) = vim
+        .active_editor
+        .clone()
+        .and_then(|editor| editor.upgrade())
+    else {
+        return;
+    };
+    let positions = editor.update(cx, |editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        match rows {
+            Some(rows) => rows
+                .into_iter()
+                .flatten()
+                .map(|row| snapshot.anchor_before(Point::new(row, 0)))
+                .collect::<Vec<_>>(),
+            None => vec![editor.selections.newest_anchor().head()],
+        }
+    });
+    let keystrokes = keys.chars().map(keystroke_for_char).collect::<Vec<_>>();
+
+    cx.spawn(|mut cx| async move {
+        for position in positions {
+            editor.update(&mut cx, |editor, cx| {
+                editor.change_selections(None, cx, |s| s.select_anchor_ranges([position..position]))
+            })?;
+            for keystroke in &keystrokes {
+                cx.update(|cx| cx.dispatch_keystroke(keystroke.clone()))?;
+            }
+            // Like Vim, end an unfinished command before moving on to the next line.
+            cx.update(|cx| {
+                Vim::update(cx, |vim, cx| {
+                    vim.clear_operator(cx);
+                    vim.switch_mode(Mode::Normal, false, cx)
+                })
+            })?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+/// Returns the keystroke that types the character.
+fn keystroke_for_char(c: char) -> Keystroke {
+    let key = match c {
+        ' ' => "space".to_string(),
+        c => c.to_ascii_lowercase().to_string(),
+    };
+    Keystroke {
+        modifiers: Modifiers {
+            shift: c.is_ascii_uppercase(),
+            ..Default::default()
+        },
+        key,
+        ime_key: Some(c.to_string()),
+    }
+}
+
 pub fn command_interceptor(mut query: &str, _: &AppContext) -> Option<CommandInterceptResult> {
     // Note: this is a very poor simulation of vim's command palette.
-    // In the future we should adjust it to handle parsing range syntax,
-    // and then calling the appropriate commands with/without ranges.
+    // Commands without a range map directly onto editor actions. A range
+    // (like `%`, `3,5` or `'<,'>`) is only understood by "go to line" and
+    // by the commands that edit lines (see `parse_line_command`), and by
+    // `:g` and `:v`.
     //
     // We also need to support passing arguments to commands like :w
     // (ideally with filename autocompletion).
-    //
-    // For now, you can only do a replace on the % range, and you can
-    // only use a specific line number range to "go to line"
     while query.starts_with(":") {
         query = &query[1..];
     }
@@ -233,6 +577,11 @@ pub fn command_interceptor(mut query: &str, _: &AppContext) -> Option<CommandInt
         "sor" | "sor " | "sort" | "sort " => ("sort", SortLinesCaseSensitive.boxed_clone()),
         "sor i" | "sort i" => ("sort i", SortLinesCaseInsensitive.boxed_clone()),
 
+        "reg" | "regi" | "regis" | "regist" | "registe" | "register" | "registers" | "di"
+        | "dis" | "disp" | "displ" | "displa" | "display" => {
+            ("registers", ShowRegisters.boxed_clone())
+        }
+
         // goto (other ranges handled under _ => )
         "$" => ("$", EndOfDocument.boxed_clone()),
         "%" => ("%", EndOfDocument.boxed_clone()),
@@ -248,18 +597,21 @@ pub fn command_interceptor(mut query: &str, _: &AppContext) -> Option<CommandInt
                     }
                     .boxed_clone(),
                 )
-            } else if query.starts_with("%") {
-                (
-                    query,
-                    ReplaceCommand {
-                        query: query.to_string(),
-                    }
-                    .boxed_clone(),
-                )
-            } else if let Ok(line) = query.parse::<u32>() {
-                (query, GoToLine { line }.boxed_clone())
             } else {
-                return None;
+                let (range, command) = parse_range(query);
+                let command = command.trim_start();
+                let action = if command.is_empty() {
+                    GoToLine { range: range? }.boxed_clone()
+                } else if let Some(action) = parse_global(range, command) {
+                    action.boxed_clone()
+                } else {
+                    WithRange {
+                        range,
+                        command: parse_line_command(command)?,
+                    }
+                    .boxed_clone()
+                };
+                (query, action)
             }
         }
     };
@@ -274,6 +626,150 @@ pub fn command_interceptor(mut query: &str, _: &AppContext) -> Option<CommandInt
     })
 }
 
+/// Parses the range at the start of a command, like `%`, `.,$` or `'a,'b+1`, and returns it
+/// with the rest of the command.
+fn parse_range(query: &str) -> (Option<CommandRange>, &str) {
+    if let Some(rest) = query.strip_prefix('%') {
+        return (Some(CommandRange::WHOLE_BUFFER), rest);
+    }
+    let Some((start, mut rest)) = parse_line_address(query) else {
+        return (None, query);
+    };
+    let mut end = None;
+    if let Some(after_separator) = rest.strip_prefix(',').or_else(|| rest.strip_prefix(';')) {
+        let (address, after_address) = parse_line_address(after_separator).unwrap_or((
+            LineAddress {
+                address: Address::CurrentLine,
+                offset: 0,
+            },
+            after_separator,
+        ));
+        end = Some(address);
+        rest = after_address;
+    }
+    (Some(CommandRange { start, end }), rest)
+}
+
+fn parse_line_address(query: &str) -> Option<(LineAddress, &str)> {
+    let digits = |text: &str| {
+        text.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len())
+    };
+    let mut chars = query.chars();
+    let (address, mut rest) = match chars.next() {
+        Some('.') => (Some(Address::CurrentLine), chars.as_str()),
+        Some('$') => (Some(Address::LastLine), chars.as_str()),
+        Some('\'') => {
+            let mark = chars
+                .next()
+                .filter(|mark| mark.is_ascii_lowercase() || *mark == '<' || *mark == '>')?;
+            (Some(Address::Mark(mark)), chars.as_str())
+        }
+        Some(c) if c.is_ascii_digit() => {
+            let len = digits(query);
+            (
+                Some(Address::Line(query[..len].parse().ok()?)),
+                &query[len..],
+            )
+        }
+        _ => (None, query),
+    };
+
+    let mut offset = None;
+    loop {
+        let sign = match rest.chars().next() {
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => break,
+        };
+        rest = &rest[1..];
+        let len = digits(rest);
+        let amount = if len == 0 {
+            1
+        } else {
+            rest[..len].parse::<i32>().ok()?
+        };
+        offset = Some(offset.unwrap_or(0) + sign * amount);
+        rest = &rest[len..];
+    }
+
+    if address.is_none() && offset.is_none() {
+        return None;
+    }
+    Some((
+        LineAddress {
+            address: address.unwrap_or(Address::CurrentLine),
+            offset: offset.unwrap_or(0),
+        },
+        rest,
+    ))
+}
+
+/// Whether `name` abbreviates the command `full`, being at least `min_len` long.
+fn abbreviates(name: &str, full: &str, min_len: usize) -> bool {
+    name.len() >= min_len && full.starts_with(name)
+}
+
+/// Splits a command into its alphabetic name and the rest.
+fn split_command_name(command: &str) -> (&str, &str) {
+    let len = command
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(command.len());
+    command.split_at(len)
+}
+
+/// Parses a command that edits lines, like `d`, `s/a/b/g` or `normal dd`.
+fn parse_line_command(command: &str) -> Option<LineCommand> {
+    let (name, rest) = split_command_name(command);
+    if abbreviates(name, "normal", 4) {
+        let rest = rest.strip_prefix('!').unwrap_or(rest);
+        let keys = rest.strip_prefix(char::is_whitespace)?;
+        return (!keys.is_empty()).then(|| LineCommand::Normal(keys.to_string()));
+    }
+
+    match (name, rest.trim()) {
+        (name, "") if abbreviates(name, "delete", 1) => Some(LineCommand::Delete),
+        (name, "") if abbreviates(name, "join", 1) => Some(LineCommand::Join),
+        (name, "") if abbreviates(name, "sort", 3) => Some(LineCommand::Sort {
+            case_sensitive: true,
+        }),
+        (name, "i") if abbreviates(name, "sort", 3) => Some(LineCommand::Sort {
+            case_sensitive: false,
+        }),
+        _ => search::parse_substitute(command).map(LineCommand::Substitute),
+    }
+}
+
+/// Parses `:g/pattern/command`, `:g!/pattern/command` or `:v/pattern/command`, which apply
+/// to the whole buffer by default.
+fn parse_global(range: Option<CommandRange>, command: &str) -> Option<OnMatchingLines> {
+    let (name, rest) = split_command_name(command);
+    let invert = if abbreviates(name, "global", 1) {
+        false
+    } else if abbreviates(name, "vglobal", 1) {
+        true
+    } else {
+        return None;
+    };
+    let (invert, rest) = match rest.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (invert, rest),
+    };
+
+    let delimiter = rest.chars().next().filter(|c| search::is_delimiter(*c))?;
+    let (pattern, command) = search::split_at_delimiter(&rest[delimiter.len_utf8()..], delimiter);
+    let command = match command.unwrap_or("").trim() {
+        "" | "p" | "pr" | "pri" | "prin" | "print" => None,
+        command => Some(parse_line_command(command)?),
+    };
+    Some(OnMatchingLines {
+        range: range.unwrap_or(CommandRange::WHOLE_BUFFER),
+        pattern: search::vim_pattern_to_regex(&pattern),
+        invert,
+        command,
+    })
+}
+
 fn generate_positions(string: &str, query: &str) -> Vec<usize> {
     let mut positions = Vec::new();
     let mut chars = query.chars().into_iter();
@@ -300,7 +796,11 @@ fn generate_positions(string: &str, query: &str) -> Vec<usize> {
 mod test {
     use std::path::Path;
 
-    use crate::test::{NeovimBackedTestContext, VimTestContext};
+    use super::{parse_range, Address, CommandRange, LineAddress};
+    use crate::{
+        state::Mode,
+        test::{NeovimBackedTestContext, VimTestContext},
+    };
     use gpui::TestAppContext;
     use indoc::indoc;
 
@@ -439,4 +939,109 @@ mod test {
         cx.simulate_keystrokes([":", "q", "a", "enter"]);
         cx.workspace(|workspace, cx| assert_eq!(workspace.items(cx).count(), 0));
     }
+
+    #[test]
+    fn test_parse_range() {
+        let address = |address, offset| LineAddress { address, offset };
+        assert_eq!(parse_range("%d"), (Some(CommandRange::WHOLE_BUFFER), "d"));
+        assert_eq!(
+            parse_range("3,$-1s/a/b/"),
+            (
+                Some(CommandRange {
+                    start: address(Address::Line(3), 0),
+                    end: Some(address(Address::LastLine, -1)),
+                }),
+                "s/a/b/"
+            )
+        );
+        assert_eq!(
+            parse_range("'<,'>norm x"),
+            (
+                Some(CommandRange {
+                    start: address(Address::Mark('<'), 0),
+                    end: Some(address(Address::Mark('>'), 0)),
+                }),
+                "norm x"
+            )
+        );
+        assert_eq!(
+            parse_range("+2;d"),
+            (
+                Some(CommandRange {
+                    start: address(Address::CurrentLine, 2),
+                    end: Some(address(Address::CurrentLine, 0)),
+                }),
+                "d"
+            )
+        );
+        assert_eq!(parse_range("sort"), (None, "sort"));
+    }
+
+    #[gpui::test]
+    async fn test_command_ranges(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes([":", "2", ",", "3", "d", "enter"]);
+        cx.assert_state("one\nˇfour", Mode::Normal);
+
+        cx.set_state("ˇc\nb\na\nd", Mode::Normal);
+        cx.simulate_keystrokes([":", ".", ",", ".", "+", "2", "s", "o", "r", "enter"]);
+        cx.assert_state("ˇa\nb\nc\nd", Mode::Normal);
+
+        cx.set_state("ˇa\nb\nc", Mode::Normal);
+        cx.simulate_keystrokes([":", "$", "enter"]);
+        cx.assert_state("a\nb\nˇc", Mode::Normal);
+        cx.simulate_keystrokes([":", "1", "+", "enter"]);
+        cx.assert_state("a\nˇb\nc", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_command_substitute_flags(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇAa a\naA\nc", Mode::Normal);
+        cx.simulate_keystrokes([":", "%", "s", "/", "a", "/", "b", "/", "g", "i", "enter"]);
+        cx.assert_state("bb b\nˇbb\nc", Mode::Normal);
+
+        cx.set_state("ˇa a\na a", Mode::Normal);
+        cx.simulate_keystrokes([":", "s", "/", "a", "/", "b", "enter"]);
+        cx.assert_state("ˇb a\na a", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_command_global(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇax\nb\ncx\nd", Mode::Normal);
+        cx.simulate_keystrokes([":", "g", "/", "x", "/", "d", "enter"]);
+        cx.assert_state("ˇb\nd", Mode::Normal);
+
+        cx.set_state("ˇax\nb\ncx\nd", Mode::Normal);
+        cx.simulate_keystrokes([":", "v", "/", "x", "/", "d", "enter"]);
+        cx.assert_state("ax\nˇcx", Mode::Normal);
+
+        cx.set_state("ˇax\nb\ncx\nd", Mode::Normal);
+        cx.simulate_keystrokes([":", "g", "/", "x", "enter"]);
+        cx.assert_state("aˇx\nb\ncˇx\nd", Mode::Normal);
+
+        cx.set_state("ˇax\nb\ncx\nd", Mode::Normal);
+        cx.simulate_keystrokes([":", "g", "/", "x", "/", "s", "/", "x", "/", "y", "enter"]);
+        cx.assert_state("ay\nb\nˇcy\nd", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_command_normal(cx: &mut TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇa\nb\nc", Mode::Normal);
+        cx.simulate_keystrokes([":", "%", "n", "o", "r", "m", " ", "shift-a", "x", "enter"]);
+        cx.assert_state("ax\nbx\ncˇx", Mode::Normal);
+
+        cx.set_state("ˇone two\nthree", Mode::Normal);
+        cx.simulate_keystrokes([
+            ":", "n", "o", "r", "m", "a", "l", " ", "w", "d", "w", "enter",
+        ]);
+        cx.assert_state("oneˇ \nthree", Mode::Normal);
+    }
 }
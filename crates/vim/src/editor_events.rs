@@ -1,4 +1,4 @@
-use crate::{insert::NormalBefore, Vim};
+use crate::{insert::NormalBefore, marks, Vim};
 use editor::{Editor, EditorEvent};
 use gpui::{Action, AppContext, Entity, EntityId, View, ViewContext, WindowContext};
 
@@ -35,6 +35,9 @@ fn focused(editor: View<Editor>, cx: &mut WindowContext) {
 fn blurred(editor: View<Editor>, cx: &mut WindowContext) {
     Vim::update(cx, |vim, cx| {
         vim.stop_recording_immediately(NormalBefore.boxed_clone());
+        if vim.enabled {
+            marks::record_jump_from_editor(vim, &editor, cx);
+        }
         if let Some(previous_editor) = vim.active_editor.clone() {
             if previous_editor
                 .upgrade()
@@ -0,0 +1,380 @@
+//! Marks and the jumplist. Lowercase marks are local to a buffer, while uppercase marks and
+//! the jumplist span files, and are persisted with the workspace.
+
+use std::path::PathBuf;
+
+use collections::HashMap;
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Bias, Editor};
+use gpui::{actions, AppContext, View, ViewContext, WindowContext};
+use language::{Point, ToPoint};
+use serde::{Deserialize, Serialize};
+use util::ResultExt;
+use workspace::{Workspace, WorkspaceId};
+
+use crate::Vim;
+
+/// The number of positions kept in the jumplist, as in Vim.
+const MAX_JUMPS: usize = 100;
+
+actions!(vim, [JumpBack, JumpForward]);
+
+pub(crate) fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace: &mut Workspace, _: &JumpBack, cx| {
+        navigate_jumps(workspace, true, cx)
+    });
+    workspace.register_action(|workspace: &mut Workspace, _: &JumpForward, cx| {
+        navigate_jumps(workspace, false, cx)
+    });
+
+    let workspace_id = workspace.database_id();
+    cx.spawn(|_, mut cx| async move {
+        let marks = cx
+            .background_executor()
+            .spawn(async move { KEY_VALUE_STORE.read_kvp(&marks_key(workspace_id)) })
+            .await
+            .log_err()
+            .flatten()
+            .and_then(|marks| serde_json::from_str::<WorkspaceMarks>(&marks).log_err());
+        if let Some(marks) = marks {
+            cx.update_global(|vim: &mut Vim, _| {
+                vim.workspace_state
+                    .workspace_marks
+                    .entry(workspace_id)
+                    .or_insert(marks);
+            })
+            .log_err();
+        }
+    })
+    .detach();
+}
+
+/// A position in a file on disk, which stays meaningful after the file is closed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Position {
+    pub path: PathBuf,
+    pub row: u32,
+    pub column: u32,
+}
+
+/// The marks of a workspace that aren't tied to a buffer.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct WorkspaceMarks {
+    jumps: Vec<Position>,
+    /// The position in `jumps` that `ctrl-o` and `ctrl-i` move from. It is the length of
+    /// `jumps` unless the jumplist is being navigated.
+    jump_index: usize,
+    global_marks: HashMap<char, Position>,
+}
+
+impl WorkspaceMarks {
+    /// Adds a position to the end of the jumplist, removing older jumps to the same line.
+    fn push_jump(&mut self, position: Position) {
+        self.jumps
+            .retain(|jump| jump.path != position.path || jump.row != position.row);
+        self.jumps.push(position);
+        if self.jumps.len() > MAX_JUMPS {
+            self.jumps.remove(0);
+        }
+        self.jump_index = self.jumps.len();
+    }
+
+    /// Moves `count` jumps back. The current position is remembered when leaving the end
+    /// of the jumplist, so that `ctrl-i` can return to it.
+    fn jump_back(&mut self, current: Option<Position>, count: usize) -> Option<Position> {
+        if self.jump_index == self.jumps.len() {
+            if let Some(current) = current {
+                self.push_jump(current);
+                self.jump_index = self.jumps.len() - 1;
+            }
+        }
+        self.jump_index = self.jump_index.checked_sub(count)?;
+        self.jumps.get(self.jump_index).cloned()
+    }
+
+    fn jump_forward(&mut self, count: usize) -> Option<Position> {
+        let index = self.jump_index + count;
+        let jump = self.jumps.get(index).cloned()?;
+        self.jump_index = index;
+        Some(jump)
+    }
+}
+
+fn marks_key(workspace_id: WorkspaceId) -> String {
+    format!("vim-marks-{workspace_id}")
+}
+
+fn save_marks(workspace_id: WorkspaceId, marks: &WorkspaceMarks, cx: &AppContext) {
+    let Some(marks) = serde_json::to_string(marks).log_err() else {
+        return;
+    };
+    cx.background_executor()
+        .spawn(KEY_VALUE_STORE.write_kvp(marks_key(workspace_id), marks))
+        .detach_and_log_err(cx);
+}
+
+/// Returns the position of the editor's cursor, if it is editing a file on disk.
+fn cursor_position(editor: &Editor, cx: &AppContext) -> Option<Position> {
+    let buffer = editor.buffer().read(cx).as_singleton()?;
+    let path = buffer.read(cx).file()?.as_local()?.abs_path(cx);
+    let head = editor.selections.newest::<Point>(cx).head();
+    Some(Position {
+        path,
+        row: head.row,
+        column: head.column,
+    })
+}
+
+/// Records the cursor's position in the jumplist, before a jump moves it.
+pub(crate) fn record_jump(vim: &mut Vim, cx: &mut WindowContext) {
+    let Some((workspace_id, position)) = vim
+        .update_active_editor(cx, |editor, cx| {
+            Some((editor.workspace_id()?, cursor_position(editor, cx)?))
+        })
+        .flatten()
+    else {
+        return;
+    };
+    let marks = vim
+        .workspace_state
+        .workspace_marks
+        .entry(workspace_id)
+        .or_default();
+    marks.push_jump(position);
+    save_marks(workspace_id, marks, cx);
+}
+
+/// Records the position of an editor that loses focus, so that `ctrl-o` returns to it after
+/// switching files. Nothing is recorded while the jumplist is being navigated, which itself
+/// switches files.
+pub(crate) fn record_jump_from_editor(
+    vim: &mut Vim,
+    editor: &View<Editor>,
+    cx: &mut WindowContext,
+) {
+    let editor = editor.read(cx);
+    let Some(workspace_id) = editor.workspace_id() else {
+        return;
+    };
+    let Some(position) = cursor_position(editor, cx) else {
+        return;
+    };
+    let marks = vim
+        .workspace_state
+        .workspace_marks
+        .entry(workspace_id)
+        .or_default();
+    if marks.jump_index == marks.jumps.len() {
+        marks.push_jump(position);
+        save_marks(workspace_id, marks, cx);
+    }
+}
+
+/// Sets a mark at the cursor: `a` to `z` in the current buffer, `A` to `Z` in the workspace.
+pub(crate) fn set_mark(mark: char, cx: &mut WindowContext) {
+    Vim::update(cx, |vim, cx| {
+        if mark.is_ascii_lowercase() {
+            let Some((buffer_id, anchor)) = vim
+                .update_active_editor(cx, |editor, cx| {
+                    let buffer = editor.buffer().read(cx).as_singleton()?;
+                    let head = editor.selections.newest::<Point>(cx).head();
+                    Some((buffer.entity_id(), buffer.read(cx).anchor_before(head)))
+                })
+                .flatten()
+            else {
+                return;
+            };
+            vim.workspace_state
+                .buffer_marks
+                .entry(buffer_id)
+                .or_default()
+                .insert(mark, anchor);
+        } else if mark.is_ascii_uppercase() {
+            let Some((workspace_id, position)) = vim
+                .update_active_editor(cx, |editor, cx| {
+                    Some((editor.workspace_id()?, cursor_position(editor, cx)?))
+                })
+                .flatten()
+            else {
+                return;
+            };
+            let marks = vim
+                .workspace_state
+                .workspace_marks
+                .entry(workspace_id)
+                .or_default();
+            marks.global_marks.insert(mark, position);
+            save_marks(workspace_id, marks, cx);
+        }
+    })
+}
+
+/// Jumps to a mark, to its exact position or, with `line`, to the first non-blank character
+/// of its line. `'` and `` ` `` are the position before the latest jump.
+pub(crate) fn jump_to_mark(mark: char, line: bool, cx: &mut WindowContext) {
+    let Some(editor) = Vim::read(cx)
+        .active_editor
+        .clone()
+        .and_then(|editor| editor.upgrade())
+    else {
+        return;
+    };
+
+    if mark.is_ascii_lowercase() {
+        let point = Vim::update(cx, |vim, cx| {
+            let buffer = editor.read(cx).buffer().read(cx).as_singleton()?;
+            let anchor = vim
+                .workspace_state
+                .buffer_marks
+                .get(&buffer.entity_id())?
+                .get(&mark)?;
+            let point = anchor.to_point(&buffer.read(cx).snapshot());
+            record_jump(vim, cx);
+            Some(point)
+        });
+        if let Some(point) = point {
+            editor.update(cx, |editor, cx| move_cursor(editor, point, line, cx));
+        }
+        return;
+    }
+
+    let Some(workspace_id) = editor.read(cx).workspace_id() else {
+        return;
+    };
+    let position = Vim::update(cx, |vim, cx| {
+        let marks = vim.workspace_state.workspace_marks.get(&workspace_id)?;
+        let position = match mark {
+            'A'..='Z' => marks.global_marks.get(&mark)?.clone(),
+            '\'' | '`' => marks.jumps.last()?.clone(),
+            _ => return None,
+        };
+        record_jump(vim, cx);
+        Some(position)
+    });
+    if let Some((position, workspace)) = position.zip(editor.read(cx).workspace()) {
+        workspace.update(cx, |workspace, cx| {
+            go_to_position(workspace, position, line, cx)
+        });
+    }
+}
+
+fn navigate_jumps(workspace: &mut Workspace, back: bool, cx: &mut ViewContext<Workspace>) {
+    let workspace_id = workspace.database_id();
+    let position = Vim::update(cx, |vim, cx| {
+        let count = vim.take_count(cx).unwrap_or(1);
+        let current = vim
+            .update_active_editor(cx, |editor, cx| cursor_position(editor, cx))
+            .flatten();
+        let marks = vim
+            .workspace_state
+            .workspace_marks
+            .entry(workspace_id)
+            .or_default();
+        let position = if back {
+            marks.jump_back(current, count)
+        } else {
+            marks.jump_forward(count)
+        };
+        save_marks(workspace_id, marks, cx);
+        position
+    });
+    if let Some(position) = position {
+        go_to_position(workspace, position, false, cx);
+    }
+}
+
+/// Moves the cursor to a position, opening its file if it isn't the active editor's.
+fn go_to_position(
+    workspace: &mut Workspace,
+    position: Position,
+    line: bool,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let point = Point::new(position.row, position.column);
+    let active_editor = workspace
+        .active_item(cx)
+        .and_then(|item| item.act_as::<Editor>(cx))
+        .filter(|editor| {
+            cursor_position(editor.read(cx), cx).map(|cursor| cursor.path)
+                == Some(position.path.clone())
+        });
+    if let Some(editor) = active_editor {
+        editor.update(cx, |editor, cx| move_cursor(editor, point, line, cx));
+        return;
+    }
+
+    let open = workspace.open_abs_path(position.path, true, cx);
+    cx.spawn(|_, mut cx| async move {
+        if let Some(editor) = open.await?.downcast::<Editor>() {
+            editor.update(&mut cx, |editor, cx| move_cursor(editor, point, line, cx))?;
+        }
+        anyhow::Ok(())
+    })
+    .detach_and_log_err(cx);
+}
+
+fn move_cursor(editor: &mut Editor, point: Point, line: bool, cx: &mut ViewContext<Editor>) {
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let point = if line {
+        Point::new(point.row, snapshot.indent_size_for_line(point.row).len)
+    } else {
+        point
+    };
+    let point = snapshot.clip_point(point, Bias::Left);
+    editor.change_selections(Some(Autoscroll::center()), cx, |s| {
+        s.select_ranges([point..point])
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::{Position, WorkspaceMarks};
+    use crate::{state::Mode, test::VimTestContext};
+
+    fn position(row: u32) -> Position {
+        Position {
+            path: PathBuf::from("/root/a.rs"),
+            row,
+            column: 0,
+        }
+    }
+
+    #[test]
+    fn test_jumplist() {
+        let mut marks = WorkspaceMarks::default();
+        marks.push_jump(position(1));
+        marks.push_jump(position(5));
+        marks.push_jump(position(1));
+        assert_eq!(marks.jumps, vec![position(5), position(1)]);
+
+        assert_eq!(marks.jump_back(Some(position(9)), 1), Some(position(1)));
+        assert_eq!(marks.jump_back(Some(position(1)), 1), Some(position(5)));
+        assert_eq!(marks.jump_back(Some(position(5)), 1), None);
+        assert_eq!(marks.jump_forward(2), Some(position(9)));
+        assert_eq!(marks.jump_forward(1), None);
+
+        for row in 0..200 {
+            marks.push_jump(position(row));
+        }
+        assert_eq!(marks.jumps.len(), 100);
+        assert_eq!(marks.jumps[0], position(100));
+    }
+
+    #[gpui::test]
+    async fn test_buffer_marks(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("one\n  tˇwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes(["m", "a", "g", "g"]);
+        cx.assert_state("ˇone\n  two\nthree", Mode::Normal);
+        cx.simulate_keystrokes(["`", "a"]);
+        cx.assert_state("one\n  tˇwo\nthree", Mode::Normal);
+        cx.simulate_keystrokes(["shift-g", "'", "a"]);
+        cx.assert_state("one\n  ˇtwo\nthree", Mode::Normal);
+
+        cx.simulate_keystrokes(["g", "g", "shift-o", "z", "e", "r", "o", "escape", "`", "a"]);
+        cx.assert_state("zero\none\n  tˇwo\nthree", Mode::Normal);
+    }
+}
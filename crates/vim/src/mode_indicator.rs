@@ -7,6 +7,7 @@ use crate::{state::Mode, Vim};
 /// The ModeIndicator displays the current mode in the status bar.
 pub struct ModeIndicator {
     pub(crate) mode: Option<Mode>,
+    pub(crate) recording_register: Option<char>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -20,6 +21,7 @@ impl ModeIndicator {
 
         let mut this = Self {
             mode: None,
+            recording_register: None,
             _subscriptions,
         };
         this.update_mode(cx);
@@ -34,8 +36,10 @@ impl ModeIndicator {
 
         if vim.enabled {
             self.mode = Some(vim.state().mode);
+            self.recording_register = vim.workspace_state.recording_register;
         } else {
             self.mode = None;
+            self.recording_register = None;
         }
     }
 }
@@ -53,11 +57,19 @@ impl Render for ModeIndicator {
             Mode::VisualLine => "-- VISUAL LINE --",
             Mode::VisualBlock => "-- VISUAL BLOCK --",
         };
+        let text = match self.recording_register {
+            Some(register) => format!("{text} recording @{register}"),
+            None => text.to_string(),
+        };
         Label::new(text).size(LabelSize::Small).into_any_element()
     }
 }
 
 impl StatusItemView for ModeIndicator {
+    fn status_item_id(&self) -> &'static str {
+        "vim_mode"
+    }
+
     fn set_active_pane_item(
         &mut self,
         _active_pane_item: Option<&dyn ItemHandle>,
@@ -9,6 +9,7 @@ use serde::Deserialize;
 use workspace::Workspace;
 
 use crate::{
+    marks,
     normal::normal_motion,
     state::{Mode, Operator},
     utils::coerce_punctuation,
@@ -242,6 +243,9 @@ pub(crate) fn motion(motion: Motion, cx: &mut WindowContext) {
 
     let count = Vim::update(cx, |vim, cx| vim.take_count(cx));
     let operator = Vim::read(cx).active_operator();
+    if operator.is_none() && motion.is_jump() {
+        Vim::update(cx, |vim, cx| marks::record_jump(vim, cx));
+    }
     match Vim::read(cx).state().mode {
         Mode::Normal => normal_motion(motion, operator, count, cx),
         Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_motion(motion, count, cx),
@@ -313,6 +317,32 @@ impl Motion {
         }
     }
 
+    /// Whether the motion is a jump, which records the cursor's position in the jumplist.
+    pub fn is_jump(&self) -> bool {
+        use Motion::*;
+        match self {
+            StartOfDocument | EndOfDocument | StartOfParagraph | EndOfParagraph | Matching => true,
+            Down { .. }
+            | Up { .. }
+            | CurrentLine
+            | EndOfLine { .. }
+            | NextWordEnd { .. }
+            | FindForward { .. }
+            | Left
+            | Backspace
+            | Right
+            | StartOfLine { .. }
+            | StartOfLineDownward
+            | EndOfLineDownward
+            | GoToColumn
+            | NextWordStart { .. }
+            | PreviousWordStart { .. }
+            | FirstNonWhitespace { .. }
+            | FindBackward { .. }
+            | NextLineStart => false,
+        }
+    }
+
     pub fn infallible(&self) -> bool {
         use Motion::*;
         match self {
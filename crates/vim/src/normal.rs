@@ -2,7 +2,7 @@ mod case;
 mod change;
 mod delete;
 mod increment;
-mod paste;
+pub(crate) mod paste;
 pub(crate) mod repeat;
 mod scroll;
 pub(crate) mod search;
@@ -14,14 +14,14 @@ use std::sync::Arc;
 use crate::{
     motion::{self, first_non_whitespace, next_line_end, right, Motion},
     object::Object,
-    state::{Mode, Operator},
+    state::{Mode, Operator, RecordedEdit},
     Vim,
 };
 use collections::HashSet;
 use editor::scroll::Autoscroll;
-use editor::{Bias, DisplayPoint};
+use editor::{display_map::ToDisplayPoint, Bias, DisplayPoint};
 use gpui::{actions, ViewContext, WindowContext};
-use language::SelectionGoal;
+use language::{Point, SelectionGoal};
 use log::error;
 use workspace::Workspace;
 
@@ -51,6 +51,8 @@ actions!(
         ConvertToUpperCase,
         ConvertToLowerCase,
         JoinLines,
+        Indent,
+        Outdent,
     ]
 );
 
@@ -108,6 +110,12 @@ pub(crate) fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace
             );
         })
     });
+    workspace.register_action(|_: &mut Workspace, _: &Indent, cx| {
+        Vim::update(cx, |vim, cx| indent(vim, true, cx))
+    });
+    workspace.register_action(|_: &mut Workspace, _: &Outdent, cx| {
+        Vim::update(cx, |vim, cx| indent(vim, false, cx))
+    });
     workspace.register_action(|_: &mut Workspace, _: &JoinLines, cx| {
         Vim::update(cx, |vim, cx| {
             vim.record_current_action(cx);
@@ -144,6 +152,12 @@ pub fn normal_motion(
     cx: &mut WindowContext,
 ) {
     Vim::update(cx, |vim, cx| {
+        if let Some(operator @ (Operator::Change | Operator::Delete)) = operator {
+            vim.record_edit(RecordedEdit::Motion {
+                operator,
+                motion: motion.clone(),
+            });
+        }
         match operator {
             None => move_cursor(vim, motion, times, cx),
             Some(Operator::Change) => change_motion(vim, motion, times, cx),
@@ -160,14 +174,11 @@ pub fn normal_motion(
 pub fn normal_object(object: Object, cx: &mut WindowContext) {
     Vim::update(cx, |vim, cx| {
         match vim.maybe_pop_operator() {
-            Some(Operator::Object { around }) => match vim.maybe_pop_operator() {
-                Some(Operator::Change) => change_object(vim, object, around, cx),
-                Some(Operator::Delete) => delete_object(vim, object, around, cx),
-                Some(Operator::Yank) => yank_object(vim, object, around, cx),
-                _ => {
-                    // Can't do anything for namespace operators. Ignoring
+            Some(Operator::Object { around }) => {
+                if let Some(operator) = vim.maybe_pop_operator() {
+                    object_operator(vim, operator, object, around, cx)
                 }
-            },
+            }
             _ => {
                 // Can't do anything with change/delete/yank and text objects. Ignoring
             }
@@ -176,6 +187,72 @@ pub fn normal_object(object: Object, cx: &mut WindowContext) {
     })
 }
 
+/// Indents or outdents as many lines as the count, like `>>` and `<<`, or the selected lines
+/// as many times as the count, like `>` in visual mode.
+fn indent(vim: &mut Vim, indent: bool, cx: &mut WindowContext) {
+    vim.record_current_action(cx);
+    let count = vim.take_count(cx).unwrap_or(1);
+    let is_visual = vim.state().mode.is_visual();
+    vim.update_active_editor(cx, |editor, cx| {
+        editor.transact(cx, |editor, cx| {
+            let original_selections = editor.selections.disjoint_anchors();
+            let times = if is_visual {
+                count
+            } else {
+                editor.change_selections(None, cx, |s| {
+                    s.move_with(|map, selection| {
+                        let buffer = &map.buffer_snapshot;
+                        let row = selection.head().to_point(map).row;
+                        let end_row = (row + count as u32 - 1).min(buffer.max_point().row);
+                        selection.start = Point::new(row, 0).to_display_point(map);
+                        selection.end =
+                            Point::new(end_row, buffer.line_len(end_row)).to_display_point(map);
+                    })
+                });
+                1
+            };
+            for _ in 0..times {
+                if indent {
+                    editor.indent(&Default::default(), cx)
+                } else {
+                    editor.outdent(&Default::default(), cx)
+                }
+            }
+            if !is_visual {
+                editor.change_selections(None, cx, |s| {
+                    s.select_anchors(original_selections.to_vec())
+                });
+            }
+        })
+    });
+}
+
+/// Applies an operator to a text object, like `ci(`.
+pub(crate) fn object_operator(
+    vim: &mut Vim,
+    operator: Operator,
+    object: Object,
+    around: bool,
+    cx: &mut WindowContext,
+) {
+    match operator {
+        Operator::Change | Operator::Delete => vim.record_edit(RecordedEdit::Object {
+            operator,
+            object,
+            around,
+        }),
+        _ => {}
+    }
+    match operator {
+        Operator::Change => change_object(vim, object, around, cx),
+        Operator::Delete => delete_object(vim, object, around, cx),
+        Operator::Yank => yank_object(vim, object, around, cx),
+        _ => {
+            // Can't do anything for namespace operators. Ignoring
+        }
+    }
+}
+
 pub(crate) fn move_cursor(
     vim: &mut Vim,
     motion: Motion,
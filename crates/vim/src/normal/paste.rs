@@ -8,11 +8,11 @@ use language::{Bias, SelectionGoal};
 use serde::Deserialize;
 use workspace::Workspace;
 
-use crate::{state::Mode, utils::copy_selections_content, Vim};
+use crate::{registers::Registers, state::Mode, utils::copy_selections_content, Vim};
 
-#[derive(Clone, Deserialize, PartialEq)]
+#[derive(Clone, Default, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
-struct Paste {
+pub(crate) struct Paste {
     #[serde(default)]
     before: bool,
     #[serde(default)]
@@ -33,7 +33,9 @@ fn paste(_: &mut Workspace, action: &Paste, cx: &mut ViewContext<Workspace>) {
             editor.transact(cx, |editor, cx| {
                 editor.set_clip_at_line_ends(false, cx);
 
-                let Some(item) = cx.read_from_clipboard() else {
+                let Some(item) =
+                    cx.update_global(|registers: &mut Registers, cx| registers.read(cx))
+                else {
                     return;
                 };
                 let clipboard_text = Cow::Borrowed(item.text());
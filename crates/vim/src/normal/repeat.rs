@@ -1,14 +1,16 @@
 use crate::{
     insert::NormalBefore,
     motion::Motion,
-    state::{Mode, RecordedSelection, ReplayableAction},
+    normal::{normal_motion, object_operator},
+    replace,
+    state::{Mode, Operator, RecordedEdit, RecordedSelection, ReplayableAction},
     visual::visual_motion,
     Vim,
 };
 use gpui::{actions, Action, ViewContext, WindowContext};
 use workspace::Workspace;
 
-actions!(vim, [Repeat, EndRepeat]);
+actions!(vim, [Repeat, EndRepeat, ToggleRecord, ReplayLastRecording]);
 
 fn should_replay(action: &Box<dyn Action>) -> bool {
     // skip so that we don't leave the character palette open
@@ -35,7 +37,30 @@ fn repeatable_insert(action: &ReplayableAction) -> Option<Box<dyn Action>> {
                 None
             }
         }
-        ReplayableAction::Insertion { .. } => None,
+        ReplayableAction::Insertion { .. } | ReplayableAction::Edit(_) => None,
+    }
+}
+
+/// Makes an edit recorded by [`Vim::record_edit`] again.
+fn replay_edit(edit: &RecordedEdit, cx: &mut WindowContext) {
+    match edit.clone() {
+        RecordedEdit::Motion { operator, motion } => {
+            let times = Vim::update(cx, |vim, cx| vim.take_count(cx));
+            normal_motion(motion, Some(operator), times, cx);
+            Vim::update(cx, |vim, cx| vim.clear_operator(cx));
+        }
+        RecordedEdit::Object {
+            operator,
+            object,
+            around,
+        } => Vim::update(cx, |vim, cx| {
+            object_operator(vim, operator, object, around, cx);
+            vim.clear_operator(cx);
+        }),
+        RecordedEdit::Replace { text } => {
+            Vim::update(cx, |vim, cx| vim.push_operator(Operator::Replace, cx));
+            replace(text, cx)
+        }
     }
 }
 
@@ -48,6 +73,91 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
     });
 
     workspace.register_action(|_: &mut Workspace, _: &Repeat, cx| repeat(cx, false));
+
+    workspace.register_action(|_: &mut Workspace, _: &ToggleRecord, cx| {
+        Vim::update(cx, |vim, cx| {
+            if let Some(register) = vim.workspace_state.recording_register.take() {
+                vim.workspace_state.last_recorded_register = Some(register);
+            } else {
+                vim.push_operator(Operator::RecordRegister, cx);
+            }
+        })
+    });
+
+    workspace.register_action(|_: &mut Workspace, _: &ReplayLastRecording, cx| {
+        let Some(register) = Vim::read(cx).workspace_state.last_recorded_register else {
+            return;
+        };
+        replay_register(register, cx)
+    });
+}
+
+/// Starts recording actions into the register, as `q` does. An uppercase register
+/// appends to the lowercase one instead of replacing it.
+pub(crate) fn record_register(register: char, cx: &mut WindowContext) {
+    if !(register.is_ascii_alphanumeric() || register == '"') {
+        return;
+    }
+    Vim::update(cx, |vim, _| {
+        let lowercase = register.to_ascii_lowercase();
+        if !register.is_ascii_uppercase() {
+            vim.workspace_state.recordings.remove(&lowercase);
+        }
+        vim.workspace_state.recording_register = Some(lowercase);
+    })
+}
+
+/// Replays the actions recorded into the register, as `@` does. `@@` replays
+/// the last replayed register.
+pub(crate) fn replay_register(register: char, cx: &mut WindowContext) {
+    let Some((actions, count)) = Vim::update(cx, |vim, cx| {
+        let count = vim.take_count(cx).unwrap_or(1);
+        let register = if register == '@' {
+            vim.workspace_state.last_replayed_register?
+        } else {
+            register.to_ascii_lowercase()
+        };
+        let actions = vim.workspace_state.recordings.get(&register)?.clone();
+        vim.workspace_state.last_replayed_register = Some(register);
+        Some((actions, count))
+    }) else {
+        return;
+    };
+
+    let window = cx.window_handle();
+    cx.spawn(move |mut cx| async move {
+        window.update(&mut cx, |_, cx| {
+            Vim::update(cx, |vim, _| vim.workspace_state.replaying_register = true)
+        })?;
+        for _ in 0..count {
+            for action in &actions {
+                window.update(&mut cx, |_, cx| match action {
+                    ReplayableAction::Action(action) => cx.dispatch_action(action.boxed_clone()),
+                    ReplayableAction::Edit(edit) => replay_edit(edit, cx),
+                    ReplayableAction::Insertion {
+                        text,
+                        utf16_range_to_replace,
+                    } => {
+                        // Recordings can switch between editors, so insert into whichever
+                        // one is active at this point.
+                        let editor = Vim::read(cx)
+                            .active_editor
+                            .as_ref()
+                            .and_then(|editor| editor.upgrade());
+                        if let Some(editor) = editor {
+                            editor.update(cx, |editor, cx| {
+                                editor.replay_insert_event(text, utf16_range_to_replace.clone(), cx)
+                            })
+                        }
+                    }
+                })?;
+            }
+        }
+        window.update(&mut cx, |_, cx| {
+            Vim::update(cx, |vim, _| vim.workspace_state.replaying_register = false)
+        })
+    })
+    .detach_and_log_err(cx);
 }
 
 pub(crate) fn repeat(cx: &mut WindowContext, from_insert_mode: bool) {
@@ -186,6 +296,9 @@ pub(crate) fn repeat(cx: &mut WindowContext, from_insert_mode: bool) {
                 } => editor.update(&mut cx, |editor, cx| {
                     editor.replay_insert_event(&text, utf16_range_to_replace.clone(), cx)
                 }),
+                ReplayableAction::Edit(edit) => {
+                    window.update(&mut cx, |_, cx| replay_edit(&edit, cx))
+                }
             }?
         }
         editor.update(&mut cx, |editor, _| {
@@ -506,4 +619,76 @@ mod test {
         cx.simulate_shared_keystrokes(["."]).await;
         cx.assert_shared_state("ˇx hello\n").await;
     }
+
+    #[gpui::test]
+    async fn test_record_and_replay_register(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo\nthree\nfour", Mode::Normal);
+        cx.simulate_keystrokes(["q", "a", "shift-a", "!", "escape", "j", "q"]);
+        cx.assert_state("one!\ntwˇo\nthree\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes(["@", "a"]);
+        cx.run_until_parked();
+        cx.assert_state("one!\ntwo!\nthrˇee\nfour", Mode::Normal);
+
+        cx.simulate_keystrokes(["@", "@"]);
+        cx.run_until_parked();
+        cx.assert_state("one!\ntwo!\nthree!\nfouˇr", Mode::Normal);
+
+        cx.simulate_keystrokes(["shift-q"]);
+        cx.run_until_parked();
+        cx.assert_state("one!\ntwo!\nthree!\nfourˇ!", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_replay_register_with_count(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇa\nb\nc\nd", Mode::Normal);
+        cx.simulate_keystrokes(["q", "b", "x", "j", "q"]);
+        cx.assert_state("\nˇb\nc\nd", Mode::Normal);
+
+        cx.simulate_keystrokes(["2", "@", "b"]);
+        cx.run_until_parked();
+        cx.assert_state("\n\n\nˇd", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_repeat_recorded_edits(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        // operators completed by a typed character
+        cx.set_state("ˇa-b c-d", Mode::Normal);
+        cx.simulate_keystrokes(["c", "f", "-", "x", "escape", "w", "."]);
+        cx.assert_state("xb ˇxd", Mode::Normal);
+
+        cx.set_state("ˇabcd", Mode::Normal);
+        cx.simulate_keystrokes(["r", "x", "l", "l", "."]);
+        cx.assert_state("xbˇxd", Mode::Normal);
+
+        // changes that insert nothing
+        cx.set_state("ˇone two three", Mode::Normal);
+        cx.simulate_keystrokes(["c", "w", "escape"]);
+        cx.assert_state("ˇ two three", Mode::Normal);
+        cx.simulate_keystrokes(["w", "."]);
+        cx.assert_state("ˇ  three", Mode::Normal);
+
+        // text objects
+        cx.set_state("ˇone two", Mode::Normal);
+        cx.simulate_keystrokes(["c", "i", "w", "x", "escape", "w", "."]);
+        cx.assert_state("x ˇx", Mode::Normal);
+
+        // edits replayed from a register
+        cx.set_state("ˇa b c d", Mode::Normal);
+        cx.simulate_keystrokes(["q", "q", "d", "w", "q", "@", "q"]);
+        cx.assert_state("ˇc d", Mode::Normal);
+        cx.simulate_keystrokes(["."]);
+        cx.assert_state("ˇd", Mode::Normal);
+
+        // indentation, with a count
+        cx.set_state("ˇa\nb\nc", Mode::Normal);
+        cx.simulate_keystrokes(["2", ">", ">", "j", "."]);
+        cx.assert_state("    a\n        ˇb\n    c", Mode::Normal);
+    }
 }
@@ -1,9 +1,15 @@
-use gpui::{actions, impl_actions, ViewContext};
+use std::borrow::Cow;
+
+use editor::{Editor, ToPoint};
+use gpui::{actions, impl_actions, ViewContext, WindowContext};
+use language::Point;
+use regex::RegexBuilder;
 use search::{buffer_search, BufferSearchBar, SearchMode, SearchOptions};
 use serde_derive::Deserialize;
 use workspace::{searchable::Direction, Workspace};
 
 use crate::{
+    marks,
     motion::Motion,
     normal::move_cursor,
     state::{Mode, SearchState},
@@ -36,24 +42,19 @@ pub struct FindCommand {
     pub backwards: bool,
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize)]
-pub struct ReplaceCommand {
-    pub query: String,
-}
-
-#[derive(Debug, Default)]
-struct Replacement {
-    search: String,
-    replacement: String,
-    should_replace_all: bool,
-    is_case_sensitive: bool,
+/// The pattern, replacement and flags of a `:s` command, converted to the syntax of Zed's
+/// regular expressions.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct Replacement {
+    pub(crate) search: String,
+    pub(crate) replacement: String,
+    pub(crate) should_replace_all: bool,
+    pub(crate) is_global: bool,
+    pub(crate) is_case_sensitive: bool,
 }
 
 actions!(vim, [SearchSubmit]);
-impl_actions!(
-    vim,
-    [FindCommand, ReplaceCommand, Search, MoveToPrev, MoveToNext]
-);
+impl_actions!(vim, [FindCommand, Search, MoveToPrev, MoveToNext]);
 
 pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(move_to_next);
@@ -63,7 +64,6 @@ pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>
     workspace.register_action(search_deploy);
 
     workspace.register_action(find_command);
-    workspace.register_action(replace_command);
 }
 
 fn move_to_next(workspace: &mut Workspace, action: &MoveToNext, cx: &mut ViewContext<Workspace>) {
@@ -83,6 +83,7 @@ fn search(workspace: &mut Workspace, action: &Search, cx: &mut ViewContext<Works
     };
     Vim::update(cx, |vim, cx| {
         let count = vim.take_count(cx).unwrap_or(1);
+        marks::record_jump(vim, cx);
         pane.update(cx, |pane, cx| {
             if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
                 search_bar.update(cx, |search_bar, cx| {
@@ -150,6 +151,7 @@ pub fn move_to_internal(
     Vim::update(cx, |vim, cx| {
         let pane = workspace.active_pane().clone();
         let count = vim.take_count(cx).unwrap_or(1);
+        marks::record_jump(vim, cx);
 
         pane.update(cx, |pane, cx| {
             if let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() {
@@ -226,137 +228,219 @@ fn find_command(workspace: &mut Workspace, action: &FindCommand, cx: &mut ViewCo
     })
 }
 
-fn replace_command(
+/// Shows the matches of a substitution and its replacement in the search bar, for the `c`
+/// and `n` flags, which don't replace anything right away.
+pub(crate) fn show_replacement(
     workspace: &mut Workspace,
-    action: &ReplaceCommand,
+    replacement: &Replacement,
     cx: &mut ViewContext<Workspace>,
 ) {
-    let replacement = parse_replace_all(&action.query);
     let pane = workspace.active_pane().clone();
     pane.update(cx, |pane, cx| {
         let Some(search_bar) = pane.toolbar().read(cx).item_of_type::<BufferSearchBar>() else {
             return;
         };
-        let search = search_bar.update(cx, |search_bar, cx| {
+        search_bar.update(cx, |search_bar, cx| {
             if !search_bar.show(cx) {
-                return None;
+                return;
             }
 
             let mut options = SearchOptions::default();
             if replacement.is_case_sensitive {
                 options.set(SearchOptions::CASE_SENSITIVE, true)
             }
-            let search = if replacement.search == "" {
-                search_bar.query(cx)
-            } else {
-                replacement.search
-            };
-
             search_bar.set_replacement(Some(&replacement.replacement), cx);
             search_bar.activate_search_mode(SearchMode::Regex, cx);
-            Some(search_bar.search(&search, Some(options), cx))
-        });
-        let Some(search) = search else { return };
-        let search_bar = search_bar.downgrade();
-        cx.spawn(|_, mut cx| async move {
-            search.await?;
-            search_bar.update(&mut cx, |search_bar, cx| {
-                if replacement.should_replace_all {
-                    search_bar.select_last_match(cx);
-                    search_bar.replace_all(&Default::default(), cx);
-                    Vim::update(cx, |vim, cx| {
-                        move_cursor(
-                            vim,
-                            Motion::StartOfLine {
-                                display_lines: false,
-                            },
-                            None,
-                            cx,
-                        )
-                    })
-                }
-            })?;
-            anyhow::Ok(())
+            drop(search_bar.search(&replacement.search, Some(options), cx));
         })
-        .detach_and_log_err(cx);
     })
 }
 
-// convert a vim query into something more usable by zed.
-// we don't attempt to fully convert between the two regex syntaxes,
-// but we do flip \( and \) to ( and ) (and vice-versa) in the pattern,
-// and convert \0..\9 to $0..$9 in the replacement so that common idioms work.
-fn parse_replace_all(query: &str) -> Replacement {
-    let mut chars = query.chars();
-    if Some('%') != chars.next() || Some('s') != chars.next() {
-        return Replacement::default();
+/// Uses the last search for a substitution with an empty pattern, as Vim does.
+pub(crate) fn with_last_search(
+    workspace: &Workspace,
+    mut replacement: Replacement,
+    cx: &WindowContext,
+) -> Replacement {
+    if replacement.search.is_empty() {
+        if let Some(search_bar) = workspace
+            .active_pane()
+            .read(cx)
+            .toolbar()
+            .read(cx)
+            .item_of_type::<BufferSearchBar>()
+        {
+            replacement.search = search_bar.read(cx).query(cx);
+        }
     }
+    replacement
+}
 
-    let Some(delimiter) = chars.next() else {
-        return Replacement::default();
-    };
+/// Replaces the matches of a substitution on the given rows, and returns the last row
+/// that changed.
+pub(crate) fn substitute_lines(
+    editor: &mut Editor,
+    rows: impl IntoIterator<Item = u32>,
+    replacement: &Replacement,
+    cx: &mut ViewContext<Editor>,
+) -> Option<u32> {
+    let regex = RegexBuilder::new(&replacement.search)
+        .case_insensitive(!replacement.is_case_sensitive)
+        .build()
+        .ok()?;
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    let mut edits = Vec::new();
+    let mut last_changed = None;
+    for row in rows {
+        let range = Point::new(row, 0)..Point::new(row, snapshot.line_len(row));
+        let line = snapshot.text_for_range(range.clone()).collect::<String>();
+        let replaced = if replacement.is_global {
+            regex.replace_all(&line, replacement.replacement.as_str())
+        } else {
+            regex.replace(&line, replacement.replacement.as_str())
+        };
+        if let Cow::Owned(replaced) = replaced {
+            last_changed = Some(snapshot.anchor_before(range.start));
+            edits.push((range, replaced));
+        }
+    }
 
-    let mut search = String::new();
-    let mut replacement = String::new();
-    let mut flags = String::new();
-
-    let mut buffer = &mut search;
-
-    let mut escaped = false;
-    // 0 - parsing search
-    // 1 - parsing replacement
-    // 2 - parsing flags
-    let mut phase = 0;
-
-    for c in chars {
-        if escaped {
-            escaped = false;
-            if phase == 1 && c.is_digit(10) {
-                buffer.push('$')
-            // unescape escaped parens
-            } else if phase == 0 && c == '(' || c == ')' {
-            } else if c != delimiter {
-                buffer.push('\\')
+    editor.transact(cx, |editor, cx| editor.edit(edits, cx));
+    let snapshot = editor.buffer().read(cx).snapshot(cx);
+    Some(last_changed?.to_point(&snapshot).row)
+}
+
+/// Whether the character can delimit the parts of a `:s` or `:g` command.
+pub(crate) fn is_delimiter(c: char) -> bool {
+    !c.is_alphanumeric() && !c.is_whitespace() && !matches!(c, '\\' | '"' | '|')
+}
+
+/// Splits the text at the first delimiter that isn't escaped with a backslash, unescaping
+/// the delimiters before it. The rest is `None` when there is no delimiter.
+pub(crate) fn split_at_delimiter(text: &str, delimiter: char) -> (String, Option<&str>) {
+    let mut part = String::new();
+    let mut chars = text.char_indices();
+    while let Some((ix, c)) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some((_, next)) if next == delimiter => part.push(next),
+                Some((_, next)) => {
+                    part.push('\\');
+                    part.push(next);
+                }
+                None => part.push('\\'),
             }
-            buffer.push(c)
-        } else if c == '\\' {
-            escaped = true;
         } else if c == delimiter {
-            if phase == 0 {
-                buffer = &mut replacement;
-                phase = 1;
-            } else if phase == 1 {
-                buffer = &mut flags;
-                phase = 2;
-            } else {
-                break;
-            }
+            return (part, Some(&text[ix + c.len_utf8()..]));
         } else {
-            // escape unescaped parens
-            if phase == 0 && c == '(' || c == ')' {
-                buffer.push('\\')
-            }
-            buffer.push(c)
+            part.push(c);
         }
     }
+    (part, None)
+}
+
+/// Parses a `:s/pattern/replacement/flags` command, without its range.
+pub(crate) fn parse_substitute(command: &str) -> Option<Replacement> {
+    let rest = command
+        .strip_prefix("substitute")
+        .or_else(|| command.strip_prefix('s'))?;
+    let delimiter = rest.chars().next().filter(|c| is_delimiter(*c))?;
+    let (search, rest) = split_at_delimiter(&rest[delimiter.len_utf8()..], delimiter);
+    let (replacement, flags) = match rest {
+        Some(rest) => {
+            let (replacement, flags) = split_at_delimiter(rest, delimiter);
+            (replacement, flags.unwrap_or(""))
+        }
+        None => (String::new(), ""),
+    };
 
     let mut replacement = Replacement {
-        search,
-        replacement,
+        search: vim_pattern_to_regex(&search),
+        replacement: vim_replacement_to_regex(&replacement),
         should_replace_all: true,
+        is_global: false,
         is_case_sensitive: true,
     };
-
-    for c in flags.chars() {
+    for c in flags.trim().chars() {
         match c {
-            'g' | 'I' => {}
+            'g' => replacement.is_global = true,
             'c' | 'n' => replacement.should_replace_all = false,
             'i' => replacement.is_case_sensitive = false,
-            _ => {}
+            'I' => replacement.is_case_sensitive = true,
+            '&' | 'e' => {}
+            _ => return None,
         }
     }
+    Some(replacement)
+}
 
-    replacement
+// convert a vim pattern into something more usable by zed.
+// we don't attempt to fully convert between the two regex syntaxes,
+// but vim treats (, ), |, {, }, + and ? as literals unless they are escaped,
+// the reverse of zed, so we flip those, and \< and \> become word boundaries.
+pub(crate) fn vim_pattern_to_regex(pattern: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = pattern.chars();
+    // in vim, the } ending \{n,m} doesn't need escaping
+    let mut in_braces = false;
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('{') => {
+                    in_braces = true;
+                    regex.push('{')
+                }
+                Some('}') => {
+                    in_braces = false;
+                    regex.push('}')
+                }
+                Some(c @ ('(' | ')' | '|' | '+' | '?')) => regex.push(c),
+                Some('=') => regex.push('?'),
+                Some('<' | '>') => regex.push_str("\\b"),
+                Some(c) => {
+                    regex.push('\\');
+                    regex.push(c)
+                }
+                None => regex.push_str("\\\\"),
+            }
+        } else if c == '}' && in_braces {
+            in_braces = false;
+            regex.push(c)
+        } else if matches!(c, '(' | ')' | '|' | '{' | '}' | '+' | '?') {
+            regex.push('\\');
+            regex.push(c)
+        } else {
+            regex.push(c)
+        }
+    }
+    regex
+}
+
+// convert a vim replacement into zed's syntax: & and \0..\9 become ${0}..${9},
+// \r becomes a line break, and a literal $ needs escaping.
+pub(crate) fn vim_replacement_to_regex(replacement: &str) -> String {
+    let mut result = String::new();
+    let mut chars = replacement.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(digit @ '0'..='9') => {
+                    result.push_str("${");
+                    result.push(digit);
+                    result.push('}');
+                }
+                Some('r') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some('$') => result.push_str("$$"),
+                Some(c) => result.push(c),
+                None => result.push('\\'),
+            },
+            '&' => result.push_str("${0}"),
+            '$' => result.push_str("$$"),
+            c => result.push(c),
+        }
+    }
+    result
 }
 
 #[cfg(test)]
@@ -364,11 +448,42 @@ mod test {
     use editor::DisplayPoint;
     use search::BufferSearchBar;
 
+    use super::{parse_substitute, vim_pattern_to_regex, Replacement};
     use crate::{
         state::Mode,
         test::{NeovimBackedTestContext, VimTestContext},
     };
 
+    #[test]
+    fn test_parse_substitute() {
+        assert_eq!(
+            parse_substitute("s/\\(a\\|b\\)+/<\\1&>/gi"),
+            Some(Replacement {
+                search: "(a|b)\\+".to_string(),
+                replacement: "<${1}${0}>".to_string(),
+                should_replace_all: true,
+                is_global: true,
+                is_case_sensitive: false,
+            })
+        );
+        assert_eq!(
+            parse_substitute("s#a\\#b#$#c").map(|replacement| replacement.replacement),
+            Some("$$".to_string())
+        );
+        assert_eq!(
+            parse_substitute("s/a/b/n").map(|replacement| replacement.should_replace_all),
+            Some(false)
+        );
+        assert_eq!(
+            parse_substitute("s/x").map(|replacement| replacement.search),
+            Some("x".to_string())
+        );
+        assert_eq!(parse_substitute("s/a/b/z"), None);
+        assert_eq!(parse_substitute("sort"), None);
+
+        assert_eq!(vim_pattern_to_regex("a\\{2,3}\\<b{c}"), "a{2,3}\\bb\\{c\\}");
+    }
+
     #[gpui::test]
     async fn test_move_to_next(cx: &mut gpui::TestAppContext) {
         let mut cx = VimTestContext::new(cx, true).await;
@@ -6,7 +6,7 @@ use editor::{
     Bias, DisplayPoint,
 };
 use gpui::{actions, impl_actions, ViewContext, WindowContext};
-use language::{char_kind, CharKind, Selection};
+use language::{char_kind, CharKind, Selection, TextObject};
 use serde::Deserialize;
 use workspace::Workspace;
 
@@ -27,6 +27,9 @@ pub enum Object {
     SquareBrackets,
     CurlyBrackets,
     AngleBrackets,
+    Method,
+    Class,
+    Argument,
 }
 
 #[derive(Clone, Deserialize, PartialEq)]
@@ -49,7 +52,10 @@ actions!(
         Parentheses,
         SquareBrackets,
         CurlyBrackets,
-        AngleBrackets
+        AngleBrackets,
+        Method,
+        Class,
+        Argument
     ]
 );
 
@@ -82,6 +88,10 @@ pub fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
     workspace.register_action(|_: &mut Workspace, _: &VerticalBars, cx: _| {
         object(Object::VerticalBars, cx)
     });
+    workspace.register_action(|_: &mut Workspace, _: &Method, cx: _| object(Object::Method, cx));
+    workspace.register_action(|_: &mut Workspace, _: &Class, cx: _| object(Object::Class, cx));
+    workspace
+        .register_action(|_: &mut Workspace, _: &Argument, cx: _| object(Object::Argument, cx));
 }
 
 fn object(object: Object, cx: &mut WindowContext) {
@@ -106,7 +116,10 @@ impl Object {
             | Object::Parentheses
             | Object::AngleBrackets
             | Object::CurlyBrackets
-            | Object::SquareBrackets => true,
+            | Object::SquareBrackets
+            | Object::Method
+            | Object::Class
+            | Object::Argument => true,
         }
     }
 
@@ -120,7 +133,10 @@ impl Object {
             | Object::Parentheses
             | Object::SquareBrackets
             | Object::CurlyBrackets
-            | Object::AngleBrackets => true,
+            | Object::AngleBrackets
+            | Object::Method
+            | Object::Class
+            | Object::Argument => true,
         }
     }
 
@@ -136,7 +152,10 @@ impl Object {
             | Object::Parentheses
             | Object::SquareBrackets
             | Object::CurlyBrackets
-            | Object::AngleBrackets => Mode::Visual,
+            | Object::AngleBrackets
+            | Object::Method
+            | Object::Class
+            | Object::Argument => Mode::Visual,
         }
     }
 
@@ -179,6 +198,21 @@ impl Object {
             Object::AngleBrackets => {
                 surrounding_markers(map, relative_to, around, self.is_multiline(), '<', '>')
             }
+            Object::Method => {
+                if around {
+                    around_definition(map, relative_to, TextObject::AroundFunction)
+                } else {
+                    in_definition(map, relative_to, TextObject::InsideFunction)
+                }
+            }
+            Object::Class => {
+                if around {
+                    around_definition(map, relative_to, TextObject::AroundClass)
+                } else {
+                    in_definition(map, relative_to, TextObject::InsideClass)
+                }
+            }
+            Object::Argument => argument(map, relative_to, around),
         }
     }
 
@@ -591,6 +625,133 @@ fn surrounding_markers(
     )
 }
 
+/// Returns the smallest text object of the given kind that contains `relative_to`, as
+/// captured by the language's text object query.
+fn text_object(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    target: TextObject,
+) -> Option<Range<usize>> {
+    let offset = relative_to.to_offset(map, Bias::Left);
+    map.buffer_snapshot
+        .text_object_ranges(offset..offset)?
+        .filter(|(range, text_object)| {
+            *text_object == target && range.start <= offset && offset < range.end
+        })
+        .map(|(range, _)| range)
+        .min_by_key(|range| range.len())
+}
+
+fn to_display_range(map: &DisplaySnapshot, range: Range<usize>) -> Range<DisplayPoint> {
+    map.clip_point(range.start.to_display_point(map), Bias::Left)
+        ..map.clip_point(range.end.to_display_point(map), Bias::Right)
+}
+
+/// Returns the body of the function or class containing `relative_to`. Like `i{`, a body
+/// in braces leaves out the braces, and the line breaks after the opening one and before
+/// the closing one.
+fn in_definition(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    target: TextObject,
+) -> Option<Range<DisplayPoint>> {
+    let mut range = text_object(map, relative_to, target)?;
+    let first = movement::chars_after(map, range.start).next();
+    let last = movement::chars_before(map, range.end).next();
+    if let Some((('{', open), ('}', close))) = first.zip(last) {
+        range = open.end..close.start;
+
+        for (ch, char_range) in movement::chars_after(map, range.start) {
+            if ch == '\n' {
+                range.start = char_range.end;
+            }
+            if !ch.is_whitespace() || ch == '\n' {
+                break;
+            }
+        }
+        for (ch, char_range) in movement::chars_before(map, range.end) {
+            if !ch.is_whitespace() || ch == '\n' {
+                break;
+            }
+            range.end = char_range.start;
+        }
+        range.end = range.end.max(range.start);
+    }
+    Some(to_display_range(map, range))
+}
+
+/// Returns the whole function or class containing `relative_to`. When it is on lines of its
+/// own, the lines are included with their line break, so that deleting it leaves no gap.
+fn around_definition(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    target: TextObject,
+) -> Option<Range<DisplayPoint>> {
+    let mut range = text_object(map, relative_to, target)?;
+
+    let mut line_start = range.start;
+    for (ch, char_range) in movement::chars_before(map, range.start) {
+        if ch == '\n' || !ch.is_whitespace() {
+            break;
+        }
+        line_start = char_range.start;
+    }
+    let starts_line = line_start == 0
+        || movement::chars_before(map, line_start)
+            .next()
+            .map(|(ch, _)| ch)
+            == Some('\n');
+
+    let mut line_end = None;
+    for (ch, char_range) in movement::chars_after(map, range.end) {
+        if ch == '\n' {
+            line_end = Some(char_range.end);
+            break;
+        }
+        if !ch.is_whitespace() {
+            break;
+        }
+    }
+
+    if starts_line {
+        if let Some(line_end) = line_end {
+            range = line_start..line_end;
+        }
+    }
+    Some(to_display_range(map, range))
+}
+
+/// Returns the argument or parameter containing `relative_to`. Around an argument includes
+/// the separator after it and the whitespace following that, or for the last argument, the
+/// separator before it.
+fn argument(
+    map: &DisplaySnapshot,
+    relative_to: DisplayPoint,
+    around: bool,
+) -> Option<Range<DisplayPoint>> {
+    let mut range = text_object(map, relative_to, TextObject::InsideParameter)?;
+    if around {
+        let mut after = movement::chars_after(map, range.end)
+            .skip_while(|(ch, _)| ch.is_whitespace() && *ch != '\n');
+        if let Some((',', separator)) = after.next() {
+            range.end = separator.end;
+            for (ch, char_range) in movement::chars_after(map, range.end) {
+                if !ch.is_whitespace() {
+                    break;
+                }
+                range.end = char_range.end;
+            }
+        } else {
+            let mut before =
+                movement::chars_before(map, range.start).skip_while(|(ch, _)| ch.is_whitespace());
+            if let Some((',', separator)) = before.next() {
+                range.start = separator.start;
+            }
+        }
+    }
+    Some(to_display_range(map, range))
+}
+
 #[cfg(test)]
 mod test {
     use indoc::indoc;
@@ -1026,4 +1187,71 @@ mod test {
                 .await;
         }
     }
+
+    #[gpui::test]
+    async fn test_syntax_text_objects(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state(
+            indoc! {"
+                fn a() {
+                    b(cˇ, d);
+                }
+
+                fn e() {}"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["d", "a", "a"]);
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    b(ˇd);
+                }
+
+                fn e() {}"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["c", "i", "a"]);
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                    b(ˇ);
+                }
+
+                fn e() {}"},
+            Mode::Insert,
+        );
+        cx.simulate_keystrokes(["escape", "d", "i", "f"]);
+        cx.assert_state(
+            indoc! {"
+                fn a() {
+                ˇ}
+
+                fn e() {}"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["d", "a", "f"]);
+        cx.assert_state(
+            indoc! {"
+                ˇ
+                fn e() {}"},
+            Mode::Normal,
+        );
+
+        cx.set_state(
+            indoc! {"
+                struct A {
+                    ˇb: u32,
+                }"},
+            Mode::Normal,
+        );
+        cx.simulate_keystrokes(["v", "a", "c"]);
+        cx.assert_state(
+            indoc! {"
+                «struct A {
+                    b: u32,
+                }ˇ»"},
+            Mode::Visual,
+        );
+    }
 }
@@ -0,0 +1,204 @@
+use std::sync::Arc;
+
+use fuzzy::{match_strings, StringMatch, StringMatchCandidate};
+use gpui::{
+    actions, Action, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView,
+    ParentElement, Render, Styled, Task, View, ViewContext, VisualContext, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{ModalView, Workspace};
+
+use crate::{normal::paste::Paste, registers::Registers};
+
+const MAX_PREVIEW_LEN: usize = 80;
+
+actions!(vim, [ShowRegisters]);
+
+pub(crate) fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+    workspace.register_action(|workspace: &mut Workspace, _: &ShowRegisters, cx| {
+        let entries = cx.global::<Registers>().entries(cx);
+        let workspace_handle = workspace.weak_handle();
+        workspace.toggle_modal(cx, move |cx| {
+            RegisterPicker::new(entries, workspace_handle, cx)
+        });
+    });
+}
+
+/// Lists the registers with their contents, like `:registers`, and puts the chosen one
+/// after the cursor.
+pub struct RegisterPicker {
+    picker: View<Picker<RegisterPickerDelegate>>,
+}
+
+impl RegisterPicker {
+    fn new(
+        entries: Vec<(char, String)>,
+        workspace: WeakView<Workspace>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate = RegisterPickerDelegate::new(cx.view().downgrade(), workspace, entries);
+        let picker = cx.new_view(|cx| Picker::new(delegate, cx));
+        Self { picker }
+    }
+}
+
+impl Render for RegisterPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+impl FocusableView for RegisterPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl EventEmitter<DismissEvent> for RegisterPicker {}
+impl ModalView for RegisterPicker {}
+
+pub struct RegisterPickerDelegate {
+    register_picker: WeakView<RegisterPicker>,
+    workspace: WeakView<Workspace>,
+    registers: Vec<char>,
+    candidates: Vec<StringMatchCandidate>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl RegisterPickerDelegate {
+    fn new(
+        register_picker: WeakView<RegisterPicker>,
+        workspace: WeakView<Workspace>,
+        entries: Vec<(char, String)>,
+    ) -> Self {
+        let candidates = entries
+            .iter()
+            .enumerate()
+            .map(|(candidate_id, (register, text))| {
+                StringMatchCandidate::new(candidate_id, format!("\"{register}  {}", preview(text)))
+            })
+            .collect();
+
+        Self {
+            register_picker,
+            workspace,
+            registers: entries.into_iter().map(|(register, _)| register).collect(),
+            candidates,
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+/// Shows the text on a single line, with newlines displayed as `^J` as Vim does.
+fn preview(text: &str) -> String {
+    let preview = text.replace('\n', "^J");
+    match preview.char_indices().nth(MAX_PREVIEW_LEN) {
+        Some((ix, _)) => format!("{}…", &preview[..ix]),
+        None => preview,
+    }
+}
+
+impl PickerDelegate for RegisterPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self) -> Arc<str> {
+        "Put register...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        if let Some(mat) = self.matches.get(self.selected_index) {
+            let register = self.registers[mat.candidate_id];
+            let workspace = self.workspace.clone();
+            // The editor only becomes vim's active editor again once the picker is
+            // dismissed and focus has returned to it.
+            cx.spawn(|_, mut cx| async move {
+                workspace.update(&mut cx, |_, cx| {
+                    cx.update_global(|registers: &mut Registers, _| registers.select(register));
+                    cx.dispatch_action(Paste::default().boxed_clone());
+                })
+            })
+            .detach_and_log_err(cx);
+        }
+        self.dismissed(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.register_picker
+            .update(cx, |_, cx| cx.emit(DismissEvent))
+            .log_err();
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let background = cx.background_executor().clone();
+        let candidates = self.candidates.clone();
+        cx.spawn(|this, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, candidate)| StringMatch {
+                        candidate_id: index,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    background,
+                )
+                .await
+            };
+
+            this.update(&mut cx, |this, cx| {
+                let delegate = &mut this.delegate;
+                delegate.matches = matches;
+                delegate.selected_index = delegate
+                    .selected_index
+                    .min(delegate.matches.len().saturating_sub(1));
+                cx.notify();
+            })
+            .log_err();
+        })
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let mat = &self.matches[ix];
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .selected(selected)
+                .child(HighlightedLabel::new(
+                    mat.string.clone(),
+                    mat.positions.clone(),
+                )),
+        )
+    }
+}
@@ -0,0 +1,156 @@
+use collections::HashMap;
+use gpui::{AppContext, ClipboardItem};
+
+/// Vim's registers, shared by all editors. The unnamed register is the system clipboard.
+///
+/// Kept apart from [`crate::Vim`], so that yanks and deletes can write to them from within
+/// an editor update, while the vim state is being updated.
+#[derive(Default)]
+pub struct Registers {
+    /// The register chosen with `"` for the next yank, delete or put.
+    selected: Option<char>,
+    contents: HashMap<char, ClipboardItem>,
+}
+
+impl Registers {
+    /// Chooses the register the next yank, delete or put uses. Uppercase letters select the
+    /// lowercase register, to be appended to.
+    pub fn select(&mut self, register: char) {
+        if register.is_ascii_alphanumeric() || "\"-_+*".contains(register) {
+            self.selected = Some(register);
+        }
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected = None;
+    }
+
+    /// Stores yanked or deleted text, in the selected register if any. Otherwise yanks go to
+    /// `"0`, deletes spanning lines shift the numbered registers and go to `"1`, and smaller
+    /// deletes go to `"-`. The unnamed register always ends up with the text too.
+    pub fn write(&mut self, item: ClipboardItem, is_yank: bool, cx: &mut AppContext) {
+        match self.selected.take() {
+            Some('_') => {}
+            Some('"' | '+' | '*') => cx.write_to_clipboard(item),
+            Some(register) => {
+                let lowercase = register.to_ascii_lowercase();
+                let item = match self.contents.get(&lowercase) {
+                    Some(existing) if register.is_ascii_uppercase() => append(existing, &item),
+                    _ => item,
+                };
+                self.contents.insert(lowercase, item.clone());
+                cx.write_to_clipboard(item);
+            }
+            None => {
+                if is_yank {
+                    self.contents.insert('0', item.clone());
+                } else if item.text().contains('\n') {
+                    for register in ('1'..='8').rev() {
+                        if let Some(shifted) = self.contents.remove(&register) {
+                            let next = char::from_digit(register.to_digit(10).unwrap() + 1, 10);
+                            self.contents.insert(next.unwrap(), shifted);
+                        }
+                    }
+                    self.contents.insert('1', item.clone());
+                } else {
+                    self.contents.insert('-', item.clone());
+                }
+                cx.write_to_clipboard(item);
+            }
+        }
+    }
+
+    /// Returns the contents of the selected register, or of the unnamed register.
+    pub fn read(&mut self, cx: &AppContext) -> Option<ClipboardItem> {
+        match self.selected.take() {
+            None | Some('"' | '+' | '*') => cx.read_from_clipboard(),
+            Some('_') => None,
+            Some(register) => self.contents.get(&register.to_ascii_lowercase()).cloned(),
+        }
+    }
+
+    /// Returns the registers that have contents, in the order `:registers` lists them.
+    pub fn entries(&self, cx: &AppContext) -> Vec<(char, String)> {
+        let unnamed = cx
+            .read_from_clipboard()
+            .map(|item| ('"', item.text().clone()));
+        let named = ('0'..='9')
+            .chain('a'..='z')
+            .chain(Some('-'))
+            .filter_map(|register| {
+                let item = self.contents.get(&register)?;
+                Some((register, item.text().clone()))
+            });
+        unnamed.into_iter().chain(named).collect()
+    }
+}
+
+/// Appends text to a register. As in Vim, appending to or from a linewise register
+/// keeps the text on separate lines.
+fn append(existing: &ClipboardItem, item: &ClipboardItem) -> ClipboardItem {
+    let mut text = existing.text().clone();
+    let linewise = text.ends_with('\n') || item.text().ends_with('\n');
+    if linewise && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    text.push_str(item.text());
+    if linewise && !text.ends_with('\n') {
+        text.push('\n');
+    }
+    ClipboardItem::new(text)
+}
+
+#[cfg(test)]
+mod test {
+    use gpui::ClipboardItem;
+
+    use super::append;
+    use crate::{state::Mode, test::VimTestContext};
+
+    #[test]
+    fn test_append() {
+        let append = |existing: &str, text: &str| {
+            append(
+                &ClipboardItem::new(existing.to_string()),
+                &ClipboardItem::new(text.to_string()),
+            )
+            .text()
+            .clone()
+        };
+        assert_eq!(append("one", "two"), "onetwo");
+        assert_eq!(append("one\n", "two"), "one\ntwo\n");
+        assert_eq!(append("one", "two\n"), "one\ntwo\n");
+        assert_eq!(append("one\n", "two\n"), "one\ntwo\n");
+    }
+
+    #[gpui::test]
+    async fn test_named_registers(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇone\ntwo", Mode::Normal);
+        cx.simulate_keystrokes(["\"", "a", "y", "y", "j", "y", "y"]);
+        cx.simulate_keystrokes(["\"", "a", "p"]);
+        cx.assert_state("one\ntwo\nˇone", Mode::Normal);
+        cx.simulate_keystrokes(["p"]);
+        cx.assert_state("one\ntwo\none\nˇtwo", Mode::Normal);
+
+        cx.simulate_keystrokes(["g", "g", "\"", "shift-a", "y", "y", "\"", "a", "shift-p"]);
+        cx.assert_state("ˇone\none\none\ntwo\none\ntwo", Mode::Normal);
+    }
+
+    #[gpui::test]
+    async fn test_numbered_registers(cx: &mut gpui::TestAppContext) {
+        let mut cx = VimTestContext::new(cx, true).await;
+
+        cx.set_state("ˇa\nb\nc\nd", Mode::Normal);
+        cx.simulate_keystrokes(["d", "d", "d", "d"]);
+        cx.assert_state("ˇc\nd", Mode::Normal);
+        cx.simulate_keystrokes(["\"", "2", "p"]);
+        cx.assert_state("c\nˇa\nd", Mode::Normal);
+        cx.simulate_keystrokes(["\"", "1", "shift-p"]);
+        cx.assert_state("c\nˇb\na\nd", Mode::Normal);
+
+        cx.simulate_keystrokes(["\"", "_", "d", "d", "p"]);
+        cx.assert_state("c\na\nˇb\nd", Mode::Normal);
+    }
+}
@@ -1,11 +1,12 @@
 use std::{ops::Range, sync::Arc};
 
-use gpui::{Action, KeyContext};
-use language::CursorShape;
+use collections::HashMap;
+use gpui::{Action, EntityId, KeyContext};
+use language::{Anchor, CursorShape};
 use serde::{Deserialize, Serialize};
-use workspace::searchable::Direction;
+use workspace::{searchable::Direction, WorkspaceId};
 
-use crate::motion::Motion;
+use crate::{marks::WorkspaceMarks, motion::Motion, object::Object};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Mode {
@@ -40,6 +41,11 @@ pub enum Operator {
     Object { around: bool },
     FindForward { before: bool },
     FindBackward { after: bool },
+    RecordRegister,
+    ReplayRegister,
+    Register,
+    Mark,
+    JumpToMark { line: bool },
 }
 
 #[derive(Default, Clone)]
@@ -86,6 +92,19 @@ pub struct WorkspaceState {
     pub recorded_count: Option<usize>,
     pub recorded_actions: Vec<ReplayableAction>,
     pub recorded_selection: RecordedSelection,
+
+    /// The register keystrokes are being recorded into by `q`, if any.
+    pub recording_register: Option<char>,
+    pub last_recorded_register: Option<char>,
+    pub last_replayed_register: Option<char>,
+    pub replaying_register: bool,
+    /// The actions recorded into each register, replayed by `@`.
+    pub recordings: HashMap<char, Vec<ReplayableAction>>,
+
+    /// The lowercase marks of each buffer.
+    pub buffer_marks: HashMap<EntityId, HashMap<char, Anchor>>,
+    /// The jumplist and uppercase marks of each workspace, by workspace id.
+    pub workspace_marks: HashMap<WorkspaceId, WorkspaceMarks>,
 }
 
 #[derive(Debug)]
@@ -95,6 +114,23 @@ pub enum ReplayableAction {
         text: Arc<str>,
         utf16_range_to_replace: Option<Range<isize>>,
     },
+    Edit(RecordedEdit),
+}
+
+/// An edit recorded for `.` as what it did rather than as the keystrokes that did it, so that
+/// it replays the same way however those were typed, or if they were replayed from a register.
+#[derive(Clone, Debug)]
+pub enum RecordedEdit {
+    /// An operator applied to a motion, like `d2w` or `cf,`.
+    Motion { operator: Operator, motion: Motion },
+    /// An operator applied to a text object, like `ci(`.
+    Object {
+        operator: Operator,
+        object: Object,
+        around: bool,
+    },
+    /// `r`, replacing characters with the text.
+    Replace { text: Arc<str> },
 }
 
 impl Clone for ReplayableAction {
@@ -108,6 +144,7 @@ impl Clone for ReplayableAction {
                 text: text.clone(),
                 utf16_range_to_replace: utf16_range_to_replace.clone(),
             },
+            Self::Edit(edit) => Self::Edit(edit.clone()),
         }
     }
 }
@@ -219,15 +256,26 @@ impl Operator {
             Operator::FindForward { before: true } => "t",
             Operator::FindBackward { after: false } => "F",
             Operator::FindBackward { after: true } => "T",
+            Operator::RecordRegister => "q",
+            Operator::ReplayRegister => "@",
+            Operator::Register => "\"",
+            Operator::Mark => "m",
+            Operator::JumpToMark { line: true } => "'",
+            Operator::JumpToMark { line: false } => "`",
         }
     }
 
     pub fn context_flags(&self) -> &'static [&'static str] {
         match self {
             Operator::Object { .. } => &["VimObject"],
-            Operator::FindForward { .. } | Operator::FindBackward { .. } | Operator::Replace => {
-                &["VimWaiting"]
-            }
+            Operator::FindForward { .. }
+            | Operator::FindBackward { .. }
+            | Operator::Replace
+            | Operator::RecordRegister
+            | Operator::ReplayRegister
+            | Operator::Register
+            | Operator::Mark
+            | Operator::JumpToMark { .. } => &["VimWaiting"],
             _ => &[],
         }
     }
@@ -2,7 +2,24 @@ use editor::{ClipboardSelection, Editor};
 use gpui::{AppContext, ClipboardItem};
 use language::{CharKind, Point};
 
+use crate::registers::Registers;
+
+/// Copies the selections into the registers, as deleted text.
 pub fn copy_selections_content(editor: &mut Editor, linewise: bool, cx: &mut AppContext) {
+    copy_selections_content_internal(editor, linewise, false, cx)
+}
+
+/// Copies the selections into the registers, as yanked text.
+pub fn yank_selections_content(editor: &mut Editor, linewise: bool, cx: &mut AppContext) {
+    copy_selections_content_internal(editor, linewise, true, cx)
+}
+
+fn copy_selections_content_internal(
+    editor: &mut Editor,
+    linewise: bool,
+    is_yank: bool,
+    cx: &mut AppContext,
+) {
     let selections = editor.selections.all_adjusted(cx);
     let buffer = editor.buffer().read(cx).snapshot(cx);
     let mut text = String::new();
@@ -46,7 +63,8 @@ pub fn copy_selections_content(editor: &mut Editor, linewise: bool, cx: &mut App
         }
     }
 
-    cx.write_to_clipboard(ClipboardItem::new(text).with_metadata(clipboard_selections));
+    let item = ClipboardItem::new(text).with_metadata(clipboard_selections);
+    cx.update_global(|registers: &mut Registers, cx| registers.write(item, is_yank, cx));
 }
 
 pub fn coerce_punctuation(kind: CharKind, treat_punctuation_as_word: bool) -> CharKind {
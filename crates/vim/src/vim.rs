@@ -6,10 +6,13 @@ mod test;
 mod command;
 mod editor_events;
 mod insert;
+mod marks;
 mod mode_indicator;
 mod motion;
 mod normal;
 mod object;
+mod register_picker;
+mod registers;
 mod state;
 mod utils;
 mod visual;
@@ -22,10 +25,15 @@ use gpui::{
     actions, impl_actions, Action, AppContext, EntityId, KeyContext, Subscription, View,
     ViewContext, WeakView, WindowContext,
 };
+use insert::NormalBefore;
 use language::{CursorShape, Point, Selection, SelectionGoal};
 pub use mode_indicator::ModeIndicator;
 use motion::Motion;
-use normal::normal_replace;
+use normal::{
+    normal_replace,
+    repeat::{record_register, replay_register},
+};
+use registers::Registers;
 use serde::Deserialize;
 use settings::{update_settings_file, Settings, SettingsStore};
 use state::{EditorState, Mode, Operator, RecordedSelection, WorkspaceState};
@@ -33,7 +41,7 @@ use std::{ops::Range, sync::Arc};
 use visual::{visual_block_motion, visual_replace};
 use workspace::{self, Workspace};
 
-use crate::state::ReplayableAction;
+use crate::state::{RecordedEdit, ReplayableAction};
 
 /// Whether or not to enable Vim mode (work in progress).
 ///
@@ -68,6 +76,7 @@ impl_actions!(vim, [SwitchMode, PushOperator, Number]);
 /// Initializes the `vim` crate.
 pub fn init(cx: &mut AppContext) {
     cx.set_global(Vim::default());
+    cx.set_global(Registers::default());
     VimModeSetting::register(cx);
 
     editor_events::init(cx);
@@ -123,6 +132,8 @@ fn register(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) {
 
     normal::register(workspace, cx);
     insert::register(workspace, cx);
+    marks::register(workspace, cx);
+    register_picker::register(workspace, cx);
     motion::register(workspace, cx);
     command::register(workspace, cx);
     object::register(workspace, cx);
@@ -138,12 +149,32 @@ pub fn observe_keystrokes(cx: &mut WindowContext) {
             .map(|action| action.boxed_clone())
         {
             Vim::update(cx, |vim, _| {
-                if vim.workspace_state.recording {
+                if let Some(register) = vim.workspace_state.recording_register {
                     vim.workspace_state
-                        .recorded_actions
+                        .recordings
+                        .entry(register)
+                        .or_default()
                         .push(ReplayableAction::Action(action.boxed_clone()));
+                }
+
+                if vim.workspace_state.recording {
+                    // The key that completed an edit recorded with `record_edit` is
+                    // already part of it, but leaving insert mode right after a change,
+                    // as in `cw<esc>`, finishes the recording.
+                    let is_normal_before = action.as_any().downcast_ref::<NormalBefore>().is_some();
+                    let completes_edit = !is_normal_before
+                        && matches!(
+                            vim.workspace_state.recorded_actions.last(),
+                            Some(ReplayableAction::Edit(_))
+                        )
+                        && action.name().starts_with("vim::");
+                    if !completes_edit {
+                        vim.workspace_state
+                            .recorded_actions
+                            .push(ReplayableAction::Action(action.boxed_clone()));
+                    }
 
-                    if vim.workspace_state.stop_recording_after_next_action {
+                    if vim.workspace_state.stop_recording_after_next_action || is_normal_before {
                         vim.workspace_state.recording = false;
                         vim.workspace_state.stop_recording_after_next_action = false;
                     }
@@ -160,7 +191,14 @@ pub fn observe_keystrokes(cx: &mut WindowContext) {
 
         Vim::update(cx, |vim, cx| match vim.active_operator() {
             Some(
-                Operator::FindForward { .. } | Operator::FindBackward { .. } | Operator::Replace,
+                Operator::FindForward { .. }
+                | Operator::FindBackward { .. }
+                | Operator::Replace
+                | Operator::RecordRegister
+                | Operator::ReplayRegister
+                | Operator::Register
+                | Operator::Mark
+                | Operator::JumpToMark { .. },
             ) => {}
             Some(_) => {
                 vim.clear_operator(cx);
@@ -171,6 +209,15 @@ pub fn observe_keystrokes(cx: &mut WindowContext) {
     .detach()
 }
 
+/// Replaces the characters under the cursors, or the selected ones, with the text.
+pub(crate) fn replace(text: Arc<str>, cx: &mut WindowContext) {
+    match Vim::read(cx).state().mode {
+        Mode::Normal => normal_replace(text, cx),
+        Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_replace(text, cx),
+        _ => Vim::update(cx, |vim, cx| vim.clear_operator(cx)),
+    }
+}
+
 /// The state pertaining to Vim mode. Stored as a global.
 #[derive(Default)]
 pub struct Vim {
@@ -205,13 +252,19 @@ impl Vim {
                 }
             }
             EditorEvent::InputIgnored { text } => {
+                // Recorded before being handled, so that the register named after `q`
+                // doesn't end up in the recording it starts. It isn't recorded for `.`, as
+                // it only completes operators like `f` and `r`, which use `record_edit`.
+                Vim::record_insertion_in_register(text, None, cx);
                 Vim::active_editor_input_ignored(text.clone(), cx);
-                Vim::record_insertion(text, None, cx)
             }
             EditorEvent::InputHandled {
                 text,
                 utf16_range_to_replace: range_to_replace,
-            } => Vim::record_insertion(text, range_to_replace.clone(), cx),
+            } => {
+                Vim::record_insertion_in_register(text, range_to_replace.clone(), cx);
+                Vim::record_insertion(text, range_to_replace.clone(), cx)
+            }
             _ => {}
         }));
 
@@ -254,6 +307,31 @@ impl Vim {
         });
     }
 
+    /// Records an insertion into the register being recorded with `q`, if any.
+    fn record_insertion_in_register(
+        text: &Arc<str>,
+        range_to_replace: Option<Range<isize>>,
+        cx: &mut WindowContext,
+    ) {
+        Vim::update(cx, |vim, _| {
+            // Insertions replayed by `.` or `@` are already recorded as the action that
+            // replayed them.
+            if vim.workspace_state.replaying || vim.workspace_state.replaying_register {
+                return;
+            }
+            if let Some(register) = vim.workspace_state.recording_register {
+                vim.workspace_state
+                    .recordings
+                    .entry(register)
+                    .or_default()
+                    .push(ReplayableAction::Insertion {
+                        text: text.clone(),
+                        utf16_range_to_replace: range_to_replace,
+                    });
+            }
+        });
+    }
+
     fn update_active_editor<S>(
         &self,
         cx: &mut WindowContext,
@@ -332,6 +410,26 @@ impl Vim {
         }
     }
 
+    /// Records an operator as the edit it made, replacing the actions recorded since the
+    /// operator started recording. Anything but a change, which goes on to record its
+    /// insertion, stops the recording.
+    pub fn record_edit(&mut self, edit: RecordedEdit) {
+        if !self.workspace_state.recording {
+            return;
+        }
+        let is_change = match &edit {
+            RecordedEdit::Motion { operator, .. } | RecordedEdit::Object { operator, .. } => {
+                *operator == Operator::Change
+            }
+            RecordedEdit::Replace { .. } => false,
+        };
+        self.workspace_state.recorded_actions = vec![ReplayableAction::Edit(edit)];
+        if !is_change {
+            self.workspace_state.recording = false;
+            self.workspace_state.stop_recording_after_next_action = false;
+        }
+    }
+
     /// Explicitly record one action (equivalents to start_recording and stop_recording)
     pub fn record_current_action(&mut self, cx: &mut WindowContext) {
         self.start_recording(cx);
@@ -347,6 +445,7 @@ impl Vim {
             state.mode = mode;
             state.operator_stack.clear();
         });
+        cx.update_global(|registers: &mut Registers, _| registers.clear_selection());
         if mode != Mode::Insert {
             self.take_count(cx);
         }
@@ -490,11 +589,34 @@ impl Vim {
                 });
                 motion::motion(find, cx)
             }
-            Some(Operator::Replace) => match Vim::read(cx).state().mode {
-                Mode::Normal => normal_replace(text, cx),
-                Mode::Visual | Mode::VisualLine | Mode::VisualBlock => visual_replace(text, cx),
-                _ => Vim::update(cx, |vim, cx| vim.clear_operator(cx)),
-            },
+            Some(Operator::Replace) => {
+                Vim::update(cx, |vim, _| {
+                    vim.record_edit(RecordedEdit::Replace { text: text.clone() })
+                });
+                replace(text, cx)
+            }
+            Some(Operator::RecordRegister) => {
+                Vim::update(cx, |vim, cx| vim.clear_operator(cx));
+                record_register(text.chars().next().unwrap(), cx)
+            }
+            Some(Operator::ReplayRegister) => {
+                Vim::update(cx, |vim, cx| vim.pop_operator(cx));
+                replay_register(text.chars().next().unwrap(), cx)
+            }
+            Some(Operator::Register) => {
+                Vim::update(cx, |vim, cx| vim.pop_operator(cx));
+                cx.update_global(|registers: &mut Registers, _| {
+                    registers.select(text.chars().next().unwrap())
+                })
+            }
+            Some(Operator::Mark) => {
+                Vim::update(cx, |vim, cx| vim.pop_operator(cx));
+                marks::set_mark(text.chars().next().unwrap(), cx)
+            }
+            Some(Operator::JumpToMark { line }) => {
+                Vim::update(cx, |vim, cx| vim.pop_operator(cx));
+                marks::jump_to_mark(text.chars().next().unwrap(), line, cx)
+            }
             _ => {}
         }
     }
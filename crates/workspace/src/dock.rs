@@ -187,6 +187,7 @@ struct PanelEntry {
 
 pub struct PanelButtons {
     dock: View<Dock>,
+    status_item_id: &'static str,
 }
 
 impl Dock {
@@ -604,6 +605,13 @@ impl Render for Dock {
                 })
                 .child(
                     div()
+                        .accessibility_role(AccessibilityRole::Group)
+                        .accessibility_label(
+                            entry
+                                .panel
+                                .icon_tooltip(cx)
+                                .unwrap_or(entry.panel.persistent_name()),
+                        )
                         .map(|this| match self.position().axis() {
                             Axis::Horizontal => this.min_w(size).h_full(),
                             Axis::Vertical => this.min_h(size).w_full(),
@@ -620,7 +628,15 @@ impl Render for Dock {
 impl PanelButtons {
     pub fn new(dock: View<Dock>, cx: &mut ViewContext<Self>) -> Self {
         cx.observe(&dock, |_, _, cx| cx.notify()).detach();
-        Self { dock }
+        let status_item_id = match dock.read(cx).position() {
+            DockPosition::Left => "left_dock",
+            DockPosition::Bottom => "bottom_dock",
+            DockPosition::Right => "right_dock",
+        };
+        Self {
+            dock,
+            status_item_id,
+        }
     }
 }
 
@@ -712,6 +728,10 @@ impl Render for PanelButtons {
 }
 
 impl StatusItemView for PanelButtons {
+    fn status_item_id(&self) -> &'static str {
+        self.status_item_id
+    }
+
     fn set_active_pane_item(
         &mut self,
         _active_pane_item: Option<&dyn crate::ItemHandle>,
@@ -39,6 +39,7 @@ use theme::Theme;
 pub struct ItemSettings {
     pub git_status: bool,
     pub close_position: ClosePosition,
+    pub file_icons: bool,
 }
 
 #[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
@@ -68,6 +69,10 @@ pub struct ItemSettingsContent {
     ///
     /// Default: right
     close_position: Option<ClosePosition>,
+    /// Whether to show the icon of the file's type on a tab item.
+    ///
+    /// Default: false
+    file_icons: Option<bool>,
 }
 
 impl Settings for ItemSettings {
@@ -670,6 +675,10 @@ pub enum FollowEvent {
     Unfollow,
 }
 
+/// An item that collaborators can follow each other into, such as editors, channel notes and
+/// project searches. Terminals aren't followable, as they run on the leader's machine and
+/// there's no protocol for sharing their contents. While the leader has an item that isn't
+/// followable active, followers see the leader's shared screen, if any, or stay where they are.
 pub trait FollowableItem: Item {
     fn remote_id(&self) -> Option<ViewId>;
     fn to_state_proto(&self, cx: &WindowContext) -> Option<proto::view::Variant>;
@@ -6,13 +6,14 @@ use crate::{
 };
 use anyhow::Result;
 use collections::{HashMap, HashSet, VecDeque};
+use file_icons::FileIcons;
 use futures::{stream::FuturesUnordered, StreamExt};
 use gpui::{
     actions, impl_actions, overlay, prelude::*, Action, AnchorCorner, AnyElement, AppContext,
     AsyncWindowContext, DismissEvent, Div, DragMoveEvent, EntityId, EventEmitter, ExternalPaths,
     FocusHandle, FocusableView, Model, MouseButton, NavigationDirection, Pixels, Point,
-    PromptLevel, Render, ScrollHandle, Subscription, Task, View, ViewContext, VisualContext,
-    WeakView, WindowContext,
+    PromptLevel, Render, ScrollHandle, Subscription, SwipeEvent, Task, TouchPhase, View,
+    ViewContext, VisualContext, WeakView, WindowContext,
 };
 use parking_lot::Mutex;
 use project::{Project, ProjectEntryId, ProjectPath};
@@ -101,6 +102,7 @@ actions!(
         GoBack,
         DeploySearch,
         GoForward,
+        GoToLastEdit,
         ReopenClosedItem,
         SplitLeft,
         SplitUp,
@@ -110,6 +112,7 @@ actions!(
 );
 
 const MAX_NAVIGATION_HISTORY_LEN: usize = 1024;
+const MAX_NAVIGATION_MENU_ENTRIES: usize = 20;
 
 pub enum Event {
     AddItem { item: Box<dyn ItemHandle> },
@@ -190,6 +193,7 @@ struct NavHistoryState {
     backward_stack: VecDeque<NavigationEntry>,
     forward_stack: VecDeque<NavigationEntry>,
     closed_stack: VecDeque<NavigationEntry>,
+    edit_stack: VecDeque<NavigationEntry>,
     paths_by_item: HashMap<EntityId, (ProjectPath, Option<PathBuf>)>,
     pane: WeakView<Pane>,
     next_timestamp: Arc<AtomicUsize>,
@@ -202,6 +206,7 @@ pub enum NavigationMode {
     GoingForward,
     ClosingItem,
     ReopeningClosedItem,
+    GoingToLastEdit,
     Disabled,
 }
 
@@ -258,6 +263,7 @@ impl Pane {
                 backward_stack: Default::default(),
                 forward_stack: Default::default(),
                 closed_stack: Default::default(),
+                edit_stack: Default::default(),
                 paths_by_item: Default::default(),
                 pane: handle.clone(),
                 next_timestamp,
@@ -1283,7 +1289,20 @@ impl Pane {
         let is_active = ix == self.active_item_index;
 
         let label = item.tab_content(Some(detail), is_active, cx);
-        let close_side = &ItemSettings::get_global(cx).close_position;
+        let item_settings = ItemSettings::get_global(cx);
+        let close_side = &item_settings.close_position;
+        let file_icon = item_settings
+            .file_icons
+            .then(|| item.project_path(cx))
+            .flatten()
+            .and_then(|project_path| FileIcons::get_icon(&project_path.path, cx))
+            .map(|icon| {
+                Icon::from_path(icon.to_string()).color(if is_active {
+                    Color::Default
+                } else {
+                    Color::Muted
+                })
+            });
 
         let indicator = maybe!({
             let indicator_color = match (item.has_conflict(cx), item.is_dirty(cx)) {
@@ -1370,6 +1389,7 @@ impl Pane {
                             .detach_and_log_err(cx);
                     })),
             )
+            .children(file_icon)
             .child(label);
 
         let single_entry_to_resolve = {
@@ -1459,6 +1479,49 @@ impl Pane {
         })
     }
 
+    /// Wrap a navigation button in a menu that lists the entries it would navigate through,
+    /// so that the user can jump straight to any one of them.
+    fn render_nav_history_menu(
+        &self,
+        mode: NavigationMode,
+        button: IconButton,
+        cx: &mut ViewContext<'_, Pane>,
+    ) -> impl IntoElement {
+        let pane = cx.view().downgrade();
+        let workspace = self.workspace.clone();
+        let id = match mode {
+            NavigationMode::GoingBack => "navigate_backward_menu",
+            _ => "navigate_forward_menu",
+        };
+        right_click_menu(id).trigger(button).menu(move |cx| {
+            let pane = pane.clone();
+            let workspace = workspace.clone();
+            ContextMenu::build(cx, move |mut menu, cx| {
+                let (Some(workspace), Some(pane)) = (workspace.upgrade(), pane.upgrade()) else {
+                    return menu;
+                };
+                let descriptions = workspace
+                    .read(cx)
+                    .navigation_history_descriptions(&pane, mode, cx);
+                for (timestamp, description) in
+                    descriptions.into_iter().take(MAX_NAVIGATION_MENU_ENTRIES)
+                {
+                    let pane = pane.downgrade();
+                    menu = menu.entry(
+                        description,
+                        None,
+                        cx.handler_for(&workspace, move |workspace, cx| {
+                            workspace
+                                .navigate_history_to(pane.clone(), mode, timestamp, cx)
+                                .detach_and_log_err(cx);
+                        }),
+                    );
+                }
+                menu
+            })
+        })
+    }
+
     fn render_tab_bar(&mut self, cx: &mut ViewContext<'_, Pane>) -> impl IntoElement {
         TabBar::new("tab_bar")
             .track_scroll(self.tab_bar_scroll_handle.clone())
@@ -1467,24 +1530,46 @@ impl Pane {
                     h_flex()
                         .gap_2()
                         .child(
-                            IconButton::new("navigate_backward", IconName::ArrowLeft)
-                                .icon_size(IconSize::Small)
-                                .on_click({
-                                    let view = cx.view().clone();
-                                    move |_, cx| view.update(cx, Self::navigate_backward)
-                                })
-                                .disabled(!self.can_navigate_backward())
-                                .tooltip(|cx| Tooltip::for_action("Go Back", &GoBack, cx)),
+                            self.render_nav_history_menu(
+                                NavigationMode::GoingBack,
+                                IconButton::new("navigate_backward", IconName::ArrowLeft)
+                                    .icon_size(IconSize::Small)
+                                    .on_click({
+                                        let view = cx.view().clone();
+                                        move |_, cx| view.update(cx, Self::navigate_backward)
+                                    })
+                                    .disabled(!self.can_navigate_backward())
+                                    .tooltip(|cx| {
+                                        Tooltip::with_meta(
+                                            "Go Back",
+                                            Some(&GoBack),
+                                            "Right-click for history",
+                                            cx,
+                                        )
+                                    }),
+                                cx,
+                            ),
                         )
                         .child(
-                            IconButton::new("navigate_forward", IconName::ArrowRight)
-                                .icon_size(IconSize::Small)
-                                .on_click({
-                                    let view = cx.view().clone();
-                                    move |_, cx| view.update(cx, Self::navigate_forward)
-                                })
-                                .disabled(!self.can_navigate_forward())
-                                .tooltip(|cx| Tooltip::for_action("Go Forward", &GoForward, cx)),
+                            self.render_nav_history_menu(
+                                NavigationMode::GoingForward,
+                                IconButton::new("navigate_forward", IconName::ArrowRight)
+                                    .icon_size(IconSize::Small)
+                                    .on_click({
+                                        let view = cx.view().clone();
+                                        move |_, cx| view.update(cx, Self::navigate_forward)
+                                    })
+                                    .disabled(!self.can_navigate_forward())
+                                    .tooltip(|cx| {
+                                        Tooltip::with_meta(
+                                            "Go Forward",
+                                            Some(&GoForward),
+                                            "Right-click for history",
+                                            cx,
+                                        )
+                                    }),
+                                cx,
+                            ),
                         ),
                 )
             })
@@ -1910,6 +1995,33 @@ impl Render for Pane {
                     }
                 }),
             )
+            .on_swipe(cx.listener(|pane, event: &SwipeEvent, cx| {
+                if let Some(workspace) = pane.workspace.upgrade() {
+                    let pane = cx.view().downgrade();
+                    let direction = event.direction;
+                    cx.window_context().defer(move |cx| {
+                        workspace.update(cx, |workspace, cx| {
+                            match direction {
+                                NavigationDirection::Back => workspace.go_back(pane, cx),
+                                NavigationDirection::Forward => workspace.go_forward(pane, cx),
+                            }
+                            .detach_and_log_err(cx)
+                        })
+                    })
+                }
+            }))
+            .on_pinch(|event, cx| {
+                // Zoom the buffer font like the zoom actions do, settling on a whole size
+                // once the gesture ends so text stays crisp.
+                let delta = event.delta;
+                let phase = event.phase;
+                theme::adjust_font_size(cx, |size| {
+                    *size = *size * (1. + delta);
+                    if phase == TouchPhase::Ended {
+                        *size = size.round();
+                    }
+                });
+            })
     }
 }
 
@@ -1925,6 +2037,10 @@ impl ItemNavHistory {
     pub fn pop_forward(&mut self, cx: &mut WindowContext) -> Option<NavigationEntry> {
         self.history.pop(NavigationMode::GoingForward, cx)
     }
+
+    pub fn push_edit<D: 'static + Send + Any>(&mut self, data: Option<D>) {
+        self.history.push_edit(data, self.item.clone());
+    }
 }
 
 impl NavHistory {
@@ -1939,6 +2055,7 @@ impl NavHistory {
             .iter()
             .chain(borrowed_history.backward_stack.iter())
             .chain(borrowed_history.closed_stack.iter())
+            .chain(borrowed_history.edit_stack.iter())
             .for_each(|entry| {
                 if let Some(project_and_abs_path) =
                     borrowed_history.paths_by_item.get(&entry.item.id())
@@ -1970,21 +2087,50 @@ impl NavHistory {
 
     pub fn pop(&mut self, mode: NavigationMode, cx: &mut WindowContext) -> Option<NavigationEntry> {
         let mut state = self.0.lock();
-        let entry = match mode {
-            NavigationMode::Normal | NavigationMode::Disabled | NavigationMode::ClosingItem => {
-                return None
-            }
-            NavigationMode::GoingBack => &mut state.backward_stack,
-            NavigationMode::GoingForward => &mut state.forward_stack,
-            NavigationMode::ReopeningClosedItem => &mut state.closed_stack,
-        }
-        .pop_back();
+        let entry = state.stack_mut(mode)?.pop_back();
         if entry.is_some() {
             state.did_update(cx);
         }
         entry
     }
 
+    /// The timestamp of the entry that navigating in the given mode would pop next, used
+    /// to find the pane with the most recent entry when history spans all panes.
+    pub fn latest_timestamp(&self, mode: NavigationMode) -> Option<usize> {
+        let state = self.0.lock();
+        state.stack(mode)?.back().map(|entry| entry.timestamp)
+    }
+
+    /// Descriptions of the entries that navigating in the given mode would pop, most recent
+    /// first, along with their timestamps.
+    pub fn entry_descriptions(
+        &self,
+        mode: NavigationMode,
+        cx: &AppContext,
+    ) -> Vec<(usize, SharedString)> {
+        let state = self.0.lock();
+        let Some(stack) = state.stack(mode) else {
+            return Vec::new();
+        };
+        stack
+            .iter()
+            .rev()
+            .filter_map(|entry| {
+                let description = if let Some(item) = entry.item.upgrade() {
+                    item.tab_description(0, cx)
+                } else {
+                    None
+                };
+                let description = description.or_else(|| {
+                    let (project_path, _) = state.paths_by_item.get(&entry.item.id())?;
+                    let file_name = project_path.path.file_name()?;
+                    Some(file_name.to_string_lossy().into_owned().into())
+                })?;
+                Some((entry.timestamp, description))
+            })
+            .collect()
+    }
+
     pub fn push<D: 'static + Send + Any>(
         &mut self,
         data: Option<D>,
@@ -1993,7 +2139,9 @@ impl NavHistory {
     ) {
         let state = &mut *self.0.lock();
         match state.mode {
-            NavigationMode::Disabled => {}
+            // When going to the last edit, the location being left is pushed up front by the
+            // workspace, even when the edit was made in the item that's already active.
+            NavigationMode::Disabled | NavigationMode::GoingToLastEdit => {}
             NavigationMode::Normal | NavigationMode::ReopeningClosedItem => {
                 if state.backward_stack.len() >= MAX_NAVIGATION_HISTORY_LEN {
                     state.backward_stack.pop_front();
@@ -2039,6 +2187,33 @@ impl NavHistory {
         state.did_update(cx);
     }
 
+    /// Record the location of an edit. Consecutive edits in the same item only keep the
+    /// location of the latest one.
+    pub fn push_edit<D: 'static + Send + Any>(
+        &mut self,
+        data: Option<D>,
+        item: Arc<dyn WeakItemHandle>,
+    ) {
+        let state = &mut *self.0.lock();
+        if let NavigationMode::Disabled = state.mode {
+            return;
+        }
+        if state
+            .edit_stack
+            .back()
+            .map_or(false, |entry| entry.item.id() == item.id())
+        {
+            state.edit_stack.pop_back();
+        } else if state.edit_stack.len() >= MAX_NAVIGATION_HISTORY_LEN {
+            state.edit_stack.pop_front();
+        }
+        state.edit_stack.push_back(NavigationEntry {
+            item,
+            data: data.map(|data| Box::new(data) as Box<dyn Any + Send>),
+            timestamp: state.next_timestamp.fetch_add(1, Ordering::SeqCst),
+        });
+    }
+
     pub fn remove_item(&mut self, item_id: EntityId) {
         let mut state = self.0.lock();
         state.paths_by_item.remove(&item_id);
@@ -2051,6 +2226,7 @@ impl NavHistory {
         state
             .closed_stack
             .retain(|entry| entry.item.id() != item_id);
+        state.edit_stack.retain(|entry| entry.item.id() != item_id);
     }
 
     pub fn path_for_item(&self, item_id: EntityId) -> Option<(ProjectPath, Option<PathBuf>)> {
@@ -2059,6 +2235,26 @@ impl NavHistory {
 }
 
 impl NavHistoryState {
+    fn stack(&self, mode: NavigationMode) -> Option<&VecDeque<NavigationEntry>> {
+        match mode {
+            NavigationMode::Normal | NavigationMode::Disabled | NavigationMode::ClosingItem => None,
+            NavigationMode::GoingBack => Some(&self.backward_stack),
+            NavigationMode::GoingForward => Some(&self.forward_stack),
+            NavigationMode::ReopeningClosedItem => Some(&self.closed_stack),
+            NavigationMode::GoingToLastEdit => Some(&self.edit_stack),
+        }
+    }
+
+    fn stack_mut(&mut self, mode: NavigationMode) -> Option<&mut VecDeque<NavigationEntry>> {
+        match mode {
+            NavigationMode::Normal | NavigationMode::Disabled | NavigationMode::ClosingItem => None,
+            NavigationMode::GoingBack => Some(&mut self.backward_stack),
+            NavigationMode::GoingForward => Some(&mut self.forward_stack),
+            NavigationMode::ReopeningClosedItem => Some(&mut self.closed_stack),
+            NavigationMode::GoingToLastEdit => Some(&mut self.edit_stack),
+        }
+    }
+
     pub fn did_update(&self, cx: &mut WindowContext) {
         if let Some(pane) = self.pane.upgrade() {
             cx.defer(move |cx| {
@@ -2622,7 +2818,7 @@ mod tests {
 
 impl Render for DraggedTab {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let ui_font = ThemeSettings::get_global(cx).ui_font.family.clone();
+        let ui_font = ThemeSettings::for_window(cx).ui_font.family.clone();
         let label = self.item.tab_content(Some(self.detail), false, cx);
         Tab::new("")
             .selected(self.is_active)
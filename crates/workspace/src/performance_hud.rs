@@ -0,0 +1,257 @@
+use crate::TogglePerformanceHud;
+use collections::HashSet;
+use gpui::{AppContext, ClipboardItem, Model, Task};
+use lsp::LanguageServer;
+use project::Project;
+use std::{fmt::Write as _, sync::Arc, time::Duration};
+use sysinfo::{Pid, PidExt, ProcessExt, ProcessRefreshKind, System, SystemExt};
+use ui::{prelude::*, Tooltip};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An overlay showing how long the window takes to draw its frames, how quickly
+/// language servers respond to requests, and how much memory Zed and its language
+/// servers are using, so that performance reports can include concrete numbers.
+pub struct PerformanceHud {
+    project: Model<Project>,
+    system: System,
+    _refresh: Task<()>,
+}
+
+struct Section {
+    title: &'static str,
+    rows: Vec<(String, String)>,
+}
+
+impl PerformanceHud {
+    pub fn new(project: Model<Project>, cx: &mut ViewContext<Self>) -> Self {
+        let refresh = cx.spawn(|this, mut cx| async move {
+            loop {
+                let refreshed = this.update(&mut cx, |this, cx| {
+                    this.refresh_processes(cx);
+                    cx.notify();
+                });
+                if refreshed.is_err() {
+                    break;
+                }
+                cx.background_executor().timer(REFRESH_INTERVAL).await;
+            }
+        });
+        Self {
+            project,
+            system: System::new(),
+            _refresh: refresh,
+        }
+    }
+
+    fn language_servers(&self, cx: &AppContext) -> Vec<Arc<LanguageServer>> {
+        let project = self.project.read(cx);
+        let mut server_ids = HashSet::default();
+        project
+            .language_servers()
+            .map(|(server_id, _, _)| server_id)
+            .chain(project.supplementary_language_servers().map(|(id, _)| *id))
+            .filter(|server_id| server_ids.insert(*server_id))
+            .filter_map(|server_id| project.language_server_for_id(server_id))
+            .collect()
+    }
+
+    fn refresh_processes(&mut self, cx: &AppContext) {
+        let process_ids = self
+            .language_servers(cx)
+            .iter()
+            .filter_map(|server| server.process_id())
+            .chain([std::process::id()])
+            .collect::<Vec<_>>();
+        for process_id in process_ids {
+            self.system
+                .refresh_process_specifics(Pid::from_u32(process_id), ProcessRefreshKind::new());
+        }
+    }
+
+    fn process_memory(&self, process_id: u32) -> Option<u64> {
+        let process = self.system.process(Pid::from_u32(process_id))?;
+        Some(process.memory())
+    }
+
+    fn sections(&self, cx: &WindowContext) -> Vec<Section> {
+        let timings = cx.frame_timings();
+        let average = timings.average();
+        let mut frames = Section {
+            title: "Frames",
+            rows: Vec::new(),
+        };
+        if let Some((last, slowest)) = timings.last().zip(timings.slowest()) {
+            frames.rows = vec![
+                ("Last".into(), format_duration(last.total)),
+                (
+                    format!("Average of {}", timings.len()),
+                    format_duration(average.total),
+                ),
+                ("  Layout".into(), format_duration(average.layout)),
+                ("  Paint".into(), format_duration(average.paint)),
+                ("Slowest".into(), format_duration(slowest.total)),
+            ];
+        }
+
+        let language_servers = self.language_servers(cx);
+        let mut requests = Section {
+            title: "Language Server Requests",
+            rows: Vec::new(),
+        };
+        for server in &language_servers {
+            let mut latencies = server.request_latencies().into_iter().collect::<Vec<_>>();
+            let count = latencies
+                .iter()
+                .map(|(_, latency)| latency.count)
+                .sum::<usize>();
+            if count == 0 {
+                continue;
+            }
+            let max = latencies
+                .iter()
+                .map(|(_, latency)| latency.max)
+                .max()
+                .unwrap_or_default();
+            requests.rows.push((
+                server.name().to_string(),
+                format!("{count} requests, max {}", format_duration(max)),
+            ));
+
+            // List the methods the server is slowest to respond to on average.
+            latencies.sort_by_key(|(_, latency)| std::cmp::Reverse(latency.average()));
+            for (method, latency) in latencies.into_iter().take(3) {
+                requests.rows.push((
+                    format!("  {method}"),
+                    format!("avg {}", format_duration(latency.average())),
+                ));
+            }
+        }
+
+        let project = self.project.read(cx);
+        let mut memory = Section {
+            title: "Memory",
+            rows: Vec::new(),
+        };
+        if let Some(bytes) = self.process_memory(std::process::id()) {
+            memory.rows.push(("Zed".into(), format_bytes(bytes)));
+        }
+        for server in &language_servers {
+            if let Some(bytes) = server
+                .process_id()
+                .and_then(|process_id| self.process_memory(process_id))
+            {
+                memory
+                    .rows
+                    .push((server.name().to_string(), format_bytes(bytes)));
+            }
+        }
+        let buffers = project.opened_buffers();
+        let buffer_bytes = buffers
+            .iter()
+            .map(|buffer| buffer.read(cx).len() as u64)
+            .sum();
+        memory.rows.push((
+            format!("{} buffers", buffers.len()),
+            format!("{} of text", format_bytes(buffer_bytes)),
+        ));
+        let file_count = project
+            .worktrees()
+            .map(|worktree| worktree.read(cx).file_count())
+            .sum::<usize>();
+        memory.rows.push((
+            format!("{} worktrees", project.worktrees().count()),
+            format!("{file_count} files"),
+        ));
+
+        vec![frames, requests, memory]
+            .into_iter()
+            .filter(|section| !section.rows.is_empty())
+            .collect()
+    }
+
+    fn copy_report(&mut self, cx: &mut ViewContext<Self>) {
+        let mut report = String::new();
+        for section in self.sections(cx) {
+            writeln!(report, "{}:", section.title).ok();
+            for (label, value) in section.rows {
+                writeln!(report, "  {label}: {value}").ok();
+            }
+        }
+        cx.write_to_clipboard(ClipboardItem::new(report));
+    }
+}
+
+impl Render for PerformanceHud {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .elevation_3(cx)
+            .w_72()
+            .p_2()
+            .gap_2()
+            .child(
+                h_flex()
+                    .justify_between()
+                    .child(Label::new("Performance"))
+                    .child(
+                        h_flex()
+                            .child(
+                                IconButton::new("copy-performance-report", IconName::Copy)
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(|cx| Tooltip::text("Copy as Text", cx))
+                                    .on_click(cx.listener(|this, _, cx| this.copy_report(cx))),
+                            )
+                            .child(
+                                IconButton::new("close-performance-hud", IconName::Close)
+                                    .icon_size(IconSize::Small)
+                                    .tooltip(|cx| {
+                                        Tooltip::for_action("Close", &TogglePerformanceHud, cx)
+                                    })
+                                    .on_click(|_, cx| {
+                                        cx.dispatch_action(Box::new(TogglePerformanceHud))
+                                    }),
+                            ),
+                    ),
+            )
+            .children(self.sections(cx).into_iter().map(|section| {
+                v_flex()
+                    .child(
+                        Label::new(section.title)
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    )
+                    .children(section.rows.into_iter().map(|(label, value)| {
+                        h_flex()
+                            .justify_between()
+                            .gap_4()
+                            .child(Label::new(label).size(LabelSize::Small))
+                            .child(Label::new(value).size(LabelSize::Small))
+                    }))
+            }))
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.1} ms", duration.as_secs_f64() * 1000.)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const MEGABYTE: u64 = 1024 * 1024;
+    if bytes < MEGABYTE {
+        format!("{:.1} KB", bytes as f64 / 1024.)
+    } else {
+        format!("{:.1} MB", bytes as f64 / MEGABYTE as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "0.5 KB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 + 512 * 1024), "3.5 MB");
+        assert_eq!(format_duration(Duration::from_micros(16_700)), "16.7 ms");
+    }
+}
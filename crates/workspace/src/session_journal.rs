@@ -0,0 +1,499 @@
+use crate::{
+    pane_group::{Member, PaneAxis},
+    AppState, Pane, ProjectItemBuilders, SplitDirection, Workspace,
+};
+use anyhow::Result;
+use collections::HashMap;
+use fs::Fs;
+use gpui::{
+    AppContext, AsyncAppContext, Axis, EntityId, PromptLevel, Task, View, ViewContext, WindowHandle,
+};
+use project::Project;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc, time::Duration};
+use util::{paths::SESSION_JOURNAL, ResultExt};
+
+const JOURNAL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Periodically records the open windows, their pane layouts, and the contents of
+/// their unsaved buffers, so that they can be recovered if Zed quits unexpectedly.
+///
+/// The journal is removed when Zed quits normally, so finding one on startup means
+/// that the previous session crashed.
+pub struct SessionJournal {
+    crashed_session: Option<JournaledSession>,
+    _write_journal: Task<()>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct JournaledSession {
+    windows: Vec<JournaledWindow>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct JournaledWindow {
+    roots: Vec<PathBuf>,
+    center: JournaledPaneGroup,
+    unsaved_files: Vec<UnsavedFile>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum JournaledPaneGroup {
+    Group {
+        axis: JournaledAxis,
+        children: Vec<JournaledPaneGroup>,
+    },
+    Pane(JournaledPane),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+enum JournaledAxis {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct JournaledPane {
+    items: Vec<JournaledItem>,
+    active_item: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum JournaledItem {
+    File { abs_path: PathBuf },
+    Untitled { text: String },
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct UnsavedFile {
+    abs_path: PathBuf,
+    text: String,
+}
+
+pub fn init(fs: Arc<dyn Fs>, cx: &mut AppContext) {
+    let crashed_session = std::fs::read(&*SESSION_JOURNAL)
+        .ok()
+        .and_then(|journal| serde_json::from_slice::<JournaledSession>(&journal).log_err())
+        .filter(|session| !session.windows.is_empty());
+
+    let write_journal = cx.spawn(|cx| async move {
+        let mut last_journal = None;
+        loop {
+            cx.background_executor().timer(JOURNAL_INTERVAL).await;
+            let Ok(session) = cx.update(|cx| JournaledSession::capture(cx)) else {
+                break;
+            };
+            let Some(journal) = serde_json::to_string(&session).log_err() else {
+                continue;
+            };
+            if last_journal.as_ref() != Some(&journal) {
+                fs.atomic_write(SESSION_JOURNAL.clone(), journal.clone())
+                    .await
+                    .log_err();
+                last_journal = Some(journal);
+            }
+        }
+    });
+
+    cx.on_app_quit(|_| {
+        std::fs::remove_file(&*SESSION_JOURNAL).ok();
+        async {}
+    })
+    .detach();
+
+    cx.set_global(SessionJournal {
+        crashed_session,
+        _write_journal: write_journal,
+    });
+}
+
+impl SessionJournal {
+    /// The session that was journaled before Zed last quit unexpectedly, if it did.
+    pub fn take_crashed_session(cx: &mut AppContext) -> Option<JournaledSession> {
+        if !cx.has_global::<Self>() {
+            return None;
+        }
+        cx.global_mut::<Self>().crashed_session.take()
+    }
+}
+
+impl JournaledSession {
+    fn capture(cx: &AppContext) -> Self {
+        Self {
+            windows: cx
+                .windows()
+                .into_iter()
+                .filter_map(|window| window.downcast::<Workspace>())
+                .filter_map(|window| window.read(cx).ok())
+                .filter_map(|workspace| JournaledWindow::capture(workspace, cx))
+                .collect(),
+        }
+    }
+
+    fn unsaved_buffer_count(&self) -> usize {
+        fn untitled_items(group: &JournaledPaneGroup) -> usize {
+            match group {
+                JournaledPaneGroup::Group { children, .. } => {
+                    children.iter().map(untitled_items).sum()
+                }
+                JournaledPaneGroup::Pane(pane) => pane
+                    .items
+                    .iter()
+                    .filter(|item| matches!(item, JournaledItem::Untitled { .. }))
+                    .count(),
+            }
+        }
+
+        self.windows
+            .iter()
+            .map(|window| window.unsaved_files.len() + untitled_items(&window.center))
+            .sum()
+    }
+}
+
+impl JournaledWindow {
+    fn capture(workspace: &Workspace, cx: &AppContext) -> Option<Self> {
+        let project = workspace.project().read(cx);
+        if !project.is_local() {
+            return None;
+        }
+
+        let roots = project
+            .visible_worktrees(cx)
+            .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+            .collect();
+
+        let mut unsaved_files = Vec::new();
+        let mut untitled_texts = HashMap::default();
+        for buffer_handle in project.opened_buffers() {
+            let buffer = buffer_handle.read(cx);
+            match buffer.file() {
+                Some(file) => {
+                    if let Some(file) = file.as_local().filter(|_| buffer.is_dirty()) {
+                        unsaved_files.push(UnsavedFile {
+                            abs_path: file.abs_path(cx),
+                            text: buffer.text(),
+                        });
+                    }
+                }
+                None => {
+                    if buffer.len() > 0 {
+                        untitled_texts.insert(buffer_handle.entity_id(), buffer.text());
+                    }
+                }
+            }
+        }
+
+        Some(Self {
+            roots,
+            center: JournaledPaneGroup::capture(
+                &workspace.center.root,
+                project,
+                &untitled_texts,
+                cx,
+            ),
+            unsaved_files,
+        })
+    }
+}
+
+impl JournaledPaneGroup {
+    fn capture(
+        member: &Member,
+        project: &Project,
+        untitled_texts: &HashMap<EntityId, String>,
+        cx: &AppContext,
+    ) -> Self {
+        match member {
+            Member::Axis(PaneAxis { axis, members, .. }) => Self::Group {
+                axis: match axis {
+                    Axis::Horizontal => JournaledAxis::Horizontal,
+                    Axis::Vertical => JournaledAxis::Vertical,
+                },
+                children: members
+                    .iter()
+                    .map(|member| Self::capture(member, project, untitled_texts, cx))
+                    .collect(),
+            },
+            Member::Pane(pane) => {
+                let pane = pane.read(cx);
+                let mut items = Vec::new();
+                let mut active_item = 0;
+                for (ix, item) in pane.items().enumerate() {
+                    let journaled_item = if let Some(project_path) = item.project_path(cx) {
+                        project
+                            .absolute_path(&project_path, cx)
+                            .map(|abs_path| JournaledItem::File { abs_path })
+                    } else {
+                        match item.project_item_model_ids(cx).as_slice() {
+                            [model_id] => untitled_texts
+                                .get(model_id)
+                                .map(|text| JournaledItem::Untitled { text: text.clone() }),
+                            _ => None,
+                        }
+                    };
+                    if let Some(journaled_item) = journaled_item {
+                        if ix == pane.active_item_index() {
+                            active_item = items.len();
+                        }
+                        items.push(journaled_item);
+                    }
+                }
+                Self::Pane(JournaledPane { items, active_item })
+            }
+        }
+    }
+}
+
+/// Opens an empty window to ask whether to restore the session that was journaled
+/// before Zed quit unexpectedly, and restores it if so. When the session isn't
+/// restored, the empty window is returned so that it can be reused.
+pub async fn offer_recovery(
+    session: JournaledSession,
+    app_state: Arc<AppState>,
+    mut cx: AsyncAppContext,
+) -> Result<Option<WindowHandle<Workspace>>> {
+    let (window, _) = cx
+        .update(|cx| Workspace::new_local(Vec::new(), app_state.clone(), None, cx))?
+        .await?;
+
+    let window_count = session.windows.len();
+    let unsaved_buffer_count = session.unsaved_buffer_count();
+    let mut detail = format!(
+        "Restore the {window_count} {} that {} open",
+        if window_count == 1 {
+            "window"
+        } else {
+            "windows"
+        },
+        if window_count == 1 { "was" } else { "were" },
+    );
+    if unsaved_buffer_count > 0 {
+        detail.push_str(&format!(
+            ", with {unsaved_buffer_count} unsaved {}",
+            if unsaved_buffer_count == 1 {
+                "file"
+            } else {
+                "files"
+            }
+        ));
+    }
+    detail.push('?');
+
+    let answer = window.update(&mut cx, |_, cx| {
+        cx.prompt(
+            PromptLevel::Warning,
+            "Zed quit unexpectedly",
+            Some(&detail),
+            &["Restore", "Don't Restore"],
+        )
+    })?;
+    if answer.await != Ok(0) {
+        return Ok(Some(window));
+    }
+
+    let mut requesting_window = Some(window);
+    for journaled_window in session.windows {
+        let (workspace, _) = cx
+            .update(|cx| {
+                Workspace::new_local(
+                    journaled_window.roots.clone(),
+                    app_state.clone(),
+                    requesting_window.take(),
+                    cx,
+                )
+            })?
+            .await?;
+        restore_window(workspace, journaled_window, &mut cx)
+            .await
+            .log_err();
+    }
+    Ok(None)
+}
+
+async fn restore_window(
+    window: WindowHandle<Workspace>,
+    journaled_window: JournaledWindow,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let project = window.update(cx, |workspace, _| workspace.project().clone())?;
+    for unsaved_file in journaled_window.unsaved_files {
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer(&unsaved_file.abs_path, cx)
+            })?
+            .await;
+        if let Some(buffer) = buffer.log_err() {
+            buffer.update(cx, |buffer, cx| {
+                buffer.set_text(unsaved_file.text, cx);
+            })?;
+        }
+    }
+
+    // Replace the layout that was restored from the workspace database, which may
+    // be older than the journal.
+    window.update(cx, |workspace, cx| {
+        for pane in workspace.panes.clone() {
+            pane.update(cx, |pane, cx| {
+                while pane.items_len() > 0 {
+                    pane.remove_item(0, false, cx);
+                }
+            });
+        }
+    })?;
+    let panes = window.update(cx, |workspace, cx| {
+        let mut panes = Vec::new();
+        let center_pane = workspace.active_pane().clone();
+        split_panes(
+            workspace,
+            journaled_window.center,
+            center_pane,
+            &mut panes,
+            cx,
+        );
+        panes
+    })?;
+
+    for (pane, journaled_pane) in panes {
+        for item in journaled_pane.items {
+            match item {
+                JournaledItem::File { abs_path } => {
+                    let open_item = window.update(cx, |workspace, cx| {
+                        let project_path = workspace
+                            .project()
+                            .read(cx)
+                            .find_local_worktree(&abs_path, cx)
+                            .map(|(worktree, path)| (worktree.read(cx).id(), path))?;
+                        Some(workspace.open_path(project_path, Some(pane.downgrade()), false, cx))
+                    })?;
+                    if let Some(open_item) = open_item {
+                        open_item.await.log_err();
+                    }
+                }
+                JournaledItem::Untitled { text } => {
+                    let buffer = project
+                        .update(cx, |project, cx| project.create_buffer(&text, None, cx))??
+                        .into_any();
+                    window.update(cx, |_, cx| {
+                        let Some(build_item) = cx
+                            .default_global::<ProjectItemBuilders>()
+                            .get(&buffer.entity_type())
+                            .copied()
+                        else {
+                            return;
+                        };
+                        pane.update(cx, |pane, cx| {
+                            let item = build_item(project.clone(), buffer, cx);
+                            pane.add_item(item, false, false, None, cx);
+                        });
+                    })?;
+                }
+            }
+        }
+        window.update(cx, |_, cx| {
+            pane.update(cx, |pane, cx| {
+                if journaled_pane.active_item < pane.items_len() {
+                    pane.activate_item(journaled_pane.active_item, false, false, cx);
+                }
+            })
+        })?;
+    }
+    Ok(())
+}
+
+/// Splits the pane to match the journaled layout, collecting each resulting pane
+/// along with the items to open in it.
+fn split_panes(
+    workspace: &mut Workspace,
+    group: JournaledPaneGroup,
+    pane: View<Pane>,
+    panes: &mut Vec<(View<Pane>, JournaledPane)>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    match group {
+        JournaledPaneGroup::Pane(journaled_pane) => panes.push((pane, journaled_pane)),
+        JournaledPaneGroup::Group { axis, children } => {
+            let direction = match axis {
+                JournaledAxis::Horizontal => SplitDirection::Right,
+                JournaledAxis::Vertical => SplitDirection::Down,
+            };
+            let mut child_panes = vec![pane];
+            for _ in 1..children.len() {
+                let last_pane = child_panes.last().unwrap().clone();
+                child_panes.push(workspace.split_pane(last_pane, direction, cx));
+            }
+            for (child, pane) in children.into_iter().zip(child_panes) {
+                split_panes(workspace, child, pane, panes, cx);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::init_test;
+    use fs::FakeFs;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_capture_session(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree("/root", json!({ "a.txt": "a", "b.txt": "b" }))
+            .await;
+        let project = Project::test(fs, ["/root".as_ref()], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project.clone(), cx));
+
+        let buffer = project
+            .update(cx, |project, cx| {
+                project.open_local_buffer("/root/a.txt", cx)
+            })
+            .await
+            .unwrap();
+        buffer.update(cx, |buffer, cx| buffer.edit([(0..0, "unsaved ")], None, cx));
+        workspace.update(cx, |workspace, cx| {
+            let pane = workspace.active_pane().clone();
+            let right_pane = workspace.split_pane(pane, SplitDirection::Right, cx);
+            workspace.split_pane(right_pane, SplitDirection::Down, cx);
+        });
+
+        let session = cx.update(|cx| JournaledSession::capture(cx));
+        let empty_pane = || {
+            JournaledPaneGroup::Pane(JournaledPane {
+                items: Vec::new(),
+                active_item: 0,
+            })
+        };
+        assert_eq!(
+            session,
+            JournaledSession {
+                windows: vec![JournaledWindow {
+                    roots: vec![PathBuf::from("/root")],
+                    center: JournaledPaneGroup::Group {
+                        axis: JournaledAxis::Horizontal,
+                        children: vec![
+                            empty_pane(),
+                            JournaledPaneGroup::Group {
+                                axis: JournaledAxis::Vertical,
+                                children: vec![empty_pane(), empty_pane()],
+                            },
+                        ],
+                    },
+                    unsaved_files: vec![UnsavedFile {
+                        abs_path: PathBuf::from("/root/a.txt"),
+                        text: "unsaved a".into(),
+                    }],
+                }],
+            }
+        );
+        assert_eq!(session.unsaved_buffer_count(), 1);
+
+        let journal = serde_json::to_string(&session).unwrap();
+        assert_eq!(
+            serde_json::from_str::<JournaledSession>(&journal).unwrap(),
+            session
+        );
+    }
+}
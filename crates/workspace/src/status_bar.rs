@@ -1,13 +1,20 @@
 use crate::{ItemHandle, Pane};
+use anyhow::Result;
 use gpui::{
-    AnyView, IntoElement, ParentElement, Render, Styled, Subscription, View, ViewContext,
-    WindowContext,
+    AnyView, AppContext, IntoElement, ParentElement, Render, Styled, Subscription, View,
+    ViewContext, WindowContext,
 };
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
 use std::any::TypeId;
 use ui::{h_flex, prelude::*};
 use util::ResultExt;
 
 pub trait StatusItemView: Render {
+    /// The identifier that the `status_bar` settings refer to this item by.
+    fn status_item_id(&self) -> &'static str;
+
     fn set_active_pane_item(
         &mut self,
         active_pane_item: Option<&dyn crate::ItemHandle>,
@@ -17,6 +24,7 @@ pub trait StatusItemView: Render {
 
 trait StatusItemViewHandle: Send {
     fn to_any(&self) -> AnyView;
+    fn status_item_id(&self, cx: &AppContext) -> &'static str;
     fn set_active_pane_item(
         &self,
         active_pane_item: Option<&dyn ItemHandle>,
@@ -25,6 +33,81 @@ trait StatusItemViewHandle: Send {
     fn item_type(&self) -> TypeId;
 }
 
+#[derive(Deserialize)]
+pub struct StatusBarSettings {
+    pub hidden_items: Vec<String>,
+    pub left_items: Vec<String>,
+    pub right_items: Vec<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StatusBarSettingsContent {
+    /// The identifiers of the status bar items to hide.
+    ///
+    /// Default: []
+    pub hidden_items: Option<Vec<String>>,
+    /// The identifiers of the items to show at the start of the left side of the status bar,
+    /// in order. Other items on the left side follow them in their usual order.
+    ///
+    /// Default: []
+    pub left_items: Option<Vec<String>>,
+    /// The identifiers of the items to show at the start of the right side of the status bar,
+    /// in order. Other items on the right side follow them in their usual order.
+    ///
+    /// Default: []
+    pub right_items: Option<Vec<String>>,
+}
+
+impl Settings for StatusBarSettings {
+    const KEY: Option<&'static str> = Some("status_bar");
+
+    type FileContent = StatusBarSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self> {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+impl StatusBarSettings {
+    /// Arranges the items of each side of the status bar, given by their identifiers in
+    /// their usual order, as these settings ask.
+    fn arrange<T: Clone>(
+        &self,
+        left_items: &[(&str, T)],
+        right_items: &[(&str, T)],
+    ) -> (Vec<T>, Vec<T>) {
+        let all_items = || left_items.iter().chain(right_items);
+        let is_listed = |id: &str| {
+            self.left_items.iter().any(|listed| listed == id)
+                || self.right_items.iter().any(|listed| listed == id)
+        };
+        let arrange_side = |listed_ids: Vec<&String>, unlisted_items: &[(&str, T)]| {
+            listed_ids
+                .into_iter()
+                .flat_map(|listed_id| all_items().filter(move |(id, _)| *id == listed_id.as_str()))
+                .chain(unlisted_items.iter().filter(|(id, _)| !is_listed(*id)))
+                .filter(|(id, _)| !self.hidden_items.iter().any(|hidden| hidden == *id))
+                .map(|(_, item)| item.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let left_ids = self.left_items.iter().collect::<Vec<_>>();
+        let right_ids = self
+            .right_items
+            .iter()
+            .filter(|id| !self.left_items.contains(id))
+            .collect::<Vec<_>>();
+        (
+            arrange_side(left_ids, left_items),
+            arrange_side(right_ids, right_items),
+        )
+    }
+}
+
 pub struct StatusBar {
     left_items: Vec<Box<dyn StatusItemViewHandle>>,
     right_items: Vec<Box<dyn StatusItemViewHandle>>,
@@ -34,6 +117,7 @@ pub struct StatusBar {
 
 impl Render for StatusBar {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let (left_tools, right_tools) = self.render_tools(cx);
         h_flex()
             .w_full()
             .justify_between()
@@ -42,23 +126,30 @@ impl Render for StatusBar {
             .px_1()
             .h_8()
             .bg(cx.theme().colors().status_bar_background)
-            .child(self.render_left_tools(cx))
-            .child(self.render_right_tools(cx))
+            .child(left_tools)
+            .child(right_tools)
     }
 }
 
 impl StatusBar {
-    fn render_left_tools(&self, _: &mut ViewContext<Self>) -> impl IntoElement {
-        h_flex()
-            .gap_2()
-            .overflow_x_hidden()
-            .children(self.left_items.iter().map(|item| item.to_any()))
-    }
+    fn render_tools(&self, cx: &mut ViewContext<Self>) -> (impl IntoElement, impl IntoElement) {
+        let item_with_id =
+            |item: &Box<dyn StatusItemViewHandle>| (item.status_item_id(cx), item.to_any());
+        let left_items = self.left_items.iter().map(item_with_id).collect::<Vec<_>>();
+        // Right items are shown from right to left in the order they were added.
+        let right_items = self
+            .right_items
+            .iter()
+            .rev()
+            .map(item_with_id)
+            .collect::<Vec<_>>();
+        let (left_items, right_items) =
+            StatusBarSettings::get_global(cx).arrange(&left_items, &right_items);
 
-    fn render_right_tools(&self, _: &mut ViewContext<Self>) -> impl IntoElement {
-        h_flex()
-            .gap_2()
-            .children(self.right_items.iter().rev().map(|item| item.to_any()))
+        (
+            h_flex().gap_2().overflow_x_hidden().children(left_items),
+            h_flex().gap_2().children(right_items),
+        )
     }
 }
 
@@ -170,6 +261,10 @@ impl<T: StatusItemView> StatusItemViewHandle for View<T> {
         self.clone().into()
     }
 
+    fn status_item_id(&self, cx: &AppContext) -> &'static str {
+        self.read(cx).status_item_id()
+    }
+
     fn set_active_pane_item(
         &self,
         active_pane_item: Option<&dyn ItemHandle>,
@@ -190,3 +285,42 @@ impl From<&dyn StatusItemViewHandle> for AnyView {
         val.to_any().clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arrange_status_items() {
+        let left_items = [("diagnostics", 1), ("activity_indicator", 2)];
+        let right_items = [("cursor_position", 3), ("language", 4), ("copilot", 5)];
+        let settings = |hidden: &[&str], left: &[&str], right: &[&str]| StatusBarSettings {
+            hidden_items: hidden.iter().map(|id| id.to_string()).collect(),
+            left_items: left.iter().map(|id| id.to_string()).collect(),
+            right_items: right.iter().map(|id| id.to_string()).collect(),
+        };
+
+        assert_eq!(
+            settings(&[], &[], &[]).arrange(&left_items, &right_items),
+            (vec![1, 2], vec![3, 4, 5])
+        );
+        assert_eq!(
+            settings(&["copilot", "diagnostics"], &[], &[]).arrange(&left_items, &right_items),
+            (vec![2], vec![3, 4])
+        );
+        assert_eq!(
+            settings(&[], &["activity_indicator"], &["copilot", "unknown"])
+                .arrange(&left_items, &right_items),
+            (vec![2, 1], vec![5, 3, 4])
+        );
+        assert_eq!(
+            settings(
+                &["language"],
+                &["cursor_position"],
+                &["diagnostics", "cursor_position"]
+            )
+            .arrange(&left_items, &right_items),
+            (vec![3, 2], vec![1, 5])
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use crate::Workspace;
+use anyhow::Result;
+use gpui::{AppContext, Keystroke, Task};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::time::Duration;
+use ui::{prelude::*, KeyBinding};
+
+#[derive(Deserialize)]
+pub struct WhichKeySettings {
+    pub enabled: bool,
+    pub delay_ms: u64,
+    pub timeout_ms: u64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WhichKeySettingsContent {
+    /// Whether to show a popup listing the keystrokes that can follow the ones typed so far
+    /// in a multi-stroke binding, along with the actions they run.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// How long to wait after a keystroke before showing the popup, in milliseconds.
+    ///
+    /// Default: 500
+    pub delay_ms: Option<u64>,
+    /// How long to wait for the next keystroke of a multi-stroke binding before the ones
+    /// typed so far are handled on their own, in milliseconds.
+    ///
+    /// Default: 1000
+    pub timeout_ms: Option<u64>,
+}
+
+impl Settings for WhichKeySettings {
+    const KEY: Option<&'static str> = Some("which_key");
+
+    type FileContent = WhichKeySettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self> {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+pub fn init(cx: &mut AppContext) {
+    update_keystroke_timeout(cx);
+    cx.observe_global::<SettingsStore>(update_keystroke_timeout)
+        .detach();
+}
+
+fn update_keystroke_timeout(cx: &mut AppContext) {
+    let timeout = Duration::from_millis(WhichKeySettings::get_global(cx).timeout_ms);
+    cx.set_keystroke_timeout(timeout);
+}
+
+/// Tracks the keystrokes pending in the workspace's window, showing the bindings they
+/// could go on to complete once they've been pending for long enough.
+#[derive(Default)]
+pub(crate) struct WhichKey {
+    pending_keystrokes: Vec<Keystroke>,
+    visible: bool,
+    _show: Option<Task<()>>,
+}
+
+impl WhichKey {
+    pub(crate) fn render(&mut self, cx: &mut ViewContext<Workspace>) -> Option<impl IntoElement> {
+        let settings = WhichKeySettings::get_global(cx);
+        let (enabled, delay) = (settings.enabled, Duration::from_millis(settings.delay_ms));
+        let Some(pending_keystrokes) = cx.pending_keystrokes().filter(|_| enabled) else {
+            *self = Self::default();
+            return None;
+        };
+
+        if self.pending_keystrokes != pending_keystrokes {
+            self.pending_keystrokes = pending_keystrokes.to_vec();
+            // Once shown, keep the popup up as further keystrokes narrow down the bindings.
+            if !self.visible {
+                self._show = Some(cx.spawn(|workspace, mut cx| async move {
+                    cx.background_executor().timer(delay).await;
+                    workspace
+                        .update(&mut cx, |workspace, cx| {
+                            workspace.which_key.visible = true;
+                            cx.notify();
+                        })
+                        .ok();
+                }));
+            }
+        }
+        if !self.visible {
+            return None;
+        }
+
+        let mut continuations = cx
+            .pending_bindings()
+            .into_iter()
+            .filter_map(|binding| {
+                let remaining_keystrokes = binding.keystrokes()[self.pending_keystrokes.len()..]
+                    .iter()
+                    .map(Keystroke::unparse)
+                    .collect::<Vec<_>>();
+                let action_name = binding.action().name().to_string();
+                let key_binding = gpui::KeyBinding::load(
+                    &remaining_keystrokes.join(" "),
+                    binding.action().boxed_clone(),
+                    None,
+                )
+                .ok()?;
+                Some((remaining_keystrokes, action_name, key_binding))
+            })
+            .collect::<Vec<_>>();
+        if continuations.is_empty() {
+            return None;
+        }
+        continuations.sort_by(|a, b| a.0.cmp(&b.0));
+
+        Some(
+            div().absolute().z_index(100).bottom_3().left_3().child(
+                v_flex()
+                    .elevation_3(cx)
+                    .p_2()
+                    .gap_1()
+                    .max_h_96()
+                    .overflow_hidden()
+                    .child(
+                        h_flex().gap_1().children(
+                            self.pending_keystrokes.iter().map(|keystroke| {
+                                Label::new(keystroke.unparse()).color(Color::Muted)
+                            }),
+                        ),
+                    )
+                    .children(
+                        continuations
+                            .into_iter()
+                            .map(|(_, action_name, key_binding)| {
+                                h_flex()
+                                    .gap_4()
+                                    .justify_between()
+                                    .child(KeyBinding::new(key_binding))
+                                    .child(Label::new(action_name))
+                            }),
+                    ),
+            ),
+        )
+    }
+}
@@ -4,20 +4,25 @@ mod modal_layer;
 pub mod notifications;
 pub mod pane;
 pub mod pane_group;
+mod performance_hud;
 mod persistence;
 pub mod searchable;
+pub mod session_journal;
 pub mod shared_screen;
 mod status_bar;
 mod toolbar;
+mod which_key;
 mod workspace_settings;
 
 use anyhow::{anyhow, Context as _, Result};
 use call::ActiveCall;
 use client::{
     proto::{self, ErrorCode, PeerId},
-    Client, ErrorExt, Status, TypedEnvelope, UserStore,
+    Client, ErrorExt, OfflineModeSetting, Status, TypedEnvelope, UserStore,
+};
+use collections::{
+    hash_map, CommandArgument, CommandArgumentKind, CommandPaletteArguments, HashMap, HashSet,
 };
-use collections::{hash_map, HashMap, HashSet};
 use dock::{Dock, DockPosition, Panel, PanelButtons, PanelHandle};
 use futures::{
     channel::{mpsc, oneshot},
@@ -42,6 +47,7 @@ use node_runtime::NodeRuntime;
 use notifications::{simple_message_notification::MessageNotification, NotificationHandle};
 pub use pane::*;
 pub use pane_group::*;
+use performance_hud::PerformanceHud;
 use persistence::{model::SerializedWorkspace, SerializedWindowsBounds, DB};
 pub use persistence::{
     model::{ItemId, WorkspaceLocation},
@@ -50,10 +56,10 @@ pub use persistence::{
 use postage::stream::Stream;
 use project::{Project, ProjectEntryId, ProjectPath, Worktree, WorktreeId};
 use serde::Deserialize;
-use settings::Settings;
+use settings::{update_settings_file, Settings, SettingsStore};
 use shared_screen::SharedScreen;
 use status_bar::StatusBar;
-pub use status_bar::StatusItemView;
+pub use status_bar::{StatusBarSettings, StatusItemView};
 use std::{
     any::TypeId,
     borrow::Cow,
@@ -62,12 +68,14 @@ use std::{
     sync::{atomic::AtomicUsize, Arc},
     time::Duration,
 };
-use theme::{ActiveTheme, ThemeSettings};
+use theme::{ActiveTheme, SystemAppearance, ThemeSettings};
 pub use toolbar::{Toolbar, ToolbarItemEvent, ToolbarItemLocation, ToolbarItemView};
 pub use ui;
 use ui::Label;
 use util::ResultExt;
 use uuid::Uuid;
+use which_key::WhichKey;
+pub use which_key::WhichKeySettings;
 pub use workspace_settings::{AutosaveSetting, WorkspaceSettings};
 
 use crate::persistence::{
@@ -93,14 +101,21 @@ actions!(
     workspace,
     [
         Open,
+        OpenScratchBuffer,
         NewFile,
+        NewScratchBuffer,
         NewWindow,
         CloseWindow,
         CloseInactiveTabsAndPanes,
+        CloseActivePane,
+        CloseInactivePanes,
         AddFolderToProject,
         Unfollow,
         SaveAs,
         ReloadActiveItem,
+        ReviewProjectTrust,
+        ToggleOfflineMode,
+        TogglePerformanceHud,
         ActivatePreviousPane,
         ActivateNextPane,
         FollowNextCollaborator,
@@ -223,11 +238,42 @@ pub type WorkspaceId = i64;
 pub fn init_settings(cx: &mut AppContext) {
     WorkspaceSettings::register(cx);
     ItemSettings::register(cx);
+    StatusBarSettings::register(cx);
+    WhichKeySettings::register(cx);
+}
+
+fn register_command_palette_arguments(cx: &mut AppContext) {
+    const DIRECTIONS: &[&str] = &["Up", "Down", "Left", "Right"];
+    let number = CommandArgument {
+        field: None,
+        kind: CommandArgumentKind::Number,
+    };
+    let direction = CommandArgument {
+        field: None,
+        kind: CommandArgumentKind::Enum(DIRECTIONS),
+    };
+    let arguments = &mut cx
+        .default_global::<CommandPaletteArguments>()
+        .arguments_by_action_name;
+    arguments.insert(ActivateItem::debug_name(), number.clone());
+    arguments.insert(ActivatePane::debug_name(), number);
+    arguments.insert(ActivatePaneInDirection::debug_name(), direction.clone());
+    arguments.insert(SwapPaneInDirection::debug_name(), direction.clone());
+    arguments.insert(NewFileInDirection::debug_name(), direction);
+    arguments.insert(
+        OpenTerminal::debug_name(),
+        CommandArgument {
+            field: Some("working_directory"),
+            kind: CommandArgumentKind::File,
+        },
+    );
 }
 
 pub fn init(app_state: Arc<AppState>, cx: &mut AppContext) {
     init_settings(cx);
     notifications::init(cx);
+    which_key::init(cx);
+    register_command_palette_arguments(cx);
 
     cx.on_action(Workspace::close_global);
     cx.on_action(restart);
@@ -348,7 +394,6 @@ impl AppState {
     #[cfg(any(test, feature = "test-support"))]
     pub fn test(cx: &mut AppContext) -> Arc<Self> {
         use node_runtime::FakeNodeRuntime;
-        use settings::SettingsStore;
 
         if !cx.has_global::<SettingsStore>() {
             let settings_store = SettingsStore::test(cx);
@@ -455,6 +500,8 @@ pub struct Workspace {
     modal_layer: View<ModalLayer>,
     titlebar_item: Option<AnyView>,
     notifications: Vec<(TypeId, usize, Box<dyn NotificationHandle>)>,
+    which_key: WhichKey,
+    performance_hud: Option<View<PerformanceHud>>,
     project: Model<Project>,
     follower_states: HashMap<View<Pane>, FollowerState>,
     last_leaders_by_pane: HashMap<WeakView<Pane>, PeerId>,
@@ -468,6 +515,7 @@ pub struct Workspace {
     _observe_current_user: Task<Result<()>>,
     _schedule_serialize: Option<Task<()>>,
     pane_history_timestamp: Arc<AtomicUsize>,
+    history_navigation_pane: Option<EntityId>,
     bounds: Bounds<Pixels>,
 }
 
@@ -506,6 +554,7 @@ impl Workspace {
 
                 project::Event::WorktreeRemoved(_) | project::Event::WorktreeAdded => {
                     this.update_window_title(cx);
+                    this.update_window_settings_location(cx);
                     this.serialize_workspace(cx);
                 }
 
@@ -535,6 +584,10 @@ impl Workspace {
                     cx.new_view(|_| MessageNotification::new(message.clone()))
                 }),
 
+                project::Event::WorktreeTrustRequested(worktree_id) => {
+                    this.prompt_for_worktree_trust(*worktree_id, cx);
+                }
+
                 _ => {}
             }
             cx.notify()
@@ -647,6 +700,10 @@ impl Workspace {
                 }
                 cx.notify();
             }),
+            cx.observe_window_appearance(|_, cx| {
+                SystemAppearance::set_global(cx.appearance().into(), cx);
+            }),
+            cx.observe_global::<SettingsStore>(|this, cx| this.update_ui_scale(cx)),
             cx.observe(&left_dock, |this, _, cx| {
                 this.serialize_workspace(cx);
                 cx.notify();
@@ -660,6 +717,7 @@ impl Workspace {
                 cx.notify();
             }),
             cx.on_release(|this, window, cx| {
+                theme::set_window_settings_location(window, None, cx);
                 this.app_state.workspace_store.update(cx, |store, _| {
                     let window = window.downcast::<Self>().unwrap();
                     store.workspaces.remove(&window);
@@ -667,8 +725,11 @@ impl Workspace {
             }),
         ];
 
+        SystemAppearance::set_global(cx.appearance().into(), cx);
+        cx.set_ui_scale(ThemeSettings::for_window(cx).ui_scale);
         cx.defer(|this, cx| {
             this.update_window_title(cx);
+            this.update_window_settings_location(cx);
         });
         Workspace {
             weak_self: weak_handle.clone(),
@@ -684,6 +745,8 @@ impl Workspace {
             modal_layer,
             titlebar_item: None,
             notifications: Default::default(),
+            which_key: WhichKey::default(),
+            performance_hud: None,
             left_dock,
             bottom_dock,
             right_dock,
@@ -700,6 +763,7 @@ impl Workspace {
             leader_updates_tx,
             _subscriptions: subscriptions,
             pane_history_timestamp,
+            history_navigation_pane: None,
             workspace_actions: Default::default(),
             // This data will be incorrect, but it will be overwritten by the time it needs to be used.
             bounds: Default::default(),
@@ -937,6 +1001,68 @@ impl Workspace {
         pane: WeakView<Pane>,
         mode: NavigationMode,
         cx: &mut ViewContext<Workspace>,
+    ) -> Task<Result<()>> {
+        let global = match mode {
+            NavigationMode::GoingBack | NavigationMode::GoingForward => {
+                WorkspaceSettings::get_global(cx).global_navigation_history
+            }
+            NavigationMode::GoingToLastEdit => true,
+            _ => false,
+        };
+        if !global {
+            return self.navigate_pane_history(pane, mode, cx);
+        }
+
+        // Navigate in whichever pane has the most recent entry, remembering where we're
+        // navigating from even if it's in another pane.
+        let Some(pane) = self
+            .panes
+            .iter()
+            .filter_map(|pane| Some((pane, pane.read(cx).nav_history().latest_timestamp(mode)?)))
+            .max_by_key(|(_, timestamp)| *timestamp)
+            .map(|(pane, _)| pane.clone())
+        else {
+            return Task::ready(Ok(()));
+        };
+        let departure_mode = match mode {
+            NavigationMode::GoingToLastEdit => Some(NavigationMode::Normal),
+            _ if pane != self.active_pane => Some(mode),
+            _ => None,
+        };
+        if let Some(departure_mode) = departure_mode {
+            if pane != self.active_pane {
+                self.history_navigation_pane = Some(pane.entity_id());
+            }
+            Self::push_active_item_to_history(&self.active_pane, departure_mode, cx);
+        }
+        self.navigate_pane_history(pane.downgrade(), mode, cx)
+    }
+
+    /// Record the location of the given pane's active item in its navigation history, as if
+    /// it was being navigated away from in the given mode.
+    fn push_active_item_to_history(
+        pane: &View<Pane>,
+        mode: NavigationMode,
+        cx: &mut WindowContext,
+    ) {
+        pane.update(cx, |pane, cx| {
+            let prev_mode = pane.nav_history().mode();
+            if let NavigationMode::Disabled = prev_mode {
+                return;
+            }
+            if let Some(item) = pane.active_item() {
+                pane.nav_history_mut().set_mode(mode);
+                item.deactivated(cx);
+                pane.nav_history_mut().set_mode(prev_mode);
+            }
+        });
+    }
+
+    fn navigate_pane_history(
+        &mut self,
+        pane: WeakView<Pane>,
+        mode: NavigationMode,
+        cx: &mut ViewContext<Workspace>,
     ) -> Task<Result<()>> {
         let to_load = if let Some(pane) = pane.upgrade() {
             pane.update(cx, |pane, cx| {
@@ -1004,7 +1130,7 @@ impl Workspace {
                 if !navigated {
                     workspace
                         .update(&mut cx, |workspace, cx| {
-                            Self::navigate_history(workspace, pane, mode, cx)
+                            Self::navigate_pane_history(workspace, pane, mode, cx)
                         })?
                         .await?;
                 }
@@ -1032,6 +1158,83 @@ impl Workspace {
         self.navigate_history(pane, NavigationMode::GoingForward, cx)
     }
 
+    /// Navigate through history until the entry with the given timestamp has been visited,
+    /// as when picking one from the menu of a pane's navigation buttons. Entries that can't be
+    /// navigated to, such as those of closed items that weren't files, are passed over.
+    pub fn navigate_history_to(
+        &mut self,
+        pane: WeakView<Pane>,
+        mode: NavigationMode,
+        timestamp: usize,
+        cx: &mut ViewContext<Workspace>,
+    ) -> Task<Result<()>> {
+        cx.spawn(|workspace, mut cx| async move {
+            let mut previous_latest = None;
+            loop {
+                let latest = workspace.update(&mut cx, |workspace, cx| {
+                    let pane = pane.upgrade()?;
+                    workspace.latest_history_timestamp(&pane, mode, cx)
+                })?;
+                if latest.map_or(true, |latest| latest < timestamp) || latest == previous_latest {
+                    break;
+                }
+                previous_latest = latest;
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.navigate_history(pane.clone(), mode, cx)
+                    })?
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// The timestamp of the entry that navigating the given pane's history in the given mode
+    /// would visit next, which may be in another pane when navigation history is global.
+    fn latest_history_timestamp(
+        &self,
+        pane: &View<Pane>,
+        mode: NavigationMode,
+        cx: &AppContext,
+    ) -> Option<usize> {
+        if !WorkspaceSettings::get_global(cx).global_navigation_history {
+            return pane.read(cx).nav_history().latest_timestamp(mode);
+        }
+
+        self.panes
+            .iter()
+            .filter_map(|pane| pane.read(cx).nav_history().latest_timestamp(mode))
+            .max()
+    }
+
+    /// Descriptions of the entries that navigating the given pane's history in the given
+    /// mode would visit, most recent first, along with their timestamps. When navigation
+    /// history is global, these are the entries of all panes.
+    pub fn navigation_history_descriptions(
+        &self,
+        pane: &View<Pane>,
+        mode: NavigationMode,
+        cx: &AppContext,
+    ) -> Vec<(usize, SharedString)> {
+        if !WorkspaceSettings::get_global(cx).global_navigation_history {
+            return pane.read(cx).nav_history().entry_descriptions(mode, cx);
+        }
+
+        self.panes
+            .iter()
+            .flat_map(|pane| pane.read(cx).nav_history().entry_descriptions(mode, cx))
+            .sorted_by_key(|(timestamp, _)| cmp::Reverse(*timestamp))
+            .collect()
+    }
+
+    pub fn go_to_last_edit(&mut self, cx: &mut ViewContext<Workspace>) -> Task<Result<()>> {
+        self.navigate_history(
+            self.active_pane().downgrade(),
+            NavigationMode::GoingToLastEdit,
+            cx,
+        )
+    }
+
     pub fn reopen_closed_item(&mut self, cx: &mut ViewContext<Workspace>) -> Task<Result<()>> {
         self.navigate_history(
             self.active_pane().downgrade(),
@@ -1534,6 +1737,33 @@ impl Workspace {
             .map(|task| task.detach_and_log_err(cx));
     }
 
+    /// Closes the active pane along with its items, unless it's the only pane.
+    pub fn close_active_pane(&mut self, _: &CloseActivePane, cx: &mut ViewContext<Self>) {
+        if self.panes.len() > 1 {
+            self.close_pane(self.active_pane.clone(), cx);
+        }
+    }
+
+    /// Closes every pane but the active one, leaving the active pane's items open.
+    pub fn close_inactive_panes(&mut self, _: &CloseInactivePanes, cx: &mut ViewContext<Self>) {
+        for pane in self.panes.clone() {
+            if pane != self.active_pane {
+                self.close_pane(pane, cx);
+            }
+        }
+    }
+
+    /// Closes the pane's items, which removes the pane once they're all closed.
+    fn close_pane(&mut self, pane: View<Pane>, cx: &mut ViewContext<Self>) {
+        if pane.read(cx).items_len() == 0 {
+            self.remove_pane(pane, cx);
+        } else if let Some(task) = pane.update(cx, |pane, cx| {
+            pane.close_all_items(&CloseAllItems { save_intent: None }, cx)
+        }) {
+            task.detach_and_log_err(cx);
+        }
+    }
+
     fn close_all_internal(
         &mut self,
         retain_active_pane: bool,
@@ -2094,6 +2324,12 @@ impl Workspace {
 
     fn handle_pane_focused(&mut self, pane: View<Pane>, cx: &mut ViewContext<Self>) {
         if self.active_pane != pane {
+            // With global navigation history, moving to another pane is a navigation, so
+            // remember where we came from unless we're already navigating through history.
+            let navigating = self.history_navigation_pane.take() == Some(pane.entity_id());
+            if !navigating && WorkspaceSettings::get_global(cx).global_navigation_history {
+                Self::push_active_item_to_history(&self.active_pane, NavigationMode::Normal, cx);
+            }
             self.active_pane = pane.clone();
             self.status_bar.update(cx, |status_bar, cx| {
                 status_bar.set_active_pane(&self.active_pane, cx);
@@ -2548,6 +2784,83 @@ impl Workspace {
         cx.set_window_title(&title);
     }
 
+    /// Applies the local settings of the first worktree in the project to the window's
+    /// theme and fonts.
+    fn update_window_settings_location(&mut self, cx: &mut WindowContext) {
+        let location = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .next()
+            .map(|worktree| (worktree.entity_id().as_u64() as usize, Path::new("").into()));
+        theme::set_window_settings_location(cx.window_handle(), location, cx);
+        self.update_ui_scale(cx);
+    }
+
+    /// Asks the user whether to trust a local worktree, which applies the project settings
+    /// in it.
+    fn prompt_for_worktree_trust(&mut self, worktree_id: WorktreeId, cx: &mut ViewContext<Self>) {
+        let Some(worktree) = self.project.read(cx).worktree_for_id(worktree_id, cx) else {
+            return;
+        };
+        let message = format!(
+            "Do you trust the project at {}?",
+            worktree.read(cx).abs_path().display()
+        );
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            &message,
+            Some(
+                "Its project settings can run commands, such as formatters and terminal shells, \
+                its .envrc is run to load its environment, and its Python virtual environments \
+                are activated in terminals. None of this happens unless you trust it.",
+            ),
+            &["Trust", "Don't Trust"],
+        );
+        let project = self.project.downgrade();
+        cx.spawn(|_, mut cx| async move {
+            let trusted = answer.await? == 0;
+            project.update(&mut cx, |project, cx| {
+                project.set_worktree_trusted(worktree_id, trusted, cx)
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn review_project_trust(&mut self, _: &ReviewProjectTrust, cx: &mut ViewContext<Self>) {
+        let worktree_ids = self
+            .project
+            .read(cx)
+            .visible_worktrees(cx)
+            .filter(|worktree| worktree.read(cx).is_local())
+            .map(|worktree| worktree.read(cx).id())
+            .collect::<Vec<_>>();
+        for worktree_id in worktree_ids {
+            self.prompt_for_worktree_trust(worktree_id, cx);
+        }
+    }
+
+    fn toggle_offline_mode(&mut self, _: &ToggleOfflineMode, cx: &mut ViewContext<Self>) {
+        let fs = self.app_state.fs.clone();
+        let offline = OfflineModeSetting::get_global(cx).0;
+        update_settings_file::<OfflineModeSetting>(fs, cx, move |setting| {
+            *setting = Some(!offline)
+        });
+    }
+
+    fn toggle_performance_hud(&mut self, _: &TogglePerformanceHud, cx: &mut ViewContext<Self>) {
+        if self.performance_hud.take().is_none() {
+            let project = self.project.clone();
+            self.performance_hud = Some(cx.new_view(|cx| PerformanceHud::new(project, cx)));
+        }
+        cx.notify();
+    }
+
+    fn update_ui_scale(&mut self, cx: &mut WindowContext) {
+        let ui_scale = ThemeSettings::for_window(cx).ui_scale;
+        cx.set_ui_scale(ui_scale);
+    }
+
     fn update_window_edited(&mut self, cx: &mut WindowContext) {
         let is_edited = !self.project.read(cx).is_disconnected()
             && self
@@ -3217,6 +3530,8 @@ impl Workspace {
         self.add_workspace_actions_listeners(div, cx)
             .on_action(cx.listener(Self::close_inactive_items_and_panes))
             .on_action(cx.listener(Self::close_all_items_and_panes))
+            .on_action(cx.listener(Self::close_active_pane))
+            .on_action(cx.listener(Self::close_inactive_panes))
             .on_action(cx.listener(Self::save_all))
             .on_action(cx.listener(Self::add_folder_to_project))
             .on_action(cx.listener(Self::follow_next_collaborator))
@@ -3268,12 +3583,20 @@ impl Workspace {
             )
             .on_action(cx.listener(Workspace::open))
             .on_action(cx.listener(Workspace::close_window))
+            .on_action(cx.listener(Workspace::review_project_trust))
+            .on_action(cx.listener(Workspace::toggle_offline_mode))
+            .on_action(cx.listener(Workspace::toggle_performance_hud))
             .on_action(cx.listener(Workspace::activate_pane_at_index))
             .on_action(
                 cx.listener(|workspace: &mut Workspace, _: &ReopenClosedItem, cx| {
                     workspace.reopen_closed_item(cx).detach();
                 }),
             )
+            .on_action(
+                cx.listener(|workspace: &mut Workspace, _: &GoToLastEdit, cx| {
+                    workspace.go_to_last_edit(cx).detach_and_log_err(cx);
+                }),
+            )
     }
 
     #[cfg(any(test, feature = "test-support"))]
@@ -3318,6 +3641,8 @@ impl Workspace {
         let mut div = div
             .on_action(cx.listener(Self::close_inactive_items_and_panes))
             .on_action(cx.listener(Self::close_all_items_and_panes))
+            .on_action(cx.listener(Self::close_active_pane))
+            .on_action(cx.listener(Self::close_inactive_panes))
             .on_action(cx.listener(Self::add_folder_to_project))
             .on_action(cx.listener(Self::save_all))
             .on_action(cx.listener(Self::open));
@@ -3484,9 +3809,9 @@ impl Render for Workspace {
         context.add("Workspace");
 
         let (ui_font, ui_font_size) = {
-            let theme_settings = ThemeSettings::get_global(cx);
+            let theme_settings = ThemeSettings::for_window(cx);
             (
-                theme_settings.ui_font.family.clone(),
+                theme_settings.ui_font.clone(),
                 theme_settings.ui_font_size.clone(),
             )
         };
@@ -3501,7 +3826,7 @@ impl Render for Workspace {
             .size_full()
             .flex()
             .flex_col()
-            .font(ui_font)
+            .text_font(ui_font)
             .gap_0()
             .justify_start()
             .items_start()
@@ -3608,6 +3933,15 @@ impl Render for Workspace {
                             )),
                     )
                     .children(self.render_notifications(cx))
+                    .children(self.which_key.render(cx))
+                    .children(self.performance_hud.clone().map(|performance_hud| {
+                        div()
+                            .absolute()
+                            .z_index(100)
+                            .top_3()
+                            .right_3()
+                            .child(performance_hud)
+                    }))
                     .children(self.zoomed.as_ref().and_then(|view| {
                         let zoomed_view = view.upgrade()?;
                         let div = div()
@@ -4931,6 +5265,116 @@ mod tests {
         });
     }
 
+    #[gpui::test]
+    async fn test_navigate_history_to_entry(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let item_a = cx.new_view(|cx| {
+            let mut item = TestItem::new(cx);
+            item.tab_descriptions = Some(vec!["a"]);
+            item
+        });
+        // Entries for this item have no description, so the navigation menu leaves them out.
+        let item_b = cx.new_view(|cx| TestItem::new(cx));
+        let pane = workspace.update(cx, |workspace, cx| {
+            workspace.add_item(Box::new(item_a.clone()), cx);
+            workspace.add_item(Box::new(item_b.clone()), cx);
+            workspace.active_pane().clone()
+        });
+        item_b.update(cx, |item, cx| item.set_state("b".to_string(), cx));
+
+        let descriptions = workspace.update(cx, |workspace, cx| {
+            workspace.navigation_history_descriptions(&pane, NavigationMode::GoingBack, cx)
+        });
+        assert_eq!(
+            descriptions
+                .iter()
+                .map(|(_, description)| description.as_ref())
+                .collect::<Vec<_>>(),
+            &["a"]
+        );
+
+        // Picking the first entry in the menu goes past the entries that aren't listed.
+        workspace
+            .update(cx, |workspace, cx| {
+                workspace.navigate_history_to(
+                    pane.downgrade(),
+                    NavigationMode::GoingBack,
+                    descriptions[0].0,
+                    cx,
+                )
+            })
+            .await
+            .unwrap();
+        pane.update(cx, |pane, _| {
+            assert_eq!(pane.active_item().unwrap().item_id(), item_a.entity_id());
+            assert!(!pane.can_navigate_backward());
+        });
+    }
+
+    #[gpui::test]
+    async fn test_close_active_and_inactive_panes(cx: &mut gpui::TestAppContext) {
+        init_test(cx);
+
+        let fs = FakeFs::new(cx.executor());
+        let project = Project::test(fs, [], cx).await;
+        let (workspace, cx) = cx.add_window_view(|cx| Workspace::test_new(project, cx));
+
+        let item_1 = cx.new_view(|cx| TestItem::new(cx));
+        let item_2 = cx.new_view(|cx| TestItem::new(cx));
+        let item_3 = cx.new_view(|cx| TestItem::new(cx));
+        let left_pane = workspace.update(cx, |workspace, cx| {
+            workspace.add_item(Box::new(item_1.clone()), cx);
+            workspace.add_item(Box::new(item_2.clone()), cx);
+            let left_pane = workspace.active_pane().clone();
+            let right_pane = workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx);
+            right_pane.update(cx, |pane, cx| {
+                pane.add_item(Box::new(item_3.clone()), true, true, None, cx)
+            });
+            cx.focus_view(&left_pane);
+            left_pane
+        });
+        cx.run_until_parked();
+
+        // Closing the other panes keeps all of the active pane's items.
+        workspace.update(cx, |workspace, cx| {
+            workspace.close_inactive_panes(&CloseInactivePanes, cx)
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes(), &[left_pane.clone()]);
+            assert_eq!(left_pane.read(cx).items_len(), 2);
+        });
+
+        // The only pane can't be closed.
+        workspace.update(cx, |workspace, cx| {
+            workspace.close_active_pane(&CloseActivePane, cx)
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.panes(), &[left_pane.clone()]);
+            assert_eq!(left_pane.read(cx).items_len(), 2);
+        });
+
+        let right_pane = workspace.update(cx, |workspace, cx| {
+            workspace.split_pane(left_pane.clone(), SplitDirection::Right, cx)
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, cx| {
+            assert_eq!(workspace.active_pane(), &right_pane);
+            workspace.close_active_pane(&CloseActivePane, cx)
+        });
+        cx.run_until_parked();
+        workspace.update(cx, |workspace, _| {
+            assert_eq!(workspace.panes(), &[left_pane.clone()]);
+            assert_eq!(workspace.active_pane(), &left_pane);
+        });
+    }
+
     #[gpui::test]
     async fn test_toggle_docks_and_panels(cx: &mut gpui::TestAppContext) {
         init_test(cx);
@@ -15,11 +15,17 @@ pub fn app_menus() -> Vec<Menu<'static>> {
                     name: "Preferences",
                     items: vec![
                         MenuItem::action("Open Settings", super::OpenSettings),
+                        MenuItem::action("Edit Settings", settings_editor::Deploy),
                         MenuItem::action("Open Key Bindings", super::OpenKeymap),
+                        MenuItem::action("Edit Key Bindings", keymap_editor::Deploy),
                         MenuItem::action("Open Default Settings", super::OpenDefaultSettings),
                         MenuItem::action("Open Default Key Bindings", super::OpenDefaultKeymap),
                         MenuItem::action("Open Local Settings", super::OpenLocalSettings),
                         MenuItem::action("Select Theme", theme_selector::Toggle),
+                        MenuItem::action(
+                            "Select Settings Profile",
+                            settings_profile_selector::Toggle,
+                        ),
                     ],
                 }),
                 MenuItem::action("Install CLI", install_cli::Install),
@@ -127,6 +133,7 @@ pub fn app_menus() -> Vec<Menu<'static>> {
             items: vec![
                 MenuItem::action("Back", workspace::GoBack),
                 MenuItem::action("Forward", workspace::GoForward),
+                MenuItem::action("Last Edit Location", workspace::GoToLastEdit),
                 MenuItem::separator(),
                 MenuItem::action("Go to File", file_finder::Toggle),
                 // MenuItem::action("Go to Symbol in Project", project_symbols::Toggle),
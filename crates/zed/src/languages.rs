@@ -58,7 +58,14 @@ pub fn init(
     DenoSettings::register(cx);
 
     let language = |name, grammar, adapters| {
-        languages.register(name, load_config(name), grammar, adapters, load_queries)
+        languages.register_lazy(
+            name,
+            load_matcher(name),
+            grammar,
+            adapters,
+            load_config,
+            load_queries,
+        )
     };
 
     language("bash", tree_sitter_bash::language(), vec![]);
@@ -308,7 +315,7 @@ pub async fn language(
     lsp_adapter: Option<Arc<dyn LspAdapter>>,
 ) -> Arc<Language> {
     Arc::new(
-        Language::new(load_config(name), Some(grammar))
+        Language::new(load_config(name).unwrap(), Some(grammar))
             .with_lsp_adapters(lsp_adapter.into_iter().collect())
             .await
             .with_queries(load_queries(name))
@@ -316,7 +323,9 @@ pub async fn language(
     )
 }
 
-fn load_config(name: &str) -> LanguageConfig {
+/// Reads only the fields of a language's config.toml that are needed to choose it
+/// for a file. The rest of the config is loaded when the language is first used.
+fn load_matcher(name: &str) -> LanguageMatcher {
     toml::from_slice(
         &LanguageDir::get(&format!("{}/config.toml", name))
             .unwrap()
@@ -326,6 +335,13 @@ fn load_config(name: &str) -> LanguageConfig {
     .unwrap()
 }
 
+fn load_config(name: &str) -> anyhow::Result<LanguageConfig> {
+    let config = LanguageDir::get(&format!("{}/config.toml", name))
+        .with_context(|| format!("no config.toml for language {name:?}"))?;
+    toml::from_slice(&config.data)
+        .with_context(|| format!("failed to load config.toml for language {name:?}"))
+}
+
 fn load_queries(name: &str) -> LanguageQueries {
     LanguageQueries {
         highlights: load_query(name, "/highlights"),
@@ -335,6 +351,7 @@ fn load_queries(name: &str) -> LanguageQueries {
         embedding: load_query(name, "/embedding"),
         injections: load_query(name, "/injections"),
         overrides: load_query(name, "/overrides"),
+        text_objects: load_query(name, "/textobjects"),
     }
 }
 
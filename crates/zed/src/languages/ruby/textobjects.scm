@@ -0,0 +1,20 @@
+(method
+    body: (_) @function.inside) @function.around
+
+(singleton_method
+    body: (_) @function.inside) @function.around
+
+(class
+    (body_statement) @class.inside) @class.around
+
+(module
+    (body_statement) @class.inside) @class.around
+
+(method_parameters
+    (_) @parameter.inside)
+
+(block_parameters
+    (_) @parameter.inside)
+
+(argument_list
+    (_) @parameter.inside)
@@ -5,7 +5,7 @@ use anyhow::{anyhow, Context as _, Result};
 use backtrace::Backtrace;
 use chrono::Utc;
 use cli::FORCE_CLI_MODE_ENV_VAR_NAME;
-use client::{Client, UserStore};
+use client::{Client, OfflineModeSetting, ProxySettings, UserStore};
 use collab_ui::channel_view::ChannelView;
 use db::kvp::KEY_VALUE_STORE;
 use editor::Editor;
@@ -38,17 +38,20 @@ use std::{
     },
     thread,
 };
-use theme::{ActiveTheme, ThemeRegistry, ThemeSettings};
+use theme::{ActiveTheme, ThemeRegistry};
 use util::{
     async_maybe,
     channel::{parse_zed_link, AppCommitSha, ReleaseChannel, RELEASE_CHANNEL},
-    http::{self, HttpClient},
+    http::{HttpClient, HttpClientWithProxy},
     paths::{self, CRASHES_DIR, CRASHES_RETIRED_DIR},
     ResultExt,
 };
 use uuid::Uuid;
 use welcome::{show_welcome_view, BaseKeymap, FIRST_OPEN};
-use workspace::{AppState, WorkspaceStore};
+use workspace::{
+    session_journal::{self, SessionJournal},
+    AppState, WorkspaceStore,
+};
 use zed::{
     app_menus, build_window_options, ensure_only_instance, handle_cli_connection,
     handle_keymap_file_changes, initialize_workspace, languages, IsOnlyInstance, OpenListener,
@@ -59,7 +62,7 @@ fn main() {
     menu::init();
     zed_actions::init();
 
-    let http = http::client();
+    let http = Arc::new(HttpClientWithProxy::new(None));
     init_paths();
     init_logger();
 
@@ -68,7 +71,9 @@ fn main() {
     }
 
     log::info!("========== starting zed ==========");
-    let app = App::new().with_assets(Assets);
+    let app = App::new()
+        .with_assets(Assets)
+        .with_http_client(http.clone());
 
     let (installation_id, existing_installation_id_found) = app
         .background_executor()
@@ -150,8 +155,19 @@ fn main() {
 
         zed::init(cx);
         theme::init(theme::LoadThemes::All(Box::new(Assets)), cx);
+        project::project_trust::init(cx);
         project::Project::init(&client, cx);
         client::init(&client, cx);
+        http.set_proxy(ProxySettings::get_global(cx).proxy_uri());
+        http.set_offline(OfflineModeSetting::get_global(cx).0);
+        cx.observe_global::<SettingsStore>({
+            let http = http.clone();
+            move |cx| {
+                http.set_proxy(ProxySettings::get_global(cx).proxy_uri());
+                http.set_offline(OfflineModeSetting::get_global(cx).0);
+            }
+        })
+        .detach();
         command_palette::init(cx);
         language::init(cx);
         editor::init(cx);
@@ -176,24 +192,26 @@ fn main() {
                         .await
                         .log_err()
                     {
-                        cx.update(|cx| {
-                            let mut theme_settings = ThemeSettings::get_global(cx).clone();
-
-                            if let Some(requested_theme) = theme_settings.requested_theme.clone() {
-                                if let Some(_theme) =
-                                    theme_settings.switch_theme(&requested_theme, cx)
-                                {
-                                    ThemeSettings::override_global(theme_settings, cx);
-                                }
-                            }
-                        })
-                        .log_err();
+                        cx.update(theme::reload_theme).log_err();
                     }
                 }
             }
         })
         .detach();
 
+        cx.spawn({
+            let fs = fs.clone();
+            |mut cx| async move {
+                file_icons::FileIcons::load_user_icon_themes(&paths::ICON_THEMES_DIR, fs, &mut cx)
+                    .await
+                    .log_err();
+            }
+        })
+        .detach();
+
+        watch_user_themes(fs.clone(), cx);
+        watch_user_icon_themes(fs.clone(), cx);
+
         cx.spawn(|_| watch_languages(fs.clone(), languages.clone()))
             .detach();
         watch_file_types(fs.clone(), cx);
@@ -233,13 +251,17 @@ fn main() {
         auto_update::init(http.clone(), client::ZED_SERVER_URL.clone(), cx);
 
         workspace::init(app_state.clone(), cx);
+        session_journal::init(fs.clone(), cx);
         recent_projects::init(cx);
 
         go_to_line::init(cx);
+        bookmarks::init(cx);
         file_finder::init(cx);
         outline::init(cx);
         project_symbols::init(cx);
-        project_panel::init(Assets, cx);
+        file_icons::init(Assets, cx);
+        project_panel::init(cx);
+        todo_panel::init(cx);
         channel::init(&client, user_store.clone(), cx);
         search::init(cx);
         semantic_index::init(fs.clone(), http.clone(), languages.clone(), cx);
@@ -247,8 +269,16 @@ fn main() {
         terminal_view::init(cx);
 
         journal::init(app_state.clone(), cx);
+        zed::scratchpad::init(app_state.clone(), cx);
+        scratch_buffers::init(cx);
+        keymap_editor::init(cx);
+        settings_editor::init(cx);
         language_selector::init(cx);
+        indentation_selector::init(cx);
+        line_ending_selector::init(cx);
+        encoding_selector::init(cx);
         theme_selector::init(cx);
+        settings_profile_selector::init(cx);
         language_tools::init(cx);
         call::init(app_state.client.clone(), app_state.user_store.clone(), cx);
         notifications::init(app_state.client.clone(), app_state.user_store.clone(), cx);
@@ -438,12 +468,29 @@ async fn installation_id() -> Result<(String, bool)> {
     Ok((installation_id, false))
 }
 
-async fn restore_or_create_workspace(app_state: &Arc<AppState>, cx: AsyncAppContext) {
+async fn restore_or_create_workspace(app_state: &Arc<AppState>, mut cx: AsyncAppContext) {
     async_maybe!({
+        // After a crash, offer to restore the journaled session in place of the
+        // workspace that was last saved to the database.
+        let mut requesting_window = None;
+        if let Some(session) = cx.update(|cx| SessionJournal::take_crashed_session(cx))? {
+            requesting_window =
+                session_journal::offer_recovery(session, app_state.clone(), cx.clone()).await?;
+            if requesting_window.is_none() {
+                return Ok(());
+            }
+        }
+
         if let Some(location) = workspace::last_opened_workspace_paths().await {
-            cx.update(|cx| workspace::open_paths(location.paths().as_ref(), app_state, None, cx))?
-                .await
-                .log_err();
+            cx.update(|cx| {
+                workspace::open_paths(location.paths().as_ref(), app_state, requesting_window, cx)
+            })?
+            .await
+            .log_err();
+        } else if let Some(window) = requesting_window {
+            window.update(&mut cx, |workspace, cx| {
+                Editor::new_file(workspace, &Default::default(), cx)
+            })?;
         } else if matches!(KEY_VALUE_STORE.read_kvp(FIRST_OPEN), Ok(None)) {
             cx.update(|cx| show_welcome_view(app_state, cx)).log_err();
         } else {
@@ -635,6 +682,10 @@ fn init_panic_hook(app: &App, installation_id: Option<String>, session_id: Strin
 }
 
 fn upload_panics_and_crashes(http: Arc<dyn HttpClient>, cx: &mut AppContext) {
+    // Reports are kept until they can be uploaded when offline mode is disabled.
+    if OfflineModeSetting::get_global(cx).0 {
+        return;
+    }
     let telemetry_settings = *client::TelemetrySettings::get_global(cx);
     cx.background_executor()
         .spawn(async move {
@@ -856,6 +907,63 @@ fn load_embedded_fonts(cx: &AppContext) {
         .unwrap();
 }
 
+/// Reloads the user themes as their files change, so that edits to a theme show up
+/// without restarting.
+fn watch_user_themes(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
+    use std::time::Duration;
+
+    cx.spawn(|cx| async move {
+        let mut events = fs
+            .watch(&paths::THEMES_DIR, Duration::from_millis(100))
+            .await;
+        while let Some(events) = events.next().await {
+            let Some(theme_registry) = cx.update(|cx| ThemeRegistry::global(cx).clone()).log_err()
+            else {
+                break;
+            };
+
+            let mut reloaded = false;
+            for event in events {
+                if event.path.extension() != Some("json".as_ref()) || !fs.is_file(&event.path).await
+                {
+                    continue;
+                }
+                reloaded |= theme_registry
+                    .load_user_theme(&event.path, fs.clone())
+                    .await
+                    .log_err()
+                    .is_some();
+            }
+            if reloaded {
+                cx.update(theme::reload_theme).log_err();
+            }
+        }
+    })
+    .detach()
+}
+
+/// Reloads the user icon themes as their files change.
+fn watch_user_icon_themes(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
+    use std::time::Duration;
+
+    cx.spawn(|mut cx| async move {
+        let mut events = fs
+            .watch(&paths::ICON_THEMES_DIR, Duration::from_millis(100))
+            .await;
+        while (events.next().await).is_some() {
+            file_icons::FileIcons::load_user_icon_themes(
+                &paths::ICON_THEMES_DIR,
+                fs.clone(),
+                &mut cx,
+            )
+            .await
+            .log_err();
+            cx.update(|cx| cx.refresh()).log_err();
+        }
+    })
+    .detach()
+}
+
 #[cfg(debug_assertions)]
 async fn watch_languages(fs: Arc<dyn fs::Fs>, languages: Arc<LanguageRegistry>) -> Option<()> {
     use std::time::Duration;
@@ -884,12 +992,8 @@ fn watch_file_types(fs: Arc<dyn fs::Fs>, cx: &mut AppContext) {
             )
             .await;
         while (events.next().await).is_some() {
-            cx.update(|cx| {
-                cx.update_global(|file_types, _| {
-                    *file_types = project_panel::file_associations::FileAssociations::new(Assets);
-                });
-            })
-            .ok();
+            cx.update(|cx| file_icons::FileIcons::reload_default_icon_theme(Assets, cx))
+                .ok();
         }
     })
     .detach()
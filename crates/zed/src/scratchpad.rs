@@ -0,0 +1,281 @@
+//! A small window with a single buffer for jotting down notes, whose text is kept in a file
+//! in the config directory. The `scratchpad.hotkey` setting registers a system-wide hotkey
+//! that shows and hides it from any application, without switching to a workspace.
+
+use anyhow::{Context as _, Result};
+use editor::Editor;
+use fs::Fs;
+use gpui::{
+    actions, div, px, size, AppContext, Bounds, Context, InteractiveElement, IntoElement,
+    Keystroke, Model, ParentElement, Render, Styled, Subscription, Task, TitlebarOptions, View,
+    ViewContext, VisualContext, WindowBounds, WindowHandle, WindowOptions,
+};
+use language::{Buffer, BufferId, LineEnding};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::{sync::Arc, time::Duration};
+use theme::{ActiveTheme, ThemeSettings};
+use util::{paths, ResultExt};
+use workspace::{AppState, Workspace};
+
+actions!(scratchpad, [Toggle]);
+
+/// How long to wait after an edit before writing the scratchpad to disk.
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Deserialize)]
+pub struct ScratchpadSettings {
+    pub hotkey: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ScratchpadSettingsContent {
+    /// The system-wide hotkey that shows and hides the scratchpad, such as `"cmd-shift-space"`.
+    ///
+    /// Default: null
+    pub hotkey: Option<String>,
+}
+
+impl Settings for ScratchpadSettings {
+    const KEY: Option<&'static str> = Some("scratchpad");
+
+    type FileContent = ScratchpadSettingsContent;
+
+    fn load(
+        default_value: &Self::FileContent,
+        user_values: &[&Self::FileContent],
+        _: &mut AppContext,
+    ) -> Result<Self> {
+        Self::load_via_json_merge(default_value, user_values)
+    }
+}
+
+/// The scratchpad's window, if it's open, and the hotkey registered to toggle it.
+#[derive(Default)]
+struct ScratchpadState {
+    window: Option<WindowHandle<Scratchpad>>,
+    hotkey: Option<Keystroke>,
+}
+
+pub fn init(app_state: Arc<AppState>, cx: &mut AppContext) {
+    ScratchpadSettings::register(cx);
+    cx.set_global(ScratchpadState::default());
+
+    cx.on_global_hotkey({
+        let app_state = app_state.clone();
+        move |cx| toggle(&app_state, cx)
+    });
+    update_hotkey(cx);
+    cx.observe_global::<SettingsStore>(update_hotkey).detach();
+
+    cx.observe_new_views(|workspace: &mut Workspace, _| {
+        workspace.register_action(|workspace, _: &Toggle, cx| {
+            toggle(workspace.app_state(), cx);
+        });
+    })
+    .detach();
+}
+
+fn update_hotkey(cx: &mut AppContext) {
+    let hotkey = ScratchpadSettings::get_global(cx)
+        .hotkey
+        .as_deref()
+        .and_then(|hotkey| {
+            Keystroke::parse(hotkey)
+                .with_context(|| format!("parsing scratchpad hotkey {hotkey:?}"))
+                .log_err()
+        });
+    if cx.global::<ScratchpadState>().hotkey == hotkey {
+        return;
+    }
+    cx.set_global_hotkey(hotkey.as_ref()).log_err();
+    cx.global_mut::<ScratchpadState>().hotkey = hotkey;
+}
+
+/// Closes the scratchpad when it's the active window, and otherwise brings it to the front,
+/// opening it if needed.
+fn toggle(app_state: &Arc<AppState>, cx: &mut AppContext) {
+    if let Some(window) = cx.global::<ScratchpadState>().window {
+        let is_active = cx.active_window() == Some(window.into());
+        let updated = window.update(cx, |_, cx| {
+            if is_active {
+                cx.remove_window();
+            } else {
+                cx.activate_window();
+            }
+        });
+        if updated.is_ok() {
+            if !is_active {
+                cx.activate(true);
+            }
+            return;
+        }
+    }
+
+    let fs = app_state.fs.clone();
+    let languages = app_state.languages.clone();
+    cx.spawn(|mut cx| async move {
+        let text = if fs.is_file(&paths::SCRATCHPAD).await {
+            fs.load(&paths::SCRATCHPAD).await?
+        } else {
+            String::new()
+        };
+        let markdown = languages.language_for_name("Markdown").await.log_err();
+
+        cx.update(|cx| {
+            let buffer = cx.new_model(|cx| {
+                let mut buffer =
+                    Buffer::new(0, BufferId::new(cx.entity_id().as_u64()).unwrap(), text);
+                buffer.set_language(markdown, cx);
+                buffer
+            });
+            let window = cx.open_window(
+                WindowOptions {
+                    bounds: WindowBounds::Fixed(Bounds {
+                        origin: Default::default(),
+                        size: size(px(640.), px(420.)).into(),
+                    }),
+                    titlebar: Some(TitlebarOptions {
+                        title: Some("Scratchpad".into()),
+                        ..Default::default()
+                    }),
+                    center: true,
+                    ..Default::default()
+                },
+                |cx| cx.new_view(|cx| Scratchpad::new(buffer, fs, cx)),
+            );
+            cx.global_mut::<ScratchpadState>().window = Some(window);
+            cx.activate(true);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+pub struct Scratchpad {
+    editor: View<Editor>,
+    buffer: Model<Buffer>,
+    fs: Arc<dyn Fs>,
+    /// Whether the buffer was edited since it was last written to disk.
+    has_unsaved_edits: bool,
+    _save_task: Option<Task<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl Scratchpad {
+    fn new(buffer: Model<Buffer>, fs: Arc<dyn Fs>, cx: &mut ViewContext<Self>) -> Self {
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer.clone(), None, cx);
+            editor.set_show_gutter(false, cx);
+            editor.set_placeholder_text("Jot something down…", cx);
+            editor
+        });
+        cx.focus_view(&editor);
+
+        let subscriptions = vec![
+            cx.subscribe(&buffer, |this, _, event, cx| {
+                if let language::Event::Edited = event {
+                    this.has_unsaved_edits = true;
+                    this._save_task = Some(cx.spawn(|this, mut cx| async move {
+                        cx.background_executor().timer(SAVE_DEBOUNCE).await;
+                        this.update(&mut cx, |this, cx| this.save(cx)).log_err();
+                    }));
+                }
+            }),
+            // Edits made just before the window closed are saved as it does.
+            cx.on_release(|this, _, cx| this.save(cx)),
+        ];
+
+        Self {
+            editor,
+            buffer,
+            fs,
+            has_unsaved_edits: false,
+            _save_task: None,
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn save(&mut self, cx: &mut AppContext) {
+        if !self.has_unsaved_edits {
+            return;
+        }
+        self.has_unsaved_edits = false;
+        let text = self.buffer.read(cx).as_rope().clone();
+        let fs = self.fs.clone();
+        cx.background_executor()
+            .spawn(async move {
+                fs.create_dir(&paths::CONFIG_DIR).await?;
+                fs.save(&paths::SCRATCHPAD, &text, LineEnding::Unix).await
+            })
+            .detach_and_log_err(cx);
+    }
+}
+
+impl Render for Scratchpad {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let theme_settings = ThemeSettings::get_global(cx);
+        let ui_font = theme_settings.ui_font.family.clone();
+        let ui_font_size = theme_settings.ui_font_size;
+        cx.set_rem_size(ui_font_size);
+        let text_color = cx.theme().colors().text;
+        let background = cx.theme().colors().editor_background;
+
+        div()
+            .key_context("Scratchpad")
+            .on_action(cx.listener(|_, _: &Toggle, cx| cx.remove_window()))
+            .size_full()
+            .p_2()
+            .font(ui_font)
+            .text_color(text_color)
+            .bg(background)
+            .child(self.editor.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+
+    #[gpui::test]
+    async fn test_scratchpad(cx: &mut TestAppContext) {
+        let app_state = cx.update(|cx| {
+            let app_state = AppState::test(cx);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            editor::init(cx);
+            init(app_state.clone(), cx);
+            app_state
+        });
+
+        cx.update(|cx| toggle(&app_state, cx));
+        cx.run_until_parked();
+        let window = cx.update(|cx| cx.global::<ScratchpadState>().window.unwrap());
+        window
+            .update(cx, |scratchpad, cx| {
+                scratchpad
+                    .editor
+                    .update(cx, |editor, cx| editor.insert("Call Ana", cx));
+            })
+            .unwrap();
+
+        cx.executor().advance_clock(SAVE_DEBOUNCE);
+        cx.run_until_parked();
+        assert_eq!(
+            app_state.fs.load(&paths::SCRATCHPAD).await.unwrap(),
+            "Call Ana"
+        );
+
+        // Reopening the scratchpad shows the saved text.
+        window.update(cx, |_, cx| cx.remove_window()).unwrap();
+        cx.update(|cx| toggle(&app_state, cx));
+        cx.run_until_parked();
+        let window = cx.update(|cx| cx.global::<ScratchpadState>().window.unwrap());
+        window
+            .update(cx, |scratchpad, cx| {
+                assert_eq!(scratchpad.editor.read(cx).text(cx), "Call Ana");
+            })
+            .unwrap();
+    }
+}
@@ -2,6 +2,7 @@ mod app_menus;
 pub mod languages;
 mod only_instance;
 mod open_listener;
+pub mod scratchpad;
 
 pub use app_menus::*;
 use assistant::AssistantPanel;
@@ -25,6 +26,7 @@ use search::project_search::ProjectSearchBar;
 use settings::{initial_local_settings_content, KeymapFile, Settings, SettingsStore};
 use std::{borrow::Cow, ops::Deref, path::Path, sync::Arc};
 use terminal_view::terminal_panel::{self, TerminalPanel};
+use todo_panel::TodoPanel;
 use util::{
     asset_str,
     channel::{AppCommitSha, ReleaseChannel},
@@ -119,17 +121,33 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
             cx.new_view(|cx| diagnostics::items::DiagnosticIndicator::new(workspace, cx));
         let activity_indicator =
             activity_indicator::ActivityIndicator::new(workspace, app_state.languages.clone(), cx);
+        let environment_indicator =
+            cx.new_view(|cx| activity_indicator::EnvironmentIndicator::new(workspace, cx));
+        let offline_indicator = cx.new_view(|cx| activity_indicator::OfflineIndicator::new(cx));
         let active_buffer_language =
             cx.new_view(|_| language_selector::ActiveBufferLanguage::new(workspace));
+        let active_buffer_indentation =
+            cx.new_view(|_| indentation_selector::ActiveBufferIndentation::new(workspace));
+        let active_buffer_line_ending =
+            cx.new_view(|_| line_ending_selector::ActiveBufferLineEnding::new(workspace));
+        let active_buffer_encoding =
+            cx.new_view(|_| encoding_selector::ActiveBufferEncoding::new(workspace));
         let vim_mode_indicator = cx.new_view(|cx| vim::ModeIndicator::new(cx));
         let feedback_button =
             cx.new_view(|_| feedback::deploy_feedback_button::DeployFeedbackButton::new(workspace));
         let cursor_position = cx.new_view(|_| editor::items::CursorPosition::new());
+        let read_only_indicator = cx.new_view(|_| editor::items::ReadOnlyIndicator::new());
         workspace.status_bar().update(cx, |status_bar, cx| {
             status_bar.add_left_item(diagnostic_summary, cx);
             status_bar.add_left_item(activity_indicator, cx);
+            status_bar.add_left_item(offline_indicator, cx);
+            status_bar.add_right_item(environment_indicator, cx);
             status_bar.add_right_item(feedback_button, cx);
             status_bar.add_right_item(copilot, cx);
+            status_bar.add_right_item(read_only_indicator, cx);
+            status_bar.add_right_item(active_buffer_indentation, cx);
+            status_bar.add_right_item(active_buffer_line_ending, cx);
+            status_bar.add_right_item(active_buffer_encoding, cx);
             status_bar.add_right_item(active_buffer_language, cx);
             status_bar.add_right_item(vim_mode_indicator, cx);
             status_bar.add_right_item(cursor_position, cx);
@@ -162,6 +180,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace_handle.clone(),
                 cx.clone(),
             );
+            let todo_panel = TodoPanel::load(workspace_handle.clone(), cx.clone());
             let (
                 project_panel,
                 terminal_panel,
@@ -169,6 +188,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                todo_panel,
             ) = futures::try_join!(
                 project_panel,
                 terminal_panel,
@@ -176,6 +196,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 channels_panel,
                 chat_panel,
                 notification_panel,
+                todo_panel,
             )?;
 
             workspace_handle.update(&mut cx, |workspace, cx| {
@@ -185,6 +206,7 @@ pub fn initialize_workspace(app_state: Arc<AppState>, cx: &mut AppContext) {
                 workspace.add_panel(channels_panel, cx);
                 workspace.add_panel(chat_panel, cx);
                 workspace.add_panel(notification_panel, cx);
+                workspace.add_panel(todo_panel, cx);
                 cx.focus_self();
             })
         })
@@ -735,6 +757,7 @@ mod tests {
     use super::*;
     use assets::Assets;
     use editor::{scroll::Autoscroll, DisplayPoint, Editor};
+    use futures::FutureExt;
     use gpui::{
         actions, Action, AnyWindowHandle, AppContext, AssetSource, Entity, TestAppContext,
         VisualTestContext, WindowHandle,
@@ -2168,6 +2191,103 @@ mod tests {
         }
     }
 
+    #[gpui::test]
+    async fn test_go_to_last_edit(cx: &mut TestAppContext) {
+        let app_state = init_test(cx);
+        app_state
+            .fs
+            .as_fake()
+            .insert_tree(
+                "/root",
+                json!({
+                    "a": {
+                        "file1": "contents 1\n".repeat(20),
+                        "file2": "contents 2\n".repeat(20),
+                    },
+                }),
+            )
+            .await;
+
+        let project = Project::test(app_state.fs.clone(), ["/root".as_ref()], cx).await;
+        let workspace = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let entries = cx.update(|cx| workspace.root(cx).unwrap().file_project_paths(cx));
+        let file1 = entries[0].clone();
+        let file2 = entries[1].clone();
+
+        let editor1 = workspace
+            .update(cx, |w, cx| w.open_path(file1.clone(), None, true, cx))
+            .unwrap()
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        workspace
+            .update(cx, |_, cx| {
+                editor1.update(cx, |editor, cx| {
+                    editor.change_selections(None, cx, |s| {
+                        s.select_display_ranges([DisplayPoint::new(5, 0)..DisplayPoint::new(5, 0)])
+                    });
+                    editor.insert("x", cx);
+                });
+            })
+            .unwrap();
+
+        let editor2 = workspace
+            .update(cx, |w, cx| w.open_path(file2.clone(), None, true, cx))
+            .unwrap()
+            .await
+            .unwrap()
+            .downcast::<Editor>()
+            .unwrap();
+        workspace
+            .update(cx, |_, cx| {
+                editor2.update(cx, |editor, cx| {
+                    editor.change_selections(None, cx, |s| {
+                        s.select_display_ranges(
+                            [DisplayPoint::new(10, 0)..DisplayPoint::new(10, 0)],
+                        )
+                    });
+                });
+            })
+            .unwrap();
+
+        workspace
+            .update(cx, |w, cx| w.go_to_last_edit(cx))
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(
+            active_cursor(&workspace, cx),
+            (file1.clone(), DisplayPoint::new(5, 1))
+        );
+
+        // Going back returns to where we were before jumping to the edit.
+        workspace
+            .update(cx, |w, cx| w.go_back(w.active_pane().downgrade(), cx))
+            .unwrap()
+            .await
+            .unwrap();
+        assert_eq!(
+            active_cursor(&workspace, cx),
+            (file2.clone(), DisplayPoint::new(10, 0))
+        );
+
+        fn active_cursor(
+            workspace: &WindowHandle<Workspace>,
+            cx: &mut TestAppContext,
+        ) -> (ProjectPath, DisplayPoint) {
+            workspace
+                .update(cx, |workspace, cx| {
+                    let item = workspace.active_item(cx).unwrap();
+                    let editor = item.downcast::<Editor>().unwrap();
+                    let selections =
+                        editor.update(cx, |editor, cx| editor.selections.display_ranges(cx));
+                    (item.project_path(cx).unwrap(), selections[0].start)
+                })
+                .unwrap()
+        }
+    }
+
     #[gpui::test]
     async fn test_reopening_closed_items(cx: &mut TestAppContext) {
         let app_state = init_test(cx);
@@ -2709,10 +2829,19 @@ mod tests {
         let languages = Arc::new(languages);
         let node_runtime = node_runtime::FakeNodeRuntime::new();
         languages::init(languages.clone(), node_runtime, cx);
-        for name in languages.language_names() {
-            languages.language_for_name(&name);
-        }
+
+        // Language configs are only loaded on first use, so load each one to check
+        // that it parses.
+        let loads = languages
+            .language_names()
+            .into_iter()
+            .map(|name| (languages.language_for_name(&name), name))
+            .collect::<Vec<_>>();
         cx.background_executor().run_until_parked();
+        for (load, name) in loads {
+            let result = load.now_or_never().expect("language should have loaded");
+            assert!(result.is_ok(), "failed to load {name}: {result:?}");
+        }
     }
 
     fn init_test(cx: &mut TestAppContext) -> Arc<AppState> {
@@ -2733,7 +2862,9 @@ mod tests {
             editor::init(cx);
             project_panel::init_settings(cx);
             collab_ui::init(&app_state, cx);
-            project_panel::init((), cx);
+            file_icons::init((), cx);
+            project_panel::init(cx);
+            todo_panel::init(cx);
             terminal_view::init(cx);
             assistant::init(cx);
             initialize_workspace(app_state.clone(), cx);